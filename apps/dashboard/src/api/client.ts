@@ -56,6 +56,10 @@ export class ApiError extends Error {
 function extractDetail(body: string): string {
   try {
     const json = JSON.parse(body);
+    // Gateway errors are `{ error: { code, message, details? } }`.
+    if (json.error && typeof json.error === "object") {
+      return json.error.message || json.error.code || body;
+    }
     return json.error || json.message || body;
   } catch {
     return body;
@@ -170,12 +174,15 @@ export type SessionEntry = {
   context_tokens: number;
   sm_session_id?: string;
   origin: SessionOrigin;
+  tags: string[];
+  pinned_model?: string;
   running?: boolean;
 };
 
 export type SessionsListResponse = {
   sessions: SessionEntry[];
   total: number;
+  offset: number;
   count: number;
 };
 
@@ -184,6 +191,9 @@ export type SessionsListParams = {
   channel?: string;
   peer?: string;
   agent_id?: string;
+  /** Only sessions active within the last N seconds. */
+  active_within_secs?: number;
+  tag?: string;
   limit?: number;
   offset?: number;
 };
@@ -198,6 +208,32 @@ export type SessionDetailResponse = SessionEntry & {
   };
 };
 
+export type SessionTagsResponse = {
+  session_key: string;
+  tags: string[];
+};
+
+export type CompactionEvent = {
+  line_index: number;
+  timestamp: string;
+  turns_compacted: number | null;
+  /** null for markers written before the count was recorded. */
+  lines_summarized: number | null;
+  summary: string;
+};
+
+export type CompactionsResponse = {
+  session_key: string;
+  session_id: string;
+  count: number;
+  compactions: CompactionEvent[];
+};
+
+export type SessionModelPinResponse = {
+  session_key: string;
+  pinned_model: string | null;
+};
+
 export type TranscriptLine = {
   timestamp: string;
   role: string;
@@ -229,13 +265,33 @@ export type NodeInfo = {
 export type NodesListResponse = {
   nodes: NodeInfo[];
   count: number;
+  latency?: Record<string, NodeLatency>;
+  outstanding?: Record<string, number>;
+};
+
+export type NodeLatency = {
+  calls: number;
+  timeouts: number;
+  mean_latency_ms: number;
+  max_latency_ms: number;
+};
+
+export type NodeSelfTest = {
+  node_id: string;
+  success: boolean;
+  latency_ms: number;
+  error?: string;
+  error_kind?: string;
 };
 
 export type AgentInfo = {
   id: string;
   tools_allow?: string[];
   tools_deny?: string[];
+  tools_rules?: string[];
+  tools_default?: "allow_all" | "deny_all";
   effective_tools_count?: number;
+  effective_tools?: string[];
   resolved_executor?: string;
   models?: Record<string, string>;
   memory_mode?: string;
@@ -307,6 +363,44 @@ export type ToolInvokeResponse = {
   duration_ms: number;
 };
 
+export type ToolStat = {
+  tool: string;
+  calls: number;
+  errors: number;
+  failure_rate: number;
+  mean_latency_ms: number;
+  max_latency_ms: number;
+  last_called_at: string | null;
+};
+
+export type ToolStatsResponse = {
+  tools: ToolStat[];
+  count: number;
+};
+
+export type ToolAuditEntry = {
+  timestamp: string;
+  session_key: string | null;
+  agent_id: string | null;
+  tool_name: string;
+  args_preview: string;
+  result_status: "ok" | "error" | "denied";
+};
+
+export type ToolAuditParams = {
+  tool?: string;
+  session_key?: string;
+  agent_id?: string;
+  status?: "ok" | "error" | "denied";
+  since?: string;
+  limit?: number;
+};
+
+export type ToolAuditResponse = {
+  entries: ToolAuditEntry[];
+  count: number;
+};
+
 // ── Admin types ──────────────────────────────────────────────────
 
 export type SystemInfo = {
@@ -478,12 +572,51 @@ export type ImportPreviewResponse = {
   conflicts_hint: ConflictsHint;
 };
 
+/** `progress` event from POST /v1/import/openclaw/preview/stream. */
+export type ImportProgress =
+  | { phase: "fetching"; bytes: number }
+  | { phase: "extracting"; files: number; total_files: number; bytes: number; total_bytes: number }
+  | { phase: "scanning"; files: number; bytes: number };
+
 export type MergeStrategy = "merge_safe" | "replace" | "skip_existing";
 
 export type ImportApplyRequestV2 = {
   staging_id: string;
   merge_strategy?: MergeStrategy;
   options?: ImportOptions;
+  /** Import only these agents / workspaces; omit or leave empty for all. */
+  only_agents?: string[];
+  only_workspaces?: string[];
+};
+
+export type ImportConflictsRequest = {
+  merge_strategy?: MergeStrategy;
+  options?: ImportOptions;
+  only_agents?: string[];
+  only_workspaces?: string[];
+};
+
+export type ConflictAction = "skip" | "overwrite" | "delete" | "relocate";
+
+export type ImportConflict = {
+  kind: "workspace" | "session" | "agent_file";
+  source: string;
+  dest: string;
+  action: ConflictAction;
+  existing?: string;
+};
+
+export type ImportConflictsResponse = {
+  staging_id: string;
+  merge_strategy: MergeStrategy;
+  conflicts: ImportConflict[];
+  totals: {
+    created: number;
+    skipped: number;
+    overwritten: number;
+    deleted: number;
+    relocated: number;
+  };
 };
 
 export type ImportedSummary = {
@@ -496,10 +629,20 @@ export type ImportedSummary = {
 
 export type ImportApplyResponseV2 = {
   staging_id: string;
+  /** Pass to `importRollback` to undo this apply. */
+  apply_id: string;
   imported: ImportedSummary;
   warnings: string[];
 };
 
+export type ImportRollbackResponse = {
+  apply_id: string;
+  staging_id: string;
+  removed: number;
+  restored: number;
+  warnings: string[];
+};
+
 export type TestSshResponse = {
   ok: boolean;
   stdout?: string;
@@ -569,6 +712,7 @@ export type RunListItem = {
 
 export type RunDetail = RunListItem & {
   nodes: RunNode[];
+  seed?: number;
 };
 
 export type RunListResponse = {
@@ -596,7 +740,7 @@ export type RunListParams = {
 
 export type ScheduleStatus = "active" | "paused" | "error";
 export type MissedPolicy = "skip" | "run_once" | "catch_up";
-export type DigestMode = "full" | "changes_only";
+export type DigestMode = "full" | "changes_only" | "per_source_sections";
 
 export type DeliveryTarget =
   | { kind: "in_app" }
@@ -606,6 +750,8 @@ export type FetchConfig = {
   timeout_ms: number;
   user_agent: string;
   max_size_bytes: number;
+  timeout_sec?: number | null;
+  retries?: number;
 };
 
 export type SourceState = {
@@ -726,6 +872,56 @@ export type DeliveryListResponse = {
   unread: number;
 };
 
+/** Filters for the bulk delivery endpoints; omit both to target everything. */
+export type DeliveryBulkFilter = {
+  schedule_id?: string;
+  older_than_secs?: number;
+};
+
+function deliveryFilterQuery(filter?: DeliveryBulkFilter): string {
+  const q = new URLSearchParams();
+  if (filter?.schedule_id) q.set("schedule_id", filter.schedule_id);
+  if (filter?.older_than_secs) q.set("older_than_secs", String(filter.older_than_secs));
+  const qs = q.toString();
+  return qs ? "?" + qs : "";
+}
+
+// ── Usage report types ──────────────────────────────────────────────
+
+export type UsageGroupBy = "day" | "model" | "agent";
+
+export type UsageBucket = {
+  key: string;
+  runs: number;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  estimated_cost_usd: number;
+};
+
+export type UsageReport = {
+  group_by: UsageGroupBy;
+  from: string | null;
+  to: string | null;
+  buckets: UsageBucket[];
+  totals: UsageBucket;
+};
+
+export type UsageQuery = {
+  from?: string;
+  to?: string;
+  group_by?: UsageGroupBy;
+};
+
+function usageQuery(query?: UsageQuery): string {
+  const q = new URLSearchParams();
+  if (query?.from) q.set("from", query.from);
+  if (query?.to) q.set("to", query.to);
+  if (query?.group_by) q.set("group_by", query.group_by);
+  const qs = q.toString();
+  return qs ? "?" + qs : "";
+}
+
 // ── Skill engine types ──────────────────────────────────────────────
 
 export type DangerLevel = "safe" | "network" | "filesystem" | "execution";
@@ -737,6 +933,7 @@ export type SkillEngineSpec = {
   args_schema: unknown;
   returns_schema: unknown;
   danger_level: DangerLevel;
+  idempotent: boolean;
 };
 
 export type SkillEngineListResponse = {
@@ -744,6 +941,22 @@ export type SkillEngineListResponse = {
   count: number;
 };
 
+// ── ClawHub types ───────────────────────────────────────────────────
+
+export type OutdatedPack = {
+  owner: string;
+  repo: string;
+  installed: string;
+  latest: string;
+};
+
+export type ClawhubOutdatedResponse = {
+  outdated: OutdatedPack[];
+  count: number;
+  unchecked: { owner: string; repo: string; reason: string }[];
+  checked: number;
+};
+
 // ── Quota types ─────────────────────────────────────────────────────
 
 export type QuotaStatus = {
@@ -791,12 +1004,21 @@ export type RouterDecision = {
   bypassed: boolean;
 };
 
+export type BreakerStatus = {
+  provider: string;
+  state: "closed" | "open" | "half-open";
+  failure_count: number;
+  next_probe_at: string | null;
+};
+
 // ── API functions ──────────────────────────────────────────────────
 
 export const api = {
   // Core
   readiness: () => get<ReadinessResponse>("/v1/models/readiness"),
   nodes: () => get<NodesListResponse>("/v1/nodes"),
+  selftestNode: (id: string) =>
+    post<NodeSelfTest>(`/v1/nodes/${encodeURIComponent(id)}/selftest`, {}),
   agents: () => get<AgentsListResponse>("/v1/agents"),
   sessions: (params?: SessionsListParams) => {
     const q = new URLSearchParams();
@@ -804,6 +1026,9 @@ export const api = {
     if (params?.channel) q.set("channel", params.channel);
     if (params?.peer) q.set("peer", params.peer);
     if (params?.agent_id) q.set("agent_id", params.agent_id);
+    if (params?.active_within_secs)
+      q.set("active_within_secs", String(params.active_within_secs));
+    if (params?.tag) q.set("tag", params.tag);
     if (params?.limit) q.set("limit", String(params.limit));
     if (params?.offset) q.set("offset", String(params.offset));
     const qs = q.toString();
@@ -815,12 +1040,30 @@ export const api = {
     get<TranscriptResponse>(
       `/v1/sessions/${encodeURIComponent(key)}/transcript?offset=${offset}&limit=${limit}`
     ),
+  compactions: (key: string) =>
+    get<CompactionsResponse>(`/v1/sessions/${encodeURIComponent(key)}/compactions`),
   resetSession: (key: string) =>
     post<SessionResetResponse>(`/v1/sessions/${encodeURIComponent(key)}/reset`, {}),
   stopSession: (key: string) =>
     post<SessionStopResponse>(`/v1/sessions/${encodeURIComponent(key)}/stop`, {}),
+  setSessionTags: (key: string, tags: string[]) =>
+    put<SessionTagsResponse>(`/v1/sessions/${encodeURIComponent(key)}/tags`, { tags }),
+  pinSessionModel: (key: string, model: string | null) =>
+    put<SessionModelPinResponse>(`/v1/sessions/${encodeURIComponent(key)}/model`, { model }),
   invokeTool: (req: ToolInvokeRequest) =>
     post<ToolInvokeResponse>("/v1/tools/invoke", req),
+  toolStats: () => get<ToolStatsResponse>("/v1/tools/stats"),
+  toolAudit: (params?: ToolAuditParams) => {
+    const q = new URLSearchParams();
+    if (params?.tool) q.set("tool", params.tool);
+    if (params?.session_key) q.set("session_key", params.session_key);
+    if (params?.agent_id) q.set("agent_id", params.agent_id);
+    if (params?.status) q.set("status", params.status);
+    if (params?.since) q.set("since", params.since);
+    if (params?.limit) q.set("limit", String(params.limit));
+    const qs = q.toString();
+    return get<ToolAuditResponse>(`/v1/tools/audit${qs ? "?" + qs : ""}`);
+  },
 
   // Admin
   systemInfo: () => get<SystemInfo>("/v1/admin/info"),
@@ -829,13 +1072,28 @@ export const api = {
   applyOpenClawImport: (req: ImportApplyRequest) =>
     post<ImportApplyResult>("/v1/admin/import/openclaw/apply", req),
   workspaceFiles: () => get<WorkspaceFilesResponse>("/v1/admin/workspace/files"),
+  writeWorkspaceFile: (path: string, content: string) =>
+    putText<{ path: string; size: number; sha256: string; backup: string | null }>(
+      `/v1/admin/workspace/files/${path.split("/").map(encodeURIComponent).join("/")}`,
+      content
+    ),
   skillsDetailed: () => get<SkillsDetailedResponse>("/v1/admin/skills"),
 
   // Import (staging-based)
   importPreview: (req: ImportPreviewRequest) =>
     post<ImportPreviewResponse>("/v1/import/openclaw/preview", req),
+  importConflicts: (stagingId: string, req: ImportConflictsRequest) =>
+    post<ImportConflictsResponse>(
+      `/v1/import/openclaw/conflicts/${encodeURIComponent(stagingId)}`,
+      req
+    ),
   importApply: (req: ImportApplyRequestV2) =>
     post<ImportApplyResponseV2>("/v1/import/openclaw/apply", req),
+  importRollback: (applyId: string) =>
+    post<ImportRollbackResponse>(
+      `/v1/import/openclaw/rollback/${encodeURIComponent(applyId)}`,
+      {}
+    ),
   testSsh: (host: string, user?: string, port?: number, auth?: SshAuth) =>
     post<TestSshResponse>("/v1/import/openclaw/test-ssh", { host, user, port, auth }),
   listStaging: () =>
@@ -882,6 +1140,7 @@ export const api = {
   // Health & metrics
   getHealth: () => get<{ status: string; version: string }>("/v1/health"),
   getMetrics: () => get<unknown>("/v1/metrics"),
+  getUsage: (query?: UsageQuery) => get<UsageReport>(`/v1/usage${usageQuery(query)}`),
 
   // Deliveries (inbox)
   getDeliveries: (limit = 25, offset = 0) =>
@@ -890,10 +1149,17 @@ export const api = {
     get<{ delivery: Delivery }>(`/v1/deliveries/${encodeURIComponent(id)}`),
   markDeliveryRead: (id: string) =>
     post<{ ok: boolean }>(`/v1/deliveries/${encodeURIComponent(id)}/read`, {}),
+  markAllDeliveriesRead: (filter?: DeliveryBulkFilter) =>
+    post<{ ok: boolean; count: number }>(`/v1/deliveries/read-all${deliveryFilterQuery(filter)}`, {}),
+  deleteDeliveries: (filter?: DeliveryBulkFilter) =>
+    del<{ ok: boolean; count: number }>(`/v1/deliveries${deliveryFilterQuery(filter)}`),
 
   // Skill engine
   getSkillEngine: () => get<SkillEngineListResponse>("/v1/skill-engine"),
 
+  // ClawHub packs
+  clawhubOutdated: () => get<ClawhubOutdatedResponse>("/v1/clawhub/outdated"),
+
   // Quotas (per-agent daily limits)
   getQuotas: () => get<QuotaListResponse>("/v1/quotas"),
 
@@ -904,8 +1170,14 @@ export const api = {
     post<{ restarting: boolean; note: string }>("/v1/admin/restart", {}),
 
   // Provider listing
-  providers: () => get<{ providers: string[]; count: number }>("/v1/models"),
+  models: () =>
+    get<{
+      object: "list";
+      data: { id: string; object: "model"; created: number; owned_by: string }[];
+    }>("/v1/models"),
   roles: () => get<{ roles: Record<string, string> }>("/v1/models/roles"),
+  refreshModels: () =>
+    post<{ providers: Record<string, string[]> }>("/v1/models/refresh", {}),
 
   // Router
   routerStatus: () => get<RouterStatus>("/v1/router/status"),
@@ -913,4 +1185,8 @@ export const api = {
     post<ClassifyResult>("/v1/router/classify", { prompt }),
   routerDecisions: (limit = 100) =>
     get<{ decisions: RouterDecision[]; count: number }>(`/v1/router/decisions?limit=${limit}`),
+  routerBreakers: () =>
+    get<{ breakers: BreakerStatus[]; count: number }>("/v1/router/breakers"),
+  resetBreaker: (provider: string) =>
+    post<BreakerStatus>(`/v1/router/breakers/${encodeURIComponent(provider)}/reset`, {}),
 };
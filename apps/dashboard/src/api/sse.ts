@@ -6,6 +6,9 @@ export interface SseOptions<T> {
   onEvent: (eventType: string, data: T) => void;
   onError?: (error: Event | string) => void;
   onClose?: () => void;
+  /** Defaults to GET; a `body` is sent as JSON. */
+  method?: string;
+  body?: unknown;
 }
 
 /**
@@ -21,7 +24,9 @@ export function subscribeSSE<T = unknown>(
   (async () => {
     try {
       const res = await fetch(path, {
-        headers: buildHeaders(),
+        method: opts.method ?? "GET",
+        headers: buildHeaders(opts.body === undefined ? undefined : "application/json"),
+        body: opts.body === undefined ? undefined : JSON.stringify(opts.body),
         signal: controller.signal,
       });
 
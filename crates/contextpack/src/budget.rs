@@ -0,0 +1,244 @@
+use sa_domain::tokens::TokenCounter;
+use serde::{Deserialize, Serialize};
+
+/// Per-section budgets plus an overall cap, in the builder's counter units.
+///
+/// The workspace budget is the builder's `total_max`; these cover the
+/// remaining sections.  When the sections together exceed `total`, the
+/// overall cap is shared fairly between them (see [`fair_allocate`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionBudgets {
+    pub skills: usize,
+    pub user_facts: usize,
+    pub total: usize,
+}
+
+impl Default for SectionBudgets {
+    /// Unbounded: skills and user facts are injected as-is.
+    fn default() -> Self {
+        Self {
+            skills: usize::MAX,
+            user_facts: usize::MAX,
+            total: usize::MAX,
+        }
+    }
+}
+
+/// How a section was cut down, if at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionTruncation {
+    None,
+    /// Exceeded its own budget.
+    SectionBudget,
+    /// Fit its budget but lost characters to the overall cap.
+    FairShare,
+}
+
+/// Budgeting outcome for one section of the context pack.
+///
+/// Sizes are in the builder's budget unit (chars or tokens).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionReport {
+    /// `workspace`, `skills`, or `user_facts`.
+    pub section: String,
+    pub raw: usize,
+    pub budget: usize,
+    pub allocated: usize,
+    pub truncation: SectionTruncation,
+}
+
+impl SectionReport {
+    pub(crate) fn new(section: &str, raw: usize, budget: usize, allocated: usize) -> Self {
+        let truncation = if allocated < raw.min(budget) {
+            SectionTruncation::FairShare
+        } else if raw > budget {
+            SectionTruncation::SectionBudget
+        } else {
+            SectionTruncation::None
+        };
+        Self {
+            section: section.to_string(),
+            raw,
+            budget,
+            allocated,
+            truncation,
+        }
+    }
+}
+
+/// Split `total` units across sections (max-min fair).
+///
+/// If the demands fit, each section gets its full demand.  Otherwise every
+/// section still wanting more receives an equal share, capped at its
+/// demand, and whatever satisfied sections leave over is redistributed to
+/// the rest.  Small sections therefore survive intact next to a huge one.
+pub fn fair_allocate<const N: usize>(demands: [usize; N], total: usize) -> [usize; N] {
+    let requested = demands.iter().fold(0usize, |acc, d| acc.saturating_add(*d));
+    if requested <= total {
+        return demands;
+    }
+
+    let mut alloc = [0usize; N];
+    let mut remaining = total;
+    let mut active: Vec<usize> = (0..N).filter(|&i| demands[i] > 0).collect();
+
+    while remaining > 0 && !active.is_empty() {
+        let share = remaining / active.len();
+        let mut granted = 0;
+        for &i in &active {
+            let grant = share.min(demands[i] - alloc[i]);
+            alloc[i] += grant;
+            granted += grant;
+        }
+        remaining -= granted;
+        active.retain(|&i| alloc[i] < demands[i]);
+
+        if granted == 0 {
+            // Fewer units left than active sections: hand them out in order.
+            for &i in &active {
+                let grant = remaining.min(demands[i] - alloc[i]);
+                alloc[i] += grant;
+                remaining -= grant;
+            }
+            break;
+        }
+    }
+
+    alloc
+}
+
+/// Cut `content` to at most `max` units, marking the cut with
+/// `[TRUNCATED_TOTAL_CAP]`.
+pub(crate) fn truncate_section(content: &str, max: usize, counter: &dyn TokenCounter) -> String {
+    if counter.count(content) <= max {
+        return content.to_string();
+    }
+    format!("{}\n\n[TRUNCATED_TOTAL_CAP]\n", counter.truncate(content, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{ContextPackBuilder, SessionMode, WorkspaceFile};
+
+    #[test]
+    fn allocation_is_identity_when_demands_fit() {
+        assert_eq!(fair_allocate([10, 5, 3], 100), [10, 5, 3]);
+    }
+
+    #[test]
+    fn allocation_redistributes_surplus() {
+        // Skills only wants 5, so its unused share goes to the others.
+        let alloc = fair_allocate([1_000, 5, 1_000], 100);
+        assert_eq!(alloc[1], 5);
+        assert_eq!(alloc[0] + alloc[2], 95);
+        assert!(alloc[0].abs_diff(alloc[2]) <= 1);
+    }
+
+    #[test]
+    fn allocation_never_exceeds_demand_or_total() {
+        let alloc = fair_allocate([7, 0, 300], 50);
+        assert_eq!(alloc, [7, 0, 43]);
+    }
+
+    #[test]
+    fn section_report_classifies_truncation() {
+        assert_eq!(SectionReport::new("s", 10, 20, 10).truncation, SectionTruncation::None);
+        assert_eq!(
+            SectionReport::new("s", 30, 20, 20).truncation,
+            SectionTruncation::SectionBudget
+        );
+        assert_eq!(
+            SectionReport::new("s", 30, 20, 12).truncation,
+            SectionTruncation::FairShare
+        );
+    }
+
+    #[test]
+    fn oversized_workspace_leaves_room_for_skills_and_user_facts() {
+        let builder = ContextPackBuilder::new(100_000, 10_000).with_budgets(SectionBudgets {
+            skills: 1_000,
+            user_facts: 1_000,
+            total: 6_000,
+        });
+        let files = vec![WorkspaceFile {
+            name: "AGENTS.md".into(),
+            content: Some("a".repeat(50_000)),
+        }];
+        let skills = "s".repeat(800);
+        let facts = "f".repeat(800);
+
+        let (assembled, report) = builder.build(
+            &files,
+            SessionMode::Normal,
+            false,
+            Some(&skills),
+            Some(&facts),
+        );
+
+        let section = |name: &str| report.sections.iter().find(|s| s.section == name).unwrap();
+        let workspace = section("workspace");
+        assert_eq!(workspace.budget, 10_000);
+        assert_eq!(workspace.truncation, SectionTruncation::FairShare);
+        assert_eq!(workspace.allocated, 6_000 - 1_600);
+
+        // Skills and user facts fit within their fair share, so they survive intact.
+        assert_eq!(section("skills").allocated, 800);
+        assert_eq!(section("skills").truncation, SectionTruncation::None);
+        assert_eq!(section("user_facts").allocated, 800);
+        assert!(assembled.contains(&skills));
+        assert!(assembled.contains(&facts));
+        assert!(report.files[0].truncated_total_cap);
+    }
+
+    #[test]
+    fn oversized_sections_are_cut_to_their_budgets() {
+        let builder = ContextPackBuilder::new(100_000, 10_000).with_budgets(SectionBudgets {
+            skills: 100,
+            user_facts: 100,
+            total: usize::MAX,
+        });
+        let skills = "s".repeat(500);
+        let (assembled, report) = builder.build(&[], SessionMode::Normal, false, Some(&skills), None);
+
+        let skills_report = report.sections.iter().find(|s| s.section == "skills").unwrap();
+        assert_eq!(skills_report.allocated, 100);
+        assert_eq!(skills_report.truncation, SectionTruncation::SectionBudget);
+        assert!(!assembled.contains(&skills));
+        assert!(assembled.contains("[TRUNCATED_TOTAL_CAP]"));
+    }
+
+    #[test]
+    fn token_budgeting_changes_inclusion_decisions() {
+        use sa_domain::tokens::{ApproxTokenCounter, CharCounter};
+        use std::sync::Arc;
+
+        let files = vec![
+            WorkspaceFile {
+                name: "AGENTS.md".into(),
+                content: Some("a".repeat(600)),
+            },
+            WorkspaceFile {
+                name: "SOUL.md".into(),
+                content: Some("s".repeat(600)),
+            },
+        ];
+        let build = |counter: Arc<dyn TokenCounter>| {
+            ContextPackBuilder::new(1_000, 1_000)
+                .with_counter(counter)
+                .build(&files, SessionMode::Normal, false, None, None)
+        };
+
+        // 1200 chars exceed a 1000-char cap, but are only ~300 tokens.
+        let (_, by_chars) = build(Arc::new(CharCounter));
+        let (_, by_tokens) = build(Arc::new(ApproxTokenCounter));
+
+        let soul = |r: &crate::report::ContextReport| {
+            r.files.iter().find(|f| f.filename == "SOUL.md").unwrap().clone()
+        };
+        assert!(soul(&by_chars).truncated_total_cap);
+        assert!(!soul(&by_tokens).truncated_total_cap);
+        assert_eq!(soul(&by_tokens).injected_chars, 600);
+    }
+}
@@ -1,5 +1,10 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use sa_domain::config::ContextConfig;
+use sa_domain::tokens::{self, CharCounter, TokenCounter};
+
+use crate::budget::{self, SectionBudgets, SectionReport};
 use crate::injection;
 use crate::report::{ContextReport, FileReport};
 use crate::truncation::{self, Section};
@@ -35,10 +40,14 @@ pub enum SessionMode {
 /// Deterministic context pack builder.
 ///
 /// Pure function: accepts pre-read workspace files and config caps,
-/// returns assembled prompt + machine-readable report.
+/// returns assembled prompt + machine-readable report.  Caps are measured
+/// by `counter` (characters unless configured otherwise).
 pub struct ContextPackBuilder {
     pub max_per_file: usize,
+    /// Budget for the workspace section (all workspace files together).
     pub total_max: usize,
+    pub budgets: SectionBudgets,
+    pub counter: Arc<dyn TokenCounter>,
 }
 
 impl ContextPackBuilder {
@@ -46,9 +55,33 @@ impl ContextPackBuilder {
         Self {
             max_per_file,
             total_max,
+            budgets: SectionBudgets::default(),
+            counter: Arc::new(CharCounter),
         }
     }
 
+    /// Builder wired to every cap in `[context]`.
+    pub fn from_config(config: &ContextConfig) -> Self {
+        Self::new(config.bootstrap_max_chars, config.bootstrap_total_max_chars).with_budgets(
+            SectionBudgets {
+                skills: config.skills_index_max_chars,
+                user_facts: config.user_facts_max_chars,
+                total: config.total_max_chars,
+            },
+        )
+        .with_counter(tokens::counter_for(config.budget_unit))
+    }
+
+    pub fn with_budgets(mut self, budgets: SectionBudgets) -> Self {
+        self.budgets = budgets;
+        self
+    }
+
+    pub fn with_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.counter = counter;
+        self
+    }
+
     /// Build the context pack.
     ///
     /// - `files`: workspace files already read (pass all that exist + missing markers)
@@ -98,7 +131,11 @@ impl ContextPackBuilder {
                     let raw_chars = raw_content.len();
                     let normalized = raw_content.replace("\r\n", "\n");
                     let (truncated_content, was_truncated) =
-                        truncation::truncate_per_file(&normalized, self.max_per_file);
+                        truncation::truncate_per_file(
+                            &normalized,
+                            self.max_per_file,
+                            self.counter.as_ref(),
+                        );
 
                     sections.push(Section {
                         filename: expected_name.to_string(),
@@ -125,8 +162,30 @@ impl ContextPackBuilder {
             }
         }
 
-        // Apply total cap
-        truncation::apply_total_cap(&mut sections, self.total_max);
+        // Share the overall cap between sections, each capped to its own budget
+        let skills_index = skills_index.unwrap_or("");
+        let user_facts = user_facts.unwrap_or("");
+        let counter = self.counter.as_ref();
+        let workspace_size: usize = sections.iter().map(|s| counter.count(&s.content)).sum();
+        let budgets = [self.total_max, self.budgets.skills, self.budgets.user_facts];
+        let raw = [
+            workspace_size,
+            counter.count(skills_index),
+            counter.count(user_facts),
+        ];
+        let demands = [
+            raw[0].min(budgets[0]),
+            raw[1].min(budgets[1]),
+            raw[2].min(budgets[2]),
+        ];
+        let allocated = budget::fair_allocate(demands, self.budgets.total);
+        let section_reports: Vec<SectionReport> = ["workspace", "skills", "user_facts"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| SectionReport::new(name, raw[i], budgets[i], allocated[i]))
+            .collect();
+
+        truncation::apply_total_cap(&mut sections, allocated[0], counter);
 
         // Assemble output
         let mut assembled = String::new();
@@ -163,21 +222,19 @@ impl ContextPackBuilder {
         }
 
         // Append skills index
-        let skills_index_chars = skills_index.map(|s| s.len()).unwrap_or(0);
-        if let Some(index) = skills_index {
-            if !index.is_empty() {
-                assembled.push_str(&injection::format_skills_index(index));
-                assembled.push('\n');
-            }
+        let skills_index_chars = skills_index.len();
+        if !skills_index.is_empty() {
+            let index = budget::truncate_section(skills_index, allocated[1], counter);
+            assembled.push_str(&injection::format_skills_index(&index));
+            assembled.push('\n');
         }
 
         // Append USER_FACTS
-        let user_facts_chars = user_facts.map(|f| f.len()).unwrap_or(0);
-        if let Some(facts) = user_facts {
-            if !facts.is_empty() {
-                assembled.push_str(&injection::format_user_facts(facts));
-                assembled.push('\n');
-            }
+        let user_facts_chars = user_facts.len();
+        if !user_facts.is_empty() {
+            let facts = budget::truncate_section(user_facts, allocated[2], counter);
+            assembled.push_str(&injection::format_user_facts(&facts));
+            assembled.push('\n');
         }
 
         let total_injected_chars = assembled.len();
@@ -193,6 +250,7 @@ impl ContextPackBuilder {
             total_injected_chars,
             bootstrap_included,
             first_run: is_first_run,
+            sections: section_reports,
         };
 
         (assembled, report)
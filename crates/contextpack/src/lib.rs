@@ -1,3 +1,4 @@
+pub mod budget;
 pub mod builder;
 pub mod injection;
 pub mod report;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::budget::SectionReport;
+
 /// Per-file report within the context pack build.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileReport {
@@ -22,4 +24,7 @@ pub struct ContextReport {
     pub total_injected_chars: usize,
     pub bootstrap_included: bool,
     pub first_run: bool,
+    /// Budgeting outcome per section (workspace, skills, user_facts).
+    #[serde(default)]
+    pub sections: Vec<SectionReport>,
 }
@@ -1,3 +1,5 @@
+use sa_domain::tokens::TokenCounter;
+
 /// A section being accumulated for total-cap processing.
 pub struct Section {
     pub filename: String,
@@ -11,20 +13,19 @@ pub struct Section {
 
 /// Per-file truncation.
 ///
-/// If `content` exceeds `max_chars`, truncate to the first `max_chars` characters
-/// (at a valid UTF-8 boundary) and append `\n\n[TRUNCATED]\n`.
-pub fn truncate_per_file(content: &str, max_chars: usize) -> (String, bool) {
-    if content.len() <= max_chars {
+/// If `content` exceeds `max` units (as measured by `counter`), keep the
+/// longest prefix that fits and append `\n\n[TRUNCATED]\n`.
+pub fn truncate_per_file(content: &str, max: usize, counter: &dyn TokenCounter) -> (String, bool) {
+    if counter.count(content) <= max {
         return (content.to_string(), false);
     }
-    let boundary = content.floor_char_boundary(max_chars);
-    let mut result = content[..boundary].to_string();
+    let mut result = counter.truncate(content, max).to_string();
     result.push_str("\n\n[TRUNCATED]\n");
     (result, true)
 }
 
-/// Apply total cap across accumulated sections in order.
-pub fn apply_total_cap(sections: &mut [Section], total_max_chars: usize) {
+/// Apply total cap (in `counter` units) across accumulated sections in order.
+pub fn apply_total_cap(sections: &mut [Section], total_max: usize, counter: &dyn TokenCounter) {
     let mut accumulated: usize = 0;
 
     for section in sections.iter_mut() {
@@ -32,19 +33,18 @@ pub fn apply_total_cap(sections: &mut [Section], total_max_chars: usize) {
             continue;
         }
 
-        let section_len = section.content.len();
+        let section_len = counter.count(&section.content);
 
-        if accumulated + section_len <= total_max_chars {
+        if accumulated + section_len <= total_max {
             accumulated += section_len;
-        } else if accumulated < total_max_chars {
-            let remaining = total_max_chars - accumulated;
-            let boundary = section.content.floor_char_boundary(remaining);
+        } else if accumulated < total_max {
+            let remaining = total_max - accumulated;
             section.content = format!(
                 "{}\n\n[TRUNCATED_TOTAL_CAP]\n",
-                &section.content[..boundary]
+                counter.truncate(&section.content, remaining)
             );
             section.truncated_total_cap = true;
-            accumulated = total_max_chars;
+            accumulated = total_max;
         } else {
             section.content.clear();
             section.included = false;
@@ -55,10 +55,11 @@ pub fn apply_total_cap(sections: &mut [Section], total_max_chars: usize) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sa_domain::tokens::CharCounter;
 
     #[test]
     fn no_truncation_when_under_limit() {
-        let (result, truncated) = truncate_per_file("hello world", 100);
+        let (result, truncated) = truncate_per_file("hello world", 100, &CharCounter);
         assert_eq!(result, "hello world");
         assert!(!truncated);
     }
@@ -66,7 +67,7 @@ mod tests {
     #[test]
     fn truncates_at_limit() {
         let content = "abcdefghij";
-        let (result, truncated) = truncate_per_file(content, 5);
+        let (result, truncated) = truncate_per_file(content, 5, &CharCounter);
         assert!(truncated);
         assert!(result.starts_with("abcde"));
         assert!(result.contains("[TRUNCATED]"));
@@ -104,7 +105,7 @@ mod tests {
             },
         ];
 
-        apply_total_cap(&mut sections, 8);
+        apply_total_cap(&mut sections, 8, &CharCounter);
 
         assert!(sections[0].included);
         assert!(!sections[0].truncated_total_cap);
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::tool::ToolChoice;
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Sub-agent definitions
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -36,6 +38,11 @@ pub struct AgentConfig {
     /// Default `false` — short-lived child sessions rarely benefit from compaction.
     #[serde(default)]
     pub compaction_enabled: bool,
+    /// Default tool choice for this agent's turns.  `required` and
+    /// `{ specific = "..." }` force a tool on the first LLM call only, so
+    /// the tool loop can still finish with a text answer.
+    #[serde(default)]
+    pub tool_choice: ToolChoice,
 }
 
 /// Hard ceilings on multi-agent fan-out to prevent runaway trees.
@@ -48,6 +55,9 @@ pub struct AgentLimits {
     /// Maximum number of agent.run calls within a single parent turn.
     #[serde(default = "d_5")]
     pub max_children_per_turn: u32,
+    /// Maximum sub-agents running at the same time under one parent turn.
+    #[serde(default = "d_3")]
+    pub max_concurrent_children: u32,
     /// Wall-clock timeout per child run (milliseconds). 0 = no limit.
     /// Default 30s — override per-agent for batch workers that need more.
     #[serde(default = "d_30000")]
@@ -59,49 +69,173 @@ impl Default for AgentLimits {
         Self {
             max_depth: 3,
             max_children_per_turn: 5,
+            max_concurrent_children: 3,
             max_duration_ms: 30_000,
         }
     }
 }
 
-/// Tool allow/deny policy — prefix-based matching similar to node capabilities.
+/// Tool allow/deny policy over fully-qualified tool names.
+///
+/// Patterns are case-insensitive.  A plain name (`exec`, `memory`) matches
+/// itself and its dotted subtree (`memory.search`); a pattern containing
+/// `*` is a glob where `*` matches any run of characters (`macos.*`,
+/// `mcp:github:*`, `*`).
+///
+/// Evaluation, in order:
+/// 1. `deny` entries — any match denies (deny always wins).
+/// 2. `rules`, ordered — the **last** matching rule decides, so a later
+///    `deny macos.shell` carves an exception out of an earlier
+///    `allow macos.*`.
+/// 3. `allow` entries — any match allows; a non-empty `allow` list denies
+///    everything else (`["*"]` allows everything).
+/// 4. Otherwise `default`.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ToolPolicy {
-    /// Tool name prefixes this agent may use.  `["*"]` or empty = unrestricted.
+    /// Tool name patterns this agent may use.  `["*"]` or empty = unrestricted.
     #[serde(default)]
     pub allow: Vec<String>,
-    /// Tool name prefixes this agent is denied (evaluated before allow).
+    /// Tool name patterns this agent is denied (evaluated before everything else).
     #[serde(default)]
     pub deny: Vec<String>,
+    /// Ordered rules such as `"allow macos.*"` / `"deny exec"`.
+    #[serde(default)]
+    pub rules: Vec<ToolRule>,
+    /// Decision for tools no entry matches.
+    #[serde(default)]
+    pub default: ToolPolicyDefault,
+}
+
+/// Fallback decision of a [`ToolPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPolicyDefault {
+    /// Anything not denied is allowed (default — matches the unrestricted
+    /// behaviour of an empty policy).
+    #[default]
+    AllowAll,
+    /// Anything not explicitly allowed is denied.
+    DenyAll,
+}
+
+/// Effect of a [`ToolRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleEffect {
+    Allow,
+    Deny,
+}
+
+/// One ordered policy rule, written as `"<allow|deny> <pattern>"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ToolRule {
+    pub effect: RuleEffect,
+    pub pattern: String,
+}
+
+impl TryFrom<String> for ToolRule {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (effect, pattern) = s.trim().split_once(char::is_whitespace).ok_or_else(|| {
+            format!("tool rule `{s}` must be `allow <pattern>` or `deny <pattern>`")
+        })?;
+        let effect = match effect.to_ascii_lowercase().as_str() {
+            "allow" => RuleEffect::Allow,
+            "deny" => RuleEffect::Deny,
+            other => return Err(format!("unknown tool rule effect `{other}` in `{s}`")),
+        };
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(format!("tool rule `{s}` has an empty pattern"));
+        }
+        Ok(Self {
+            effect,
+            pattern: pattern.to_owned(),
+        })
+    }
+}
+
+impl From<ToolRule> for String {
+    fn from(rule: ToolRule) -> Self {
+        let effect = match rule.effect {
+            RuleEffect::Allow => "allow",
+            RuleEffect::Deny => "deny",
+        };
+        format!("{effect} {}", rule.pattern)
+    }
+}
+
+impl std::fmt::Display for ToolRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&String::from(self.clone()))
+    }
+}
+
+/// Whether `pattern` matches the (already lowercased) tool `name`.
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    if pattern.contains('*') {
+        glob_matches(pattern.as_bytes(), name.as_bytes())
+    } else {
+        name == pattern || name.starts_with(&format!("{pattern}."))
+    }
+}
+
+/// Minimal glob matcher where `*` matches any (possibly empty) run.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == name[n] {
+            p += 1;
+            n += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
 }
 
 impl ToolPolicy {
     /// Check whether the given tool name is permitted by this policy.
     ///
     /// Matching is **case-insensitive** — tool names are normalized to
-    /// lowercase before comparison.  Deny always wins over allow.
+    /// lowercase before comparison.  `deny` entries always win.
     pub fn allows(&self, tool_name: &str) -> bool {
         let name = tool_name.to_ascii_lowercase();
 
-        // Deny takes precedence.
-        for d in &self.deny {
-            let d_lower = d.to_ascii_lowercase();
-            if d_lower == "*" || name == d_lower || name.starts_with(&format!("{d_lower}.")) {
-                return false;
-            }
+        if self.deny.iter().any(|d| pattern_matches(d, &name)) {
+            return false;
         }
-        // Empty allow or ["*"] means unrestricted (after deny check).
-        if self.allow.is_empty() || self.allow.iter().any(|a| a == "*") {
-            return true;
+        if let Some(rule) = self
+            .rules
+            .iter()
+            .rev()
+            .find(|r| pattern_matches(&r.pattern, &name))
+        {
+            return rule.effect == RuleEffect::Allow;
         }
-        // Otherwise must match at least one allow entry.
-        for a in &self.allow {
-            let a_lower = a.to_ascii_lowercase();
-            if name == a_lower || name.starts_with(&format!("{a_lower}.")) {
-                return true;
-            }
+        if !self.allow.is_empty() {
+            return self.allow.iter().any(|a| pattern_matches(a, &name));
         }
-        false
+        self.default == ToolPolicyDefault::AllowAll
+    }
+
+    /// The subset of `tool_names` this policy permits, in input order.
+    pub fn resolve<'a>(&self, tool_names: &[&'a str]) -> Vec<&'a str> {
+        tool_names
+            .iter()
+            .copied()
+            .filter(|t| self.allows(t))
+            .collect()
     }
 }
 
@@ -149,6 +283,7 @@ mod tests {
         let policy = ToolPolicy {
             allow: vec!["exec".into(), "memory".into()],
             deny: vec![],
+            ..Default::default()
         };
         assert!(policy.allows("exec"));
         assert!(policy.allows("memory.search"));
@@ -162,6 +297,7 @@ mod tests {
         let policy = ToolPolicy {
             allow: vec!["*".into()],
             deny: vec!["exec".into()],
+            ..Default::default()
         };
         assert!(!policy.allows("exec"));
         assert!(policy.allows("memory.search"));
@@ -173,6 +309,7 @@ mod tests {
         let policy = ToolPolicy {
             allow: vec![],
             deny: vec!["memory".into()],
+            ..Default::default()
         };
         assert!(policy.allows("exec"));
         assert!(!policy.allows("memory.search"));
@@ -184,6 +321,7 @@ mod tests {
         let policy = ToolPolicy {
             allow: vec!["exec".into()],
             deny: vec!["*".into()],
+            ..Default::default()
         };
         assert!(!policy.allows("exec"));
         assert!(!policy.allows("memory.search"));
@@ -194,6 +332,7 @@ mod tests {
         let policy = ToolPolicy {
             allow: vec!["Exec".into(), "Memory".into()],
             deny: vec![],
+            ..Default::default()
         };
         assert!(policy.allows("exec"));
         assert!(policy.allows("EXEC"));
@@ -202,11 +341,104 @@ mod tests {
         assert!(!policy.allows("agent.run"));
     }
 
+    fn rules(rules: &[&str]) -> Vec<ToolRule> {
+        rules
+            .iter()
+            .map(|r| ToolRule::try_from(r.to_string()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn tool_policy_rule_prefix_allow() {
+        let policy = ToolPolicy {
+            rules: rules(&["allow macos.*", "allow memory"]),
+            default: ToolPolicyDefault::DenyAll,
+            ..Default::default()
+        };
+        assert!(policy.allows("macos.notes.create"));
+        assert!(policy.allows("memory"));
+        assert!(policy.allows("memory.search"));
+        assert!(!policy.allows("macos"));
+        assert!(!policy.allows("exec"));
+        assert!(!policy.allows("memorybank"));
+    }
+
+    #[test]
+    fn tool_policy_later_deny_overrides_allow() {
+        let policy = ToolPolicy {
+            rules: rules(&["allow *", "allow macos.*", "deny macos.shell", "deny exec"]),
+            ..Default::default()
+        };
+        assert!(policy.allows("macos.notes.create"));
+        assert!(!policy.allows("macos.shell"));
+        assert!(!policy.allows("exec"));
+        assert!(policy.allows("web.fetch"));
+    }
+
+    #[test]
+    fn tool_policy_deny_list_beats_rules() {
+        let policy = ToolPolicy {
+            deny: vec!["exec".into()],
+            rules: rules(&["allow exec"]),
+            ..Default::default()
+        };
+        assert!(!policy.allows("exec"));
+    }
+
+    #[test]
+    fn tool_policy_default_applies_when_nothing_matches() {
+        let allow_all = ToolPolicy::default();
+        assert!(allow_all.allows("anything.at.all"));
+
+        let deny_all = ToolPolicy {
+            default: ToolPolicyDefault::DenyAll,
+            ..Default::default()
+        };
+        assert!(!deny_all.allows("exec"));
+        assert!(deny_all.resolve(&["exec", "memory.search"]).is_empty());
+    }
+
+    #[test]
+    fn tool_policy_glob_matches_mcp_names() {
+        let policy = ToolPolicy {
+            rules: rules(&["allow mcp:github:*", "allow *.read"]),
+            default: ToolPolicyDefault::DenyAll,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.resolve(&[
+                "mcp:github:list_prs",
+                "mcp:slack:post",
+                "fs.read",
+                "fs.write"
+            ]),
+            vec!["mcp:github:list_prs", "fs.read"]
+        );
+    }
+
+    #[test]
+    fn tool_policy_parses_from_toml() {
+        let policy: ToolPolicy = toml::from_str(
+            r#"
+            default = "deny_all"
+            rules = ["allow macos.*", "DENY macos.shell"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.default, ToolPolicyDefault::DenyAll);
+        assert_eq!(policy.rules[1].effect, RuleEffect::Deny);
+        assert_eq!(policy.rules[1].to_string(), "deny macos.shell");
+
+        let bad: Result<ToolPolicy, _> = toml::from_str(r#"rules = ["permit exec"]"#);
+        assert!(bad.is_err());
+    }
+
     #[test]
     fn agent_limits_defaults() {
         let limits = AgentLimits::default();
         assert_eq!(limits.max_depth, 3);
         assert_eq!(limits.max_children_per_turn, 5);
+        assert_eq!(limits.max_concurrent_children, 3);
         assert_eq!(limits.max_duration_ms, 30_000);
     }
 }
@@ -14,6 +14,24 @@ pub struct ContextConfig {
     pub user_facts_max_chars: usize,
     #[serde(default = "d_2000")]
     pub skills_index_max_chars: usize,
+    /// Overall cap across workspace, skills and user facts.  When exceeded,
+    /// the sections share it fairly so one oversized section cannot crowd
+    /// out the others.
+    #[serde(default = "d_30000")]
+    pub total_max_chars: usize,
+    /// Unit the caps above are measured in.  `tokens` uses the real
+    /// tokenizer when built with the `tiktoken` feature, char/4 otherwise.
+    #[serde(default)]
+    pub budget_unit: BudgetUnit,
+}
+
+/// Unit for context budgets (see [`crate::tokens::counter_for`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetUnit {
+    #[default]
+    Chars,
+    Tokens,
 }
 
 impl Default for ContextConfig {
@@ -23,6 +41,8 @@ impl Default for ContextConfig {
             bootstrap_total_max_chars: 24_000,
             user_facts_max_chars: 4_000,
             skills_index_max_chars: 2_000,
+            total_max_chars: 30_000,
+            budget_unit: BudgetUnit::Chars,
         }
     }
 }
@@ -35,6 +55,9 @@ fn d_20000() -> usize {
 fn d_24000() -> usize {
     24_000
 }
+fn d_30000() -> usize {
+    30_000
+}
 fn d_4000() -> usize {
     4_000
 }
@@ -42,6 +42,13 @@ pub struct LlmConfig {
     /// Smart router configuration (optional).
     #[serde(default)]
     pub router: Option<RouterConfig>,
+    /// Per-provider circuit breaker thresholds.
+    #[serde(default)]
+    pub breaker: BreakerConfig,
+    /// How long each provider's discovered model list is cached before
+    /// it is fetched again.  `0` disables model discovery.
+    #[serde(default = "d_3600")]
+    pub model_discovery_ttl_sec: u64,
 }
 
 impl Default for LlmConfig {
@@ -56,6 +63,28 @@ impl Default for LlmConfig {
             providers: Vec::new(),
             pricing: HashMap::new(),
             router: None,
+            breaker: BreakerConfig::default(),
+            model_discovery_ttl_sec: 3600,
+        }
+    }
+}
+
+/// Circuit breaker thresholds applied to every provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerConfig {
+    /// Consecutive failures before a provider's breaker opens.
+    #[serde(default = "d_5")]
+    pub failure_threshold: u32,
+    /// Seconds an open breaker waits before admitting a probe request.
+    #[serde(default = "d_30")]
+    pub cooldown_sec: u64,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_sec: 30,
         }
     }
 }
@@ -131,6 +160,15 @@ pub struct ProviderConfig {
     pub auth: AuthConfig,
     #[serde(default)]
     pub default_model: Option<String>,
+    /// TCP/TLS connect timeout for this provider's HTTP client.
+    #[serde(default = "d_10")]
+    pub connect_timeout_sec: u64,
+    /// Overall timeout for a single request, including the response body.
+    #[serde(default = "d_120")]
+    pub request_timeout_sec: u64,
+    /// Maximum silence between streamed chunks before the stream is aborted.
+    #[serde(default = "d_60")]
+    pub stream_idle_timeout_sec: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -196,6 +234,25 @@ fn d_20000u() -> u64 {
 fn d_2() -> u32 {
     2
 }
+fn d_5() -> u32 {
+    5
+}
+fn d_30() -> u64 {
+    30
+}
+fn d_10() -> u64 {
+    10
+}
+fn d_60() -> u64 {
+    60
+}
+fn d_120() -> u64 {
+    120
+}
+
+fn d_3600() -> u64 {
+    3600
+}
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Smart router types
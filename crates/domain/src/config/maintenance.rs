@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Background maintenance intervals
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Periods (in seconds) of the gateway's background loops, plus the node
+/// staleness threshold the prune loop applies.
+///
+/// Every value must be non-zero; `Config::validate` reports zeros as
+/// errors.  The run retention limits are optional (unset = keep runs on
+/// disk forever) but likewise may not be zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// Flush the session table to disk.
+    #[serde(default = "d_30")]
+    pub session_flush_secs: u64,
+    /// Flush pending delivery read-state changes.
+    #[serde(default = "d_30")]
+    pub delivery_flush_secs: u64,
+    /// Clean up finished processes and prune idle session locks and task
+    /// runners.
+    #[serde(default = "d_60")]
+    pub process_cleanup_secs: u64,
+    /// Drop nodes that stopped sending heartbeats.
+    #[serde(default = "d_30")]
+    pub node_prune_secs: u64,
+    /// How long a node may go unseen before the prune loop removes it.
+    /// Raise it for nodes on flaky links.
+    #[serde(default = "d_120")]
+    pub node_stale_secs: u64,
+    /// Remove stale import staging directories.
+    #[serde(default = "d_3600")]
+    pub import_sweep_secs: u64,
+    /// Purge expired memory tombstones (soft delete only).
+    #[serde(default = "d_600")]
+    pub tombstone_purge_secs: u64,
+    /// Check for due schedules.  Lower for near-real-time triggering.
+    #[serde(default = "d_30")]
+    pub schedule_tick_secs: u64,
+    /// Delete persisted runs beyond the retention limits below.
+    #[serde(default = "d_3600")]
+    pub run_prune_secs: u64,
+    /// Delete finished runs that started more than this many days ago.
+    #[serde(default)]
+    pub run_retention_days: Option<u64>,
+    /// Keep at most this many finished runs on disk, deleting the oldest
+    /// first.  Unfinished runs are not counted.
+    #[serde(default)]
+    pub run_retention_max: Option<usize>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            session_flush_secs: 30,
+            delivery_flush_secs: 30,
+            process_cleanup_secs: 60,
+            node_prune_secs: 30,
+            node_stale_secs: 120,
+            import_sweep_secs: 3_600,
+            tombstone_purge_secs: 600,
+            schedule_tick_secs: 30,
+            run_prune_secs: 3_600,
+            run_retention_days: None,
+            run_retention_max: None,
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    /// Every interval and threshold as `(field name, seconds)`, for
+    /// validation.
+    pub fn intervals(&self) -> [(&'static str, u64); 9] {
+        [
+            ("session_flush_secs", self.session_flush_secs),
+            ("delivery_flush_secs", self.delivery_flush_secs),
+            ("process_cleanup_secs", self.process_cleanup_secs),
+            ("node_prune_secs", self.node_prune_secs),
+            ("node_stale_secs", self.node_stale_secs),
+            ("import_sweep_secs", self.import_sweep_secs),
+            ("tombstone_purge_secs", self.tombstone_purge_secs),
+            ("schedule_tick_secs", self.schedule_tick_secs),
+            ("run_prune_secs", self.run_prune_secs),
+        ]
+    }
+
+    /// `secs` as a tick period.  Clamped to at least one second so an
+    /// unvalidated zero can't make `tokio::time::interval` panic.
+    pub fn period(secs: u64) -> Duration {
+        Duration::from_secs(secs.max(1))
+    }
+}
+
+fn d_30() -> u64 {
+    30
+}
+fn d_60() -> u64 {
+    60
+}
+fn d_120() -> u64 {
+    120
+}
+fn d_600() -> u64 {
+    600
+}
+fn d_3600() -> u64 {
+    3_600
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_apply_when_unset() {
+        let cfg: MaintenanceConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(cfg.session_flush_secs, 30);
+        assert_eq!(cfg.delivery_flush_secs, 30);
+        assert_eq!(cfg.process_cleanup_secs, 60);
+        assert_eq!(cfg.node_prune_secs, 30);
+        assert_eq!(cfg.node_stale_secs, 120);
+        assert_eq!(cfg.import_sweep_secs, 3_600);
+        assert_eq!(cfg.tombstone_purge_secs, 600);
+        assert_eq!(cfg.schedule_tick_secs, 30);
+        assert_eq!(cfg.run_prune_secs, 3_600);
+        assert_eq!(cfg.run_retention_days, None);
+        assert_eq!(cfg.run_retention_max, None);
+    }
+
+    #[test]
+    fn configured_intervals_are_respected() {
+        let cfg: MaintenanceConfig =
+            serde_json::from_str(r#"{"schedule_tick_secs": 10, "node_prune_secs": 5}"#).unwrap();
+        assert_eq!(
+            MaintenanceConfig::period(cfg.schedule_tick_secs),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            MaintenanceConfig::period(cfg.node_prune_secs),
+            Duration::from_secs(5)
+        );
+        // Unset fields keep their defaults.
+        assert_eq!(cfg.session_flush_secs, 30);
+    }
+
+    #[test]
+    fn zero_period_is_clamped() {
+        assert_eq!(MaintenanceConfig::period(0), Duration::from_secs(1));
+    }
+}
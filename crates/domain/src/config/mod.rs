@@ -2,7 +2,9 @@ mod agents;
 mod compaction;
 mod context;
 mod llm;
+mod maintenance;
 mod mcp;
+mod nodes;
 mod observability;
 mod pruning;
 mod quota;
@@ -17,7 +19,9 @@ pub use agents::*;
 pub use compaction::*;
 pub use context::*;
 pub use llm::*;
+pub use maintenance::*;
 pub use mcp::*;
+pub use nodes::*;
 pub use observability::*;
 pub use pruning::*;
 pub use quota::*;
@@ -77,6 +81,12 @@ pub struct Config {
     /// Per-agent daily token and cost quota limits.
     #[serde(default)]
     pub quota: QuotaConfig,
+    /// Background loop intervals (flushes, pruning, schedule tick).
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// Per-node tokens and revocations for node WebSocket auth.
+    #[serde(default)]
+    pub nodes: NodesConfig,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -195,6 +205,15 @@ impl Config {
             });
         }
 
+        // The router's per-call timeout must leave room for a response.
+        if self.llm.default_timeout_ms == 0 {
+            errors.push(ConfigError {
+                severity: ConfigSeverity::Error,
+                field: "llm.default_timeout_ms".into(),
+                message: "default_timeout_ms must be greater than 0".into(),
+            });
+        }
+
         // Track seen provider IDs for duplicate detection.
         let mut seen_ids: HashSet<&str> = HashSet::new();
 
@@ -230,6 +249,21 @@ impl Config {
                 });
             }
 
+            // Timeouts of zero would fail every request immediately.
+            for (name, secs) in [
+                ("connect_timeout_sec", provider.connect_timeout_sec),
+                ("request_timeout_sec", provider.request_timeout_sec),
+                ("stream_idle_timeout_sec", provider.stream_idle_timeout_sec),
+            ] {
+                if secs == 0 {
+                    errors.push(ConfigError {
+                        severity: ConfigSeverity::Error,
+                        field: format!("llm.providers[{i}].{name}"),
+                        message: format!("{name} must be greater than 0"),
+                    });
+                }
+            }
+
             // Duplicate provider ID detection.
             if !provider.id.is_empty() && !seen_ids.insert(&provider.id) {
                 errors.push(ConfigError {
@@ -325,6 +359,22 @@ impl Config {
             }
         }
 
+        // Exec rlimits of zero would make every command fail to start.
+        let limits = &self.tools.exec.limits;
+        for (name, value) in [
+            ("max_memory_mb", limits.max_memory_mb),
+            ("max_cpu_secs", limits.max_cpu_secs),
+            ("max_open_files", limits.max_open_files),
+        ] {
+            if value == Some(0) {
+                errors.push(ConfigError {
+                    severity: ConfigSeverity::Error,
+                    field: format!("tools.exec.limits.{name}"),
+                    message: "must be greater than 0 (omit it for no limit)".into(),
+                });
+            }
+        }
+
         // ── Observability validation ──────────────────────────────────
         if !(0.0..=1.0).contains(&self.observability.sample_rate) {
             errors.push(ConfigError {
@@ -337,6 +387,49 @@ impl Config {
             });
         }
 
+        // ── Maintenance intervals ─────────────────────────────────────
+        for (name, secs) in self.maintenance.intervals() {
+            if secs == 0 {
+                errors.push(ConfigError {
+                    severity: ConfigSeverity::Error,
+                    field: format!("maintenance.{name}"),
+                    message: "must be greater than 0".into(),
+                });
+            }
+        }
+
+        let m = &self.maintenance;
+        for (name, is_zero) in [
+            ("run_retention_days", m.run_retention_days == Some(0)),
+            ("run_retention_max", m.run_retention_max == Some(0)),
+        ] {
+            if is_zero {
+                errors.push(ConfigError {
+                    severity: ConfigSeverity::Error,
+                    field: format!("maintenance.{name}"),
+                    message: "must be greater than 0 (omit it to keep runs forever)".into(),
+                });
+            }
+        }
+
+        // ── Node tool timeouts ────────────────────────────────────────
+        if self.nodes.tool_timeout_secs == 0 {
+            errors.push(ConfigError {
+                severity: ConfigSeverity::Error,
+                field: "nodes.tool_timeout_secs".into(),
+                message: "must be greater than 0".into(),
+            });
+        }
+        for (node_id, secs) in &self.nodes.tool_timeouts {
+            if *secs == 0 {
+                errors.push(ConfigError {
+                    severity: ConfigSeverity::Error,
+                    field: format!("nodes.tool_timeouts.{node_id}"),
+                    message: "must be greater than 0".into(),
+                });
+            }
+        }
+
         // ── MCP server validation ─────────────────────────────────────
         let mut seen_mcp_ids: HashSet<&str> = HashSet::new();
         for (i, server) in self.mcp.servers.iter().enumerate() {
@@ -421,6 +514,9 @@ mod tests {
                         ..AuthConfig::default()
                     },
                     default_model: None,
+                    connect_timeout_sec: 10,
+                    request_timeout_sec: 120,
+                    stream_idle_timeout_sec: 60,
                 }],
                 ..LlmConfig::default()
             },
@@ -504,6 +600,25 @@ mod tests {
         assert_eq!(issue.severity, ConfigSeverity::Error);
     }
 
+    #[test]
+    fn zero_timeouts_are_errors() {
+        let mut cfg = valid_config();
+        cfg.llm.default_timeout_ms = 0;
+        cfg.llm.providers[0].request_timeout_sec = 0;
+        cfg.llm.providers[0].stream_idle_timeout_sec = 0;
+        let issues = cfg.validate();
+        for field in [
+            "llm.default_timeout_ms",
+            "llm.providers[0].request_timeout_sec",
+            "llm.providers[0].stream_idle_timeout_sec",
+        ] {
+            let issue =
+                find_issue(&issues, field).unwrap_or_else(|| panic!("expected {field} error"));
+            assert_eq!(issue.severity, ConfigSeverity::Error);
+        }
+        assert!(find_issue(&issues, "llm.providers[0].connect_timeout_sec").is_none());
+    }
+
     // ── Provider auth completeness ──────────────────────────────────
 
     #[test]
@@ -611,6 +726,9 @@ mod tests {
                 ..AuthConfig::default()
             },
             default_model: None,
+            connect_timeout_sec: 10,
+            request_timeout_sec: 120,
+            stream_idle_timeout_sec: 60,
         };
         cfg.llm.providers.push(second);
         let issues = cfg.validate();
@@ -635,6 +753,9 @@ mod tests {
                 ..AuthConfig::default()
             },
             default_model: None,
+            connect_timeout_sec: 10,
+            request_timeout_sec: 120,
+            stream_idle_timeout_sec: 60,
         };
         cfg.llm.providers.push(second);
         let issues = cfg.validate();
@@ -726,6 +847,18 @@ mod tests {
         assert!(find_issue(&issues, "tools.exec_security.denied_patterns").is_none());
     }
 
+    #[test]
+    fn zero_exec_limit_is_error() {
+        let mut cfg = valid_config();
+        cfg.tools.exec.limits.max_cpu_secs = Some(0);
+        cfg.tools.exec.limits.max_open_files = Some(256);
+        let issues = cfg.validate();
+        let issue = find_issue(&issues, "tools.exec.limits.max_cpu_secs")
+            .expect("expected error for zero CPU limit");
+        assert_eq!(issue.severity, ConfigSeverity::Error);
+        assert!(find_issue(&issues, "tools.exec.limits.max_open_files").is_none());
+    }
+
     // ── CORS wildcard warning ───────────────────────────────────────
 
     #[test]
@@ -750,6 +883,33 @@ mod tests {
         assert_eq!(issue.severity, ConfigSeverity::Warning);
     }
 
+    // ── Maintenance intervals ───────────────────────────────────────
+
+    #[test]
+    fn zero_maintenance_interval_is_error() {
+        let mut cfg = valid_config();
+        cfg.maintenance.schedule_tick_secs = 0;
+        let issues = cfg.validate();
+        let issue = find_issue(&issues, "maintenance.schedule_tick_secs")
+            .expect("expected zero-interval error");
+        assert_eq!(issue.severity, ConfigSeverity::Error);
+    }
+
+    #[test]
+    fn zero_run_retention_is_error() {
+        let mut cfg = valid_config();
+        cfg.maintenance.run_retention_max = Some(0);
+        let issues = cfg.validate();
+        assert!(find_issue(&issues, "maintenance.run_retention_max").is_some());
+        assert!(find_issue(&issues, "maintenance.run_retention_days").is_none());
+    }
+
+    #[test]
+    fn default_maintenance_intervals_pass() {
+        let issues = valid_config().validate();
+        assert!(find_issue(&issues, "maintenance.").is_none());
+    }
+
     // ── Display formatting ──────────────────────────────────────────
 
     #[test]
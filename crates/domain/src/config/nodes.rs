@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Nodes (auth + tool requests)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Connected-node settings: per-node credentials for `/v1/nodes/ws` and
+/// tool request timeouts.
+///
+/// A node with its own token must present exactly that token; the shared
+/// `SA_NODE_TOKEN` only admits nodes without one.  This lets a single
+/// compromised node be rotated or revoked without touching the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodesConfig {
+    /// node_id → env var holding that node's token.  Merged with the
+    /// `SA_NODE_TOKENS` env var (`"node1:tokA,node2:tokB"`).
+    #[serde(default)]
+    pub tokens: HashMap<String, String>,
+    /// node_ids refused regardless of the token they present.
+    #[serde(default)]
+    pub revoked: Vec<String>,
+    /// How long to wait for a node's `tool_response` (seconds).
+    #[serde(default = "d_300")]
+    pub tool_timeout_secs: u64,
+    /// Per-node overrides of `tool_timeout_secs` (node_id → seconds).
+    #[serde(default)]
+    pub tool_timeouts: HashMap<String, u64>,
+    /// Tool requests a single node may have outstanding at once.  Further
+    /// calls fail fast with "node overloaded" instead of queueing
+    /// (0 = unlimited).
+    #[serde(default = "d_50")]
+    pub max_outstanding: usize,
+    /// Lowest `node_hello` protocol version accepted.  Nodes speaking a
+    /// newer protocol than the gateway are always refused.
+    #[serde(default = "d_1")]
+    pub min_protocol_version: u32,
+    /// Lowest node SDK version accepted (dotted numeric, e.g. `"0.4.0"`).
+    /// Unset admits any version.
+    #[serde(default)]
+    pub min_node_version: Option<String>,
+}
+
+impl Default for NodesConfig {
+    fn default() -> Self {
+        Self {
+            tokens: HashMap::new(),
+            revoked: Vec::new(),
+            tool_timeout_secs: 300,
+            tool_timeouts: HashMap::new(),
+            max_outstanding: 50,
+            min_protocol_version: 1,
+            min_node_version: None,
+        }
+    }
+}
+
+fn d_300() -> u64 {
+    300
+}
+fn d_50() -> usize {
+    50
+}
+fn d_1() -> u32 {
+    1
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // SerialMemory connection
@@ -22,6 +23,15 @@ pub struct SerialMemoryConfig {
     pub max_retries: u32,
     #[serde(default = "d_user")]
     pub default_user_id: String,
+    /// Provenance filtering for facts retrieved into the USER_FACTS block.
+    #[serde(default)]
+    pub user_facts: UserFactsSourceConfig,
+    /// Tombstone-then-purge behaviour for `DELETE /v1/memory/:id`.
+    #[serde(default)]
+    pub soft_delete: SoftDeleteConfig,
+    /// Behaviour when SerialMemory cannot be reached.
+    #[serde(default)]
+    pub availability: MemoryAvailabilityConfig,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -43,10 +53,164 @@ impl Default for SerialMemoryConfig {
             timeout_ms: 8000,
             max_retries: 3,
             default_user_id: d_user(),
+            user_facts: UserFactsSourceConfig::default(),
+            soft_delete: SoftDeleteConfig::default(),
+            availability: MemoryAvailabilityConfig::default(),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Soft delete
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// When enabled, deleting a memory only tombstones it: the entry is
+/// flagged `deleted` upstream, hidden from search, and restorable until
+/// `retention_hours` pass, after which it is hard-deleted.  Tombstones are
+/// kept in `state_path/memory/tombstones.json` across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoftDeleteConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "d_72")]
+    pub retention_hours: u64,
+}
+
+impl Default for SoftDeleteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_hours: 72,
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Availability
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// What the gateway does when SerialMemory is unreachable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryUnavailablePolicy {
+    /// Start anyway; memory reads return empty results until a background
+    /// probe sees the server again.
+    #[default]
+    Degrade,
+    /// Refuse to start unless the startup health check succeeds.
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryAvailabilityConfig {
+    #[serde(default)]
+    pub on_unavailable: MemoryUnavailablePolicy,
+    /// Seconds between reconnect probes while degraded.
+    #[serde(default = "d_30")]
+    pub probe_interval_secs: u64,
+}
+
+impl Default for MemoryAvailabilityConfig {
+    fn default() -> Self {
+        Self {
+            on_unavailable: MemoryUnavailablePolicy::Degrade,
+            probe_interval_secs: 30,
         }
     }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// USER_FACTS provenance filtering
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Which retrieved memories are allowed into the USER_FACTS block, keyed
+/// by the memory `source` recorded at ingest time (`auto_capture`,
+/// `session_summary`, `explicit`, ...).  Memories without a source are
+/// matched against the empty string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserFactsSourceConfig {
+    /// Sources whose memories are never injected.
+    #[serde(default)]
+    pub exclude_sources: Vec<String>,
+    /// Per-source minimum similarity score.  Memories from a listed source
+    /// scoring below the threshold (or carrying no score) are dropped.
+    #[serde(default)]
+    pub min_similarity: HashMap<String, f64>,
+    /// Sources emitted first, in list order.  Unlisted sources keep their
+    /// retrieval order after the preferred ones.
+    #[serde(default)]
+    pub preferred_sources: Vec<String>,
+    /// Whether persona attributes lead the block (and get the budget
+    /// first) or follow the retrieved facts.
+    #[serde(default)]
+    pub persona_position: PersonaPosition,
+    /// Which side is kept when a retrieved `key: value` fact contradicts
+    /// a persona attribute with the same key.
+    #[serde(default)]
+    pub on_conflict: FactConflictPolicy,
+    /// Drop retrieved facts that restate a persona attribute.
+    #[serde(default = "d_true")]
+    pub dedupe_persona: bool,
+}
+
+impl Default for UserFactsSourceConfig {
+    fn default() -> Self {
+        Self {
+            exclude_sources: Vec::new(),
+            min_similarity: HashMap::new(),
+            preferred_sources: Vec::new(),
+            persona_position: PersonaPosition::default(),
+            on_conflict: FactConflictPolicy::default(),
+            dedupe_persona: true,
+        }
+    }
+}
+
+/// Placement of persona attributes relative to retrieved facts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonaPosition {
+    /// Persona first; retrieved facts fill the remaining budget.
+    #[default]
+    First,
+    /// Retrieved facts first; persona fills the remaining budget.
+    Last,
+}
+
+/// Resolution when a retrieved fact and a persona attribute disagree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FactConflictPolicy {
+    /// Keep the persona attribute, drop the fact.
+    #[default]
+    PersonaWins,
+    /// Keep the fact, drop the persona attribute.
+    FactsWin,
+}
+
+impl UserFactsSourceConfig {
+    /// Whether a memory with the given source and similarity passes the
+    /// exclusion list and per-source threshold.
+    pub fn admits(&self, source: &str, similarity: Option<f64>) -> bool {
+        if self.exclude_sources.iter().any(|s| s == source) {
+            return false;
+        }
+        match self.min_similarity.get(source) {
+            Some(&min) => similarity.is_some_and(|score| score >= min),
+            None => true,
+        }
+    }
+
+    /// Sort rank for a source: its index in `preferred_sources`, or
+    /// `preferred_sources.len()` when unlisted.
+    pub fn preference_rank(&self, source: &str) -> usize {
+        self.preferred_sources
+            .iter()
+            .position(|s| s == source)
+            .unwrap_or(self.preferred_sources.len())
+    }
+}
+
 // ── serde default helpers ───────────────────────────────────────────
 
 fn d_sm_url() -> String {
@@ -61,6 +225,15 @@ fn d_8000() -> u64 {
 fn d_3() -> u32 {
     3
 }
+fn d_30() -> u64 {
+    30
+}
+fn d_72() -> u64 {
+    72
+}
 fn d_user() -> String {
     "default_user".into()
 }
+fn d_true() -> bool {
+    true
+}
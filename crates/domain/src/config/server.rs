@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
@@ -23,6 +24,11 @@ pub struct ServerConfig {
     /// Environment variable holding the API bearer token (fallback).
     #[serde(default = "d_api_token_env")]
     pub api_token_env: String,
+    /// Additional scoped API tokens: env var name → scopes granted to the
+    /// token it holds.  The `api_token` / `api_token_env` token above keeps
+    /// full scope.  Auth is enforced when any token is configured.
+    #[serde(default)]
+    pub api_tokens: HashMap<String, Vec<ApiScope>>,
     /// Per-IP token-bucket rate limiting configuration.
     /// When `None` (the default), rate limiting is disabled — suitable for local
     /// development.  Set `requests_per_second` and `burst_size` in production.
@@ -33,6 +39,13 @@ pub struct ServerConfig {
     /// prevents multiple instances from running with the same PID file.
     #[serde(default)]
     pub pid_file: Option<PathBuf>,
+    /// Request body size caps, applied per route group.
+    #[serde(default)]
+    pub body_limits: BodyLimitsConfig,
+    /// On SIGINT/SIGTERM, how long to wait for in-flight turns and node
+    /// tool calls to finish before flushing stores and exiting.
+    #[serde(default = "d_30")]
+    pub shutdown_grace_secs: u64,
 }
 
 impl Default for ServerConfig {
@@ -43,12 +56,58 @@ impl Default for ServerConfig {
             cors: CorsConfig::default(),
             api_token: None,
             api_token_env: d_api_token_env(),
+            api_tokens: HashMap::new(),
             rate_limit: None,
             pid_file: None,
+            body_limits: BodyLimitsConfig::default(),
+            shutdown_grace_secs: 30,
+        }
+    }
+}
+
+/// Maximum request body sizes in bytes.  Oversized requests are rejected
+/// with 413 before the handler runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyLimitsConfig {
+    /// Control endpoints (sessions, schedules, tools, admin, ...).
+    #[serde(default = "d_64k")]
+    pub control_bytes: usize,
+    /// Content-carrying endpoints: chat, OpenAI-compatible completions,
+    /// inbound events, memory ingest, task submission, webhook triggers,
+    /// and workspace file writes.
+    #[serde(default = "d_4m")]
+    pub ingest_bytes: usize,
+}
+
+impl Default for BodyLimitsConfig {
+    fn default() -> Self {
+        Self {
+            control_bytes: d_64k(),
+            ingest_bytes: d_4m(),
         }
     }
 }
 
+/// What an API token may do.
+///
+/// - `read`  — `GET`/`HEAD` on non-admin routes (dashboards, monitoring).
+/// - `chat`  — state-changing requests on non-admin routes (chat, tasks,
+///   schedules, memory writes, ...).
+/// - `admin` — anything under the admin and import routes, plus tool
+///   execution, exec approvals, ClawHub installs, node self-tests, model
+///   refreshes, router reconfiguration and skill reloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    Read,
+    Chat,
+    Admin,
+}
+
+impl ApiScope {
+    pub const ALL: [ApiScope; 3] = [ApiScope::Read, ApiScope::Chat, ApiScope::Admin];
+}
+
 /// Per-IP token-bucket rate limiting configuration.
 ///
 /// `requests_per_second` controls the replenishment rate, while `burst_size`
@@ -96,6 +155,15 @@ fn d_cors_origins() -> Vec<String> {
 fn d_api_token_env() -> String {
     "SA_API_TOKEN".into()
 }
+fn d_30() -> u64 {
+    30
+}
+fn d_64k() -> usize {
+    64 * 1024
+}
+fn d_4m() -> usize {
+    4 * 1024 * 1024
+}
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Tests
@@ -147,6 +215,34 @@ mod tests {
         assert_eq!(cfg.api_token_env, "SA_API_TOKEN");
         assert!(cfg.rate_limit.is_none());
         assert!(cfg.pid_file.is_none());
+        assert_eq!(cfg.body_limits.control_bytes, 64 * 1024);
+        assert_eq!(cfg.body_limits.ingest_bytes, 4 * 1024 * 1024);
+    }
+
+    #[test]
+    fn server_config_parses_scoped_tokens() {
+        let toml_str = r#"
+            [api_tokens]
+            SA_DASHBOARD_TOKEN = ["read"]
+            SA_BOT_TOKEN = ["read", "chat"]
+        "#;
+        let cfg: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(cfg.api_tokens["SA_DASHBOARD_TOKEN"], vec![ApiScope::Read]);
+        assert_eq!(
+            cfg.api_tokens["SA_BOT_TOKEN"],
+            vec![ApiScope::Read, ApiScope::Chat]
+        );
+    }
+
+    #[test]
+    fn server_config_parses_body_limits() {
+        let toml_str = r#"
+            [body_limits]
+            control_bytes = 1024
+        "#;
+        let cfg: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(cfg.body_limits.control_bytes, 1024);
+        assert_eq!(cfg.body_limits.ingest_bytes, 4 * 1024 * 1024);
     }
 
     #[test]
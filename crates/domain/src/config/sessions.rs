@@ -29,6 +29,10 @@ pub struct SessionsConfig {
     /// Send policy — controls whether the agent responds in different contexts.
     #[serde(default)]
     pub send_policy: SendPolicyConfig,
+
+    /// Per-session turn lock (queueing) settings.
+    #[serde(default)]
+    pub locks: SessionLockConfig,
 }
 
 impl Default for SessionsConfig {
@@ -39,6 +43,7 @@ impl Default for SessionsConfig {
             identity_links: Vec::new(),
             lifecycle: LifecycleConfig::default(),
             send_policy: SendPolicyConfig::default(),
+            locks: SessionLockConfig::default(),
         }
     }
 }
@@ -130,6 +135,31 @@ pub struct InboundMetadata {
     pub is_direct: bool,
 }
 
+/// Per-session turn lock settings.  Turns on one session run one at a time
+/// in arrival order; extra arrivals wait in a bounded queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionLockConfig {
+    /// Maximum turns waiting behind the running one.  Further arrivals are
+    /// rejected as busy (HTTP 429).
+    #[serde(default = "d_max_queue_depth")]
+    pub max_queue_depth: usize,
+    /// Force-release a session lock held longer than this (a leaked or
+    /// wedged turn), logging a warning.  `0` (the default) disables it: a
+    /// turn that is merely slow keeps its lock, since releasing it would
+    /// let a second turn run on the session concurrently.
+    #[serde(default = "d_max_hold_secs")]
+    pub max_hold_secs: u64,
+}
+
+impl Default for SessionLockConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_depth: d_max_queue_depth(),
+            max_hold_secs: d_max_hold_secs(),
+        }
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Send policy
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -177,3 +207,9 @@ fn d_allow() -> SendPolicyMode {
 fn d_true() -> bool {
     true
 }
+fn d_max_queue_depth() -> usize {
+    4
+}
+fn d_max_hold_secs() -> u64 {
+    0
+}
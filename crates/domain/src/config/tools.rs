@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -11,6 +13,10 @@ pub struct ToolsConfig {
     pub exec: ExecConfig,
     #[serde(default)]
     pub exec_security: ExecSecurityConfig,
+    #[serde(default)]
+    pub result_cache: ToolResultCacheConfig,
+    #[serde(default)]
+    pub rate_limit: ToolRateLimitConfig,
 }
 
 /// Exec tool configuration (matches OpenClaw semantics).
@@ -37,6 +43,73 @@ pub struct ExecConfig {
     /// Skip notification if exit code is 0 and output is empty.
     #[serde(default)]
     pub notify_on_exit_empty_success: bool,
+    /// How commands are launched.
+    #[serde(default)]
+    pub mode: ExecMode,
+    /// Shell metacharacters tolerated unquoted in argv mode (e.g. `"{}"`
+    /// for `find -exec`).  Each character of the string is allowed.
+    #[serde(default)]
+    pub allowed_metachars: String,
+    /// Maximum processes running at once (every exec may auto-background,
+    /// so all running sessions count).  0 = unlimited.
+    #[serde(default = "d_32")]
+    pub max_background_processes: usize,
+    /// Largest single `process write` to stdin, in bytes (0 = unlimited).
+    #[serde(default = "d_1048576")]
+    pub max_stdin_write_bytes: usize,
+    /// Total bytes that may be written to one session's stdin
+    /// (0 = unlimited).
+    #[serde(default = "d_67108864")]
+    pub max_stdin_total_bytes: usize,
+    /// Kernel resource limits applied to every spawned command (Unix only).
+    #[serde(default)]
+    pub limits: ExecLimitsConfig,
+    /// Syscall sandbox applied to every spawned command (Linux only).
+    #[serde(default)]
+    pub sandbox: ExecSandboxConfig,
+}
+
+/// Per-process rlimits for exec'd commands.  Unset = inherit the
+/// gateway's own limits.  Ignored on non-Unix platforms.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecLimitsConfig {
+    /// Address-space limit (`RLIMIT_AS`), in MiB.
+    #[serde(default)]
+    pub max_memory_mb: Option<u64>,
+    /// CPU time limit (`RLIMIT_CPU`), in seconds.  The kernel sends
+    /// SIGXCPU at the limit and SIGKILL one second later.
+    #[serde(default)]
+    pub max_cpu_secs: Option<u64>,
+    /// Open file descriptor limit (`RLIMIT_NOFILE`).
+    #[serde(default)]
+    pub max_open_files: Option<u64>,
+}
+
+/// Seccomp sandbox for exec'd commands.  Requires Linux and the gateway's
+/// `sandbox` feature; when enabled but unavailable, exec refuses to run
+/// rather than run unsandboxed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecSandboxConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Allow IP sockets.  When false, only Unix-domain sockets can be
+    /// opened, so commands have no network access.
+    #[serde(default)]
+    pub allow_network: bool,
+}
+
+/// How the exec tool launches a command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecMode {
+    /// Run through `sh -c`: pipes, redirects and globs work, and
+    /// `denied_patterns` is the only guard against chained commands.
+    #[default]
+    Shell,
+    /// Split the command into program + args with shell-words quoting and
+    /// run it without a shell.  Unquoted metacharacters (`;`, `|`, `&`,
+    /// `$`, backticks, redirects, globs) are rejected.
+    Argv,
 }
 
 impl Default for ExecConfig {
@@ -49,6 +122,13 @@ impl Default for ExecConfig {
             pending_max_output_chars: 500_000,
             notify_on_exit: true,
             notify_on_exit_empty_success: false,
+            mode: ExecMode::Shell,
+            allowed_metachars: String::new(),
+            max_background_processes: 32,
+            max_stdin_write_bytes: 1_048_576,
+            max_stdin_total_bytes: 67_108_864,
+            limits: ExecLimitsConfig::default(),
+            sandbox: ExecSandboxConfig::default(),
         }
     }
 }
@@ -82,6 +162,74 @@ impl Default for ExecSecurityConfig {
     }
 }
 
+/// Result cache for idempotent, read-only tools (e.g. `file.read`).
+///
+/// Identical calls within `ttl_secs` reuse the previous successful result.
+/// Any call to a non-idempotent tool flushes the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolResultCacheConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a cached result stays valid (seconds).
+    #[serde(default = "d_60")]
+    pub ttl_secs: u64,
+    /// Maximum cached results; expired and oldest entries are evicted first.
+    #[serde(default = "d_512")]
+    pub max_entries: usize,
+}
+
+impl Default for ToolResultCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 60,
+            max_entries: 512,
+        }
+    }
+}
+
+/// Per-session call rate limits for tools, so a runaway or prompt-injected
+/// agent can't hammer a tool in a tight loop.
+///
+/// Limits are calls per minute per `(session, tool)` — or per sub-agent or
+/// direct caller for calls without a session; 0 = unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRateLimitConfig {
+    #[serde(default = "d_true")]
+    pub enabled: bool,
+    /// Limit for tools without an entry in `per_tool`.
+    #[serde(default)]
+    pub default_per_minute: u32,
+    /// Per-tool overrides keyed by tool name (e.g. `exec`, `web.fetch`).
+    #[serde(default = "d_rate_limit_per_tool")]
+    pub per_tool: HashMap<String, u32>,
+}
+
+impl ToolRateLimitConfig {
+    /// Calls per minute allowed for `tool_name`, or `None` when unlimited.
+    pub fn limit_for(&self, tool_name: &str) -> Option<u32> {
+        if !self.enabled {
+            return None;
+        }
+        let limit = self
+            .per_tool
+            .get(tool_name)
+            .copied()
+            .unwrap_or(self.default_per_minute);
+        (limit > 0).then_some(limit)
+    }
+}
+
+impl Default for ToolRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_per_minute: 0,
+            per_tool: d_rate_limit_per_tool(),
+        }
+    }
+}
+
 // ── serde default helpers ───────────────────────────────────────────
 
 fn d_10000() -> u64 {
@@ -105,6 +253,24 @@ fn d_true() -> bool {
 fn d_300() -> u64 {
     300
 }
+fn d_60() -> u64 {
+    60
+}
+fn d_512() -> usize {
+    512
+}
+fn d_32() -> usize {
+    32
+}
+fn d_1048576() -> usize {
+    1_048_576
+}
+fn d_67108864() -> usize {
+    67_108_864
+}
+fn d_rate_limit_per_tool() -> HashMap<String, u32> {
+    HashMap::from([("exec".into(), 30), ("web.fetch".into(), 30)])
+}
 fn d_denied_patterns() -> Vec<String> {
     vec![
         // Destructive filesystem operations (multiple flag formats)
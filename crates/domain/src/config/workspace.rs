@@ -30,12 +30,18 @@ impl Default for WorkspaceConfig {
 pub struct SkillsConfig {
     #[serde(default = "d_skills_path")]
     pub path: PathBuf,
+    /// List only the `index_top_k` skills most relevant to the user's
+    /// message in the system prompt (the rest stay reachable through the
+    /// `skills.list` tool).  0 = list every ready skill.
+    #[serde(default)]
+    pub index_top_k: usize,
 }
 
 impl Default for SkillsConfig {
     fn default() -> Self {
         Self {
             path: PathBuf::from("./skills"),
+            index_top_k: 0,
         }
     }
 }
@@ -13,9 +13,24 @@ pub enum Error {
     #[error("timeout: {0}")]
     Timeout(String),
 
+    /// A provider call that failed in a way expected to clear on retry
+    /// (e.g. it timed out or its stream stalled).  Retry and fallback act
+    /// on it.
+    #[error("transient: {0}")]
+    Transient(String),
+
     #[error("provider {provider}: {message}")]
     Provider { provider: String, message: String },
 
+    /// The provider answered HTTP 429.  `retry_after_secs` is taken from
+    /// its `Retry-After` header when present.
+    #[error("provider {provider} rate limited: {message}")]
+    RateLimited {
+        provider: String,
+        retry_after_secs: Option<u64>,
+        message: String,
+    },
+
     #[error("SerialMemory: {0}")]
     SerialMemory(String),
 
@@ -28,6 +43,10 @@ pub enum Error {
     #[error("auth: {0}")]
     Auth(String),
 
+    /// Credentials were rejected by a provider and could not be renewed.
+    #[error("auth failed for provider {provider}: {message}")]
+    AuthFailed { provider: String, message: String },
+
     #[error("{0}")]
     Other(String),
 }
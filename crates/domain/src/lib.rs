@@ -1,6 +1,8 @@
 pub mod capability;
 pub mod config;
 pub mod error;
+pub mod persistence;
 pub mod stream;
+pub mod tokens;
 pub mod tool;
 pub mod trace;
@@ -0,0 +1,484 @@
+//! Pluggable persistence for gateway stores.
+//!
+//! Stores (`SessionStore`, `RunStore`, `ScheduleStore`, `DeliveryStore`)
+//! keep their working set in memory and persist it as opaque blobs under
+//! `/`-separated keys such as `sessions/sessions.json` or `runs/runs.jsonl`.
+//! [`FsBackend`] maps keys onto files below the state path and is the
+//! default; [`MemoryBackend`] keeps everything in process.  A shared
+//! backend (SQL, Redis, ...) only needs to implement
+//! [`PersistenceBackend`] to let several gateway instances share state.
+//!
+//! Calls are blocking; async callers run them on the blocking pool.
+//!
+//! Individual records carry a `schema_version` (see [`RecordSchema`]) so a
+//! store can upgrade what older releases wrote and keep, untouched, what
+//! newer releases wrote.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Keyed blob storage used by the gateway stores.
+pub trait PersistenceBackend: Send + Sync {
+    /// Read the blob stored under `key`, or `None` if there is none.
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Replace the blob under `key`.  Readers never observe a partial write.
+    fn save(&self, key: &str, data: &[u8]) -> Result<()>;
+
+    /// Append to the blob under `key`, creating it if missing.  Used for
+    /// JSONL logs; the default reads, concatenates and saves.
+    fn append(&self, key: &str, data: &[u8]) -> Result<()> {
+        let mut blob = self.load(key)?.unwrap_or_default();
+        blob.extend_from_slice(data);
+        self.save(key, &blob)
+    }
+
+    /// Keys starting with `prefix`, sorted.
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Record schema versioning
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Field holding a persisted record's schema version.  Records written
+/// before versioning existed have no tag and count as version 1.
+pub const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+/// Upgrades a record object by exactly one schema version.
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// v1 → v2: version 2 only introduced the `schema_version` tag itself.
+pub fn tag_unversioned(_record: &mut Map<String, Value>) {}
+
+/// Versioning policy for one kind of persisted record.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordSchema {
+    /// Version written by this release.
+    pub current: u32,
+    /// `migrations[i]` upgrades version `i + 1` to `i + 2`; there must be
+    /// `current - 1` of them.
+    pub migrations: &'static [Migration],
+}
+
+/// A record read back through [`RecordSchema::load`].
+#[derive(Debug)]
+pub enum LoadedRecord<T> {
+    /// Parsed, upgraded first if it was older than `current`.
+    Current(T),
+    /// Written by a newer release.  Keep the raw JSON and write it back
+    /// as-is so a downgrade does not lose data.
+    Future(Value),
+    /// Not a record this release can read.
+    Invalid(String),
+}
+
+impl RecordSchema {
+    /// Serialize `record` tagged with the current version.
+    pub fn stamp<T: Serialize>(&self, record: &T) -> serde_json::Result<Value> {
+        let mut value = serde_json::to_value(record)?;
+        if let Value::Object(obj) = &mut value {
+            obj.insert(SCHEMA_VERSION_FIELD.into(), self.current.into());
+        }
+        Ok(value)
+    }
+
+    /// Upgrade `value` to the current version and deserialize it.
+    pub fn load<T: DeserializeOwned>(&self, value: Value) -> LoadedRecord<T> {
+        let Value::Object(mut obj) = value else {
+            return LoadedRecord::Invalid("record is not a JSON object".into());
+        };
+        let version = match obj.get(SCHEMA_VERSION_FIELD) {
+            None => 1,
+            Some(v) => match v.as_u64() {
+                Some(v) => v.min(u32::MAX as u64) as u32,
+                None => return LoadedRecord::Invalid(format!("bad {SCHEMA_VERSION_FIELD}: {v}")),
+            },
+        };
+        if version > self.current {
+            return LoadedRecord::Future(Value::Object(obj));
+        }
+        for migration in &self.migrations[version.saturating_sub(1) as usize..] {
+            migration(&mut obj);
+        }
+        obj.remove(SCHEMA_VERSION_FIELD);
+        match serde_json::from_value(Value::Object(obj)) {
+            Ok(record) => LoadedRecord::Current(record),
+            Err(e) => LoadedRecord::Invalid(e.to_string()),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Filesystem
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Stores each key as a file below `root` (the configured state path).
+#[derive(Debug, Clone)]
+pub struct FsBackend {
+    root: PathBuf,
+}
+
+impl FsBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Filesystem path for `key`.  Keys may not escape the root.
+    pub fn path_for(&self, key: &str) -> Result<PathBuf> {
+        let rel = Path::new(key);
+        if key.is_empty() || !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(Error::Other(format!("invalid persistence key: {key:?}")));
+        }
+        Ok(self.root.join(rel))
+    }
+
+    fn collect_keys(&self, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.collect_keys(&path, out)?;
+            } else if let Ok(rel) = path.strip_prefix(&self.root) {
+                let key: Vec<_> = rel.iter().map(|c| c.to_string_lossy()).collect();
+                out.push(key.join("/"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace `path` with `data` so that a crash at any point leaves either
+/// the old or the new contents, never a truncated file.
+///
+/// Writes a sibling `.tmp` file, fsyncs it, renames it over `path`, then
+/// fsyncs the directory so the rename itself is durable.  Each call gets
+/// its own temp file (suffixed with the pid and a counter), so overlapping
+/// saves of one path never share a temp inode; the last rename wins.  A
+/// `.tmp` left behind by an interrupted write is ignored by [`FsBackend`].
+pub fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = PathBuf::from(tmp);
+
+    let written = std::fs::File::create(&tmp).and_then(|mut f| {
+        f.write_all(data)?;
+        f.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Best-effort fsync of the directory holding `path`.  Directories cannot
+/// be opened for syncing on Windows, where rename is already durable.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let dir = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Err(e) = std::fs::File::open(dir).and_then(|d| d.sync_all()) {
+            tracing::debug!(dir = %dir.display(), error = %e, "directory fsync failed");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+impl PersistenceBackend for FsBackend {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match std::fs::read(self.path_for(key)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.path_for(key)?;
+        create_parent(&path)?;
+        write_atomic(&path, data)?;
+        Ok(())
+    }
+
+    /// Appends in place.  If a previous append was cut short, the torn
+    /// line is terminated first so it cannot swallow the new record.
+    fn append(&self, key: &str, data: &[u8]) -> Result<()> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let path = self.path_for(key)?;
+        create_parent(&path)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        if file.metadata()?.len() > 0 {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                file.write_all(b"\n")?;
+            }
+        }
+        file.write_all(data)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        self.collect_keys(&self.root, &mut keys)?;
+        keys.retain(|k| k.starts_with(prefix) && !k.ends_with(".tmp"));
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// In-memory
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Process-local backend.  Nothing survives a restart; useful for tests
+/// and ephemeral gateways.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    blobs: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PersistenceBackend for MemoryBackend {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.blobs.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.blobs
+            .lock()
+            .unwrap()
+            .insert(key.to_owned(), data.to_vec());
+        Ok(())
+    }
+
+    fn append(&self, key: &str, data: &[u8]) -> Result<()> {
+        self.blobs
+            .lock()
+            .unwrap()
+            .entry(key.to_owned())
+            .or_default()
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .blobs
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(backend: &dyn PersistenceBackend) {
+        assert_eq!(backend.load("runs/runs.jsonl").unwrap(), None);
+
+        backend.append("runs/runs.jsonl", b"a\n").unwrap();
+        backend.append("runs/runs.jsonl", b"b\n").unwrap();
+        assert_eq!(
+            backend.load("runs/runs.jsonl").unwrap().as_deref(),
+            Some(&b"a\nb\n"[..])
+        );
+
+        backend.save("runs/runs.jsonl", b"b\n").unwrap();
+        backend.save("schedules.json", b"[]").unwrap();
+        assert_eq!(
+            backend.load("runs/runs.jsonl").unwrap().as_deref(),
+            Some(&b"b\n"[..])
+        );
+
+        assert_eq!(
+            backend.list("").unwrap(),
+            vec!["runs/runs.jsonl", "schedules.json"]
+        );
+        assert_eq!(backend.list("runs/").unwrap(), vec!["runs/runs.jsonl"]);
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+    struct Rec {
+        title: String,
+    }
+
+    /// v2 → v3 renamed `name` to `title`.
+    fn rename_name_to_title(obj: &mut Map<String, Value>) {
+        if let Some(name) = obj.remove("name") {
+            obj.insert("title".into(), name);
+        }
+    }
+
+    const REC_V3: RecordSchema = RecordSchema {
+        current: 3,
+        migrations: &[tag_unversioned, rename_name_to_title],
+    };
+
+    #[test]
+    fn records_are_upgraded_stamped_or_preserved() {
+        let untagged = serde_json::json!({ "name": "legacy" });
+        match REC_V3.load::<Rec>(untagged) {
+            LoadedRecord::Current(r) => assert_eq!(r.title, "legacy"),
+            other => panic!("expected upgrade, got {other:?}"),
+        }
+
+        let stamped = REC_V3.stamp(&Rec { title: "t".into() }).unwrap();
+        assert_eq!(stamped[SCHEMA_VERSION_FIELD], 3);
+        assert!(matches!(
+            REC_V3.load::<Rec>(stamped),
+            LoadedRecord::Current(_)
+        ));
+
+        let future = serde_json::json!({ "schema_version": 4, "headline": "new" });
+        match REC_V3.load::<Rec>(future.clone()) {
+            LoadedRecord::Future(raw) => assert_eq!(raw, future),
+            other => panic!("expected future record, got {other:?}"),
+        }
+
+        assert!(matches!(
+            REC_V3.load::<Rec>(serde_json::json!([1])),
+            LoadedRecord::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn memory_backend_semantics() {
+        exercise(&MemoryBackend::new());
+    }
+
+    #[test]
+    fn fs_backend_semantics() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FsBackend::new(dir.path());
+        exercise(&backend);
+        assert!(dir.path().join("runs").join("runs.jsonl").is_file());
+    }
+
+    #[test]
+    fn interrupted_save_keeps_last_good_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FsBackend::new(dir.path());
+        backend.save("schedules.json", b"[1,2,3]").unwrap();
+
+        // Simulate a crash between writing the temp file and the rename.
+        std::fs::write(dir.path().join("schedules.json.tmp"), b"[1,2").unwrap();
+
+        assert_eq!(
+            backend.load("schedules.json").unwrap().as_deref(),
+            Some(&b"[1,2,3]"[..])
+        );
+        assert_eq!(backend.list("").unwrap(), vec!["schedules.json"]);
+
+        // The next save goes through despite the stale temp file.
+        backend.save("schedules.json", b"[4]").unwrap();
+        assert_eq!(
+            backend.load("schedules.json").unwrap().as_deref(),
+            Some(&b"[4]"[..])
+        );
+        assert_eq!(backend.list("").unwrap(), vec!["schedules.json"]);
+    }
+
+    #[test]
+    fn concurrent_saves_of_one_key_stay_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FsBackend::new(dir.path());
+
+        std::thread::scope(|s| {
+            for i in 0..8 {
+                let backend = &backend;
+                s.spawn(move || {
+                    let data = serde_json::to_vec(&vec![i; 50_000]).unwrap();
+                    for _ in 0..10 {
+                        backend.save("sessions/sessions.json", &data).unwrap();
+                    }
+                });
+            }
+        });
+
+        let raw = backend.load("sessions/sessions.json").unwrap().unwrap();
+        let values: Vec<u32> = serde_json::from_slice(&raw).unwrap();
+        assert_eq!(values.len(), 50_000);
+        assert!(values.iter().all(|v| *v == values[0]));
+        assert_eq!(backend.list("").unwrap(), vec!["sessions/sessions.json"]);
+    }
+
+    #[test]
+    fn append_after_torn_line_starts_a_new_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = FsBackend::new(dir.path());
+        backend.append("runs/runs.jsonl", b"{\"a\":1}\n").unwrap();
+        // A crash mid-append leaves half a record.
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("runs/runs.jsonl"))
+            .unwrap()
+            .write_all(b"{\"b\":")
+            .unwrap();
+
+        backend.append("runs/runs.jsonl", b"{\"c\":3}\n").unwrap();
+        let log = String::from_utf8(backend.load("runs/runs.jsonl").unwrap().unwrap()).unwrap();
+        assert_eq!(
+            log.lines().collect::<Vec<_>>(),
+            vec!["{\"a\":1}", "{\"b\":", "{\"c\":3}"]
+        );
+    }
+
+    #[test]
+    fn fs_keys_cannot_escape_root() {
+        let backend = FsBackend::new("/tmp/state");
+        assert!(backend.path_for("../etc/passwd").is_err());
+        assert!(backend.path_for("/etc/passwd").is_err());
+        assert!(backend.path_for("").is_err());
+        assert!(backend.path_for("sessions/sessions.json").is_ok());
+    }
+}
@@ -0,0 +1,166 @@
+//! Pluggable size measurement for prompt budgeting.
+//!
+//! Context caps (`[context]`) are expressed in whatever unit the configured
+//! [`TokenCounter`] counts: raw characters (the historical behaviour), an
+//! approximate char/4 token estimate, or — with the `tiktoken` feature —
+//! real BPE tokens.
+
+use std::sync::{Arc, OnceLock};
+
+use crate::config::BudgetUnit;
+
+/// Measures text in budget units.
+pub trait TokenCounter: Send + Sync {
+    /// Size of `text` in budget units.
+    fn count(&self, text: &str) -> usize;
+
+    /// Longest prefix of `text` (ending on a UTF-8 boundary) whose size is
+    /// at most `budget`.
+    ///
+    /// The default binary-searches char boundaries, assuming `count` grows
+    /// with prefix length.
+    fn truncate<'t>(&self, text: &'t str, budget: usize) -> &'t str {
+        if self.count(text) <= budget {
+            return text;
+        }
+        let (mut lo, mut hi) = (0, text.len());
+        while lo < hi {
+            let mid = text.floor_char_boundary(lo + (hi - lo).div_ceil(2));
+            if mid <= lo {
+                break;
+            }
+            if self.count(&text[..mid]) <= budget {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        &text[..text.floor_char_boundary(lo)]
+    }
+}
+
+/// One unit per byte — budgets are plain character caps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CharCounter;
+
+impl TokenCounter for CharCounter {
+    fn count(&self, text: &str) -> usize {
+        text.len()
+    }
+
+    fn truncate<'t>(&self, text: &'t str, budget: usize) -> &'t str {
+        &text[..text.floor_char_boundary(budget)]
+    }
+}
+
+/// Tokenizer-free estimate: one token per four characters, rounded up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxTokenCounter;
+
+impl TokenCounter for ApproxTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+
+    fn truncate<'t>(&self, text: &'t str, budget: usize) -> &'t str {
+        &text[..text.floor_char_boundary(budget.saturating_mul(4))]
+    }
+}
+
+/// Exact `cl100k_base` BPE token counts.
+#[cfg(feature = "tiktoken")]
+pub struct TiktokenCounter {
+    bpe: tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenCounter {
+    pub fn new() -> Result<Self, crate::error::Error> {
+        let bpe = tiktoken_rs::cl100k_base()
+            .map_err(|e| crate::error::Error::Config(format!("loading tokenizer: {e}")))?;
+        Ok(Self { bpe })
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl TokenCounter for TiktokenCounter {
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_ordinary(text).len()
+    }
+}
+
+/// Shared counter for `unit`.
+///
+/// `Tokens` uses the real tokenizer when built with the `tiktoken` feature
+/// and falls back to [`ApproxTokenCounter`] otherwise (or if the tokenizer
+/// fails to load).  Counters are built once and shared.
+pub fn counter_for(unit: BudgetUnit) -> Arc<dyn TokenCounter> {
+    match unit {
+        BudgetUnit::Chars => Arc::new(CharCounter),
+        BudgetUnit::Tokens => {
+            static TOKENS: OnceLock<Arc<dyn TokenCounter>> = OnceLock::new();
+            TOKENS.get_or_init(token_counter).clone()
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+fn token_counter() -> Arc<dyn TokenCounter> {
+    match TiktokenCounter::new() {
+        Ok(counter) => Arc::new(counter),
+        Err(e) => {
+            tracing::warn!(error = %e, "falling back to approximate token counts");
+            Arc::new(ApproxTokenCounter)
+        }
+    }
+}
+
+#[cfg(not(feature = "tiktoken"))]
+fn token_counter() -> Arc<dyn TokenCounter> {
+    Arc::new(ApproxTokenCounter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts whitespace-separated words; exercises the default `truncate`.
+    struct WordCounter;
+
+    impl TokenCounter for WordCounter {
+        fn count(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn char_counter_matches_byte_length() {
+        assert_eq!(CharCounter.count("héllo"), 6);
+        assert_eq!(CharCounter.truncate("héllo", 2), "h");
+        assert_eq!(CharCounter.truncate("hello", 10), "hello");
+    }
+
+    #[test]
+    fn approx_counter_rounds_up() {
+        assert_eq!(ApproxTokenCounter.count(""), 0);
+        assert_eq!(ApproxTokenCounter.count("abcd"), 1);
+        assert_eq!(ApproxTokenCounter.count("abcde"), 2);
+        assert_eq!(ApproxTokenCounter.truncate("abcdefghij", 2), "abcdefgh");
+    }
+
+    #[test]
+    fn default_truncate_finds_longest_fitting_prefix() {
+        let text = "one two three four";
+        let cut = WordCounter.truncate(text, 2);
+        assert_eq!(WordCounter.count(cut), 2);
+        assert!(cut.starts_with("one two"));
+        assert!(!cut.contains("thr"));
+        assert_eq!(WordCounter.truncate(text, 10), text);
+    }
+
+    #[test]
+    fn counter_for_chars_is_exact() {
+        assert_eq!(counter_for(BudgetUnit::Chars).count("abcdefgh"), 8);
+        assert!(counter_for(BudgetUnit::Tokens).count("abcdefgh") < 8);
+    }
+}
@@ -18,6 +18,23 @@ pub struct ToolDefinition {
     pub parameters: serde_json::Value,
 }
 
+/// Whether, and which, tools the model may call on a request.
+///
+/// In TOML: `"auto"`, `"none"`, `"required"`, or `{ specific = "exec" }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides.
+    #[default]
+    Auto,
+    /// The model must not call tools.
+    None,
+    /// The model must call at least one tool.
+    Required,
+    /// The model must call the named tool.
+    Specific(String),
+}
+
 /// A message in the conversation (provider-agnostic).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -59,6 +76,8 @@ pub enum ContentPart {
         #[serde(default)]
         is_error: bool,
     },
+    /// An image.  `url` is an `http(s)` URL, a `data:` URL, or bare
+    /// base64 data (see [`ImageSource::parse`]).
     #[serde(rename = "image")]
     Image {
         url: String,
@@ -67,6 +86,51 @@ pub enum ContentPart {
     },
 }
 
+/// Where the bytes of a [`ContentPart::Image`] come from, as each provider
+/// needs to encode the two cases differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSource<'a> {
+    /// A remote image the provider fetches itself.
+    Url(&'a str),
+    /// Inline, base64-encoded image data.
+    Base64 { media_type: &'a str, data: &'a str },
+}
+
+impl<'a> ImageSource<'a> {
+    /// Classify an image part.  `data:<mime>;base64,<data>` URLs are split
+    /// into their parts; anything that isn't an `http(s)` URL is treated
+    /// as bare base64 of type `media_type` (default `image/png`).
+    pub fn parse(url: &'a str, media_type: Option<&'a str>) -> Self {
+        if let Some(rest) = url.strip_prefix("data:") {
+            if let Some((mime, data)) = rest.split_once(";base64,") {
+                return ImageSource::Base64 {
+                    media_type: if mime.is_empty() {
+                        media_type.unwrap_or("image/png")
+                    } else {
+                        mime
+                    },
+                    data,
+                };
+            }
+        }
+        if url.starts_with("https://") || url.starts_with("http://") {
+            return ImageSource::Url(url);
+        }
+        ImageSource::Base64 {
+            media_type: media_type.unwrap_or("image/png"),
+            data: url,
+        }
+    }
+
+    /// The image as a single URL (`data:` URL for inline data).
+    pub fn to_url(&self) -> String {
+        match self {
+            ImageSource::Url(url) => (*url).to_owned(),
+            ImageSource::Base64 { media_type, data } => format!("data:{media_type};base64,{data}"),
+        }
+    }
+}
+
 // ── Convenience constructors ───────────────────────────────────────
 
 impl Message {
@@ -88,6 +152,19 @@ impl Message {
             content: MessageContent::Text(text.into()),
         }
     }
+    /// A user message with text followed by images.  Without images this
+    /// is the same as [`Message::user`].
+    pub fn user_with_images(text: impl Into<String>, images: Vec<ContentPart>) -> Self {
+        if images.is_empty() {
+            return Self::user(text);
+        }
+        let mut parts = vec![ContentPart::Text { text: text.into() }];
+        parts.extend(images);
+        Self {
+            role: Role::User,
+            content: MessageContent::Parts(parts),
+        }
+    }
     pub fn tool_result(tool_use_id: impl Into<String>, content: impl Into<String>) -> Self {
         Self {
             role: Role::Tool,
@@ -161,4 +238,49 @@ mod tests {
         let content = MessageContent::Parts(vec![]);
         assert_eq!(content.extract_all_text(), "");
     }
+
+    #[test]
+    fn image_source_classifies_urls_and_data() {
+        assert_eq!(
+            ImageSource::parse("https://example.com/a.jpg", None),
+            ImageSource::Url("https://example.com/a.jpg")
+        );
+        assert_eq!(
+            ImageSource::parse("data:image/jpeg;base64,QUJD", Some("image/png")),
+            ImageSource::Base64 {
+                media_type: "image/jpeg",
+                data: "QUJD"
+            }
+        );
+        assert_eq!(
+            ImageSource::parse("QUJD", Some("image/webp")),
+            ImageSource::Base64 {
+                media_type: "image/webp",
+                data: "QUJD"
+            }
+        );
+        assert_eq!(
+            ImageSource::parse("QUJD", None).to_url(),
+            "data:image/png;base64,QUJD"
+        );
+    }
+
+    #[test]
+    fn user_with_images_keeps_text_only_messages_plain() {
+        let plain = Message::user_with_images("hi", Vec::new());
+        assert!(matches!(plain.content, MessageContent::Text(ref t) if t == "hi"));
+
+        let image = ContentPart::Image {
+            url: "QUJD".into(),
+            media_type: None,
+        };
+        let msg = Message::user_with_images("look", vec![image]);
+        match msg.content {
+            MessageContent::Parts(parts) => {
+                assert!(matches!(&parts[0], ContentPart::Text { text } if text == "look"));
+                assert!(matches!(&parts[1], ContentPart::Image { .. }));
+            }
+            MessageContent::Text(_) => panic!("expected parts"),
+        }
+    }
 }
@@ -8,10 +8,10 @@ use async_trait::async_trait;
 use axum::extract::FromRequestParts;
 use axum::http::request::Parts;
 use axum::http::StatusCode;
-use axum::response::Json;
 use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
 /// Axum extractor that enforces the admin bearer token.
@@ -24,7 +24,7 @@ pub struct AdminGuard;
 
 #[async_trait]
 impl FromRequestParts<AppState> for AdminGuard {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -47,9 +47,9 @@ impl FromRequestParts<AppState> for AdminGuard {
         let provided_hash = Sha256::digest(provided.as_bytes());
 
         if !bool::from(provided_hash.ct_eq(expected_hash.as_slice())) {
-            return Err((
+            return Err(ApiError::new(
                 StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({ "error": "invalid admin token" })),
+                "invalid admin token",
             ));
         }
         Ok(AdminGuard)
@@ -1,9 +1,9 @@
 //! Health, metrics, system info, config save, and restart endpoints.
 
 use axum::extract::State;
-use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
 use super::guard::AdminGuard;
@@ -46,8 +46,17 @@ pub async fn openapi_spec() -> impl IntoResponse {
             "schemas": {
                 "Error": {
                     "type": "object",
+                    "required": ["error"],
                     "properties": {
-                        "error": { "type": "string" }
+                        "error": {
+                            "type": "object",
+                            "required": ["code", "message"],
+                            "properties": {
+                                "code": { "type": "string", "description": "Machine-readable code (invalid_args, not_allowed, not_found, conflict, rate_limited, unavailable, failed, ...)" },
+                                "message": { "type": "string" },
+                                "details": { "type": "object", "description": "Endpoint-specific context" }
+                            }
+                        }
                     }
                 }
             }
@@ -65,7 +74,7 @@ pub async fn openapi_spec() -> impl IntoResponse {
                 "post": {
                     "summary": "Send a chat message (non-streaming)",
                     "tags": ["Chat"],
-                    "requestBody": { "required": true, "content": { "application/json": { "schema": { "type": "object", "required": ["message"], "properties": { "message": { "type": "string" }, "session_key": { "type": "string" }, "model": { "type": "string" } } } } } },
+                    "requestBody": { "required": true, "content": { "application/json": { "schema": { "type": "object", "required": ["message"], "properties": { "message": { "type": "string" }, "session_key": { "type": "string" }, "model": { "type": "string" }, "messages": { "type": "array", "description": "Prior conversation used instead of the stored transcript", "items": { "type": "object", "required": ["role", "content"], "properties": { "role": { "type": "string", "enum": ["system", "user", "assistant"] }, "content": { "type": "string" } } } }, "images": { "type": "array", "description": "Images sent with the message", "items": { "type": "object", "required": ["url"], "properties": { "url": { "type": "string", "description": "http(s) URL, data: URL or base64" }, "media_type": { "type": "string" } } } }, "seed": { "type": "integer", "description": "Sampling seed; the turn runs at temperature 0 and the seed is recorded on the run" }, "stop": { "type": "array", "description": "Stop sequences; generation ends when the model produces one", "items": { "type": "string" } } } } } } },
                     "responses": { "200": { "description": "Chat response" } }
                 }
             },
@@ -73,15 +82,27 @@ pub async fn openapi_spec() -> impl IntoResponse {
                 "post": {
                     "summary": "Send a chat message (SSE streaming)",
                     "tags": ["Chat"],
-                    "requestBody": { "required": true, "content": { "application/json": { "schema": { "type": "object", "required": ["message"], "properties": { "message": { "type": "string" }, "session_key": { "type": "string" }, "model": { "type": "string" } } } } } },
+                    "requestBody": { "required": true, "content": { "application/json": { "schema": { "type": "object", "required": ["message"], "properties": { "message": { "type": "string" }, "session_key": { "type": "string" }, "model": { "type": "string" }, "messages": { "type": "array", "description": "Prior conversation used instead of the stored transcript", "items": { "type": "object", "required": ["role", "content"], "properties": { "role": { "type": "string", "enum": ["system", "user", "assistant"] }, "content": { "type": "string" } } } }, "images": { "type": "array", "description": "Images sent with the message", "items": { "type": "object", "required": ["url"], "properties": { "url": { "type": "string", "description": "http(s) URL, data: URL or base64" }, "media_type": { "type": "string" } } } }, "seed": { "type": "integer", "description": "Sampling seed; the turn runs at temperature 0 and the seed is recorded on the run" }, "stop": { "type": "array", "description": "Stop sequences; generation ends when the model produces one", "items": { "type": "string" } } } } } } },
                     "responses": { "200": { "description": "SSE event stream" } }
                 }
             },
             "/v1/sessions": {
                 "get": {
-                    "summary": "List all sessions",
+                    "summary": "List sessions (filtered, newest-active first, paginated)",
                     "tags": ["Sessions"],
-                    "responses": { "200": { "description": "Array of sessions" } }
+                    "parameters": [
+                        { "name": "agent_id", "in": "query", "schema": { "type": "string" } },
+                        { "name": "channel", "in": "query", "schema": { "type": "string" } },
+                        { "name": "peer", "in": "query", "schema": { "type": "string" } },
+                        { "name": "active_within_secs", "in": "query", "schema": { "type": "integer" } },
+                        { "name": "tag", "in": "query", "schema": { "type": "string" } },
+                        { "name": "since", "in": "query", "schema": { "type": "string", "format": "date-time" } },
+                        { "name": "until", "in": "query", "schema": { "type": "string", "format": "date-time" } },
+                        { "name": "q", "in": "query", "schema": { "type": "string" } },
+                        { "name": "limit", "in": "query", "schema": { "type": "integer", "default": 100, "maximum": 500 } },
+                        { "name": "offset", "in": "query", "schema": { "type": "integer", "default": 0 } }
+                    ],
+                    "responses": { "200": { "description": "{ sessions, total, offset, count }" } }
                 }
             },
             "/v1/sessions/{key}": {
@@ -92,6 +113,46 @@ pub async fn openapi_spec() -> impl IntoResponse {
                     "responses": { "200": { "description": "Session object" }, "404": { "description": "Not found" } }
                 }
             },
+            "/v1/sessions/{key}/tags": {
+                "put": {
+                    "summary": "Replace the session's user tags",
+                    "tags": ["Sessions"],
+                    "parameters": [{ "name": "key", "in": "path", "required": true, "schema": { "type": "string" } }],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "required": ["tags"],
+                            "properties": { "tags": { "type": "array", "maxItems": 20, "items": { "type": "string", "maxLength": 64 } } }
+                        } } }
+                    },
+                    "responses": { "200": { "description": "{ session_key, tags }" }, "400": { "description": "Too many or too long tags" }, "404": { "description": "Not found" } }
+                }
+            },
+            "/v1/sessions/{key}/model": {
+                "put": {
+                    "summary": "Pin (or unpin with null) the model a session uses; a request's own model still wins",
+                    "tags": ["Sessions"],
+                    "parameters": [{ "name": "key", "in": "path", "required": true, "schema": { "type": "string" } }],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": {
+                            "type": "object",
+                            "required": ["model"],
+                            "properties": { "model": { "type": ["string", "null"], "description": "provider/model spec" } }
+                        } } }
+                    },
+                    "responses": { "200": { "description": "{ session_key, pinned_model }" }, "400": { "description": "Unknown provider" }, "404": { "description": "Not found" } }
+                }
+            },
+            "/v1/sessions/{key}/compactions": {
+                "get": {
+                    "summary": "List compactions of the session transcript (timestamp, lines summarized, summary)",
+                    "tags": ["Sessions"],
+                    "parameters": [{ "name": "key", "in": "path", "required": true, "schema": { "type": "string" } }],
+                    "responses": { "200": { "description": "{ session_key, session_id, count, compactions }" }, "404": { "description": "Not found" } }
+                }
+            },
             "/v1/sessions/{key}/transcript": {
                 "get": {
                     "summary": "Get session transcript",
@@ -171,6 +232,26 @@ pub async fn openapi_spec() -> impl IntoResponse {
                         { "name": "offset", "in": "query", "schema": { "type": "integer", "default": 0 } }
                     ],
                     "responses": { "200": { "description": "Paginated delivery list" } }
+                },
+                "delete": {
+                    "summary": "Bulk-delete deliveries (all, or those matching the filters)",
+                    "tags": ["Deliveries"],
+                    "parameters": [
+                        { "name": "schedule_id", "in": "query", "schema": { "type": "string", "format": "uuid" } },
+                        { "name": "older_than_secs", "in": "query", "schema": { "type": "integer" } }
+                    ],
+                    "responses": { "200": { "description": "Number of deliveries deleted" } }
+                }
+            },
+            "/v1/deliveries/read-all": {
+                "post": {
+                    "summary": "Mark all deliveries (or those matching the filters) as read",
+                    "tags": ["Deliveries"],
+                    "parameters": [
+                        { "name": "schedule_id", "in": "query", "schema": { "type": "string", "format": "uuid" } },
+                        { "name": "older_than_secs", "in": "query", "schema": { "type": "integer" } }
+                    ],
+                    "responses": { "200": { "description": "Number of deliveries marked read" } }
                 }
             },
             "/v1/deliveries/{id}": {
@@ -197,6 +278,14 @@ pub async fn openapi_spec() -> impl IntoResponse {
                     "responses": { "200": { "description": "Search results" } }
                 }
             },
+            "/v1/memory/multi-hop": {
+                "post": {
+                    "summary": "Graph-expanded (multi-hop) memory search",
+                    "tags": ["Memory"],
+                    "requestBody": { "required": true, "content": { "application/json": { "schema": { "type": "object", "required": ["query"], "properties": { "query": { "type": "string" }, "limit": { "type": "integer" }, "hops": { "type": "integer", "minimum": 1, "maximum": 5, "default": 2 } } } } } },
+                    "responses": { "200": { "description": "Search results" } }
+                }
+            },
             "/v1/memory/ingest": {
                 "post": {
                     "summary": "Ingest content into memory",
@@ -205,6 +294,17 @@ pub async fn openapi_spec() -> impl IntoResponse {
                     "responses": { "200": { "description": "Ingested" } }
                 }
             },
+            "/v1/memory/about": {
+                "get": {
+                    "summary": "Structured persona and top facts about the user",
+                    "tags": ["Memory"],
+                    "parameters": [
+                        { "name": "q", "in": "query", "schema": { "type": "string", "default": "about the user" } },
+                        { "name": "limit", "in": "query", "schema": { "type": "integer", "default": 10, "maximum": 50 } }
+                    ],
+                    "responses": { "200": { "description": "{ persona: [{ heading, items }], facts: [{ content, source?, similarity? }] }" } }
+                }
+            },
             "/v1/skills": {
                 "get": {
                     "summary": "List available skills",
@@ -214,9 +314,16 @@ pub async fn openapi_spec() -> impl IntoResponse {
             },
             "/v1/models": {
                 "get": {
-                    "summary": "List configured LLM providers",
+                    "summary": "List routable models (OpenAI models-list shape)",
+                    "tags": ["Providers"],
+                    "responses": { "200": { "description": "{ object: \"list\", data: [{ id, object, created, owned_by }] }" } }
+                }
+            },
+            "/v1/models/refresh": {
+                "post": {
+                    "summary": "Clear the model-discovery cache and re-fetch provider model lists",
                     "tags": ["Providers"],
-                    "responses": { "200": { "description": "Provider list" } }
+                    "responses": { "200": { "description": "{ providers: { <provider_id>: [model ids] } }" } }
                 }
             },
             "/v1/models/readiness": {
@@ -231,7 +338,30 @@ pub async fn openapi_spec() -> impl IntoResponse {
                 "get": {
                     "summary": "List connected tool nodes",
                     "tags": ["Nodes"],
-                    "responses": { "200": { "description": "Node list" } }
+                    "responses": { "200": { "description": "Node list with per-node latency and outstanding request counts" } }
+                }
+            },
+            "/v1/nodes/{id}/selftest": {
+                "post": {
+                    "summary": "Round-trip a node.selftest request to a connected node",
+                    "tags": ["Nodes"],
+                    "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                    "responses": { "200": { "description": "{ node_id, success, latency_ms, error?, error_kind? }" }, "404": { "description": "Node not connected" } }
+                }
+            },
+            "/v1/nodes/events": {
+                "get": {
+                    "summary": "SSE stream of node events (node.connected, node.disconnected, node.pruned)",
+                    "tags": ["Nodes"],
+                    "responses": { "200": { "description": "Event stream" } }
+                }
+            },
+            "/v1/events": {
+                "get": {
+                    "summary": "Unified SSE stream of run status, schedule, delivery and node events",
+                    "tags": ["Events"],
+                    "parameters": [{ "name": "sources", "in": "query", "required": false, "schema": { "type": "string" }, "description": "Comma-separated sources: run, schedule, delivery, node (default all)" }],
+                    "responses": { "200": { "description": "Event stream; each event is { type, source, data }" } }
                 }
             },
             "/v1/tools/exec": {
@@ -241,6 +371,28 @@ pub async fn openapi_spec() -> impl IntoResponse {
                     "responses": { "200": { "description": "Tool execution result" } }
                 }
             },
+            "/v1/tools/stats": {
+                "get": {
+                    "summary": "Per-tool call counts, failure rate and latency since startup",
+                    "tags": ["Tools"],
+                    "responses": { "200": { "description": "{ tools: [{ tool, calls, errors, failure_rate, mean_latency_ms, max_latency_ms, last_called_at }], count }" } }
+                }
+            },
+            "/v1/tools/audit": {
+                "get": {
+                    "summary": "Recent tool dispatches with redacted arguments (filters: tool, session_key, agent_id, status, since, limit)",
+                    "tags": ["Tools"],
+                    "responses": { "200": { "description": "{ entries: [{ timestamp, session_key, agent_id, tool_name, args_preview, result_status }], count }" } }
+                }
+            },
+            "/v1/tools/definitions": {
+                "get": {
+                    "summary": "Dry run of the tool definitions the model sees for an agent (policy-filtered, with collisions)",
+                    "tags": ["Tools"],
+                    "parameters": [{ "name": "agent", "in": "query", "required": false, "schema": { "type": "string" } }],
+                    "responses": { "200": { "description": "{ agent, count, tools, denied_by_policy, shadowed, duplicate_names }" }, "404": { "description": "Unknown agent" } }
+                }
+            },
             "/v1/metrics": {
                 "get": {
                     "summary": "Runtime metrics",
@@ -248,6 +400,18 @@ pub async fn openapi_spec() -> impl IntoResponse {
                     "responses": { "200": { "description": "Metrics object" } }
                 }
             },
+            "/v1/usage": {
+                "get": {
+                    "summary": "Token and cost usage from persisted runs, grouped by day, model or agent",
+                    "tags": ["Admin"],
+                    "parameters": [
+                        { "name": "from", "in": "query", "required": false, "schema": { "type": "string", "format": "date-time" } },
+                        { "name": "to", "in": "query", "required": false, "schema": { "type": "string", "format": "date-time" } },
+                        { "name": "group_by", "in": "query", "required": false, "schema": { "type": "string", "enum": ["day", "model", "agent"] } }
+                    ],
+                    "responses": { "200": { "description": "Usage buckets and totals" }, "400": { "description": "Invalid window" } }
+                }
+            },
             "/v1/admin/info": {
                 "get": {
                     "summary": "System info (admin-only)",
@@ -266,7 +430,11 @@ pub async fn openapi_spec() -> impl IntoResponse {
                 "post": {
                     "summary": "Inbound channel connector",
                     "tags": ["Inbound"],
-                    "responses": { "200": { "description": "Processed" } }
+                    "responses": {
+                        "200": { "description": "Processed" },
+                        "400": { "description": "Missing chat_id or invalid attachment data" },
+                        "413": { "description": "An attachment exceeds the per-file size limit" }
+                    }
                 }
             }
         },
@@ -276,6 +444,7 @@ pub async fn openapi_spec() -> impl IntoResponse {
             { "name": "Schedules", "description": "Cron-based schedule management" },
             { "name": "Runs", "description": "Run execution tracking" },
             { "name": "Deliveries", "description": "Inbox/notification deliveries" },
+            { "name": "Events", "description": "Unified cross-subsystem event stream" },
             { "name": "Memory", "description": "Long-term memory (SerialMemory proxy)" },
             { "name": "Skills", "description": "Skill registry and engine" },
             { "name": "Providers", "description": "LLM provider management" },
@@ -373,13 +542,7 @@ pub async fn save_config(
 ) -> impl IntoResponse {
     // Validate the TOML parses as a Config before saving.
     if let Err(e) = toml::from_str::<sa_domain::config::Config>(&body) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("invalid TOML: {e}"),
-            })),
-        )
-            .into_response();
+        return ApiError::bad_request(format!("invalid TOML: {e}")).into_response();
     }
 
     let config_path = &state.config_path;
@@ -403,11 +566,7 @@ pub async fn save_config(
     // Atomic write: tmp file + rename.
     let tmp_path = config_path.with_extension("toml.tmp");
     if let Err(e) = tokio::fs::write(&tmp_path, &body).await {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("write failed: {e}") })),
-        )
-            .into_response();
+        return ApiError::internal(format!("write failed: {e}")).into_response();
     }
 
     #[cfg(unix)]
@@ -421,11 +580,7 @@ pub async fn save_config(
     }
 
     if let Err(e) = tokio::fs::rename(&tmp_path, config_path).await {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("rename failed: {e}") })),
-        )
-            .into_response();
+        return ApiError::internal(format!("rename failed: {e}")).into_response();
     }
 
     tracing::info!(path = %config_path.display(), "config saved via API");
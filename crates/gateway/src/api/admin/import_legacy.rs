@@ -4,10 +4,10 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use axum::extract::State;
-use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 
+use crate::api::error::ApiError;
 use crate::import::openclaw::sanitize::sanitize_ident;
 use crate::state::AppState;
 
@@ -236,13 +236,7 @@ pub async fn scan_openclaw(
     let canonical = match std::fs::canonicalize(&path) {
         Ok(p) => p,
         Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": format!("cannot resolve path: {e}"),
-                })),
-            )
-                .into_response();
+            return ApiError::bad_request(format!("cannot resolve path: {e}")).into_response();
         }
     };
 
@@ -262,11 +256,7 @@ pub async fn apply_openclaw_import(
     let source = match std::fs::canonicalize(PathBuf::from(&body.path)) {
         Ok(p) => p,
         Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "error": format!("invalid path: {e}") })),
-            )
-                .into_response();
+            return ApiError::bad_request(format!("invalid path: {e}")).into_response();
         }
     };
 
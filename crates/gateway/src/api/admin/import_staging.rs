@@ -1,10 +1,16 @@
-//! Staging-based OpenClaw import endpoints (preview, apply, test-ssh, list, delete).
+//! Staging-based OpenClaw import endpoints (preview, conflicts, apply, rollback,
+//! test-ssh, list, delete).
+
+use std::convert::Infallible;
 
 use axum::extract::State;
 use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Json};
+use futures_core::Stream;
 use serde::Deserialize;
 
+use crate::api::error::ApiError;
 use crate::api::import_openclaw::SshAuth;
 use crate::state::AppState;
 
@@ -37,6 +43,103 @@ pub async fn import_openclaw_preview(
     }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// POST /v1/import/openclaw/preview/stream — preview with SSE progress
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+enum PreviewMessage {
+    Progress(crate::import::openclaw::ImportProgress),
+    Done(
+        Result<
+            crate::api::import_openclaw::ImportPreviewResponse,
+            crate::import::openclaw::OpenClawImportError,
+        >,
+    ),
+}
+
+/// Same as the preview endpoint, but streams `progress` events
+/// (`{"phase":"fetching"|"extracting"|"scanning", ...counts}`) while the
+/// import runs, then a final `done` (preview body) or `error` event.
+pub async fn import_openclaw_preview_stream(
+    _guard: AdminGuard,
+    State(state): State<AppState>,
+    Json(req): Json<crate::api::import_openclaw::ImportPreviewRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let staging_root = state.import_root.join("openclaw");
+    let ws_dest = state.config.workspace.path.clone();
+    let sess_dest = state.config.workspace.state_path.join("sessions");
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let progress_tx = tx.clone();
+        let report = move |p| {
+            let _ = progress_tx.send(PreviewMessage::Progress(p));
+        };
+        let result = crate::import::openclaw::preview_openclaw_import_with_progress(
+            req.source,
+            req.options,
+            &staging_root,
+            &ws_dest,
+            &sess_dest,
+            &report,
+        )
+        .await;
+        let _ = tx.send(PreviewMessage::Done(result));
+    });
+
+    let stream = async_stream::stream! {
+        while let Some(msg) = rx.recv().await {
+            match msg {
+                PreviewMessage::Progress(p) => {
+                    if let Ok(json) = serde_json::to_string(&p) {
+                        yield Ok(Event::default().event("progress").data(json));
+                    }
+                }
+                PreviewMessage::Done(Ok(resp)) => {
+                    if let Ok(json) = serde_json::to_string(&resp) {
+                        yield Ok(Event::default().event("done").data(json));
+                    }
+                    break;
+                }
+                PreviewMessage::Done(Err(e)) => {
+                    yield Ok(map_import_err(e).sse_event());
+                    break;
+                }
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// POST /v1/import/openclaw/conflicts/:staging_id — dry-run a merge strategy
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub async fn import_openclaw_conflicts(
+    _guard: AdminGuard,
+    State(state): State<AppState>,
+    axum::extract::Path(staging_id): axum::extract::Path<uuid::Uuid>,
+    Json(req): Json<crate::api::import_openclaw::ImportConflictsRequest>,
+) -> impl IntoResponse {
+    let staging_root = state.import_root.join("openclaw");
+    let ws_dest = state.config.workspace.path.clone();
+    let sess_dest = state.config.workspace.state_path.join("sessions");
+
+    match crate::import::openclaw::find_import_conflicts(
+        staging_id,
+        req,
+        &staging_root,
+        &ws_dest,
+        &sess_dest,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => map_import_err(e).into_response(),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/import/openclaw/apply — apply staged import
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -135,6 +238,26 @@ pub async fn import_openclaw_apply_v2(
     }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// POST /v1/import/openclaw/rollback/:apply_id — undo an apply
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub async fn import_openclaw_rollback(
+    _guard: AdminGuard,
+    State(state): State<AppState>,
+    axum::extract::Path(apply_id): axum::extract::Path<uuid::Uuid>,
+) -> impl IntoResponse {
+    let staging_root = state.import_root.join("openclaw");
+
+    match crate::import::openclaw::rollback_openclaw_import(apply_id, &staging_root).await {
+        Ok(resp) => {
+            state.workspace.refresh();
+            Json(resp).into_response()
+        }
+        Err(e) => map_import_err(e).into_response(),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/import/openclaw/test-ssh — quick SSH connectivity check
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -173,19 +296,11 @@ pub async fn import_openclaw_test_ssh(
     }
 
     if !is_valid_host(&req.host) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "invalid hostname" })),
-        )
-            .into_response();
+        return ApiError::bad_request("invalid hostname").into_response();
     }
     if let Some(ref u) = req.user {
         if !is_valid_user(u) {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "error": "invalid username" })),
-            )
-                .into_response();
+            return ApiError::bad_request("invalid username").into_response();
         }
     }
 
@@ -242,10 +357,8 @@ pub async fn import_openclaw_test_ssh(
             }))
             .into_response()
         }
-        Err(e) => (
-            StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "ok": false, "error": e.to_string() })),
-        )
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string())
+            .with_details(serde_json::json!({ "ok": false }))
             .into_response(),
     }
 }
@@ -264,11 +377,7 @@ pub async fn import_openclaw_list_staging(
             "count": entries.len(),
         }))
         .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::internal(e.to_string()).into_response(),
     }
 }
 
@@ -283,29 +392,32 @@ pub async fn import_openclaw_delete_staging(
 ) -> impl IntoResponse {
     match crate::import::openclaw::delete_staging(&state.import_root, &staging_id).await {
         Ok(true) => Json(serde_json::json!({ "deleted": true })).into_response(),
-        Ok(false) => (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "staging dir not found" })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Ok(false) => ApiError::not_found("staging dir not found").into_response(),
+        Err(e) => ApiError::internal(e.to_string()).into_response(),
     }
 }
 
-/// Map OpenClawImportError to HTTP status + JSON body.
-fn map_import_err(e: crate::import::openclaw::OpenClawImportError) -> (StatusCode, Json<serde_json::Value>) {
-    let msg = e.to_string();
-    let code = match &e {
+/// Map OpenClawImportError to an API error.
+fn map_import_err(e: crate::import::openclaw::OpenClawImportError) -> ApiError {
+    let err = ApiError::new(import_err_status(&e), e.to_string());
+    match e {
+        crate::import::openclaw::OpenClawImportError::ApplyFailed { apply_id, .. } => {
+            err.with_details(serde_json::json!({ "apply_id": apply_id }))
+        }
+        _ => err,
+    }
+}
+
+fn import_err_status(e: &crate::import::openclaw::OpenClawImportError) -> StatusCode {
+    match e {
         crate::import::openclaw::OpenClawImportError::InvalidPath(_) => StatusCode::BAD_REQUEST,
         crate::import::openclaw::OpenClawImportError::ArchiveInvalid(_) => StatusCode::BAD_REQUEST,
         crate::import::openclaw::OpenClawImportError::SizeLimitExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
         crate::import::openclaw::OpenClawImportError::SshFailed(_) => StatusCode::BAD_GATEWAY,
         crate::import::openclaw::OpenClawImportError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
         crate::import::openclaw::OpenClawImportError::Json(_) => StatusCode::BAD_REQUEST,
-    };
-    (code, Json(serde_json::json!({ "error": msg })))
+        crate::import::openclaw::OpenClawImportError::ApplyFailed { source, .. } => {
+            import_err_status(source)
+        }
+    }
 }
@@ -17,10 +17,11 @@ pub use guard::AdminGuard;
 pub use health::{health, metrics, openapi_spec, restart, save_config, system_info};
 pub use import_legacy::{apply_openclaw_import, scan_openclaw};
 pub use import_staging::{
-    import_openclaw_apply_v2, import_openclaw_delete_staging, import_openclaw_list_staging,
-    import_openclaw_preview, import_openclaw_test_ssh,
+    import_openclaw_apply_v2, import_openclaw_conflicts, import_openclaw_delete_staging,
+    import_openclaw_list_staging, import_openclaw_preview, import_openclaw_preview_stream,
+    import_openclaw_rollback, import_openclaw_test_ssh,
 };
-pub use workspace::{list_skills_detailed, list_workspace_files};
+pub use workspace::{list_skills_detailed, list_workspace_files, write_workspace_file};
 
 // Re-export public types for backward compatibility.
 pub use import_legacy::{
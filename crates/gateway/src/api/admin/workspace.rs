@@ -1,9 +1,12 @@
 //! Workspace and skills admin endpoints.
 
-use axum::extract::State;
-use axum::response::{IntoResponse, Json};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
+use crate::workspace::files::WorkspaceWriteError;
 
 use super::guard::AdminGuard;
 
@@ -36,6 +39,43 @@ pub async fn list_workspace_files(
     }))
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PUT /v1/admin/workspace/files/*path — write a workspace file
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Replace a workspace file with the raw request body.  The previous
+/// version (if any) is backed up under `.backups/`.
+pub async fn write_workspace_file(
+    _guard: AdminGuard,
+    State(state): State<AppState>,
+    Path(path): Path<String>,
+    body: String,
+) -> Response {
+    let workspace = state.workspace.clone();
+    let result = tokio::task::spawn_blocking(move || workspace.write_file(&path, &body)).await;
+
+    match result {
+        Ok(Ok(written)) => {
+            tracing::info!(
+                path = %written.path,
+                size = written.size,
+                backup = ?written.backup,
+                "workspace file written via admin API"
+            );
+            Json(serde_json::json!(written)).into_response()
+        }
+        Ok(Err(e)) => {
+            let status = match e {
+                WorkspaceWriteError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+                WorkspaceWriteError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+                WorkspaceWriteError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            ApiError::new(status, e.to_string()).into_response()
+        }
+        Err(e) => ApiError::internal(format!("write task failed: {e}")).into_response(),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // GET /v1/admin/skills — detailed skills list
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
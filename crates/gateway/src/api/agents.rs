@@ -33,7 +33,7 @@ pub async fn list_agents(State(state): State<AppState>) -> impl IntoResponse {
             let runtime = manager.get(&id);
             match runtime {
                 Some(r) => {
-                    let effective_count = manager.effective_tool_count(&id, &tool_refs);
+                    let effective_tools = manager.effective_tools(&id, &tool_refs);
                     let resolved_model = r
                         .config
                         .models
@@ -44,16 +44,21 @@ pub async fn list_agents(State(state): State<AppState>) -> impl IntoResponse {
                         "id": id,
                         "tools_allow": r.config.tool_policy.allow,
                         "tools_deny": r.config.tool_policy.deny,
-                        "effective_tools_count": effective_count,
+                        "tools_rules": r.config.tool_policy.rules,
+                        "tools_default": r.config.tool_policy.default,
+                        "effective_tools_count": effective_tools.len(),
+                        "effective_tools": effective_tools,
                         "models": r.config.models,
                         "resolved_executor": resolved_model,
                         "memory_mode": r.config.memory_mode,
                         "limits": {
                             "max_depth": r.config.limits.max_depth,
                             "max_children_per_turn": r.config.limits.max_children_per_turn,
+                            "max_concurrent_children": r.config.limits.max_concurrent_children,
                             "max_duration_ms": r.config.limits.max_duration_ms,
                         },
                         "compaction_enabled": r.config.compaction_enabled,
+                        "tool_choice": r.config.tool_choice,
                     })
                 }
                 None => serde_json::json!({ "id": id }),
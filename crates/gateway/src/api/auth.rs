@@ -1,22 +1,115 @@
 //! API authentication middleware.
 //!
-//! Reads the env var named by `config.server.api_token_env` (default `SA_API_TOKEN`)
-//! **once at startup** and caches the SHA-256 digest in `AppState`.
-//! - If the env var is set and non-empty, every protected request must carry
-//!   `Authorization: Bearer <token>`.
-//! - If the env var is unset or empty, the server logs a warning once and
-//!   allows unauthenticated access (dev mode).
+//! Tokens are read **once at startup** and cached as SHA-256 digests in
+//! `AppState`:
+//! - the primary token (`server.api_token`, else the env var named by
+//!   `server.api_token_env`, default `SA_API_TOKEN`) has full scope;
+//! - each `server.api_tokens` entry maps an env var to a scope set
+//!   (`read`, `chat`, `admin`).
+//!
+//! If any token is configured, every protected request must carry
+//! `Authorization: Bearer <token>` (401 otherwise) whose scopes include the
+//! route's required scope (403 otherwise).  If none is configured, the
+//! server logs a warning once and allows unauthenticated access (dev mode).
 
 use axum::body::Body;
 use axum::extract::State;
-use axum::http::Request;
+use axum::http::{Method, Request, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
+use sa_domain::config::ApiScope;
 use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
+/// Hashed API tokens with the scopes each one grants.
+#[derive(Debug, Default)]
+pub struct ApiTokens {
+    entries: Vec<(Vec<u8>, Vec<ApiScope>)>,
+}
+
+/// Why a request was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// No token, or a token that matches nothing.
+    Unauthorized,
+    /// A valid token that lacks the required scope.
+    Forbidden,
+}
+
+impl ApiTokens {
+    /// Register a plaintext token with the given scopes.
+    pub fn insert(&mut self, token: &str, scopes: Vec<ApiScope>) {
+        self.entries
+            .push((Sha256::digest(token.as_bytes()).to_vec(), scopes));
+    }
+
+    /// `true` when no token is configured (dev mode).
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check `provided` against every configured token.
+    ///
+    /// All entries are compared in constant time so neither the token
+    /// length nor which entry matched leaks through timing.
+    pub fn authorize(&self, provided: &str, required: ApiScope) -> Result<(), AuthError> {
+        let provided_hash = Sha256::digest(provided.as_bytes());
+        let mut granted: Option<&[ApiScope]> = None;
+        for (hash, scopes) in &self.entries {
+            if bool::from(provided_hash.ct_eq(hash.as_slice())) {
+                granted = Some(scopes);
+            }
+        }
+        match granted {
+            None => Err(AuthError::Unauthorized),
+            Some(scopes) if scopes.contains(&required) => Ok(()),
+            Some(_) => Err(AuthError::Forbidden),
+        }
+    }
+}
+
+/// Routes that need `admin` for any method.
+const ADMIN_ROUTES: &[&str] = &["/v1/admin", "/v1/import"];
+
+/// Routes whose state-changing requests need `admin`: host command
+/// execution, exec approvals, skill installs, node self-tests, and
+/// router/model/skill reconfiguration.  Reads under them stay at `read`.
+const ADMIN_WRITE_ROUTES: &[&str] = &[
+    "/v1/tools/exec",
+    "/v1/tools/process",
+    "/v1/tools/invoke",
+    "/v1/clawhub",
+    "/v1/nodes",
+    "/v1/models/refresh",
+    "/v1/router/config",
+    "/v1/router/breakers",
+    "/v1/skills/reload",
+];
+
+/// Whether `path` is `prefix` or lies beneath it.
+fn under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Scope a request needs: admin routes and admin-only writes need `admin`,
+/// other reads need `read`, and everything else needs `chat`.
+pub fn required_scope(method: &Method, path: &str) -> ApiScope {
+    let read = method == Method::GET || method == Method::HEAD;
+    if ADMIN_ROUTES.iter().any(|p| under(path, p))
+        || (!read && ADMIN_WRITE_ROUTES.iter().any(|p| under(path, p)))
+    {
+        ApiScope::Admin
+    } else if read {
+        ApiScope::Read
+    } else {
+        ApiScope::Chat
+    }
+}
+
 /// Axum middleware that enforces bearer-token authentication on protected
 /// routes. Attach via `axum::middleware::from_fn_with_state`.
 pub async fn require_api_token(
@@ -24,11 +117,18 @@ pub async fn require_api_token(
     req: Request<Body>,
     next: Next,
 ) -> Response {
-    // `api_token_hash` is `None` in dev mode (no token configured).
-    let expected_hash = match &state.api_token_hash {
-        Some(h) => h,
-        None => return next.run(req).await,
-    };
+    match reject_request(&state.api_tokens, &req) {
+        None => next.run(req).await,
+        Some(rejection) => rejection,
+    }
+}
+
+/// Authorize `req` against `tokens`, returning the 401/403 response to send
+/// if it is rejected.  Lets everything through in dev mode.
+pub(crate) fn reject_request(tokens: &ApiTokens, req: &Request<Body>) -> Option<Response> {
+    if tokens.is_empty() {
+        return None;
+    }
 
     let provided = req
         .headers()
@@ -37,17 +137,155 @@ pub async fn require_api_token(
         .and_then(|v: &str| v.strip_prefix("Bearer "))
         .unwrap_or("");
 
-    // Hash the provided token to a fixed-length digest, then compare
-    // in constant time. This avoids leaking the token length.
-    let provided_hash = Sha256::digest(provided.as_bytes());
+    let required = required_scope(req.method(), req.uri().path());
+    let err = tokens.authorize(provided, required).err()?;
+    Some(match err {
+        AuthError::Unauthorized => {
+            ApiError::new(StatusCode::UNAUTHORIZED, "invalid or missing API token").into_response()
+        }
+        AuthError::Forbidden => {
+            ApiError::new(StatusCode::FORBIDDEN, "API token lacks the required scope")
+                .with_details(serde_json::json!({ "required_scope": required }))
+                .into_response()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn tokens() -> ApiTokens {
+        let mut tokens = ApiTokens::default();
+        tokens.insert("full", ApiScope::ALL.to_vec());
+        tokens.insert("reader", vec![ApiScope::Read]);
+        tokens
+    }
+
+    /// Minimal router running the middleware's check, so the test does not
+    /// need a full `AppState`.
+    fn app(tokens: ApiTokens) -> Router {
+        let tokens = std::sync::Arc::new(tokens);
+        Router::new()
+            .route("/v1/sessions", get(|| async { "ok" }))
+            .route("/v1/chat", post(|| async { "ok" }))
+            .route("/v1/admin/info", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(
+                move |req: Request<Body>, next: Next| {
+                    let tokens = tokens.clone();
+                    async move {
+                        match reject_request(&tokens, &req) {
+                            None => next.run(req).await,
+                            Some(rejection) => rejection,
+                        }
+                    }
+                },
+            ))
+    }
 
-    if !bool::from(provided_hash.ct_eq(expected_hash.as_slice())) {
-        return (
-            axum::http::StatusCode::UNAUTHORIZED,
-            axum::Json(serde_json::json!({ "error": "invalid or missing API token" })),
-        )
-            .into_response();
+    async fn status(method: Method, path: &str, token: &str) -> StatusCode {
+        let req = Request::builder()
+            .method(method)
+            .uri(path)
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        app(tokens()).oneshot(req).await.unwrap().status()
     }
 
-    next.run(req).await
+    #[tokio::test]
+    async fn read_token_can_list_sessions_but_not_chat() {
+        assert_eq!(
+            status(Method::GET, "/v1/sessions", "reader").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/v1/chat", "reader").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(Method::GET, "/v1/admin/info", "reader").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn full_token_works_everywhere() {
+        assert_eq!(
+            status(Method::GET, "/v1/sessions", "full").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::POST, "/v1/chat", "full").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::GET, "/v1/admin/info", "full").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_token_is_unauthorized() {
+        assert_eq!(
+            status(Method::GET, "/v1/sessions", "nope").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn required_scope_by_route() {
+        assert_eq!(required_scope(&Method::GET, "/v1/sessions"), ApiScope::Read);
+        assert_eq!(required_scope(&Method::POST, "/v1/chat"), ApiScope::Chat);
+        assert_eq!(
+            required_scope(&Method::DELETE, "/v1/memory/x"),
+            ApiScope::Chat
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/v1/admin/info"),
+            ApiScope::Admin
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/v1/import/openclaw/apply"),
+            ApiScope::Admin
+        );
+        for (method, path) in [
+            (Method::POST, "/v1/tools/exec"),
+            (Method::POST, "/v1/tools/process"),
+            (Method::POST, "/v1/tools/invoke"),
+            (Method::POST, "/v1/tools/exec/approve/abc"),
+            (Method::POST, "/v1/tools/exec/deny/abc"),
+            (Method::POST, "/v1/clawhub/install"),
+            (Method::POST, "/v1/clawhub/update"),
+            (Method::POST, "/v1/clawhub/uninstall"),
+            (Method::PUT, "/v1/router/config"),
+            (Method::POST, "/v1/router/breakers/openai/reset"),
+            (Method::POST, "/v1/skills/reload"),
+            (Method::POST, "/v1/nodes/node-1/selftest"),
+            (Method::POST, "/v1/models/refresh"),
+        ] {
+            assert_eq!(required_scope(&method, path), ApiScope::Admin, "{method} {path}");
+        }
+        assert_eq!(
+            required_scope(&Method::GET, "/v1/tools/exec/pending"),
+            ApiScope::Read
+        );
+        assert_eq!(
+            required_scope(&Method::GET, "/v1/clawhub/installed"),
+            ApiScope::Read
+        );
+        assert_eq!(required_scope(&Method::GET, "/v1/nodes"), ApiScope::Read);
+        assert_eq!(required_scope(&Method::GET, "/v1/models"), ApiScope::Read);
+        assert_eq!(
+            required_scope(&Method::POST, "/v1/router/classify"),
+            ApiScope::Chat
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/v1/tools/executor"),
+            ApiScope::Chat
+        );
+    }
 }
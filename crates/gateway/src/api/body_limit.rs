@@ -0,0 +1,108 @@
+//! Per-route-group request body size limits.
+//!
+//! Each group gets a `RequestBodyLimitLayer` (rejects on `Content-Length`
+//! up front and caps streamed bodies) plus a matching `DefaultBodyLimit`
+//! so axum's extractors agree with it.  The 413 is rewritten into the
+//! gateway's standard [`ApiError`] shape.
+
+use axum::extract::DefaultBodyLimit;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{middleware, Router};
+use tower_http::limit::RequestBodyLimitLayer;
+
+use crate::api::error::ApiError;
+
+/// Cap request bodies on every route in `router` at `max_bytes`.
+pub fn limit_body<S>(router: Router<S>, max_bytes: usize) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .layer(DefaultBodyLimit::max(max_bytes))
+        .layer(RequestBodyLimitLayer::new(max_bytes))
+        .layer(middleware::map_response(move |resp: Response| {
+            payload_too_large_json(resp, max_bytes)
+        }))
+}
+
+async fn payload_too_large_json(resp: Response, max_bytes: usize) -> Response {
+    if resp.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return resp;
+    }
+    let is_json = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if is_json {
+        return resp;
+    }
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("request body exceeds the {max_bytes}-byte limit for this endpoint"),
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::post;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn app(max_bytes: usize, called: Arc<AtomicBool>) -> Router {
+        let route = Router::new().route(
+            "/echo",
+            post(move |body: String| async move {
+                called.store(true, Ordering::SeqCst);
+                body
+            }),
+        );
+        limit_body(route, max_bytes)
+    }
+
+    fn post_body(body: &'static str) -> Request<Body> {
+        Request::post("/echo")
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_rejected_before_handler() {
+        let called = Arc::new(AtomicBool::new(false));
+        let resp = app(16, called.clone())
+            .oneshot(post_body("this body is well over sixteen bytes"))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!called.load(Ordering::SeqCst), "handler must not run");
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["error"]["code"], "payload_too_large");
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("16-byte limit"));
+    }
+
+    #[tokio::test]
+    async fn body_within_limit_reaches_handler() {
+        let called = Arc::new(AtomicBool::new(false));
+        let resp = app(16, called.clone())
+            .oneshot(post_body("small"))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(called.load(Ordering::SeqCst));
+    }
+}
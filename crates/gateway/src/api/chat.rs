@@ -2,18 +2,24 @@
 //!
 //! - `POST /v1/chat`        — non-streaming: returns full response
 //! - `POST /v1/chat/stream` — SSE streaming: streams deltas + tool activity
+//!
+//! Both accept a JSON or MessagePack request body; `/v1/chat` replies in
+//! the negotiated format (see [`crate::api::negotiate`]).
 
 use axum::extract::State;
 use axum::response::sse::{Event, KeepAlive, Sse};
-use axum::response::{IntoResponse, Json};
+use axum::response::IntoResponse;
 use futures_util::stream::Stream;
 use serde::Deserialize;
 
 use sa_domain::config::InboundMetadata;
+use sa_domain::tool::{ContentPart, Message};
 use sa_providers::ResponseFormat;
 use sa_sessions::compute_session_key;
 use sa_sessions::store::SessionOrigin;
 
+use crate::api::error::ApiError;
+use crate::api::negotiate::Negotiated;
 use crate::runtime::session_lock::SessionBusy;
 use crate::runtime::{run_turn, TurnEvent, TurnInput};
 use crate::state::AppState;
@@ -38,6 +44,72 @@ pub struct ChatRequest {
     /// Inbound channel context (used to compute session key if not explicit).
     #[serde(default)]
     pub channel_context: Option<InboundMetadata>,
+    /// Prior conversation for stateless clients.  When present it is sent
+    /// verbatim instead of the stored transcript; `message` is still
+    /// appended, and the new exchange is persisted as usual.
+    #[serde(default)]
+    pub messages: Option<Vec<ChatHistoryMessage>>,
+    /// Images sent with `message` (vision-capable models only).
+    #[serde(default)]
+    pub images: Vec<ChatImage>,
+    /// Sampling seed for reproducible turns (e.g. golden-turn tests).  The
+    /// turn runs at temperature 0 and the seed is sent to providers that
+    /// accept one (OpenAI-compat).
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Stop sequences; generation ends when the model produces one.
+    #[serde(default)]
+    pub stop: Vec<String>,
+}
+
+/// An image attached to the user message.
+#[derive(Debug, Deserialize)]
+pub struct ChatImage {
+    /// An `http(s)` URL, a `data:` URL, or bare base64 data.
+    pub url: String,
+    /// MIME type for bare base64 data (default `image/png`).
+    #[serde(default)]
+    pub media_type: Option<String>,
+}
+
+impl From<ChatImage> for ContentPart {
+    fn from(image: ChatImage) -> Self {
+        ContentPart::Image {
+            url: image.url,
+            media_type: image.media_type,
+        }
+    }
+}
+
+/// One caller-supplied prior message.
+#[derive(Debug, Deserialize)]
+pub struct ChatHistoryMessage {
+    /// `system`, `user` or `assistant`.
+    pub role: String,
+    pub content: String,
+}
+
+/// Convert supplied prior messages to LLM messages, in order.  `None`
+/// (no `messages` field) means the turn loads the session transcript.
+fn supplied_history(
+    messages: Option<&[ChatHistoryMessage]>,
+) -> Result<Option<Vec<Message>>, String> {
+    let Some(messages) = messages else {
+        return Ok(None);
+    };
+    messages
+        .iter()
+        .enumerate()
+        .map(|(i, m)| match m.role.as_str() {
+            "system" => Ok(Message::system(&m.content)),
+            "user" => Ok(Message::user(&m.content)),
+            "assistant" => Ok(Message::assistant(&m.content)),
+            other => Err(format!(
+                "messages[{i}]: unsupported role '{other}' (expected system, user or assistant)"
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -46,21 +118,24 @@ pub struct ChatRequest {
 
 pub async fn chat(
     State(state): State<AppState>,
-    Json(body): Json<ChatRequest>,
+    Negotiated { body, reply }: Negotiated<ChatRequest>,
 ) -> impl IntoResponse {
     // Pre-flight: reject early with 503 if no LLM providers are available.
     if let Err(resp) = require_llm_provider(&state) {
         return resp.into_response();
     }
 
+    let history = match supplied_history(body.messages.as_deref()) {
+        Ok(h) => h,
+        Err(e) => {
+            return ApiError::bad_request(e).into_response();
+        }
+    };
+
     let (session_key, session_id) = match resolve_session(&state, &body) {
         Ok(s) => s,
         Err(e) => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "error": e })),
-            )
-                .into_response();
+            return ApiError::bad_request(e).into_response();
         }
     };
 
@@ -68,13 +143,11 @@ pub async fn chat(
     let _permit = match state.session_locks.acquire(&session_key).await {
         Ok(p) => p,
         Err(SessionBusy) => {
-            return (
+            return ApiError::new(
                 axum::http::StatusCode::TOO_MANY_REQUESTS,
-                Json(serde_json::json!({
-                    "error": "session is busy — a turn is already in progress"
-                })),
+                "session is busy — too many turns queued",
             )
-                .into_response();
+            .into_response();
         }
     };
 
@@ -86,6 +159,10 @@ pub async fn chat(
         response_format: body.response_format,
         agent: None,
         routing_profile: None,
+        history,
+        images: body.images.into_iter().map(ContentPart::from).collect(),
+        seed: body.seed,
+        stop: body.stop,
     };
 
     let (_run_id, mut rx) = run_turn(state.clone(), input);
@@ -144,7 +221,7 @@ pub async fn chat(
         }
     }
 
-    Json(serde_json::json!({
+    reply.respond(&serde_json::json!({
         "session_key": session_key,
         "session_id": session_id,
         "content": final_content,
@@ -153,7 +230,6 @@ pub async fn chat(
         "usage": usage,
         "errors": errors,
     }))
-    .into_response()
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -162,23 +238,31 @@ pub async fn chat(
 
 pub async fn chat_stream(
     State(state): State<AppState>,
-    Json(body): Json<ChatRequest>,
+    Negotiated { body, .. }: Negotiated<ChatRequest>,
 ) -> impl IntoResponse {
     // Pre-flight: reject early with 503 if no LLM providers are available.
     if let Err(resp) = require_llm_provider(&state) {
         return resp.into_response();
     }
 
+    let history = match supplied_history(body.messages.as_deref()) {
+        Ok(h) => h,
+        Err(e) => {
+            let stream = futures_util::stream::once(async move {
+                Ok::<_, std::convert::Infallible>(ApiError::bad_request(e).sse_event())
+            });
+            return Sse::new(stream)
+                .keep_alive(KeepAlive::default())
+                .into_response();
+        }
+    };
+
     let (session_key, session_id) = match resolve_session(&state, &body) {
         Ok(s) => s,
         Err(e) => {
             // Can't return SSE error properly — return a single error event.
             let stream = futures_util::stream::once(async move {
-                Ok::<_, std::convert::Infallible>(
-                    Event::default()
-                        .event("error")
-                        .data(serde_json::json!({ "error": e }).to_string()),
-                )
+                Ok::<_, std::convert::Infallible>(ApiError::bad_request(e).sse_event())
             });
             return Sse::new(stream)
                 .keep_alive(KeepAlive::default())
@@ -190,12 +274,12 @@ pub async fn chat_stream(
     let permit = match state.session_locks.acquire(&session_key).await {
         Ok(p) => p,
         Err(SessionBusy) => {
-            let stream = futures_util::stream::once(async {
-                Ok::<_, std::convert::Infallible>(
-                    Event::default()
-                        .event("error")
-                        .data(r#"{"error":"session is busy"}"#),
-                )
+            let err = ApiError::new(
+                axum::http::StatusCode::TOO_MANY_REQUESTS,
+                "session is busy — too many turns queued",
+            );
+            let stream = futures_util::stream::once(async move {
+                Ok::<_, std::convert::Infallible>(err.sse_event())
             });
             return Sse::new(stream)
                 .keep_alive(KeepAlive::default())
@@ -211,6 +295,10 @@ pub async fn chat_stream(
         response_format: body.response_format,
         agent: None,
         routing_profile: None,
+        history,
+        images: body.images.into_iter().map(ContentPart::from).collect(),
+        seed: body.seed,
+        stop: body.stop,
     };
 
     let (_run_id, rx) = run_turn(state.clone(), input);
@@ -224,7 +312,7 @@ pub async fn chat_stream(
 
 fn make_sse_stream(
     mut rx: tokio::sync::mpsc::Receiver<TurnEvent>,
-    _permit: tokio::sync::OwnedSemaphorePermit,
+    _permit: crate::runtime::session_lock::SessionPermit,
 ) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
     async_stream::stream! {
         while let Some(event) = rx.recv().await {
@@ -254,9 +342,7 @@ fn make_sse_stream(
 /// a vague "no_provider_configured" buried inside a turn-error stream)
 /// and includes the init_errors summary so operators can diagnose the root
 /// cause without scraping logs.
-fn require_llm_provider(
-    state: &AppState,
-) -> Result<(), (axum::http::StatusCode, Json<serde_json::Value>)> {
+fn require_llm_provider(state: &AppState) -> Result<(), ApiError> {
     if !state.llm.is_empty() {
         return Ok(());
     }
@@ -275,17 +361,17 @@ fn require_llm_provider(
         })
         .collect();
 
-    Err((
+    Err(ApiError::new(
         axum::http::StatusCode::SERVICE_UNAVAILABLE,
-        Json(serde_json::json!({
-            "error": "no_llm_provider",
-            "reason": "No LLM providers are available. Configure at least one \
-                       provider in config.toml under [llm.providers], or check \
-                       /v1/models/readiness for details.",
-            "init_errors": init_errors,
-            "startup_policy": format!("{:?}", state.config.llm.startup_policy),
-        })),
-    ))
+        "No LLM providers are available. Configure at least one \
+         provider in config.toml under [llm.providers], or check \
+         /v1/models/readiness for details.",
+    )
+    .with_code("no_llm_provider")
+    .with_details(serde_json::json!({
+        "init_errors": init_errors,
+        "startup_policy": format!("{:?}", state.config.llm.startup_policy),
+    })))
 }
 
 fn resolve_session(
@@ -348,3 +434,72 @@ fn resolve_session(
 
     Ok((session_key, entry.session_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_domain::tool::{MessageContent, Role};
+
+    fn request(json: serde_json::Value) -> ChatRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn supplied_messages_are_used_verbatim() {
+        let body = request(serde_json::json!({
+            "message": "and now?",
+            "messages": [
+                { "role": "system", "content": "Be terse." },
+                { "role": "user", "content": "  hi  " },
+                { "role": "assistant", "content": "hello\n" },
+            ],
+        }));
+        let history = supplied_history(body.messages.as_deref())
+            .unwrap()
+            .expect("supplied messages seed the turn");
+
+        let got: Vec<(Role, &str)> = history
+            .iter()
+            .map(|m| match &m.content {
+                MessageContent::Text(t) => (m.role, t.as_str()),
+                MessageContent::Parts(_) => panic!("expected plain text"),
+            })
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (Role::System, "Be terse."),
+                (Role::User, "  hi  "),
+                (Role::Assistant, "hello\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn omitted_messages_fall_back_to_transcript() {
+        let body = request(serde_json::json!({ "message": "hi" }));
+        assert!(body.messages.is_none());
+        assert!(supplied_history(None).unwrap().is_none());
+
+        // An explicit empty list is a stateless turn with no prior context,
+        // not a transcript load.
+        let body = request(serde_json::json!({ "message": "hi", "messages": [] }));
+        let history = supplied_history(body.messages.as_deref()).unwrap();
+        assert_eq!(history.map(|h| h.len()), Some(0));
+    }
+
+    #[test]
+    fn unsupported_roles_are_rejected() {
+        for role in ["tool", "developer", "User", ""] {
+            let body = request(serde_json::json!({
+                "message": "hi",
+                "messages": [
+                    { "role": "user", "content": "ok" },
+                    { "role": role, "content": "x" },
+                ],
+            }));
+            let err = supplied_history(body.messages.as_deref()).unwrap_err();
+            assert!(err.starts_with("messages[1]:"), "{role:?}: {err}");
+        }
+    }
+}
@@ -3,10 +3,23 @@
 //!
 //! Routes:
 //!   GET  /v1/clawhub/installed        — list installed third-party packs
+//!   GET  /v1/clawhub/outdated         — installed packs with newer versions upstream
 //!   GET  /v1/clawhub/skill/:owner/:repo — show manifest + install status
 //!   POST /v1/clawhub/install          — download and install from GitHub
 //!   POST /v1/clawhub/update           — reinstall latest (or pinned version)
 //!   POST /v1/clawhub/uninstall        — remove installed pack
+//!
+//! Install and update also install the packs a pack's SKILL.md `depends:`
+//! on (see [`sa_skills::deps`]); uninstall refuses while other installed
+//! packs depend on the pack unless `force` is set.
+//!
+//! A pack needing high-danger permissions (`filesystem`, `execution`, see
+//! [`sa_skills::permissions`]) is refused with 403 and the permissions it
+//! needs until the request repeats them: the pack's own in
+//! `accept_permissions`, each dependency's in
+//! `accept_dependency_permissions` keyed by `owner/repo`.
+
+use std::collections::HashMap;
 
 use axum::extract::State;
 use axum::http::{HeaderMap, StatusCode};
@@ -14,6 +27,11 @@ use axum::response::{IntoResponse, Json};
 use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
 
+use sa_skills::deps::{DependencyError, DepsInstall, FetchedPack, PackId, PackRequest};
+use sa_skills::permissions::Permission;
+
+use crate::api::error::ApiError;
+use crate::api::skills::reload_skills_registry;
 use crate::state::AppState;
 
 /// Verify the admin bearer token from the `Authorization` header.
@@ -25,15 +43,13 @@ use crate::state::AppState;
 fn verify_admin_token(
     headers: &HeaderMap,
     expected_hash: &Option<Vec<u8>>,
-) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+) -> Result<(), ApiError> {
     let expected_hash = match expected_hash {
         Some(h) => h,
         None => {
-            return Err((
+            return Err(ApiError::new(
                 StatusCode::FORBIDDEN,
-                Json(serde_json::json!({
-                    "error": "admin endpoints are disabled (SA_ADMIN_TOKEN not set)"
-                })),
+                "admin endpoints are disabled (SA_ADMIN_TOKEN not set)",
             ));
         }
     };
@@ -47,9 +63,9 @@ fn verify_admin_token(
     let provided_hash = Sha256::digest(provided.as_bytes());
 
     if !bool::from(provided_hash.ct_eq(expected_hash.as_slice())) {
-        return Err((
+        return Err(ApiError::new(
             StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "error": "invalid admin token" })),
+            "invalid admin token",
         ));
     }
 
@@ -66,6 +82,21 @@ pub async fn list_installed(State(state): State<AppState>) -> impl IntoResponse
     }))
 }
 
+/// List installed packs whose upstream SKILL.md declares a newer version.
+pub async fn list_outdated(State(state): State<AppState>) -> impl IntoResponse {
+    let skills_root = &state.config.skills.path;
+    let client = reqwest::Client::new();
+    let report =
+        sa_skills::outdated::check_outdated(skills_root, |origin| remote_version(&client, origin))
+            .await;
+    Json(serde_json::json!({
+        "outdated": report.outdated,
+        "count": report.outdated.len(),
+        "unchecked": report.unchecked,
+        "checked": report.checked,
+    }))
+}
+
 /// Body for install/update/uninstall requests.
 #[derive(serde::Deserialize)]
 pub struct PackRef {
@@ -79,12 +110,73 @@ pub struct PackRef {
     /// Optional subdirectory within the repo (e.g. "skills/sonoscli").
     #[serde(default)]
     pub subdir: Option<String>,
+    /// Uninstall even when other installed packs depend on this one.
+    #[serde(default)]
+    pub force: bool,
+    /// High-danger permissions the operator accepts for this pack.
+    #[serde(default)]
+    pub accept_permissions: Vec<Permission>,
+    /// High-danger permissions accepted for each dependency (`owner/repo`).
+    #[serde(default)]
+    pub accept_dependency_permissions: HashMap<String, Vec<Permission>>,
 }
 
 fn default_version() -> String {
     "latest".into()
 }
 
+impl PackRef {
+    fn request(&self) -> PackRequest {
+        let id = PackId::new(&self.owner, &self.repo);
+        let mut accept_permissions: HashMap<PackId, Vec<Permission>> = self
+            .accept_dependency_permissions
+            .iter()
+            .filter_map(|(pack, perms)| Some((PackId::parse(pack)?, perms.clone())))
+            .collect();
+        accept_permissions.insert(id.clone(), self.accept_permissions.clone());
+        PackRequest {
+            id,
+            version: self.version.clone(),
+            git_ref: self.git_ref.clone(),
+            subdir: self.subdir.clone(),
+            accept_permissions,
+        }
+    }
+}
+
+/// Error response for a failed install: 403 listing the permissions to
+/// accept, 409 when the dependency graph is unsatisfiable (cycle, version
+/// conflict), 500 otherwise.
+fn install_error(e: DependencyError) -> axum::response::Response {
+    if let DependencyError::PermissionsNotAccepted(ref packs) = e {
+        let required: Vec<serde_json::Value> = packs
+            .iter()
+            .map(|(pack, permissions)| {
+                serde_json::json!({ "pack": pack.to_string(), "permissions": permissions })
+            })
+            .collect();
+        return ApiError::new(StatusCode::FORBIDDEN, e.to_string())
+            .with_code("permissions_not_accepted")
+            .with_details(serde_json::json!({ "permissions_required": required }))
+            .into_response();
+    }
+    let status = if e.is_conflict() {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    ApiError::new(status, e.to_string()).into_response()
+}
+
+/// `owner/repo` of every dependency installed along with the root pack.
+fn installed_dependencies(result: &DepsInstall) -> Vec<String> {
+    result
+        .dependencies
+        .iter()
+        .map(|r| format!("{}/{}", r.origin.owner, r.origin.repo))
+        .collect()
+}
+
 /// Install a skill pack from GitHub.
 ///
 /// Downloads the repository archive, extracts the skill pack, and installs
@@ -99,28 +191,28 @@ pub async fn install_pack(
     }
     let skills_root = &state.config.skills.path;
 
-    // Download from GitHub via tarball API.
-    match download_and_install(skills_root, &body).await {
+    // Download from GitHub via tarball API, dependencies included.
+    match sa_skills::deps::install_with_deps(skills_root, body.request(), fetch_pack).await {
         Ok(result) => {
             // Reload the skills registry to pick up the new pack.
-            if let Err(e) = state.skills.reload() {
+            if let Err(e) = reload_skills_registry(&state) {
                 tracing::warn!(error = %e, "failed to reload skills after install");
             }
+            let dependencies = installed_dependencies(&result);
+            let root = result.root;
             Json(serde_json::json!({
                 "installed": true,
-                "skill_dir": result.skill_dir,
-                "manifest_found": result.manifest_found,
-                "origin": result.origin,
-                "changed_files": result.changed_files,
-                "scripts_changed": result.scripts_changed,
+                "skill_dir": root.skill_dir,
+                "manifest_found": root.manifest_found,
+                "origin": root.origin,
+                "changed_files": root.changed_files,
+                "scripts_changed": root.scripts_changed,
+                "dependencies_installed": dependencies,
+                "dependencies_satisfied": result.already_satisfied,
             }))
             .into_response()
         }
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e })),
-        )
-            .into_response(),
+        Err(e) => install_error(e),
     }
 }
 
@@ -139,27 +231,27 @@ pub async fn update_pack(
     let was_installed =
         sa_skills::installer::read_origin(skills_root, &body.owner, &body.repo).is_some();
 
-    match download_and_install(skills_root, &body).await {
+    match sa_skills::deps::install_with_deps(skills_root, body.request(), fetch_pack).await {
         Ok(result) => {
-            if let Err(e) = state.skills.reload() {
+            if let Err(e) = reload_skills_registry(&state) {
                 tracing::warn!(error = %e, "failed to reload skills after update");
             }
+            let dependencies = installed_dependencies(&result);
+            let root = result.root;
             Json(serde_json::json!({
                 "updated": true,
                 "was_installed": was_installed,
-                "skill_dir": result.skill_dir,
-                "manifest_found": result.manifest_found,
-                "origin": result.origin,
-                "changed_files": result.changed_files,
-                "scripts_changed": result.scripts_changed,
+                "skill_dir": root.skill_dir,
+                "manifest_found": root.manifest_found,
+                "origin": root.origin,
+                "changed_files": root.changed_files,
+                "scripts_changed": root.scripts_changed,
+                "dependencies_installed": dependencies,
+                "dependencies_satisfied": result.already_satisfied,
             }))
             .into_response()
         }
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e })),
-        )
-            .into_response(),
+        Err(e) => install_error(e),
     }
 }
 
@@ -174,24 +266,30 @@ pub async fn uninstall_pack(
     }
     let skills_root = &state.config.skills.path;
 
-    match sa_skills::installer::uninstall(skills_root, &body.owner, &body.repo) {
+    match sa_skills::installer::uninstall(skills_root, &body.owner, &body.repo, body.force) {
+        Ok(result) if !result.removed && !result.dependents.is_empty() => ApiError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "{}/{} is required by other installed packs (pass force to remove anyway)",
+                body.owner, body.repo
+            ),
+        )
+        .with_details(serde_json::json!({ "dependents": result.dependents }))
+        .into_response(),
         Ok(result) => {
             if result.removed {
-                if let Err(e) = state.skills.reload() {
+                if let Err(e) = reload_skills_registry(&state) {
                     tracing::warn!(error = %e, "failed to reload skills after uninstall");
                 }
             }
             Json(serde_json::json!({
                 "uninstalled": result.removed,
                 "skill_dir": result.skill_dir,
+                "dependents": result.dependents,
             }))
             .into_response()
         }
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::internal(e.to_string()).into_response(),
     }
 }
 
@@ -207,14 +305,12 @@ pub async fn show_pack(
             "origin": origin,
         }))
         .into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
+        None => ApiError::not_found(format!("{owner}/{repo} is not installed"))
+            .with_details(serde_json::json!({
                 "installed": false,
                 "owner": owner,
                 "repo": repo,
-            })),
-        )
+            }))
             .into_response(),
     }
 }
@@ -223,10 +319,49 @@ pub async fn show_pack(
 // GitHub download helper
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-async fn download_and_install(
-    skills_root: &std::path::Path,
-    pack: &PackRef,
-) -> Result<sa_skills::installer::InstallResult, String> {
+/// GET `url` on the GitHub API, authenticated with `GITHUB_TOKEN` when set
+/// (private repos, higher rate limits).
+fn github_get(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    let req = client.get(url).header("User-Agent", "SerialAgent/0.1");
+    match std::env::var("GITHUB_TOKEN") {
+        Ok(token) => req.header("Authorization", format!("Bearer {token}")),
+        Err(_) => req,
+    }
+}
+
+/// The `version` declared by an installed pack's SKILL.md on its repo's
+/// default branch.
+async fn remote_version(
+    client: &reqwest::Client,
+    origin: sa_skills::installer::OriginMeta,
+) -> Result<Option<String>, String> {
+    let path = match &origin.subdir {
+        Some(sub) => format!("{}/SKILL.md", sub.trim_matches('/')),
+        None => "SKILL.md".into(),
+    };
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/contents/{path}",
+        origin.owner, origin.repo
+    );
+    let resp = github_get(client, &url)
+        .header("Accept", "application/vnd.github.raw")
+        .send()
+        .await
+        .map_err(|e| format!("GitHub request failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(format!("GitHub API returned {}", resp.status()));
+    }
+    let body = resp
+        .text()
+        .await
+        .map_err(|e| format!("failed to read SKILL.md: {e}"))?;
+    let (manifest, _) = sa_skills::manifest::parse_frontmatter(&body);
+    Ok(manifest.and_then(|m| m.version))
+}
+
+/// Download and extract one pack; the extracted files live in a temp dir
+/// kept alive by the returned [`FetchedPack`] until installation.
+async fn fetch_pack(pack: PackRequest) -> Result<FetchedPack, String> {
     // Determine the git ref to fetch.
     let effective_ref = pack.git_ref.as_deref().unwrap_or(
         if pack.version == "latest" {
@@ -238,19 +373,12 @@ async fn download_and_install(
 
     let url = format!(
         "https://api.github.com/repos/{}/{}/tarball/{effective_ref}",
-        pack.owner, pack.repo
+        pack.id.owner, pack.id.repo
     );
 
     // Download tarball.
     let client = reqwest::Client::new();
-    let mut req = client.get(&url).header("User-Agent", "SerialAgent/0.1");
-
-    // Use GITHUB_TOKEN if available for private repos / rate limits.
-    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-        req = req.header("Authorization", format!("Bearer {token}"));
-    }
-
-    let resp = req
+    let resp = github_get(&client, &url)
         .send()
         .await
         .map_err(|e| format!("GitHub download failed: {e}"))?;
@@ -298,14 +426,10 @@ async fn download_and_install(
     // Compute content hash for change detection.
     let hash = sa_skills::installer::compute_dir_hash(&source_dir);
 
-    sa_skills::installer::install_from_dir(
-        skills_root,
-        &pack.owner,
-        &pack.repo,
-        &source_dir,
-        &pack.version,
-        Some(effective_ref.to_string()),
-        Some(hash),
-    )
-    .map_err(|e| format!("install failed: {e}"))
+    Ok(FetchedPack {
+        source_dir,
+        git_ref: Some(effective_ref.to_string()),
+        files_hash: Some(hash),
+        keep_alive: Some(Box::new(tmp_dir)),
+    })
 }
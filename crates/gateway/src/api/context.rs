@@ -2,9 +2,11 @@ use axum::extract::{Query, State};
 use axum::response::{IntoResponse, Json};
 use serde::Deserialize;
 
-use sa_contextpack::builder::{ContextPackBuilder, SessionMode};
+use sa_contextpack::builder::{ContextPackBuilder, SessionMode, WorkspaceFile};
+use sa_contextpack::report::ContextReport;
 use sa_memory::UserFactsBuilder;
 
+use crate::runtime::system_context_mode;
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -40,10 +42,7 @@ pub async fn get_context(
         Some(user_facts.as_str())
     };
 
-    let builder = ContextPackBuilder::new(
-        state.config.context.bootstrap_max_chars,
-        state.config.context.bootstrap_total_max_chars,
-    );
+    let builder = ContextPackBuilder::from_config(&state.config.context);
 
     let ws_files = state.workspace.read_all_context_files();
     let skills_index = state.skills.render_ready_index();
@@ -68,15 +67,31 @@ pub async fn get_context(
     }))
 }
 
+/// `GET /v1/context/assembled` — the assembled system context as plain text.
+///
+/// With `?mode=bootstrap` or `?mode=normal`, previews exactly what the
+/// runtime sends in that mode (regardless of `BootstrapTracker` state) and
+/// returns JSON with the assembled text and the builder report.
 pub async fn get_assembled(
     State(state): State<AppState>,
     Query(params): Query<ContextParams>,
 ) -> impl IntoResponse {
-    let is_first_run = params
-        .force_first_run
-        .unwrap_or_else(|| state.bootstrap.is_first_run(&params.workspace_id));
+    let preview_first_run = match params.mode.as_deref() {
+        Some("bootstrap") => Some(true),
+        Some("normal") => Some(false),
+        _ => None,
+    };
 
-    let session_mode = parse_session_mode(params.mode.as_deref(), is_first_run);
+    let is_first_run = preview_first_run.unwrap_or_else(|| {
+        params
+            .force_first_run
+            .unwrap_or_else(|| state.bootstrap.is_first_run(&params.workspace_id))
+    });
+
+    let session_mode = match preview_first_run {
+        Some(first_run) => system_context_mode(first_run),
+        None => parse_session_mode(params.mode.as_deref(), is_first_run),
+    };
 
     let user_facts = build_user_facts(&state).await;
     let user_facts_opt = if user_facts.is_empty() {
@@ -85,10 +100,7 @@ pub async fn get_assembled(
         Some(user_facts.as_str())
     };
 
-    let builder = ContextPackBuilder::new(
-        state.config.context.bootstrap_max_chars,
-        state.config.context.bootstrap_total_max_chars,
-    );
+    let builder = ContextPackBuilder::from_config(&state.config.context);
 
     let ws_files = state.workspace.read_all_context_files();
     let skills_index = state.skills.render_ready_index();
@@ -98,6 +110,18 @@ pub async fn get_assembled(
         Some(skills_index.as_str())
     };
 
+    if preview_first_run.is_some() {
+        let (assembled, report) =
+            preview_context(&builder, &ws_files, is_first_run, skills_idx, user_facts_opt);
+        return Json(serde_json::json!({
+            "workspace_id": params.workspace_id,
+            "mode": params.mode,
+            "assembled": assembled,
+            "report": report,
+        }))
+        .into_response();
+    }
+
     let (assembled, _report) = builder.build(
         &ws_files,
         session_mode,
@@ -113,13 +137,34 @@ pub async fn get_assembled(
         .into_response()
 }
 
+/// Build the context the runtime would assemble for the given first-run state.
+fn preview_context(
+    builder: &ContextPackBuilder,
+    files: &[WorkspaceFile],
+    is_first_run: bool,
+    skills_index: Option<&str>,
+    user_facts: Option<&str>,
+) -> (String, ContextReport) {
+    builder.build(
+        files,
+        system_context_mode(is_first_run),
+        is_first_run,
+        skills_index,
+        user_facts,
+    )
+}
+
 async fn build_user_facts(state: &AppState) -> String {
     let user_id = &state.config.serial_memory.default_user_id;
     let facts_builder = UserFactsBuilder::new(
         state.memory.as_ref(),
         user_id,
         state.config.context.user_facts_max_chars,
-    );
+    )
+    .with_source_policy(state.config.serial_memory.user_facts.clone())
+    .with_token_counter(sa_domain::tokens::counter_for(
+        state.config.context.budget_unit,
+    ));
     facts_builder.build().await
 }
 
@@ -134,3 +179,43 @@ fn parse_session_mode(mode: Option<&str>, is_first_run: bool) -> SessionMode {
         _ => SessionMode::Normal,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<WorkspaceFile> {
+        ["AGENTS.md", "SOUL.md", "BOOTSTRAP.md"]
+            .into_iter()
+            .map(|name| WorkspaceFile {
+                name: name.into(),
+                content: Some(format!("contents of {name}")),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bootstrap_preview_includes_bootstrap_sections() {
+        let builder = ContextPackBuilder::new(10_000, 50_000);
+        let (assembled, report) = preview_context(&builder, &files(), true, None, None);
+
+        assert!(report.bootstrap_included);
+        assert!(report.first_run);
+        assert!(report.files.iter().any(|f| f.filename == "BOOTSTRAP.md" && f.included));
+        assert!(assembled.contains("contents of BOOTSTRAP.md"));
+        // The bootstrap ritual uses the minimal file set.
+        assert!(!report.files.iter().any(|f| f.filename == "SOUL.md"));
+    }
+
+    #[test]
+    fn normal_preview_omits_bootstrap_sections() {
+        let builder = ContextPackBuilder::new(10_000, 50_000);
+        let (assembled, report) = preview_context(&builder, &files(), false, None, None);
+
+        assert!(!report.bootstrap_included);
+        assert!(!report.first_run);
+        assert!(!report.files.iter().any(|f| f.filename == "BOOTSTRAP.md"));
+        assert!(!assembled.contains("contents of BOOTSTRAP.md"));
+        assert!(assembled.contains("contents of SOUL.md"));
+    }
+}
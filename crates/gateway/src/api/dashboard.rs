@@ -96,9 +96,10 @@ pub async fn index(State(state): State<AppState>) -> impl IntoResponse {
 <ul>
 <li><a href="/v1/context">/v1/context</a> — Context introspection</li>
 <li><a href="/v1/context/assembled">/v1/context/assembled</a> — Assembled prompt</li>
+<li><a href="/v1/context/assembled?mode=bootstrap">bootstrap</a> / <a href="/v1/context/assembled?mode=normal">normal</a> — Mode previews with report</li>
 <li><a href="/v1/skills">/v1/skills</a> — Skill list</li>
 <li><a href="/v1/memory/health">/v1/memory/health</a> — SerialMemory health</li>
-<li><a href="/v1/models">/v1/models</a> — Provider list</li>
+<li><a href="/v1/models">/v1/models</a> — Model list (OpenAI-compatible)</li>
 <li><a href="/v1/models/roles">/v1/models/roles</a> — Role assignments</li>
 <li><a href="/v1/nodes">/v1/nodes</a> — Connected nodes</li>
 <li><a href="/v1/sessions">/v1/sessions</a> — Active sessions</li>
@@ -6,7 +6,8 @@ use axum::response::{IntoResponse, Json};
 use futures_util::stream::Stream;
 use serde::Deserialize;
 
-use crate::runtime::deliveries::DeliveryEvent;
+use crate::api::error::ApiError;
+use crate::runtime::deliveries::{DeliveryEvent, DeliveryFilter};
 use crate::state::AppState;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -52,11 +53,7 @@ pub async fn get_delivery(
 ) -> impl IntoResponse {
     match state.delivery_store.get(&id).await {
         Some(delivery) => Json(serde_json::json!({ "delivery": delivery })).into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "delivery not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("delivery not found").into_response(),
     }
 }
 
@@ -69,12 +66,53 @@ pub async fn mark_delivery_read(
     Path(id): Path<uuid::Uuid>,
 ) -> impl IntoResponse {
     if state.delivery_store.mark_read(&id).await {
-        Json(serde_json::json!({ "ok": true }))
+        Json(serde_json::json!({ "ok": true })).into_response()
     } else {
-        Json(serde_json::json!({ "ok": false, "error": "delivery not found" }))
+        ApiError::not_found("delivery not found").into_response()
     }
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// POST /v1/deliveries/read-all, DELETE /v1/deliveries
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Filters shared by the bulk endpoints.  No filters = every delivery.
+#[derive(Debug, Deserialize)]
+pub struct BulkDeliveriesQuery {
+    #[serde(default)]
+    pub schedule_id: Option<uuid::Uuid>,
+    /// Only deliveries at least this many seconds old.
+    #[serde(default)]
+    pub older_than_secs: Option<u64>,
+}
+
+impl BulkDeliveriesQuery {
+    fn filter(&self) -> DeliveryFilter {
+        DeliveryFilter {
+            schedule_id: self.schedule_id,
+            created_before: self.older_than_secs.map(|secs| {
+                chrono::Utc::now() - chrono::Duration::seconds(secs.min(i64::MAX as u64) as i64)
+            }),
+        }
+    }
+}
+
+pub async fn mark_all_deliveries_read(
+    State(state): State<AppState>,
+    Query(query): Query<BulkDeliveriesQuery>,
+) -> impl IntoResponse {
+    let count = state.delivery_store.mark_read_where(&query.filter()).await;
+    Json(serde_json::json!({ "ok": true, "count": count }))
+}
+
+pub async fn delete_deliveries(
+    State(state): State<AppState>,
+    Query(query): Query<BulkDeliveriesQuery>,
+) -> impl IntoResponse {
+    let count = state.delivery_store.delete_where(&query.filter()).await;
+    Json(serde_json::json!({ "ok": true, "count": count }))
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // GET /v1/deliveries/events (SSE)
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -91,6 +129,8 @@ pub async fn delivery_events_sse(
                     let event_type = match &event {
                         DeliveryEvent::NewDelivery { .. } => "delivery.new",
                         DeliveryEvent::DeliveryRead { .. } => "delivery.read",
+                        DeliveryEvent::DeliveriesRead { .. } => "delivery.read_bulk",
+                        DeliveryEvent::DeliveriesDeleted { .. } => "delivery.deleted",
                     };
                     if let Ok(json) = serde_json::to_string(&event) {
                         yield Ok(Event::default().event(event_type).data(json));
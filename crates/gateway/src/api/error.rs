@@ -0,0 +1,226 @@
+//! Standard error response for gateway endpoints.
+//!
+//! Handlers report failures as [`ApiError`], which renders as:
+//!
+//! ```json
+//! { "error": { "code": "not_found", "message": "session not found", "details": { ... } } }
+//! ```
+//!
+//! `code` is a stable machine-readable string derived from the HTTP status
+//! (using the [`ErrorKind`] names where one applies: `invalid_args`,
+//! `not_allowed`, `not_found`, `timeout`, `failed`) unless a handler sets a
+//! more specific one.  `details` is omitted when there's nothing to add.
+//!
+//! SSE endpoints send the same body as the data of an `error` event (see
+//! [`ApiError::sse_event`]).  The OpenAI-compatible endpoints keep OpenAI's
+//! own error format, since their clients parse it.
+
+use axum::http::StatusCode;
+use axum::response::sse::Event;
+use axum::response::{IntoResponse, Json, Response};
+use sa_protocol::ErrorKind;
+use serde_json::Value;
+
+/// An error response: HTTP status plus the standard JSON body.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    details: Option<Value>,
+}
+
+impl ApiError {
+    /// An error with the default code for `status`.
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: default_code(status),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// An error for a failure categorised by [`ErrorKind`].
+    pub fn from_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        let (status, code) = match kind {
+            ErrorKind::InvalidArgs => (StatusCode::BAD_REQUEST, "invalid_args"),
+            ErrorKind::NotAllowed => (StatusCode::FORBIDDEN, "not_allowed"),
+            ErrorKind::NotFound => (StatusCode::NOT_FOUND, "not_found"),
+            ErrorKind::Timeout => (StatusCode::GATEWAY_TIMEOUT, "timeout"),
+            ErrorKind::Cancelled => (StatusCode::CONFLICT, "cancelled"),
+            ErrorKind::Failed => (StatusCode::INTERNAL_SERVER_ERROR, "failed"),
+        };
+        Self::new(status, message).with_code(code)
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    /// Replace the code derived from the status.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Attach structured context (conflicting items, limits, ...).
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The JSON body.
+    pub fn body(&self) -> Value {
+        let mut error = serde_json::json!({
+            "code": self.code,
+            "message": self.message,
+        });
+        if let Some(ref details) = self.details {
+            error["details"] = details.clone();
+        }
+        serde_json::json!({ "error": error })
+    }
+
+    /// An SSE `error` event carrying the same JSON body, for streams that
+    /// fail before (or instead of) producing their first event.
+    pub fn sse_event(&self) -> Event {
+        Event::default()
+            .event("error")
+            .data(self.body().to_string())
+    }
+}
+
+/// Code for a status, following `ErrorKind` names where they fit.
+fn default_code(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "invalid_args",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "not_allowed",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::CONFLICT => "conflict",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+        StatusCode::NOT_IMPLEMENTED => "not_implemented",
+        StatusCode::BAD_GATEWAY => "upstream_failed",
+        StatusCode::SERVICE_UNAVAILABLE => "unavailable",
+        StatusCode::GATEWAY_TIMEOUT => "timeout",
+        s if s.is_client_error() => "invalid_request",
+        _ => "failed",
+    }
+}
+
+/// Shorthand for `ApiError::new(status, message).into_response()`.
+pub fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
+    ApiError::new(status, message).into_response()
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body())).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_of(resp: Response) -> Value {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn renders_the_standard_shape() {
+        let resp = ApiError::not_found("session not found").into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_of(resp).await,
+            serde_json::json!({
+                "error": { "code": "not_found", "message": "session not found" }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn details_and_custom_codes_are_kept() {
+        let resp = ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "no providers")
+            .with_code("no_llm_provider")
+            .with_details(serde_json::json!({ "init_errors": ["boom"] }))
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = body_of(resp).await;
+        assert_eq!(body["error"]["code"], "no_llm_provider");
+        assert_eq!(body["error"]["details"]["init_errors"][0], "boom");
+    }
+
+    #[tokio::test]
+    async fn sse_event_carries_the_standard_body() {
+        let event = ApiError::not_found("run not found").sse_event();
+        let stream =
+            futures_util::stream::once(async move { Ok::<_, std::convert::Infallible>(event) });
+        let resp = axum::response::sse::Sse::new(stream).into_response();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+
+        assert!(text.starts_with("event: error\n"), "{text}");
+        let data = text.lines().find_map(|l| l.strip_prefix("data: ")).unwrap();
+        let body: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(body["error"]["code"], "not_found");
+        assert_eq!(body["error"]["message"], "run not found");
+    }
+
+    #[test]
+    fn codes_follow_error_kind_semantics() {
+        let cases = [
+            (ErrorKind::InvalidArgs, StatusCode::BAD_REQUEST),
+            (ErrorKind::NotAllowed, StatusCode::FORBIDDEN),
+            (ErrorKind::NotFound, StatusCode::NOT_FOUND),
+            (ErrorKind::Timeout, StatusCode::GATEWAY_TIMEOUT),
+            (ErrorKind::Failed, StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+        for (kind, status) in cases {
+            let err = ApiError::from_kind(kind, "x");
+            assert_eq!(err.status(), status);
+            assert_eq!(err.code(), kind.to_string());
+            // The status alone maps to the same code.
+            assert_eq!(ApiError::new(status, "x").code(), err.code());
+        }
+        assert_eq!(
+            ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, "x").code(),
+            "invalid_request"
+        );
+    }
+}
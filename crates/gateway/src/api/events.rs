@@ -0,0 +1,79 @@
+//! Unified event stream — `GET /v1/events`.
+//!
+//! One SSE stream carrying run status, schedule, delivery and node events
+//! (see [`crate::runtime::events`]).  The per-resource streams
+//! (`/v1/deliveries/events`, `/v1/nodes/events`, ...) are unchanged.
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::Stream;
+use serde::Deserialize;
+
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Comma-separated sources to include (`run`, `schedule`, `delivery`,
+    /// `node`).  Absent or empty = all.
+    #[serde(default)]
+    pub sources: Option<String>,
+}
+
+impl EventsQuery {
+    fn wants(&self, source: &str) -> bool {
+        match self.sources.as_deref().map(str::trim) {
+            None | Some("") => true,
+            Some(list) => list.split(',').any(|s| s.trim() == source),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/events (SSE)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Each SSE event is named by its type tag (`run.status`,
+/// `node.connected`, ...) and carries `{ "type", "source", "data" }`.
+pub async fn event_stream_sse(
+    State(state): State<AppState>,
+    Query(query): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let mut rx = state.events.subscribe();
+
+    let stream = async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if !query.wants(event.source()) {
+                        continue;
+                    }
+                    yield Ok(Event::default()
+                        .event(event.event_type())
+                        .data(event.to_json().to_string()));
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+    };
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_filter() {
+        let all = EventsQuery { sources: None };
+        assert!(all.wants("run") && all.wants("node"));
+
+        let some = EventsQuery {
+            sources: Some("run, node".into()),
+        };
+        assert!(some.wants("run"));
+        assert!(some.wants("node"));
+        assert!(!some.wants("delivery"));
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! These types define the staging-based import flow:
 //!   1. POST /v1/import/openclaw/preview  → fetch + scan → ImportPreviewResponse
-//!   2. POST /v1/import/openclaw/apply    → copy staged files → ImportApplyResponse
+//!      (or POST /v1/import/openclaw/preview/stream for SSE phase progress)
+//!   2. POST /v1/import/openclaw/conflicts/:staging_id → dry-run a strategy
+//!      → ImportConflictsResponse (optional)
+//!   3. POST /v1/import/openclaw/apply    → copy staged files → ImportApplyResponse
+//!   4. POST /v1/import/openclaw/rollback/:apply_id → undo an apply
+//!      → ImportRollbackResponse (optional)
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -184,7 +189,7 @@ pub struct ConflictsHint {
 // Merge strategy
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MergeStrategy {
     /// Copy into workspace/imported/openclaw/... and sessions/imported/openclaw/...
@@ -210,15 +215,34 @@ pub struct ImportApplyRequest {
     pub merge_strategy: MergeStrategy,
     #[serde(default)]
     pub options: ImportOptions,
+    /// Only import these agents (sessions, models, auth profiles).  Empty = all.
+    #[serde(default)]
+    pub only_agents: Vec<String>,
+    /// Only import these workspaces (`workspace`, `workspace-*`).  Empty = all.
+    #[serde(default)]
+    pub only_workspaces: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportApplyResponse {
     pub staging_id: Uuid,
+    /// Pass to `POST /v1/import/openclaw/rollback/:apply_id` to undo.
+    pub apply_id: Uuid,
     pub imported: ImportedSummary,
     pub warnings: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRollbackResponse {
+    pub apply_id: Uuid,
+    pub staging_id: Uuid,
+    /// Files the apply had created that were removed.
+    pub removed: u32,
+    /// Overwritten or deleted files restored from backup.
+    pub restored: u32,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ImportedSummary {
     pub agents: Vec<String>,
@@ -230,6 +254,71 @@ pub struct ImportedSummary {
     pub schedules_imported: Vec<String>,
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Conflict report (dry run of apply)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportConflictsRequest {
+    #[serde(default = "default_merge")]
+    pub merge_strategy: MergeStrategy,
+    #[serde(default)]
+    pub options: ImportOptions,
+    /// Same selection as [`ImportApplyRequest::only_agents`].
+    #[serde(default)]
+    pub only_agents: Vec<String>,
+    /// Same selection as [`ImportApplyRequest::only_workspaces`].
+    #[serde(default)]
+    pub only_workspaces: Vec<String>,
+}
+
+/// What apply would do to an existing destination file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    /// Existing file kept; the staged copy is not imported (`skip_existing`).
+    Skip,
+    /// Existing file replaced by the staged copy.
+    Overwrite,
+    /// Existing file removed because its folder is replaced (`replace`).
+    Delete,
+    /// Staged copy lands under `imported/openclaw/` beside the existing
+    /// file instead of replacing it (`merge_safe`).
+    Relocate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportConflict {
+    /// `workspace`, `session`, or `agent_file`.
+    pub kind: String,
+    /// Path relative to the staged export.
+    pub source: String,
+    /// Destination apply would write (or delete).
+    pub dest: String,
+    pub action: ConflictAction,
+    /// For `relocate`: the existing file the staged copy would have clashed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existing: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConflictTotals {
+    /// Staged files that land on a fresh path (no conflict, not listed).
+    pub created: u32,
+    pub skipped: u32,
+    pub overwritten: u32,
+    pub deleted: u32,
+    pub relocated: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportConflictsResponse {
+    pub staging_id: Uuid,
+    pub merge_strategy: MergeStrategy,
+    pub conflicts: Vec<ImportConflict>,
+    pub totals: ConflictTotals,
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Import status (for async apply polling)
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
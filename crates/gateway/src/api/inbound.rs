@@ -7,23 +7,31 @@
 //! The endpoint handles:
 //! - Idempotent delivery (event_id deduplication)
 //! - Send policy enforcement (deny groups by default)
+//! - Attachment staging (inline files written under the import root)
 //! - Identity resolution + session key computation
 //! - Full turn execution (blocking)
 //! - Reply splitting for platforms with character limits
 //! - Outbound action assembly
+//!
+//! Request and response bodies may be JSON or MessagePack
+//! (see [`crate::api::negotiate`]).
 
 use std::collections::HashMap;
 
 use std::time::{Duration, Instant};
 
 use axum::extract::State;
-use axum::response::{IntoResponse, Json};
+use axum::response::IntoResponse;
 use serde::{Deserialize, Serialize};
 
 use sa_domain::config::{InboundMetadata, SendPolicyMode};
+use sa_domain::tool::ContentPart;
 use sa_sessions::{compute_session_key, validate_metadata};
 use sa_sessions::store::SessionOrigin;
 
+use crate::api::error::ApiError;
+use crate::api::negotiate::Negotiated;
+use crate::import::attachments::{self, AttachmentError};
 use crate::runtime::session_lock::SessionBusy;
 use crate::runtime::{run_turn, TurnEvent, TurnInput};
 use crate::state::AppState;
@@ -100,7 +108,9 @@ pub struct InboundEnvelope {
     pub display: Option<DisplayInfo>,
     /// The user's message text.
     pub text: String,
-    /// Attachments (reserved for future use).
+    /// Attachments.  Images (`{"type": "image", "url": ...}`) are passed
+    /// to the model; files carrying inline base64 `data` are staged under
+    /// the import root and referenced in the user message.
     #[serde(default)]
     pub attachments: Vec<serde_json::Value>,
     /// Model override.
@@ -257,14 +267,14 @@ pub struct TurnTelemetry {
 
 pub async fn inbound(
     State(state): State<AppState>,
-    Json(body): Json<InboundEnvelope>,
+    Negotiated { body, reply }: Negotiated<InboundEnvelope>,
 ) -> impl IntoResponse {
     let is_direct = body.chat_type == ChatType::Direct;
 
     // ── 0. Idempotency check ──────────────────────────────────────
     if let Some(ref event_id) = body.event_id {
         if state.dedupe.check_and_insert(event_id) {
-            return Json(InboundResponse {
+            return reply.respond(&InboundResponse {
                 accepted: true,
                 deduped: true,
                 session_key: String::new(),
@@ -272,8 +282,7 @@ pub async fn inbound(
                 actions: vec![],
                 policy: Some("deduped".into()),
                 telemetry: None,
-            })
-            .into_response();
+            });
         }
     }
 
@@ -283,7 +292,7 @@ pub async fn inbound(
         .as_deref()
         .unwrap_or("message.create");
     if event_type != "message.create" {
-        return Json(InboundResponse {
+        return reply.respond(&InboundResponse {
             accepted: true,
             deduped: false,
             session_key: String::new(),
@@ -291,8 +300,7 @@ pub async fn inbound(
             actions: vec![],
             policy: Some(format!("unsupported_event:{event_type}")),
             telemetry: None,
-        })
-        .into_response();
+        });
     }
 
     // ── 1. Resolve identity ───────────────────────────────────────
@@ -312,15 +320,14 @@ pub async fn inbound(
 
     // Enforce channel_id for non-DM (connectors MUST provide it).
     if !is_direct && channel_id.is_none() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": "missing chat_id for non-direct message — connectors must provide the reply container ID",
-                "channel": body.channel,
-                "chat_type": body.chat_type,
-            })),
+        return ApiError::bad_request(
+            "missing chat_id for non-direct message — connectors must provide the reply container ID",
         )
-            .into_response();
+        .with_details(serde_json::json!({
+            "channel": body.channel,
+            "chat_type": body.chat_type,
+        }))
+        .into_response();
     }
 
     let meta = InboundMetadata {
@@ -366,7 +373,7 @@ pub async fn inbound(
         .unwrap_or(policy.default);
 
     if channel_policy == SendPolicyMode::Deny {
-        return Json(InboundResponse {
+        return reply.respond(&InboundResponse {
             accepted: true,
             deduped: false,
             session_key: session_key.clone(),
@@ -374,12 +381,11 @@ pub async fn inbound(
             actions: vec![],
             policy: Some("denied:channel".into()),
             telemetry: None,
-        })
-        .into_response();
+        });
     }
 
     if !is_direct && policy.deny_groups {
-        return Json(InboundResponse {
+        return reply.respond(&InboundResponse {
             accepted: true,
             deduped: false,
             session_key: session_key.clone(),
@@ -387,10 +393,37 @@ pub async fn inbound(
             actions: vec![],
             policy: Some("denied:group".into()),
             telemetry: None,
-        })
-        .into_response();
+        });
     }
 
+    // ── 4b. Stage file attachments ────────────────────────────────
+    let staged = match attachments::stage_attachments(
+        &state.config.workspace.path,
+        &body.attachments,
+        attachments::max_attachment_bytes(),
+    )
+    .await
+    {
+        Ok(staged) => staged,
+        Err(e) => {
+            let status = match e {
+                AttachmentError::TooLarge { .. } => axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+                AttachmentError::Invalid { .. } => axum::http::StatusCode::BAD_REQUEST,
+                AttachmentError::Io(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return ApiError::new(status, e.to_string()).into_response();
+        }
+    };
+    let user_message = if staged.is_empty() {
+        body.text
+    } else {
+        format!(
+            "{}\n\n{}",
+            body.text,
+            attachments::attachment_references(&staged)
+        )
+    };
+
     // ── 5. Resolve or create session ──────────────────────────────
     let origin = SessionOrigin {
         channel: Some(body.channel.clone()),
@@ -422,14 +455,13 @@ pub async fn inbound(
     let _permit = match state.session_locks.acquire(&session_key).await {
         Ok(p) => p,
         Err(SessionBusy) => {
-            return (
+            return ApiError::new(
                 axum::http::StatusCode::TOO_MANY_REQUESTS,
-                Json(serde_json::json!({
-                    "error": "session is busy — a turn is already in progress",
-                    "session_key": session_key,
-                })),
+                "session is busy — too many turns queued",
             )
-                .into_response();
+            .with_code("session_busy")
+            .with_details(serde_json::json!({ "session_key": session_key }))
+            .into_response();
         }
     };
 
@@ -456,11 +488,15 @@ pub async fn inbound(
     let input = TurnInput {
         session_key: session_key.clone(),
         session_id: entry.session_id.clone(),
-        user_message: body.text,
+        user_message,
         model: body.model,
         response_format: None,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: image_attachments(&body.attachments),
+        seed: None,
+        stop: Vec::new(),
     };
 
     let (_run_id, mut rx) = run_turn(state.clone(), input);
@@ -503,13 +539,8 @@ pub async fn inbound(
                 output_tokens = ot;
             }
             TurnEvent::Error { message } => {
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": message,
-                        "session_key": session_key,
-                    })),
-                )
+                return ApiError::internal(message)
+                    .with_details(serde_json::json!({ "session_key": session_key }))
                     .into_response();
             }
             _ => { /* ignore deltas, tool calls in blocking mode */ }
@@ -551,7 +582,7 @@ pub async fn inbound(
         None
     };
 
-    Json(InboundResponse {
+    reply.respond(&InboundResponse {
         accepted: true,
         deduped: false,
         session_key,
@@ -560,7 +591,33 @@ pub async fn inbound(
         policy: policy_label,
         telemetry,
     })
-    .into_response()
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Attachments
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// The image attachments of an envelope as content parts.  An attachment
+/// is an image when its `type` is `"image"` or its `media_type` /
+/// `mime_type` starts with `image/`; `url` may be an `http(s)` URL, a
+/// `data:` URL, or bare base64.
+fn image_attachments(attachments: &[serde_json::Value]) -> Vec<ContentPart> {
+    attachments
+        .iter()
+        .filter_map(|a| {
+            let media_type = a
+                .get("media_type")
+                .or_else(|| a.get("mime_type"))
+                .and_then(|v| v.as_str());
+            let is_image = a.get("type").and_then(|v| v.as_str()) == Some("image")
+                || media_type.is_some_and(|m| m.starts_with("image/"));
+            let url = a.get("url").and_then(|v| v.as_str())?;
+            is_image.then(|| ContentPart::Image {
+                url: url.to_owned(),
+                media_type: media_type.map(str::to_owned),
+            })
+        })
+        .collect()
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -763,4 +820,30 @@ mod tests {
         assert!(json.get("policy").is_none());
         assert!(json.get("telemetry").is_none());
     }
+
+    #[test]
+    fn image_attachments_become_image_parts() {
+        let attachments = vec![
+            serde_json::json!({ "type": "image", "url": "https://cdn.example/a.png" }),
+            serde_json::json!({ "mime_type": "image/jpeg", "url": "QUJD" }),
+            serde_json::json!({ "type": "file", "media_type": "application/pdf", "url": "x" }),
+            serde_json::json!({ "type": "image" }),
+        ];
+        let parts = image_attachments(&attachments);
+        assert_eq!(parts.len(), 2);
+        let urls: Vec<(&str, Option<&str>)> = parts
+            .iter()
+            .map(|p| match p {
+                ContentPart::Image { url, media_type } => (url.as_str(), media_type.as_deref()),
+                _ => panic!("expected an image part"),
+            })
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                ("https://cdn.example/a.png", None),
+                ("QUJD", Some("image/jpeg")),
+            ]
+        );
+    }
 }
@@ -1,9 +1,12 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use serde::Deserialize;
 
 use sa_memory::types::{MemoryIngestRequest, RagSearchRequest};
+use sa_memory::UserFactsBuilder;
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -30,11 +33,44 @@ pub async fn search(
             "count": resp.count,
         }))
         .into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+    }
+}
+
+/// Default and ceiling for graph hops on `/v1/memory/multi-hop`.
+const DEFAULT_HOPS: u32 = 2;
+const MAX_HOPS: u32 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct MultiHopBody {
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Graph hops to expand from the seed matches (1–5, default 2).
+    #[serde(default)]
+    pub hops: Option<u32>,
+}
+
+pub async fn multi_hop_search(
+    State(state): State<AppState>,
+    Json(body): Json<MultiHopBody>,
+) -> impl IntoResponse {
+    let hops = body.hops.unwrap_or(DEFAULT_HOPS).clamp(1, MAX_HOPS);
+    let req = RagSearchRequest {
+        query: body.query,
+        limit: body.limit,
+        ..Default::default()
+    };
+
+    match state.memory.multi_hop_search(req, hops).await {
+        Ok(resp) => Json(serde_json::json!({
+            "query": resp.query,
+            "hops": hops,
+            "memories": resp.memories,
+            "count": resp.count,
+        }))
+        .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
 
@@ -68,33 +104,50 @@ pub async fn ingest(
             "message": resp.message,
         }))
         .into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
 
-pub async fn about_user(State(state): State<AppState>) -> impl IntoResponse {
-    match state.memory.get_persona().await {
-        Ok(persona) => Json(persona).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
-    }
+/// Search query used for `/v1/memory/about` when the caller gives none.
+const DEFAULT_ABOUT_QUERY: &str = "about the user";
+
+#[derive(Debug, Deserialize)]
+pub struct AboutQuery {
+    /// Search query for retrieved facts (default: "about the user").
+    #[serde(default)]
+    pub q: Option<String>,
+    /// Max facts returned (default 10, capped at 50).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Structured persona + top facts for UI display — the same data the
+/// USER_FACTS prompt section is built from, without the prompt budget.
+/// An unreachable or empty memory yields empty lists, not an error.
+pub async fn about_user(
+    State(state): State<AppState>,
+    Query(params): Query<AboutQuery>,
+) -> impl IntoResponse {
+    let query = params
+        .q
+        .filter(|q| !q.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_ABOUT_QUERY.to_owned());
+    let summary = UserFactsBuilder::new(
+        state.memory.as_ref(),
+        &state.config.serial_memory.default_user_id,
+        state.config.context.user_facts_max_chars,
+    )
+    .with_source_policy(state.config.serial_memory.user_facts.clone())
+    .with_query(query)
+    .summary(params.limit.unwrap_or(10).min(50))
+    .await;
+    Json(summary)
 }
 
 pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
     match state.memory.health().await {
         Ok(h) => Json(h).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
     }
 }
 
@@ -110,11 +163,7 @@ pub async fn update_entry(
 ) -> impl IntoResponse {
     match state.memory.update_memory(&id, &body.content).await {
         Ok(resp) => Json(resp).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
 
@@ -123,12 +172,25 @@ pub async fn delete_entry(
     Path(id): Path<String>,
 ) -> impl IntoResponse {
     match state.memory.delete_memory(&id).await {
-        Ok(()) => Json(serde_json::json!({ "deleted": true })).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Ok(()) => Json(serde_json::json!({
+            "deleted": true,
+            "soft": state.config.serial_memory.soft_delete.enabled,
+        }))
+        .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+    }
+}
+
+pub async fn restore_entry(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.memory.restore_memory(&id).await {
+        Ok(true) => Json(serde_json::json!({ "restored": true })).into_response(),
+        Ok(false) => {
+            ApiError::not_found(format!("memory {id} is not soft-deleted")).into_response()
+        }
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
 
@@ -150,11 +212,7 @@ pub async fn init_session(
 
     match state.memory.init_session(req).await {
         Ok(resp) => Json(resp).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
 
@@ -169,10 +227,6 @@ pub async fn end_session(
 ) -> impl IntoResponse {
     match state.memory.end_session(&body.session_id).await {
         Ok(()) => Json(serde_json::json!({ "ended": true })).into_response(),
-        Err(e) => (
-            axum::http::StatusCode::BAD_GATEWAY,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::new(StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
     }
 }
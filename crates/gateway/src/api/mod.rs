@@ -1,18 +1,23 @@
 pub mod admin;
 pub mod agents;
 pub mod auth;
+pub mod body_limit;
 pub mod chat;
 pub mod clawhub;
 pub mod context;
 pub mod dashboard;
 pub mod deliveries;
+pub mod error;
+pub mod events;
 pub mod import_openclaw;
 pub mod inbound;
 pub mod memory;
+pub mod negotiate;
 pub mod nodes;
 pub mod openai_compat;
 pub mod providers;
 pub mod quota;
+pub mod rate_limit;
 pub mod router;
 pub mod runs;
 pub mod schedules;
@@ -20,6 +25,7 @@ pub mod sessions;
 pub mod skills;
 pub mod tasks;
 pub mod tools;
+pub mod usage;
 pub mod webhooks;
 
 use axum::middleware;
@@ -31,7 +37,10 @@ use crate::state::AppState;
 /// Build the full API router.
 ///
 /// Routes are split into **public** (no auth required) and **protected**
-/// (gated behind the `SA_API_TOKEN` bearer-token middleware).
+/// (gated behind the `SA_API_TOKEN` bearer-token middleware).  Protected
+/// routes are further grouped by request body cap: content-carrying
+/// endpoints use `server.body_limits.ingest_bytes`, everything else
+/// `server.body_limits.control_bytes`.
 ///
 /// `state` is needed to wire up the auth middleware at build time.
 pub fn router(state: AppState) -> Router<AppState> {
@@ -44,9 +53,35 @@ pub fn router(state: AppState) -> Router<AppState> {
         // Health probe (public, no auth)
         .route("/v1/health", get(admin::health))
         // OpenAPI spec (public, no auth)
-        .route("/v1/openapi.json", get(admin::openapi_spec));
+        .route("/v1/openapi.json", get(admin::openapi_spec))
+        // Node WebSocket (authenticated by node tokens in the handler; the
+        // API token middleware would reject a node's bearer token)
+        .route("/v1/nodes/ws", get(crate::nodes::ws::node_ws));
 
-    let protected = Router::new()
+    // Content-carrying routes get the larger body cap.
+    let ingest = Router::new()
+        .route("/v1/memory/ingest", post(memory::ingest))
+        // Chat (core runtime)
+        .route("/v1/chat", post(chat::chat))
+        .route("/v1/chat/stream", post(chat::chat_stream))
+        // OpenAI-compatible chat completions
+        .route(
+            "/v1/chat/completions",
+            post(openai_compat::chat_completions),
+        )
+        // Inbound (channel connector contract)
+        .route("/v1/inbound", post(inbound::inbound))
+        // Tasks (concurrent task queue)
+        .route("/v1/tasks", post(tasks::create_task))
+        // Workspace file edits (admin-gated in the handler)
+        .route(
+            "/v1/admin/workspace/files/*path",
+            put(admin::write_workspace_file),
+        )
+        // Webhook triggers (arbitrary external payloads)
+        .route("/v1/schedules/:id/trigger", post(webhooks::trigger_webhook));
+
+    let control = Router::new()
         // Context introspection
         .route("/v1/context", get(context::get_context))
         .route("/v1/context/assembled", get(context::get_assembled))
@@ -57,11 +92,12 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/v1/skills/reload", post(skills::reload_skills))
         // Memory (proxy to SerialMemoryServer)
         .route("/v1/memory/search", post(memory::search))
-        .route("/v1/memory/ingest", post(memory::ingest))
+        .route("/v1/memory/multi-hop", post(memory::multi_hop_search))
         .route("/v1/memory/about", get(memory::about_user))
         .route("/v1/memory/health", get(memory::health))
         .route("/v1/memory/:id", put(memory::update_entry))
         .route("/v1/memory/:id", delete(memory::delete_entry))
+        .route("/v1/memory/:id/restore", post(memory::restore_entry))
         // Legacy session proxy (SerialMemory)
         .route("/v1/session/init", post(memory::init_session))
         .route("/v1/session/end", post(memory::end_session))
@@ -72,20 +108,13 @@ pub fn router(state: AppState) -> Router<AppState> {
         // Session detail (path-based)
         .route("/v1/sessions/:key", get(sessions::get_session))
         .route("/v1/sessions/:key/transcript", get(sessions::get_transcript))
+        .route("/v1/sessions/:key/compactions", get(sessions::list_compactions))
         .route("/v1/sessions/:key/export", get(sessions::export_transcript))
         .route("/v1/sessions/:key/reset", post(sessions::reset_session_by_key))
         .route("/v1/sessions/:key/stop", post(sessions::stop_session))
+        .route("/v1/sessions/:key/tags", put(sessions::set_session_tags))
+        .route("/v1/sessions/:key/model", put(sessions::pin_session_model))
         .route("/v1/sessions/:key/compact", post(sessions::compact_session))
-        // Chat (core runtime)
-        .route("/v1/chat", post(chat::chat))
-        .route("/v1/chat/stream", post(chat::chat_stream))
-        // OpenAI-compatible chat completions
-        .route(
-            "/v1/chat/completions",
-            post(openai_compat::chat_completions),
-        )
-        // Inbound (channel connector contract)
-        .route("/v1/inbound", post(inbound::inbound))
         // Tools (exec / process / invoke / approval)
         .route("/v1/tools/exec", post(tools::exec_tool))
         .route("/v1/tools/process", post(tools::process_tool))
@@ -93,17 +122,21 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/v1/tools/exec/pending", get(tools::list_pending_approvals))
         .route("/v1/tools/exec/approve/:id", post(tools::approve_exec))
         .route("/v1/tools/exec/deny/:id", post(tools::deny_exec))
+        .route("/v1/tools/stats", get(tools::tool_stats))
+        .route("/v1/tools/audit", get(tools::tool_audit))
+        .route("/v1/tools/definitions", get(tools::tool_definitions))
         // Nodes
         .route("/v1/nodes", get(nodes::list_nodes))
-        .route("/v1/nodes/ws", get(crate::nodes::ws::node_ws))
+        .route("/v1/nodes/events", get(nodes::node_events_sse))
+        .route("/v1/nodes/:id/selftest", post(nodes::selftest_node))
         // ClawHub (third-party skill packs)
         .route("/v1/clawhub/installed", get(clawhub::list_installed))
+        .route("/v1/clawhub/outdated", get(clawhub::list_outdated))
         .route("/v1/clawhub/skill/:owner/:repo", get(clawhub::show_pack))
         .route("/v1/clawhub/install", post(clawhub::install_pack))
         .route("/v1/clawhub/update", post(clawhub::update_pack))
         .route("/v1/clawhub/uninstall", post(clawhub::uninstall_pack))
         // Tasks (concurrent task queue)
-        .route("/v1/tasks", post(tasks::create_task))
         .route("/v1/tasks", get(tasks::list_tasks))
         .route("/v1/tasks/:id", get(tasks::get_task))
         .route("/v1/tasks/:id", delete(tasks::cancel_task))
@@ -115,6 +148,8 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/v1/router/config", put(router::update_config))
         .route("/v1/router/classify", post(router::classify))
         .route("/v1/router/decisions", get(router::decisions))
+        .route("/v1/router/breakers", get(router::breakers))
+        .route("/v1/router/breakers/:provider/reset", post(router::reset_breaker))
         // Runs (execution tracking)
         .route("/v1/runs", get(runs::list_runs))
         .route("/v1/runs/:id", get(runs::get_run))
@@ -131,21 +166,27 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route("/v1/schedules/:id/dry-run", post(schedules::dry_run_schedule))
         .route("/v1/schedules/:id/reset-errors", post(schedules::reset_schedule_errors))
         .route("/v1/schedules/:id/deliveries", get(schedules::list_schedule_deliveries))
-        .route("/v1/schedules/:id/trigger", post(webhooks::trigger_webhook))
         // Deliveries (inbox)
         .route("/v1/deliveries", get(deliveries::list_deliveries))
+        .route("/v1/deliveries", delete(deliveries::delete_deliveries))
+        .route("/v1/deliveries/read-all", post(deliveries::mark_all_deliveries_read))
         .route("/v1/deliveries/events", get(deliveries::delivery_events_sse))
         .route("/v1/deliveries/:id", get(deliveries::get_delivery))
         .route("/v1/deliveries/:id/read", post(deliveries::mark_delivery_read))
+        // Unified event stream (runs, schedules, deliveries, nodes)
+        .route("/v1/events", get(events::event_stream_sse))
         // Skill engine (callable skills)
         .route("/v1/skill-engine", get(skills::list_skill_engine))
         // Agents (audit / introspection)
         .route("/v1/agents", get(agents::list_agents))
         // Providers / Models
-        .route("/v1/models", get(providers::list_providers))
+        .route("/v1/models", get(openai_compat::list_models))
         .route("/v1/models/roles", get(providers::list_roles))
+        .route("/v1/models/refresh", post(providers::refresh_models))
         // Metrics
         .route("/v1/metrics", get(admin::metrics))
+        // Usage reports
+        .route("/v1/usage", get(usage::get_usage))
         // Admin
         .route("/v1/admin/info", get(admin::system_info))
         .route("/v1/admin/config", put(admin::save_config))
@@ -165,10 +206,22 @@ pub fn router(state: AppState) -> Router<AppState> {
             "/v1/import/openclaw/preview",
             post(admin::import_openclaw_preview),
         )
+        .route(
+            "/v1/import/openclaw/preview/stream",
+            post(admin::import_openclaw_preview_stream),
+        )
+        .route(
+            "/v1/import/openclaw/conflicts/:staging_id",
+            post(admin::import_openclaw_conflicts),
+        )
         .route(
             "/v1/import/openclaw/apply",
             post(admin::import_openclaw_apply_v2),
         )
+        .route(
+            "/v1/import/openclaw/rollback/:apply_id",
+            post(admin::import_openclaw_rollback),
+        )
         .route(
             "/v1/import/openclaw/test-ssh",
             post(admin::import_openclaw_test_ssh),
@@ -180,10 +233,14 @@ pub fn router(state: AppState) -> Router<AppState> {
         .route(
             "/v1/import/openclaw/staging/:id",
             delete(admin::import_openclaw_delete_staging),
-        )
+        );
+
+    let limits = &state.config.server.body_limits;
+    let protected = body_limit::limit_body(control, limits.control_bytes)
+        .merge(body_limit::limit_body(ingest, limits.ingest_bytes))
         // Apply API auth middleware to all protected routes.
         .route_layer(middleware::from_fn_with_state(
-            state,
+            state.clone(),
             auth::require_api_token,
         ));
 
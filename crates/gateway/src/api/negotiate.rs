@@ -0,0 +1,290 @@
+//! Content negotiation: JSON or MessagePack bodies.
+//!
+//! High-throughput clients (connectors, nodes) may send
+//! `Content-Type: application/msgpack` and ask for
+//! `Accept: application/msgpack`.  Everything else is JSON, so existing
+//! clients are unaffected.  Both encodings go through the same serde
+//! types; MessagePack structs are encoded as maps with field names, so a
+//! payload carries exactly the same data in either format.
+//!
+//! Handlers opt in by taking [`Negotiated<T>`] instead of `Json<T>` and
+//! replying with [`Format::respond`].  Error responses stay JSON.
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::api::error::ApiError;
+
+/// Canonical MessagePack media type.
+pub const MSGPACK: &str = "application/msgpack";
+
+/// Media types accepted as MessagePack (`x-` and `vnd.` are still common).
+const MSGPACK_ALIASES: &[&str] = &[MSGPACK, "application/x-msgpack", "application/vnd.msgpack"];
+
+/// A body encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+impl Format {
+    /// Encoding of the request body.  Anything that isn't MessagePack is
+    /// treated as JSON.
+    pub fn from_content_type(headers: &HeaderMap) -> Self {
+        match headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(ct) if is_msgpack(media_type(ct)) => Self::MsgPack,
+            _ => Self::Json,
+        }
+    }
+
+    /// Encoding the client asked for via `Accept`, or `None` when there is
+    /// no `Accept` header.  MessagePack wins only when it is listed with a
+    /// quality at least as high as any JSON-compatible entry.
+    pub fn from_accept(headers: &HeaderMap) -> Option<Self> {
+        let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+        let mut msgpack_q = 0.0f32;
+        let mut json_q = 0.0f32;
+        for entry in accept.split(',') {
+            let mut params = entry.split(';');
+            let mime = params.next().unwrap_or("").trim();
+            let q = params
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if is_msgpack(mime) {
+                msgpack_q = msgpack_q.max(q);
+            } else if matches!(mime, "application/json" | "application/*" | "*/*") {
+                json_q = json_q.max(q);
+            }
+        }
+        Some(if msgpack_q > 0.0 && msgpack_q >= json_q {
+            Self::MsgPack
+        } else {
+            Self::Json
+        })
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MsgPack => MSGPACK,
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::MsgPack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            // Named fields, so structs become maps exactly like in JSON.
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// A 200 response with `value` encoded in this format.
+    pub fn respond<T: Serialize>(self, value: &T) -> Response {
+        match self.encode(value) {
+            Ok(bytes) => (
+                [(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(self.content_type()),
+                )],
+                bytes,
+            )
+                .into_response(),
+            Err(e) => ApiError::internal(format!("failed to encode response: {e}")).into_response(),
+        }
+    }
+}
+
+/// `type/subtype` without parameters.
+fn media_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or("").trim()
+}
+
+fn is_msgpack(mime: &str) -> bool {
+    MSGPACK_ALIASES.iter().any(|m| mime.eq_ignore_ascii_case(m))
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Extractor
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// A request body decoded from JSON or MessagePack, plus the format the
+/// response should use (the `Accept`ed one, else the request's own).
+pub struct Negotiated<T> {
+    pub body: T,
+    pub reply: Format,
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for Negotiated<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let format = Format::from_content_type(req.headers());
+        let reply = Format::from_accept(req.headers()).unwrap_or(format);
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+        let body = format
+            .decode(&bytes)
+            .map_err(|e| ApiError::bad_request(format!("invalid request body: {e}")))?;
+        Ok(Self { body, reply })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::api::inbound::{InboundEnvelope, InboundResponse, TurnTelemetry};
+
+    fn headers(pairs: &[(header::HeaderName, &'static str)]) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        for (name, value) in pairs {
+            h.insert(name, HeaderValue::from_static(value));
+        }
+        h
+    }
+
+    #[test]
+    fn accept_prefers_msgpack_only_when_ranked_high_enough() {
+        let accept = |v| Format::from_accept(&headers(&[(header::ACCEPT, v)]));
+        assert_eq!(Format::from_accept(&HeaderMap::new()), None);
+        assert_eq!(accept("application/msgpack"), Some(Format::MsgPack));
+        assert_eq!(accept("application/msgpack, */*"), Some(Format::MsgPack));
+        assert_eq!(
+            accept("application/json, application/x-msgpack;q=0.5"),
+            Some(Format::Json)
+        );
+        assert_eq!(accept("text/html"), Some(Format::Json));
+        assert_eq!(
+            Format::from_content_type(&headers(&[(
+                header::CONTENT_TYPE,
+                "application/msgpack; charset=binary"
+            )])),
+            Format::MsgPack
+        );
+    }
+
+    async fn echo(req: Negotiated<serde_json::Value>) -> Response {
+        req.reply.respond(&req.body)
+    }
+
+    async fn post_as(body: Vec<u8>, content_type: &'static str) -> (StatusCode, String, Vec<u8>) {
+        let app = Router::new().route("/", post(echo));
+        let req = axum::http::Request::post("/")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        let status = resp.status();
+        let ct = resp.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, ct, bytes.to_vec())
+    }
+
+    fn inbound_payload() -> serde_json::Value {
+        serde_json::json!({
+            "channel": "discord",
+            "account_id": "bot1",
+            "peer_id": "discord:42",
+            "chat_type": "group",
+            "group_id": "g1",
+            "text": "hello",
+            "attachments": [{ "type": "image", "url": "https://example.com/a.png" }],
+            "chat_id": "c1",
+            "event_id": "discord:bot1:m1",
+            "mentions": [],
+        })
+    }
+
+    #[tokio::test]
+    async fn msgpack_round_trips_like_json() {
+        let payload = inbound_payload();
+
+        let (status, ct, json_body) =
+            post_as(serde_json::to_vec(&payload).unwrap(), "application/json").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ct, "application/json");
+
+        let (status, ct, msgpack_body) =
+            post_as(rmp_serde::to_vec_named(&payload).unwrap(), MSGPACK).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ct, MSGPACK);
+
+        let from_json: serde_json::Value = serde_json::from_slice(&json_body).unwrap();
+        let from_msgpack: serde_json::Value = rmp_serde::from_slice(&msgpack_body).unwrap();
+        assert_eq!(from_json, payload);
+        assert_eq!(from_msgpack, payload);
+    }
+
+    #[test]
+    fn inbound_types_decode_and_encode_identically() {
+        let payload = inbound_payload();
+        let from_json: InboundEnvelope = Format::Json
+            .decode(&serde_json::to_vec(&payload).unwrap())
+            .unwrap();
+        let from_msgpack: InboundEnvelope = Format::MsgPack
+            .decode(&rmp_serde::to_vec_named(&payload).unwrap())
+            .unwrap();
+        assert_eq!(format!("{from_json:?}"), format!("{from_msgpack:?}"));
+
+        let resp = InboundResponse {
+            accepted: true,
+            deduped: false,
+            session_key: "agent:a:discord:group:g1".into(),
+            session_id: "s1".into(),
+            actions: vec![],
+            policy: None,
+            telemetry: Some(TurnTelemetry {
+                input_tokens: 10,
+                output_tokens: 4,
+            }),
+        };
+        let json: serde_json::Value =
+            serde_json::from_slice(&Format::Json.encode(&resp).unwrap()).unwrap();
+        let msgpack: serde_json::Value =
+            rmp_serde::from_slice(&Format::MsgPack.encode(&resp).unwrap()).unwrap();
+        assert_eq!(json, msgpack);
+    }
+
+    #[tokio::test]
+    async fn undecodable_body_is_a_bad_request() {
+        let (status, ct, body) = post_as(vec![0xc1], MSGPACK).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(ct, "application/json");
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "invalid_args");
+    }
+}
@@ -1,8 +1,12 @@
 //! Node management REST endpoints.
 
-use axum::extract::State;
+use axum::extract::{Path, State};
+use axum::response::sse::{Event, Sse};
 use axum::response::{IntoResponse, Json};
+use futures_util::stream::Stream;
 
+use crate::api::error::ApiError;
+use crate::nodes::registry::NodeEvent;
 use crate::state::AppState;
 
 /// GET /v1/nodes — list connected nodes.
@@ -11,5 +15,54 @@ pub async fn list_nodes(State(state): State<AppState>) -> impl IntoResponse {
     Json(serde_json::json!({
         "nodes": *nodes,
         "count": nodes.len(),
+        "latency": state.tool_router.latency_snapshot(),
+        "outstanding": state.tool_router.outstanding_counts(),
     }))
 }
+
+/// POST /v1/nodes/:id/selftest — round-trip a `node.selftest` request
+/// through the tool router and report success and latency.
+pub async fn selftest_node(
+    State(state): State<AppState>,
+    Path(node_id): Path<String>,
+) -> impl IntoResponse {
+    if state.nodes.get_sink(&node_id).is_none() {
+        return ApiError::not_found(format!("node not connected: {node_id}")).into_response();
+    }
+    let report = state.tool_router.selftest(&node_id).await;
+    tracing::info!(
+        node_id = %node_id,
+        success = report.success,
+        latency_ms = report.latency_ms,
+        "node self-test"
+    );
+    Json(report).into_response()
+}
+
+/// GET /v1/nodes/events — SSE stream of node topology events.
+pub async fn node_events_sse(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let mut rx = state.nodes.subscribe();
+
+    let stream = async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let event_type = match &event {
+                        NodeEvent::Connected { .. } => "node.connected",
+                        NodeEvent::Disconnected { .. } => "node.disconnected",
+                        NodeEvent::Pruned { .. } => "node.pruned",
+                    };
+                    if let Ok(json) = serde_json::to_string(&event) {
+                        yield Ok(Event::default().event(event_type).data(json));
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            }
+        }
+    };
+
+    Sse::new(stream)
+}
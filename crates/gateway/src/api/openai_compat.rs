@@ -1,22 +1,38 @@
-//! OpenAI-compatible `/v1/chat/completions` endpoint.
+//! OpenAI-compatible `/v1/chat/completions` and `/v1/models` endpoints.
 //!
 //! Accepts the standard OpenAI `ChatCompletion` request format, translates it
 //! into the internal `run_turn` pipeline, and returns an OpenAI-shaped response
-//! (both streaming and non-streaming).
+//! (both streaming and non-streaming).  `/v1/models` lists the models the
+//! gateway can route to in the OpenAI models-list shape.
+//!
+//! When the request declares `tools` (or carries `role: "tool"` results),
+//! the client drives the tool loop: the conversation is forwarded to the
+//! provider as-is and any `tool_calls` are returned to the caller instead of
+//! being executed by the gateway.
 //!
 //! This enables drop-in compatibility with any client that speaks the OpenAI
 //! API (e.g. `openai` Python SDK, LangChain, Cursor, etc.).
 
+use std::collections::BTreeMap;
+
 use axum::extract::State;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Json};
 use futures_util::stream::Stream;
+use sa_domain::config::LlmConfig;
 use serde::{Deserialize, Serialize};
 
-use sa_providers::ResponseFormat;
+use sa_domain::stream::Usage;
+use sa_domain::tool::{Message, ToolCall, ToolChoice, ToolDefinition};
+use sa_providers::{ChatResponse, ResponseFormat};
 use sa_sessions::store::SessionOrigin;
 
+use crate::runtime::runs::{Run, RunEvent, RunStatus};
 use crate::runtime::session_lock::SessionBusy;
+use crate::runtime::turn::{complete_run, fail_run};
+use crate::runtime::{
+    build_assistant_tool_message, persist_transcript, resolve_provider, wait_for_rate_limit,
+};
 use crate::runtime::{run_turn, TurnEvent, TurnInput};
 use crate::state::AppState;
 
@@ -37,12 +53,108 @@ pub struct OpenAIChatRequest {
     /// Controls the response format (text, json_object, json_schema).
     #[serde(default)]
     pub response_format: Option<ResponseFormat>,
+    /// Streaming options (`include_usage`).
+    #[serde(default)]
+    pub stream_options: Option<StreamOptions>,
+    /// Client-declared tools.  When present, the client runs the tool loop.
+    #[serde(default)]
+    pub tools: Vec<OpenAITool>,
+    /// Which of the declared tools the model may call.
+    #[serde(default)]
+    pub tool_choice: Option<OpenAIToolChoice>,
+    /// Sampling seed for reproducible output.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Stop sequences: a single string or an array.
+    #[serde(default)]
+    pub stop: Option<StopSequences>,
+}
+
+/// OpenAI accepts `stop` as either `"###"` or `["###", "END"]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StopSequences {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StopSequences {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            StopSequences::One(s) => vec![s],
+            StopSequences::Many(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamOptions {
+    /// Emit a final chunk carrying token usage (with empty `choices`).
+    #[serde(default)]
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct OpenAIMessage {
     pub role: String,
-    pub content: String,
+    /// Null for assistant messages that only carry `tool_calls`.
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Tool calls previously made by the assistant.
+    #[serde(default)]
+    pub tool_calls: Vec<OpenAIToolCall>,
+    /// For `role: "tool"`: the call this message answers.
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAITool {
+    pub function: OpenAIFunctionDef,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAIFunctionDef {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema for the function's parameters.
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// `"none"`, `"auto"`, `"required"`, or `{"type":"function","function":{"name":...}}`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAIToolChoice {
+    Mode(String),
+    Function { function: OpenAIFunctionName },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAIFunctionName {
+    pub name: String,
+}
+
+/// A tool call as it appears in assistant messages (request and response).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAIToolCall {
+    pub id: String,
+    #[serde(default = "function_type")]
+    pub r#type: String,
+    pub function: OpenAIFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAIFunctionCall {
+    pub name: String,
+    /// JSON-encoded arguments, as OpenAI sends them.
+    #[serde(default)]
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".into()
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -70,7 +182,9 @@ struct OpenAIChoice {
 #[derive(Debug, Serialize)]
 struct OpenAIResponseMessage {
     role: &'static str,
-    content: String,
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<OpenAIToolCall>,
 }
 
 #[derive(Debug, Serialize)]
@@ -89,6 +203,8 @@ struct OpenAIChunk {
     created: i64,
     model: String,
     choices: Vec<OpenAIChunkChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<OpenAIUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -104,6 +220,104 @@ struct OpenAIChunkDelta {
     role: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<OpenAIToolCallDelta>>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIToolCallDelta {
+    index: u32,
+    id: String,
+    r#type: &'static str,
+    function: OpenAIFunctionDelta,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIFunctionDelta {
+    name: String,
+    /// JSON-encoded arguments, as OpenAI sends them.
+    arguments: String,
+}
+
+// ── Models list types ────────────────────────────────────────────────
+
+#[derive(Debug, Serialize)]
+struct OpenAIModelList {
+    object: &'static str,
+    data: Vec<OpenAIModel>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIModel {
+    id: String,
+    object: &'static str,
+    created: i64,
+    owned_by: String,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/models
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// List routable models as `{ "object": "list", "data": [...] }`.
+///
+/// Model ids use the `provider/model` spec accepted by `/v1/chat/completions`.
+/// Provider-discovered models come from the registry's TTL cache, so this
+/// does not hit provider APIs on every call.
+pub async fn list_models(State(state): State<AppState>) -> impl IntoResponse {
+    let providers = state.llm.list_providers();
+    let discovered = state.llm.discovered_models().await;
+    Json(model_list(&state.config.llm, &providers, &discovered))
+}
+
+/// Collect each available provider's models: its `default_model`, any
+/// model a role or fallback routes to, and any model discovered from the
+/// provider's API.  Sorted and de-duplicated.
+fn model_list(
+    llm: &LlmConfig,
+    available_providers: &[String],
+    discovered: &BTreeMap<String, Vec<String>>,
+) -> OpenAIModelList {
+    let role_specs = llm.roles.values().flat_map(|role| {
+        std::iter::once(role.model.as_str())
+            .chain(role.fallbacks.iter().map(|f| f.model.as_str()))
+    });
+    let default_specs = llm.providers.iter().filter_map(|p| {
+        p.default_model
+            .as_deref()
+            .map(|m| format!("{}/{m}", p.id))
+    });
+
+    let discovered_specs = discovered
+        .iter()
+        .flat_map(|(provider, models)| models.iter().map(move |m| format!("{provider}/{m}")));
+
+    let mut specs: Vec<String> = default_specs
+        .chain(role_specs.map(str::to_owned))
+        .chain(discovered_specs)
+        .filter(|spec| {
+            spec.split_once('/')
+                .is_some_and(|(provider, _)| available_providers.iter().any(|p| p == provider))
+        })
+        .collect();
+    specs.sort();
+    specs.dedup();
+
+    OpenAIModelList {
+        object: "list",
+        data: specs
+            .into_iter()
+            .map(|id| {
+                let owned_by = id.split_once('/').map_or("", |(p, _)| p).to_owned();
+                OpenAIModel {
+                    id,
+                    object: "model",
+                    created: 0,
+                    owned_by,
+                }
+            })
+            .collect(),
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -114,7 +328,9 @@ pub async fn chat_completions(
     State(state): State<AppState>,
     Json(body): Json<OpenAIChatRequest>,
 ) -> impl IntoResponse {
-    if body.stream {
+    if is_client_tool_request(&body) {
+        chat_completions_client_tools(state, body).await.into_response()
+    } else if body.stream {
         chat_completions_stream(state, body).await.into_response()
     } else {
         chat_completions_blocking(state, body).await.into_response()
@@ -156,7 +372,7 @@ async fn chat_completions_blocking(
             return openai_error_response(
                 axum::http::StatusCode::TOO_MANY_REQUESTS,
                 "rate_limit_error",
-                "Session is busy - a turn is already in progress",
+                "Session is busy - too many turns queued",
             )
             .into_response();
         }
@@ -171,6 +387,10 @@ async fn chat_completions_blocking(
         response_format: body.response_format,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: body.seed,
+        stop: body.stop.map(StopSequences::into_vec).unwrap_or_default(),
     };
 
     let (_run_id, mut rx) = run_turn(state, input);
@@ -224,7 +444,8 @@ async fn chat_completions_blocking(
             index: 0,
             message: OpenAIResponseMessage {
                 role: "assistant",
-                content: final_content,
+                content: Some(final_content),
+                tool_calls: Vec::new(),
             },
             finish_reason: "stop",
         }],
@@ -278,7 +499,12 @@ async fn chat_completions_stream(state: AppState, body: OpenAIChatRequest) -> im
         }
     };
 
-    let model = body.model.clone();
+    let chunk_meta = ChunkMeta {
+        id: format!("chatcmpl-{}", uuid::Uuid::new_v4()),
+        created: chrono::Utc::now().timestamp(),
+        model: body.model.clone(),
+        include_usage: body.stream_options.is_some_and(|o| o.include_usage),
+    };
     let input = TurnInput {
         session_key,
         session_id,
@@ -287,87 +513,141 @@ async fn chat_completions_stream(state: AppState, body: OpenAIChatRequest) -> im
         response_format: body.response_format,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: body.seed,
+        stop: body.stop.map(StopSequences::into_vec).unwrap_or_default(),
     };
 
     let (_run_id, rx) = run_turn(state, input);
 
-    let completion_id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
-    let created = chrono::Utc::now().timestamp();
-
-    let stream = make_openai_sse_stream(rx, permit, completion_id, created, model);
+    let stream = make_openai_sse_stream(rx, permit, chunk_meta);
 
     Sse::new(stream)
         .keep_alive(KeepAlive::default())
         .into_response()
 }
 
-fn make_openai_sse_stream(
-    mut rx: tokio::sync::mpsc::Receiver<TurnEvent>,
-    _permit: tokio::sync::OwnedSemaphorePermit,
-    completion_id: String,
+/// Fields shared by every chunk of one streamed completion.
+struct ChunkMeta {
+    id: String,
     created: i64,
     model: String,
-) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
-    async_stream::stream! {
-        // Send initial chunk with the assistant role.
-        let initial_chunk = OpenAIChunk {
-            id: completion_id.clone(),
+    include_usage: bool,
+}
+
+impl ChunkMeta {
+    fn chunk(&self, delta: OpenAIChunkDelta, finish_reason: Option<&'static str>) -> String {
+        self.encode(OpenAIChunk {
+            id: self.id.clone(),
             object: "chat.completion.chunk",
-            created,
-            model: model.clone(),
+            created: self.created,
+            model: self.model.clone(),
             choices: vec![OpenAIChunkChoice {
                 index: 0,
-                delta: OpenAIChunkDelta {
-                    role: Some("assistant"),
-                    content: None,
-                },
-                finish_reason: None,
+                delta,
+                finish_reason,
             }],
-        };
-        if let Ok(data) = serde_json::to_string(&initial_chunk) {
+            usage: None,
+        })
+    }
+
+    fn usage_chunk(&self, usage: OpenAIUsage) -> String {
+        self.encode(OpenAIChunk {
+            id: self.id.clone(),
+            object: "chat.completion.chunk",
+            created: self.created,
+            model: self.model.clone(),
+            choices: Vec::new(),
+            usage: Some(usage),
+        })
+    }
+
+    fn encode(&self, chunk: OpenAIChunk) -> String {
+        serde_json::to_string(&chunk).unwrap_or_default()
+    }
+}
+
+fn make_openai_sse_stream(
+    rx: tokio::sync::mpsc::Receiver<TurnEvent>,
+    _permit: crate::runtime::session_lock::SessionPermit,
+    meta: ChunkMeta,
+) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
+    async_stream::stream! {
+        let payloads = openai_stream_payloads(rx, meta);
+        futures_util::pin_mut!(payloads);
+        while let Some(data) = futures_util::StreamExt::next(&mut payloads).await {
             yield Ok(Event::default().data(data));
         }
 
+        // _permit is dropped here, releasing the session lock.
+    }
+}
+
+/// Bridge turn events to OpenAI `chat.completion.chunk` SSE payloads.
+///
+/// Emits a role chunk, then `content` deltas and `tool_calls` deltas (the
+/// gateway runs the tools itself, so these are informational and the
+/// stream still finishes with `finish_reason: "stop"`), a finish chunk,
+/// an optional usage chunk, and finally `[DONE]`.
+fn openai_stream_payloads(
+    mut rx: tokio::sync::mpsc::Receiver<TurnEvent>,
+    meta: ChunkMeta,
+) -> impl Stream<Item = String> {
+    async_stream::stream! {
+        // Send initial chunk with the assistant role.
+        yield meta.chunk(
+            OpenAIChunkDelta {
+                role: Some("assistant"),
+                content: None,
+                tool_calls: None,
+            },
+            None,
+        );
+
+        let mut tool_call_index = 0u32;
+        let mut usage = None;
         while let Some(event) = rx.recv().await {
             match event {
                 TurnEvent::AssistantDelta { text } => {
-                    let chunk = OpenAIChunk {
-                        id: completion_id.clone(),
-                        object: "chat.completion.chunk",
-                        created,
-                        model: model.clone(),
-                        choices: vec![OpenAIChunkChoice {
-                            index: 0,
-                            delta: OpenAIChunkDelta {
-                                role: None,
-                                content: Some(text),
-                            },
-                            finish_reason: None,
-                        }],
-                    };
-                    if let Ok(data) = serde_json::to_string(&chunk) {
-                        yield Ok(Event::default().data(data));
-                    }
+                    yield meta.chunk(
+                        OpenAIChunkDelta {
+                            role: None,
+                            content: Some(text),
+                            tool_calls: None,
+                        },
+                        None,
+                    );
+                }
+                TurnEvent::ToolCallEvent { call_id, tool_name, arguments } => {
+                    yield meta.chunk(
+                        OpenAIChunkDelta {
+                            role: None,
+                            content: None,
+                            tool_calls: Some(vec![OpenAIToolCallDelta {
+                                index: tool_call_index,
+                                id: call_id,
+                                r#type: "function",
+                                function: OpenAIFunctionDelta {
+                                    name: tool_name,
+                                    arguments: arguments.to_string(),
+                                },
+                            }]),
+                        },
+                        None,
+                    );
+                    tool_call_index += 1;
                 }
                 TurnEvent::Final { .. } | TurnEvent::Stopped { .. } => {
                     // Send the final chunk with finish_reason.
-                    let chunk = OpenAIChunk {
-                        id: completion_id.clone(),
-                        object: "chat.completion.chunk",
-                        created,
-                        model: model.clone(),
-                        choices: vec![OpenAIChunkChoice {
-                            index: 0,
-                            delta: OpenAIChunkDelta {
-                                role: None,
-                                content: None,
-                            },
-                            finish_reason: Some("stop"),
-                        }],
-                    };
-                    if let Ok(data) = serde_json::to_string(&chunk) {
-                        yield Ok(Event::default().data(data));
-                    }
+                    yield meta.chunk(
+                        OpenAIChunkDelta {
+                            role: None,
+                            content: None,
+                            tool_calls: None,
+                        },
+                        Some("stop"),
+                    );
                 }
                 TurnEvent::Error { message } => {
                     let err = serde_json::json!({
@@ -376,23 +656,432 @@ fn make_openai_sse_stream(
                             "type": "server_error",
                         }
                     });
-                    yield Ok(Event::default().data(err.to_string()));
+                    yield err.to_string();
+                }
+                TurnEvent::UsageEvent { input_tokens, output_tokens, total_tokens } => {
+                    usage = Some(OpenAIUsage {
+                        prompt_tokens: input_tokens,
+                        completion_tokens: output_tokens,
+                        total_tokens,
+                    });
                 }
-                // Tool events, usage, and thought events are not surfaced
-                // in OpenAI compat streaming — only text deltas and the
-                // final stop marker.
-                TurnEvent::ToolCallEvent { .. }
-                | TurnEvent::ToolResult { .. }
-                | TurnEvent::UsageEvent { .. }
-                | TurnEvent::Thought { .. } => {}
+                // Tool results and thoughts have no OpenAI chunk equivalent.
+                TurnEvent::ToolResult { .. } | TurnEvent::Thought { .. } => {}
+            }
+        }
+
+        if meta.include_usage {
+            if let Some(usage) = usage {
+                yield meta.usage_chunk(usage);
             }
         }
 
         // Terminate the stream with [DONE].
-        yield Ok(Event::default().data("[DONE]"));
+        yield "[DONE]".to_owned();
+    }
+}
 
-        // _permit is dropped here, releasing the session lock.
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Client-driven tool calls
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// The client runs its own tool loop when it declares tools or sends back
+/// tool results.
+fn is_client_tool_request(body: &OpenAIChatRequest) -> bool {
+    !body.tools.is_empty() || body.messages.iter().any(|m| m.role == "tool")
+}
+
+/// Forward the client's conversation and tools to the provider in a single
+/// call.  Tool calls are returned to the client rather than executed, and the
+/// client continues the conversation with `role: "tool"` messages.
+///
+/// Each call is still accounted like a turn: it is checked against and
+/// charged to the default quota, recorded as a run, and its new messages
+/// are persisted to an ephemeral session transcript.
+async fn chat_completions_client_tools(
+    state: AppState,
+    body: OpenAIChatRequest,
+) -> axum::response::Response {
+    // Pre-flight: reject early with 503 if no LLM providers are available.
+    if let Err(resp) = require_llm_provider(&state) {
+        return resp.into_response();
     }
+
+    let req = match client_chat_request(&body) {
+        Ok(req) => req,
+        Err(message) => {
+            return openai_error_response(
+                axum::http::StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                &message,
+            )
+            .into_response();
+        }
+    };
+
+    let provider = match resolve_provider(&state, Some(&body.model), None, None, None) {
+        Ok((provider, _)) => provider,
+        Err(e) => {
+            return openai_error_response(
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                "server_error",
+                &e.to_string(),
+            )
+            .into_response();
+        }
+    };
+
+    // Pre-flight: quota check (same limits as `run_turn`).
+    if let Err(exceeded) = state.quota_tracker.check_quota(None) {
+        return openai_error_response(
+            axum::http::StatusCode::TOO_MANY_REQUESTS,
+            "insufficient_quota",
+            &format!(
+                "daily {} quota exceeded: {:.2}/{:.2}",
+                exceeded.kind, exceeded.used, exceeded.limit,
+            ),
+        )
+        .into_response();
+    }
+
+    // ── Create run record ────────────────────────────────────────
+    let (session_key, session_id) = resolve_ephemeral_session(&state);
+    let mut run = Run::new(
+        session_key.clone(),
+        session_id.clone(),
+        &extract_last_user_message(&body.messages).unwrap_or_default(),
+    );
+    run.model = Some(body.model.clone());
+    run.seed = body.seed;
+    run.status = RunStatus::Running;
+    let run_id = state.run_store.insert(run);
+    state.run_store.emit(
+        &run_id,
+        RunEvent::RunStatus {
+            run_id,
+            status: RunStatus::Running,
+        },
+    );
+    persist_client_inputs(&state, &session_id, &body.messages).await;
+
+    let breakers = state.llm.breakers();
+    let rate_limits = state.llm.rate_limits();
+    let result = match wait_for_rate_limit(rate_limits, provider.provider_id()).await {
+        Ok(()) => provider.chat(&req).await,
+        Err(e) => Err(e),
+    };
+    let response = match result {
+        Ok(response) => {
+            breakers.record_success(provider.provider_id());
+            response
+        }
+        Err(e @ sa_domain::error::Error::RateLimited { .. }) => {
+            rate_limits.record_error(provider.provider_id(), &e);
+            fail_run(&state.run_store, run_id, e.to_string());
+            return openai_error_response(
+                axum::http::StatusCode::TOO_MANY_REQUESTS,
+                "rate_limit_error",
+                &e.to_string(),
+            )
+            .into_response();
+        }
+        Err(e) => {
+            breakers.record_failure(provider.provider_id());
+            fail_run(&state.run_store, run_id, e.to_string());
+            return openai_error_response(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                &e.to_string(),
+            )
+            .into_response();
+        }
+    };
+
+    // ── Finalize run (success) ───────────────────────────
+    let tool_calls = (!response.tool_calls.is_empty()).then(|| {
+        let tc_json = serde_json::to_string(&response.tool_calls).unwrap_or_default();
+        serde_json::json!({ "tool_calls": tc_json })
+    });
+    persist_transcript(
+        &state.transcripts,
+        &session_id,
+        "assistant",
+        &response.content,
+        tool_calls,
+        Some(state.sessions.search_index()),
+    )
+    .await;
+    let usage = response.usage.clone().unwrap_or(Usage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        total_tokens: 0,
+    });
+    state.sessions.record_usage(
+        &session_key,
+        usage.prompt_tokens as u64,
+        usage.completion_tokens as u64,
+    );
+    complete_run(
+        &state.run_store,
+        &state.quota_tracker,
+        &state.config.llm.pricing,
+        run_id,
+        &usage,
+        &response.content,
+    );
+
+    let meta = ChunkMeta {
+        id: format!("chatcmpl-{}", uuid::Uuid::new_v4()),
+        created: chrono::Utc::now().timestamp(),
+        model: body.model,
+        include_usage: body.stream_options.is_some_and(|o| o.include_usage),
+    };
+
+    if body.stream {
+        let events = client_tool_payloads(&meta, response)
+            .into_iter()
+            .map(|data| Ok::<_, std::convert::Infallible>(Event::default().data(data)));
+        Sse::new(futures_util::stream::iter(events))
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    } else {
+        Json(client_tool_response(&meta, response)).into_response()
+    }
+}
+
+/// Translate an OpenAI request into a provider request, mapping messages,
+/// tool calls and tool results onto the internal types.
+///
+/// `tool_choice` is applied twice: `"none"` sends no tools and a named
+/// function sends only that one, and `"required"` or a named function is
+/// also forwarded so the provider forces the call.
+fn client_chat_request(body: &OpenAIChatRequest) -> Result<sa_providers::ChatRequest, String> {
+    let messages = body
+        .messages
+        .iter()
+        .map(to_internal_message)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let declared = body.tools.iter().map(|t| ToolDefinition {
+        name: t.function.name.clone(),
+        description: t.function.description.clone().unwrap_or_default(),
+        parameters: t
+            .function
+            .parameters
+            .clone()
+            .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+    });
+    let tool_choice = match &body.tool_choice {
+        Some(OpenAIToolChoice::Mode(mode)) if mode == "required" => ToolChoice::Required,
+        Some(OpenAIToolChoice::Function { function }) => {
+            ToolChoice::Specific(function.name.clone())
+        }
+        _ => ToolChoice::Auto,
+    };
+    let tools: Vec<ToolDefinition> = match &body.tool_choice {
+        Some(OpenAIToolChoice::Mode(mode)) if mode == "none" => Vec::new(),
+        Some(OpenAIToolChoice::Function { function }) => {
+            let named: Vec<_> = declared.filter(|t| t.name == function.name).collect();
+            if named.is_empty() {
+                return Err(format!(
+                    "tool_choice names undeclared function: {}",
+                    function.name
+                ));
+            }
+            named
+        }
+        _ => declared.collect(),
+    };
+
+    // Extract the model name from "provider/model" format.
+    let model = body
+        .model
+        .split_once('/')
+        .map_or(body.model.as_str(), |(_, m)| m)
+        .to_owned();
+
+    Ok(sa_providers::ChatRequest {
+        messages,
+        tools,
+        temperature: body.temperature.map(|t| t as f32),
+        max_tokens: body.max_tokens,
+        response_format: body.response_format.clone().unwrap_or_default(),
+        model: Some(model),
+        seed: body.seed,
+        tool_choice,
+        stop: body
+            .stop
+            .clone()
+            .map(StopSequences::into_vec)
+            .unwrap_or_default(),
+    })
+}
+
+fn to_internal_message(msg: &OpenAIMessage) -> Result<Message, String> {
+    let content = msg.content.clone().unwrap_or_default();
+    match msg.role.as_str() {
+        "system" | "developer" => Ok(Message::system(content)),
+        "user" => Ok(Message::user(content)),
+        "assistant" if msg.tool_calls.is_empty() => Ok(Message::assistant(content)),
+        "assistant" => {
+            let calls = msg
+                .tool_calls
+                .iter()
+                .map(to_internal_tool_call)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(build_assistant_tool_message(&content, &calls))
+        }
+        "tool" => {
+            let call_id = msg
+                .tool_call_id
+                .as_deref()
+                .ok_or("tool message is missing tool_call_id")?;
+            Ok(Message::tool_result(call_id, content))
+        }
+        other => Err(format!("unsupported message role: {other}")),
+    }
+}
+
+fn to_internal_tool_call(call: &OpenAIToolCall) -> Result<ToolCall, String> {
+    let arguments = if call.function.arguments.trim().is_empty() {
+        serde_json::json!({})
+    } else {
+        serde_json::from_str(&call.function.arguments)
+            .map_err(|e| format!("invalid arguments for tool call {}: {e}", call.id))?
+    };
+    Ok(ToolCall {
+        call_id: call.id.clone(),
+        tool_name: call.function.name.clone(),
+        arguments,
+    })
+}
+
+fn to_openai_tool_call(call: ToolCall) -> OpenAIToolCall {
+    OpenAIToolCall {
+        id: call.call_id,
+        r#type: function_type(),
+        function: OpenAIFunctionCall {
+            name: call.tool_name,
+            arguments: call.arguments.to_string(),
+        },
+    }
+}
+
+fn client_finish_reason(response: &ChatResponse) -> &'static str {
+    if !response.tool_calls.is_empty() {
+        "tool_calls"
+    } else if matches!(response.finish_reason.as_deref(), Some("length" | "max_tokens")) {
+        "length"
+    } else {
+        "stop"
+    }
+}
+
+fn to_openai_usage(usage: Usage) -> OpenAIUsage {
+    OpenAIUsage {
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+    }
+}
+
+fn client_tool_response(meta: &ChunkMeta, response: ChatResponse) -> OpenAIChatResponse {
+    let finish_reason = client_finish_reason(&response);
+    let tool_calls: Vec<_> = response
+        .tool_calls
+        .into_iter()
+        .map(to_openai_tool_call)
+        .collect();
+    // OpenAI sends `content: null` alongside tool calls.
+    let content = if response.content.is_empty() && !tool_calls.is_empty() {
+        None
+    } else {
+        Some(response.content)
+    };
+
+    OpenAIChatResponse {
+        id: meta.id.clone(),
+        object: "chat.completion",
+        created: meta.created,
+        model: meta.model.clone(),
+        choices: vec![OpenAIChoice {
+            index: 0,
+            message: OpenAIResponseMessage {
+                role: "assistant",
+                content,
+                tool_calls,
+            },
+            finish_reason,
+        }],
+        usage: response.usage.map(to_openai_usage),
+    }
+}
+
+/// Replay a complete provider response as chunk payloads, ending in `[DONE]`.
+fn client_tool_payloads(meta: &ChunkMeta, response: ChatResponse) -> Vec<String> {
+    let finish_reason = client_finish_reason(&response);
+    let mut payloads = vec![meta.chunk(
+        OpenAIChunkDelta {
+            role: Some("assistant"),
+            content: None,
+            tool_calls: None,
+        },
+        None,
+    )];
+
+    if !response.content.is_empty() {
+        payloads.push(meta.chunk(
+            OpenAIChunkDelta {
+                role: None,
+                content: Some(response.content),
+                tool_calls: None,
+            },
+            None,
+        ));
+    }
+
+    if !response.tool_calls.is_empty() {
+        let deltas = response
+            .tool_calls
+            .into_iter()
+            .zip(0u32..)
+            .map(|(call, index)| OpenAIToolCallDelta {
+                index,
+                id: call.call_id,
+                r#type: "function",
+                function: OpenAIFunctionDelta {
+                    name: call.tool_name,
+                    arguments: call.arguments.to_string(),
+                },
+            })
+            .collect();
+        payloads.push(meta.chunk(
+            OpenAIChunkDelta {
+                role: None,
+                content: None,
+                tool_calls: Some(deltas),
+            },
+            None,
+        ));
+    }
+
+    payloads.push(meta.chunk(
+        OpenAIChunkDelta {
+            role: None,
+            content: None,
+            tool_calls: None,
+        },
+        Some(finish_reason),
+    ));
+
+    if meta.include_usage {
+        if let Some(usage) = response.usage {
+            payloads.push(meta.usage_chunk(to_openai_usage(usage)));
+        }
+    }
+
+    payloads.push("[DONE]".to_owned());
+    payloads
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -405,7 +1094,33 @@ fn extract_last_user_message(messages: &[OpenAIMessage]) -> Option<String> {
         .iter()
         .rev()
         .find(|m| m.role == "user")
-        .map(|m| m.content.clone())
+        .map(|m| m.content.clone().unwrap_or_default())
+}
+
+/// Persist the messages the client added since the last assistant turn
+/// (the new user message or the tool results it ran).  Earlier history was
+/// persisted by the call that produced it.
+async fn persist_client_inputs(state: &AppState, session_id: &str, messages: &[OpenAIMessage]) {
+    let start = messages
+        .iter()
+        .rposition(|m| m.role == "assistant")
+        .map_or(0, |i| i + 1);
+    for msg in &messages[start..] {
+        let metadata = match msg.role.as_str() {
+            "user" => None,
+            "tool" => Some(serde_json::json!({ "call_id": msg.tool_call_id })),
+            _ => continue,
+        };
+        persist_transcript(
+            &state.transcripts,
+            session_id,
+            &msg.role,
+            msg.content.as_deref().unwrap_or_default(),
+            metadata,
+            Some(state.sessions.search_index()),
+        )
+        .await;
+    }
 }
 
 /// Create an ephemeral session for OpenAI-compat requests.
@@ -460,3 +1175,371 @@ fn openai_error_response(
         })),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn llm_config() -> LlmConfig {
+        toml::from_str(
+            r#"
+            [roles.executor]
+            model = "openai/gpt-4o"
+            fallbacks = [{ model = "anthropic/claude-sonnet" }, { model = "gone/model-x" }]
+
+            [roles.planner]
+            model = "openai/gpt-4o"
+
+            [[providers]]
+            id = "openai"
+            kind = "openai_compat"
+            base_url = "https://api.openai.com/v1"
+            default_model = "gpt-4o-mini"
+
+            [[providers]]
+            id = "anthropic"
+            kind = "anthropic"
+            base_url = "https://api.anthropic.com"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn model_list_matches_openai_shape() {
+        let providers = vec!["anthropic".to_string(), "openai".to_string()];
+        let body = serde_json::to_value(model_list(&llm_config(), &providers, &BTreeMap::new())).unwrap();
+
+        assert_eq!(body["object"], "list");
+        let data = body["data"].as_array().unwrap();
+        for model in data {
+            let obj = model.as_object().unwrap();
+            let mut keys: Vec<_> = obj.keys().map(String::as_str).collect();
+            keys.sort();
+            assert_eq!(keys, ["created", "id", "object", "owned_by"]);
+            assert!(model["id"].is_string());
+            assert_eq!(model["object"], "model");
+            assert!(model["created"].is_i64());
+            assert!(model["owned_by"].is_string());
+        }
+
+        let ids: Vec<_> = data.iter().map(|m| m["id"].as_str().unwrap()).collect();
+        assert_eq!(
+            ids,
+            ["anthropic/claude-sonnet", "openai/gpt-4o", "openai/gpt-4o-mini"]
+        );
+        assert_eq!(data[0]["owned_by"], "anthropic");
+    }
+
+    fn meta(include_usage: bool) -> ChunkMeta {
+        ChunkMeta {
+            id: "chatcmpl-test".into(),
+            created: 1_700_000_000,
+            model: "openai/gpt-4o".into(),
+            include_usage,
+        }
+    }
+
+    async fn stream_payloads(events: Vec<TurnEvent>, include_usage: bool) -> Vec<String> {
+        use futures_util::StreamExt;
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        drop(tx);
+        openai_stream_payloads(rx, meta(include_usage))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn streamed_completion_emits_well_formed_chunks_ending_in_done() {
+        let payloads = stream_payloads(
+            vec![
+                TurnEvent::AssistantDelta { text: "Hel".into() },
+                TurnEvent::ToolCallEvent {
+                    call_id: "call_1".into(),
+                    tool_name: "web.search".into(),
+                    arguments: serde_json::json!({ "query": "rust" }),
+                },
+                TurnEvent::ToolResult {
+                    call_id: "call_1".into(),
+                    tool_name: "web.search".into(),
+                    content: "results".into(),
+                    is_error: false,
+                },
+                TurnEvent::AssistantDelta { text: "lo".into() },
+                TurnEvent::UsageEvent {
+                    input_tokens: 10,
+                    output_tokens: 2,
+                    total_tokens: 12,
+                },
+                TurnEvent::Final { content: "Hello".into() },
+            ],
+            false,
+        )
+        .await;
+
+        assert_eq!(payloads.last().map(String::as_str), Some("[DONE]"));
+        let chunks: Vec<serde_json::Value> = payloads[..payloads.len() - 1]
+            .iter()
+            .map(|p| serde_json::from_str(p).unwrap())
+            .collect();
+        // role, "Hel", tool call, "lo", finish — no usage chunk unless asked.
+        assert_eq!(chunks.len(), 5);
+        for chunk in &chunks {
+            assert_eq!(chunk["id"], "chatcmpl-test");
+            assert_eq!(chunk["object"], "chat.completion.chunk");
+            assert_eq!(chunk["created"], 1_700_000_000);
+            assert_eq!(chunk["model"], "openai/gpt-4o");
+            assert_eq!(chunk["choices"][0]["index"], 0);
+            assert!(chunk["choices"][0]["delta"].is_object());
+            assert!(chunk.get("usage").is_none());
+        }
+
+        assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+        assert_eq!(chunks[1]["choices"][0]["delta"]["content"], "Hel");
+        assert!(chunks[1]["choices"][0]["finish_reason"].is_null());
+
+        let call = &chunks[2]["choices"][0]["delta"]["tool_calls"][0];
+        assert_eq!(call["index"], 0);
+        assert_eq!(call["id"], "call_1");
+        assert_eq!(call["type"], "function");
+        assert_eq!(call["function"]["name"], "web.search");
+        assert_eq!(call["function"]["arguments"], r#"{"query":"rust"}"#);
+
+        assert_eq!(chunks[3]["choices"][0]["delta"]["content"], "lo");
+        assert_eq!(chunks[4]["choices"][0]["finish_reason"], "stop");
+        assert_eq!(chunks[4]["choices"][0]["delta"], serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn streamed_completion_includes_usage_when_requested() {
+        let payloads = stream_payloads(
+            vec![
+                TurnEvent::UsageEvent {
+                    input_tokens: 10,
+                    output_tokens: 2,
+                    total_tokens: 12,
+                },
+                TurnEvent::Final { content: String::new() },
+            ],
+            true,
+        )
+        .await;
+
+        assert_eq!(payloads.last().map(String::as_str), Some("[DONE]"));
+        let usage: serde_json::Value = serde_json::from_str(&payloads[payloads.len() - 2]).unwrap();
+        assert_eq!(usage["choices"], serde_json::json!([]));
+        assert_eq!(usage["usage"]["prompt_tokens"], 10);
+        assert_eq!(usage["usage"]["completion_tokens"], 2);
+        assert_eq!(usage["usage"]["total_tokens"], 12);
+    }
+
+    fn chat_request(body: serde_json::Value) -> OpenAIChatRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn request_declaring_tool_maps_to_tool_definitions() {
+        let body = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "Weather in Paris?" }],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "get_weather",
+                    "description": "Current weather for a city",
+                    "parameters": {
+                        "type": "object",
+                        "properties": { "city": { "type": "string" } },
+                    },
+                },
+            }],
+            "tool_choice": "auto",
+        }));
+        assert!(is_client_tool_request(&body));
+
+        let req = client_chat_request(&body).unwrap();
+        assert_eq!(req.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(req.messages.len(), 1);
+        assert_eq!(req.tools.len(), 1);
+        assert_eq!(req.tools[0].name, "get_weather");
+        assert_eq!(req.tools[0].description, "Current weather for a city");
+        assert_eq!(req.tools[0].parameters["properties"]["city"]["type"], "string");
+
+        let none = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "tools": [{ "type": "function", "function": { "name": "get_weather" } }],
+            "tool_choice": "none",
+        }));
+        assert!(client_chat_request(&none).unwrap().tools.is_empty());
+
+        let required = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "tools": [{ "type": "function", "function": { "name": "get_weather" } }],
+            "tool_choice": "required",
+        }));
+        let req = client_chat_request(&required).unwrap();
+        assert_eq!(req.tool_choice, ToolChoice::Required);
+        assert_eq!(req.tools.len(), 1);
+
+        let named = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "tools": [
+                { "type": "function", "function": { "name": "get_weather" } },
+                { "type": "function", "function": { "name": "send_email" } },
+            ],
+            "tool_choice": { "type": "function", "function": { "name": "send_email" } },
+        }));
+        let req = client_chat_request(&named).unwrap();
+        assert_eq!(req.tool_choice, ToolChoice::Specific("send_email".into()));
+        assert_eq!(req.tools.len(), 1);
+
+        let undeclared = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "tools": [{ "type": "function", "function": { "name": "get_weather" } }],
+            "tool_choice": { "type": "function", "function": { "name": "send_email" } },
+        }));
+        assert!(client_chat_request(&undeclared).is_err());
+    }
+
+    #[test]
+    fn stop_accepts_a_string_or_an_array() {
+        let one = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "tools": [{ "type": "function", "function": { "name": "get_weather" } }],
+            "stop": "###",
+        }));
+        assert_eq!(client_chat_request(&one).unwrap().stop, ["###"]);
+
+        let many = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "user", "content": "hi" }],
+            "stop": ["END", "\n\n"],
+        }));
+        assert_eq!(
+            many.stop.map(StopSequences::into_vec).unwrap_or_default(),
+            ["END", "\n\n"]
+        );
+    }
+
+    fn tool_call_response() -> ChatResponse {
+        ChatResponse {
+            content: String::new(),
+            tool_calls: vec![ToolCall {
+                call_id: "call_1".into(),
+                tool_name: "get_weather".into(),
+                arguments: serde_json::json!({ "city": "Paris" }),
+            }],
+            usage: None,
+            model: "gpt-4o".into(),
+            finish_reason: Some("tool_calls".into()),
+        }
+    }
+
+    #[test]
+    fn response_surfaces_tool_calls() {
+        let body =
+            serde_json::to_value(client_tool_response(&meta(false), tool_call_response())).unwrap();
+
+        let choice = &body["choices"][0];
+        assert_eq!(choice["finish_reason"], "tool_calls");
+        assert_eq!(choice["message"]["role"], "assistant");
+        assert!(choice["message"]["content"].is_null());
+        let call = &choice["message"]["tool_calls"][0];
+        assert_eq!(call["id"], "call_1");
+        assert_eq!(call["type"], "function");
+        assert_eq!(call["function"]["name"], "get_weather");
+        assert_eq!(call["function"]["arguments"], r#"{"city":"Paris"}"#);
+
+        let payloads = client_tool_payloads(&meta(false), tool_call_response());
+        assert_eq!(payloads.last().map(String::as_str), Some("[DONE]"));
+        let chunks: Vec<serde_json::Value> = payloads[..payloads.len() - 1]
+            .iter()
+            .map(|p| serde_json::from_str(p).unwrap())
+            .collect();
+        // role, tool call, finish.
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[1]["choices"][0]["delta"]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(chunks[2]["choices"][0]["finish_reason"], "tool_calls");
+    }
+
+    #[test]
+    fn follow_up_tool_message_continues_conversation() {
+        use sa_domain::tool::{ContentPart, MessageContent, Role};
+
+        let body = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Weather in Paris?" },
+                {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" },
+                    }],
+                },
+                { "role": "tool", "tool_call_id": "call_1", "content": "18C, sunny" },
+            ],
+        }));
+        assert!(is_client_tool_request(&body));
+
+        let req = client_chat_request(&body).unwrap();
+        let roles: Vec<_> = req.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [Role::System, Role::User, Role::Assistant, Role::Tool]);
+
+        let MessageContent::Parts(parts) = &req.messages[2].content else {
+            panic!("assistant tool message should use parts");
+        };
+        assert!(matches!(
+            &parts[..],
+            [ContentPart::ToolUse { id, name, input }]
+                if id == "call_1" && name == "get_weather" && input["city"] == "Paris"
+        ));
+
+        let MessageContent::Parts(parts) = &req.messages[3].content else {
+            panic!("tool result should use parts");
+        };
+        assert!(matches!(
+            &parts[..],
+            [ContentPart::ToolResult { tool_use_id, content, is_error: false }]
+                if tool_use_id == "call_1" && content == "18C, sunny"
+        ));
+    }
+
+    #[test]
+    fn tool_message_without_call_id_is_rejected() {
+        let body = chat_request(serde_json::json!({
+            "model": "openai/gpt-4o",
+            "messages": [{ "role": "tool", "content": "orphan" }],
+        }));
+        assert!(client_chat_request(&body).is_err());
+    }
+
+    #[test]
+    fn model_list_skips_unavailable_providers() {
+        let body = model_list(&llm_config(), &["anthropic".to_string()], &BTreeMap::new());
+        let ids: Vec<_> = body.data.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["anthropic/claude-sonnet"]);
+    }
+
+    #[test]
+    fn model_list_merges_discovered_models() {
+        let discovered = BTreeMap::from([(
+            "anthropic".to_string(),
+            vec!["claude-haiku".to_string(), "claude-sonnet".to_string()],
+        )]);
+        let body = model_list(&llm_config(), &["anthropic".to_string()], &discovered);
+        let ids: Vec<_> = body.data.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["anthropic/claude-haiku", "anthropic/claude-sonnet"]);
+    }
+}
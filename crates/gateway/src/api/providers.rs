@@ -3,18 +3,19 @@ use axum::response::{IntoResponse, Json};
 
 use crate::state::AppState;
 
-pub async fn list_providers(State(state): State<AppState>) -> impl IntoResponse {
-    let providers = state.llm.list_providers();
+pub async fn list_roles(State(state): State<AppState>) -> impl IntoResponse {
+    let roles = state.llm.list_roles();
     Json(serde_json::json!({
-        "providers": providers,
-        "count": providers.len(),
+        "roles": roles,
     }))
 }
 
-pub async fn list_roles(State(state): State<AppState>) -> impl IntoResponse {
-    let roles = state.llm.list_roles();
+/// POST /v1/models/refresh — drop the model-discovery cache and re-query
+/// every provider's model list.
+pub async fn refresh_models(State(state): State<AppState>) -> impl IntoResponse {
+    let models = state.llm.refresh_models().await;
     Json(serde_json::json!({
-        "roles": roles,
+        "providers": models,
     }))
 }
 
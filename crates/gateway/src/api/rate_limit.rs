@@ -0,0 +1,141 @@
+//! Per-IP rate limiting (token bucket via `tower_governor`).
+//!
+//! Rejections use the gateway's standard [`ApiError`] body and carry a
+//! `Retry-After` header so clients can back off without guessing.
+
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use sa_domain::config::RateLimitConfig;
+use tower_governor::governor::GovernorConfigBuilder;
+use tower_governor::{GovernorError, GovernorLayer};
+
+use crate::api::error::ApiError;
+
+/// Wrap `router` in the per-IP rate limiter described by `rl`.
+///
+/// Keys on the peer address, so the server must be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+///
+/// # Panics
+///
+/// If `requests_per_second` or `burst_size` is zero.
+pub fn with_rate_limit<S>(router: Router<S>, rl: &RateLimitConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    // Same interval `per_second()` would set: one token per period.
+    let refill = Duration::from_secs(rl.requests_per_second);
+    let gov_config = GovernorConfigBuilder::default()
+        .period(refill)
+        .burst_size(rl.burst_size)
+        .error_handler(move |err| rate_limit_response(err, refill))
+        .finish()
+        .expect("rate_limit: requests_per_second and burst_size must be > 0");
+
+    router.layer(GovernorLayer {
+        config: std::sync::Arc::new(gov_config),
+    })
+}
+
+/// Seconds a throttled client should wait.  The governor reports whole
+/// seconds rounded down (often 0), so never hint less than one refill
+/// period — by then at least one token is back in the bucket.
+fn retry_after_secs(wait_time: u64, refill: Duration) -> u64 {
+    let refill_secs = refill.as_secs() + u64::from(refill.subsec_nanos() > 0);
+    wait_time.max(refill_secs).max(1)
+}
+
+fn rate_limit_response(err: GovernorError, refill: Duration) -> Response<Body> {
+    match err {
+        GovernorError::TooManyRequests { wait_time, headers } => {
+            let retry_after = retry_after_secs(wait_time, refill);
+            let mut resp = ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("rate limit exceeded; retry after {retry_after}s"),
+            )
+            .with_details(serde_json::json!({ "retry_after_secs": retry_after }))
+            .into_response();
+            if let Some(headers) = headers {
+                resp.headers_mut().extend(headers);
+            }
+            resp.headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            resp
+        }
+        GovernorError::UnableToExtractKey => {
+            ApiError::internal("rate limiter could not determine the client address")
+                .into_response()
+        }
+        GovernorError::Other { code, msg, .. } => {
+            ApiError::new(code, msg.unwrap_or_else(|| "rate limiter error".into())).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::ConnectInfo;
+    use axum::http::Request;
+    use axum::routing::get;
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    fn request() -> Request<Body> {
+        let mut req = Request::get("/").body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+        req
+    }
+
+    #[tokio::test]
+    async fn exceeding_burst_returns_429_with_retry_after() {
+        let app = with_rate_limit(
+            Router::new().route("/", get(|| async { "ok" })),
+            &RateLimitConfig {
+                requests_per_second: 30,
+                burst_size: 2,
+            },
+        );
+
+        for _ in 0..2 {
+            let resp = app.clone().oneshot(request()).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        let resp = app.oneshot(request()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = resp.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .expect("Retry-After must be numeric");
+        assert!(
+            (1..=30).contains(&retry_after),
+            "retry_after = {retry_after}"
+        );
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["error"]["code"], "rate_limited");
+        assert_eq!(json["error"]["details"]["retry_after_secs"], retry_after);
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("rate limit exceeded"));
+    }
+
+    #[test]
+    fn retry_after_never_below_one_refill_period() {
+        assert_eq!(retry_after_secs(0, Duration::from_secs(5)), 5);
+        assert_eq!(retry_after_secs(9, Duration::from_secs(5)), 9);
+        assert_eq!(retry_after_secs(0, Duration::from_millis(200)), 1);
+        assert_eq!(retry_after_secs(0, Duration::from_millis(1500)), 2);
+    }
+}
@@ -4,13 +4,16 @@
 //! - `PUT  /v1/router/config`    — update profile, tiers (stub — not yet implemented)
 //! - `POST /v1/router/classify`  — test: send a prompt, get back tier + scores + model
 //! - `GET  /v1/router/decisions` — last N routing decisions
+//! - `GET  /v1/router/breakers`   — per-provider circuit breaker state
+//! - `POST /v1/router/breakers/:provider/reset` — force-close a breaker
 
-use axum::extract::{Json, Query, State};
+use axum::extract::{Json, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::api::error::api_error;
 use crate::state::AppState;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -60,11 +63,6 @@ fn default_limit() -> usize {
 // Helper
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-/// Build a standardized JSON error response: `{ "error": "<message>" }`.
-fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
-    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
-}
-
 /// Serialize a serde-serializable value to its lowercase JSON string
 /// representation (e.g. `RoutingProfile::Auto` -> `"auto"`).
 fn ser_lowercase<T: Serialize>(value: &T) -> String {
@@ -227,3 +225,32 @@ pub async fn decisions(
         "count": items.len(),
     }))
 }
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/router/breakers
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub async fn breakers(State(state): State<AppState>) -> impl IntoResponse {
+    let items = state.llm.breaker_statuses();
+    Json(serde_json::json!({
+        "breakers": items,
+        "count": items.len(),
+    }))
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// POST /v1/router/breakers/:provider/reset
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+pub async fn reset_breaker(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+) -> Response {
+    if !state.llm.reset_breaker(&provider) {
+        return api_error(
+            StatusCode::NOT_FOUND,
+            format!("unknown provider: {provider}"),
+        );
+    }
+    Json(state.llm.breakers().status(&provider)).into_response()
+}
@@ -11,6 +11,7 @@ use axum::response::{IntoResponse, Json};
 use futures_util::stream::Stream;
 use serde::Deserialize;
 
+use crate::api::error::ApiError;
 use crate::runtime::runs::RunStatus;
 use crate::state::AppState;
 
@@ -95,11 +96,7 @@ pub async fn get_run(
 ) -> impl IntoResponse {
     match state.run_store.get(&run_id) {
         Some(run) => Json(serde_json::json!(run)).into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "run not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("run not found").into_response(),
     }
 }
 
@@ -118,11 +115,7 @@ pub async fn get_run_nodes(
             "count": run.nodes.len(),
         }))
         .into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "run not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("run not found").into_response(),
     }
 }
 
@@ -138,11 +131,7 @@ pub async fn run_events_sse(
     let run = state.run_store.get(&run_id);
     if run.is_none() {
         let stream = futures_util::stream::once(async {
-            Ok::<_, std::convert::Infallible>(
-                Event::default()
-                    .event("error")
-                    .data(r#"{"error":"run not found"}"#),
-            )
+            Ok::<_, std::convert::Infallible>(ApiError::not_found("run not found").sse_event())
         });
         return Sse::new(stream)
             .keep_alive(KeepAlive::default())
@@ -164,40 +153,56 @@ pub async fn run_events_sse(
         }
     }
 
-    // Subscribe to live events.
-    let rx = state.run_store.subscribe(&run_id);
+    // Replay what the run has emitted so far, then follow live events.
+    let (backlog, rx) = state.run_store.subscribe(&run_id);
 
-    let stream = make_run_event_stream(rx);
+    let stream = make_run_event_stream(backlog, rx);
 
     Sse::new(stream)
         .keep_alive(KeepAlive::default())
         .into_response()
 }
 
+fn run_event_sse(event: &crate::runtime::runs::RunEvent) -> Event {
+    let event_type = match event {
+        crate::runtime::runs::RunEvent::RunStatus { .. } => "run.status",
+        crate::runtime::runs::RunEvent::NodeStarted { .. } => "node.started",
+        crate::runtime::runs::RunEvent::NodeCompleted { .. } => "node.completed",
+        crate::runtime::runs::RunEvent::NodeFailed { .. } => "node.failed",
+        crate::runtime::runs::RunEvent::Log { .. } => "log",
+        crate::runtime::runs::RunEvent::Usage { .. } => "usage",
+        crate::runtime::runs::RunEvent::ExecApprovalRequired { .. } => "exec.approval_required",
+    };
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Event::default().event(event_type).data(data)
+}
+
+fn is_terminal_event(event: &crate::runtime::runs::RunEvent) -> bool {
+    matches!(
+        event,
+        crate::runtime::runs::RunEvent::RunStatus { status, .. } if status.is_terminal()
+    )
+}
+
 fn make_run_event_stream(
+    backlog: Vec<crate::runtime::runs::RunEvent>,
     mut rx: tokio::sync::broadcast::Receiver<crate::runtime::runs::RunEvent>,
 ) -> impl Stream<Item = Result<Event, std::convert::Infallible>> {
     async_stream::stream! {
+        for event in &backlog {
+            yield Ok(run_event_sse(event));
+            if is_terminal_event(event) {
+                return;
+            }
+        }
         loop {
             match rx.recv().await {
                 Ok(event) => {
-                    let event_type = match &event {
-                        crate::runtime::runs::RunEvent::RunStatus { .. } => "run.status",
-                        crate::runtime::runs::RunEvent::NodeStarted { .. } => "node.started",
-                        crate::runtime::runs::RunEvent::NodeCompleted { .. } => "node.completed",
-                        crate::runtime::runs::RunEvent::NodeFailed { .. } => "node.failed",
-                        crate::runtime::runs::RunEvent::Log { .. } => "log",
-                        crate::runtime::runs::RunEvent::Usage { .. } => "usage",
-                        crate::runtime::runs::RunEvent::ExecApprovalRequired { .. } => "exec.approval_required",
-                    };
-                    let data = serde_json::to_string(&event).unwrap_or_default();
-                    yield Ok(Event::default().event(event_type).data(data));
+                    yield Ok(run_event_sse(&event));
 
                     // Close stream after terminal status.
-                    if let crate::runtime::runs::RunEvent::RunStatus { status, .. } = &event {
-                        if status.is_terminal() {
-                            break;
-                        }
+                    if is_terminal_event(&event) {
+                        break;
                     }
                 }
                 Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
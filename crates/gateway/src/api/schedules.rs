@@ -7,17 +7,14 @@ use axum::response::{IntoResponse, Json, Response};
 use futures_util::stream::Stream;
 use serde::Deserialize;
 
+use crate::api::error::api_error;
 use crate::runtime::schedules::{
-    cron_next_n_tz, parse_tz, validate_cron, validate_timezone, validate_url, DeliveryTarget,
-    DigestMode, FetchConfig, MissedPolicy, ScheduleEvent,
+    cron_next_n_tz, parse_tz, validate_cron, validate_timezone, validate_trigger_params,
+    validate_url, DeliveryTarget, DigestMode, FetchConfig, MissedPolicy, ScheduleEvent,
+    TriggerParam,
 };
 use crate::state::AppState;
 
-/// Build a standardized JSON error response: `{ "error": "<message>" }`.
-fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
-    (status, Json(serde_json::json!({ "error": message.into() }))).into_response()
-}
-
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // GET /v1/schedules
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -90,6 +87,8 @@ pub struct CreateScheduleRequest {
     #[serde(default)]
     pub webhook_secret: Option<String>,
     #[serde(default)]
+    pub trigger_params: Vec<TriggerParam>,
+    #[serde(default)]
     pub routing_profile: Option<String>,
 }
 
@@ -145,6 +144,10 @@ pub async fn create_schedule(
         }
     }
 
+    if let Err(msg) = validate_trigger_params(&req.trigger_params) {
+        return api_error(StatusCode::BAD_REQUEST, msg);
+    }
+
     // Validate routing_profile if set
     if let Some(ref rp) = req.routing_profile {
         if !matches!(rp.as_str(), "auto" | "eco" | "premium" | "free" | "reasoning") {
@@ -176,6 +179,7 @@ pub async fn create_schedule(
         fetch_config: req.fetch_config,
         max_catchup_runs: req.max_catchup_runs,
         webhook_secret: req.webhook_secret,
+        trigger_params: req.trigger_params,
         routing_profile: req.routing_profile,
         source_states: std::collections::HashMap::new(),
         last_error: None,
@@ -217,6 +221,7 @@ pub struct UpdateScheduleRequest {
     pub fetch_config: Option<FetchConfig>,
     pub max_catchup_runs: Option<usize>,
     pub webhook_secret: Option<Option<String>>,
+    pub trigger_params: Option<Vec<TriggerParam>>,
     pub routing_profile: Option<Option<String>>,
 }
 
@@ -266,6 +271,12 @@ pub async fn update_schedule(
         }
     }
 
+    if let Some(ref params) = req.trigger_params {
+        if let Err(msg) = validate_trigger_params(params) {
+            return api_error(StatusCode::BAD_REQUEST, msg);
+        }
+    }
+
     // Validate routing_profile if provided
     if let Some(Some(ref rp)) = req.routing_profile {
         if !matches!(rp.as_str(), "auto" | "eco" | "premium" | "free" | "reasoning") {
@@ -324,6 +335,9 @@ pub async fn update_schedule(
             if let Some(ws) = req.webhook_secret {
                 s.webhook_secret = ws;
             }
+            if let Some(tp) = req.trigger_params {
+                s.trigger_params = tp;
+            }
             if let Some(rp) = req.routing_profile {
                 s.routing_profile = rp;
             }
@@ -342,11 +356,11 @@ pub async fn update_schedule(
 pub async fn delete_schedule(
     State(state): State<AppState>,
     Path(id): Path<uuid::Uuid>,
-) -> impl IntoResponse {
+) -> Response {
     if state.schedule_store.delete(&id).await {
-        Json(serde_json::json!({ "deleted": true }))
+        Json(serde_json::json!({ "deleted": true })).into_response()
     } else {
-        Json(serde_json::json!({ "deleted": false, "error": "schedule not found" }))
+        api_error(StatusCode::NOT_FOUND, "schedule not found")
     }
 }
 
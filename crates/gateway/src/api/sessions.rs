@@ -6,8 +6,11 @@
 //! Path-based endpoints for individual sessions:
 //!   GET  /v1/sessions/:key            — session metadata
 //!   GET  /v1/sessions/:key/transcript  — transcript lines (with offset/limit)
+//!   GET  /v1/sessions/:key/compactions — compaction history
 //!   POST /v1/sessions/:key/reset       — manual reset
 //!   POST /v1/sessions/:key/stop        — cancel a running turn
+//!   PUT  /v1/sessions/:key/tags        — replace user tags
+//!   PUT  /v1/sessions/:key/model       — pin or unpin the session model
 
 use axum::extract::{Path, Query, State};
 use axum::http::{header, StatusCode};
@@ -19,6 +22,7 @@ use sa_domain::config::InboundMetadata;
 use sa_sessions::store::{SessionEntry, SessionOrigin};
 use sa_sessions::transcript::TranscriptLine;
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -127,7 +131,7 @@ pub async fn resolve_session(
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 /// Query parameters for filtering the session list.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct SessionListQuery {
     /// Filter by connector channel (e.g. `"discord"`, `"telegram"`).
     #[serde(default)]
@@ -144,6 +148,12 @@ pub struct SessionListQuery {
     /// Only include sessions updated at or before this timestamp (RFC 3339).
     #[serde(default)]
     pub until: Option<DateTime<Utc>>,
+    /// Only include sessions active within the last N seconds.
+    #[serde(default)]
+    pub active_within_secs: Option<u64>,
+    /// Only include sessions carrying this user tag.
+    #[serde(default)]
+    pub tag: Option<String>,
     /// Maximum number of sessions to return (default 100, max 500).
     #[serde(default)]
     pub limit: Option<usize>,
@@ -156,7 +166,7 @@ pub struct SessionListQuery {
     pub q: Option<String>,
 }
 
-/// List active sessions with optional filtering and pagination.
+/// List sessions with optional filtering, newest-active first, paginated.
 pub async fn list_sessions(
     State(state): State<AppState>,
     Query(query): Query<SessionListQuery>,
@@ -179,51 +189,16 @@ pub async fn list_sessions(
                 .collect()
         });
 
-    // Apply filters.
-    let filtered: Vec<_> = all_sessions
-        .into_iter()
-        .filter(|s| {
-            // If search was requested, only include sessions that matched.
-            if let Some(ref map) = search_map {
-                if !map.contains_key(&s.session_id) {
-                    return false;
-                }
-            }
-            if let Some(ref ch) = query.channel {
-                if s.origin.channel.as_deref() != Some(ch.as_str()) {
-                    return false;
-                }
-            }
-            if let Some(ref peer) = query.peer {
-                if s.origin.peer.as_deref() != Some(peer.as_str()) {
-                    return false;
-                }
-            }
-            if let Some(ref agent_id) = query.agent_id {
-                let prefix = format!("agent:{agent_id}:");
-                if !s.session_key.starts_with(&prefix) {
-                    return false;
-                }
-            }
-            if let Some(since) = query.since {
-                if s.updated_at < since {
-                    return false;
-                }
-            }
-            if let Some(until) = query.until {
-                if s.updated_at > until {
-                    return false;
-                }
-            }
-            true
-        })
-        .collect();
-
-    let total = filtered.len();
     let offset = query.offset.unwrap_or(0);
     let limit = query.limit.unwrap_or(100).min(500);
-
-    let page: Vec<_> = filtered.into_iter().skip(offset).take(limit).collect();
+    let (page, total) = select_sessions(
+        all_sessions,
+        &query,
+        search_map.as_ref(),
+        Utc::now(),
+        limit,
+        offset,
+    );
 
     // Enrich response with search metadata when a query was provided.
     let sessions_json: Vec<serde_json::Value> = page
@@ -250,6 +225,55 @@ pub async fn list_sessions(
     }))
 }
 
+/// Apply the list filters, order newest-active first (ties broken by key so
+/// pages are stable), and cut the `offset`/`limit` page.  Returns the page
+/// and the total number of matching sessions.
+fn select_sessions(
+    sessions: Vec<SessionEntry>,
+    query: &SessionListQuery,
+    search_map: Option<&std::collections::HashMap<String, (usize, String)>>,
+    now: DateTime<Utc>,
+    limit: usize,
+    offset: usize,
+) -> (Vec<SessionEntry>, usize) {
+    let active_since = query
+        .active_within_secs
+        .map(|secs| now - chrono::Duration::seconds(secs.min(i64::MAX as u64) as i64));
+    let agent_prefix = query.agent_id.as_ref().map(|id| format!("agent:{id}:"));
+
+    let mut filtered: Vec<SessionEntry> = sessions
+        .into_iter()
+        .filter(|s| {
+            // If search was requested, only include sessions that matched.
+            search_map.is_none_or(|map| map.contains_key(&s.session_id))
+                && query
+                    .channel
+                    .as_ref()
+                    .is_none_or(|ch| s.origin.channel.as_deref() == Some(ch.as_str()))
+                && query
+                    .peer
+                    .as_ref()
+                    .is_none_or(|peer| s.origin.peer.as_deref() == Some(peer.as_str()))
+                && agent_prefix
+                    .as_ref()
+                    .is_none_or(|prefix| s.session_key.starts_with(prefix.as_str()))
+                && query.since.is_none_or(|since| s.updated_at >= since)
+                && query.until.is_none_or(|until| s.updated_at <= until)
+                && active_since.is_none_or(|cutoff| s.updated_at >= cutoff)
+                && query.tag.as_ref().is_none_or(|tag| s.tags.contains(tag))
+        })
+        .collect();
+    filtered.sort_by(|a, b| {
+        b.updated_at
+            .cmp(&a.updated_at)
+            .then_with(|| a.session_key.cmp(&b.session_key))
+    });
+
+    let total = filtered.len();
+    let page = filtered.into_iter().skip(offset).take(limit).collect();
+    (page, total)
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/sessions/reset (body-based, kept for backwards compat)
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -284,6 +308,8 @@ pub async fn get_session(
             "origin": entry.origin,
             "model": entry.model,
             "sm_session_id": entry.sm_session_id,
+            "tags": entry.tags,
+            "pinned_model": entry.pinned_model,
             "running": state.cancel_map.is_running(&key),
             "tokens": {
                 "input": entry.input_tokens,
@@ -293,11 +319,7 @@ pub async fn get_session(
             }
         }))
         .into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "session not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("session not found").into_response(),
     }
 }
 
@@ -324,11 +346,7 @@ pub async fn get_transcript(
     let entry = match state.sessions.get(&key) {
         Some(e) => e,
         None => {
-            return (
-                axum::http::StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "session not found" })),
-            )
-                .into_response();
+            return ApiError::not_found("session not found").into_response();
         }
     };
 
@@ -354,6 +372,35 @@ pub async fn get_transcript(
     .into_response()
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/sessions/:key/compactions  — compaction history
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Every compaction of the session's current transcript, oldest first.
+/// The summarized lines stay in the transcript before each marker.
+pub async fn list_compactions(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    let Some(entry) = state.sessions.get(&key) else {
+        return ApiError::not_found("session not found").into_response();
+    };
+
+    let lines = state
+        .transcripts
+        .read(&entry.session_id)
+        .unwrap_or_default();
+    let compactions = crate::runtime::compact::compaction_history(&lines);
+
+    Json(serde_json::json!({
+        "session_key": key,
+        "session_id": entry.session_id,
+        "count": compactions.len(),
+        "compactions": compactions,
+    }))
+    .into_response()
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/sessions/:key/reset  — path-based reset
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -375,11 +422,7 @@ pub async fn stop_session(
 ) -> impl IntoResponse {
     // Check the session exists.
     if state.sessions.get(&key).is_none() {
-        return (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "session not found" })),
-        )
-            .into_response();
+        return ApiError::not_found("session not found").into_response();
     }
 
     let was_running = state.cancel_map.cancel(&key);
@@ -392,6 +435,88 @@ pub async fn stop_session(
     .into_response()
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PUT /v1/sessions/:key/tags  — replace user tags
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Max tags per session.
+const MAX_TAGS: usize = 20;
+/// Max length of a single tag in characters.
+const MAX_TAG_LEN: usize = 64;
+
+#[derive(Debug, Deserialize)]
+pub struct SetTagsBody {
+    pub tags: Vec<String>,
+}
+
+/// Reject tag lists the dashboard could not sensibly display.
+fn validate_tags(tags: &[String]) -> Result<(), String> {
+    if tags.len() > MAX_TAGS {
+        return Err(format!("at most {MAX_TAGS} tags are allowed"));
+    }
+    if let Some(tag) = tags.iter().find(|t| t.trim().chars().count() > MAX_TAG_LEN) {
+        return Err(format!("tag '{tag}' exceeds {MAX_TAG_LEN} characters"));
+    }
+    Ok(())
+}
+
+/// Replace a session's tags.  Persisted with the next session flush.
+pub async fn set_session_tags(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(body): Json<SetTagsBody>,
+) -> impl IntoResponse {
+    if let Err(e) = validate_tags(&body.tags) {
+        return ApiError::bad_request(e).into_response();
+    }
+    match state.sessions.set_tags(&key, body.tags) {
+        Some(entry) => Json(serde_json::json!({
+            "session_key": entry.session_key,
+            "tags": entry.tags,
+        }))
+        .into_response(),
+        None => ApiError::not_found("session not found").into_response(),
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// PUT /v1/sessions/:key/model  — pin or unpin the session model
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Deserialize)]
+pub struct PinModelBody {
+    /// `provider/model` (or a bare provider id); `null` unpins.
+    pub model: Option<String>,
+}
+
+/// Pin the model a session uses.  A request's own `model` still wins;
+/// the pin takes precedence over the router and role defaults.
+pub async fn pin_session_model(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(body): Json<PinModelBody>,
+) -> impl IntoResponse {
+    let model = body
+        .model
+        .map(|m| m.trim().to_owned())
+        .filter(|m| !m.is_empty());
+    if let Some(spec) = &model {
+        let provider_id = crate::runtime::model_provider_id(spec);
+        if state.llm.get(provider_id).is_none() {
+            return ApiError::bad_request(format!("unknown provider '{provider_id}'"))
+                .into_response();
+        }
+    }
+    match state.sessions.set_pinned_model(&key, model) {
+        Some(entry) => Json(serde_json::json!({
+            "session_key": entry.session_key,
+            "pinned_model": entry.pinned_model,
+        }))
+        .into_response(),
+        None => ApiError::not_found("session not found").into_response(),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/sessions/:key/compact  — manual compaction
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -403,11 +528,7 @@ pub async fn compact_session(
     let entry = match state.sessions.get(&key) {
         Some(e) => e,
         None => {
-            return (
-                axum::http::StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "session not found" })),
-            )
-                .into_response();
+            return ApiError::not_found("session not found").into_response();
         }
     };
 
@@ -415,13 +536,11 @@ pub async fn compact_session(
     let provider = match crate::runtime::compact::resolve_compaction_provider(&state) {
         Some(p) => p,
         None => {
-            return (
-                axum::http::StatusCode::SERVICE_UNAVAILABLE,
-                Json(serde_json::json!({
-                    "error": "no LLM provider available for compaction"
-                })),
+            return ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "no LLM provider available for compaction",
             )
-                .into_response();
+            .into_response();
         }
     };
 
@@ -448,13 +567,7 @@ pub async fn compact_session(
             "summary_length": summary.len(),
         }))
         .into_response(),
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("compaction failed: {e}"),
-            })),
-        )
-            .into_response(),
+        Err(e) => ApiError::internal(format!("compaction failed: {e}")).into_response(),
     }
 }
 
@@ -479,11 +592,7 @@ pub async fn export_transcript(
     let entry = match state.sessions.get(&key) {
         Some(e) => e,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "session not found" })),
-            )
-                .into_response();
+            return ApiError::not_found("session not found").into_response();
         }
     };
 
@@ -496,11 +605,7 @@ pub async fn export_transcript(
         "markdown" => render_markdown(&lines, &entry),
         "jsonl" => render_jsonl(&lines, &key),
         "json" => render_json(&lines, &key),
-        other => (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": format!("unknown format: {other}") })),
-        )
-            .into_response(),
+        other => ApiError::bad_request(format!("unknown format: {other}")).into_response(),
     }
 }
 
@@ -603,10 +708,152 @@ fn do_reset(state: &AppState, session_key: &str) -> axum::response::Response {
             "reset": true,
         }))
         .into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "session not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("session not found").into_response(),
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, channel: &str, idle_mins: i64, now: DateTime<Utc>) -> SessionEntry {
+        SessionEntry {
+            session_key: key.into(),
+            session_id: format!("id-{key}"),
+            created_at: now - chrono::Duration::days(1),
+            updated_at: now - chrono::Duration::minutes(idle_mins),
+            model: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            context_tokens: 0,
+            sm_session_id: None,
+            origin: SessionOrigin {
+                channel: Some(channel.into()),
+                ..Default::default()
+            },
+            tags: Vec::new(),
+            pinned_model: None,
+        }
+    }
+
+    fn fixtures(now: DateTime<Utc>) -> Vec<SessionEntry> {
+        vec![
+            entry("agent:main:discord:dm:a", "discord", 5, now),
+            entry("agent:main:telegram:dm:b", "telegram", 90, now),
+            entry("agent:coder:discord:dm:c", "discord", 30, now),
+            entry("agent:coder:telegram:dm:d", "telegram", 600, now),
+        ]
+    }
+
+    fn keys(page: &[SessionEntry]) -> Vec<&str> {
+        page.iter().map(|s| s.session_key.as_str()).collect()
+    }
+
+    #[test]
+    fn pages_are_ordered_by_recent_activity() {
+        let now = Utc::now();
+        let query = SessionListQuery::default();
+
+        let (first, total) = select_sessions(fixtures(now), &query, None, now, 2, 0);
+        assert_eq!(total, 4);
+        assert_eq!(
+            keys(&first),
+            ["agent:main:discord:dm:a", "agent:coder:discord:dm:c"]
+        );
+
+        let (last, _) = select_sessions(fixtures(now), &query, None, now, 2, 3);
+        assert_eq!(keys(&last), ["agent:coder:telegram:dm:d"]);
+
+        let (past_end, total) = select_sessions(fixtures(now), &query, None, now, 2, 4);
+        assert!(past_end.is_empty());
+        assert_eq!(total, 4, "total counts matches, not the page");
+
+        let (none, _) = select_sessions(fixtures(now), &query, None, now, 0, 0);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn agent_filter_matches_key_prefix() {
+        let now = Utc::now();
+        let query = SessionListQuery {
+            agent_id: Some("coder".into()),
+            ..Default::default()
+        };
+        let (page, total) = select_sessions(fixtures(now), &query, None, now, 100, 0);
+        assert_eq!(total, 2);
+        assert_eq!(
+            keys(&page),
+            ["agent:coder:discord:dm:c", "agent:coder:telegram:dm:d"]
+        );
+    }
+
+    #[test]
+    fn channel_filter_narrows_results() {
+        let now = Utc::now();
+        let query = SessionListQuery {
+            channel: Some("telegram".into()),
+            ..Default::default()
+        };
+        let (page, total) = select_sessions(fixtures(now), &query, None, now, 100, 0);
+        assert_eq!(total, 2);
+        assert!(page
+            .iter()
+            .all(|s| s.origin.channel.as_deref() == Some("telegram")));
+    }
+
+    #[test]
+    fn active_within_drops_idle_sessions() {
+        let now = Utc::now();
+        let query = SessionListQuery {
+            active_within_secs: Some(3600),
+            ..Default::default()
+        };
+        let (page, total) = select_sessions(fixtures(now), &query, None, now, 100, 0);
+        assert_eq!(total, 2);
+        assert_eq!(
+            keys(&page),
+            ["agent:main:discord:dm:a", "agent:coder:discord:dm:c"]
+        );
+    }
+
+    #[test]
+    fn tag_filter_matches_exact_tag() {
+        let now = Utc::now();
+        let mut sessions = fixtures(now);
+        sessions[1].tags = vec!["work".into(), "urgent".into()];
+        sessions[3].tags = vec!["personal".into()];
+        let query = SessionListQuery {
+            tag: Some("work".into()),
+            ..Default::default()
+        };
+        let (page, total) = select_sessions(sessions, &query, None, now, 100, 0);
+        assert_eq!(total, 1);
+        assert_eq!(keys(&page), ["agent:main:telegram:dm:b"]);
+    }
+
+    #[test]
+    fn tag_limits_are_enforced() {
+        assert!(validate_tags(&["work".into(), "personal".into()]).is_ok());
+        assert!(validate_tags(&vec!["t".to_owned(); MAX_TAGS + 1]).is_err());
+        assert!(validate_tags(&["x".repeat(MAX_TAG_LEN + 1)]).is_err());
+    }
+
+    #[test]
+    fn filters_combine_with_search_hits() {
+        let now = Utc::now();
+        let query = SessionListQuery {
+            channel: Some("discord".into()),
+            ..Default::default()
+        };
+        let hits: std::collections::HashMap<_, _> =
+            [("id-agent:coder:discord:dm:c".to_owned(), (1, String::new()))].into();
+        let (page, total) = select_sessions(fixtures(now), &query, Some(&hits), now, 100, 0);
+        assert_eq!(total, 1);
+        assert_eq!(keys(&page), ["agent:coder:discord:dm:c"]);
     }
 }
@@ -1,6 +1,8 @@
 use axum::extract::{Path, Query, State};
-use axum::response::{IntoResponse, Json};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
 
+use crate::api::error::ApiError;
 use crate::state::AppState;
 
 pub async fn list_skills(State(state): State<AppState>) -> impl IntoResponse {
@@ -45,11 +47,7 @@ pub async fn read_skill_doc(
             "chars": doc.len(),
         }))
         .into_response(),
-        Err(e) => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::not_found(e.to_string()).into_response(),
     }
 }
 
@@ -57,6 +55,9 @@ pub async fn read_skill_doc(
 #[derive(serde::Deserialize)]
 pub struct ResourceQuery {
     pub path: String,
+    /// Serve the raw bytes with a detected `Content-Type` instead of JSON.
+    #[serde(default)]
+    pub raw: bool,
 }
 
 /// Read a bundled resource from a skill's references/, scripts/, or assets/ dir.
@@ -64,11 +65,24 @@ pub struct ResourceQuery {
 /// Returns `content_type` field indicating whether the resource is a script
 /// (requires explicit user confirmation before execution), reference data,
 /// or a generic asset.
+///
+/// With `?raw=true` or a `Range` header, the file itself is served with its
+/// detected MIME type and single-range `bytes=` support (206 / 416), so the
+/// dashboard can fetch large images or PDFs in pieces.
 pub async fn read_skill_resource(
     State(state): State<AppState>,
     Path(name): Path<String>,
     Query(query): Query<ResourceQuery>,
-) -> impl IntoResponse {
+    headers: HeaderMap,
+) -> Response {
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    if query.raw || range.is_some() {
+        return match state.skills.read_resource_bytes(&name, &query.path) {
+            Ok(bytes) => raw_resource_response(&query.path, bytes, range),
+            Err(e) => resource_error(e),
+        };
+    }
+
     match state.skills.read_resource(&name, &query.path) {
         Ok(content) => {
             let content_type = classify_resource_path(&query.path);
@@ -78,6 +92,7 @@ pub async fn read_skill_resource(
                 "content": content,
                 "chars": content.len(),
                 "content_type": content_type,
+                "mime": detect_mime(&query.path, content.as_bytes()),
             });
             // Add a warning for scripts.
             if content_type == "script" {
@@ -88,18 +103,155 @@ pub async fn read_skill_resource(
             }
             Json(json).into_response()
         }
-        Err(e) => {
-            let status = if e.to_string().contains("not found") {
-                axum::http::StatusCode::NOT_FOUND
-            } else {
-                axum::http::StatusCode::FORBIDDEN
+        Err(e) => resource_error(e),
+    }
+}
+
+fn resource_error(e: sa_domain::error::Error) -> Response {
+    let status = if e.to_string().contains("not found") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::FORBIDDEN
+    };
+    ApiError::new(status, e.to_string()).into_response()
+}
+
+/// Build the raw-bytes response, honouring a single `bytes=` range.
+///
+/// Multi-range and malformed headers are ignored (full 200), as RFC 9110
+/// permits.  Served with `nosniff` and a sandboxing CSP since skill packs are
+/// third-party content.
+fn raw_resource_response(path: &str, bytes: Vec<u8>, range: Option<&str>) -> Response {
+    let mime = detect_mime(path, &bytes);
+    let len = bytes.len() as u64;
+
+    let (status, body, content_range) = match range.map(|r| parse_range(r, len)) {
+        Some(ByteRange::Partial(start, end)) => (
+            StatusCode::PARTIAL_CONTENT,
+            bytes[start as usize..=end as usize].to_vec(),
+            Some(format!("bytes {start}-{end}/{len}")),
+        ),
+        Some(ByteRange::Unsatisfiable) => (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            Vec::new(),
+            Some(format!("bytes */{len}")),
+        ),
+        Some(ByteRange::Full) | None => (StatusCode::OK, bytes, None),
+    };
+
+    let mut resp = (status, body).into_response();
+    let h = resp.headers_mut();
+    h.insert(header::CONTENT_TYPE, HeaderValue::from_static(mime));
+    h.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    h.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    h.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static("default-src 'none'; sandbox"),
+    );
+    if let Some(value) = content_range.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        h.insert(header::CONTENT_RANGE, value);
+    }
+    resp
+}
+
+/// Outcome of parsing a `Range` header against a body of known length.
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    /// Absent, malformed, or multi-range: serve everything.
+    Full,
+    /// Inclusive byte offsets.
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// Parse a single `bytes=start-end`, `bytes=start-`, or `bytes=-suffix` range.
+fn parse_range(header: &str, len: u64) -> ByteRange {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return ByteRange::Full,
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) => (len.saturating_sub(n), len.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            let end = match end {
+                "" => len.saturating_sub(1),
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+                    _ => return ByteRange::Full,
+                },
             };
-            (
-                status,
-                Json(serde_json::json!({ "error": e.to_string() })),
-            )
-                .into_response()
+            (start, end)
         }
+    };
+
+    if len == 0 || start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end)
+}
+
+/// MIME type from magic bytes, then extension; unknown text is
+/// `text/plain`, unknown binary `application/octet-stream`.
+fn detect_mime(path: &str, bytes: &[u8]) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+    ];
+    if let Some(&(_, mime)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return mime;
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "json" => "application/json",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "xml" => "application/xml",
+        "md" | "markdown" => "text/markdown; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "sh" => "application/x-sh",
+        "py" => "text/x-python; charset=utf-8",
+        _ if std::str::from_utf8(bytes).is_ok() => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
     }
 }
 
@@ -123,21 +275,105 @@ pub async fn list_skill_engine(State(state): State<AppState>) -> impl IntoRespon
     }))
 }
 
+/// Rescan the skills directory, reload the installed packs' permission
+/// grants, and drop cached tool definitions so the next turn is built
+/// against the new skill set.
+pub(crate) fn reload_skills_registry(state: &AppState) -> sa_domain::error::Result<usize> {
+    let count = state.skills.reload()?;
+    state
+        .skill_engine
+        .load_pack_grants(&state.config.skills.path);
+    state.tool_defs_cache.invalidate("skills reloaded");
+    Ok(count)
+}
+
 pub async fn reload_skills(State(state): State<AppState>) -> impl IntoResponse {
-    match state.skills.reload() {
+    match reload_skills_registry(&state) {
         Ok(count) => {
             let summary = state.skills.readiness_summary();
+            let dangling = state.skills.validate_resource_refs();
             Json(serde_json::json!({
                 "reloaded": true,
                 "skills_count": count,
                 "readiness": summary,
+                "dangling_resource_refs": dangling,
             }))
             .into_response()
         }
-        Err(e) => (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e.to_string() })),
-        )
-            .into_response(),
+        Err(e) => ApiError::internal(e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_mime_from_extension() {
+        assert_eq!(detect_mime("assets/diagram.svg", b"<svg/>"), "image/svg+xml");
+        assert_eq!(detect_mime("references/api.json", b"{}"), "application/json");
+        assert_eq!(
+            detect_mime("references/GUIDE.MD", b"# Guide"),
+            "text/markdown; charset=utf-8"
+        );
+        assert_eq!(detect_mime("scripts/run.sh", b"#!/bin/sh"), "application/x-sh");
+        assert_eq!(
+            detect_mime("assets/notes", b"plain words"),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            detect_mime("assets/blob.bin", &[0xff, 0xfe, 0x00]),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn detects_mime_from_content() {
+        assert_eq!(detect_mime("assets/logo", b"\x89PNG\r\n\x1a\n...."), "image/png");
+        assert_eq!(detect_mime("assets/doc.dat", b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(detect_mime("assets/photo.png", b"\xff\xd8\xff\xe0"), "image/jpeg");
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("bytes=0-3", 10), ByteRange::Partial(0, 3));
+        assert_eq!(parse_range("bytes=4-", 10), ByteRange::Partial(4, 9));
+        assert_eq!(parse_range("bytes=-3", 10), ByteRange::Partial(7, 9));
+        assert_eq!(parse_range("bytes=5-100", 10), ByteRange::Partial(5, 9));
+        assert_eq!(parse_range("bytes=10-", 10), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), ByteRange::Full);
+        assert_eq!(parse_range("items=0-1", 10), ByteRange::Full);
+        assert_eq!(parse_range("bytes=5-2", 10), ByteRange::Full);
+    }
+
+    #[tokio::test]
+    async fn range_request_returns_partial_content() {
+        let bytes = b"%PDF-0123456789".to_vec();
+        let resp = raw_resource_response("assets/manual.pdf", bytes, Some("bytes=5-9"));
+
+        assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+        let h = resp.headers();
+        assert_eq!(h[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(h[header::CONTENT_RANGE], "bytes 5-9/15");
+        assert_eq!(h[header::ACCEPT_RANGES], "bytes");
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"01234");
+    }
+
+    #[tokio::test]
+    async fn unsatisfiable_range_returns_416() {
+        let resp = raw_resource_response("assets/a.png", vec![0; 4], Some("bytes=8-"));
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(resp.headers()[header::CONTENT_RANGE], "bytes */4");
+    }
+
+    #[tokio::test]
+    async fn raw_without_range_returns_whole_file() {
+        let resp = raw_resource_response("assets/a.txt", b"hello".to_vec(), None);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello");
     }
 }
@@ -16,6 +16,7 @@ use sa_domain::config::InboundMetadata;
 use sa_sessions::compute_session_key;
 use sa_sessions::store::SessionOrigin;
 
+use crate::api::error::ApiError;
 use crate::runtime::tasks::{Task, TaskEvent, TaskStatus};
 use crate::runtime::TurnInput;
 use crate::state::AppState;
@@ -68,18 +69,10 @@ pub async fn create_task(
 ) -> impl IntoResponse {
     // Input validation.
     if body.message.is_empty() {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "message is required" })),
-        )
-            .into_response();
+        return ApiError::bad_request("message is required").into_response();
     }
     if body.message.len() > MAX_MESSAGE_BYTES {
-        return (
-            axum::http::StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "message too large (max 32 KB)" })),
-        )
-            .into_response();
+        return ApiError::bad_request("message too large (max 32 KB)").into_response();
     }
 
     // Pre-flight: reject early with 503 if no LLM providers are available.
@@ -90,11 +83,7 @@ pub async fn create_task(
     let (session_key, session_id) = match resolve_task_session(&state, &body) {
         Ok(s) => s,
         Err(e) => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "error": e })),
-            )
-                .into_response();
+            return ApiError::bad_request(e).into_response();
         }
     };
 
@@ -113,6 +102,10 @@ pub async fn create_task(
         response_format: None,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: None,
+        stop: Vec::new(),
     };
 
     // Enqueue the task for execution.
@@ -147,13 +140,10 @@ pub async fn list_tasks(
         Some(s) => match parse_task_status(s) {
             Some(st) => Some(st),
             None => {
-                return (
-                    axum::http::StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({
-                        "error": format!("unknown status: '{s}'"),
+                return ApiError::bad_request(format!("unknown status: '{s}'"))
+                    .with_details(serde_json::json!({
                         "valid": ["queued", "running", "completed", "failed", "cancelled"],
-                    })),
-                )
+                    }))
                     .into_response();
             }
         },
@@ -204,11 +194,7 @@ pub async fn get_task(
 ) -> impl IntoResponse {
     match state.task_store.get(&task_id) {
         Some(task) => Json(serde_json::json!(task)).into_response(),
-        None => (
-            axum::http::StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "task not found" })),
-        )
-            .into_response(),
+        None => ApiError::not_found("task not found").into_response(),
     }
 }
 
@@ -226,22 +212,15 @@ pub async fn cancel_task(
     if !cancelled {
         // Check if it exists at all.
         if state.task_store.get(&task_id).is_none() {
-            return (
-                axum::http::StatusCode::NOT_FOUND,
-                Json(serde_json::json!({ "error": "task not found" })),
-            )
-                .into_response();
+            return ApiError::not_found("task not found").into_response();
         }
         // Task exists but is already terminal.
-        return (
+        return ApiError::new(
             axum::http::StatusCode::CONFLICT,
-            Json(serde_json::json!({
-                "error": "task is already in a terminal state",
-                "task_id": task_id,
-                "cancelled": false,
-            })),
+            "task is already in a terminal state",
         )
-            .into_response();
+        .with_details(serde_json::json!({ "task_id": task_id, "cancelled": false }))
+        .into_response();
     }
 
     // Signal the cancel token to abort a running turn.
@@ -269,11 +248,7 @@ pub async fn task_events_sse(
     let task = state.task_store.get(&task_id);
     if task.is_none() {
         let stream = futures_util::stream::once(async {
-            Ok::<_, std::convert::Infallible>(
-                Event::default()
-                    .event("error")
-                    .data(r#"{"error":"task not found"}"#),
-            )
+            Ok::<_, std::convert::Infallible>(ApiError::not_found("task not found").sse_event())
         });
         return Sse::new(stream)
             .keep_alive(KeepAlive::default())
@@ -360,9 +335,7 @@ fn make_task_event_stream(
 
 /// Pre-flight check: return a structured 503 if no LLM providers are
 /// available.
-fn require_llm_provider(
-    state: &AppState,
-) -> Result<(), (axum::http::StatusCode, Json<serde_json::Value>)> {
+fn require_llm_provider(state: &AppState) -> Result<(), ApiError> {
     if !state.llm.is_empty() {
         return Ok(());
     }
@@ -381,15 +354,13 @@ fn require_llm_provider(
         })
         .collect();
 
-    Err((
+    Err(ApiError::new(
         axum::http::StatusCode::SERVICE_UNAVAILABLE,
-        Json(serde_json::json!({
-            "error": "no_llm_provider",
-            "reason": "No LLM providers are available. Configure at least one \
-                       provider in config.toml under [llm.providers].",
-            "init_errors": init_errors,
-        })),
-    ))
+        "No LLM providers are available. Configure at least one \
+         provider in config.toml under [llm.providers].",
+    )
+    .with_code("no_llm_provider")
+    .with_details(serde_json::json!({ "init_errors": init_errors })))
 }
 
 /// Resolve session for a task request — mirrors the chat endpoint's
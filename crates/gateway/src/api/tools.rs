@@ -6,17 +6,24 @@
 //! - `POST /v1/tools/exec/approve/:id` — approve a pending exec command
 //! - `POST /v1/tools/exec/deny/:id`    — deny a pending exec command
 //! - `GET  /v1/tools/exec/pending`     — list pending exec approvals
+//! - `GET  /v1/tools/stats`            — per-tool call counts, failure rate, latency
+//! - `GET  /v1/tools/audit`            — recent tool dispatches (redacted args)
+//! - `GET  /v1/tools/definitions`      — tool schemas the model sees (dry run)
 
+use std::net::SocketAddr;
 use std::time::Duration;
 
-use axum::extract::{Path, State};
+use axum::extract::{ConnectInfo, Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Json};
 use serde::Deserialize;
 
+use sa_domain::config::AgentLimits;
 use sa_tools::exec::{self, ExecRequest};
 use sa_tools::process::{self, ProcessRequest};
 
+use crate::api::error::ApiError;
+use crate::runtime::agent::FanOut;
 use crate::state::AppState;
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -30,12 +37,7 @@ pub async fn exec_tool(
     // Enforce denied-patterns denylist (precompiled RegexSet) before executing.
     if state.denied_command_set.is_match(&req.command) {
         tracing::warn!(command = %req.command, "exec blocked by denied_patterns");
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({
-                "error": "command blocked by security policy",
-            })),
-        )
+        return ApiError::new(StatusCode::FORBIDDEN, "command blocked by security policy")
             .into_response();
     }
 
@@ -84,6 +86,7 @@ pub struct ToolInvokeRequest {
 /// not HTTP errors). Returns 503 only when routing itself fails.
 pub async fn invoke_tool(
     State(state): State<AppState>,
+    peer: Option<ConnectInfo<SocketAddr>>,
     Json(req): Json<ToolInvokeRequest>,
 ) -> impl IntoResponse {
     let start = std::time::Instant::now();
@@ -125,12 +128,18 @@ pub async fn invoke_tool(
     // Clamp timeout.
     let timeout = Duration::from_millis(req.timeout_ms.unwrap_or(30_000).min(120_000));
 
+    // Session-less invokes are rate limited per peer address.
+    let caller = peer.map(|ConnectInfo(addr)| addr.ip().to_string());
+
+    let fan_out = FanOut::new(&AgentLimits::default());
     let dispatch = crate::runtime::tools::dispatch_tool(
         &state,
         &req.tool,
         &req.args,
         req.session_key.as_deref(),
+        caller.as_deref(),
         None, // no agent context for admin invoke
+        &fan_out,
     );
 
     let (content, is_error) = match tokio::time::timeout(timeout, dispatch).await {
@@ -189,6 +198,120 @@ pub async fn list_pending_approvals(
     }))
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/tools/stats
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Aggregated execution metrics for every tool called by agent turns
+/// since startup.
+pub async fn tool_stats(State(state): State<AppState>) -> impl IntoResponse {
+    let tools = state.tool_stats.snapshot();
+    Json(serde_json::json!({
+        "tools": tools,
+        "count": tools.len(),
+    }))
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/tools/audit
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub session_key: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    /// `ok`, `error` or `denied`.
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Only entries at or after this RFC 3339 timestamp.
+    #[serde(default)]
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default = "default_audit_limit")]
+    pub limit: usize,
+}
+
+fn default_audit_limit() -> usize {
+    100
+}
+
+/// Recent tool dispatches, newest first.
+pub async fn tool_audit(
+    State(state): State<AppState>,
+    Query(q): Query<AuditQuery>,
+) -> impl IntoResponse {
+    use crate::runtime::tool_audit::{AuditFilter, AuditStatus};
+
+    let status = match q.status.as_deref() {
+        None => None,
+        Some(s) => match AuditStatus::parse(s) {
+            Some(status) => Some(status),
+            None => {
+                return ApiError::bad_request(format!(
+                    "unknown status '{s}' (expected ok, error or denied)"
+                ))
+                .into_response();
+            }
+        },
+    };
+    let filter = AuditFilter {
+        tool_name: q.tool.as_deref(),
+        session_key: q.session_key.as_deref(),
+        agent_id: q.agent_id.as_deref(),
+        status,
+        since: q.since,
+    };
+    let entries = state.tool_audit.query(&filter, q.limit.min(1_000));
+    Json(serde_json::json!({
+        "entries": entries,
+        "count": entries.len(),
+    }))
+    .into_response()
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// GET /v1/tools/definitions
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Deserialize)]
+pub struct DefinitionsQuery {
+    /// Sub-agent whose tool policy applies; omitted = the main agent.
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+/// The exact tool list a turn for `agent` would send to the model, plus
+/// the tools left out by policy or name collisions.
+pub async fn tool_definitions(
+    State(state): State<AppState>,
+    Query(q): Query<DefinitionsQuery>,
+) -> impl IntoResponse {
+    use crate::runtime::tools::tool_definitions_report;
+
+    let agent = match q.agent.as_deref() {
+        None => None,
+        Some(id) => match state.agents.as_ref().and_then(|m| m.get(id)) {
+            Some(agent) => Some(agent),
+            None => {
+                return ApiError::not_found(format!("unknown agent '{id}'")).into_response();
+            }
+        },
+    };
+    let report = tool_definitions_report(&state, agent.as_ref().map(|a| &a.config.tool_policy));
+    Json(serde_json::json!({
+        "agent": q.agent,
+        "count": report.tools.len(),
+        "tools": report.tools,
+        "denied_by_policy": report.denied_by_policy,
+        "shadowed": report.shadowed,
+        "duplicate_names": report.duplicate_names,
+    }))
+    .into_response()
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // POST /v1/tools/exec/approve/:id
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -207,14 +330,7 @@ pub async fn approve_exec(
         }))
         .into_response()
     } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "ok": false,
-                "error": format!("no pending approval with id {id}"),
-            })),
-        )
-            .into_response()
+        ApiError::not_found(format!("no pending approval with id {id}")).into_response()
     }
 }
 
@@ -245,13 +361,6 @@ pub async fn deny_exec(
         }))
         .into_response()
     } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "ok": false,
-                "error": format!("no pending approval with id {id}"),
-            })),
-        )
-            .into_response()
+        ApiError::not_found(format!("no pending approval with id {id}")).into_response()
     }
 }
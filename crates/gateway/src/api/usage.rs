@@ -0,0 +1,61 @@
+//! Usage report API endpoint.
+//!
+//! - `GET /v1/usage?from=&to=&group_by=day|model|agent` — token and cost
+//!   totals from persisted runs, grouped by one dimension
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::api::error::api_error;
+use crate::runtime::usage::{self, UsageGroupBy};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Inclusive lower bound on run start time (RFC 3339).
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on run start time (RFC 3339).
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+}
+
+/// `GET /v1/usage` — aggregate persisted runs started in `[from, to)`.
+pub async fn get_usage(State(state): State<AppState>, Query(query): Query<UsageQuery>) -> Response {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return api_error(StatusCode::BAD_REQUEST, "`from` must be before `to`");
+        }
+    }
+
+    // The full run log can be large; read and aggregate it off the runtime.
+    let run_store = state.run_store.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let runs = run_store.persisted_runs();
+        usage::aggregate(&runs, query.from, query.to, query.group_by)
+    })
+    .await;
+
+    match result {
+        Ok(buckets) => {
+            let totals = usage::totals(&buckets);
+            Json(serde_json::json!({
+                "group_by": query.group_by,
+                "from": query.from,
+                "to": query.to,
+                "buckets": buckets,
+                "totals": totals,
+            }))
+            .into_response()
+        }
+        Err(e) => api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("usage task failed: {e}"),
+        ),
+    }
+}
@@ -6,29 +6,25 @@
 //!      (this route lives in the protected router).
 //!   2. HMAC-SHA256 — when `schedule.webhook_secret` is set, the handler also
 //!      verifies `X-Hub-Signature-256: sha256=<hex>` against the request body.
+//!
+//! When the schedule declares `trigger_params`, the body must be a JSON
+//! object matching them; values fill `{{trigger.<name>}}` in the prompt.
 
 use axum::body::Bytes;
 use axum::extract::{Path, State};
 use axum::http::{HeaderMap, StatusCode};
-use axum::response::{IntoResponse, Json, Response};
+use axum::response::{IntoResponse, Json};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use subtle::ConstantTimeEq;
 use uuid::Uuid;
 
+use crate::api::error::api_error;
+use crate::runtime::schedules::validate_trigger_payload;
 use crate::state::AppState;
 
 type HmacSha256 = Hmac<Sha256>;
 
-/// Build a standardized JSON error response: `{ "error": "<message>" }`.
-fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
-    (
-        status,
-        Json(serde_json::json!({ "error": message.into() })),
-    )
-        .into_response()
-}
-
 /// `POST /v1/schedules/:id/trigger`
 ///
 /// Triggers a scheduled run from an external webhook. The route sits behind
@@ -72,10 +68,18 @@ pub async fn trigger_webhook(
         }
     }
 
-    // 4. Spawn the run (reuses the shared digest + LLM + delivery pipeline).
+    // 4. Validate the body against the declared trigger parameters.
+    let values = match validate_trigger_payload(&schedule.trigger_params, &body) {
+        Ok(v) => v,
+        Err(msg) => return api_error(StatusCode::BAD_REQUEST, msg),
+    };
+    let mut schedule = schedule;
+    schedule.prompt_template = apply_trigger_values(&schedule.prompt_template, &values);
+
+    // 5. Spawn the run (reuses the shared digest + LLM + delivery pipeline).
     crate::runtime::schedule_runner::spawn_scheduled_run(state, schedule, None).await;
 
-    // 5. Return 202 Accepted.
+    // 6. Return 202 Accepted.
     (
         StatusCode::ACCEPTED,
         Json(serde_json::json!({
@@ -85,3 +89,34 @@ pub async fn trigger_webhook(
     )
         .into_response()
 }
+
+/// Substitute `{{trigger.<name>}}` placeholders with trigger values.
+fn apply_trigger_values(template: &str, values: &[(String, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{{trigger.{name}}}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::schedules::{TriggerParam, TriggerParamType};
+
+    #[test]
+    fn valid_trigger_fills_the_prompt() {
+        let params = vec![TriggerParam {
+            name: "repo".into(),
+            kind: TriggerParamType::String,
+            required: true,
+            description: None,
+        }];
+        let values = validate_trigger_payload(&params, br#"{"repo": "acme/api"}"#).unwrap();
+        assert_eq!(
+            apply_trigger_values("Review {{trigger.repo}} on {{date}}", &values),
+            "Review acme/api on {{date}}"
+        );
+        assert!(validate_trigger_payload(&params, br#"{"repo": 7}"#).is_err());
+    }
+}
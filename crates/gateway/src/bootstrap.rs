@@ -9,7 +9,8 @@ use std::sync::Arc;
 use anyhow::Context;
 use sha2::{Digest, Sha256};
 
-use sa_domain::config::{Config, ConfigSeverity};
+use sa_domain::config::{Config, ConfigSeverity, MaintenanceConfig, MemoryUnavailablePolicy};
+use sa_domain::persistence::{FsBackend, PersistenceBackend};
 use sa_memory::create_provider as create_memory_provider;
 use sa_mcp_client::McpManager;
 use sa_providers::registry::ProviderRegistry;
@@ -63,10 +64,19 @@ pub async fn build_app_state(
     let skills = Arc::new(SkillsRegistry::load(&config.skills.path).context("loading skills")?);
     tracing::info!(skills_count = skills.list().len(), "skills loaded");
 
+    // ── Persistence backend (shared by the stores below) ─────────────
+    let persistence: Arc<dyn PersistenceBackend> =
+        Arc::new(FsBackend::new(&config.workspace.state_path));
+
     // ── SerialMemory client ──────────────────────────────────────────
     let memory: Arc<dyn sa_memory::SerialMemoryProvider> =
-        create_memory_provider(&config.serial_memory)
+        create_memory_provider(&config.serial_memory, persistence.clone())
             .context("creating SerialMemory client")?;
+    if config.serial_memory.availability.on_unavailable == MemoryUnavailablePolicy::Fail {
+        memory.health().await.context(
+            "SerialMemory is unreachable and serial_memory.availability.on_unavailable = \"fail\"",
+        )?;
+    }
     tracing::info!(
         url = %config.serial_memory.base_url,
         transport = ?config.serial_memory.transport,
@@ -87,7 +97,7 @@ pub async fn build_app_state(
 
     // ── Session management ───────────────────────────────────────────
     let sessions = Arc::new(
-        SessionStore::new(&config.workspace.state_path)
+        SessionStore::with_backend(&config.workspace.state_path, persistence.clone())
             .context("initializing session store")?,
     );
     let identity = Arc::new(IdentityResolver::from_config(
@@ -110,16 +120,24 @@ pub async fn build_app_state(
     // ── Node registry + tool router ──────────────────────────────────
     let nodes = Arc::new(NodeRegistry::new());
     nodes.load_allowlists_from_env();
-    let tool_router = Arc::new(ToolRouter::new(
-        nodes.clone(),
-        config.tools.exec.timeout_sec,
-    ));
+    let tool_router = Arc::new(
+        ToolRouter::new(nodes.clone(), config.nodes.tool_timeout_secs)
+            .with_node_timeouts(
+                config
+                    .nodes
+                    .tool_timeouts
+                    .iter()
+                    .map(|(id, &secs)| (id.clone(), std::time::Duration::from_secs(secs)))
+                    .collect(),
+            )
+            .with_max_outstanding(config.nodes.max_outstanding),
+    );
     tracing::info!("node registry + tool router ready");
 
     // ── Session locks (per-session concurrency) ──────────────────────
-    let session_locks = Arc::new(
-        crate::runtime::session_lock::SessionLockMap::new(),
-    );
+    let session_locks = Arc::new(crate::runtime::session_lock::SessionLockMap::from_config(
+        &config.sessions.locks,
+    ));
     tracing::info!("session lock map ready");
 
     // ── Cancel map (per-session cancellation) ─────────────────────────
@@ -134,6 +152,19 @@ pub async fn build_app_state(
     );
     tracing::info!("quota tracker ready");
 
+    // ── Tool stats (per-tool execution metrics) ─────────────────────
+    let tool_stats = Arc::new(crate::runtime::tool_stats::ToolStats::new());
+
+    // ── Tool result cache (idempotent tools) ────────────────────────
+    let tool_cache = Arc::new(crate::runtime::tool_cache::ToolResultCache::from_config(
+        &config.tools.result_cache,
+    ));
+
+    // ── Tool audit log (audit/tools.jsonl) ──────────────────────────
+    let tool_audit = Arc::new(crate::runtime::tool_audit::ToolAuditLog::new(
+        persistence.clone(),
+    ));
+
     // ── Dedupe store (inbound idempotency, 24h TTL) ────────────────
     let dedupe = Arc::new(
         crate::api::inbound::DedupeStore::new(std::time::Duration::from_secs(86_400)),
@@ -148,8 +179,8 @@ pub async fn build_app_state(
     tracing::info!(path = %import_root.display(), "import staging root ready");
 
     // ── Run store ────────────────────────────────────────────────────
-    let run_store = Arc::new(crate::runtime::runs::RunStore::new(
-        &config.workspace.state_path,
+    let run_store = Arc::new(crate::runtime::runs::RunStore::with_backend(
+        persistence.clone(),
     ));
     tracing::info!("run store ready");
 
@@ -171,23 +202,41 @@ pub async fn build_app_state(
         crate::skills::build_default_engine()
             .context("initializing skill engine")?,
     );
-    tracing::info!(skills = skill_engine.len(), "skill engine ready");
+    let pack_grants = skill_engine.load_pack_grants(&config.skills.path);
+    tracing::info!(
+        skills = skill_engine.len(),
+        pack_grants,
+        "skill engine ready"
+    );
+
+    // ── Tool-dispatch middleware ─────────────────────────────────────
+    let tool_rate_limiter = Arc::new(crate::runtime::tool_rate_limit::ToolRateLimiter::new(
+        config.tools.rate_limit.clone(),
+    ));
+    let tool_middleware = Arc::new(crate::runtime::tool_middleware::ToolChain::builtin(
+        tool_audit.clone(),
+        tool_rate_limiter,
+        tool_cache.clone(),
+        skill_engine.clone(),
+    ));
 
     // ── Schedule store ───────────────────────────────────────────────
     let schedule_store = Arc::new(
-        crate::runtime::schedules::ScheduleStore::new(&config.workspace.state_path),
+        crate::runtime::schedules::ScheduleStore::with_backend(persistence.clone()),
     );
     tracing::info!("schedule store ready");
 
     // ── Delivery store ──────────────────────────────────────────────
     let delivery_store = Arc::new(
-        crate::runtime::deliveries::DeliveryStore::new(&config.workspace.state_path),
+        crate::runtime::deliveries::DeliveryStore::with_backend(persistence),
     );
     tracing::info!("delivery store ready");
 
-    // ── API token (read once, hash for constant-time comparison) ────
-    // Priority: config.server.api_token > env var (config.server.api_token_env)
-    let api_token_hash = {
+    // ── API tokens (read once, hash for constant-time comparison) ───
+    // Primary token: config.server.api_token > env var (config.server.api_token_env),
+    // full scope.  Scoped tokens come from config.server.api_tokens.
+    let api_tokens = {
+        let mut tokens = crate::api::auth::ApiTokens::default();
         let env_var = &config.server.api_token_env;
         let token = config
             .server
@@ -201,18 +250,32 @@ pub async fn build_app_state(
                     .filter(|t| !t.is_empty())
                     .map(|t| (format!("env:{env_var}"), t))
             });
-        match token {
-            Some((source, t)) => {
-                tracing::info!(source = %source, "API bearer-token auth enabled");
-                Some(Sha256::digest(t.as_bytes()).to_vec())
-            }
-            None => {
-                tracing::warn!(
-                    "API bearer-token auth DISABLED — set server.api_token in config.toml or {env_var} env var"
-                );
-                None
+        if let Some((source, t)) = token {
+            tracing::info!(source = %source, "API bearer-token auth enabled");
+            tokens.insert(&t, sa_domain::config::ApiScope::ALL.to_vec());
+        }
+        for (scoped_env, scopes) in &config.server.api_tokens {
+            match std::env::var(scoped_env).ok().filter(|t| !t.is_empty()) {
+                Some(t) => {
+                    tracing::info!(
+                        source = %format!("env:{scoped_env}"),
+                        ?scopes,
+                        "scoped API token enabled"
+                    );
+                    tokens.insert(&t, scopes.clone());
+                }
+                None => tracing::warn!(
+                    env_var = %scoped_env,
+                    "scoped API token env var unset — skipping"
+                ),
             }
         }
+        if tokens.is_empty() {
+            tracing::warn!(
+                "API bearer-token auth DISABLED — set server.api_token in config.toml or {env_var} env var"
+            );
+        }
+        Arc::new(tokens)
     };
 
     // ── Admin token (read once, hash for constant-time comparison) ──
@@ -350,6 +413,11 @@ pub async fn build_app_state(
         session_locks,
         cancel_map,
         quota_tracker,
+        tool_stats,
+        tool_cache,
+        tool_audit,
+        tool_middleware,
+        chat_interceptors: Arc::new(crate::runtime::interceptor::ChatInterceptors::new()),
         agents: None,
         dedupe,
         run_store,
@@ -358,12 +426,13 @@ pub async fn build_app_state(
         skill_engine,
         schedule_store,
         delivery_store,
+        events: Arc::new(crate::runtime::events::EventBus::new()),
         config_path: PathBuf::from(config_path),
         import_root,
         shutdown_tx,
         user_facts_cache: Arc::new(parking_lot::RwLock::new(std::collections::HashMap::new())),
-        tool_defs_cache: Arc::new(parking_lot::RwLock::new(std::collections::HashMap::new())),
-        api_token_hash,
+        tool_defs_cache: Arc::new(crate::runtime::tool_cache::ToolDefsCache::new()),
+        api_tokens,
         admin_token_hash,
         denied_command_set,
         approval_command_set,
@@ -380,40 +449,70 @@ pub async fn build_app_state(
     Ok(state)
 }
 
+/// The periodic session and delivery flush loops started by
+/// [`spawn_background_tasks`].  Shutdown stops them before its final flush
+/// so the two never save the same store at once.  Dropping this without
+/// calling [`StoreFlushers::stop`] leaves the loops running.
+pub struct StoreFlushers {
+    stop: tokio::sync::watch::Sender<bool>,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl StoreFlushers {
+    /// Stop the flush loops, waiting for any flush already in progress.
+    pub async fn stop(self) {
+        let _ = self.stop.send(true);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+    }
+}
+
 /// Spawn the long-running background tokio tasks (session flush, delivery
-/// flush, process cleanup, node pruning, import cleanup, schedule runner).
+/// flush, process cleanup, run retention, node pruning, event bus
+/// forwarding, import cleanup, schedule runner).
 ///
 /// Call this **after** [`build_app_state`] when running the HTTP server.
 /// CLI one-shot commands (`run`) typically skip this.
-pub fn spawn_background_tasks(state: &AppState) {
+pub fn spawn_background_tasks(state: &AppState) -> StoreFlushers {
+    let maintenance = &state.config.maintenance;
+    let (stop, stop_rx) = tokio::sync::watch::channel(false);
+    let mut flushers = Vec::new();
+
     // ── Periodic session flush ───────────────────────────────────────
     {
         let sessions = state.sessions.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(30),
-            );
+        let period = MaintenanceConfig::period(maintenance.session_flush_secs);
+        let mut stop_rx = stop_rx.clone();
+        flushers.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    Ok(()) = stop_rx.changed() => break,
+                }
                 if let Err(e) = sessions.flush().await {
                     tracing::warn!(error = %e, "session store flush failed");
                 }
             }
-        });
+        }));
     }
 
     // ── Periodic delivery flush ──────────────────────────────────────
     {
         let delivery_store = state.delivery_store.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(30),
-            );
+        let period = MaintenanceConfig::period(maintenance.delivery_flush_secs);
+        let mut stop_rx = stop_rx;
+        flushers.push(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    Ok(()) = stop_rx.changed() => break,
+                }
                 delivery_store.flush_if_dirty().await;
             }
-        });
+        }));
     }
 
     // ── Periodic process cleanup + session lock pruning + task runner pruning ──
@@ -422,10 +521,9 @@ pub fn spawn_background_tasks(state: &AppState) {
         let session_locks = state.session_locks.clone();
         let task_runner = state.task_runner.clone();
         let task_store = state.task_store.clone();
+        let period = MaintenanceConfig::period(maintenance.process_cleanup_secs);
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(60),
-            );
+            let mut interval = tokio::time::interval(period);
             loop {
                 interval.tick().await;
                 processes.cleanup_stale();
@@ -436,27 +534,60 @@ pub fn spawn_background_tasks(state: &AppState) {
         });
     }
 
+    // ── Periodic run retention ──────────────────────────────────────
+    if maintenance.run_retention_days.is_some() || maintenance.run_retention_max.is_some() {
+        let run_store = state.run_store.clone();
+        let period = MaintenanceConfig::period(maintenance.run_prune_secs);
+        // Capped at a century so the conversion can't overflow.
+        let max_age = maintenance
+            .run_retention_days
+            .map(|days| chrono::Duration::days(days.min(36_500) as i64));
+        let max_count = maintenance.run_retention_max;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let store = run_store.clone();
+                match tokio::task::spawn_blocking(move || store.prune_persisted(max_age, max_count))
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(deleted = n, "pruned persisted runs"),
+                    Err(e) => tracing::warn!(error = %e, "run retention sweep failed"),
+                }
+            }
+        });
+    }
+
     // ── Periodic stale node pruning ─────────────────────────────────
     {
         let nodes = state.nodes.clone();
+        let period = MaintenanceConfig::period(maintenance.node_prune_secs);
+        let stale_secs = maintenance.node_stale_secs as i64;
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(30),
-            );
+            let mut interval = tokio::time::interval(period);
             loop {
                 interval.tick().await;
-                nodes.prune_stale(120);
+                nodes.prune_stale(stale_secs);
             }
         });
     }
 
-    // ── Periodic import staging cleanup (24h TTL, hourly sweep) ─────
+    // ── Unified event bus ───────────────────────────────────────────
+    state.events.attach(
+        &state.run_store,
+        &state.schedule_store,
+        &state.delivery_store,
+        &state.nodes,
+    );
+
+    // ── Periodic import + attachment staging cleanup (24h TTL) ─────
     {
         let import_root = state.import_root.clone();
+        let workspace = state.config.workspace.path.clone();
+        let period = MaintenanceConfig::period(maintenance.import_sweep_secs);
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(3_600),
-            );
+            let mut interval = tokio::time::interval(period);
             loop {
                 interval.tick().await;
                 match crate::import::openclaw::cleanup_stale_staging(
@@ -469,18 +600,52 @@ pub fn spawn_background_tasks(state: &AppState) {
                     Ok(n) => tracing::info!(removed = n, "cleaned up stale import staging dirs"),
                     Err(e) => tracing::warn!(error = %e, "import staging cleanup failed"),
                 }
+                // Apply journals outlive staging so recent applies can
+                // still be rolled back.
+                match crate::import::openclaw::cleanup_stale_journals(&import_root, 7 * 86_400)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(removed = n, "cleaned up stale import apply journals"),
+                    Err(e) => tracing::warn!(error = %e, "import apply journal cleanup failed"),
+                }
+                match crate::import::attachments::cleanup_stale_attachments(&workspace, 86_400)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => {
+                        tracing::info!(removed = n, "cleaned up stale inbound attachment dirs")
+                    }
+                    Err(e) => tracing::warn!(error = %e, "inbound attachment cleanup failed"),
+                }
+            }
+        });
+    }
+
+    // ── Periodic memory tombstone purge (soft delete only) ──────────
+    if state.config.serial_memory.soft_delete.enabled {
+        let memory = state.memory.clone();
+        let period = MaintenanceConfig::period(maintenance.tombstone_purge_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match memory.purge_expired_tombstones().await {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(purged = n, "purged expired memory tombstones"),
+                    Err(e) => tracing::warn!(error = %e, "memory tombstone purge failed"),
+                }
             }
         });
     }
 
-    // ── Schedule runner (tick every 30s, trigger due schedules) ───────
+    // ── Schedule runner (tick, trigger due schedules) ───────────────
     {
         let state_for_sched = state.clone();
+        let period = MaintenanceConfig::period(maintenance.schedule_tick_secs);
         tokio::spawn(async move {
             let runner = crate::runtime::schedule_runner::ScheduleRunner::new();
-            let mut interval = tokio::time::interval(
-                std::time::Duration::from_secs(30),
-            );
+            let mut interval = tokio::time::interval(period);
             loop {
                 interval.tick().await;
                 runner.tick(&state_for_sched).await;
@@ -488,4 +653,8 @@ pub fn spawn_background_tasks(state: &AppState) {
         });
     }
     tracing::info!("background tasks spawned");
+    StoreFlushers {
+        stop,
+        tasks: flushers,
+    }
 }
@@ -36,7 +36,7 @@ pub async fn chat(
     .await?;
 
     // 2. Spawn background tasks (chat is long-lived).
-    bootstrap::spawn_background_tasks(&state);
+    let _flushers = bootstrap::spawn_background_tasks(&state);
 
     // 3. Initialize rustyline editor with persistent history.
     let history_path = dirs::home_dir()
@@ -206,6 +206,10 @@ async fn send_message(
         response_format: None,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: None,
+        stop: Vec::new(),
     };
 
     let (_run_id, mut rx) = run_turn(state.clone(), input);
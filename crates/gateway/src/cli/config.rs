@@ -40,6 +40,110 @@ pub fn show(config: &Config) {
     }
 }
 
+// ── Config migration ────────────────────────────────────────────────
+
+/// Top-level sections that were renamed: `(old, new)`.
+const RENAMED_SECTIONS: &[(&str, &str)] = &[("serialmemory", "serial_memory")];
+
+/// Result of [`migrate_config_str`].
+#[derive(Debug)]
+pub struct ConfigMigration {
+    /// Upgraded config as TOML.
+    pub output: String,
+    /// Human-readable description of each change, in the order applied.
+    pub changes: Vec<String>,
+}
+
+/// Upgrade an old-style config: apply known renames, then add any
+/// top-level sections the file is missing with their default values.
+pub fn migrate_config_str(raw: &str) -> anyhow::Result<ConfigMigration> {
+    let mut table: toml::Table = toml::from_str(raw)?;
+    let mut changes = Vec::new();
+
+    for (old, new) in RENAMED_SECTIONS {
+        let Some(value) = table.remove(*old) else {
+            continue;
+        };
+        if table.contains_key(*new) {
+            changes.push(format!("removed [{old}] (superseded by existing [{new}])"));
+        } else {
+            table.insert((*new).to_owned(), value);
+            changes.push(format!("renamed [{old}] to [{new}]"));
+        }
+    }
+
+    // `llm.require_provider` predates `llm.startup_policy`.
+    if let Some(toml::Value::Table(llm)) = table.get_mut("llm") {
+        if let Some(old) = llm.remove("require_provider") {
+            if llm.contains_key("startup_policy") {
+                changes.push(
+                    "removed llm.require_provider (llm.startup_policy takes precedence)".into(),
+                );
+            } else if old.as_bool() == Some(true) {
+                llm.insert("startup_policy".into(), "require_one".into());
+                changes.push(
+                    "replaced llm.require_provider = true with llm.startup_policy = \"require_one\""
+                        .into(),
+                );
+            } else {
+                changes.push("removed llm.require_provider = false (the default)".into());
+            }
+        }
+    }
+
+    // The renamed file must still load before we fill in defaults.
+    toml::Value::Table(table.clone())
+        .try_into::<Config>()
+        .map_err(|e| anyhow::anyhow!("config does not parse after migration: {e}"))?;
+
+    let defaults = toml::Value::try_from(Config::default())?;
+    if let toml::Value::Table(defaults) = defaults {
+        for (key, value) in defaults {
+            if !table.contains_key(&key) {
+                changes.push(format!("added [{key}] with defaults"));
+                table.insert(key, value);
+            }
+        }
+    }
+
+    Ok(ConfigMigration {
+        output: toml::to_string_pretty(&table)?,
+        changes,
+    })
+}
+
+/// Migrate the config file at `config_path` in place, keeping a backup.
+pub fn migrate(config_path: &str) -> anyhow::Result<()> {
+    let raw = std::fs::read_to_string(config_path)
+        .map_err(|e| anyhow::anyhow!("reading {config_path}: {e}"))?;
+    let migration = migrate_config_str(&raw)
+        .map_err(|e| anyhow::anyhow!("migrating {config_path}: {e}"))?;
+
+    if migration.changes.is_empty() {
+        println!("Config is up to date ({config_path})");
+        return Ok(());
+    }
+
+    let backup = format!(
+        "{config_path}.bak-{}",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    );
+    std::fs::copy(config_path, &backup)
+        .map_err(|e| anyhow::anyhow!("writing backup {backup}: {e}"))?;
+    std::fs::write(config_path, &migration.output)
+        .map_err(|e| anyhow::anyhow!("writing {config_path}: {e}"))?;
+
+    for change in &migration.changes {
+        println!("  - {change}");
+    }
+    println!(
+        "\nMigrated {config_path} ({} change(s)). Backup: {backup}",
+        migration.changes.len()
+    );
+    println!("Note: comments are not preserved; compare with the backup if needed.");
+    Ok(())
+}
+
 // ── Keychain secret management ──────────────────────────────────────
 
 const DEFAULT_KEYCHAIN_SERVICE: &str = "serialagent";
@@ -140,6 +244,171 @@ pub fn get_secret(config: &Config, provider_id: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Replace a provider's keychain API key, validating it first.
+///
+/// The new key is stored, then checked with a cheap models-list request
+/// against the provider.  If the provider rejects it, the previous key (or
+/// the absence of one) is restored before returning the error.
+pub async fn rotate_secret(config: &Config, provider_id: &str) -> anyhow::Result<()> {
+    let provider = find_provider(config, provider_id)?;
+    let (service, account) = keychain_coords(provider);
+
+    // Fail fast for providers we cannot validate before touching the keychain.
+    supports_models_list(provider)?;
+
+    let secret = rpassword::prompt_password_stderr(&format!(
+        "Enter new API key for provider '{provider_id}' (input hidden): "
+    ))
+    .map_err(|e| anyhow::anyhow!("failed to read secret from stdin: {e}"))?;
+
+    let secret = secret.trim().to_owned();
+    if secret.is_empty() {
+        anyhow::bail!("empty secret provided — aborting");
+    }
+
+    let entry = keyring::Entry::new(&service, &account)
+        .map_err(|e| anyhow::anyhow!("keyring entry creation failed: {e}"))?;
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(provider.connect_timeout_sec))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+    rotate_with(&entry, &secret, |key| async move {
+        validate_key(&client, provider, &key).await
+    })
+    .await?;
+
+    println!(
+        "Secret rotated and validated (service={service:?}, account={account:?}): {}",
+        mask_secret(&secret)
+    );
+    Ok(())
+}
+
+/// Minimal keychain surface used by [`rotate_with`], so the commit/rollback
+/// logic can be exercised without a real credential store.
+trait SecretStore {
+    fn get(&self) -> anyhow::Result<Option<String>>;
+    fn set(&self, secret: &str) -> anyhow::Result<()>;
+    fn delete(&self) -> anyhow::Result<()>;
+}
+
+impl SecretStore for keyring::Entry {
+    fn get(&self) -> anyhow::Result<Option<String>> {
+        match self.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("keyring get_password failed: {e}")),
+        }
+    }
+
+    fn set(&self, secret: &str) -> anyhow::Result<()> {
+        self.set_password(secret)
+            .map_err(|e| anyhow::anyhow!("keyring set_password failed: {e}"))
+    }
+
+    fn delete(&self) -> anyhow::Result<()> {
+        self.delete_credential()
+            .map_err(|e| anyhow::anyhow!("keyring delete_credential failed: {e}"))
+    }
+}
+
+/// Store `new_secret`, run `validate` against it, and restore the previous
+/// value if validation fails.
+async fn rotate_with<S, F, Fut>(store: &S, new_secret: &str, validate: F) -> anyhow::Result<()>
+where
+    S: SecretStore + ?Sized,
+    F: FnOnce(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<()>>,
+{
+    let previous = store.get()?;
+    store.set(new_secret)?;
+
+    let Err(e) = validate(new_secret.to_owned()).await else {
+        return Ok(());
+    };
+
+    let restored = match &previous {
+        Some(old) => store.set(old),
+        None => store.delete(),
+    };
+    match restored {
+        Ok(()) => anyhow::bail!("new key rejected, previous key restored: {e}"),
+        Err(re) => anyhow::bail!("new key rejected ({e}) and rollback failed: {re}"),
+    }
+}
+
+/// Check a key with the provider's models-list endpoint.
+async fn validate_key(
+    client: &reqwest::Client,
+    provider: &sa_domain::config::ProviderConfig,
+    key: &str,
+) -> anyhow::Result<()> {
+    let resp = models_list_request(client, provider, key)?
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("validation request failed: {e}"))?;
+    let status = resp.status();
+    if !status.is_success() {
+        anyhow::bail!("provider '{}' returned HTTP {status}", provider.id);
+    }
+    Ok(())
+}
+
+/// Fail unless the provider has a models-list endpoint to validate keys with.
+fn supports_models_list(provider: &sa_domain::config::ProviderConfig) -> anyhow::Result<()> {
+    use sa_domain::config::ProviderKind;
+
+    match provider.kind {
+        ProviderKind::OpenaiCodexOauth | ProviderKind::AwsBedrock => anyhow::bail!(
+            "provider '{}' ({:?}) does not use a keychain API key that can be rotated",
+            provider.id,
+            provider.kind
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Build a cheap authenticated request that only succeeds with a valid key.
+fn models_list_request(
+    client: &reqwest::Client,
+    provider: &sa_domain::config::ProviderConfig,
+    key: &str,
+) -> anyhow::Result<reqwest::RequestBuilder> {
+    use sa_domain::config::ProviderKind;
+
+    supports_models_list(provider)?;
+    let base = provider.base_url.trim_end_matches('/');
+    let auth = &provider.auth;
+    let req = match provider.kind {
+        ProviderKind::OpenaiCompat => {
+            let header = auth.header.as_deref().unwrap_or("Authorization");
+            let prefix = auth.prefix.as_deref().unwrap_or("Bearer ");
+            client
+                .get(format!("{base}/models"))
+                .header(header, format!("{prefix}{key}"))
+        }
+        ProviderKind::AzureOpenai => {
+            let header = auth.header.as_deref().unwrap_or("api-key");
+            let prefix = auth.prefix.as_deref().unwrap_or("");
+            client
+                .get(format!("{base}/openai/models?api-version=2024-10-21"))
+                .header(header, format!("{prefix}{key}"))
+        }
+        ProviderKind::Anthropic => client
+            .get(format!("{base}/v1/models"))
+            .header("x-api-key", key)
+            .header("anthropic-version", "2023-06-01"),
+        ProviderKind::Google => client
+            .get(format!("{base}/v1beta/models"))
+            .query(&[("key", key)]),
+        ProviderKind::OpenaiCodexOauth | ProviderKind::AwsBedrock => {
+            unreachable!("rejected by supports_models_list")
+        }
+    };
+    Ok(req)
+}
+
 /// Mask a secret string: show first 4 + `...` + last 4.
 ///
 /// For short secrets (8 chars or fewer), replaces the entire value with `****`.
@@ -152,3 +421,147 @@ fn mask_secret(secret: &str) -> String {
     let suffix: String = chars[chars.len() - 4..].iter().collect();
     format!("{prefix}...{suffix}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_domain::config::TaskConfig;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<Option<String>>);
+
+    impl SecretStore for MemoryStore {
+        fn get(&self) -> anyhow::Result<Option<String>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+        fn set(&self, secret: &str) -> anyhow::Result<()> {
+            *self.0.lock().unwrap() = Some(secret.to_owned());
+            Ok(())
+        }
+        fn delete(&self) -> anyhow::Result<()> {
+            *self.0.lock().unwrap() = None;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn rotate_commits_when_validation_passes() {
+        let store = MemoryStore(Mutex::new(Some("old-key".into())));
+        rotate_with(&store, "new-key", |key| async move {
+            assert_eq!(key, "new-key");
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(store.get().unwrap().as_deref(), Some("new-key"));
+    }
+
+    #[tokio::test]
+    async fn rotate_restores_previous_key_on_failure() {
+        let store = MemoryStore(Mutex::new(Some("old-key".into())));
+        let err = rotate_with(&store, "bad-key", |_| async {
+            anyhow::bail!("HTTP 401 Unauthorized")
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("previous key restored"));
+        assert_eq!(store.get().unwrap().as_deref(), Some("old-key"));
+    }
+
+    #[tokio::test]
+    async fn rotate_removes_key_on_failure_when_none_existed() {
+        let store = MemoryStore::default();
+        rotate_with(&store, "bad-key", |_| async { anyhow::bail!("HTTP 403") })
+            .await
+            .unwrap_err();
+        assert_eq!(store.get().unwrap(), None);
+    }
+
+    const OLD_CONFIG: &str = r#"
+[serialmemory]
+base_url = "http://localhost:5000"
+
+[llm]
+require_provider = true
+"#;
+
+    #[test]
+    fn migrate_renames_and_adds_default_sections() {
+        let migration = migrate_config_str(OLD_CONFIG).unwrap();
+        let table: toml::Table = toml::from_str(&migration.output).unwrap();
+
+        assert!(!table.contains_key("serialmemory"));
+        assert_eq!(
+            table["serial_memory"]["base_url"].as_str(),
+            Some("http://localhost:5000")
+        );
+        assert_eq!(table["llm"]["startup_policy"].as_str(), Some("require_one"));
+        assert!(table["llm"].get("require_provider").is_none());
+
+        // Sections introduced since the old file was written get defaults.
+        let defaults = TaskConfig::default();
+        assert_eq!(
+            table["tasks"]["max_concurrent"].as_integer(),
+            Some(defaults.max_concurrent as i64)
+        );
+        assert!(table.contains_key("agents"));
+        assert!(migration
+            .changes
+            .contains(&"added [tasks] with defaults".to_string()));
+        assert!(migration
+            .changes
+            .contains(&"renamed [serialmemory] to [serial_memory]".to_string()));
+
+        // The result loads and is already fully migrated.
+        let config: Config = toml::from_str(&migration.output).unwrap();
+        assert_eq!(config.serial_memory.base_url, "http://localhost:5000");
+        assert!(migrate_config_str(&migration.output)
+            .unwrap()
+            .changes
+            .is_empty());
+    }
+
+    #[test]
+    fn migrate_prefers_existing_startup_policy() {
+        let raw = "[llm]\nrequire_provider = true\nstartup_policy = \"allow_none\"\n";
+        let migration = migrate_config_str(raw).unwrap();
+        let table: toml::Table = toml::from_str(&migration.output).unwrap();
+        assert_eq!(table["llm"]["startup_policy"].as_str(), Some("allow_none"));
+        assert!(table["llm"].get("require_provider").is_none());
+    }
+
+    #[test]
+    fn migrate_writes_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, OLD_CONFIG).unwrap();
+
+        migrate(path.to_str().unwrap()).unwrap();
+
+        let backups: Vec<_> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("config.toml.bak-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(std::fs::read_to_string(backups[0].path()).unwrap(), OLD_CONFIG);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("[serial_memory]"));
+    }
+
+    #[test]
+    fn models_list_request_rejects_oauth_providers() {
+        let provider: sa_domain::config::ProviderConfig = toml::from_str(
+            r#"
+            id = "codex"
+            kind = "openai_codex_oauth"
+            base_url = "https://chatgpt.com/backend-api"
+            "#,
+        )
+        .unwrap();
+        assert!(supports_models_list(&provider).is_err());
+        assert!(models_list_request(&reqwest::Client::new(), &provider, "k").is_err());
+    }
+}
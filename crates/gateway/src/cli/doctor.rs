@@ -25,6 +25,12 @@ pub async fn run(config: &Config, config_path: &str) -> anyhow::Result<bool> {
     // 5. Workspace directory
     check_workspace(config, &mut all_passed);
 
+    // 6. Skills (resource references in SKILL.md)
+    check_skill_resources(config, &mut all_passed);
+
+    // 7. Nodes (token, WS route, capability allowlists)
+    check_nodes(config, &mut all_passed).await;
+
     // Summary
     println!();
     if all_passed {
@@ -153,9 +159,374 @@ fn check_workspace(config: &Config, all_passed: &mut bool) {
     }
 }
 
+fn check_skill_resources(config: &Config, all_passed: &mut bool) {
+    let registry = match sa_skills::registry::SkillsRegistry::load(&config.skills.path) {
+        Ok(r) => r,
+        Err(e) => {
+            print_check("Skills load", false, e.to_string());
+            *all_passed = false;
+            return;
+        }
+    };
+    let dangling = registry.validate_resource_refs();
+    if dangling.is_empty() {
+        print_check(
+            "Skill resource references",
+            true,
+            format!("{} skill(s), no dangling references", registry.list().len()),
+        );
+    } else {
+        // A broken link degrades a skill but doesn't stop the gateway.
+        print_status(
+            "Skill resource references",
+            CheckStatus::Warn,
+            format!("{} dangling reference(s)", dangling.len()),
+        );
+        for d in &dangling {
+            println!("      {}: {} not found", d.skill, d.path);
+        }
+    }
+}
+
+async fn check_nodes(config: &Config, all_passed: &mut bool) {
+    let env = NodeEnv::from_env();
+    let probe = probe_gateway(config).await;
+    for d in node_diagnostics(&env, &probe) {
+        print_status(d.name, d.status, d.detail);
+        if d.status == CheckStatus::Fail {
+            *all_passed = false;
+        }
+    }
+}
+
+// ── Node diagnostics ──────────────────────────────────────────────────
+
+/// Node-related environment (see `nodes::ws` and `NodeRegistry`).
+#[derive(Debug, Default)]
+struct NodeEnv {
+    /// `SA_NODE_TOKEN`: one token for every node.
+    token: Option<String>,
+    /// `SA_NODE_TOKENS`: `node1:tokA,node2:tokB`.
+    tokens: Option<String>,
+    /// `SA_NODE_CAPS`: `node1:prefix1+prefix2,node2:prefix3`.
+    caps: Option<String>,
+}
+
+impl NodeEnv {
+    fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            token: var("SA_NODE_TOKEN"),
+            tokens: var("SA_NODE_TOKENS"),
+            caps: var("SA_NODE_CAPS"),
+        }
+    }
+
+    /// Node IDs from `SA_NODE_TOKENS`, plus entries that don't parse.
+    fn token_nodes(&self) -> (Vec<&str>, Vec<&str>) {
+        let mut ids = Vec::new();
+        let mut malformed = Vec::new();
+        for pair in self.tokens.as_deref().unwrap_or("").split(',') {
+            let pair = pair.trim();
+            match pair.split_once(':') {
+                Some((id, tok)) if !id.trim().is_empty() && !tok.trim().is_empty() => {
+                    ids.push(id.trim())
+                }
+                _ if pair.is_empty() => {}
+                _ => malformed.push(pair),
+            }
+        }
+        (ids, malformed)
+    }
+
+    /// Node IDs with a capability allowlist in `SA_NODE_CAPS`.
+    fn allowlisted_nodes(&self) -> Vec<&str> {
+        self.caps
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|e| e.split_once(':'))
+            .map(|(id, _)| id.trim())
+            .filter(|id| !id.is_empty())
+            .collect()
+    }
+}
+
+/// What a running gateway told us, if one answered.
+#[derive(Debug, Default)]
+struct GatewayProbe {
+    /// HTTP status of a plain GET on `/v1/nodes/ws` (`None` = unreachable).
+    ws_status: Option<u16>,
+    /// Node IDs currently connected, from `GET /v1/nodes`.
+    connected: Option<Vec<String>>,
+}
+
+async fn probe_gateway(config: &Config) -> GatewayProbe {
+    let base = super::gateway_base_url(config);
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+    else {
+        return GatewayProbe::default();
+    };
+
+    let token = super::gateway_api_token(config);
+    let get = |path: &str| {
+        let req = client.get(format!("{base}{path}"));
+        match &token {
+            Some(t) => req.bearer_auth(t),
+            None => req,
+        }
+    };
+
+    // Without upgrade headers the route answers 400/426 (or 401); only
+    // a 404 means it is missing.
+    let ws_status = get("/v1/nodes/ws")
+        .send()
+        .await
+        .ok()
+        .map(|r| r.status().as_u16());
+
+    let connected = match get("/v1/nodes").send().await {
+        Ok(r) if r.status().is_success() => r
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|v| {
+                v["nodes"].as_array().map(|nodes| {
+                    nodes
+                        .iter()
+                        .filter_map(|n| n["node_id"].as_str().map(str::to_owned))
+                        .collect()
+                })
+            }),
+        _ => None,
+    };
+
+    GatewayProbe {
+        ws_status,
+        connected,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+struct Diagnostic {
+    name: &'static str,
+    status: CheckStatus,
+    detail: String,
+}
+
+fn diag(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Diagnostic {
+    Diagnostic {
+        name,
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn node_diagnostics(env: &NodeEnv, probe: &GatewayProbe) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    let (token_ids, malformed) = env.token_nodes();
+    let allowlisted = env.allowlisted_nodes();
+    let connected = probe.connected.as_deref().unwrap_or_default();
+    let nodes_expected = !allowlisted.is_empty() || !connected.is_empty();
+
+    // Token
+    out.push(if !malformed.is_empty() {
+        diag(
+            "Node token",
+            CheckStatus::Fail,
+            format!("SA_NODE_TOKENS has malformed entries: {}", malformed.join(", ")),
+        )
+    } else if !token_ids.is_empty() {
+        diag(
+            "Node token",
+            CheckStatus::Pass,
+            format!("per-node tokens for {}", token_ids.join(", ")),
+        )
+    } else if env.token.is_some() {
+        diag("Node token", CheckStatus::Pass, "SA_NODE_TOKEN set (global)")
+    } else if nodes_expected {
+        diag(
+            "Node token",
+            CheckStatus::Fail,
+            "nodes are configured but neither SA_NODE_TOKEN nor SA_NODE_TOKENS is set \
+             (any client can connect as a node)",
+        )
+    } else {
+        diag(
+            "Node token",
+            CheckStatus::Warn,
+            "no SA_NODE_TOKEN; node connections are unauthenticated (dev mode)",
+        )
+    });
+
+    // WS route
+    out.push(match probe.ws_status {
+        Some(404) => diag("Node WS route", CheckStatus::Fail, "/v1/nodes/ws returned 404"),
+        Some(code) => diag(
+            "Node WS route",
+            CheckStatus::Pass,
+            format!("/v1/nodes/ws reachable (HTTP {code})"),
+        ),
+        None => diag(
+            "Node WS route",
+            CheckStatus::Warn,
+            "gateway not reachable; start it to verify /v1/nodes/ws",
+        ),
+    });
+
+    // Capability allowlists
+    if !allowlisted.is_empty() {
+        let token_known = |id: &str| token_ids.contains(&id);
+        let seen = |id: &str| connected.iter().any(|c| c == id);
+        let unauthorized: Vec<&str> = allowlisted
+            .iter()
+            .copied()
+            .filter(|id| !token_ids.is_empty() && !token_known(id))
+            .collect();
+        let unseen: Vec<&str> = allowlisted
+            .iter()
+            .copied()
+            .filter(|id| !token_known(id) && !seen(id))
+            .collect();
+
+        out.push(if !unauthorized.is_empty() {
+            diag(
+                "Node allowlists",
+                CheckStatus::Fail,
+                format!(
+                    "SA_NODE_CAPS lists node(s) with no SA_NODE_TOKENS entry: {}",
+                    unauthorized.join(", ")
+                ),
+            )
+        } else if !unseen.is_empty() {
+            diag(
+                "Node allowlists",
+                CheckStatus::Warn,
+                format!(
+                    "SA_NODE_CAPS lists node(s) never seen by the gateway: {}",
+                    unseen.join(", ")
+                ),
+            )
+        } else {
+            diag(
+                "Node allowlists",
+                CheckStatus::Pass,
+                format!("{} node(s) restricted", allowlisted.len()),
+            )
+        });
+    }
+
+    out
+}
+
 // ── Formatting helper ─────────────────────────────────────────────────
 
 fn print_check(name: &str, passed: bool, detail: String) {
-    let status = if passed { "PASS" } else { "FAIL" };
+    let status = if passed {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail
+    };
+    print_status(name, status, detail);
+}
+
+fn print_status(name: &str, status: CheckStatus, detail: String) {
+    let status = match status {
+        CheckStatus::Pass => "PASS",
+        CheckStatus::Warn => "WARN",
+        CheckStatus::Fail => "FAIL",
+    };
     println!("  [{status}] {name}: {detail}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(token: Option<&str>, tokens: Option<&str>, caps: Option<&str>) -> NodeEnv {
+        NodeEnv {
+            token: token.map(Into::into),
+            tokens: tokens.map(Into::into),
+            caps: caps.map(Into::into),
+        }
+    }
+
+    fn status_of(diags: &[Diagnostic], name: &str) -> Option<CheckStatus> {
+        diags.iter().find(|d| d.name == name).map(|d| d.status)
+    }
+
+    fn running(connected: &[&str]) -> GatewayProbe {
+        GatewayProbe {
+            ws_status: Some(400),
+            connected: Some(connected.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn missing_token_fails_when_nodes_are_expected() {
+        let diags = node_diagnostics(&env(None, None, Some("mac1:macos.notes")), &running(&[]));
+        assert_eq!(status_of(&diags, "Node token"), Some(CheckStatus::Fail));
+
+        let diags = node_diagnostics(&env(None, None, None), &running(&["pi"]));
+        assert_eq!(status_of(&diags, "Node token"), Some(CheckStatus::Fail));
+    }
+
+    #[test]
+    fn missing_token_only_warns_without_nodes() {
+        let diags = node_diagnostics(&env(None, None, None), &GatewayProbe::default());
+        assert_eq!(status_of(&diags, "Node token"), Some(CheckStatus::Warn));
+        assert_eq!(status_of(&diags, "Node WS route"), Some(CheckStatus::Warn));
+        assert_eq!(status_of(&diags, "Node allowlists"), None);
+    }
+
+    #[test]
+    fn malformed_per_node_tokens_fail() {
+        let diags = node_diagnostics(&env(None, Some("mac1:tok,broken"), None), &running(&[]));
+        let token = diags.iter().find(|d| d.name == "Node token").unwrap();
+        assert_eq!(token.status, CheckStatus::Fail);
+        assert!(token.detail.contains("broken"));
+    }
+
+    #[test]
+    fn allowlist_for_never_seen_node_warns() {
+        let diags = node_diagnostics(
+            &env(Some("secret"), None, Some("mac1:macos.notes,ghost:home")),
+            &running(&["mac1"]),
+        );
+        assert_eq!(status_of(&diags, "Node token"), Some(CheckStatus::Pass));
+        let allow = diags.iter().find(|d| d.name == "Node allowlists").unwrap();
+        assert_eq!(allow.status, CheckStatus::Warn);
+        assert!(allow.detail.contains("ghost"));
+        assert!(!allow.detail.contains("mac1"));
+    }
+
+    #[test]
+    fn allowlist_for_node_without_token_fails() {
+        let diags = node_diagnostics(
+            &env(None, Some("mac1:tokA"), Some("mac1:macos,ghost:home")),
+            &GatewayProbe::default(),
+        );
+        let allow = diags.iter().find(|d| d.name == "Node allowlists").unwrap();
+        assert_eq!(allow.status, CheckStatus::Fail);
+        assert!(allow.detail.contains("ghost"));
+    }
+
+    #[test]
+    fn ws_route_404_fails() {
+        let probe = GatewayProbe {
+            ws_status: Some(404),
+            connected: None,
+        };
+        let diags = node_diagnostics(&env(Some("t"), None, None), &probe);
+        assert_eq!(status_of(&diags, "Node WS route"), Some(CheckStatus::Fail));
+    }
+}
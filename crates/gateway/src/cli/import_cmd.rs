@@ -2,12 +2,14 @@
 //!
 //! Subcommands:
 //!   serialagent import preview   --path ~/.openclaw
-//!   serialagent import apply     <staging-id> --strategy merge_safe
+//!   serialagent import apply     <staging-id> --strategy merge_safe [--agent main] [--dry-run]
+//!   serialagent import rollback  <apply-id>
 //!   serialagent import staging-list
 //!   serialagent import staging-delete <id>
 
 use crate::api::import_openclaw::{
-    ImportApplyRequest, ImportOptions, ImportSource, MergeStrategy,
+    ConflictAction, ImportApplyRequest, ImportConflictsRequest, ImportConflictsResponse,
+    ImportOptions, ImportSource, MergeStrategy,
 };
 use crate::cli::ImportCommand;
 use crate::import::openclaw;
@@ -42,9 +44,23 @@ pub async fn run(config: Config, cmd: ImportCommand) -> anyhow::Result<()> {
         ImportCommand::Apply {
             staging_id,
             strategy,
+            agents,
+            workspaces,
+            dry_run,
         } => {
-            run_apply(&import_root, workspace_dest, sessions_dest, staging_id, strategy).await
+            run_apply(
+                &import_root,
+                workspace_dest,
+                sessions_dest,
+                staging_id,
+                strategy,
+                agents,
+                workspaces,
+                dry_run,
+            )
+            .await
         }
+        ImportCommand::Rollback { apply_id } => run_rollback(&import_root, apply_id).await,
         ImportCommand::StagingList => run_staging_list(&import_root).await,
         ImportCommand::StagingDelete { id } => run_staging_delete(&import_root, id).await,
     }
@@ -118,12 +134,16 @@ async fn run_preview(
 
 // ── Apply ───────────────────────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 async fn run_apply(
     import_root: &std::path::Path,
     workspace_dest: &std::path::Path,
     sessions_dest: &std::path::Path,
     staging_id: String,
     strategy: String,
+    only_agents: Vec<String>,
+    only_workspaces: Vec<String>,
+    dry_run: bool,
 ) -> anyhow::Result<()> {
     let staging_uuid: uuid::Uuid = staging_id
         .parse()
@@ -131,10 +151,32 @@ async fn run_apply(
 
     let merge_strategy = parse_merge_strategy(&strategy)?;
 
+    if dry_run {
+        let req = ImportConflictsRequest {
+            merge_strategy,
+            options: ImportOptions::default(),
+            only_agents,
+            only_workspaces,
+        };
+        let plan = openclaw::find_import_conflicts(
+            staging_uuid,
+            req,
+            import_root,
+            workspace_dest,
+            sessions_dest,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+        print!("{}", render_plan(&plan));
+        return Ok(());
+    }
+
     let req = ImportApplyRequest {
         staging_id: staging_uuid,
         merge_strategy,
         options: ImportOptions::default(),
+        only_agents,
+        only_workspaces,
     };
 
     let result =
@@ -146,6 +188,7 @@ async fn run_apply(
     println!("  Workspaces: {}", result.imported.workspaces.join(", "));
     println!("  Agents:     {}", result.imported.agents.join(", "));
     println!("  Sessions copied: {}", result.imported.sessions_copied);
+    println!("  Apply ID:   {} (undo with `import rollback`)", result.apply_id);
 
     if !result.warnings.is_empty() {
         println!();
@@ -158,6 +201,68 @@ async fn run_apply(
     Ok(())
 }
 
+/// Human-readable dry-run plan for `import apply --dry-run`.
+fn render_plan(plan: &ImportConflictsResponse) -> String {
+    use std::fmt::Write;
+
+    let strategy = match plan.merge_strategy {
+        MergeStrategy::MergeSafe => "merge_safe",
+        MergeStrategy::Replace => "replace",
+        MergeStrategy::SkipExisting => "skip_existing",
+    };
+    let t = &plan.totals;
+    let mut out = String::new();
+    let _ = writeln!(out, "Dry run: nothing was written.");
+    let _ = writeln!(out, "  Strategy:    {strategy}");
+    let _ = writeln!(out, "  New files:   {}", t.created);
+    let _ = writeln!(out, "  Overwritten: {}", t.overwritten);
+    let _ = writeln!(out, "  Skipped:     {}", t.skipped);
+    let _ = writeln!(out, "  Deleted:     {}", t.deleted);
+    let _ = writeln!(out, "  Relocated:   {}", t.relocated);
+
+    if !plan.conflicts.is_empty() {
+        let _ = writeln!(out);
+        for c in &plan.conflicts {
+            let action = match c.action {
+                ConflictAction::Skip => "skip",
+                ConflictAction::Overwrite => "overwrite",
+                ConflictAction::Delete => "delete",
+                ConflictAction::Relocate => "relocate",
+            };
+            let _ = writeln!(out, "  {action:<9}  {} -> {}", c.source, c.dest);
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "To apply: serialagent import apply {} --strategy {strategy}",
+        plan.staging_id
+    );
+    out
+}
+
+// ── Rollback ────────────────────────────────────────────────────────
+
+async fn run_rollback(import_root: &std::path::Path, apply_id: String) -> anyhow::Result<()> {
+    let apply_uuid: uuid::Uuid = apply_id
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid apply ID: {apply_id}"))?;
+
+    let result = openclaw::rollback_openclaw_import(apply_uuid, import_root)
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    println!("Import {apply_id} rolled back.");
+    println!("  Files removed:  {}", result.removed);
+    println!("  Files restored: {}", result.restored);
+    for w in &result.warnings {
+        println!("  - {w}");
+    }
+
+    Ok(())
+}
+
 // ── Staging list ────────────────────────────────────────────────────
 
 async fn run_staging_list(import_root: &std::path::Path) -> anyhow::Result<()> {
@@ -213,3 +318,87 @@ fn parse_merge_strategy(s: &str) -> anyhow::Result<MergeStrategy> {
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Every file under `root` with its content.
+    fn snapshot(root: &Path) -> Vec<(String, String)> {
+        let mut files = Vec::new();
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            for e in std::fs::read_dir(&dir).unwrap().flatten() {
+                let path = e.path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let rel = path.strip_prefix(root).unwrap().to_string_lossy().to_string();
+                    files.push((rel, std::fs::read_to_string(&path).unwrap()));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn dry_run_prints_plan_and_leaves_destinations_unchanged() {
+        let import_root = tempfile::tempdir().unwrap();
+        let ws = tempfile::tempdir().unwrap();
+        let sess = tempfile::tempdir().unwrap();
+
+        let staging_id = uuid::Uuid::new_v4();
+        let x = import_root.path().join(staging_id.to_string()).join("extracted");
+        write(&x.join("workspace/AGENTS.md"), "staged");
+        write(&x.join("workspace/SOUL.md"), "staged");
+        write(&x.join("agents/main/sessions/s1.jsonl"), "{}\n");
+        write(&ws.path().join("workspace/AGENTS.md"), "existing");
+        write(&ws.path().join("workspace/NOTES.md"), "existing");
+
+        let before = (snapshot(ws.path()), snapshot(sess.path()));
+        let req = ImportConflictsRequest {
+            merge_strategy: MergeStrategy::Replace,
+            options: ImportOptions::default(),
+            only_agents: Vec::new(),
+            only_workspaces: Vec::new(),
+        };
+        let plan = openclaw::find_import_conflicts(
+            staging_id,
+            req,
+            import_root.path(),
+            ws.path(),
+            sess.path(),
+        )
+        .await
+        .unwrap();
+        let text = render_plan(&plan);
+        assert!(text.contains("Strategy:    replace"), "{text}");
+        assert!(text.contains("New files:   2"), "{text}");
+        assert!(text.contains("Overwritten: 1"), "{text}");
+        assert!(text.contains("Deleted:     1"), "{text}");
+        assert!(text.contains("overwrite  workspace/AGENTS.md"), "{text}");
+        assert!(text.contains("delete     workspace/NOTES.md"), "{text}");
+
+        run_apply(
+            import_root.path(),
+            ws.path(),
+            sess.path(),
+            staging_id.to_string(),
+            "replace".into(),
+            Vec::new(),
+            Vec::new(),
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!((snapshot(ws.path()), snapshot(sess.path())), before);
+        assert!(!import_root.path().join("applies").exists());
+    }
+}
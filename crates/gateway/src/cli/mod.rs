@@ -6,7 +6,9 @@ pub mod init;
 pub mod login;
 pub mod pid;
 pub mod run;
+pub mod sd_notify;
 pub mod systemd;
+pub mod tail;
 
 use clap::{Parser, Subcommand};
 
@@ -46,6 +48,9 @@ pub enum Command {
         /// Output the full response as JSON instead of plain text.
         #[arg(long)]
         json: bool,
+        /// Print assistant deltas and tool calls as they arrive.
+        #[arg(long)]
+        stream: bool,
     },
     /// Start an interactive chat session with the agent.
     Chat {
@@ -64,6 +69,21 @@ pub enum Command {
     /// Import data from external systems (e.g. OpenClaw).
     #[command(subcommand)]
     Import(ImportCommand),
+    /// Follow a run's events, or new deliveries, from a running gateway.
+    Tail {
+        /// Run ID to follow (default: follow deliveries).
+        #[arg(long)]
+        run: Option<String>,
+        /// Only show run/node events with this status (repeatable).
+        #[arg(long)]
+        status: Vec<String>,
+        /// Only show deliveries from this schedule (ID or name).
+        #[arg(long)]
+        schedule: Option<String>,
+        /// Gateway base URL (default: from `[server]` in config).
+        #[arg(long)]
+        url: Option<String>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -88,6 +108,9 @@ pub enum ConfigCommand {
     Validate,
     /// Dump the resolved configuration (with defaults) as TOML.
     Show,
+    /// Upgrade an old config file in place (renamed keys, new sections),
+    /// keeping a timestamped backup.
+    Migrate,
     /// Store an API key in the OS keychain for a provider.
     SetSecret {
         /// Provider ID from config.toml.
@@ -98,6 +121,11 @@ pub enum ConfigCommand {
         /// Provider ID from config.toml.
         provider_id: String,
     },
+    /// Replace a provider's keychain API key after validating it.
+    RotateSecret {
+        /// Provider ID from config.toml.
+        provider_id: String,
+    },
     /// Authenticate with an OAuth provider (e.g. OpenAI Codex).
     Login {
         /// Provider ID from config.toml (must use oauth_device auth mode).
@@ -129,6 +157,21 @@ pub enum ImportCommand {
         /// Merge strategy: merge_safe, replace, or skip_existing.
         #[arg(long, default_value = "merge_safe")]
         strategy: String,
+        /// Only import this agent (repeatable; default: all).
+        #[arg(long = "agent")]
+        agents: Vec<String>,
+        /// Only import this workspace (repeatable; default: all).
+        #[arg(long = "workspace")]
+        workspaces: Vec<String>,
+        /// Print what would be copied, skipped, or overwritten and exit
+        /// without touching the destinations.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Undo an applied import.
+    Rollback {
+        /// Apply ID printed by `import apply`.
+        apply_id: String,
     },
     /// List all staged imports.
     StagingList,
@@ -141,6 +184,11 @@ pub enum ImportCommand {
 
 // ── Config loading helper ─────────────────────────────────────────────
 
+/// Path of the config file: `$SA_CONFIG`, else `config.toml`.
+pub fn config_path() -> String {
+    std::env::var("SA_CONFIG").unwrap_or_else(|_| "config.toml".into())
+}
+
 /// Load the configuration from the path specified by `SA_CONFIG` (or
 /// `config.toml` by default).  Returns the parsed [`Config`] and the
 /// path that was used.
@@ -148,8 +196,7 @@ pub enum ImportCommand {
 /// This is shared by `serve`, `doctor`, and `config` subcommands so the
 /// logic lives in one place.
 pub fn load_config() -> anyhow::Result<(sa_domain::config::Config, String)> {
-    let config_path =
-        std::env::var("SA_CONFIG").unwrap_or_else(|_| "config.toml".into());
+    let config_path = config_path();
 
     let config = if std::path::Path::new(&config_path).exists() {
         let raw = std::fs::read_to_string(&config_path)
@@ -162,3 +209,25 @@ pub fn load_config() -> anyhow::Result<(sa_domain::config::Config, String)> {
 
     Ok((config, config_path))
 }
+
+// ── Gateway client helpers ────────────────────────────────────────────
+
+/// Base URL of the gateway described by `config`.  Wildcard bind
+/// addresses are reached over loopback.
+pub(crate) fn gateway_base_url(config: &sa_domain::config::Config) -> String {
+    let host = match config.server.host.as_str() {
+        "0.0.0.0" | "::" | "[::]" => "127.0.0.1",
+        h => h,
+    };
+    format!("http://{host}:{}", config.server.port)
+}
+
+/// Bearer token for calling the gateway: `server.api_token`, else the env
+/// var named by `server.api_token_env`.
+pub(crate) fn gateway_api_token(config: &sa_domain::config::Config) -> Option<String> {
+    config
+        .server
+        .api_token
+        .clone()
+        .or_else(|| std::env::var(&config.server.api_token_env).ok())
+}
@@ -1,11 +1,15 @@
 //! `serialagent run` — one-shot execution command.
 //!
-//! Sends a single message to the agent, streams the response to stdout,
+//! Sends a single message to the agent, prints the response to stdout,
 //! and exits.  Useful for scripting, piping, and quick CLI interactions.
+//! With `--stream`, assistant deltas and tool calls are printed as they
+//! arrive instead of only the final answer.
 
 use std::io::Write;
 use std::sync::Arc;
 
+use tokio::sync::mpsc;
+
 use sa_domain::config::Config;
 use sa_sessions::store::SessionOrigin;
 
@@ -21,6 +25,7 @@ pub async fn run(
     session_key: String,
     model: Option<String>,
     json_output: bool,
+    stream: bool,
 ) -> anyhow::Result<()> {
     // 1. Boot the full runtime (without background tasks).
     let state = bootstrap::build_app_state(
@@ -44,63 +49,205 @@ pub async fn run(
         response_format: None,
         agent: None,
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: None,
+        stop: Vec::new(),
     };
 
     // 4. Run the turn and obtain the event receiver.
     let (_run_id, mut rx) = run_turn(state.clone(), input);
 
-    // 5. Drain the receiver, printing events to stdout/stderr.
-    let mut exit_code: i32 = 0;
+    // 5. Drain the receiver, rendering events per the output mode.
+    let mode = if json_output {
+        OutputMode::Json
+    } else if stream {
+        OutputMode::Stream
+    } else {
+        OutputMode::Final
+    };
+    let ok = render_turn(
+        &mut rx,
+        mode,
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+    )
+    .await?;
+    let exit_code: i32 = if ok { 0 } else { 1 };
+
+    // 7. Flush session store before exit.
+    if let Err(e) = state.sessions.flush().await {
+        tracing::warn!(error = %e, "session store flush on exit failed");
+    }
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// How [`render_turn`] prints a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputMode {
+    /// Only the final answer.
+    Final,
+    /// Deltas, thoughts, and tool calls as they arrive.
+    Stream,
+    /// All events as one JSON array once the turn ends.
+    Json,
+}
+
+/// Drain `rx`, writing the answer to `out` and diagnostics to `err`.
+///
+/// Returns `false` if the turn reported an error.
+async fn render_turn(
+    rx: &mut mpsc::Receiver<TurnEvent>,
+    mode: OutputMode,
+    out: &mut impl Write,
+    err: &mut impl Write,
+) -> anyhow::Result<bool> {
+    let mut ok = true;
+    let mut streamed = false;
     let mut collected_events: Vec<TurnEvent> = Vec::new();
 
     while let Some(event) = rx.recv().await {
-        if json_output {
+        if mode == OutputMode::Json {
             collected_events.push(event);
-        } else {
-            match &event {
-                TurnEvent::AssistantDelta { text } => {
-                    print!("{text}");
-                    std::io::stdout().flush().ok();
-                }
-                TurnEvent::Thought { content } => {
-                    // Dim output to stderr so it doesn't pollute stdout.
-                    eprint!("\x1b[2m{content}\x1b[0m");
-                    std::io::stderr().flush().ok();
-                }
-                TurnEvent::ToolCallEvent { tool_name, .. } => {
-                    eprintln!("\x1b[2m[tool: {tool_name}]\x1b[0m");
+            continue;
+        }
+        let stream = mode == OutputMode::Stream;
+        match &event {
+            TurnEvent::AssistantDelta { text } if stream => {
+                write!(out, "{text}")?;
+                out.flush()?;
+                streamed = true;
+            }
+            TurnEvent::Thought { content } if stream => {
+                // Dim output to stderr so it doesn't pollute stdout.
+                write!(err, "\x1b[2m{content}\x1b[0m")?;
+                err.flush()?;
+            }
+            TurnEvent::ToolCallEvent { tool_name, .. } if stream => {
+                if streamed {
+                    // Keep the notice off the partially printed line.
+                    writeln!(out)?;
+                    out.flush()?;
+                    streamed = false;
                 }
-                TurnEvent::Final { .. } => {
+                writeln!(err, "\x1b[2m[tool: {tool_name}]\x1b[0m")?;
+            }
+            TurnEvent::Final { content } => {
+                if streamed {
                     // Ensure a trailing newline after streamed deltas.
-                    println!();
+                    writeln!(out)?;
+                } else {
+                    writeln!(out, "{content}")?;
                 }
-                TurnEvent::Error { message } => {
-                    eprintln!("error: {message}");
-                    exit_code = 1;
-                }
-                TurnEvent::Stopped { .. } => {
-                    eprintln!("turn stopped");
+            }
+            TurnEvent::Error { message } => {
+                writeln!(err, "error: {message}")?;
+                ok = false;
+            }
+            TurnEvent::Stopped { content } => {
+                if !streamed && !content.is_empty() {
+                    writeln!(out, "{content}")?;
+                } else if streamed {
+                    writeln!(out)?;
                 }
-                _ => {}
+                writeln!(err, "turn stopped")?;
             }
+            _ => {}
         }
     }
 
-    // 6. In JSON mode, serialize all collected events to stdout.
-    if json_output {
+    // In JSON mode, serialize all collected events at the end.
+    if mode == OutputMode::Json {
         let json = serde_json::to_string_pretty(&collected_events)
             .map_err(|e| anyhow::anyhow!("serializing events: {e}"))?;
-        println!("{json}");
+        writeln!(out, "{json}")?;
     }
+    out.flush()?;
 
-    // 7. Flush session store before exit.
-    if let Err(e) = state.sessions.flush().await {
-        tracing::warn!(error = %e, "session store flush on exit failed");
+    Ok(ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `events` through a channel the way `run_turn` does, then render.
+    async fn render(events: Vec<TurnEvent>, mode: OutputMode) -> (String, String, bool) {
+        let (tx, mut rx) = mpsc::channel(64);
+        tokio::spawn(async move {
+            for e in events {
+                tx.send(e).await.unwrap();
+            }
+        });
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let ok = render_turn(&mut rx, mode, &mut out, &mut err).await.unwrap();
+        (
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+            ok,
+        )
     }
 
-    if exit_code != 0 {
-        std::process::exit(exit_code);
+    fn turn() -> Vec<TurnEvent> {
+        vec![
+            TurnEvent::AssistantDelta { text: "Let me ".into() },
+            TurnEvent::AssistantDelta { text: "check.".into() },
+            TurnEvent::ToolCallEvent {
+                call_id: "c1".into(),
+                tool_name: "exec".into(),
+                arguments: serde_json::json!({ "command": "date" }),
+            },
+            TurnEvent::ToolResult {
+                call_id: "c1".into(),
+                tool_name: "exec".into(),
+                content: "Mon".into(),
+                is_error: false,
+            },
+            TurnEvent::AssistantDelta { text: "It is ".into() },
+            TurnEvent::AssistantDelta { text: "Monday.".into() },
+            TurnEvent::Final {
+                content: "It is Monday.".into(),
+            },
+        ]
     }
 
-    Ok(())
+    #[tokio::test]
+    async fn stream_prints_deltas_in_order() {
+        let (out, err, ok) = render(turn(), OutputMode::Stream).await;
+        assert!(ok);
+        assert_eq!(out, "Let me check.\nIt is Monday.\n");
+        assert!(err.contains("[tool: exec]"));
+    }
+
+    #[tokio::test]
+    async fn default_prints_only_the_final_answer() {
+        let (out, err, ok) = render(turn(), OutputMode::Final).await;
+        assert!(ok);
+        assert_eq!(out, "It is Monday.\n");
+        assert!(err.is_empty());
+    }
+
+    #[tokio::test]
+    async fn json_aggregates_all_events() {
+        let (out, _, _) = render(turn(), OutputMode::Json).await;
+        let events: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert_eq!(events.len(), 7);
+        assert_eq!(events[6]["type"], "final");
+    }
+
+    #[tokio::test]
+    async fn errors_are_reported() {
+        let events = vec![TurnEvent::Error {
+            message: "provider down".into(),
+        }];
+        let (out, err, ok) = render(events, OutputMode::Final).await;
+        assert!(!ok);
+        assert!(out.is_empty());
+        assert!(err.contains("provider down"));
+    }
 }
@@ -0,0 +1,184 @@
+//! Optional systemd `sd_notify` integration.
+//!
+//! When the gateway is started by systemd with `Type=notify`, the service
+//! manager passes a datagram socket path in `NOTIFY_SOCKET`.  We send
+//! `READY=1` once the HTTP listener is bound, `WATCHDOG=1` pings at half
+//! of `WATCHDOG_USEC`, and `STOPPING=1` when shutdown begins.  Without
+//! `NOTIFY_SOCKET` every function here is a no-op.
+
+use std::time::Duration;
+
+/// Connection to the systemd notification socket.
+#[derive(Debug)]
+pub struct Notifier {
+    #[cfg(unix)]
+    socket: std::os::unix::net::UnixDatagram,
+}
+
+impl Notifier {
+    /// Connect to the socket named by `NOTIFY_SOCKET`, if set.
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var("NOTIFY_SOCKET").ok()?;
+        match Self::connect(&path) {
+            Ok(n) => Some(n),
+            Err(e) => {
+                tracing::warn!(socket = %path, error = %e, "sd_notify: cannot connect to NOTIFY_SOCKET");
+                None
+            }
+        }
+    }
+
+    /// Connect to a notification socket path.  A leading `@` denotes a
+    /// Linux abstract-namespace socket, as systemd uses by default.
+    #[cfg(unix)]
+    pub fn connect(path: &str) -> std::io::Result<Self> {
+        use std::os::unix::net::UnixDatagram;
+
+        let socket = UnixDatagram::unbound()?;
+        match path.strip_prefix('@') {
+            #[cfg(target_os = "linux")]
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.connect_addr(&addr)?;
+            }
+            #[cfg(not(target_os = "linux"))]
+            Some(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "abstract notify sockets are Linux-only",
+                ));
+            }
+            None => socket.connect(path)?,
+        }
+        Ok(Self { socket })
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect(_path: &str) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "sd_notify requires Unix domain sockets",
+        ))
+    }
+
+    /// Send a raw state string (e.g. `"READY=1"`).
+    pub fn notify(&self, state: &str) -> std::io::Result<()> {
+        #[cfg(unix)]
+        self.socket.send(state.as_bytes())?;
+        #[cfg(not(unix))]
+        let _ = state;
+        Ok(())
+    }
+
+    fn notify_or_warn(&self, state: &str) {
+        if let Err(e) = self.notify(state) {
+            tracing::warn!(state, error = %e, "sd_notify send failed");
+        }
+    }
+
+    /// Report that the service finished starting up.
+    pub fn ready(&self) {
+        self.notify_or_warn("READY=1");
+    }
+
+    /// Report that the service is shutting down.
+    pub fn stopping(&self) {
+        self.notify_or_warn("STOPPING=1");
+    }
+}
+
+/// Watchdog ping interval derived from `WATCHDOG_USEC` (half the timeout),
+/// or `None` when the watchdog is disabled or targets another process.
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.trim() != std::process::id().to_string() {
+            return None;
+        }
+    }
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.trim().parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Send `WATCHDOG=1` every `interval` for as long as the runtime is alive.
+pub fn spawn_watchdog(
+    notifier: std::sync::Arc<Notifier>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(interval);
+        loop {
+            tick.tick().await;
+            notifier.notify_or_warn("WATCHDOG=1");
+        }
+    })
+}
+
+/// Bind the gateway listener, then report readiness to systemd.
+///
+/// `READY=1` is only sent once the socket is accepting connections, so
+/// units ordered `After=serialagent.service` never race the bind.
+pub async fn bind_and_notify(
+    addr: &str,
+    notifier: Option<&Notifier>,
+) -> std::io::Result<tokio::net::TcpListener> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    if let Some(n) = notifier {
+        n.ready();
+    }
+    Ok(listener)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixDatagram;
+
+    #[tokio::test]
+    async fn ready_is_sent_after_bind() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sock_path = tmp.path().join("notify.sock");
+        let systemd = UnixDatagram::bind(&sock_path).unwrap();
+        systemd.set_nonblocking(true).unwrap();
+
+        let notifier = Notifier::connect(sock_path.to_str().unwrap()).unwrap();
+
+        // Nothing is sent before the listener exists.
+        let mut buf = [0u8; 64];
+        assert!(systemd.recv(&mut buf).is_err());
+
+        let listener = bind_and_notify("127.0.0.1:0", Some(&notifier))
+            .await
+            .unwrap();
+
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+        // The listener is already accepting connections when READY arrives.
+        tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn no_notifier_just_binds() {
+        let listener = bind_and_notify("127.0.0.1:0", None).await.unwrap();
+        assert!(listener.local_addr().unwrap().port() > 0);
+    }
+
+    #[test]
+    fn stopping_and_raw_states_are_forwarded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sock_path = tmp.path().join("notify.sock");
+        let systemd = UnixDatagram::bind(&sock_path).unwrap();
+        let notifier = Notifier::connect(sock_path.to_str().unwrap()).unwrap();
+
+        notifier.notify("WATCHDOG=1").unwrap();
+        notifier.stopping();
+
+        let mut buf = [0u8; 64];
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"WATCHDOG=1");
+        let n = systemd.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"STOPPING=1");
+    }
+}
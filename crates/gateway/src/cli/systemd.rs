@@ -13,6 +13,18 @@ pub fn generate(user: &str, working_dir: Option<&str>, config_path: &str) {
         });
 
     println!(
+        "{}",
+        render_unit(user, &resolved_working_dir, &exe_path, config_path)
+    );
+}
+
+/// Render the unit file text.
+///
+/// `Type=notify` relies on the gateway sending `READY=1` after it binds
+/// (see [`super::sd_notify`]); `WatchdogSec` restarts it if the runtime
+/// stops sending keepalives.
+fn render_unit(user: &str, working_dir: &str, exe_path: &str, config_path: &str) -> String {
+    format!(
         "\
 [Unit]
 Description=SerialAgent AI Gateway
@@ -20,7 +32,9 @@ After=network-online.target
 Wants=network-online.target
 
 [Service]
-Type=simple
+Type=notify
+NotifyAccess=main
+WatchdogSec=30
 User={user}
 WorkingDirectory={working_dir}
 ExecStart={exe_path} serve
@@ -41,57 +55,21 @@ ReadWritePaths={working_dir}/data
 PrivateTmp=true
 
 [Install]
-WantedBy=multi-user.target",
-        user = user,
-        working_dir = resolved_working_dir,
-        exe_path = exe_path,
-        config_path = config_path,
-    );
+WantedBy=multi-user.target"
+    )
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn generate_contains_expected_sections() {
-        // Capture output by calling the function logic directly.
-        let exe_path = std::env::current_exe()
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|_| "/usr/local/bin/serialagent".to_string());
-
-        let working_dir = "/opt/serialagent";
-        let user = "sa-test";
-        let config_path = "config.toml";
-
-        let output = format!(
-            "\
-[Unit]
-Description=SerialAgent AI Gateway
-After=network-online.target
-Wants=network-online.target
-
-[Service]
-Type=simple
-User={user}
-WorkingDirectory={working_dir}
-ExecStart={exe_path} serve
-Environment=SA_CONFIG={config_path}
-Restart=on-failure
-RestartSec=5
-
-# Logging
-StandardOutput=journal
-StandardError=journal
-SyslogIdentifier=serialagent
-
-# Security hardening
-NoNewPrivileges=true
-ProtectSystem=strict
-ProtectHome=read-only
-ReadWritePaths={working_dir}/data
-PrivateTmp=true
-
-[Install]
-WantedBy=multi-user.target",
+        let output = render_unit(
+            "sa-test",
+            "/opt/serialagent",
+            "/usr/local/bin/serialagent",
+            "config.toml",
         );
 
         assert!(output.contains("[Unit]"));
@@ -99,8 +77,17 @@ WantedBy=multi-user.target",
         assert!(output.contains("[Install]"));
         assert!(output.contains("User=sa-test"));
         assert!(output.contains("WorkingDirectory=/opt/serialagent"));
+        assert!(output.contains("ExecStart=/usr/local/bin/serialagent serve"));
         assert!(output.contains("Environment=SA_CONFIG=config.toml"));
         assert!(output.contains("ReadWritePaths=/opt/serialagent/data"));
         assert!(output.contains("WantedBy=multi-user.target"));
     }
+
+    #[test]
+    fn generate_uses_notify_with_watchdog() {
+        let output = render_unit("u", "/w", "/bin/sa", "c.toml");
+        assert!(output.contains("Type=notify"));
+        assert!(!output.contains("Type=simple"));
+        assert!(output.contains("WatchdogSec="));
+    }
 }
@@ -0,0 +1,319 @@
+//! `serialagent tail` — live run/delivery monitoring.
+//!
+//! Connects to a running gateway's SSE endpoints and pretty-prints events
+//! as they arrive.  With `--run <id>` it follows `/v1/runs/:id/events`
+//! until the run reaches a terminal status; otherwise it follows
+//! `/v1/deliveries/events` until interrupted.
+
+use std::io::Write;
+
+use anyhow::Context;
+use futures_util::StreamExt;
+use serde_json::Value;
+
+use sa_domain::config::Config;
+
+/// Client-side filters applied to incoming events.
+#[derive(Debug, Default, Clone)]
+pub struct TailFilter {
+    /// Only show run/node events whose status is in this list (empty = all).
+    pub status: Vec<String>,
+    /// Only show deliveries whose schedule ID or name matches.
+    pub schedule: Option<String>,
+}
+
+/// Follow a run (or the delivery feed) and print events to stdout.
+///
+/// This is the entry point for `serialagent tail`.
+pub async fn tail(
+    config: &Config,
+    url: Option<String>,
+    run: Option<String>,
+    filter: TailFilter,
+) -> anyhow::Result<()> {
+    let base = url.unwrap_or_else(|| super::gateway_base_url(config));
+    let base = base.trim_end_matches('/');
+    let path = match &run {
+        Some(id) => format!("/v1/runs/{id}/events"),
+        None => "/v1/deliveries/events".to_string(),
+    };
+
+    let mut req = reqwest::Client::new()
+        .get(format!("{base}{path}"))
+        .header("Accept", "text/event-stream");
+    if let Some(token) = super::gateway_api_token(config) {
+        req = req.bearer_auth(token);
+    }
+    let resp = req
+        .send()
+        .await
+        .with_context(|| format!("connecting to {base}"))?;
+    if !resp.status().is_success() {
+        anyhow::bail!("{path} returned HTTP {}", resp.status());
+    }
+
+    match &run {
+        Some(id) => eprintln!("Following run {id} (Ctrl-C to stop)"),
+        None => eprintln!("Following deliveries (Ctrl-C to stop)"),
+    }
+
+    let mut parser = SseParser::default();
+    let mut body = resp.bytes_stream();
+    let mut stdout = std::io::stdout();
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.context("reading event stream")?;
+        for frame in parser.push(&chunk) {
+            if frame.event == "error" {
+                anyhow::bail!("gateway error: {}", frame.data);
+            }
+            if let Some(line) = render_frame(&frame, &filter) {
+                writeln!(stdout, "{line}")?;
+                stdout.flush()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ── SSE parsing ───────────────────────────────────────────────────────
+
+/// A single dispatched server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseFrame {
+    /// Event name (`message` when the server sent none).
+    pub event: String,
+    /// Data lines joined with `\n`.
+    pub data: String,
+}
+
+/// Incremental SSE parser.  Feed it arbitrary chunks of the response body;
+/// complete frames are returned once their terminating blank line arrives.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buf: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseFrame> {
+        self.buf.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+            // Split on raw bytes so multi-byte characters straddling a
+            // chunk boundary are decoded intact.
+            let raw: Vec<u8> = self.buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    frames.push(SseFrame {
+                        event: self.event.take().unwrap_or_else(|| "message".into()),
+                        data: std::mem::take(&mut self.data).join("\n"),
+                    });
+                }
+                self.event = None;
+                continue;
+            }
+            if line.starts_with(':') {
+                // Comment / keep-alive.
+                continue;
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+        frames
+    }
+}
+
+// ── Rendering ─────────────────────────────────────────────────────────
+
+/// Render a frame as a single display line, or `None` if it is filtered
+/// out or carries nothing worth printing.
+pub fn render_frame(frame: &SseFrame, filter: &TailFilter) -> Option<String> {
+    let v: Value = serde_json::from_str(&frame.data).ok()?;
+    let str_of = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).map(str::to_string);
+
+    match frame.event.as_str() {
+        "run.status" => {
+            let status = str_of(&v, "status")?;
+            status_allowed(filter, &status).then(|| format!("[run]  status → {status}"))
+        }
+        "run.snapshot" => {
+            let status = str_of(&v, "status")?;
+            if !status_allowed(filter, &status) {
+                return None;
+            }
+            let mut line = format!("[run]  already {status}");
+            if let Some(err) = str_of(&v, "error") {
+                line.push_str(&format!(": {err}"));
+            }
+            Some(line)
+        }
+        "node.started" | "node.completed" | "node.failed" => {
+            let node = v.get("node")?;
+            let status = str_of(node, "status").unwrap_or_default();
+            if !status_allowed(filter, &status) {
+                return None;
+            }
+            let name = str_of(node, "name").unwrap_or_else(|| "?".into());
+            let kind = str_of(node, "kind").unwrap_or_default();
+            let mut line = format!("[node] {name} ({kind}) {status}");
+            if let Some(ms) = node.get("duration_ms").and_then(Value::as_u64) {
+                line.push_str(&format!(" in {ms}ms"));
+            }
+            if let Some(err) = str_of(node, "error") {
+                line.push_str(&format!(": {err}"));
+            }
+            Some(line)
+        }
+        "log" if filter.status.is_empty() => {
+            let level = str_of(&v, "level").unwrap_or_else(|| "info".into());
+            let message = str_of(&v, "message")?;
+            Some(format!("[log]  {level}: {message}"))
+        }
+        "usage" if filter.status.is_empty() => Some(format!(
+            "[usage] in={} out={} total={}",
+            v["input_tokens"], v["output_tokens"], v["total_tokens"]
+        )),
+        "exec.approval_required" => Some(format!(
+            "[exec] approval required: {} (id {})",
+            str_of(&v, "command").unwrap_or_default(),
+            str_of(&v, "approval_id").unwrap_or_default()
+        )),
+        "delivery.new" => {
+            let d = v.get("delivery")?;
+            if let Some(want) = &filter.schedule {
+                let id = str_of(d, "schedule_id");
+                let name = str_of(d, "schedule_name");
+                if id.as_deref() != Some(want.as_str()) && name.as_deref() != Some(want.as_str()) {
+                    return None;
+                }
+            }
+            let from = str_of(d, "schedule_name").unwrap_or_else(|| "manual".into());
+            Some(format!(
+                "[delivery] {} — {} ({})",
+                from,
+                str_of(d, "title").unwrap_or_default(),
+                str_of(d, "id").unwrap_or_default()
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn status_allowed(filter: &TailFilter, status: &str) -> bool {
+    filter.status.is_empty() || filter.status.iter().any(|s| s.eq_ignore_ascii_case(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_all(body: &str, filter: &TailFilter) -> Vec<String> {
+        let mut parser = SseParser::default();
+        // Feed in awkward chunk sizes to exercise buffering.
+        let mut lines = Vec::new();
+        for chunk in body.as_bytes().chunks(7) {
+            for frame in parser.push(chunk) {
+                lines.extend(render_frame(&frame, filter));
+            }
+        }
+        lines
+    }
+
+    #[test]
+    fn parses_chunked_frames() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"event: run.sta").is_empty());
+        assert!(parser.push(b"tus\r\ndata: {\"a\":1}\r\n").is_empty());
+        let frames = parser.push(b"\r\n: keep-alive\n\ndata: x\ndata: y\n\n");
+        assert_eq!(
+            frames,
+            vec![
+                SseFrame {
+                    event: "run.status".into(),
+                    data: "{\"a\":1}".into()
+                },
+                SseFrame {
+                    event: "message".into(),
+                    data: "x\ny".into()
+                },
+            ]
+        );
+    }
+
+    const RUN_BODY: &str = concat!(
+        "event: run.status\ndata: {\"type\":\"run.status\",\"run_id\":\"r1\",\"status\":\"queued\"}\n\n",
+        "event: run.status\ndata: {\"type\":\"run.status\",\"run_id\":\"r1\",\"status\":\"running\"}\n\n",
+        "event: node.completed\ndata: {\"type\":\"node.completed\",\"run_id\":\"r1\",",
+        "\"node\":{\"node_id\":1,\"kind\":\"llm_request\",\"name\":\"openai/gpt-4o\",",
+        "\"status\":\"completed\",\"duration_ms\":42}}\n\n",
+        ":\n\n",
+        "event: run.status\ndata: {\"type\":\"run.status\",\"run_id\":\"r1\",\"status\":\"completed\"}\n\n",
+    );
+
+    #[test]
+    fn renders_run_status_transitions() {
+        let lines = render_all(RUN_BODY, &TailFilter::default());
+        assert_eq!(
+            lines,
+            vec![
+                "[run]  status → queued",
+                "[run]  status → running",
+                "[node] openai/gpt-4o (llm_request) completed in 42ms",
+                "[run]  status → completed",
+            ]
+        );
+    }
+
+    #[test]
+    fn status_filter_hides_other_transitions() {
+        let filter = TailFilter {
+            status: vec!["COMPLETED".into()],
+            schedule: None,
+        };
+        let lines = render_all(RUN_BODY, &filter);
+        assert_eq!(
+            lines,
+            vec![
+                "[node] openai/gpt-4o (llm_request) completed in 42ms",
+                "[run]  status → completed",
+            ]
+        );
+    }
+
+    #[test]
+    fn schedule_filter_matches_id_or_name() {
+        let body = concat!(
+            "event: delivery.new\ndata: {\"type\":\"new_delivery\",\"delivery\":",
+            "{\"id\":\"d1\",\"schedule_id\":\"s1\",\"schedule_name\":\"digest\",\"title\":\"Morning\"}}\n\n",
+            "event: delivery.new\ndata: {\"type\":\"new_delivery\",\"delivery\":",
+            "{\"id\":\"d2\",\"schedule_id\":\"s2\",\"schedule_name\":\"alerts\",\"title\":\"Disk\"}}\n\n",
+            "event: delivery.read\ndata: {\"type\":\"delivery_read\",\"id\":\"d1\",\"unread\":0}\n\n",
+        );
+        let filter = TailFilter {
+            status: vec![],
+            schedule: Some("digest".into()),
+        };
+        assert_eq!(
+            render_all(body, &filter),
+            vec!["[delivery] digest — Morning (d1)"]
+        );
+        let filter = TailFilter {
+            status: vec![],
+            schedule: Some("s2".into()),
+        };
+        assert_eq!(
+            render_all(body, &filter),
+            vec!["[delivery] alerts — Disk (d2)"]
+        );
+    }
+}
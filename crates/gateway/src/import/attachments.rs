@@ -0,0 +1,346 @@
+//! Staging for inbound channel attachments.
+//!
+//! Connectors deliver files inline on the `/v1/inbound` envelope as
+//! base64 `data`.  Each envelope's files are written inside the workspace
+//! under `.inbound/<uuid>/` with sanitized names and a per-file size cap,
+//! and their workspace-relative paths are handed to the turn as
+//! references, so the file tools can open them.  Stale dirs are swept by
+//! the same maintenance loop as OpenClaw staging.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::openclaw::sanitize::{sanitize_ident, MAX_IDENT_LEN};
+use super::openclaw::staging::cleanup_stale_dirs;
+
+/// Name used when an attachment has no usable filename.
+const FALLBACK_NAME: &str = "attachment";
+
+/// Workspace subdirectory holding staged attachments.
+pub const INBOUND_DIR: &str = ".inbound";
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Limits (configurable via env, sensible defaults)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Max decoded size of a single attachment in bytes (default 2MB).
+pub fn max_attachment_bytes() -> u64 {
+    std::env::var("SA_INBOUND_MAX_ATTACHMENT_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2 * 1024 * 1024)
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Types
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("attachments[{index}]: {reason}")]
+    Invalid { index: usize, reason: String },
+    #[error("attachments[{index}] ({name}) is {size} bytes, over the {max}-byte limit")]
+    TooLarge {
+        index: usize,
+        name: String,
+        size: u64,
+        max: u64,
+    },
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// A file written to the staging dir.
+#[derive(Debug, Clone, Serialize)]
+pub struct StagedAttachment {
+    /// Sanitized filename within the staging dir.
+    pub name: String,
+    /// Path relative to the workspace root, as the file tools take it.
+    pub path: PathBuf,
+    pub media_type: Option<String>,
+    pub size_bytes: u64,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Staging
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Reduce a connector-supplied filename to a safe single path component:
+/// the last `/` or `\` segment, with anything outside `[A-Za-z0-9._-]`
+/// replaced by `_`, leading dots stripped and the length capped.
+pub fn sanitize_filename(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let trimmed: String = cleaned
+        .trim_start_matches('.')
+        .chars()
+        .take(MAX_IDENT_LEN)
+        .collect();
+    match sanitize_ident(&trimmed) {
+        Ok(()) => trimmed,
+        Err(_) => FALLBACK_NAME.to_owned(),
+    }
+}
+
+/// Write every attachment carrying inline base64 `data` to a fresh dir
+/// under `<workspace>/.inbound/`.  Attachments without `data` (e.g.
+/// URL-only images) are skipped.  Any oversized or undecodable attachment
+/// fails the whole batch and nothing is left on disk.
+pub async fn stage_attachments(
+    workspace: &Path,
+    attachments: &[serde_json::Value],
+    max_bytes: u64,
+) -> Result<Vec<StagedAttachment>, AttachmentError> {
+    let mut files = Vec::new();
+    for (index, a) in attachments.iter().enumerate() {
+        let Some(data) = a.get("data") else {
+            continue;
+        };
+        let data = data.as_str().ok_or_else(|| AttachmentError::Invalid {
+            index,
+            reason: "`data` must be a base64 string".into(),
+        })?;
+        let raw_name = a
+            .get("name")
+            .or_else(|| a.get("filename"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(FALLBACK_NAME);
+        let name = sanitize_filename(raw_name);
+
+        // Reject on the encoded length before paying for the decode.
+        let estimated = (data.len() as u64 / 4) * 3;
+        if estimated > max_bytes + 2 {
+            return Err(AttachmentError::TooLarge {
+                index,
+                name,
+                size: estimated,
+                max: max_bytes,
+            });
+        }
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .map_err(|e| AttachmentError::Invalid {
+                index,
+                reason: format!("invalid base64: {e}"),
+            })?;
+        if bytes.len() as u64 > max_bytes {
+            return Err(AttachmentError::TooLarge {
+                index,
+                name,
+                size: bytes.len() as u64,
+                max: max_bytes,
+            });
+        }
+
+        let media_type = a
+            .get("media_type")
+            .or_else(|| a.get("mime_type"))
+            .and_then(|v| v.as_str())
+            .map(str::to_owned);
+        files.push((name, media_type, bytes));
+    }
+
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rel_dir = Path::new(INBOUND_DIR).join(Uuid::new_v4().to_string());
+    let dir = workspace.join(&rel_dir);
+    tokio::fs::create_dir_all(&dir).await?;
+    match write_files(&dir, &rel_dir, files).await {
+        Ok(staged) => Ok(staged),
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&dir).await;
+            Err(e.into())
+        }
+    }
+}
+
+async fn write_files(
+    dir: &Path,
+    rel_dir: &Path,
+    files: Vec<(String, Option<String>, Vec<u8>)>,
+) -> Result<Vec<StagedAttachment>, io::Error> {
+    let mut staged: Vec<StagedAttachment> = Vec::with_capacity(files.len());
+    for (name, media_type, bytes) in files {
+        // Two attachments sanitizing to the same name must not overwrite
+        // each other.
+        let name = if staged.iter().any(|s| s.name == name) {
+            format!("{}-{name}", staged.len())
+        } else {
+            name
+        };
+        tokio::fs::write(dir.join(&name), &bytes).await?;
+        staged.push(StagedAttachment {
+            path: rel_dir.join(&name),
+            name,
+            media_type,
+            size_bytes: bytes.len() as u64,
+        });
+    }
+    Ok(staged)
+}
+
+/// The note appended to the user message so the model knows where the
+/// staged files are and can pass the paths to tools.
+pub fn attachment_references(staged: &[StagedAttachment]) -> String {
+    let mut out = String::from("[Attachments]");
+    for s in staged {
+        let media_type = s
+            .media_type
+            .as_deref()
+            .unwrap_or("application/octet-stream");
+        out.push_str(&format!(
+            "\n- {} ({media_type}, {} bytes): {}",
+            s.name,
+            s.size_bytes,
+            s.path.display()
+        ));
+    }
+    out
+}
+
+/// Delete inbound attachment dirs older than `max_age` seconds.
+pub async fn cleanup_stale_attachments(
+    workspace: &Path,
+    max_age_secs: u64,
+) -> Result<u32, io::Error> {
+    cleanup_stale_dirs(&workspace.join(INBOUND_DIR), max_age_secs).await
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    #[tokio::test]
+    async fn stages_attachment_inside_workspace() {
+        let root = tempfile::tempdir().unwrap();
+        let attachments = vec![
+            json!({"type": "image", "url": "https://example.com/a.png"}),
+            json!({"name": "notes.txt", "media_type": "text/plain", "data": encode(b"hello")}),
+        ];
+
+        let staged = stage_attachments(root.path(), &attachments, 1024)
+            .await
+            .unwrap();
+
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].name, "notes.txt");
+        assert_eq!(staged[0].size_bytes, 5);
+        assert!(staged[0].path.starts_with(INBOUND_DIR));
+        assert_eq!(
+            std::fs::read(root.path().join(&staged[0].path)).unwrap(),
+            b"hello"
+        );
+
+        let refs = attachment_references(&staged);
+        assert!(refs.contains("notes.txt (text/plain, 5 bytes)"));
+    }
+
+    #[tokio::test]
+    async fn oversized_attachment_is_rejected_and_nothing_staged() {
+        let root = tempfile::tempdir().unwrap();
+        let attachments = vec![
+            json!({"name": "ok.txt", "data": encode(b"fine")}),
+            json!({"name": "big.bin", "data": encode(&[0u8; 64])}),
+        ];
+
+        let err = stage_attachments(root.path(), &attachments, 32)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AttachmentError::TooLarge { index: 1, .. }));
+        assert!(!root.path().join(INBOUND_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn traversal_names_stay_inside_staging_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let attachments = vec![
+            json!({"name": "../../etc/passwd", "data": encode(b"a")}),
+            json!({"name": "..\\..\\boot.ini", "data": encode(b"b")}),
+            json!({"name": "..", "data": encode(b"c")}),
+        ];
+
+        let staged = stage_attachments(root.path(), &attachments, 1024)
+            .await
+            .unwrap();
+
+        let names: Vec<&str> = staged.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["passwd", "boot.ini", "attachment"]);
+        let dir = staged[0].path.parent().unwrap();
+        assert!(dir.starts_with(INBOUND_DIR));
+        assert!(staged.iter().all(|s| s.path.parent() == Some(dir)));
+    }
+
+    #[test]
+    fn sanitize_filename_replaces_unsafe_chars() {
+        assert_eq!(sanitize_filename("my report (1).pdf"), "my_report__1_.pdf");
+        assert_eq!(sanitize_filename(".hidden"), "hidden");
+        assert_eq!(sanitize_filename(""), "attachment");
+        assert_eq!(sanitize_filename(&"a".repeat(300)).len(), MAX_IDENT_LEN);
+    }
+
+    #[tokio::test]
+    async fn duplicate_names_do_not_overwrite() {
+        let root = tempfile::tempdir().unwrap();
+        let attachments = vec![
+            json!({"name": "a/x.txt", "data": encode(b"1")}),
+            json!({"name": "b/x.txt", "data": encode(b"2")}),
+        ];
+
+        let staged = stage_attachments(root.path(), &attachments, 1024)
+            .await
+            .unwrap();
+
+        assert_eq!(staged[0].name, "x.txt");
+        assert_eq!(staged[1].name, "1-x.txt");
+        assert_eq!(
+            std::fs::read(root.path().join(&staged[1].path)).unwrap(),
+            b"2"
+        );
+    }
+
+    #[tokio::test]
+    async fn staged_file_is_readable_through_the_file_tool() {
+        let workspace = tempfile::tempdir().unwrap();
+        let attachments = vec![json!({"name": "notes.txt", "data": encode(b"line one\nline two")})];
+
+        let staged = stage_attachments(workspace.path(), &attachments, 1024)
+            .await
+            .unwrap();
+
+        let req = sa_tools::file_ops::FileReadRequest {
+            path: staged[0].path.to_string_lossy().into_owned(),
+            offset: None,
+            limit: None,
+        };
+        let out = sa_tools::file_ops::file_read(workspace.path(), req)
+            .await
+            .unwrap();
+        assert!(out.to_string().contains("line two"), "{out}");
+    }
+}
@@ -176,6 +176,9 @@ pub async fn generate_config_from_import(
                 ..AuthConfig::default()
             },
             default_model,
+            connect_timeout_sec: 10,
+            request_timeout_sec: 120,
+            stream_idle_timeout_sec: 60,
         });
         changes.push(format!("Added LLM provider: {provider_id}"));
     }
@@ -248,6 +251,7 @@ pub async fn generate_config_from_import(
                     memory_mode: MemoryMode::default(),
                     limits: AgentLimits::default(),
                     compaction_enabled: false,
+                    tool_choice: Default::default(),
                 },
             );
             changes.push(format!("Added agent: {agent_id}"));
@@ -0,0 +1,413 @@
+//! Dry run of [`apply_openclaw_import`](super::apply_openclaw_import).
+//!
+//! Walks the staged inventory against the destination roots and reports
+//! every existing file the chosen [`MergeStrategy`] would skip, overwrite,
+//! delete, or sidestep — without touching the filesystem.
+
+use std::path::{Path, PathBuf};
+
+use glob::glob;
+use uuid::Uuid;
+
+use super::sanitize::sanitize_ident;
+use super::scan::scan_inventory;
+use super::{
+    agent_files_dest, check_selection, is_selected, no_progress, sessions_dest, workspace_dest,
+    OpenClawImportError, SESSION_PATTERNS,
+};
+use crate::api::import_openclaw::*;
+
+pub async fn find_import_conflicts(
+    staging_id: Uuid,
+    req: ImportConflictsRequest,
+    staging_root: &Path,
+    workspace_dest_root: &Path,
+    sessions_dest_root: &Path,
+) -> Result<ImportConflictsResponse, OpenClawImportError> {
+    let extracted_dir = staging_root.join(staging_id.to_string()).join("extracted");
+    if !extracted_dir.exists() {
+        return Err(OpenClawImportError::InvalidPath(format!(
+            "staging_id {staging_id} not found"
+        )));
+    }
+
+    let strategy = req.merge_strategy;
+    let inv = scan_inventory(&extracted_dir, &req.options, &no_progress).await?;
+    check_selection(
+        "agent",
+        &req.only_agents,
+        inv.agents.iter().map(|a| a.agent_id.as_str()),
+    )?;
+    check_selection(
+        "workspace",
+        &req.only_workspaces,
+        inv.workspaces.iter().map(|w| w.name.as_str()),
+    )?;
+    let agents: Vec<_> = inv
+        .agents
+        .iter()
+        .filter(|a| is_selected(&req.only_agents, &a.agent_id))
+        .collect();
+    let mut report = Report::default();
+
+    // ── Workspaces ──────────────────────────────────────────────
+    if req.options.include_workspaces {
+        for ws in &inv.workspaces {
+            if !is_selected(&req.only_workspaces, &ws.name) {
+                continue;
+            }
+            sanitize_ident(&ws.name)?;
+
+            let src = extracted_dir.join(&ws.rel_path);
+            let dst = workspace_dest(workspace_dest_root, &ws.rel_path, strategy);
+            let canonical =
+                workspace_dest(workspace_dest_root, &ws.rel_path, MergeStrategy::Replace);
+            let staged = list_files(&src)?;
+
+            for rel in &staged {
+                report.check(
+                    "workspace",
+                    format!("{}/{rel}", ws.rel_path),
+                    &dst.join(rel),
+                    &canonical.join(rel),
+                    strategy,
+                );
+            }
+
+            // Replace removes the destination folder before copying.
+            if strategy == MergeStrategy::Replace {
+                for rel in list_files(&dst)? {
+                    if !staged.contains(&rel) {
+                        report.push(
+                            "workspace",
+                            format!("{}/{rel}", ws.rel_path),
+                            &dst.join(&rel),
+                            ConflictAction::Delete,
+                            None,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // ── Sessions per agent ──────────────────────────────────────
+    if req.options.include_sessions {
+        for a in &agents {
+            sanitize_ident(&a.agent_id)?;
+
+            let src_sessions = extracted_dir
+                .join("agents")
+                .join(&a.agent_id)
+                .join("sessions");
+            if !src_sessions.exists() {
+                continue;
+            }
+            let dst = sessions_dest(sessions_dest_root, &a.agent_id, strategy);
+            let canonical = sessions_dest(sessions_dest_root, &a.agent_id, MergeStrategy::Replace);
+
+            for name in glob_file_names(&src_sessions, SESSION_PATTERNS) {
+                report.check(
+                    "session",
+                    format!("agents/{}/sessions/{name}", a.agent_id),
+                    &dst.join(&name),
+                    &canonical.join(&name),
+                    strategy,
+                );
+            }
+        }
+    }
+
+    // ── Models + auth profiles ──────────────────────────────────
+    // These always land under imported/openclaw/, so there is nothing to
+    // relocate: merge_safe and replace both overwrite.
+    let agent_files = [
+        (req.options.include_models, "models.json"),
+        (req.options.include_auth_profiles, "auth-profiles.json"),
+    ];
+    if agent_files.iter().any(|(included, _)| *included) {
+        for a in &agents {
+            sanitize_ident(&a.agent_id)?;
+
+            let src_agent_dir = extracted_dir.join("agents").join(&a.agent_id).join("agent");
+            let dst_agent_dir = agent_files_dest(workspace_dest_root, &a.agent_id);
+            for (included, name) in agent_files {
+                if !included || !src_agent_dir.join(name).exists() {
+                    continue;
+                }
+                let dst = dst_agent_dir.join(name);
+                report.check(
+                    "agent_file",
+                    format!("agents/{}/agent/{name}", a.agent_id),
+                    &dst,
+                    &dst,
+                    strategy,
+                );
+            }
+        }
+    }
+
+    Ok(ImportConflictsResponse {
+        staging_id,
+        merge_strategy: strategy,
+        conflicts: report.conflicts,
+        totals: report.totals,
+    })
+}
+
+#[derive(Default)]
+struct Report {
+    conflicts: Vec<ImportConflict>,
+    totals: ConflictTotals,
+}
+
+impl Report {
+    /// Record what copying a staged file to `dst` would do.  `canonical` is
+    /// where the file would land without `merge_safe` relocation.
+    fn check(
+        &mut self,
+        kind: &str,
+        source: String,
+        dst: &Path,
+        canonical: &Path,
+        strategy: MergeStrategy,
+    ) {
+        if dst.is_file() {
+            let action = match strategy {
+                MergeStrategy::SkipExisting => ConflictAction::Skip,
+                MergeStrategy::Replace | MergeStrategy::MergeSafe => ConflictAction::Overwrite,
+            };
+            self.push(kind, source, dst, action, None);
+        } else if dst != canonical && canonical.is_file() {
+            let existing = canonical.to_string_lossy().to_string();
+            self.push(kind, source, dst, ConflictAction::Relocate, Some(existing));
+        } else {
+            self.totals.created += 1;
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: &str,
+        source: String,
+        dst: &Path,
+        action: ConflictAction,
+        existing: Option<String>,
+    ) {
+        match action {
+            ConflictAction::Skip => self.totals.skipped += 1,
+            ConflictAction::Overwrite => self.totals.overwritten += 1,
+            ConflictAction::Delete => self.totals.deleted += 1,
+            ConflictAction::Relocate => self.totals.relocated += 1,
+        }
+        self.conflicts.push(ImportConflict {
+            kind: kind.to_string(),
+            source,
+            dest: dst.to_string_lossy().to_string(),
+            action,
+            existing,
+        });
+    }
+}
+
+/// Regular files under `root`, as sorted `/`-separated relative paths.
+/// Symlinks are ignored, matching the copy helpers.
+fn list_files(root: &Path) -> Result<Vec<String>, OpenClawImportError> {
+    let mut out = Vec::new();
+    if root.is_dir() {
+        let mut stack = vec![PathBuf::new()];
+        while let Some(rel) = stack.pop() {
+            for entry in std::fs::read_dir(root.join(&rel))? {
+                let entry = entry?;
+                let ft = entry.file_type()?;
+                let child = rel.join(entry.file_name());
+                if ft.is_dir() {
+                    stack.push(child);
+                } else if ft.is_file() {
+                    out.push(child.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// File names in `dir` matching any of `patterns`, sorted.
+fn glob_file_names(dir: &Path, patterns: &[&str]) -> Vec<String> {
+    let mut names: Vec<String> = patterns
+        .iter()
+        .filter_map(|pat| glob(&dir.join(pat).to_string_lossy()).ok())
+        .flatten()
+        .flatten()
+        .filter(|p| p.is_file())
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixture {
+        staging: tempfile::TempDir,
+        ws_root: tempfile::TempDir,
+        sess_root: tempfile::TempDir,
+        id: Uuid,
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Staged: workspace/{AGENTS.md, SOUL.md}, agents/main/sessions/{a,b}.jsonl,
+    /// agents/main/agent/models.json.
+    /// Destination: workspace/{AGENTS.md, NOTES.md}, sessions main/a.jsonl,
+    /// and an earlier merge_safe copy of SOUL.md.
+    fn fixture() -> Fixture {
+        let f = Fixture {
+            staging: tempfile::tempdir().unwrap(),
+            ws_root: tempfile::tempdir().unwrap(),
+            sess_root: tempfile::tempdir().unwrap(),
+            id: Uuid::new_v4(),
+        };
+        let x = f.staging.path().join(f.id.to_string()).join("extracted");
+        write(&x.join("workspace/AGENTS.md"), "staged");
+        write(&x.join("workspace/SOUL.md"), "staged");
+        write(&x.join("agents/main/sessions/a.jsonl"), "{}\n");
+        write(&x.join("agents/main/sessions/b.jsonl"), "{}\n");
+        write(&x.join("agents/main/agent/models.json"), "{}");
+
+        let ws = f.ws_root.path();
+        write(&ws.join("workspace/AGENTS.md"), "existing");
+        write(&ws.join("workspace/NOTES.md"), "existing");
+        write(
+            &ws.join("imported/openclaw/workspace/SOUL.md"),
+            "earlier import",
+        );
+        write(
+            &ws.join("imported/openclaw/agents/main/agent/models.json"),
+            "{}",
+        );
+        write(&f.sess_root.path().join("main/a.jsonl"), "{}\n");
+        f
+    }
+
+    async fn conflicts(f: &Fixture, strategy: MergeStrategy) -> ImportConflictsResponse {
+        let req = ImportConflictsRequest {
+            merge_strategy: strategy,
+            options: ImportOptions {
+                include_models: true,
+                ..ImportOptions::default()
+            },
+            only_agents: Vec::new(),
+            only_workspaces: Vec::new(),
+        };
+        find_import_conflicts(
+            f.id,
+            req,
+            f.staging.path(),
+            f.ws_root.path(),
+            f.sess_root.path(),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn actions(resp: &ImportConflictsResponse) -> Vec<(&str, ConflictAction)> {
+        resp.conflicts
+            .iter()
+            .map(|c| (c.source.as_str(), c.action))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn replace_overwrites_and_deletes() {
+        let f = fixture();
+        let resp = conflicts(&f, MergeStrategy::Replace).await;
+        assert_eq!(
+            actions(&resp),
+            vec![
+                ("workspace/AGENTS.md", ConflictAction::Overwrite),
+                ("workspace/NOTES.md", ConflictAction::Delete),
+                ("agents/main/sessions/a.jsonl", ConflictAction::Overwrite),
+                ("agents/main/agent/models.json", ConflictAction::Overwrite),
+            ]
+        );
+        assert_eq!(resp.totals.overwritten, 3);
+        assert_eq!(resp.totals.deleted, 1);
+        assert_eq!(resp.totals.created, 2);
+    }
+
+    #[tokio::test]
+    async fn skip_existing_reports_skips() {
+        let f = fixture();
+        let resp = conflicts(&f, MergeStrategy::SkipExisting).await;
+        assert_eq!(
+            actions(&resp),
+            vec![
+                ("workspace/AGENTS.md", ConflictAction::Skip),
+                ("agents/main/sessions/a.jsonl", ConflictAction::Skip),
+                ("agents/main/agent/models.json", ConflictAction::Skip),
+            ]
+        );
+        assert_eq!(resp.totals.skipped, 3);
+        assert_eq!(resp.totals.deleted, 0);
+        assert_eq!(resp.totals.created, 2);
+    }
+
+    #[tokio::test]
+    async fn merge_safe_relocates_beside_existing_files() {
+        let f = fixture();
+        let resp = conflicts(&f, MergeStrategy::MergeSafe).await;
+        assert_eq!(
+            actions(&resp),
+            vec![
+                ("workspace/AGENTS.md", ConflictAction::Relocate),
+                ("workspace/SOUL.md", ConflictAction::Overwrite),
+                ("agents/main/sessions/a.jsonl", ConflictAction::Relocate),
+                ("agents/main/agent/models.json", ConflictAction::Overwrite),
+            ]
+        );
+        let agents = &resp.conflicts[0];
+        assert!(agents
+            .dest
+            .contains("imported/openclaw/workspace/AGENTS.md"));
+        let existing = f.ws_root.path().join("workspace/AGENTS.md");
+        assert_eq!(
+            agents.existing.as_deref(),
+            Some(&*existing.to_string_lossy())
+        );
+        assert_eq!(resp.totals.relocated, 2);
+        assert_eq!(resp.totals.created, 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_staging_id_is_rejected() {
+        let f = fixture();
+        let req = ImportConflictsRequest {
+            merge_strategy: MergeStrategy::Replace,
+            options: ImportOptions::default(),
+            only_agents: Vec::new(),
+            only_workspaces: Vec::new(),
+        };
+        let err = find_import_conflicts(
+            Uuid::new_v4(),
+            req,
+            f.staging.path(),
+            f.ws_root.path(),
+            f.sess_root.path(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, OpenClawImportError::InvalidPath(_)));
+    }
+}
@@ -1,15 +1,22 @@
+//! Strategy-aware copies from staging into the live destinations.
+//!
+//! Every write goes through the [`ApplyJournal`] so the apply can be
+//! rolled back.
+
 use std::ffi::OsStr;
 use std::path::Path;
 
 use glob::glob;
 
 use crate::api::import_openclaw::MergeStrategy;
+use super::rollback::ApplyJournal;
 use super::OpenClawImportError;
 
 pub(super) async fn copy_dir_strategy(
     src: &Path,
     dst: &Path,
     strategy: MergeStrategy,
+    journal: &mut ApplyJournal,
 ) -> Result<(), OpenClawImportError> {
     if !src.exists() {
         return Ok(());
@@ -17,15 +24,15 @@ pub(super) async fn copy_dir_strategy(
     match strategy {
         MergeStrategy::Replace => {
             if dst.exists() {
-                tokio::fs::remove_dir_all(dst).await?;
+                journal.remove_dir_all(dst).await?;
             }
-            copy_dir_recursive(src, dst).await?;
+            copy_dir_recursive(src, dst, false, journal).await?;
         }
         MergeStrategy::MergeSafe => {
-            copy_dir_recursive(src, dst).await?;
+            copy_dir_recursive(src, dst, false, journal).await?;
         }
         MergeStrategy::SkipExisting => {
-            copy_dir_recursive_skip_existing(src, dst).await?;
+            copy_dir_recursive(src, dst, true, journal).await?;
         }
     }
     Ok(())
@@ -36,6 +43,7 @@ pub(super) async fn copy_glob_strategy(
     dst_dir: &Path,
     patterns: &[&str],
     strategy: MergeStrategy,
+    journal: &mut ApplyJournal,
 ) -> Result<u32, OpenClawImportError> {
     let mut copied = 0u32;
     for pat in patterns {
@@ -50,7 +58,7 @@ pub(super) async fn copy_glob_strategy(
             if src.is_file() {
                 let name = src.file_name().unwrap_or_else(|| OsStr::new("file"));
                 let dst = dst_dir.join(name);
-                copy_file_strategy(&src, &dst, strategy).await?;
+                copy_file_strategy(&src, &dst, strategy, journal).await?;
                 copied += 1;
             }
         }
@@ -62,6 +70,7 @@ pub(super) async fn copy_file_strategy(
     src: &Path,
     dst: &Path,
     strategy: MergeStrategy,
+    journal: &mut ApplyJournal,
 ) -> Result<(), OpenClawImportError> {
     if !src.exists() {
         return Ok(());
@@ -73,28 +82,26 @@ pub(super) async fn copy_file_strategy(
             MergeStrategy::MergeSafe => { /* overwrite for deterministic behavior */ }
         }
     }
-    if let Some(parent) = dst.parent() {
-        tokio::fs::create_dir_all(parent).await?;
-    }
-    tokio::fs::copy(src, dst).await?;
-    Ok(())
+    journal.copy_file(src, dst).await
 }
 
 fn copy_dir_recursive<'a>(
     src: &'a Path,
     dst: &'a Path,
+    skip_existing: bool,
+    journal: &'a mut ApplyJournal,
 ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), OpenClawImportError>> + Send + 'a>> {
     Box::pin(async move {
-        tokio::fs::create_dir_all(dst).await?;
+        journal.create_dir_all(dst).await?;
         let mut rd = tokio::fs::read_dir(src).await?;
         while let Some(e) = rd.next_entry().await? {
             let ft = e.file_type().await?;
             let from = e.path();
             let to = dst.join(e.file_name());
             if ft.is_dir() {
-                copy_dir_recursive(&from, &to).await?;
-            } else if ft.is_file() {
-                tokio::fs::copy(&from, &to).await?;
+                copy_dir_recursive(&from, &to, skip_existing, journal).await?;
+            } else if ft.is_file() && !(skip_existing && to.exists()) {
+                journal.copy_file(&from, &to).await?;
             }
             // Skip symlinks and other special files during copy
         }
@@ -102,29 +109,6 @@ fn copy_dir_recursive<'a>(
     })
 }
 
-fn copy_dir_recursive_skip_existing<'a>(
-    src: &'a Path,
-    dst: &'a Path,
-) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), OpenClawImportError>> + Send + 'a>> {
-    Box::pin(async move {
-        tokio::fs::create_dir_all(dst).await?;
-        let mut rd = tokio::fs::read_dir(src).await?;
-        while let Some(e) = rd.next_entry().await? {
-            let ft = e.file_type().await?;
-            let from = e.path();
-            let to = dst.join(e.file_name());
-            if ft.is_dir() {
-                copy_dir_recursive_skip_existing(&from, &to).await?;
-            } else if ft.is_file()
-                && !to.exists() {
-                    tokio::fs::copy(&from, &to).await?;
-                }
-            // Skip symlinks and other special files during copy
-        }
-        Ok(())
-    })
-}
-
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Tests
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -132,11 +116,14 @@ fn copy_dir_recursive_skip_existing<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_skip_existing_does_not_overwrite() {
         let src = tempfile::tempdir().unwrap();
         let dst = tempfile::tempdir().unwrap();
+        let staging = tempfile::tempdir().unwrap();
+        let mut journal = ApplyJournal::create(staging.path(), Uuid::new_v4()).await.unwrap();
 
         // Create source file
         let src_file = src.path().join("test.txt");
@@ -146,7 +133,7 @@ mod tests {
         let dst_file = dst.path().join("test.txt");
         std::fs::write(&dst_file, "original content").unwrap();
 
-        copy_file_strategy(&src_file, &dst_file, MergeStrategy::SkipExisting)
+        copy_file_strategy(&src_file, &dst_file, MergeStrategy::SkipExisting, &mut journal)
             .await
             .unwrap();
 
@@ -161,6 +148,8 @@ mod tests {
     async fn test_replace_does_overwrite() {
         let src = tempfile::tempdir().unwrap();
         let dst = tempfile::tempdir().unwrap();
+        let staging = tempfile::tempdir().unwrap();
+        let mut journal = ApplyJournal::create(staging.path(), Uuid::new_v4()).await.unwrap();
 
         let src_file = src.path().join("test.txt");
         std::fs::write(&src_file, "new content").unwrap();
@@ -168,7 +157,7 @@ mod tests {
         let dst_file = dst.path().join("test.txt");
         std::fs::write(&dst_file, "original content").unwrap();
 
-        copy_file_strategy(&src_file, &dst_file, MergeStrategy::Replace)
+        copy_file_strategy(&src_file, &dst_file, MergeStrategy::Replace, &mut journal)
             .await
             .unwrap();
 
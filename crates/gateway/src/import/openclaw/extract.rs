@@ -3,21 +3,20 @@
 //! All tar paths pass through [`normalize_tar_path()`] which is the **single source
 //! of truth** for both the dedup key (validation) and the filesystem target (extraction).
 
-use std::io;
+use std::io::{self, Read};
 use std::path::{Component, Path, PathBuf};
 
 use flate2::read::GzDecoder;
 use tar::Archive;
 
+use super::progress::{ImportProgress, ProgressFn, EXTRACT_REPORT_ENTRIES};
 use super::OpenClawImportError;
+use crate::paths::{portable_path, reject_drive_prefix, validate_relative_path};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Constants
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-/// Max path depth to prevent zip-bomb-style deeply nested directories.
-const MAX_PATH_DEPTH: usize = 64;
-
 /// Max total tar entries (including metadata like PAX headers) to prevent
 /// entry-count DoS even without materializing files.
 const MAX_ENTRIES_TOTAL: u64 = 100_000;
@@ -49,10 +48,37 @@ fn max_file_count() -> u64 {
 pub(super) async fn safe_extract_tgz(
     tgz_path: &Path,
     dest_dir: &Path,
+    progress: &ProgressFn<'_>,
+) -> Result<(), OpenClawImportError> {
+    extract_tgz_with_limit(tgz_path, dest_dir, max_extracted_bytes(), progress)
+}
+
+/// [`safe_extract_tgz`] with an explicit cap on bytes actually written.
+///
+/// Header sizes are only claims: GNU sparse maps and PAX size records can
+/// make an entry expand far beyond what `validate_tgz_entries` counted.  The
+/// real output is therefore metered during the copy and extraction aborts as
+/// soon as it exceeds `max_bytes`.
+fn extract_tgz_with_limit(
+    tgz_path: &Path,
+    dest_dir: &Path,
+    max_bytes: u64,
+    progress: &ProgressFn<'_>,
 ) -> Result<(), OpenClawImportError> {
     // Phase 1: Stream validation — check all entries before extracting.
     // This catches path traversal, symlinks, duplicates, size limits, etc.
-    validate_tgz_entries(tgz_path)?;
+    let (total_files, total_bytes) = validate_tgz_entries(tgz_path)?;
+    let mut files: u64 = 0;
+    let mut bytes: u64 = 0;
+    let report = |files, bytes| {
+        progress(ImportProgress::Extracting {
+            files,
+            total_files,
+            bytes,
+            total_bytes,
+        })
+    };
+    report(0, 0);
 
     // Phase 2: Manual extraction with hardened file creation.
     // We do NOT use `unpack_in()` — instead we control every file open.
@@ -97,7 +123,7 @@ pub(super) async fn safe_extract_tgz(
             .into_owned();
 
         // Defense-in-depth: re-validate path even though phase 1 already did
-        validate_relative_path(&raw_path)?;
+        validate_relative_path(&raw_path).map_err(OpenClawImportError::ArchiveInvalid)?;
 
         // Use the same normalized path as validation — ensures the filesystem path
         // matches the dedup key (a/./b → a/b, a//b → a/b, etc.)
@@ -140,7 +166,20 @@ pub(super) async fn safe_extract_tgz(
                         }
                     })?;
 
-                std::io::copy(&mut entry, &mut out_file)?;
+                // Read at most one byte past the remaining budget so an
+                // overflow is detected without writing the whole bomb.
+                let budget = max_bytes.saturating_sub(bytes);
+                let copied = std::io::copy(&mut (&mut entry).take(budget + 1), &mut out_file)?;
+                bytes += copied;
+                if bytes > max_bytes {
+                    drop(out_file);
+                    let _ = std::fs::remove_file(&full_path);
+                    return Err(OpenClawImportError::SizeLimitExceeded(format!(
+                        "extracted output exceeds limit of {max_bytes} bytes at {} \
+                         (archive headers understate entry sizes)",
+                        normalized_path.display()
+                    )));
+                }
 
                 // Safe permissions: strip setuid(04000)/setgid(02000)/sticky(01000)
                 #[cfg(unix)]
@@ -154,14 +193,23 @@ pub(super) async fn safe_extract_tgz(
                 }
             }
         }
+
+        files += 1;
+        if files.is_multiple_of(EXTRACT_REPORT_ENTRIES) {
+            report(files, bytes);
+        }
     }
 
+    report(files, bytes);
     Ok(())
 }
 
 /// Validate tar entries without extracting: check paths, types, cumulative sizes,
 /// and duplicate file paths. Uses streaming (BufReader) — NOT tokio::fs::read.
-fn validate_tgz_entries(tgz_path: &Path) -> Result<(), OpenClawImportError> {
+///
+/// Returns the materialized entry count and their total size, used as the
+/// denominator for extraction progress.
+fn validate_tgz_entries(tgz_path: &Path) -> Result<(u64, u64), OpenClawImportError> {
     let file = std::fs::File::open(tgz_path)?;
     let gz = GzDecoder::new(std::io::BufReader::new(file));
     let mut archive = Archive::new(gz);
@@ -171,6 +219,7 @@ fn validate_tgz_entries(tgz_path: &Path) -> Result<(), OpenClawImportError> {
     let mut total_bytes: u64 = 0;
     let mut total_files: u64 = 0;
     let mut total_entries: u64 = 0;
+    let mut content_bytes: u64 = 0;
     let mut seen_file_paths = std::collections::HashSet::new();
 
     for entry in archive.entries().map_err(|e| {
@@ -236,7 +285,7 @@ fn validate_tgz_entries(tgz_path: &Path) -> Result<(), OpenClawImportError> {
         let path = entry.path().map_err(|e| {
             OpenClawImportError::ArchiveInvalid(format!("tar path read failed: {e}"))
         })?;
-        validate_relative_path(&path)?;
+        validate_relative_path(&path).map_err(OpenClawImportError::ArchiveInvalid)?;
 
         // ── Normalize path and check for collisions ──
         let (normalized_key, _) = normalize_tar_path(&path)?;
@@ -267,8 +316,9 @@ fn validate_tgz_entries(tgz_path: &Path) -> Result<(), OpenClawImportError> {
                 max_files
             )));
         }
+        content_bytes += entry_size;
     }
-    Ok(())
+    Ok((total_files, content_bytes))
 }
 
 /// Normalize a tar path to a canonical form for dedup and filesystem use.
@@ -279,6 +329,7 @@ fn validate_tgz_entries(tgz_path: &Path) -> Result<(), OpenClawImportError> {
 ///
 /// Invariants enforced:
 /// - Rejects non-UTF8 paths and components (encoding bypass prevention)
+/// - Treats `\` as a separator (Windows exporters), see [`portable_path()`]
 /// - Rejects `..` (ParentDir), absolute (`/`, RootDir), and platform prefixes (`C:\`)
 /// - Strips `.` (CurDir) components and collapses repeated separators
 /// - Rejects empty Normal components (e.g. from pathological inputs)
@@ -292,6 +343,8 @@ fn normalize_tar_path(path: &Path) -> Result<(String, PathBuf), OpenClawImportEr
             path.display()
         ))
     })?;
+    reject_drive_prefix(path).map_err(OpenClawImportError::ArchiveInvalid)?;
+    let path = portable_path(path);
 
     // Rebuild from components: this strips `.`, collapses `//`, and normalizes.
     // Dangerous components are hard-rejected here (not just in validate_relative_path)
@@ -351,63 +404,6 @@ fn normalize_tar_path(path: &Path) -> Result<(String, PathBuf), OpenClawImportEr
     Ok((key, normalized))
 }
 
-fn validate_relative_path(path: &Path) -> Result<(), OpenClawImportError> {
-    // Reject empty paths
-    if path.as_os_str().is_empty() {
-        return Err(OpenClawImportError::ArchiveInvalid(
-            "empty path in archive".to_string(),
-        ));
-    }
-    if path.is_absolute() {
-        return Err(OpenClawImportError::ArchiveInvalid(format!(
-            "absolute path in archive: {}",
-            path.display()
-        )));
-    }
-    let mut depth = 0usize;
-    for comp in path.components() {
-        match comp {
-            Component::Normal(_) => {
-                depth += 1;
-            }
-            Component::CurDir => {}
-            Component::ParentDir => {
-                return Err(OpenClawImportError::ArchiveInvalid(format!(
-                    "parent dir traversal in archive: {}",
-                    path.display()
-                )));
-            }
-            Component::Prefix(_) => {
-                return Err(OpenClawImportError::ArchiveInvalid(format!(
-                    "platform prefix in archive path: {}",
-                    path.display()
-                )));
-            }
-            Component::RootDir => {
-                return Err(OpenClawImportError::ArchiveInvalid(format!(
-                    "root dir in archive path: {}",
-                    path.display()
-                )));
-            }
-        }
-    }
-    // Reject paths like "." or "./" that have no real components
-    if depth == 0 {
-        return Err(OpenClawImportError::ArchiveInvalid(format!(
-            "path resolves to empty: {}",
-            path.display()
-        )));
-    }
-    if depth > MAX_PATH_DEPTH {
-        return Err(OpenClawImportError::ArchiveInvalid(format!(
-            "path depth {} exceeds limit of {MAX_PATH_DEPTH}: {}",
-            depth,
-            path.display()
-        )));
-    }
-    Ok(())
-}
-
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Tests
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -415,6 +411,8 @@ fn validate_relative_path(path: &Path) -> Result<(), OpenClawImportError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::import::openclaw::progress::no_progress;
+    use crate::paths::MAX_PATH_DEPTH;
 
     // ── Test helpers ─────────────────────────────────────────────
 
@@ -513,56 +511,6 @@ mod tests {
         tmp
     }
 
-    // ── Path validation ─────────────────────────────────────────
-
-    #[test]
-    fn test_relative_path_ok() {
-        assert!(validate_relative_path(Path::new("agents/main/sessions/foo.jsonl")).is_ok());
-        assert!(validate_relative_path(Path::new("workspace/MEMORY.md")).is_ok());
-        assert!(validate_relative_path(Path::new("workspace-kimi/file.txt")).is_ok());
-    }
-
-    #[test]
-    fn test_relative_path_traversal_rejected() {
-        assert!(validate_relative_path(Path::new("../../../etc/passwd")).is_err());
-        assert!(validate_relative_path(Path::new("agents/../../../etc/shadow")).is_err());
-        assert!(validate_relative_path(Path::new("agents/main/../../..")).is_err());
-    }
-
-    #[test]
-    fn test_absolute_path_rejected() {
-        assert!(validate_relative_path(Path::new("/etc/passwd")).is_err());
-        assert!(validate_relative_path(Path::new("/tmp/evil")).is_err());
-    }
-
-    #[test]
-    fn test_empty_path_rejected() {
-        assert!(validate_relative_path(Path::new("")).is_err());
-    }
-
-    #[test]
-    fn test_curdir_only_rejected() {
-        // "." and "./" resolve to zero Normal components → rejected
-        assert!(validate_relative_path(Path::new(".")).is_err());
-        assert!(validate_relative_path(Path::new("./")).is_err());
-    }
-
-    #[test]
-    fn test_deep_nesting_rejected() {
-        let deep = (0..MAX_PATH_DEPTH + 1)
-            .map(|i| format!("d{i}"))
-            .collect::<Vec<_>>()
-            .join("/");
-        assert!(validate_relative_path(Path::new(&deep)).is_err());
-
-        // Just at the limit should be OK
-        let at_limit = (0..MAX_PATH_DEPTH)
-            .map(|i| format!("d{i}"))
-            .collect::<Vec<_>>()
-            .join("/");
-        assert!(validate_relative_path(Path::new(&at_limit)).is_ok());
-    }
-
     // ── Tar entry validation with real archives ─────────────────
 
     #[test]
@@ -739,6 +687,33 @@ mod tests {
         assert!(normalize_tar_path(Path::new("../x")).is_err());
     }
 
+    #[test]
+    fn test_normalize_tar_path_converts_backslashes() {
+        let (key, pb) = normalize_tar_path(Path::new("workspace\\MEMORY.md")).unwrap();
+        assert_eq!(key, "workspace/MEMORY.md");
+        assert_eq!(pb, Path::new("workspace").join("MEMORY.md"));
+
+        let (key, _) =
+            normalize_tar_path(Path::new("agents\\main\\.\\sessions\\s.jsonl")).unwrap();
+        assert_eq!(key, "agents/main/sessions/s.jsonl");
+        assert!(validate_relative_path(Path::new("workspace\\MEMORY.md")).is_ok());
+    }
+
+    #[test]
+    fn test_backslash_paths_still_reject_escapes() {
+        for bad in [
+            "C:\\evil",
+            "c:evil",
+            "C:/evil",
+            "\\evil",
+            "\\\\server\\share\\x",
+            "workspace\\..\\..\\x",
+        ] {
+            assert!(normalize_tar_path(Path::new(bad)).is_err(), "normalize accepted {bad}");
+            assert!(validate_relative_path(Path::new(bad)).is_err(), "validate accepted {bad}");
+        }
+    }
+
     #[test]
     fn test_normalize_tar_path_rejects_empty_result() {
         assert!(normalize_tar_path(Path::new(".")).is_err());
@@ -755,7 +730,7 @@ mod tests {
         ]);
 
         let dest = tempfile::tempdir().unwrap();
-        let result = safe_extract_tgz(tgz.path(), dest.path()).await;
+        let result = safe_extract_tgz(tgz.path(), dest.path(), &no_progress).await;
         assert!(result.is_ok(), "extract should succeed: {:?}", result);
 
         // Verify files exist
@@ -767,21 +742,47 @@ mod tests {
     async fn test_safe_extract_rejects_traversal() {
         let tgz = create_test_tgz_with_traversal(&[("../../../etc/shadow", b"bad")]);
         let dest = tempfile::tempdir().unwrap();
-        let result = safe_extract_tgz(tgz.path(), dest.path()).await;
+        let result = safe_extract_tgz(tgz.path(), dest.path(), &no_progress).await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_safe_extract_windows_separators() {
+        let tgz = create_test_tgz_with_traversal(&[
+            ("workspace\\MEMORY.md", b"# Memory file"),
+            ("agents\\main\\sessions\\s1.jsonl", b"{}"),
+        ]);
+        let dest = tempfile::tempdir().unwrap();
+        safe_extract_tgz(tgz.path(), dest.path(), &no_progress)
+            .await
+            .unwrap();
+        assert!(dest.path().join("workspace/MEMORY.md").is_file());
+        assert!(dest.path().join("agents/main/sessions/s1.jsonl").is_file());
+        assert!(!dest.path().join("workspace\\MEMORY.md").exists());
+
+        let tgz = create_test_tgz_with_traversal(&[("C:\\evil", b"bad")]);
+        assert!(validate_tgz_entries(tgz.path()).is_err());
+
+        // Both spellings map to the same file.
+        let tgz = create_test_tgz_with_traversal(&[
+            ("workspace/MEMORY.md", b"a"),
+            ("workspace\\MEMORY.md", b"b"),
+        ]);
+        let err = validate_tgz_entries(tgz.path()).unwrap_err().to_string();
+        assert!(err.contains("duplicate"), "{err}");
+    }
+
     #[tokio::test]
     async fn test_safe_extract_create_new_prevents_overwrite() {
         let tgz = create_test_tgz(&[("workspace/MEMORY.md", b"# Memory file")]);
         let dest = tempfile::tempdir().unwrap();
 
         // First extraction should succeed
-        let r1 = safe_extract_tgz(tgz.path(), dest.path()).await;
+        let r1 = safe_extract_tgz(tgz.path(), dest.path(), &no_progress).await;
         assert!(r1.is_ok(), "first extract should succeed: {:?}", r1);
 
         // Second extraction into same dir should fail due to create_new(true)
-        let r2 = safe_extract_tgz(tgz.path(), dest.path()).await;
+        let r2 = safe_extract_tgz(tgz.path(), dest.path(), &no_progress).await;
         assert!(r2.is_err(), "second extract should fail (file collision)");
         let err = r2.unwrap_err().to_string();
         assert!(
@@ -813,7 +814,7 @@ mod tests {
         gz.finish().unwrap();
 
         let dest = tempfile::tempdir().unwrap();
-        let result = safe_extract_tgz(tmp.path(), dest.path()).await;
+        let result = safe_extract_tgz(tmp.path(), dest.path(), &no_progress).await;
         assert!(result.is_ok(), "extract should succeed: {:?}", result);
 
         // Verify setuid bit was stripped
@@ -867,7 +868,7 @@ mod tests {
         gz.finish().unwrap();
 
         let dest = tempfile::tempdir().unwrap();
-        let result = safe_extract_tgz(tmp.path(), dest.path()).await;
+        let result = safe_extract_tgz(tmp.path(), dest.path(), &no_progress).await;
         // Should fail: can't create a file where a directory exists
         assert!(result.is_err(), "dir-then-file collision should fail: {:?}", result);
     }
@@ -908,8 +909,79 @@ mod tests {
         gz.finish().unwrap();
 
         let dest = tempfile::tempdir().unwrap();
-        let result = safe_extract_tgz(tmp.path(), dest.path()).await;
+        let result = safe_extract_tgz(tmp.path(), dest.path(), &no_progress).await;
         // Should fail: create_dir_all on a path that's already a file
         assert!(result.is_err(), "file-then-dir collision should fail: {:?}", result);
     }
+
+    // ── Decompression bomb ──────────────────────────────────────
+
+    /// A GNU sparse entry that stores a single byte but whose sparse map
+    /// expands it to `real_size` bytes on extraction.
+    fn create_sparse_bomb_tgz(real_size: u64) -> tempfile::NamedTempFile {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        fn octal(field: &mut [u8; 12], value: u64) {
+            field.copy_from_slice(format!("{value:011o}\0").as_bytes());
+        }
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let gz = GzEncoder::new(tmp.as_file(), Compression::fast());
+        let mut builder = tar::Builder::new(gz);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::GNUSparse);
+        header.set_size(1);
+        header.set_mode(0o644);
+        {
+            let gnu = header.as_gnu_mut().unwrap();
+            octal(&mut gnu.sparse[0].offset, real_size - 1);
+            octal(&mut gnu.sparse[0].numbytes, 1);
+            octal(&mut gnu.realsize, real_size);
+        }
+        builder
+            .append_data(&mut header, "workspace/bomb.bin", &b"x"[..])
+            .unwrap();
+
+        let gz = builder.into_inner().unwrap();
+        gz.finish().unwrap();
+        tmp
+    }
+
+    #[test]
+    fn test_validate_counts_sparse_real_size() {
+        // The reader reports the expanded size of a sparse entry, not the
+        // single byte stored in the archive.
+        let tgz = create_sparse_bomb_tgz(1_000_000);
+        let (files, bytes) = validate_tgz_entries(tgz.path()).unwrap();
+        assert_eq!((files, bytes), (1, 1_000_000));
+    }
+
+    #[test]
+    fn test_extract_aborts_on_real_byte_overflow() {
+        let tgz = create_sparse_bomb_tgz(1_000_000);
+        let dest = tempfile::tempdir().unwrap();
+
+        let result = extract_tgz_with_limit(tgz.path(), dest.path(), 4096, &no_progress);
+
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, OpenClawImportError::SizeLimitExceeded(_)),
+            "expected size limit error, got: {err}"
+        );
+        // The partial output is removed rather than left at the limit.
+        assert!(!dest.path().join("workspace/bomb.bin").exists());
+    }
+
+    #[test]
+    fn test_extract_within_limit_counts_real_bytes() {
+        let tgz = create_sparse_bomb_tgz(2_000);
+        let dest = tempfile::tempdir().unwrap();
+
+        extract_tgz_with_limit(tgz.path(), dest.path(), 4096, &no_progress).unwrap();
+
+        let written = std::fs::metadata(dest.path().join("workspace/bomb.bin")).unwrap();
+        assert_eq!(written.len(), 2_000);
+    }
 }
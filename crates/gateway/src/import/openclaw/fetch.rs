@@ -1,10 +1,11 @@
 use std::io;
 use std::path::Path;
 use std::process::Stdio;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::process::Command;
 
 use crate::api::import_openclaw::*;
+use super::progress::{ImportProgress, ProgressFn, FETCH_REPORT_BYTES};
 use super::OpenClawImportError;
 use super::redact_secrets;
 
@@ -12,6 +13,7 @@ pub(super) async fn fetch_export_tarball(
     source: &ImportSource,
     options: &ImportOptions,
     tar_path: &Path,
+    progress: &ProgressFn<'_>,
 ) -> Result<(), OpenClawImportError> {
     match source {
         ImportSource::Local { path, .. } => {
@@ -20,7 +22,7 @@ pub(super) async fn fetch_export_tarball(
                     "local path must be absolute".into(),
                 ));
             }
-            fetch_local_tar(path, options, tar_path).await
+            fetch_local_tar(path, options, tar_path, progress).await
         }
         ImportSource::Ssh {
             host,
@@ -50,6 +52,7 @@ pub(super) async fn fetch_export_tarball(
                 auth,
                 options,
                 tar_path,
+                progress,
             )
             .await
         }
@@ -60,8 +63,9 @@ async fn fetch_local_tar(
     openclaw_dir: &Path,
     options: &ImportOptions,
     tar_path: &Path,
+    progress: &ProgressFn<'_>,
 ) -> Result<(), OpenClawImportError> {
-    let includes = build_export_includes(options);
+    let includes = expand_local_includes(openclaw_dir, &build_export_includes(options))?;
     let mut cmd = Command::new("tar");
     cmd.arg("-C")
         .arg(openclaw_dir)
@@ -79,7 +83,7 @@ async fn fetch_local_tar(
     })?;
 
     let mut file = tokio::fs::File::create(tar_path).await?;
-    tokio::io::copy(&mut out, &mut file).await?;
+    copy_with_progress(&mut out, &mut file, progress).await?;
 
     let status = child.wait().await?;
     if !status.success() {
@@ -104,91 +108,342 @@ async fn fetch_ssh_tar(
     auth: &SshAuth,
     options: &ImportOptions,
     tar_path: &Path,
+    progress: &ProgressFn<'_>,
 ) -> Result<(), OpenClawImportError> {
-    let includes = build_export_includes(options);
-
-    // Remote command: tar -C ~/.openclaw -czf - agents workspace workspace-* ...
-    // Run via "sh -lc" to expand workspace-* safely.
-    // Use bash + nullglob so workspace-* expands to nothing when no matches exist.
-    let remote_cmd = format!(
-        "bash -lc {}",
-        shell_escape(&format!(
-            "shopt -s nullglob; tar -C {} -czf - {}",
-            remote_openclaw,
-            includes.join(" ")
-        ))
-    );
-
-    let target = match user {
-        Some(u) => format!("{u}@{host}"),
-        None => host.to_string(),
+    let target = SshTarget {
+        host,
+        user,
+        port,
+        strict_host_key_checking,
+        auth,
     };
 
-    let is_password = matches!(auth, SshAuth::Password { .. });
+    // Build the tarball into a remote temp file first: a live `tar | ssh`
+    // stream cannot be resumed, but a file on disk can be re-read from any
+    // byte offset after a dropped connection.
+    let mut export = SshExport::prepare(target, remote_openclaw, options).await?;
+    if export.len > super::max_tgz_bytes() {
+        export.cleanup().await;
+        return Err(OpenClawImportError::SizeLimitExceeded(format!(
+            "remote tarball is {} bytes, exceeds limit of {} bytes",
+            export.len,
+            super::max_tgz_bytes()
+        )));
+    }
+
+    let result = fetch_resumable(&mut export, tar_path, RetryPolicy::from_env(), progress).await;
+    export.cleanup().await;
+    result
+}
 
-    let mut cmd = if is_password {
-        let mut c = Command::new("sshpass");
-        c.arg("-e"); // read password from SSHPASS env var
-        c.arg("ssh");
-        c
-    } else {
-        Command::new("ssh")
-    };
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Resumable transfer
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-    if is_password {
-        if let SshAuth::Password { password } = auth {
-            cmd.env("SSHPASS", password);
+/// A remote file of known length that can be streamed from any offset.
+#[async_trait::async_trait]
+pub(super) trait ResumableSource: Send {
+    fn len(&self) -> u64;
+
+    /// Append the bytes from `offset` to the end onto `out`.  May fail
+    /// part-way; whatever reached `out` is kept and the next attempt resumes
+    /// after it.
+    async fn copy_from(
+        &mut self,
+        offset: u64,
+        out: &mut tokio::fs::File,
+        progress: &ProgressFn<'_>,
+    ) -> io::Result<()>;
+}
+
+/// Retry-on-interrupt policy for resumable fetches.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RetryPolicy {
+    /// Consecutive attempts without progress before giving up.
+    pub max_attempts: u32,
+    /// Delay before retry `n` is `n * backoff`.
+    pub backoff: std::time::Duration,
+}
+
+impl RetryPolicy {
+    /// `SA_IMPORT_FETCH_ATTEMPTS` (default 5), 2s linear backoff.
+    fn from_env() -> Self {
+        Self {
+            max_attempts: std::env::var("SA_IMPORT_FETCH_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5)
+                .max(1),
+            backoff: std::time::Duration::from_secs(2),
         }
-        cmd.arg("-o").arg("PreferredAuthentications=password,keyboard-interactive");
-    } else {
-        cmd.arg("-o").arg("BatchMode=yes");
-        cmd.arg("-o").arg("PreferredAuthentications=publickey");
-        cmd.arg("-o").arg("KbdInteractiveAuthentication=no");
     }
+}
 
-    if strict_host_key_checking {
-        cmd.arg("-o").arg("StrictHostKeyChecking=yes");
-    } else {
-        cmd.arg("-o").arg("StrictHostKeyChecking=accept-new");
+/// Download `source` into `tar_path`, resuming from the bytes already on
+/// disk after each interruption.  An attempt that makes progress resets the
+/// retry budget, so only a transfer that is genuinely stuck gives up.
+pub(super) async fn fetch_resumable<S: ResumableSource>(
+    source: &mut S,
+    tar_path: &Path,
+    policy: RetryPolicy,
+    progress: &ProgressFn<'_>,
+) -> Result<(), OpenClawImportError> {
+    let total = source.len();
+    let mut file = tokio::fs::File::create(tar_path).await?;
+    let mut offset: u64 = 0;
+    let mut failures: u32 = 0;
+
+    while offset < total {
+        let result = source.copy_from(offset, &mut file, progress).await;
+        file.flush().await?;
+        let written = file.metadata().await?.len();
+
+        let err = match result {
+            Ok(()) if written >= total => {
+                offset = written;
+                break;
+            }
+            Ok(()) => io::Error::new(io::ErrorKind::UnexpectedEof, "transfer ended early"),
+            Err(e) => e,
+        };
+
+        if written > offset {
+            failures = 0;
+        }
+        failures += 1;
+        offset = written;
+        if failures >= policy.max_attempts {
+            return Err(OpenClawImportError::SshFailed(format!(
+                "transfer interrupted at {offset} of {total} bytes after {failures} attempts: {}",
+                redact_secrets(&err.to_string())
+            )));
+        }
+
+        tracing::warn!(
+            offset,
+            total,
+            attempt = failures,
+            error = %err,
+            "import transfer interrupted; resuming"
+        );
+        tokio::time::sleep(policy.backoff * failures).await;
     }
-    cmd.arg("-o").arg("ConnectTimeout=30");
 
-    if let Some(p) = port {
-        cmd.arg("-p").arg(p.to_string());
+    if offset != total {
+        return Err(OpenClawImportError::ArchiveInvalid(format!(
+            "transfer size mismatch: got {offset} bytes, expected {total}"
+        )));
     }
+    progress(ImportProgress::Fetching { bytes: offset });
+    Ok(())
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// SSH
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Remote temp files are created (and only ever deleted) under this prefix.
+const REMOTE_EXPORT_PREFIX: &str = "openclaw-export.";
 
-    match auth {
-        SshAuth::Agent => {}
-        SshAuth::KeyFile { key_path } => {
+#[derive(Clone, Copy)]
+struct SshTarget<'a> {
+    host: &'a str,
+    user: Option<&'a str>,
+    port: Option<u16>,
+    strict_host_key_checking: bool,
+    auth: &'a SshAuth,
+}
+
+impl SshTarget<'_> {
+    /// A hardened `ssh` invocation running `remote_cmd`.
+    fn command(&self, remote_cmd: &str) -> Command {
+        let is_password = matches!(self.auth, SshAuth::Password { .. });
+
+        let mut cmd = if is_password {
+            let mut c = Command::new("sshpass");
+            c.arg("-e"); // read password from SSHPASS env var
+            c.arg("ssh");
+            c
+        } else {
+            Command::new("ssh")
+        };
+
+        if let SshAuth::Password { password } = self.auth {
+            cmd.env("SSHPASS", password);
+            cmd.arg("-o").arg("PreferredAuthentications=password,keyboard-interactive");
+        } else {
+            cmd.arg("-o").arg("BatchMode=yes");
+            cmd.arg("-o").arg("PreferredAuthentications=publickey");
+            cmd.arg("-o").arg("KbdInteractiveAuthentication=no");
+        }
+
+        if self.strict_host_key_checking {
+            cmd.arg("-o").arg("StrictHostKeyChecking=yes");
+        } else {
+            cmd.arg("-o").arg("StrictHostKeyChecking=accept-new");
+        }
+        cmd.arg("-o").arg("ConnectTimeout=30");
+
+        if let Some(p) = self.port {
+            cmd.arg("-p").arg(p.to_string());
+        }
+
+        if let SshAuth::KeyFile { key_path } = self.auth {
             cmd.arg("-i").arg(key_path);
         }
-        SshAuth::Password { .. } => {
-            // handled above via sshpass
+
+        let target = match self.user {
+            Some(u) => format!("{u}@{}", self.host),
+            None => self.host.to_string(),
+        };
+        cmd.arg(&target);
+        cmd.arg(remote_cmd);
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        cmd.kill_on_drop(true);
+        cmd
+    }
+}
+
+/// An export tarball staged in a remote temp file.
+struct SshExport<'a> {
+    target: SshTarget<'a>,
+    remote_file: String,
+    len: u64,
+}
+
+impl<'a> SshExport<'a> {
+    /// Run `tar` remotely into a fresh temp file; returns its path and size.
+    async fn prepare(
+        target: SshTarget<'a>,
+        remote_openclaw: &str,
+        options: &ImportOptions,
+    ) -> Result<Self, OpenClawImportError> {
+        let includes = build_export_includes(options);
+
+        // Use bash + nullglob so workspace-* expands to nothing when no matches exist.
+        let script = format!(
+            "shopt -s nullglob; umask 077; \
+             f=$(mktemp \"${{TMPDIR:-/tmp}}/{REMOTE_EXPORT_PREFIX}XXXXXX\") || exit 1; \
+             tar -C {remote_openclaw} -czf \"$f\" {} || {{ rm -f -- \"$f\"; exit 1; }}; \
+             printf '%s\\n' \"$f\"; wc -c < \"$f\"",
+            includes.join(" ")
+        );
+        let output = target
+            .command(&format!("bash -lc {}", shell_escape(&script)))
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(OpenClawImportError::SshFailed(redact_secrets(
+                &String::from_utf8_lossy(&output.stderr),
+            )));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        let remote_file = lines.next().unwrap_or_default().trim().to_string();
+        let len = lines.next().and_then(|l| l.trim().parse::<u64>().ok());
+        let valid_path = remote_file.starts_with('/')
+            && remote_file
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| name.starts_with(REMOTE_EXPORT_PREFIX));
+        match len {
+            Some(len) if valid_path => Ok(Self {
+                target,
+                remote_file,
+                len,
+            }),
+            _ => Err(OpenClawImportError::SshFailed(format!(
+                "unexpected export output: {}",
+                redact_secrets(&stdout)
+            ))),
         }
     }
 
-    cmd.arg(&target);
-    cmd.arg(&remote_cmd);
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
+    /// Best-effort removal of the remote temp file.
+    async fn cleanup(&self) {
+        let cmd = format!("rm -f -- {}", shell_escape(&self.remote_file));
+        if let Err(e) = self.target.command(&cmd).output().await {
+            tracing::warn!(error = %e, "failed to remove remote export tarball");
+        }
+    }
+}
 
-    let mut child = cmd.spawn()?;
-    let mut out = child.stdout.take().ok_or_else(|| {
-        OpenClawImportError::Io(io::Error::other("missing ssh stdout"))
-    })?;
+#[async_trait::async_trait]
+impl ResumableSource for SshExport<'_> {
+    fn len(&self) -> u64 {
+        self.len
+    }
 
-    let mut file = tokio::fs::File::create(tar_path).await?;
-    tokio::io::copy(&mut out, &mut file).await?;
+    async fn copy_from(
+        &mut self,
+        offset: u64,
+        out: &mut tokio::fs::File,
+        progress: &ProgressFn<'_>,
+    ) -> io::Result<()> {
+        // `tail -c +N` is 1-based.
+        let cmd = format!(
+            "tail -c +{} -- {}",
+            offset + 1,
+            shell_escape(&self.remote_file)
+        );
+        let mut child = self.target.command(&cmd).spawn()?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("missing ssh stdout"))?;
 
-    let status = child.wait().await?;
-    if !status.success() {
-        let mut stderr = String::new();
-        if let Some(mut e) = child.stderr.take() {
-            let _ = e.read_to_string(&mut stderr).await;
+        let resumed = |p: ImportProgress| match p {
+            ImportProgress::Fetching { bytes } => progress(ImportProgress::Fetching {
+                bytes: offset + bytes,
+            }),
+            other => progress(other),
+        };
+        let copied = copy_with_progress(&mut stdout, out, &resumed).await;
+
+        let status = child.wait().await?;
+        copied?;
+        if !status.success() {
+            let mut stderr = String::new();
+            if let Some(mut e) = child.stderr.take() {
+                let _ = e.read_to_string(&mut stderr).await;
+            }
+            return Err(io::Error::other(redact_secrets(&stderr)));
         }
-        return Err(OpenClawImportError::SshFailed(redact_secrets(&stderr)));
+        Ok(())
     }
-    Ok(())
+}
+
+/// `tokio::io::copy` that reports `fetching` progress roughly every
+/// [`FETCH_REPORT_BYTES`] and once at the end.
+async fn copy_with_progress<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    progress: &ProgressFn<'_>,
+) -> io::Result<u64>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut total: u64 = 0;
+    let mut last_reported: u64 = 0;
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await?;
+        total += n as u64;
+        if total - last_reported >= FETCH_REPORT_BYTES {
+            progress(ImportProgress::Fetching { bytes: total });
+            last_reported = total;
+        }
+    }
+    writer.flush().await?;
+    progress(ImportProgress::Fetching { bytes: total });
+    Ok(total)
 }
 
 fn build_export_includes(options: &ImportOptions) -> Vec<String> {
@@ -203,6 +458,31 @@ fn build_export_includes(options: &ImportOptions) -> Vec<String> {
     inc
 }
 
+/// Expand trailing-`*` includes against `dir`, since the local `tar` is
+/// spawned without a shell.  Like bash `nullglob`, a pattern with no
+/// matches expands to nothing; literal includes pass through unchanged.
+fn expand_local_includes(dir: &Path, includes: &[String]) -> io::Result<Vec<String>> {
+    let mut out = Vec::new();
+    for inc in includes {
+        let Some(prefix) = inc.strip_suffix('*') else {
+            out.push(inc.clone());
+            continue;
+        };
+        let mut matches = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            if let Some(name) = name.to_str() {
+                if name.starts_with(prefix) && !name.starts_with('.') {
+                    matches.push(name.to_string());
+                }
+            }
+        }
+        matches.sort();
+        out.extend(matches);
+    }
+    Ok(out)
+}
+
 fn shell_escape(s: &str) -> String {
     let mut out = String::from("'");
     for ch in s.chars() {
@@ -215,3 +495,120 @@ fn shell_escape(s: &str) -> String {
     out.push('\'');
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::openclaw::progress::no_progress;
+
+    /// Serves `data`, dropping the connection after `chunk` bytes per call.
+    struct FlakySource {
+        data: Vec<u8>,
+        chunk: usize,
+        offsets: Vec<u64>,
+    }
+
+    #[async_trait::async_trait]
+    impl ResumableSource for FlakySource {
+        fn len(&self) -> u64 {
+            self.data.len() as u64
+        }
+
+        async fn copy_from(
+            &mut self,
+            offset: u64,
+            out: &mut tokio::fs::File,
+            _progress: &ProgressFn<'_>,
+        ) -> io::Result<()> {
+            self.offsets.push(offset);
+            let start = offset as usize;
+            let end = (start + self.chunk).min(self.data.len());
+            out.write_all(&self.data[start..end]).await?;
+            if end < self.data.len() {
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "connection reset"));
+            }
+            Ok(())
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            backoff: std::time::Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn interrupted_transfer_resumes_from_last_offset() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let mut source = FlakySource {
+            data: data.clone(),
+            chunk: 300,
+            offsets: Vec::new(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let tar_path = dir.path().join("export.tgz");
+
+        fetch_resumable(&mut source, &tar_path, policy(2), &no_progress)
+            .await
+            .unwrap();
+
+        assert_eq!(source.offsets, vec![0, 300, 600, 900]);
+        assert_eq!(std::fs::read(&tar_path).unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn stalled_transfer_gives_up_after_max_attempts() {
+        let mut source = FlakySource {
+            data: vec![7; 100],
+            chunk: 0,
+            offsets: Vec::new(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = fetch_resumable(&mut source, &dir.path().join("x.tgz"), policy(3), &no_progress)
+            .await
+            .unwrap_err();
+
+        assert_eq!(source.offsets, vec![0, 0, 0]);
+        assert!(err.to_string().contains("at 0 of 100 bytes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn resumed_transfer_reports_cumulative_progress() {
+        let mut source = FlakySource {
+            data: vec![1; 50],
+            chunk: 20,
+            offsets: Vec::new(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let seen = std::sync::Mutex::new(Vec::new());
+        let record = |p: ImportProgress| seen.lock().unwrap().push(p);
+
+        fetch_resumable(&mut source, &dir.path().join("x.tgz"), policy(2), &record)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            seen.into_inner().unwrap().last(),
+            Some(&ImportProgress::Fetching { bytes: 50 })
+        );
+    }
+
+    #[test]
+    fn local_includes_expand_workspace_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["workspace", "workspace-b", "workspace-a", "agents"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        let includes = vec!["agents".into(), "workspace".into(), "workspace-*".into()];
+
+        let expanded = expand_local_includes(dir.path(), &includes).unwrap();
+        assert_eq!(expanded, ["agents", "workspace", "workspace-a", "workspace-b"]);
+
+        std::fs::remove_dir(dir.path().join("workspace-a")).unwrap();
+        std::fs::remove_dir(dir.path().join("workspace-b")).unwrap();
+        let expanded = expand_local_includes(dir.path(), &includes).unwrap();
+        assert_eq!(expanded, ["agents", "workspace"]);
+    }
+}
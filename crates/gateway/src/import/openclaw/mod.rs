@@ -41,23 +41,31 @@
 //!
 //! ## Staging lifecycle
 //! - Staging dirs identified by UUID (Axum extracts `Path<Uuid>` — non-UUID rejected at routing)
-//! - Periodic hourly sweep deletes staging >24h old
+//! - Periodic hourly sweep deletes staging >24h old, and apply journals
+//!   (rollback data) >7 days old
 //! - Filesystem identifiers (agent IDs, workspace names) validated via [`sanitize_ident()`]
 
 pub(crate) mod sanitize;
 pub mod config_gen;
 pub mod staging;
+mod conflicts;
 mod copy;
 mod extract;
 mod fetch;
+mod progress;
+mod rollback;
 mod scan;
 
+pub use conflicts::find_import_conflicts;
+pub use rollback::{cleanup_stale_journals, rollback_openclaw_import};
+pub use progress::{no_progress, ImportProgress, ProgressFn};
 pub use staging::{cleanup_stale_staging, delete_staging, list_staging, StagingEntry};
 
 use crate::api::import_openclaw::*;
 use copy::{copy_dir_strategy, copy_glob_strategy, copy_file_strategy};
 use extract::safe_extract_tgz;
 use fetch::fetch_export_tarball;
+use rollback::ApplyJournal;
 use sanitize::sanitize_ident;
 use scan::{scan_inventory, scan_sensitive};
 use scan::redact_secrets;
@@ -96,6 +104,13 @@ pub enum OpenClawImportError {
     Io(#[from] io::Error),
     #[error("json: {0}")]
     Json(#[from] serde_json::Error),
+    /// An apply failed partway.  What it changed is journaled under
+    /// `apply_id` and can be rolled back.
+    #[error("apply {apply_id} failed partway (roll it back to undo partial changes): {source}")]
+    ApplyFailed {
+        apply_id: Uuid,
+        source: Box<OpenClawImportError>,
+    },
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -109,6 +124,27 @@ pub async fn preview_openclaw_import(
     staging_root: &Path,
     workspace_dest_root: &Path,
     sessions_dest_root: &Path,
+) -> Result<ImportPreviewResponse, OpenClawImportError> {
+    preview_openclaw_import_with_progress(
+        source,
+        options,
+        staging_root,
+        workspace_dest_root,
+        sessions_dest_root,
+        &no_progress,
+    )
+    .await
+}
+
+/// [`preview_openclaw_import`], reporting `fetching` → `extracting` →
+/// `scanning` progress through `progress`.
+pub async fn preview_openclaw_import_with_progress(
+    source: ImportSource,
+    options: ImportOptions,
+    staging_root: &Path,
+    workspace_dest_root: &Path,
+    sessions_dest_root: &Path,
+    progress: &ProgressFn<'_>,
 ) -> Result<ImportPreviewResponse, OpenClawImportError> {
     let staging_id = Uuid::new_v4();
     let staging_dir = staging_root.join(staging_id.to_string());
@@ -119,7 +155,7 @@ pub async fn preview_openclaw_import(
 
     // 1) Fetch tarball into staging/raw/export.tgz
     let tar_path = raw_dir.join("openclaw-export.tgz");
-    fetch_export_tarball(&source, &options, &tar_path).await?;
+    fetch_export_tarball(&source, &options, &tar_path, progress).await?;
 
     // 1.5) Check tarball size limit
     let tgz_meta = tokio::fs::metadata(&tar_path).await?;
@@ -135,10 +171,10 @@ pub async fn preview_openclaw_import(
     }
 
     // 2) Safe extract into staging/extracted (validates entries first)
-    safe_extract_tgz(&tar_path, &extracted_dir).await?;
+    safe_extract_tgz(&tar_path, &extracted_dir, progress).await?;
 
     // 3) Scan inventory + detect sensitive
-    let inventory = scan_inventory(&extracted_dir, &options).await?;
+    let inventory = scan_inventory(&extracted_dir, &options, progress).await?;
     let sensitive = scan_sensitive(&extracted_dir, &options).await?;
 
     Ok(ImportPreviewResponse {
@@ -155,6 +191,60 @@ pub async fn preview_openclaw_import(
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Apply: copy staged files to final destinations
+
+/// Session files copied per agent.
+const SESSION_PATTERNS: &[&str] = &["*.jsonl", "*.jsonl.reset.*", "sessions.json"];
+
+/// Where a staged workspace lands under `strategy`.
+fn workspace_dest(root: &Path, rel_path: &str, strategy: MergeStrategy) -> PathBuf {
+    match strategy {
+        MergeStrategy::MergeSafe => root.join("imported").join("openclaw").join(rel_path),
+        MergeStrategy::Replace | MergeStrategy::SkipExisting => root.join(rel_path),
+    }
+}
+
+/// Where an agent's session files land under `strategy`.
+fn sessions_dest(root: &Path, agent_id: &str, strategy: MergeStrategy) -> PathBuf {
+    match strategy {
+        MergeStrategy::MergeSafe => root.join("imported").join("openclaw").join(agent_id),
+        MergeStrategy::Replace | MergeStrategy::SkipExisting => root.join(agent_id),
+    }
+}
+
+/// Where an agent's models.json / auth-profiles.json land (any strategy).
+fn agent_files_dest(workspace_root: &Path, agent_id: &str) -> PathBuf {
+    workspace_root
+        .join("imported")
+        .join("openclaw")
+        .join("agents")
+        .join(agent_id)
+        .join("agent")
+}
+
+/// An empty `only_*` list selects everything.
+fn is_selected(only: &[String], name: &str) -> bool {
+    only.is_empty() || only.iter().any(|n| n == name)
+}
+
+/// Reject `only_*` names that are malformed or absent from the staged export.
+fn check_selection<'a>(
+    what: &str,
+    only: &[String],
+    available: impl Iterator<Item = &'a str>,
+) -> Result<(), OpenClawImportError> {
+    let available: Vec<&str> = available.collect();
+    for name in only {
+        sanitize_ident(name)?;
+        if !available.contains(&name.as_str()) {
+            return Err(OpenClawImportError::InvalidPath(format!(
+                "unknown {what} {name:?} (staged: {})",
+                available.join(", ")
+            )));
+        }
+    }
+    Ok(())
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 pub async fn apply_openclaw_import(
@@ -172,8 +262,57 @@ pub async fn apply_openclaw_import(
         )));
     }
 
-    let inv = scan_inventory(&extracted_dir, &req.options).await?;
-    let warnings = Vec::new();
+    let inv = scan_inventory(&extracted_dir, &req.options, &no_progress).await?;
+    check_selection(
+        "agent",
+        &req.only_agents,
+        inv.agents.iter().map(|a| a.agent_id.as_str()),
+    )?;
+    check_selection(
+        "workspace",
+        &req.only_workspaces,
+        inv.workspaces.iter().map(|w| w.name.as_str()),
+    )?;
+
+    let mut journal = ApplyJournal::create(staging_root, req.staging_id).await?;
+    let result = copy_staged(
+        &req,
+        &inv,
+        &extracted_dir,
+        workspace_dest_root,
+        sessions_dest_root,
+        &mut journal,
+    )
+    .await;
+    // Save even on failure so a partial apply can be rolled back.
+    journal.save().await?;
+
+    let apply_id = journal.apply_id();
+    let imported = result.map_err(|e| OpenClawImportError::ApplyFailed {
+        apply_id,
+        source: Box::new(e),
+    })?;
+    Ok(ImportApplyResponse {
+        staging_id: req.staging_id,
+        apply_id,
+        imported,
+        warnings: Vec::new(),
+    })
+}
+
+async fn copy_staged(
+    req: &ImportApplyRequest,
+    inv: &ImportInventory,
+    extracted_dir: &Path,
+    workspace_dest_root: &Path,
+    sessions_dest_root: &Path,
+    journal: &mut ApplyJournal,
+) -> Result<ImportedSummary, OpenClawImportError> {
+    let agents: Vec<_> = inv
+        .agents
+        .iter()
+        .filter(|a| is_selected(&req.only_agents, &a.agent_id))
+        .collect();
     let mut imported = ImportedSummary {
         dest_workspace_root: workspace_dest_root.to_string_lossy().to_string(),
         dest_sessions_root: sessions_dest_root.to_string_lossy().to_string(),
@@ -183,26 +322,22 @@ pub async fn apply_openclaw_import(
     // ── Workspaces ──────────────────────────────────────────────
     if req.options.include_workspaces {
         for ws in &inv.workspaces {
+            if !is_selected(&req.only_workspaces, &ws.name) {
+                continue;
+            }
             // Validate workspace name
             sanitize_ident(&ws.name)?;
 
             let src = extracted_dir.join(&ws.rel_path);
-            let dst = match req.merge_strategy {
-                MergeStrategy::MergeSafe => workspace_dest_root
-                    .join("imported")
-                    .join("openclaw")
-                    .join(&ws.rel_path),
-                MergeStrategy::Replace => workspace_dest_root.join(&ws.rel_path),
-                MergeStrategy::SkipExisting => workspace_dest_root.join(&ws.rel_path),
-            };
-            copy_dir_strategy(&src, &dst, req.merge_strategy).await?;
+            let dst = workspace_dest(workspace_dest_root, &ws.rel_path, req.merge_strategy);
+            copy_dir_strategy(&src, &dst, req.merge_strategy, journal).await?;
             imported.workspaces.push(dst.to_string_lossy().to_string());
         }
     }
 
     // ── Sessions per agent ──────────────────────────────────────
     if req.options.include_sessions {
-        for a in &inv.agents {
+        for a in &agents {
             // Validate agent ID
             sanitize_ident(&a.agent_id)?;
 
@@ -214,21 +349,15 @@ pub async fn apply_openclaw_import(
                 continue;
             }
 
-            let dst_sessions = match req.merge_strategy {
-                MergeStrategy::MergeSafe => sessions_dest_root
-                    .join("imported")
-                    .join("openclaw")
-                    .join(&a.agent_id),
-                MergeStrategy::Replace => sessions_dest_root.join(&a.agent_id),
-                MergeStrategy::SkipExisting => sessions_dest_root.join(&a.agent_id),
-            };
-            tokio::fs::create_dir_all(&dst_sessions).await?;
+            let dst_sessions = sessions_dest(sessions_dest_root, &a.agent_id, req.merge_strategy);
+            journal.create_dir_all(&dst_sessions).await?;
 
             let copied = copy_glob_strategy(
                 &src_sessions,
                 &dst_sessions,
-                &["*.jsonl", "*.jsonl.reset.*", "sessions.json"],
+                SESSION_PATTERNS,
                 req.merge_strategy,
+                journal,
             )
             .await?;
             imported.sessions_copied += copied;
@@ -239,7 +368,7 @@ pub async fn apply_openclaw_import(
     // ── Models + auth profiles ──────────────────────────────────
     if req.options.include_models || req.options.include_auth_profiles {
 
-        for a in &inv.agents {
+        for a in &agents {
             sanitize_ident(&a.agent_id)?;
 
             let src_agent_dir = extracted_dir
@@ -250,13 +379,8 @@ pub async fn apply_openclaw_import(
                 continue;
             }
 
-            let dst_agent_dir = workspace_dest_root
-                .join("imported")
-                .join("openclaw")
-                .join("agents")
-                .join(&a.agent_id)
-                .join("agent");
-            tokio::fs::create_dir_all(&dst_agent_dir).await?;
+            let dst_agent_dir = agent_files_dest(workspace_dest_root, &a.agent_id);
+            journal.create_dir_all(&dst_agent_dir).await?;
 
             if req.options.include_models {
                 let src = src_agent_dir.join("models.json");
@@ -265,6 +389,7 @@ pub async fn apply_openclaw_import(
                         &src,
                         &dst_agent_dir.join("models.json"),
                         req.merge_strategy,
+                        journal,
                     )
                     .await?;
                 }
@@ -278,6 +403,7 @@ pub async fn apply_openclaw_import(
                         &src,
                         &dst_agent_dir.join("auth-profiles.json"),
                         req.merge_strategy,
+                        journal,
                     )
                     .await?;
                 }
@@ -285,11 +411,7 @@ pub async fn apply_openclaw_import(
         }
     }
 
-    Ok(ImportApplyResponse {
-        staging_id: req.staging_id,
-        imported,
-        warnings,
-    })
+    Ok(imported)
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -405,6 +527,7 @@ pub async fn import_schedules(
             cooldown_until: None,
             routing_profile: None,
             webhook_secret: None,
+            trigger_params: Vec::new(),
             total_input_tokens: 0,
             total_output_tokens: 0,
             total_runs: 0,
@@ -430,4 +553,171 @@ fn walkdir_json(root: &Path, pattern: &str) -> Vec<PathBuf> {
     results
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn preview_reports_phases_in_order() {
+        let openclaw = tempfile::tempdir().unwrap();
+        let sessions = openclaw.path().join("agents/main/sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        std::fs::write(sessions.join("s1.jsonl"), "{\"role\":\"user\"}\n").unwrap();
+        let workspace = openclaw.path().join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::write(workspace.join("AGENTS.md"), "# Agents").unwrap();
+        std::fs::write(workspace.join("SOUL.md"), "# Soul").unwrap();
+
+        let staging = tempfile::tempdir().unwrap();
+        let events = Mutex::new(Vec::new());
+        let record = |p: ImportProgress| events.lock().unwrap().push(p);
+
+        let preview = preview_openclaw_import_with_progress(
+            ImportSource::Local {
+                path: openclaw.path().to_path_buf(),
+                follow_symlinks: false,
+            },
+            ImportOptions::default(),
+            staging.path(),
+            Path::new("/tmp/ws"),
+            Path::new("/tmp/sessions"),
+            &record,
+        )
+        .await
+        .unwrap();
+        assert_eq!(preview.inventory.workspaces.len(), 1);
+
+        let events = events.into_inner().unwrap();
+        let phase = |p: &ImportProgress| match p {
+            ImportProgress::Fetching { .. } => 0,
+            ImportProgress::Extracting { .. } => 1,
+            ImportProgress::Scanning { .. } => 2,
+        };
+        let phases: Vec<u8> = events.iter().map(phase).collect();
+        assert!(phases.windows(2).all(|w| w[0] <= w[1]), "out of order: {phases:?}");
+        for expected in 0..=2 {
+            assert!(phases.contains(&expected), "missing phase {expected}: {phases:?}");
+        }
+
+        // Final counts in each phase reflect the whole import.
+        let last_fetch = events.iter().rev().find_map(|p| match p {
+            ImportProgress::Fetching { bytes } => Some(*bytes),
+            _ => None,
+        });
+        assert!(last_fetch.unwrap() > 0);
+        let last_extract = events.iter().rev().find_map(|p| match p {
+            ImportProgress::Extracting { files, total_files, .. } => Some((*files, *total_files)),
+            _ => None,
+        });
+        let (files, total_files) = last_extract.unwrap();
+        assert_eq!(files, total_files);
+        assert_eq!(
+            events.last(),
+            Some(&ImportProgress::Scanning { files: 3, bytes: 14 })
+        );
+    }
+
+    /// Stage `agents/{main,kimi}/sessions/s.jsonl` plus two workspaces.
+    fn stage_two_agents(staging_root: &Path) -> Uuid {
+        let id = Uuid::new_v4();
+        let extracted = staging_root.join(id.to_string()).join("extracted");
+        for agent in ["main", "kimi"] {
+            let sessions = extracted.join("agents").join(agent).join("sessions");
+            std::fs::create_dir_all(&sessions).unwrap();
+            std::fs::write(sessions.join("s.jsonl"), "{}\n").unwrap();
+        }
+        for ws in ["workspace", "workspace-kimi"] {
+            std::fs::create_dir_all(extracted.join(ws)).unwrap();
+            std::fs::write(extracted.join(ws).join("AGENTS.md"), "# Agents").unwrap();
+        }
+        id
+    }
+
+    fn apply_request(staging_id: Uuid, only_agents: &[&str]) -> ImportApplyRequest {
+        ImportApplyRequest {
+            staging_id,
+            merge_strategy: MergeStrategy::Replace,
+            options: ImportOptions::default(),
+            only_agents: only_agents.iter().map(|s| s.to_string()).collect(),
+            only_workspaces: vec!["workspace".into()],
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_imports_only_selected_agents_and_workspaces() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws_root = tempfile::tempdir().unwrap();
+        let sess_root = tempfile::tempdir().unwrap();
+        let id = stage_two_agents(staging.path());
+
+        let resp = apply_openclaw_import(
+            apply_request(id, &["kimi"]),
+            staging.path(),
+            ws_root.path(),
+            sess_root.path(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(resp.imported.agents, vec!["kimi".to_string()]);
+        assert_eq!(resp.imported.sessions_copied, 1);
+        assert!(sess_root.path().join("kimi/s.jsonl").exists());
+        assert!(!sess_root.path().join("main").exists());
+        assert_eq!(resp.imported.workspaces.len(), 1);
+        assert!(ws_root.path().join("workspace/AGENTS.md").exists());
+        assert!(!ws_root.path().join("workspace-kimi").exists());
+    }
 
+    #[tokio::test]
+    async fn apply_rejects_unknown_or_invalid_selection() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws_root = tempfile::tempdir().unwrap();
+        let sess_root = tempfile::tempdir().unwrap();
+        let id = stage_two_agents(staging.path());
+
+        for bad in ["ghost", "../main"] {
+            let err = apply_openclaw_import(
+                apply_request(id, &[bad]),
+                staging.path(),
+                ws_root.path(),
+                sess_root.path(),
+            )
+            .await
+            .unwrap_err();
+            assert!(matches!(err, OpenClawImportError::InvalidPath(_)), "{bad}: {err}");
+        }
+        // Nothing was copied.
+        assert!(std::fs::read_dir(sess_root.path()).unwrap().next().is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_apply_reports_its_apply_id_for_rollback() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws_root = tempfile::tempdir().unwrap();
+        let id = stage_two_agents(staging.path());
+        // Sessions can't be written under a regular file, so the apply
+        // fails after copying the workspace.
+        let blocked = ws_root.path().join("not-a-dir");
+        std::fs::write(&blocked, "").unwrap();
+
+        let err = apply_openclaw_import(
+            apply_request(id, &["kimi"]),
+            staging.path(),
+            ws_root.path(),
+            &blocked,
+        )
+        .await
+        .unwrap_err();
+        let OpenClawImportError::ApplyFailed { apply_id, .. } = err else {
+            panic!("expected ApplyFailed, got {err}");
+        };
+        assert!(err.to_string().contains(&apply_id.to_string()));
+        assert!(ws_root.path().join("workspace/AGENTS.md").exists());
+
+        rollback_openclaw_import(apply_id, staging.path())
+            .await
+            .unwrap();
+        assert!(!ws_root.path().join("workspace").exists());
+    }
+}
@@ -0,0 +1,37 @@
+//! Phase progress for long-running imports.
+//!
+//! `fetch_export_tarball`, `safe_extract_tgz`, and `scan_inventory` report
+//! through a [`ProgressFn`] callback; the SSE preview endpoint forwards these
+//! as `progress` events so the dashboard can draw a progress bar.
+
+use serde::Serialize;
+
+/// One progress update.  Counts are cumulative within their phase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum ImportProgress {
+    /// Tarball bytes written to staging so far.
+    Fetching { bytes: u64 },
+    /// Entries materialized so far; totals come from the validation pass.
+    Extracting {
+        files: u64,
+        total_files: u64,
+        bytes: u64,
+        total_bytes: u64,
+    },
+    /// Files and bytes counted by the inventory scan so far.
+    Scanning { files: u64, bytes: u64 },
+}
+
+/// Progress callback.  Invoked synchronously from the import task, so it
+/// must be cheap (e.g. push onto a channel).
+pub type ProgressFn<'a> = dyn Fn(ImportProgress) + Send + Sync + 'a;
+
+/// Callback for callers that don't care about progress.
+pub fn no_progress(_: ImportProgress) {}
+
+/// Minimum bytes between `fetching` updates.
+pub(super) const FETCH_REPORT_BYTES: u64 = 1024 * 1024;
+
+/// Minimum entries between `extracting` updates.
+pub(super) const EXTRACT_REPORT_ENTRIES: u64 = 100;
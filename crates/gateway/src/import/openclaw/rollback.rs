@@ -0,0 +1,391 @@
+//! Apply journal and rollback.
+//!
+//! Every file [`apply_openclaw_import`](super::apply_openclaw_import)
+//! writes or removes goes through an [`ApplyJournal`], which backs up
+//! anything it is about to clobber.  The journal lives at
+//! `<staging_root>/applies/<apply_id>/`:
+//!
+//! ```text
+//! manifest.json    ApplyManifest (entries in the order they happened)
+//! backup/<n>       prior content of overwritten / deleted files
+//! ```
+//!
+//! [`rollback_openclaw_import`] replays the manifest backwards: created
+//! files and directories are removed, backups are restored.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::OpenClawImportError;
+use crate::api::import_openclaw::ImportRollbackResponse;
+
+/// Journal directory under the staging root.  Not a UUID, so staging
+/// listing and stale cleanup leave it alone.
+pub(super) const APPLIES_DIR: &str = "applies";
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JournalAction {
+    /// File did not exist before the apply.
+    Created,
+    /// Directory (and any missing parents below it) created by the apply.
+    CreatedDir,
+    /// Existing file replaced; prior content in `backup`.
+    Overwritten,
+    /// Existing file removed (`replace` clears the destination folder);
+    /// prior content in `backup`.
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    action: JournalAction,
+    path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backup: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ApplyManifest {
+    apply_id: Uuid,
+    staging_id: Uuid,
+    created_at: DateTime<Utc>,
+    entries: Vec<JournalEntry>,
+}
+
+/// Records filesystem changes made by one apply.
+pub(super) struct ApplyJournal {
+    dir: PathBuf,
+    manifest: ApplyManifest,
+}
+
+impl ApplyJournal {
+    pub(super) async fn create(
+        staging_root: &Path,
+        staging_id: Uuid,
+    ) -> Result<Self, OpenClawImportError> {
+        let apply_id = Uuid::new_v4();
+        let dir = staging_root.join(APPLIES_DIR).join(apply_id.to_string());
+        tokio::fs::create_dir_all(dir.join("backup")).await?;
+        Ok(Self {
+            dir,
+            manifest: ApplyManifest {
+                apply_id,
+                staging_id,
+                created_at: Utc::now(),
+                entries: Vec::new(),
+            },
+        })
+    }
+
+    pub(super) fn apply_id(&self) -> Uuid {
+        self.manifest.apply_id
+    }
+
+    /// Copy `src` over `dst`, backing up `dst` first if it exists.
+    pub(super) async fn copy_file(
+        &mut self,
+        src: &Path,
+        dst: &Path,
+    ) -> Result<(), OpenClawImportError> {
+        if dst.is_file() {
+            let backup = self.backup(dst).await?;
+            self.record(JournalAction::Overwritten, dst, Some(backup));
+        } else {
+            if let Some(parent) = dst.parent() {
+                self.create_dir_all(parent).await?;
+            }
+            self.record(JournalAction::Created, dst, None);
+        }
+        tokio::fs::copy(src, dst).await?;
+        Ok(())
+    }
+
+    /// Create `dir`, recording the outermost directory that was missing.
+    pub(super) async fn create_dir_all(&mut self, dir: &Path) -> Result<(), OpenClawImportError> {
+        if dir.is_dir() {
+            return Ok(());
+        }
+        let mut outermost = dir;
+        while let Some(parent) = outermost.parent() {
+            if parent.as_os_str().is_empty() || parent.exists() {
+                break;
+            }
+            outermost = parent;
+        }
+        tokio::fs::create_dir_all(dir).await?;
+        self.record(JournalAction::CreatedDir, outermost, None);
+        Ok(())
+    }
+
+    /// Remove `dir` recursively, backing up every regular file in it.
+    pub(super) async fn remove_dir_all(&mut self, dir: &Path) -> Result<(), OpenClawImportError> {
+        let mut stack = vec![dir.to_path_buf()];
+        while let Some(d) = stack.pop() {
+            let mut rd = tokio::fs::read_dir(&d).await?;
+            while let Some(e) = rd.next_entry().await? {
+                let ft = e.file_type().await?;
+                if ft.is_dir() {
+                    stack.push(e.path());
+                } else if ft.is_file() {
+                    let path = e.path();
+                    let backup = self.backup(&path).await?;
+                    self.record(JournalAction::Deleted, &path, Some(backup));
+                }
+            }
+        }
+        tokio::fs::remove_dir_all(dir).await?;
+        Ok(())
+    }
+
+    /// Persist the manifest.  Called whether or not the apply succeeded, so
+    /// a half-finished apply can still be rolled back.
+    pub(super) async fn save(&self) -> Result<(), OpenClawImportError> {
+        let json = serde_json::to_vec_pretty(&self.manifest)?;
+        tokio::fs::write(self.dir.join(MANIFEST_FILE), json).await?;
+        Ok(())
+    }
+
+    async fn backup(&mut self, path: &Path) -> Result<String, OpenClawImportError> {
+        let name = format!("backup/{}", self.manifest.entries.len());
+        tokio::fs::copy(path, self.dir.join(&name)).await?;
+        Ok(name)
+    }
+
+    fn record(&mut self, action: JournalAction, path: &Path, backup: Option<String>) {
+        self.manifest.entries.push(JournalEntry {
+            action,
+            path: path.to_path_buf(),
+            backup,
+        });
+    }
+}
+
+/// Undo an apply recorded under `staging_root`.  The journal is deleted on
+/// success, so an apply can only be rolled back once.
+pub async fn rollback_openclaw_import(
+    apply_id: Uuid,
+    staging_root: &Path,
+) -> Result<ImportRollbackResponse, OpenClawImportError> {
+    let dir = staging_root.join(APPLIES_DIR).join(apply_id.to_string());
+    let manifest_path = dir.join(MANIFEST_FILE);
+    if !manifest_path.exists() {
+        return Err(OpenClawImportError::InvalidPath(format!(
+            "apply_id {apply_id} not found"
+        )));
+    }
+    let manifest: ApplyManifest = serde_json::from_slice(&tokio::fs::read(&manifest_path).await?)?;
+
+    let mut resp = ImportRollbackResponse {
+        apply_id,
+        staging_id: manifest.staging_id,
+        removed: 0,
+        restored: 0,
+        warnings: Vec::new(),
+    };
+
+    for entry in manifest.entries.iter().rev() {
+        match entry.action {
+            JournalAction::Created => match tokio::fs::remove_file(&entry.path).await {
+                Ok(()) => resp.removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => resp
+                    .warnings
+                    .push(format!("could not remove {}: {e}", entry.path.display())),
+            },
+            JournalAction::CreatedDir => {
+                if !remove_empty_dirs(&entry.path) {
+                    resp.warnings.push(format!(
+                        "left {} in place: it contains files not written by the import",
+                        entry.path.display()
+                    ));
+                }
+            }
+            JournalAction::Overwritten | JournalAction::Deleted => {
+                let Some(backup) = &entry.backup else {
+                    continue;
+                };
+                if let Some(parent) = entry.path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::copy(dir.join(backup), &entry.path).await?;
+                resp.restored += 1;
+            }
+        }
+    }
+
+    tokio::fs::remove_dir_all(&dir).await?;
+    Ok(resp)
+}
+
+/// Delete apply journals older than `max_age` seconds, after which those
+/// applies can no longer be rolled back.  Call this from a periodic
+/// background task.
+pub async fn cleanup_stale_journals(
+    staging_root: &Path,
+    max_age_secs: u64,
+) -> Result<u32, std::io::Error> {
+    super::staging::cleanup_stale_dirs(
+        &staging_root.join("openclaw").join(APPLIES_DIR),
+        max_age_secs,
+    )
+    .await
+}
+
+/// Remove `dir` if it holds nothing but (recursively) empty directories.
+/// Returns `false` if anything was left behind.
+fn remove_empty_dirs(dir: &Path) -> bool {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return !dir.exists();
+    };
+    let mut empty = true;
+    for e in rd.flatten() {
+        let is_dir = e.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+        if !is_dir || !remove_empty_dirs(&e.path()) {
+            empty = false;
+        }
+    }
+    empty && std::fs::remove_dir(dir).is_ok()
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::super::apply_openclaw_import;
+    use super::*;
+    use crate::api::import_openclaw::{ImportApplyRequest, ImportOptions, MergeStrategy};
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn stage(staging_root: &Path) -> Uuid {
+        let id = Uuid::new_v4();
+        let x = staging_root.join(id.to_string()).join("extracted");
+        write(&x.join("workspace/AGENTS.md"), "imported agents");
+        write(&x.join("workspace/memory/notes.md"), "imported notes");
+        write(&x.join("agents/main/sessions/s1.jsonl"), "{}\n");
+        id
+    }
+
+    async fn apply(staging: &Path, ws: &Path, sess: &Path, strategy: MergeStrategy) -> Uuid {
+        let req = ImportApplyRequest {
+            staging_id: stage(staging),
+            merge_strategy: strategy,
+            options: ImportOptions::default(),
+            only_agents: Vec::new(),
+            only_workspaces: Vec::new(),
+        };
+        apply_openclaw_import(req, staging, ws, sess)
+            .await
+            .unwrap()
+            .apply_id
+    }
+
+    #[tokio::test]
+    async fn rollback_removes_imported_files() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws = tempfile::tempdir().unwrap();
+        let sess = tempfile::tempdir().unwrap();
+
+        let apply_id = apply(
+            staging.path(),
+            ws.path(),
+            sess.path(),
+            MergeStrategy::MergeSafe,
+        )
+        .await;
+        assert!(ws
+            .path()
+            .join("imported/openclaw/workspace/memory/notes.md")
+            .exists());
+        assert!(sess.path().join("imported/openclaw/main/s1.jsonl").exists());
+
+        let resp = rollback_openclaw_import(apply_id, staging.path())
+            .await
+            .unwrap();
+        assert_eq!(resp.removed, 3);
+        assert_eq!(resp.restored, 0);
+        assert!(resp.warnings.is_empty(), "{:?}", resp.warnings);
+        assert!(!ws.path().join("imported").exists());
+        assert!(!sess.path().join("imported").exists());
+
+        // The journal is consumed.
+        let err = rollback_openclaw_import(apply_id, staging.path())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenClawImportError::InvalidPath(_)));
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_overwritten_and_deleted_files() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws = tempfile::tempdir().unwrap();
+        let sess = tempfile::tempdir().unwrap();
+        write(&ws.path().join("workspace/AGENTS.md"), "my agents");
+        write(&ws.path().join("workspace/TOOLS.md"), "my tools");
+        write(&sess.path().join("main/s1.jsonl"), "my session\n");
+
+        let apply_id = apply(
+            staging.path(),
+            ws.path(),
+            sess.path(),
+            MergeStrategy::Replace,
+        )
+        .await;
+        let agents = ws.path().join("workspace/AGENTS.md");
+        assert_eq!(std::fs::read_to_string(&agents).unwrap(), "imported agents");
+        assert!(!ws.path().join("workspace/TOOLS.md").exists());
+
+        let resp = rollback_openclaw_import(apply_id, staging.path())
+            .await
+            .unwrap();
+        assert_eq!(resp.restored, 3);
+        assert_eq!(std::fs::read_to_string(&agents).unwrap(), "my agents");
+        assert_eq!(
+            std::fs::read_to_string(ws.path().join("workspace/TOOLS.md")).unwrap(),
+            "my tools"
+        );
+        assert_eq!(
+            std::fs::read_to_string(sess.path().join("main/s1.jsonl")).unwrap(),
+            "my session\n"
+        );
+        assert!(!ws.path().join("workspace/memory").exists());
+    }
+
+    #[tokio::test]
+    async fn rollback_keeps_directories_with_foreign_files() {
+        let staging = tempfile::tempdir().unwrap();
+        let ws = tempfile::tempdir().unwrap();
+        let sess = tempfile::tempdir().unwrap();
+
+        let apply_id = apply(
+            staging.path(),
+            ws.path(),
+            sess.path(),
+            MergeStrategy::MergeSafe,
+        )
+        .await;
+        let later = ws.path().join("imported/openclaw/workspace/later.md");
+        write(&later, "written after the import");
+
+        let resp = rollback_openclaw_import(apply_id, staging.path())
+            .await
+            .unwrap();
+        assert!(later.exists());
+        assert!(!ws
+            .path()
+            .join("imported/openclaw/workspace/AGENTS.md")
+            .exists());
+        assert_eq!(resp.warnings.len(), 1);
+    }
+}
@@ -5,6 +5,7 @@ use glob::glob;
 use serde_json::Value;
 
 use crate::api::import_openclaw::*;
+use super::progress::{ImportProgress, ProgressFn};
 use super::OpenClawImportError;
 use super::sanitize::sanitize_ident;
 
@@ -15,8 +16,12 @@ use super::sanitize::sanitize_ident;
 pub(super) async fn scan_inventory(
     extracted_root: &Path,
     options: &ImportOptions,
+    progress: &ProgressFn<'_>,
 ) -> Result<ImportInventory, OpenClawImportError> {
     let mut inv = ImportInventory::default();
+    let mut scanned_files: u64 = 0;
+    let mut scanned_bytes: u64 = 0;
+    progress(ImportProgress::Scanning { files: 0, bytes: 0 });
 
     // ── Agents ──────────────────────────────────────────────────
     let agents_dir = extracted_root.join("agents");
@@ -60,6 +65,11 @@ pub(super) async fn scan_inventory(
                 has_models_json: options.include_models && models_json.exists(),
                 has_auth_profiles_json: options.include_auth_profiles && auth_json.exists(),
             });
+            scanned_files += u64::from(session_files);
+            progress(ImportProgress::Scanning {
+                files: scanned_files,
+                bytes: scanned_bytes,
+            });
         }
     }
     inv.agents.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
@@ -76,6 +86,12 @@ pub(super) async fn scan_inventory(
                 approx_files: files,
                 approx_bytes: bytes,
             });
+            scanned_files += u64::from(files);
+            scanned_bytes += bytes;
+            progress(ImportProgress::Scanning {
+                files: scanned_files,
+                bytes: scanned_bytes,
+            });
         }
 
         // Check workspace-* directories
@@ -102,6 +118,12 @@ pub(super) async fn scan_inventory(
                         approx_files: files,
                         approx_bytes: bytes,
                     });
+                    scanned_files += u64::from(files);
+                    scanned_bytes += bytes;
+                    progress(ImportProgress::Scanning {
+                        files: scanned_files,
+                        bytes: scanned_bytes,
+                    });
                 }
             }
         }
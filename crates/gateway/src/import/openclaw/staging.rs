@@ -19,21 +19,32 @@ pub async fn cleanup_stale_staging(
     staging_root: &Path,
     max_age_secs: u64,
 ) -> Result<u32, io::Error> {
-    let openclaw_root = staging_root.join("openclaw");
-    if !openclaw_root.exists() {
+    cleanup_stale_dirs(&staging_root.join("openclaw"), max_age_secs).await
+}
+
+/// Delete UUID-named dirs directly under `root` older than `max_age`
+/// seconds.  Other entries are left alone.
+pub(crate) async fn cleanup_stale_dirs(root: &Path, max_age_secs: u64) -> Result<u32, io::Error> {
+    if !root.exists() {
         return Ok(0);
     }
 
     let now = std::time::SystemTime::now();
     let mut removed = 0u32;
 
-    let mut rd = tokio::fs::read_dir(&openclaw_root).await?;
+    let mut rd = tokio::fs::read_dir(root).await?;
     while let Some(entry) = rd.next_entry().await? {
         let ft = entry.file_type().await?;
         if !ft.is_dir() {
             continue;
         }
 
+        // Only UUID-named staging dirs; apply journals live alongside.
+        let name = entry.file_name().to_string_lossy().to_string();
+        if Uuid::parse_str(&name).is_err() {
+            continue;
+        }
+
         let meta = entry.metadata().await?;
         let created = meta
             .created()
@@ -1,8 +1,12 @@
+// The OpenAPI spec in `api::admin::health` is one large `json!` literal.
+#![recursion_limit = "256"]
+
 pub mod api;
 pub mod bootstrap;
 pub mod cli;
 pub mod import;
 pub mod nodes;
+pub mod paths;
 pub mod pruning;
 pub mod runtime;
 pub mod skills;
@@ -49,6 +49,10 @@ async fn main() -> anyhow::Result<()> {
             sa_gateway::cli::config::show(&config);
             Ok(())
         }
+        Some(Command::Config(ConfigCommand::Migrate)) => {
+            // Deliberately not `load_config()`: old files may not parse yet.
+            sa_gateway::cli::config::migrate(&sa_gateway::cli::config_path())
+        }
         Some(Command::Config(ConfigCommand::SetSecret { provider_id })) => {
             let (config, _config_path) = sa_gateway::cli::load_config()?;
             sa_gateway::cli::config::set_secret(&config, &provider_id)?;
@@ -59,6 +63,11 @@ async fn main() -> anyhow::Result<()> {
             sa_gateway::cli::config::get_secret(&config, &provider_id)?;
             Ok(())
         }
+        Some(Command::Config(ConfigCommand::RotateSecret { provider_id })) => {
+            let (config, _config_path) = sa_gateway::cli::load_config()?;
+            sa_gateway::cli::config::rotate_secret(&config, &provider_id).await?;
+            Ok(())
+        }
         Some(Command::Config(ConfigCommand::Login { provider_id })) => {
             let (config, _config_path) = sa_gateway::cli::load_config()?;
             sa_gateway::cli::login::login(&config, &provider_id).await?;
@@ -67,10 +76,17 @@ async fn main() -> anyhow::Result<()> {
         Some(Command::Init { defaults }) => {
             sa_gateway::cli::init::init(defaults)
         }
-        Some(Command::Run { message, session, model, json }) => {
+        Some(Command::Run {
+            message,
+            session,
+            model,
+            json,
+            stream,
+        }) => {
             init_cli_tracing();
             let (config, _) = sa_gateway::cli::load_config()?;
-            sa_gateway::cli::run::run(Arc::new(config), message, session, model, json).await
+            sa_gateway::cli::run::run(Arc::new(config), message, session, model, json, stream)
+                .await
         }
         Some(Command::Chat { session, model }) => {
             init_cli_tracing();
@@ -93,6 +109,17 @@ async fn main() -> anyhow::Result<()> {
             let (config, _) = sa_gateway::cli::load_config()?;
             sa_gateway::cli::import_cmd::run(config, import_cmd).await
         }
+        Some(Command::Tail {
+            run,
+            status,
+            schedule,
+            url,
+        }) => {
+            init_cli_tracing();
+            let (config, _) = sa_gateway::cli::load_config()?;
+            let filter = sa_gateway::cli::tail::TailFilter { status, schedule };
+            sa_gateway::cli::tail::tail(&config, url, run, filter).await
+        }
     }
 }
 
@@ -190,7 +217,7 @@ async fn run_server(
     // ── Build shared state & spawn background loops ──────────────────
     let shutdown_tx = Arc::new(tokio::sync::Notify::new());
     let state = bootstrap::build_app_state(config.clone(), config_path, shutdown_tx.clone()).await?;
-    bootstrap::spawn_background_tasks(&state);
+    let flushers = bootstrap::spawn_background_tasks(&state);
 
     // ── CORS layer (config-aware) ────────────────────────────────────
     let cors_layer = build_cors_layer(&config.server.cors);
@@ -203,28 +230,14 @@ async fn run_server(
     tracing::info!(max_concurrent, "concurrency limit set");
 
     // ── Rate-limit layer (per-IP token bucket via governor) ─────────
-    let governor_layer = config.server.rate_limit.as_ref().map(|rl| {
-        use tower_governor::governor::GovernorConfigBuilder;
-        use tower_governor::GovernorLayer;
-
-        let gov_config = GovernorConfigBuilder::default()
-            .per_second(rl.requests_per_second)
-            .burst_size(rl.burst_size)
-            .finish()
-            .expect("rate_limit: requests_per_second and burst_size must be > 0");
-
-        tracing::info!(
+    let rate_limit = config.server.rate_limit.as_ref();
+    match rate_limit {
+        Some(rl) => tracing::info!(
             requests_per_second = rl.requests_per_second,
             burst_size = rl.burst_size,
             "per-IP rate limiting enabled"
-        );
-
-        GovernorLayer {
-            config: std::sync::Arc::new(gov_config),
-        }
-    });
-    if governor_layer.is_none() {
-        tracing::info!("per-IP rate limiting disabled (no [server.rate_limit] in config)");
+        ),
+        None => tracing::info!("per-IP rate limiting disabled (no [server.rate_limit] in config)"),
     }
 
     // ── Router ───────────────────────────────────────────────────────
@@ -237,8 +250,8 @@ async fn run_server(
             .nest_service("/app", spa)
             .layer(cors_layer)
             .layer(tower::limit::ConcurrencyLimitLayer::new(max_concurrent));
-        if let Some(gov) = governor_layer {
-            router.layer(gov).with_state(state.clone())
+        if let Some(rl) = rate_limit {
+            api::rate_limit::with_rate_limit(router, rl).with_state(state.clone())
         } else {
             router.with_state(state.clone())
         }
@@ -247,8 +260,8 @@ async fn run_server(
         let router = api::router(state.clone())
             .layer(cors_layer)
             .layer(tower::limit::ConcurrencyLimitLayer::new(max_concurrent));
-        if let Some(gov) = governor_layer {
-            router.layer(gov).with_state(state.clone())
+        if let Some(rl) = rate_limit {
+            api::rate_limit::with_rate_limit(router, rl).with_state(state.clone())
         } else {
             router.with_state(state.clone())
         }
@@ -264,20 +277,54 @@ async fn run_server(
         .context("PID file")?;
 
     // ── Bind ─────────────────────────────────────────────────────────
+    // Under systemd `Type=notify`, READY=1 is sent once the socket is bound.
+    let sd_notifier = sa_gateway::cli::sd_notify::Notifier::from_env().map(Arc::new);
     let addr = format!("{}:{}", config.server.host, config.server.port);
-    let listener = tokio::net::TcpListener::bind(&addr)
+    let listener = sa_gateway::cli::sd_notify::bind_and_notify(&addr, sd_notifier.as_deref())
         .await
         .with_context(|| format!("binding to {addr}"))?;
 
     tracing::info!(addr = %addr, "SerialAgent listening");
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(shutdown_tx))
-        .await
-        .context("axum server error")?;
+    let watchdog = sd_notifier.clone().and_then(|n| {
+        let interval = sa_gateway::cli::sd_notify::watchdog_interval()?;
+        tracing::info!(interval_ms = interval.as_millis() as u64, "systemd watchdog enabled");
+        Some(sa_gateway::cli::sd_notify::spawn_watchdog(n, interval))
+    });
+
+    // Connect info supplies the peer address the rate limiter keys on.
+    let stopping = sd_notifier.clone();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown_signal(shutdown_tx).await;
+        if let Some(n) = stopping {
+            n.stopping();
+        }
+    })
+    .await
+    .context("axum server error")?;
+
+    if let Some(handle) = watchdog {
+        handle.abort();
+    }
 
-    // ── Post-shutdown flush ─────────────────────────────────────────
-    tracing::info!("server stopped, flushing stores...");
+    // ── Drain in-flight work & flush stores ─────────────────────────
+    tracing::info!("server stopped, draining in-flight turns and flushing stores...");
+    flushers.stop().await;
+    let report = sa_gateway::runtime::shutdown::drain_and_flush(
+        &state,
+        std::time::Duration::from_secs(config.server.shutdown_grace_secs),
+    )
+    .await;
+    tracing::info!(
+        turns_drained = report.turns_drained,
+        node_requests_failed = report.node_requests_failed,
+        nodes_disconnected = report.nodes_disconnected,
+        "drain complete"
+    );
 
     // Flush and shut down the OTel tracer provider so pending spans
     // are exported before the process exits.
@@ -287,11 +334,6 @@ async fn run_server(
         }
     }
 
-    if let Err(e) = state.sessions.flush().await {
-        tracing::warn!(error = %e, "session store flush on shutdown failed");
-    }
-    state.delivery_store.flush_if_dirty().await;
-
     // ── PID file cleanup ────────────────────────────────────────────
     if let (Some(path), Some(handle)) = (&config.server.pid_file, pid_handle) {
         sa_gateway::cli::pid::remove_pid_file(path, handle);
@@ -7,7 +7,7 @@ use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::Serialize;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 
 /// A message the gateway can push to a connected node's WebSocket.
 pub type NodeSink = mpsc::Sender<sa_protocol::WsMessage>;
@@ -42,6 +42,78 @@ pub struct NodeInfo {
     pub last_seen: DateTime<Utc>,
 }
 
+/// Node topology change, broadcast to SSE subscribers.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    /// A node registered (first connection or reconnect).  `capabilities`
+    /// are the ones left after the allowlist filter.
+    Connected {
+        node_id: String,
+        node_type: String,
+        name: String,
+        capabilities: Vec<String>,
+    },
+    /// A node left the registry, either because its WebSocket closed or
+    /// because [`NodeRegistry::prune_stale`] dropped it after going quiet.
+    Disconnected {
+        node_id: String,
+        node_type: String,
+        name: String,
+        capabilities: Vec<String>,
+        last_seen: DateTime<Utc>,
+        reason: DisconnectReason,
+        /// Seconds since `last_seen` (pruned nodes only).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        idle_secs: Option<i64>,
+    },
+    /// Removed by [`NodeRegistry::prune_stale`] after going quiet.  Sent
+    /// right after the matching `Disconnected`.
+    Pruned {
+        node_id: String,
+        name: String,
+        last_seen: DateTime<Utc>,
+        idle_secs: i64,
+    },
+}
+
+/// Why a node left the registry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The node's connection closed.
+    Closed,
+    /// No traffic within the stale timeout.
+    Pruned,
+}
+
+impl NodeEvent {
+    fn connected(node: &ConnectedNode) -> Self {
+        Self::Connected {
+            node_id: node.node_id.clone(),
+            node_type: node.node_type.clone(),
+            name: node.name.clone(),
+            capabilities: node.capabilities.clone(),
+        }
+    }
+
+    fn disconnected(
+        node: &ConnectedNode,
+        reason: DisconnectReason,
+        idle_secs: Option<i64>,
+    ) -> Self {
+        Self::Disconnected {
+            node_id: node.node_id.clone(),
+            node_type: node.node_type.clone(),
+            name: node.name.clone(),
+            capabilities: node.capabilities.clone(),
+            last_seen: node.last_seen,
+            reason,
+            idle_secs,
+        }
+    }
+}
+
 /// Thread-safe registry of all connected nodes.
 ///
 /// Supports optional per-node capability allowlists. When configured,
@@ -59,6 +131,7 @@ pub struct NodeRegistry {
     /// Cached `list()` output, invalidated by generation changes.
     /// Avoids deep-cloning all node data on every call.
     list_cache: RwLock<(u64, Arc<Vec<NodeInfo>>)>,
+    event_tx: broadcast::Sender<NodeEvent>,
 }
 
 impl Default for NodeRegistry {
@@ -74,9 +147,15 @@ impl NodeRegistry {
             allowlists: RwLock::new(HashMap::new()),
             generation: AtomicU64::new(0),
             list_cache: RwLock::new((0, Arc::new(Vec::new()))),
+            event_tx: broadcast::channel(64).0,
         }
     }
 
+    /// Subscribe to node topology events.
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.event_tx.subscribe()
+    }
+
     /// Return the current generation counter. Callers can compare this
     /// against a cached value to detect topology changes.
     pub fn generation(&self) -> u64 {
@@ -167,16 +246,45 @@ impl NodeRegistry {
             capabilities = node.capabilities.len(),
             "node registered"
         );
+        let event = NodeEvent::connected(&node);
         self.nodes.write().insert(id, node);
         self.generation.fetch_add(1, Ordering::Relaxed);
+        let _ = self.event_tx.send(event);
     }
 
     /// Remove a node (on disconnect).
     pub fn remove(&self, node_id: &str) {
-        if self.nodes.write().remove(node_id).is_some() {
-            self.generation.fetch_add(1, Ordering::Relaxed);
-            tracing::info!(node_id = %node_id, "node removed");
-        }
+        self.remove_if(node_id, |_| true);
+    }
+
+    /// Remove a node only while it is still the connection identified by
+    /// `session_id`.  A node that reconnected under the same id has a new
+    /// session, so the old connection's cleanup leaves it alone.  Returns
+    /// whether the node was removed.
+    pub fn remove_session(&self, node_id: &str, session_id: &str) -> bool {
+        self.remove_if(node_id, |n| n.session_id == session_id)
+    }
+
+    fn remove_if(&self, node_id: &str, matches: impl FnOnce(&ConnectedNode) -> bool) -> bool {
+        let removed = {
+            let mut nodes = self.nodes.write();
+            if nodes.get(node_id).is_some_and(matches) {
+                nodes.remove(node_id)
+            } else {
+                None
+            }
+        };
+        let Some(node) = removed else {
+            return false;
+        };
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        tracing::info!(node_id = %node_id, "node removed");
+        let _ = self.event_tx.send(NodeEvent::disconnected(
+            &node,
+            DisconnectReason::Closed,
+            None,
+        ));
+        true
     }
 
     /// Update the last_seen timestamp (called on pong or any message).
@@ -304,19 +412,41 @@ impl NodeRegistry {
         self.nodes.read().is_empty()
     }
 
-    /// Remove nodes that haven't been seen for longer than `timeout_secs`.
+    /// Remove nodes that haven't been seen for `timeout_secs` or longer,
+    /// emitting a [`NodeEvent::Disconnected`] (reason `pruned`) followed by
+    /// a [`NodeEvent::Pruned`] for each.
     pub fn prune_stale(&self, timeout_secs: i64) {
         let now = Utc::now();
-        let mut nodes = self.nodes.write();
-        let before = nodes.len();
-        nodes.retain(|_, n| {
-            let age = now.signed_duration_since(n.last_seen).num_seconds();
-            age < timeout_secs
-        });
-        let pruned = before - nodes.len();
-        if pruned > 0 {
-            self.generation.fetch_add(1, Ordering::Relaxed);
-            tracing::info!(pruned, remaining = nodes.len(), "pruned stale nodes");
+        let mut pruned = Vec::new();
+        let remaining = {
+            let mut nodes = self.nodes.write();
+            nodes.retain(|_, n| {
+                let age = now.signed_duration_since(n.last_seen).num_seconds();
+                if age < timeout_secs {
+                    return true;
+                }
+                pruned.push(NodeEvent::disconnected(
+                    n,
+                    DisconnectReason::Pruned,
+                    Some(age),
+                ));
+                pruned.push(NodeEvent::Pruned {
+                    node_id: n.node_id.clone(),
+                    name: n.name.clone(),
+                    last_seen: n.last_seen,
+                    idle_secs: age,
+                });
+                false
+            });
+            nodes.len()
+        };
+        if pruned.is_empty() {
+            return;
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        tracing::info!(pruned = pruned.len() / 2, remaining, "pruned stale nodes");
+        for event in pruned {
+            let _ = self.event_tx.send(event);
         }
     }
 }
@@ -446,4 +576,138 @@ mod tests {
         assert_eq!(reg.len(), 0);
         assert!(reg.is_empty());
     }
+
+    #[test]
+    fn prune_stale_respects_threshold_and_emits_event() {
+        let reg = NodeRegistry::new();
+
+        let mut fresh = make_node("fresh", "t", vec![]);
+        fresh.last_seen = Utc::now() - chrono::Duration::seconds(85);
+        let mut stale = make_node("stale", "t", vec![]);
+        stale.last_seen = Utc::now() - chrono::Duration::seconds(95);
+        reg.register(fresh);
+        reg.register(stale);
+        let mut rx = reg.subscribe();
+
+        reg.prune_stale(90);
+
+        assert_eq!(reg.len(), 1);
+        assert_eq!(reg.list()[0].node_id, "fresh");
+        match rx.try_recv().unwrap() {
+            NodeEvent::Disconnected {
+                node_id,
+                reason,
+                idle_secs,
+                ..
+            } => {
+                assert_eq!(node_id, "stale");
+                assert_eq!(reason, DisconnectReason::Pruned);
+                assert!(idle_secs.unwrap() >= 95);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match rx.try_recv().unwrap() {
+            NodeEvent::Pruned {
+                node_id, idle_secs, ..
+            } => {
+                assert_eq!(node_id, "stale");
+                assert!(idle_secs >= 95);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn connect_and_prune_emit_lifecycle_events_with_metadata() {
+        let reg = NodeRegistry::new();
+        let mut rx = reg.subscribe();
+
+        let mut node = make_node("mac1", "macos", vec!["macos.notes", "macos.calendar"]);
+        node.last_seen = Utc::now() - chrono::Duration::seconds(120);
+        reg.register(node);
+
+        match rx.try_recv().unwrap() {
+            NodeEvent::Connected {
+                node_id,
+                node_type,
+                capabilities,
+                ..
+            } => {
+                assert_eq!(node_id, "mac1");
+                assert_eq!(node_type, "macos");
+                assert_eq!(capabilities, ["macos.notes", "macos.calendar"]);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        reg.prune_stale(60);
+        let event = rx.try_recv().unwrap();
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "disconnected");
+        assert_eq!(json["reason"], "pruned");
+        assert_eq!(json["node_id"], "mac1");
+        assert_eq!(json["node_type"], "macos");
+        assert_eq!(
+            json["capabilities"],
+            serde_json::json!(["macos.notes", "macos.calendar"])
+        );
+        assert!(json["idle_secs"].as_i64().unwrap() >= 120);
+        // `node.pruned` keeps its original payload for existing subscribers.
+        let pruned = serde_json::to_value(rx.try_recv().unwrap()).unwrap();
+        assert_eq!(pruned["type"], "pruned");
+        assert_eq!(pruned["node_id"], "mac1");
+        assert!(pruned["idle_secs"].as_i64().unwrap() >= 120);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn closing_a_connection_emits_disconnected() {
+        let reg = NodeRegistry::new();
+        reg.register(make_node("pi", "linux", vec!["home.lights"]));
+        let mut rx = reg.subscribe();
+
+        reg.remove("pi");
+        reg.remove("pi"); // already gone: no second event
+        match rx.try_recv().unwrap() {
+            NodeEvent::Disconnected {
+                node_id,
+                capabilities,
+                reason,
+                idle_secs,
+                ..
+            } => {
+                assert_eq!(node_id, "pi");
+                assert_eq!(capabilities, ["home.lights"]);
+                assert_eq!(reason, DisconnectReason::Closed);
+                assert_eq!(idle_secs, None);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn stale_connection_cleanup_keeps_the_reconnected_node() {
+        let reg = NodeRegistry::new();
+        let mut old = make_node("mac1", "macos", vec![]);
+        old.session_id = "old".into();
+        reg.register(old);
+        let mut new = make_node("mac1", "macos", vec![]);
+        new.session_id = "new".into();
+        reg.register(new);
+        let mut rx = reg.subscribe();
+
+        // The old socket closes after the node already reconnected.
+        assert!(!reg.remove_session("mac1", "old"));
+        assert_eq!(reg.len(), 1);
+        assert!(rx.try_recv().is_err(), "no false disconnect");
+
+        assert!(reg.remove_session("mac1", "new"));
+        assert!(reg.is_empty());
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            NodeEvent::Disconnected { .. }
+        ));
+    }
 }
@@ -8,14 +8,14 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
 use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::oneshot;
 
-use sa_protocol::WsMessage;
+use sa_protocol::{ErrorKind, ToolResponseError, WsMessage};
 
 use super::registry::NodeRegistry;
 
@@ -30,6 +30,10 @@ pub struct ToolRouteResult {
     pub result: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Machine-readable failure class (e.g. `timeout` when the node never
+    /// answered).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ErrorKind>,
     /// Where the call was dispatched: "node:<id>", "local:exec", "local:process".
     pub routed_to: String,
 }
@@ -51,13 +55,45 @@ pub enum LocalTool {
     Process,
 }
 
+/// Tool-call latency for one node, as returned by
+/// [`ToolRouter::latency_snapshot`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NodeLatency {
+    pub calls: u64,
+    pub timeouts: u64,
+    pub mean_latency_ms: f64,
+    pub max_latency_ms: u64,
+}
+
+/// Outcome of [`ToolRouter::selftest`].
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeSelfTest {
+    pub node_id: String,
+    pub success: bool,
+    /// Round trip from sending the request to receiving the response.
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ErrorKind>,
+}
+
+/// Running latency counters for one node.
+#[derive(Default)]
+struct LatencyCounters {
+    calls: u64,
+    timeouts: u64,
+    total_ms: u64,
+    max_ms: u64,
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Pending request tracker
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 struct PendingRequest {
     node_id: String,
-    tx: oneshot::Sender<(bool, Value, Option<String>)>,
+    tx: oneshot::Sender<(bool, Value, Option<ToolResponseError>)>,
 }
 
 /// Internal state protected by a single mutex to keep pending requests
@@ -110,8 +146,12 @@ pub struct ToolRouter {
     /// Map of request_id → pending oneshot sender + owning node_id,
     /// plus per-node in-flight counters.
     pending: Mutex<PendingState>,
-    /// Timeout for node tool requests.
+    /// Default timeout for node tool requests.
     timeout: Duration,
+    /// Per-node overrides of `timeout`.
+    node_timeouts: HashMap<String, Duration>,
+    /// Per-node tool-call latency.
+    latency: Mutex<HashMap<String, LatencyCounters>>,
     /// Maximum pending requests per node (0 = unlimited).
     max_pending_per_node: usize,
     /// Maximum pending requests globally (0 = unlimited).
@@ -124,11 +164,69 @@ impl ToolRouter {
             nodes,
             pending: Mutex::new(PendingState::new()),
             timeout: Duration::from_secs(timeout_secs),
+            node_timeouts: HashMap::new(),
+            latency: Mutex::new(HashMap::new()),
             max_pending_per_node: 50,
             max_pending_global: 200,
         }
     }
 
+    /// Set the default node tool timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Cap the requests a single node may have outstanding (0 = unlimited).
+    pub fn with_max_outstanding(mut self, max: usize) -> Self {
+        self.max_pending_per_node = max;
+        self
+    }
+
+    /// Override the tool timeout for specific nodes.
+    pub fn with_node_timeouts(mut self, node_timeouts: HashMap<String, Duration>) -> Self {
+        self.node_timeouts = node_timeouts;
+        self
+    }
+
+    fn timeout_for(&self, node_id: &str) -> Duration {
+        self.node_timeouts
+            .get(node_id)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    fn record_latency(&self, node_id: &str, elapsed: Duration, timed_out: bool) {
+        let ms = elapsed.as_millis() as u64;
+        let mut latency = self.latency.lock();
+        let c = latency.entry(node_id.to_owned()).or_default();
+        c.calls += 1;
+        if timed_out {
+            c.timeouts += 1;
+        }
+        c.total_ms += ms;
+        c.max_ms = c.max_ms.max(ms);
+    }
+
+    /// Tool-call latency per node that has handled at least one call.
+    pub fn latency_snapshot(&self) -> HashMap<String, NodeLatency> {
+        self.latency
+            .lock()
+            .iter()
+            .map(|(node_id, c)| {
+                (
+                    node_id.clone(),
+                    NodeLatency {
+                        calls: c.calls,
+                        timeouts: c.timeouts,
+                        mean_latency_ms: c.total_ms as f64 / c.calls.max(1) as f64,
+                        max_latency_ms: c.max_ms,
+                    },
+                )
+            })
+            .collect()
+    }
+
     /// Determine where a tool call should be routed.
     pub fn resolve(&self, tool_name: &str) -> ToolDestination {
         // Check local tools first.
@@ -168,6 +266,7 @@ impl ToolRouter {
                         "global pending limit reached ({} requests in-flight)",
                         pending.len()
                     )),
+                    error_kind: Some(ErrorKind::Failed),
                     routed_to: format!("node:{node_id}"),
                 };
             }
@@ -178,8 +277,9 @@ impl ToolRouter {
                         success: false,
                         result: Value::Null,
                         error: Some(format!(
-                            "per-node pending limit reached ({node_count} requests in-flight for node {node_id})"
+                            "node {node_id} overloaded ({node_count} requests outstanding)"
                         )),
+                        error_kind: Some(ErrorKind::Failed),
                         routed_to: format!("node:{node_id}"),
                     };
                 }
@@ -214,6 +314,7 @@ impl ToolRouter {
                     success: false,
                     result: Value::Null,
                     error: Some(format!("node {node_id} not connected")),
+                    error_kind: None,
                     routed_to: format!("node:{node_id}"),
                 };
             }
@@ -225,16 +326,23 @@ impl ToolRouter {
                 success: false,
                 result: Value::Null,
                 error: Some(format!("failed to send to node {node_id}")),
+                error_kind: None,
                 routed_to: format!("node:{node_id}"),
             };
         }
 
-        // Wait for the response with timeout.
-        match tokio::time::timeout(self.timeout, rx).await {
+        // Wait for the response with timeout.  On timeout the pending entry
+        // is dropped, so a late `tool_response` is ignored.
+        let timeout = self.timeout_for(node_id);
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(timeout, rx).await;
+        self.record_latency(node_id, started.elapsed(), outcome.is_err());
+        match outcome {
             Ok(Ok((success, result, error))) => ToolRouteResult {
                 success,
                 result,
-                error,
+                error_kind: error.as_ref().map(|e| e.kind),
+                error: error.map(|e| format!("{}: {}", e.kind, e.message)),
                 routed_to: format!("node:{node_id}"),
             },
             Ok(Err(_)) => {
@@ -243,32 +351,73 @@ impl ToolRouter {
                     success: false,
                     result: Value::Null,
                     error: Some(format!("node {node_id} disconnected before responding")),
+                    error_kind: None,
                     routed_to: format!("node:{node_id}"),
                 }
             }
             Err(_) => {
                 // Timeout.
                 self.pending.lock().remove(&request_id);
+                tracing::warn!(
+                    node_id = %node_id,
+                    tool = %tool_name,
+                    timeout_ms = timeout.as_millis() as u64,
+                    "node tool request timed out"
+                );
                 ToolRouteResult {
                     success: false,
                     result: Value::Null,
                     error: Some(format!(
-                        "tool request to node {node_id} timed out after {}s",
-                        self.timeout.as_secs()
+                        "tool request to node {node_id} timed out after {}ms",
+                        timeout.as_millis()
                     )),
+                    error_kind: Some(ErrorKind::Timeout),
                     routed_to: format!("node:{node_id}"),
                 }
             }
         }
     }
 
+    /// Send the standard [`SELFTEST_TOOL`] request to `node_id` and report
+    /// whether it answered with the expected nonce, and how fast.  Subject
+    /// to the node's usual timeout and outstanding-request limits.
+    ///
+    /// [`SELFTEST_TOOL`]: sa_protocol::SELFTEST_TOOL
+    pub async fn selftest(&self, node_id: &str) -> NodeSelfTest {
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let started = Instant::now();
+        let route = self
+            .dispatch_to_node(
+                node_id,
+                sa_protocol::SELFTEST_TOOL,
+                serde_json::json!({ "nonce": nonce }),
+                None,
+            )
+            .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let echoed = route.result.get("nonce").and_then(Value::as_str) == Some(nonce.as_str());
+        let (success, error) = match (route.success, echoed) {
+            (false, _) => (false, route.error),
+            (true, false) => (false, Some("self-test reply did not echo the nonce".into())),
+            (true, true) => (true, None),
+        };
+        NodeSelfTest {
+            node_id: node_id.to_owned(),
+            success,
+            latency_ms,
+            error,
+            error_kind: route.error_kind,
+        }
+    }
+
     /// Called by the WS handler when a node sends a `tool_response`.
     pub fn complete_request(
         &self,
         request_id: &str,
         success: bool,
         result: Value,
-        error: Option<String>,
+        error: Option<ToolResponseError>,
     ) {
         if let Some(pending) = self.pending.lock().remove(request_id) {
             let _ = pending.tx.send((success, result, error));
@@ -297,7 +446,10 @@ impl ToolRouter {
                 let _ = pr.tx.send((
                     false,
                     Value::Null,
-                    Some(format!("node {node_id} disconnected")),
+                    Some(ToolResponseError {
+                        kind: ErrorKind::Failed,
+                        message: format!("node {node_id} disconnected"),
+                    }),
                 ));
             }
         }
@@ -316,6 +468,11 @@ impl ToolRouter {
     pub fn pending_count(&self) -> usize {
         self.pending.lock().len()
     }
+
+    /// Outstanding tool requests per node (nodes with none are omitted).
+    pub fn outstanding_counts(&self) -> HashMap<String, usize> {
+        self.pending.lock().node_counts.clone()
+    }
 }
 
 #[cfg(test)]
@@ -391,7 +548,7 @@ mod tests {
             None,
         );
 
-        let (success, result, error) = rx.await.unwrap();
+        let (success, result, _error) = rx.await.unwrap();
         assert!(success);
         assert_eq!(result, serde_json::json!({"result": "ok"}));
         assert_eq!(router.pending_count(), 0);
@@ -418,4 +575,201 @@ mod tests {
         assert_eq!(failed, 2);
         assert_eq!(router.pending_count(), 1); // only n2's request remains
     }
+
+    #[tokio::test]
+    async fn slow_node_times_out_with_timeout_kind() {
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = ToolRouter::new(nodes.clone(), 30)
+            .with_node_timeouts(HashMap::from([("slow".into(), Duration::from_millis(50))]));
+        // The node accepts the request but never answers.
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        nodes.register(super::super::registry::ConnectedNode {
+            node_id: "slow".into(),
+            node_type: "t".into(),
+            name: "slow".into(),
+            capabilities: vec!["slow.tool".into()],
+            version: "0.1.0".into(),
+            tags: vec![],
+            session_id: "s1".into(),
+            connected_at: chrono::Utc::now(),
+            last_seen: chrono::Utc::now(),
+            sink: tx,
+        });
+
+        let result = router
+            .dispatch_to_node("slow", "slow.tool", serde_json::json!({}), None)
+            .await;
+
+        assert!(!result.success);
+        assert_eq!(result.error_kind, Some(ErrorKind::Timeout));
+        assert_eq!(router.pending_count(), 0);
+
+        let latency = &router.latency_snapshot()["slow"];
+        assert_eq!(latency.calls, 1);
+        assert_eq!(latency.timeouts, 1);
+        assert!(latency.max_latency_ms >= 50);
+
+        // A late response for the abandoned request is ignored.
+        let Some(WsMessage::ToolRequest { request_id, .. }) = rx.recv().await else {
+            panic!("expected the tool_request to reach the node");
+        };
+        router.complete_request(&request_id, true, serde_json::json!("late"), None);
+        assert_eq!(router.pending_count(), 0);
+    }
+
+    fn register_node(
+        nodes: &NodeRegistry,
+        node_id: &str,
+    ) -> tokio::sync::mpsc::Receiver<WsMessage> {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        nodes.register(super::super::registry::ConnectedNode {
+            node_id: node_id.into(),
+            node_type: "t".into(),
+            name: node_id.into(),
+            capabilities: vec![],
+            version: "0.1.0".into(),
+            tags: vec![],
+            session_id: "s1".into(),
+            connected_at: chrono::Utc::now(),
+            last_seen: chrono::Utc::now(),
+            sink: tx,
+        });
+        rx
+    }
+
+    #[tokio::test]
+    async fn selftest_reports_success_for_responsive_node() {
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = Arc::new(ToolRouter::new(nodes.clone(), 30));
+        let mut rx = register_node(&nodes, "live");
+
+        // Answer like the SDK's built-in handler: echo the nonce.
+        let responder = router.clone();
+        tokio::spawn(async move {
+            while let Some(WsMessage::ToolRequest {
+                request_id,
+                tool,
+                args,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(tool, sa_protocol::SELFTEST_TOOL);
+                let result = serde_json::json!({ "ok": true, "nonce": args["nonce"] });
+                responder.complete_request(&request_id, true, result, None);
+            }
+        });
+
+        let report = router.selftest("live").await;
+        assert!(report.success, "{report:?}");
+        assert_eq!(report.node_id, "live");
+        assert!(report.error.is_none());
+        assert!(report.latency_ms < 30_000);
+    }
+
+    #[tokio::test]
+    async fn selftest_reports_timeout_for_dead_node() {
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = ToolRouter::new(nodes.clone(), 30)
+            .with_node_timeouts(HashMap::from([("dead".into(), Duration::from_millis(50))]));
+        // The node is registered but never answers.
+        let _rx = register_node(&nodes, "dead");
+
+        let report = router.selftest("dead").await;
+        assert!(!report.success);
+        assert_eq!(report.error_kind, Some(ErrorKind::Timeout));
+        assert!(report.latency_ms >= 50);
+        assert_eq!(router.pending_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn selftest_rejects_wrong_nonce() {
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = Arc::new(ToolRouter::new(nodes.clone(), 30));
+        let mut rx = register_node(&nodes, "odd");
+
+        let responder = router.clone();
+        tokio::spawn(async move {
+            if let Some(WsMessage::ToolRequest { request_id, .. }) = rx.recv().await {
+                let result = serde_json::json!({ "ok": true, "nonce": "stale" });
+                responder.complete_request(&request_id, true, result, None);
+            }
+        });
+
+        let report = router.selftest("odd").await;
+        assert!(!report.success);
+        assert!(report.error.unwrap().contains("nonce"));
+    }
+
+    #[tokio::test]
+    async fn node_error_kind_is_preserved() {
+        let (_, router) = make_router();
+        let (tx, rx) = oneshot::channel();
+        router.pending.lock().insert(
+            "req-1".into(),
+            PendingRequest {
+                node_id: "n1".into(),
+                tx,
+            },
+        );
+        router.complete_request(
+            "req-1",
+            false,
+            Value::Null,
+            Some(ToolResponseError {
+                kind: ErrorKind::NotAllowed,
+                message: "outside allowed dir".into(),
+            }),
+        );
+        let (_, _, error) = rx.await.unwrap();
+        assert_eq!(error.unwrap().kind, ErrorKind::NotAllowed);
+    }
+
+    #[tokio::test]
+    async fn exceeding_outstanding_limit_fails_fast() {
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = Arc::new(ToolRouter::new(nodes.clone(), 30).with_max_outstanding(2));
+        // The node accepts requests but never answers.
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        nodes.register(super::super::registry::ConnectedNode {
+            node_id: "busy".into(),
+            node_type: "t".into(),
+            name: "busy".into(),
+            capabilities: vec!["busy.tool".into()],
+            version: "0.1.0".into(),
+            tags: vec![],
+            session_id: "s1".into(),
+            connected_at: chrono::Utc::now(),
+            last_seen: chrono::Utc::now(),
+            sink: tx,
+        });
+
+        let mut waiting = Vec::new();
+        for _ in 0..2 {
+            let router = router.clone();
+            waiting.push(tokio::spawn(async move {
+                router
+                    .dispatch_to_node("busy", "busy.tool", serde_json::json!({}), None)
+                    .await
+            }));
+        }
+        while router.pending_count() < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(router.outstanding_counts().get("busy"), Some(&2));
+
+        let rejected = router
+            .dispatch_to_node("busy", "busy.tool", serde_json::json!({}), None)
+            .await;
+        assert!(!rejected.success);
+        assert_eq!(rejected.error_kind, Some(ErrorKind::Failed));
+        assert!(rejected.error.unwrap().contains("overloaded"));
+        // The rejected call never became outstanding.
+        assert_eq!(router.outstanding_counts().get("busy"), Some(&2));
+
+        assert_eq!(router.fail_pending_for_node("busy"), 2);
+        for handle in waiting {
+            assert!(!handle.await.unwrap().success);
+        }
+        assert!(router.outstanding_counts().is_empty());
+    }
 }
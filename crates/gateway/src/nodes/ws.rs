@@ -1,16 +1,20 @@
 //! WebSocket endpoint for node connections.
 //!
 //! Flow:
-//! 1. Node connects to `/v1/nodes/ws?token=<pre-shared-token>`
+//! 1. Node connects to `/v1/nodes/ws?node_id=<id>` with
+//!    `Authorization: Bearer <pre-shared-token>` (the `token` query param
+//!    is still accepted but deprecated: URLs end up in logs and proxies)
 //! 2. Node sends `node_hello` with its NodeInfo + capabilities
 //! 3. Gateway responds with `gateway_welcome`
 //! 4. Bidirectional message loop: gateway sends `tool_request`,
 //!    node sends `tool_response`, both exchange `ping`/`pong`
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use axum::extract::ws::{Message, WebSocket};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
 use axum::extract::{Query, State, WebSocketUpgrade};
+use axum::http::HeaderMap;
 use axum::response::IntoResponse;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
@@ -20,8 +24,11 @@ use tokio::sync::mpsc;
 use sha2::{Sha256, Digest};
 use subtle::ConstantTimeEq;
 
-use sa_protocol::{NodeInfo, WsMessage, PROTOCOL_VERSION};
+use sa_domain::config::NodesConfig;
+use sa_protocol::{ErrorKind, NodeInfo, WsMessage, PROTOCOL_VERSION};
+use sa_skills::deps::parse_version;
 
+use crate::api::error::ApiError;
 use crate::nodes::registry::{ConnectedNode, NodeRegistry};
 use crate::state::AppState;
 
@@ -37,71 +44,260 @@ fn token_eq(a: &str, b: &str) -> bool {
 // Query params
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct WsQuery {
-    /// Pre-shared token for node authentication.
+    /// Pre-shared token for node authentication.  Deprecated in favour of
+    /// the `Authorization: Bearer` header.
     pub token: Option<String>,
-    /// Optional node_id hint (for per-node token validation).
+    /// The node's id; selects its per-node token.
     pub node_id: Option<String>,
 }
 
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Authentication
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Where the node presented its token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenSource {
+    Header,
+    /// Deprecated `?token=` query param.
+    Query,
+    None,
+}
+
+/// Pick the presented token: the `Authorization: Bearer` header wins over
+/// the query param.
+fn presented_token<'a>(headers: &'a HeaderMap, query: &'a WsQuery) -> (&'a str, TokenSource) {
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match (bearer, query.token.as_deref()) {
+        (Some(tok), _) => (tok, TokenSource::Header),
+        (None, Some(tok)) => (tok, TokenSource::Query),
+        (None, None) => ("", TokenSource::None),
+    }
+}
+
+/// How an admitted node authenticated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthMode {
+    PerNode,
+    Shared,
+    /// No tokens configured (dev mode).
+    Open,
+}
+
+/// Why a node was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthReject {
+    InvalidToken,
+    Revoked,
+}
+
+impl IntoResponse for AuthReject {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::InvalidToken => ApiError::new(
+                axum::http::StatusCode::UNAUTHORIZED,
+                "invalid or missing node token",
+            )
+            .into_response(),
+            Self::Revoked => {
+                ApiError::new(axum::http::StatusCode::FORBIDDEN, "node has been revoked")
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// Node token policy, resolved from `[nodes]` config and the environment.
+///
+/// A node with its own token must present exactly that token; the shared
+/// token only admits nodes without one.  With no tokens at all every
+/// non-revoked node is admitted.
+#[derive(Debug, Default)]
+struct NodeAuth {
+    /// node_id → its own token.  `None` when the configured env var is
+    /// unset: the node is refused rather than falling back to the shared
+    /// token.
+    per_node: HashMap<String, Option<String>>,
+    shared: Option<String>,
+    revoked: HashSet<String>,
+}
+
+impl NodeAuth {
+    fn from_env(cfg: &NodesConfig) -> Self {
+        Self::from_sources(
+            cfg,
+            std::env::var("SA_NODE_TOKENS").ok().as_deref(),
+            std::env::var("SA_NODE_TOKEN").ok(),
+            |var| std::env::var(var).ok(),
+        )
+    }
+
+    /// Merge `[nodes.tokens]` (resolved through `lookup`) with the raw
+    /// `SA_NODE_TOKENS` value (`"node1:tokA,node2:tokB"`).
+    fn from_sources(
+        cfg: &NodesConfig,
+        env_pairs: Option<&str>,
+        shared: Option<String>,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut per_node = HashMap::new();
+        for pair in env_pairs.unwrap_or("").split(',') {
+            if let Some((nid, tok)) = pair.trim().split_once(':') {
+                per_node.insert(nid.to_owned(), Some(tok.to_owned()));
+            }
+        }
+        for (node_id, env_var) in &cfg.tokens {
+            let token = lookup(env_var).filter(|t| !t.is_empty());
+            if token.is_none() {
+                tracing::warn!(
+                    node_id = %node_id,
+                    env_var = %env_var,
+                    "per-node token env var is not set; node will be refused"
+                );
+            }
+            per_node.insert(node_id.clone(), token);
+        }
+        Self {
+            per_node,
+            shared: shared.filter(|t| !t.is_empty()),
+            revoked: cfg.revoked.iter().cloned().collect(),
+        }
+    }
+
+    /// Check `token` for a node claiming to be `node_id` (`None` when the
+    /// node didn't say who it is).
+    fn check(&self, node_id: Option<&str>, token: &str) -> Result<AuthMode, AuthReject> {
+        if let Some(id) = node_id {
+            if self.revoked.contains(id) {
+                return Err(AuthReject::Revoked);
+            }
+            if let Some(own) = self.per_node.get(id) {
+                return match own {
+                    Some(expected) if token_eq(token, expected) => Ok(AuthMode::PerNode),
+                    _ => Err(AuthReject::InvalidToken),
+                };
+            }
+        }
+        match &self.shared {
+            Some(expected) if token_eq(token, expected) => Ok(AuthMode::Shared),
+            Some(_) => Err(AuthReject::InvalidToken),
+            None if self.per_node.is_empty() => Ok(AuthMode::Open),
+            None => Err(AuthReject::InvalidToken),
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Version compatibility
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Check a `node_hello` against the `[nodes]` minimums.  Returns the
+/// reason sent to the node in the close frame when it is refused.
+fn check_compat(
+    cfg: &NodesConfig,
+    protocol_version: u32,
+    node_version: &str,
+) -> Result<(), String> {
+    if protocol_version > PROTOCOL_VERSION {
+        return Err(format!(
+            "protocol {protocol_version} is newer than gateway protocol {PROTOCOL_VERSION}"
+        ));
+    }
+    if protocol_version < cfg.min_protocol_version {
+        return Err(format!(
+            "protocol {protocol_version} is below minimum {}",
+            cfg.min_protocol_version
+        ));
+    }
+    if let Some(min) = cfg.min_node_version.as_deref() {
+        match (parse_version(node_version), parse_version(min)) {
+            (Some(have), Some(want)) if have.parts >= want.parts => {}
+            (Some(_), Some(_)) => {
+                return Err(format!("node version {node_version} is below {min}"));
+            }
+            (None, _) => return Err(format!("unparseable node version {node_version:?}")),
+            (_, None) => {
+                // A bad minimum in config shouldn't lock every node out.
+                tracing::warn!(min_node_version = %min, "ignoring unparseable min_node_version");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Close frame refusing an incompatible node.  The reason is prefixed with
+/// [`ErrorKind::NotAllowed`] and capped at the 123 bytes a close frame
+/// allows.
+fn incompatible_close_frame(reason: &str) -> CloseFrame<'static> {
+    let mut reason = format!("{}: {reason}", ErrorKind::NotAllowed);
+    if reason.len() > 123 {
+        let mut end = 123;
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        reason.truncate(end);
+    }
+    CloseFrame {
+        code: close_code::POLICY,
+        reason: reason.into(),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Handler
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 /// GET /v1/nodes/ws — upgrade to WebSocket.
 ///
-/// Authentication (checked in priority order):
-/// 1. `SA_NODE_TOKENS` env: `"node1:tokA,node2:tokB"` — per-node tokens.
-///    The `node_id` query param selects which token to check.
-/// 2. `SA_NODE_TOKEN` env: single global token for all nodes.
+/// The token is read from `Authorization: Bearer <token>`, falling back to
+/// the deprecated `token` query param, and checked against:
+/// 1. The node's own token (`[nodes.tokens]` / `SA_NODE_TOKENS`), selected
+///    by the `node_id` query param.  Nodes in `[nodes] revoked` are refused.
+/// 2. `SA_NODE_TOKEN` env: shared token for nodes without their own.
 /// 3. Neither set → unauthenticated (open access, dev mode).
+///
+/// The identity in `node_hello` is checked again after the upgrade, so a
+/// node can't authenticate as one node and register as another.
+///
+/// Mounted on the public router: the bearer token here is a node token,
+/// which the API token middleware would reject.
 pub async fn node_ws(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<WsQuery>,
 ) -> impl IntoResponse {
-    let provided = query.token.as_deref().unwrap_or("");
-
-    // Per-node tokens: SA_NODE_TOKENS="node1:tokA,node2:tokB"
-    if let Ok(tokens_raw) = std::env::var("SA_NODE_TOKENS") {
-        let node_hint = query.node_id.as_deref().unwrap_or("");
-        let valid = tokens_raw.split(',').any(|pair| {
-            if let Some((nid, tok)) = pair.trim().split_once(':') {
-                (node_hint.is_empty() || nid == node_hint) && token_eq(tok, provided)
-            } else {
-                false
-            }
-        });
-        if !valid {
-            return (
-                axum::http::StatusCode::UNAUTHORIZED,
-                "invalid or missing node token",
-            )
-                .into_response();
-        }
-    } else if let Ok(expected) = std::env::var("SA_NODE_TOKEN") {
-        // Global token fallback.
-        if !token_eq(provided, &expected) {
-            return (
-                axum::http::StatusCode::UNAUTHORIZED,
-                "invalid or missing node token",
-            )
-                .into_response();
+    let auth = NodeAuth::from_env(&state.config.nodes);
+    let (token, source) = presented_token(&headers, &query);
+    let token = token.to_owned();
+
+    let mode = match auth.check(query.node_id.as_deref(), &token) {
+        Ok(mode) => mode,
+        Err(reject) => {
+            tracing::warn!(
+                node_id = query.node_id.as_deref().unwrap_or(""),
+                reason = ?reject,
+                "node WS upgrade refused"
+            );
+            return reject.into_response();
         }
-    }
-
-    // Log authentication mode for observability.
-    let auth_mode = if std::env::var("SA_NODE_TOKENS").is_ok() {
-        "per_node_token"
-    } else if std::env::var("SA_NODE_TOKEN").is_ok() {
-        "global_token"
-    } else {
-        "unauthenticated"
     };
-    tracing::debug!(auth_mode, "node WS upgrade accepted");
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    if source == TokenSource::Query {
+        tracing::warn!(
+            node_id = query.node_id.as_deref().unwrap_or(""),
+            "node authenticated via deprecated token query param; \
+             send Authorization: Bearer instead"
+        );
+    }
+    tracing::debug!(auth_mode = ?mode, token_source = ?source, "node WS upgrade accepted");
+
+    ws.on_upgrade(move |socket| handle_socket(socket, state, auth, token))
         .into_response()
 }
 
@@ -109,7 +305,7 @@ pub async fn node_ws(
 // Socket handler
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-async fn handle_socket(socket: WebSocket, state: AppState) {
+async fn handle_socket(socket: WebSocket, state: AppState, auth: NodeAuth, token: String) {
     let (mut ws_sink, mut ws_stream) = socket.split();
 
     // 1. Wait for node_hello.
@@ -123,21 +319,32 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
 
     let node_id = hello.node.id.clone();
 
-    // 1b. Check protocol version compatibility.
-    if hello.protocol_version != PROTOCOL_VERSION {
+    // 1a. Re-check the token against the identity the node registers as.
+    if let Err(reject) = auth.check(Some(&node_id), &token) {
         tracing::warn!(
             node_id = %node_id,
-            node_version = hello.protocol_version,
-            gateway_version = PROTOCOL_VERSION,
-            "protocol version mismatch — rejecting node"
+            reason = ?reject,
+            "node_hello identity not authorized by presented token — rejecting node"
         );
-        // Send a welcome with our version so the node can log the mismatch,
-        // then close the connection.
-        let reject = WsMessage::GatewayWelcome {
-            protocol_version: PROTOCOL_VERSION,
-            gateway_version: env!("CARGO_PKG_VERSION").to_string(),
-        };
-        let _ = send_ws_message(&mut ws_sink, &reject).await;
+        return;
+    }
+
+    // 1b. Check protocol and node version against the configured minimums.
+    if let Err(reason) = check_compat(
+        &state.config.nodes,
+        hello.protocol_version,
+        &hello.node.version,
+    ) {
+        tracing::warn!(
+            node_id = %node_id,
+            protocol_version = hello.protocol_version,
+            node_version = %hello.node.version,
+            gateway_protocol = PROTOCOL_VERSION,
+            reason = %reason,
+            "incompatible node — rejecting"
+        );
+        let close = Message::Close(Some(incompatible_close_frame(&reason)));
+        let _ = ws_sink.send(close).await;
         return;
     }
 
@@ -189,7 +396,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         capabilities,
         version: hello.node.version,
         tags: hello.node.tags,
-        session_id,
+        session_id: session_id.clone(),
         connected_at: Utc::now(),
         last_seen: Utc::now(),
         sink: outbound_tx,
@@ -227,10 +434,15 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         }
     }
 
-    // Cleanup: fail in-flight requests, remove node, abort writer.
-    let failed = state.tool_router.fail_pending_for_node(&node_id);
+    // Cleanup: abort writer; if this is still the registered connection
+    // (the node may have reconnected meanwhile), remove it and fail its
+    // in-flight requests.
     writer.abort();
-    registry.remove(&node_id);
+    let failed = if registry.remove_session(&node_id, &session_id) {
+        state.tool_router.fail_pending_for_node(&node_id)
+    } else {
+        0
+    };
     tracing::info!(
         node_id = %node_id,
         failed_in_flight = failed,
@@ -299,14 +511,12 @@ async fn handle_inbound(
             result,
             error,
         } => {
-            // Convert protocol types to the router's internal format.
-            let error_string = error.map(|e| format!("{}: {}", e.kind, e.message));
             let result_value = result.unwrap_or(serde_json::Value::Null);
             state.tool_router.complete_request(
                 &request_id,
                 ok,
                 result_value,
-                error_string,
+                error,
             );
         }
         WsMessage::Ping { timestamp } => {
@@ -327,3 +537,251 @@ async fn handle_inbound(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", format!("Bearer {token}").parse().unwrap());
+        headers
+    }
+
+    fn query(token: Option<&str>, node_id: Option<&str>) -> WsQuery {
+        WsQuery {
+            token: token.map(String::from),
+            node_id: node_id.map(String::from),
+        }
+    }
+
+    /// `mac1` has a token in config, `pi` one in `SA_NODE_TOKENS`, and
+    /// `old` is revoked.
+    fn auth(shared: Option<&str>) -> NodeAuth {
+        let cfg = NodesConfig {
+            tokens: HashMap::from([("mac1".into(), "SA_NODE_TOKEN_MAC1".into())]),
+            revoked: vec!["old".into()],
+            ..NodesConfig::default()
+        };
+        NodeAuth::from_sources(&cfg, Some("pi:tokPi"), shared.map(String::from), |var| {
+            (var == "SA_NODE_TOKEN_MAC1").then(|| "tokMac".to_owned())
+        })
+    }
+
+    // ── Token source ────────────────────────────────────────────────
+
+    #[test]
+    fn header_token_wins_over_query() {
+        let q = query(Some("from-query"), None);
+        assert_eq!(
+            presented_token(&bearer("from-header"), &q),
+            ("from-header", TokenSource::Header)
+        );
+        assert_eq!(
+            presented_token(&HeaderMap::new(), &q),
+            ("from-query", TokenSource::Query)
+        );
+        assert_eq!(
+            presented_token(&HeaderMap::new(), &WsQuery::default()),
+            ("", TokenSource::None)
+        );
+    }
+
+    // ── Version compatibility ───────────────────────────────────────
+
+    fn min_versions(min_protocol: u32, min_node: Option<&str>) -> NodesConfig {
+        NodesConfig {
+            min_protocol_version: min_protocol,
+            min_node_version: min_node.map(String::from),
+            ..NodesConfig::default()
+        }
+    }
+
+    #[test]
+    fn under_version_node_is_rejected_with_reason() {
+        let cfg = min_versions(1, Some("0.4.0"));
+        let reason = check_compat(&cfg, PROTOCOL_VERSION, "0.3.9").unwrap_err();
+        assert_eq!(reason, "node version 0.3.9 is below 0.4.0");
+
+        let frame = incompatible_close_frame(&reason);
+        assert_eq!(frame.code, close_code::POLICY);
+        assert_eq!(frame.reason, format!("not_allowed: {reason}"));
+
+        assert!(check_compat(&cfg, PROTOCOL_VERSION, "garbage").is_err());
+    }
+
+    #[test]
+    fn compatible_node_is_accepted() {
+        let cfg = min_versions(1, Some("0.4"));
+        for version in ["0.4.0", "v1.0.0-beta.1", "0.10.2"] {
+            assert_eq!(check_compat(&cfg, PROTOCOL_VERSION, version), Ok(()));
+        }
+        // No minimum version configured: any version string is fine.
+        let open = NodesConfig::default();
+        assert_eq!(check_compat(&open, PROTOCOL_VERSION, "dev"), Ok(()));
+    }
+
+    #[test]
+    fn protocol_outside_supported_range_is_rejected() {
+        let cfg = min_versions(PROTOCOL_VERSION + 1, None);
+        assert!(check_compat(&cfg, PROTOCOL_VERSION, "1.0.0")
+            .unwrap_err()
+            .contains("below minimum"));
+        let newer = check_compat(&NodesConfig::default(), PROTOCOL_VERSION + 1, "1.0.0");
+        assert!(newer.unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn close_reason_is_capped() {
+        let frame = incompatible_close_frame(&"é".repeat(200));
+        assert!(frame.reason.len() <= 123);
+    }
+
+    // ── Per-node tokens ─────────────────────────────────────────────
+
+    #[test]
+    fn correct_per_node_token_connects() {
+        let auth = auth(Some("shared"));
+        assert_eq!(auth.check(Some("mac1"), "tokMac"), Ok(AuthMode::PerNode));
+        assert_eq!(auth.check(Some("pi"), "tokPi"), Ok(AuthMode::PerNode));
+    }
+
+    #[test]
+    fn mismatched_node_and_token_is_rejected() {
+        let auth = auth(Some("shared"));
+        assert_eq!(auth.check(Some("mac1"), "tokPi"), Err(AuthReject::InvalidToken));
+        assert_eq!(auth.check(Some("pi"), "tokMac"), Err(AuthReject::InvalidToken));
+        // A node with its own token can't fall back to the shared one.
+        assert_eq!(auth.check(Some("mac1"), "shared"), Err(AuthReject::InvalidToken));
+        // Without a node_id, per-node tokens don't authenticate.
+        assert_eq!(auth.check(None, "tokMac"), Err(AuthReject::InvalidToken));
+    }
+
+    #[test]
+    fn unset_per_node_env_var_refuses_the_node() {
+        let cfg = NodesConfig {
+            tokens: HashMap::from([("mac1".into(), "SA_UNSET".into())]),
+            ..NodesConfig::default()
+        };
+        let auth = NodeAuth::from_sources(&cfg, None, Some("shared".into()), |_| None);
+        assert_eq!(auth.check(Some("mac1"), "shared"), Err(AuthReject::InvalidToken));
+        assert_eq!(auth.check(Some("mac1"), ""), Err(AuthReject::InvalidToken));
+    }
+
+    #[test]
+    fn revoked_node_is_refused() {
+        let auth = auth(Some("shared"));
+        assert_eq!(auth.check(Some("old"), "shared"), Err(AuthReject::Revoked));
+        assert_eq!(auth.check(Some("pi"), "tokPi"), Ok(AuthMode::PerNode));
+    }
+
+    // ── Shared token ────────────────────────────────────────────────
+
+    #[test]
+    fn shared_token_works_for_nodes_without_their_own() {
+        let auth = auth(Some("shared"));
+        assert_eq!(auth.check(Some("linux"), "shared"), Ok(AuthMode::Shared));
+        assert_eq!(auth.check(None, "shared"), Ok(AuthMode::Shared));
+        assert_eq!(auth.check(Some("linux"), "nope"), Err(AuthReject::InvalidToken));
+    }
+
+    #[test]
+    fn per_node_tokens_without_shared_refuse_unknown_nodes() {
+        let auth = auth(None);
+        assert_eq!(auth.check(Some("linux"), ""), Err(AuthReject::InvalidToken));
+        assert_eq!(auth.check(Some("mac1"), "tokMac"), Ok(AuthMode::PerNode));
+    }
+
+    #[test]
+    fn no_tokens_configured_admits_everyone() {
+        let auth = NodeAuth::default();
+        assert_eq!(auth.check(Some("any"), ""), Ok(AuthMode::Open));
+        assert_eq!(auth.check(None, ""), Ok(AuthMode::Open));
+    }
+
+    // ── Routing ─────────────────────────────────────────────────────
+
+    /// The public/protected split of `api::router`, with the real API
+    /// token check on the protected side and the real node token check
+    /// standing in for the upgrade.
+    fn split_router(api_tokens: crate::api::auth::ApiTokens, nodes: NodeAuth) -> axum::Router {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use axum::middleware::Next;
+        use axum::routing::get;
+
+        let api_tokens = Arc::new(api_tokens);
+        let nodes = Arc::new(nodes);
+        let protected = axum::Router::new()
+            .route("/v1/nodes", get(|| async { "ok" }))
+            .route_layer(axum::middleware::from_fn(
+                move |req: Request<Body>, next: Next| {
+                    let api_tokens = api_tokens.clone();
+                    async move {
+                        match crate::api::auth::reject_request(&api_tokens, &req) {
+                            None => next.run(req).await,
+                            Some(rejection) => rejection,
+                        }
+                    }
+                },
+            ));
+        let public = axum::Router::new().route(
+            "/v1/nodes/ws",
+            get(move |headers: HeaderMap, Query(query): Query<WsQuery>| {
+                let nodes = nodes.clone();
+                async move {
+                    let (token, _) = presented_token(&headers, &query);
+                    match nodes.check(query.node_id.as_deref(), token) {
+                        Ok(_) => StatusCode::OK.into_response(),
+                        Err(reject) => reject.into_response(),
+                    }
+                }
+            }),
+        );
+        public.merge(protected)
+    }
+
+    #[tokio::test]
+    async fn node_connects_with_api_tokens_configured() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use sa_domain::config::ApiScope;
+        use tower::ServiceExt;
+
+        let mut api_tokens = crate::api::auth::ApiTokens::default();
+        api_tokens.insert("apiTok", ApiScope::ALL.to_vec());
+        let app = split_router(api_tokens, auth(Some("shared")));
+
+        let get = |uri: &str, token: &str| {
+            Request::get(uri)
+                .header("authorization", format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let status = |req: Request<Body>| {
+            let app = app.clone();
+            async move { app.oneshot(req).await.unwrap().status() }
+        };
+
+        // The node's own token gets past routing to the node check...
+        assert_eq!(
+            status(get("/v1/nodes/ws?node_id=mac1", "tokMac")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(get("/v1/nodes/ws?node_id=linux", "shared")).await,
+            StatusCode::OK
+        );
+        // ...which still refuses a wrong token, including an API token.
+        assert_eq!(
+            status(get("/v1/nodes/ws?node_id=mac1", "apiTok")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        // Control routes still need an API token.
+        assert_eq!(
+            status(get("/v1/nodes", "tokMac")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status(get("/v1/nodes", "apiTok")).await, StatusCode::OK);
+    }
+}
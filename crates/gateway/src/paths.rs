@@ -0,0 +1,137 @@
+//! Validation for relative paths supplied by untrusted callers.
+//!
+//! Shared by OpenClaw archive import (tar entry names) and workspace file
+//! writes, so both reject traversal, absolute paths and platform prefixes
+//! the same way.
+
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+
+/// Max path depth to prevent zip-bomb-style deeply nested directories.
+pub const MAX_PATH_DEPTH: usize = 64;
+
+/// `path` with `\` separators turned into `/`.
+///
+/// Windows exporters may write `workspace\MEMORY.md`; on Unix that would be
+/// one file name containing backslashes.  Splitting on both separators maps
+/// it to `workspace/MEMORY.md` and exposes `..\` and `\\server` to the
+/// component checks.
+pub fn portable_path(path: &Path) -> Cow<'_, Path> {
+    match path.to_str() {
+        Some(s) if s.contains('\\') => Cow::Owned(PathBuf::from(s.replace('\\', "/"))),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Reject a leading drive letter (`C:`, `C:\x`, `C:x`).  Windows parses
+/// these as [`Component::Prefix`], but elsewhere they are ordinary names.
+pub fn reject_drive_prefix(path: &Path) -> Result<(), String> {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return Err(format!("platform prefix in path: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Check that `path` stays beneath whatever directory it is joined to:
+/// non-empty, relative, no `..`, no platform prefix, and at most
+/// [`MAX_PATH_DEPTH`] components deep.  Returns the reason on rejection.
+pub fn validate_relative_path(path: &Path) -> Result<(), String> {
+    if path.as_os_str().is_empty() {
+        return Err("empty path".to_string());
+    }
+    reject_drive_prefix(path)?;
+    let path = portable_path(path);
+    let path = path.as_ref();
+    if path.is_absolute() {
+        return Err(format!("absolute path: {}", path.display()));
+    }
+    let mut depth = 0usize;
+    for comp in path.components() {
+        match comp {
+            Component::Normal(_) => {
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(format!("parent dir traversal: {}", path.display()));
+            }
+            Component::Prefix(_) => {
+                return Err(format!("platform prefix in path: {}", path.display()));
+            }
+            Component::RootDir => {
+                return Err(format!("root dir in path: {}", path.display()));
+            }
+        }
+    }
+    // Reject paths like "." or "./" that have no real components
+    if depth == 0 {
+        return Err(format!("path resolves to empty: {}", path.display()));
+    }
+    if depth > MAX_PATH_DEPTH {
+        return Err(format!(
+            "path depth {depth} exceeds limit of {MAX_PATH_DEPTH}: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path_ok() {
+        assert!(validate_relative_path(Path::new("agents/main/sessions/foo.jsonl")).is_ok());
+        assert!(validate_relative_path(Path::new("workspace/MEMORY.md")).is_ok());
+        assert!(validate_relative_path(Path::new("workspace-kimi/file.txt")).is_ok());
+    }
+
+    #[test]
+    fn test_relative_path_traversal_rejected() {
+        assert!(validate_relative_path(Path::new("../../../etc/passwd")).is_err());
+        assert!(validate_relative_path(Path::new("agents/../../../etc/shadow")).is_err());
+        assert!(validate_relative_path(Path::new("agents/main/../../..")).is_err());
+    }
+
+    #[test]
+    fn test_absolute_path_rejected() {
+        assert!(validate_relative_path(Path::new("/etc/passwd")).is_err());
+        assert!(validate_relative_path(Path::new("/tmp/evil")).is_err());
+    }
+
+    #[test]
+    fn test_empty_path_rejected() {
+        assert!(validate_relative_path(Path::new("")).is_err());
+    }
+
+    #[test]
+    fn test_curdir_only_rejected() {
+        // "." and "./" resolve to zero Normal components → rejected
+        assert!(validate_relative_path(Path::new(".")).is_err());
+        assert!(validate_relative_path(Path::new("./")).is_err());
+    }
+
+    #[test]
+    fn test_deep_nesting_rejected() {
+        let deep = (0..MAX_PATH_DEPTH + 1)
+            .map(|i| format!("d{i}"))
+            .collect::<Vec<_>>()
+            .join("/");
+        assert!(validate_relative_path(Path::new(&deep)).is_err());
+
+        // Just at the limit should be OK
+        let at_limit = (0..MAX_PATH_DEPTH)
+            .map(|i| format!("d{i}"))
+            .collect::<Vec<_>>()
+            .join("/");
+        assert!(validate_relative_path(Path::new(&at_limit)).is_ok());
+    }
+
+    #[test]
+    fn test_backslash_depth_is_counted() {
+        let deep = vec!["d"; MAX_PATH_DEPTH + 1].join("\\");
+        assert!(validate_relative_path(Path::new(&deep)).is_err());
+    }
+}
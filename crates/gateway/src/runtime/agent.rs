@@ -7,13 +7,21 @@
 //! Hard ceilings prevent runaway trees:
 //! - `max_depth` — nesting depth (parent→child→grandchild)
 //! - `max_children_per_turn` — calls within a single parent turn
+//! - `max_concurrent_children` — children running at once under one turn
 //! - `max_duration_ms` — wall-clock timeout per child run
+//!
+//! Depth limits are inherited: a child may never nest deeper than any
+//! ancestor allows, even if its own `max_depth` is larger.  The master's
+//! own turns have no agent config and are held to [`AgentLimits::default`].
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
-use sa_domain::config::{AgentConfig, MemoryMode, ToolPolicy};
+use futures_util::StreamExt;
+use sa_domain::config::{AgentConfig, AgentLimits, MemoryMode, ToolPolicy};
+use sa_domain::tool::ToolChoice;
+use serde::{Deserialize, Serialize};
 use sa_skills::registry::SkillsRegistry;
 
 use crate::state::AppState;
@@ -44,10 +52,101 @@ pub struct AgentContext {
     pub memory_mode: MemoryMode,
     /// Whether auto-compaction is enabled for this agent's session.
     pub compaction_enabled: bool,
-    /// Counter of children spawned so far (shared across all tool calls in a turn).
-    pub children_spawned: Arc<AtomicU32>,
-    /// Max children per turn (from the agent config that spawned us).
-    pub max_children_per_turn: u32,
+    /// Default tool choice for this agent's turns.
+    pub tool_choice: ToolChoice,
+    /// Sub-agent budget of this agent's turn.
+    pub fan_out: FanOut,
+    /// Deepest nesting allowed below this agent — the tightest `max_depth`
+    /// along the path from the master.
+    pub max_depth: u32,
+}
+
+/// Per-turn sub-agent budget, shared by every tool call of one turn —
+/// sub-agent and master turns alike.
+#[derive(Clone)]
+pub struct FanOut {
+    /// Children spawned so far in this turn.
+    spawned: Arc<AtomicU32>,
+    /// Children of this turn currently running.
+    running: Arc<AtomicU32>,
+    max_children_per_turn: u32,
+    max_concurrent_children: u32,
+}
+
+impl FanOut {
+    pub fn new(limits: &AgentLimits) -> Self {
+        Self {
+            spawned: Arc::new(AtomicU32::new(0)),
+            running: Arc::new(AtomicU32::new(0)),
+            max_children_per_turn: limits.max_children_per_turn,
+            max_concurrent_children: limits.max_concurrent_children,
+        }
+    }
+
+    pub fn max_concurrent_children(&self) -> u32 {
+        self.max_concurrent_children
+    }
+
+    /// Children spawned so far in this turn.
+    pub fn spawned(&self) -> u32 {
+        self.spawned.load(Ordering::Relaxed)
+    }
+
+    /// Reserve a slot for spawning one child from this turn.
+    ///
+    /// Enforces both `max_children_per_turn` and `max_concurrent_children`;
+    /// the returned guard frees the concurrency slot when dropped.
+    pub fn admit_child(&self) -> Result<ChildSlot, String> {
+        let prev = self.spawned.fetch_add(1, Ordering::Relaxed);
+        if prev >= self.max_children_per_turn {
+            // Undo the increment since we're not actually spawning.
+            self.spawned.fetch_sub(1, Ordering::Relaxed);
+            return Err(format!(
+                "children-per-turn limit exceeded: {prev} >= {}. \
+                 Too many sub-agent calls in one turn.",
+                self.max_children_per_turn
+            ));
+        }
+
+        let running = self.running.fetch_add(1, Ordering::Relaxed);
+        if running >= self.max_concurrent_children {
+            self.running.fetch_sub(1, Ordering::Relaxed);
+            self.spawned.fetch_sub(1, Ordering::Relaxed);
+            return Err(format!(
+                "concurrent sub-agent limit exceeded: {running} >= {} already running. \
+                 Wait for running sub-agents to finish.",
+                self.max_concurrent_children
+            ));
+        }
+
+        Ok(ChildSlot(self.running.clone()))
+    }
+}
+
+/// A reserved concurrency slot for one running child; released on drop.
+pub struct ChildSlot(Arc<AtomicU32>);
+
+impl Drop for ChildSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Depth a new child would run at, or an error if that exceeds the
+/// child's own `max_depth` or any ancestor's.
+pub fn resolve_child_depth(
+    parent: Option<&AgentContext>,
+    child_max_depth: u32,
+) -> Result<u32, String> {
+    let child_depth = parent.map_or(0, |a| a.depth) + 1;
+    let max_depth = parent.map_or(child_max_depth, |a| a.max_depth.min(child_max_depth));
+    if child_depth > max_depth {
+        return Err(format!(
+            "agent depth limit exceeded: depth={child_depth} > max_depth={max_depth}. \
+             Agent tree too deep — refactor task to reduce nesting."
+        ));
+    }
+    Ok(child_depth)
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -87,8 +186,9 @@ impl AgentRuntime {
             agent_path,
             memory_mode: self.config.memory_mode,
             compaction_enabled: self.config.compaction_enabled,
-            children_spawned: Arc::new(AtomicU32::new(0)),
-            max_children_per_turn: self.config.limits.max_children_per_turn,
+            tool_choice: self.config.tool_choice.clone(),
+            fan_out: FanOut::new(&self.config.limits),
+            max_depth: self.config.limits.max_depth,
         }
     }
 }
@@ -178,14 +278,46 @@ impl AgentManager {
         self.agents.is_empty()
     }
 
-    /// Count how many tools the given agent can effectively see.
-    pub fn effective_tool_count(&self, agent_id: &str, all_tool_names: &[&str]) -> usize {
+    /// Run several sub-agent jobs concurrently and collect their outcomes.
+    ///
+    /// At most `max_concurrent` jobs are in flight at once.  Outcomes are
+    /// returned in job order regardless of completion order, so the parent
+    /// sees a deterministic array.  Jobs naming an unknown agent fail
+    /// without calling `run`.
+    pub async fn run_parallel<F, Fut>(
+        &self,
+        jobs: Vec<AgentJob>,
+        max_concurrent: usize,
+        run: F,
+    ) -> Vec<AgentRunOutcome>
+    where
+        F: Fn(AgentJob) -> Fut,
+        Fut: std::future::Future<Output = AgentRunOutcome>,
+    {
+        futures_util::stream::iter(jobs)
+            .map(|job| {
+                let known = self.agents.contains_key(&job.agent_id);
+                let fut = known.then(|| run(job.clone()));
+                async move {
+                    match fut {
+                        Some(fut) => fut.await,
+                        None => AgentRunOutcome::failed(
+                            &job.agent_id,
+                            format!("agent '{}' not found", job.agent_id),
+                        ),
+                    }
+                }
+            })
+            .buffered(max_concurrent.max(1))
+            .collect()
+            .await
+    }
+
+    /// The tools the given agent can effectively see, after its tool policy.
+    pub fn effective_tools<'a>(&self, agent_id: &str, all_tool_names: &[&'a str]) -> Vec<&'a str> {
         match self.agents.get(agent_id) {
-            Some(r) => all_tool_names
-                .iter()
-                .filter(|t| r.config.tool_policy.allows(t))
-                .count(),
-            None => 0,
+            Some(r) => r.config.tool_policy.resolve(all_tool_names),
+            None => Vec::new(),
         }
     }
 }
@@ -194,10 +326,44 @@ impl AgentManager {
 // agent.run — execute a task as a sub-agent
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// One sub-agent invocation requested by a parent (`agent.run_parallel`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AgentJob {
+    pub agent_id: String,
+    pub task: String,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Result of one sub-agent run, with the ids needed to trace it.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentRunOutcome {
+    pub agent_id: String,
+    /// Run id of the child turn (`None` if it was refused before starting).
+    pub run_id: Option<uuid::Uuid>,
+    /// Child session key (`None` if it was refused before starting).
+    pub session_key: Option<String>,
+    pub output: String,
+    pub is_error: bool,
+}
+
+impl AgentRunOutcome {
+    fn failed(agent_id: &str, message: String) -> Self {
+        Self {
+            agent_id: agent_id.to_owned(),
+            run_id: None,
+            session_key: None,
+            output: message,
+            is_error: true,
+        }
+    }
+}
+
 /// Execute a task as a sub-agent.  Blocks until the child turn completes
 /// (or the wall-clock timeout fires).
 ///
-/// Returns `(result_text, is_error)`.
+/// `fan_out` is the calling turn's budget — the parent agent's, or the
+/// master turn's when `parent_agent` is `None`.
 pub async fn run_agent(
     state: &AppState,
     agent_id: &str,
@@ -205,53 +371,35 @@ pub async fn run_agent(
     model_override: Option<String>,
     parent_session_key: &str,
     parent_agent: Option<&AgentContext>,
-) -> (String, bool) {
+    fan_out: &FanOut,
+) -> AgentRunOutcome {
     let manager = match &state.agents {
         Some(m) => m,
-        None => return ("no agent manager configured".into(), true),
+        None => return AgentRunOutcome::failed(agent_id, "no agent manager configured".into()),
     };
 
     let runtime = match manager.get(agent_id) {
         Some(r) => r,
         None => {
-            return (
+            return AgentRunOutcome::failed(
+                agent_id,
                 format!("agent '{agent_id}' not found. Available: {:?}", manager.list()),
-                true,
             );
         }
     };
 
     // ── Depth guard ──────────────────────────────────────────────
-    let parent_depth = parent_agent.map_or(0, |a| a.depth);
-    let child_depth = parent_depth + 1;
-    let max_depth = runtime.config.limits.max_depth;
-
-    if child_depth > max_depth {
-        return (
-            format!(
-                "agent depth limit exceeded: depth={child_depth} > max_depth={max_depth}. \
-                 Agent tree too deep — refactor task to reduce nesting."
-            ),
-            true,
-        );
-    }
+    let child_depth = match resolve_child_depth(parent_agent, runtime.config.limits.max_depth) {
+        Ok(d) => d,
+        Err(e) => return AgentRunOutcome::failed(agent_id, e),
+    };
 
-    // ── Children-per-turn guard ──────────────────────────────────
-    if let Some(parent_ctx) = parent_agent {
-        let prev = parent_ctx.children_spawned.fetch_add(1, Ordering::Relaxed);
-        if prev >= parent_ctx.max_children_per_turn {
-            // Undo the increment since we're not actually spawning.
-            parent_ctx.children_spawned.fetch_sub(1, Ordering::Relaxed);
-            return (
-                format!(
-                    "children-per-turn limit exceeded: {prev} >= {}. \
-                     Too many sub-agent calls in one turn.",
-                    parent_ctx.max_children_per_turn
-                ),
-                true,
-            );
-        }
-    }
+    // ── Fan-out guards (per turn + concurrent) ───────────────────
+    // Held until the child finishes, freeing its concurrency slot.
+    let _child_slot = match fan_out.admit_child() {
+        Ok(slot) => slot,
+        Err(e) => return AgentRunOutcome::failed(agent_id, e),
+    };
 
     // ── Build parent path ───────────────────────────────────────
     let parent_path = parent_agent
@@ -276,11 +424,14 @@ pub async fn run_agent(
             .cloned()
     });
 
-    let ctx = runtime.context(
+    let mut ctx = runtime.context(
         Some(parent_session_key.to_string()),
         child_depth,
         &parent_path,
     );
+    if let Some(parent) = parent_agent {
+        ctx.max_depth = ctx.max_depth.min(parent.max_depth);
+    }
 
     tracing::info!(
         agent_id = agent_id,
@@ -299,9 +450,13 @@ pub async fn run_agent(
         response_format: None,
         agent: Some(ctx),
         routing_profile: None,
+        history: None,
+        images: Vec::new(),
+        seed: None,
+        stop: Vec::new(),
     };
 
-    let (_run_id, mut rx) = run_turn((*state).clone(), input);
+    let (run_id, mut rx) = run_turn((*state).clone(), input);
 
     // ── Drain events with wall-clock timeout ─────────────────────
     let timeout_ms = runtime.config.limits.max_duration_ms;
@@ -348,7 +503,52 @@ pub async fn run_agent(
         .cancel_map
         .remove_from_group(parent_session_key, &child_session_key);
 
-    (result, errored)
+    AgentRunOutcome {
+        agent_id: agent_id.to_owned(),
+        run_id: Some(run_id),
+        session_key: Some(child_session_key),
+        output: result,
+        is_error: errored,
+    }
+}
+
+/// Run several sub-agents concurrently for one parent turn and aggregate
+/// their outcomes (in job order).
+///
+/// Concurrency is bounded by the calling turn's `max_concurrent_children`;
+/// each child still passes the usual depth and per-turn guards.
+pub async fn run_agents_parallel(
+    state: &AppState,
+    jobs: Vec<AgentJob>,
+    parent_session_key: &str,
+    parent_agent: Option<&AgentContext>,
+    fan_out: &FanOut,
+) -> Vec<AgentRunOutcome> {
+    let manager = match &state.agents {
+        Some(m) => m,
+        None => {
+            return jobs
+                .iter()
+                .map(|j| AgentRunOutcome::failed(&j.agent_id, "no agent manager configured".into()))
+                .collect();
+        }
+    };
+    let max_concurrent = fan_out.max_concurrent_children();
+
+    manager
+        .run_parallel(jobs, max_concurrent as usize, |job| async move {
+            run_agent(
+                state,
+                &job.agent_id,
+                &job.task,
+                job.model,
+                parent_session_key,
+                parent_agent,
+                fan_out,
+            )
+            .await
+        })
+        .await
 }
 
 /// Helper: drain all TurnEvents from a receiver into result/errored.
@@ -421,6 +621,7 @@ mod tests {
             memory_mode: MemoryMode::Shared,
             limits: AgentLimits::default(),
             compaction_enabled: false,
+            tool_choice: Default::default(),
         };
         let rt = AgentRuntime {
             id: "researcher".into(),
@@ -442,6 +643,7 @@ mod tests {
             memory_mode: MemoryMode::Isolated,
             limits: AgentLimits::default(),
             compaction_enabled: false,
+            tool_choice: Default::default(),
         };
         let rt2 = AgentRuntime {
             id: "coder".into(),
@@ -454,6 +656,197 @@ mod tests {
         assert_eq!(ctx2.depth, 2);
     }
 
+    fn runtime_with_limits(id: &str, limits: AgentLimits) -> AgentRuntime {
+        AgentRuntime {
+            id: id.into(),
+            config: AgentConfig {
+                workspace_path: None,
+                skills_path: None,
+                tool_policy: ToolPolicy::default(),
+                models: HashMap::new(),
+                memory_mode: MemoryMode::Shared,
+                limits,
+                compaction_enabled: false,
+                tool_choice: Default::default(),
+            },
+            workspace: Arc::new(WorkspaceReader::new(".".into())),
+            skills: Arc::new(SkillsRegistry::empty()),
+        }
+    }
+
+    #[test]
+    fn depth_beyond_limit_is_refused() {
+        let limits = AgentLimits {
+            max_depth: 2,
+            ..AgentLimits::default()
+        };
+        assert_eq!(resolve_child_depth(None, 2), Ok(1));
+
+        let child = runtime_with_limits("researcher", limits.clone()).context(None, 1, "main");
+        assert_eq!(resolve_child_depth(Some(&child), 2), Ok(2));
+
+        let grandchild = runtime_with_limits("coder", limits).context(None, 2, &child.agent_path);
+        let err = resolve_child_depth(Some(&grandchild), 2).unwrap_err();
+        assert!(err.contains("depth=3 > max_depth=2"), "{err}");
+    }
+
+    #[test]
+    fn depth_limit_is_inherited_from_ancestors() {
+        let strict = AgentLimits {
+            max_depth: 1,
+            ..AgentLimits::default()
+        };
+        let parent = runtime_with_limits("planner", strict).context(None, 1, "main");
+        // A permissive child config cannot nest below a strict ancestor.
+        assert!(resolve_child_depth(Some(&parent), 10).is_err());
+    }
+
+    #[test]
+    fn concurrent_fan_out_is_capped() {
+        let limits = AgentLimits {
+            max_children_per_turn: 10,
+            max_concurrent_children: 2,
+            ..AgentLimits::default()
+        };
+        let fan_out = runtime_with_limits("planner", limits)
+            .context(None, 1, "main")
+            .fan_out;
+
+        let a = fan_out.admit_child().unwrap();
+        let _b = fan_out.admit_child().unwrap();
+        let err = fan_out.admit_child().err().unwrap();
+        assert!(err.contains("concurrent sub-agent limit"), "{err}");
+
+        // Finishing a child frees its slot.
+        drop(a);
+        assert!(fan_out.admit_child().is_ok());
+        assert_eq!(fan_out.spawned(), 3);
+    }
+
+    #[test]
+    fn children_per_turn_is_capped() {
+        let limits = AgentLimits {
+            max_children_per_turn: 2,
+            ..AgentLimits::default()
+        };
+        let fan_out = runtime_with_limits("planner", limits)
+            .context(None, 1, "main")
+            .fan_out;
+
+        drop(fan_out.admit_child().unwrap());
+        drop(fan_out.admit_child().unwrap());
+        let err = fan_out.admit_child().err().unwrap();
+        assert!(err.contains("children-per-turn limit"), "{err}");
+    }
+
+    #[test]
+    fn master_turn_budget_uses_default_limits() {
+        let fan_out = FanOut::new(&AgentLimits::default());
+        let limits = AgentLimits::default();
+
+        let running: Vec<_> = (0..limits.max_concurrent_children)
+            .map(|_| fan_out.admit_child().unwrap())
+            .collect();
+        assert!(fan_out.admit_child().is_err());
+        drop(running);
+
+        while fan_out.spawned() < limits.max_children_per_turn {
+            drop(fan_out.admit_child().unwrap());
+        }
+        let err = fan_out.admit_child().err().unwrap();
+        assert!(err.contains("children-per-turn limit"), "{err}");
+    }
+
+    fn manager(ids: &[&str]) -> AgentManager {
+        AgentManager {
+            agents: ids
+                .iter()
+                .map(|id| {
+                    let rt = runtime_with_limits(id, AgentLimits::default());
+                    (id.to_string(), Arc::new(rt))
+                })
+                .collect(),
+        }
+    }
+
+    fn job(agent_id: &str, task: &str) -> AgentJob {
+        AgentJob {
+            agent_id: agent_id.into(),
+            task: task.into(),
+            model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn run_parallel_runs_concurrently_and_keeps_job_order() {
+        let manager = manager(&["a", "b", "c"]);
+        let running = Arc::new(AtomicU32::new(0));
+        let peak = Arc::new(AtomicU32::new(0));
+
+        // Later jobs finish first; output must still follow job order.
+        let delays = HashMap::from([("a", 60u64), ("b", 30), ("c", 5)]);
+        let outcomes = manager
+            .run_parallel(
+                vec![job("a", "t1"), job("b", "t2"), job("c", "t3")],
+                3,
+                |job| {
+                    let running = running.clone();
+                    let peak = peak.clone();
+                    let delay = delays[job.agent_id.as_str()];
+                    async move {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        AgentRunOutcome {
+                            agent_id: job.agent_id.clone(),
+                            run_id: Some(uuid::Uuid::new_v4()),
+                            session_key: Some(format!("agent:{}:task:x", job.agent_id)),
+                            output: format!("{} done", job.task),
+                            is_error: false,
+                        }
+                    }
+                },
+            )
+            .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        let outputs: Vec<_> = outcomes.iter().map(|o| o.output.as_str()).collect();
+        assert_eq!(outputs, vec!["t1 done", "t2 done", "t3 done"]);
+        assert!(outcomes.iter().all(|o| o.run_id.is_some()));
+    }
+
+    #[tokio::test]
+    async fn run_parallel_respects_concurrency_bound_and_unknown_agents() {
+        let manager = manager(&["a"]);
+        let running = Arc::new(AtomicU32::new(0));
+        let peak = Arc::new(AtomicU32::new(0));
+
+        let outcomes = manager
+            .run_parallel(
+                vec![job("a", "1"), job("ghost", "2"), job("a", "3"), job("a", "4")],
+                2,
+                |job| {
+                    let running = running.clone();
+                    let peak = peak.clone();
+                    async move {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        AgentRunOutcome::failed(&job.agent_id, job.task)
+                    }
+                },
+            )
+            .await;
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(outcomes[1].agent_id, "ghost");
+        assert!(outcomes[1].output.contains("not found"));
+        assert_eq!(outcomes[3].output, "4");
+    }
+
     #[test]
     fn provenance_metadata_returns_none_for_master() {
         assert!(provenance_metadata(None, "sk", "sid").is_none());
@@ -469,6 +862,7 @@ mod tests {
             memory_mode: MemoryMode::Isolated,
             limits: AgentLimits::default(),
             compaction_enabled: false,
+            tool_choice: Default::default(),
         };
         let rt = AgentRuntime {
             id: "coder".into(),
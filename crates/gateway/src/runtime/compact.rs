@@ -8,6 +8,7 @@ use sa_domain::config::CompactionConfig;
 use sa_providers::traits::ChatRequest;
 use sa_providers::LlmProvider;
 use sa_sessions::transcript::{TranscriptLine, TranscriptWriter};
+use serde::Serialize;
 
 /// Find the index of the first line after the last compaction marker.
 /// Returns 0 if no compaction marker exists.
@@ -122,6 +123,9 @@ pub async fn generate_summary(
         max_tokens: Some(2000),
         response_format: sa_providers::ResponseFormat::Text,
         model: None,
+        seed: None,
+        tool_choice: sa_providers::ToolChoice::Auto,
+        stop: Vec::new(),
     };
 
     let resp = provider.chat(&req).await?;
@@ -129,15 +133,57 @@ pub async fn generate_summary(
 }
 
 /// Create a transcript line that serves as the compaction marker.
-pub fn compaction_line(summary: &str, turns_compacted: usize) -> TranscriptLine {
+pub fn compaction_line(
+    summary: &str,
+    turns_compacted: usize,
+    lines_compacted: usize,
+) -> TranscriptLine {
     let mut line = TranscriptWriter::line("system", summary);
     line.metadata = Some(serde_json::json!({
         "compaction": true,
         "turns_compacted": turns_compacted,
+        "lines_compacted": lines_compacted,
     }));
     line
 }
 
+/// One compaction marker found in a transcript.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompactionEvent {
+    /// Index of the marker line within the transcript.
+    pub line_index: usize,
+    pub timestamp: String,
+    pub turns_compacted: Option<u64>,
+    /// Transcript lines folded into the summary.  `None` for markers
+    /// written before the count was recorded.
+    pub lines_summarized: Option<u64>,
+    pub summary: String,
+}
+
+/// Every compaction marker in `lines`, oldest first.
+pub fn compaction_history(lines: &[TranscriptLine]) -> Vec<CompactionEvent> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| is_compaction_marker(line))
+        .map(|(line_index, line)| {
+            let count = |key: &str| {
+                line.metadata
+                    .as_ref()
+                    .and_then(|m| m.get(key))
+                    .and_then(|v| v.as_u64())
+            };
+            CompactionEvent {
+                line_index,
+                timestamp: line.timestamp.clone(),
+                turns_compacted: count("turns_compacted"),
+                lines_summarized: count("lines_compacted"),
+                summary: line.content.clone(),
+            }
+        })
+        .collect()
+}
+
 /// Run the full compaction flow: split → summarize → persist marker.
 pub async fn run_compaction(
     provider: &dyn LlmProvider,
@@ -155,7 +201,7 @@ pub async fn run_compaction(
     let turns_compacted = to_compact.iter().filter(|l| l.role == "user").count();
     let summary = generate_summary(provider, to_compact).await?;
 
-    let marker = compaction_line(&summary, turns_compacted);
+    let marker = compaction_line(&summary, turns_compacted, to_compact.len());
     transcripts.append(session_id, &[marker])?;
 
     tracing::info!(
@@ -221,7 +267,7 @@ mod tests {
     }
 
     fn compaction(summary: &str) -> TranscriptLine {
-        compaction_line(summary, 5)
+        compaction_line(summary, 5, 10)
     }
 
     #[test]
@@ -323,12 +369,47 @@ mod tests {
 
     #[test]
     fn compaction_line_metadata() {
-        let marker = compaction_line("a summary", 10);
+        let marker = compaction_line("a summary", 10, 24);
         assert_eq!(marker.role, "system");
         assert_eq!(marker.content, "a summary");
         let meta = marker.metadata.unwrap();
         assert_eq!(meta["compaction"], true);
         assert_eq!(meta["turns_compacted"], 10);
+        assert_eq!(meta["lines_compacted"], 24);
+    }
+
+    // ── compaction_history ────────────────────────────────────────
+
+    #[test]
+    fn compaction_history_lists_every_boundary() {
+        let mut legacy = line("system", "first summary");
+        legacy.metadata = Some(serde_json::json!({"compaction": true, "turns_compacted": 3}));
+        let lines = vec![
+            line("user", "a"),
+            line("assistant", "b"),
+            legacy,
+            line("user", "c"),
+            compaction_line("second summary", 2, 4),
+            line("user", "d"),
+            compaction_line("third summary", 1, 2),
+            line("assistant", "e"),
+        ];
+
+        let history = compaction_history(&lines);
+        let indices: Vec<usize> = history.iter().map(|e| e.line_index).collect();
+        assert_eq!(indices, [2, 4, 6]);
+        assert_eq!(history[0].summary, "first summary");
+        assert_eq!(history[0].turns_compacted, Some(3));
+        assert_eq!(history[0].lines_summarized, None);
+        assert_eq!(history[1].lines_summarized, Some(4));
+        assert_eq!(history[2].summary, "third summary");
+        assert_eq!(history[2].timestamp, lines[6].timestamp);
+    }
+
+    #[test]
+    fn compaction_history_empty_without_markers() {
+        let lines = vec![line("user", "hello"), line("assistant", "hi")];
+        assert!(compaction_history(&lines).is_empty());
     }
 
     // ── should_compact_with_boundary ─────────────────────────────
@@ -1,13 +1,15 @@
 //! Delivery store — in-app notification/delivery system for scheduled job results.
 //!
 //! Deliveries are the output of scheduled runs: digest summaries, alerts, etc.
-//! They are persisted to JSONL and kept in a bounded in-memory ring.
+//! They are persisted to a JSONL log through a [`PersistenceBackend`] and
+//! kept in a bounded in-memory ring.
 
 use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
+use sa_domain::persistence::{FsBackend, PersistenceBackend};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
@@ -68,7 +70,37 @@ impl Delivery {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum DeliveryEvent {
     NewDelivery { delivery: Delivery },
-    DeliveryRead { id: Uuid },
+    /// A delivery went from unread to read; `unread` is the store-wide
+    /// unread count afterwards so every client can resync its badge.
+    DeliveryRead {
+        id: Uuid,
+        unread: usize,
+    },
+    /// Several deliveries were marked read at once.
+    DeliveriesRead {
+        ids: Vec<Uuid>,
+        unread: usize,
+    },
+    /// Deliveries were removed from the inbox.
+    DeliveriesDeleted {
+        ids: Vec<Uuid>,
+        unread: usize,
+    },
+}
+
+/// Selects deliveries for bulk operations.  Unset fields match everything.
+#[derive(Clone, Debug, Default)]
+pub struct DeliveryFilter {
+    pub schedule_id: Option<Uuid>,
+    /// Only deliveries created before this instant.
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl DeliveryFilter {
+    pub fn matches(&self, d: &Delivery) -> bool {
+        self.schedule_id.is_none_or(|id| d.schedule_id == Some(id))
+            && self.created_before.is_none_or(|t| d.created_at < t)
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -77,25 +109,33 @@ pub enum DeliveryEvent {
 
 const MAX_DELIVERIES: usize = 1000;
 
+/// Backend key of the deliveries JSONL log.
+const DELIVERIES_KEY: &str = "deliveries.jsonl";
+
 pub struct DeliveryStore {
     inner: RwLock<VecDeque<Delivery>>,
     /// O(1) lookup index: id → position in deque (rebuilt on load, maintained on insert).
     index: RwLock<HashMap<Uuid, usize>>,
-    persist_path: PathBuf,
+    backend: Arc<dyn PersistenceBackend>,
     event_tx: broadcast::Sender<DeliveryEvent>,
     /// Dirty flag: set when mark_read mutates in-memory state but disk is stale.
     dirty: AtomicBool,
 }
 
 impl DeliveryStore {
+    /// Load deliveries from `state_path/deliveries.jsonl`.
     pub fn new(state_path: &std::path::Path) -> Self {
-        let persist_path = state_path.join("deliveries.jsonl");
+        Self::with_backend(Arc::new(FsBackend::new(state_path)))
+    }
+
+    /// Load deliveries from, and persist them through, `backend`.
+    pub fn with_backend(backend: Arc<dyn PersistenceBackend>) -> Self {
         let (event_tx, _) = broadcast::channel(64);
 
         let mut store = Self {
             inner: RwLock::new(VecDeque::new()),
             index: RwLock::new(HashMap::new()),
-            persist_path,
+            backend,
             event_tx,
             dirty: AtomicBool::new(false),
         };
@@ -104,7 +144,8 @@ impl DeliveryStore {
     }
 
     fn load(&mut self) {
-        if let Ok(data) = std::fs::read_to_string(&self.persist_path) {
+        if let Ok(Some(raw)) = self.backend.load(DELIVERIES_KEY) {
+            let data = String::from_utf8_lossy(&raw);
             let mut deliveries = VecDeque::new();
             for line in data.lines() {
                 if let Ok(d) = serde_json::from_str::<Delivery>(line) {
@@ -119,7 +160,7 @@ impl DeliveryStore {
             let count = deliveries.len();
             // Truncate JSONL on disk if we trimmed entries.
             if count < original_count {
-                Self::rewrite_jsonl(&self.persist_path, &deliveries);
+                Self::rewrite_jsonl(self.backend.as_ref(), &deliveries);
             }
             // Build O(1) lookup index.
             let idx: HashMap<Uuid, usize> = deliveries
@@ -135,45 +176,23 @@ impl DeliveryStore {
         }
     }
 
-    /// Rewrite the entire JSONL file from the in-memory ring.
-    fn rewrite_jsonl(path: &std::path::Path, deliveries: &VecDeque<Delivery>) {
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        let tmp = path.with_extension("jsonl.tmp");
-        let mut ok = false;
-        if let Ok(mut f) = std::fs::File::create(&tmp) {
-            use std::io::Write;
-            ok = true;
-            for d in deliveries {
-                if let Ok(json) = serde_json::to_string(d) {
-                    if writeln!(f, "{}", json).is_err() {
-                        ok = false;
-                        break;
-                    }
-                }
+    /// Rewrite the entire JSONL log from the in-memory ring.
+    fn rewrite_jsonl(backend: &dyn PersistenceBackend, deliveries: &VecDeque<Delivery>) {
+        let mut log = String::new();
+        for d in deliveries {
+            if let Ok(json) = serde_json::to_string(d) {
+                log.push_str(&json);
+                log.push('\n');
             }
         }
-        if ok {
-            let _ = std::fs::rename(&tmp, path);
-        } else {
-            let _ = std::fs::remove_file(&tmp);
+        if let Err(e) = backend.save(DELIVERIES_KEY, log.as_bytes()) {
+            tracing::warn!(error = %e, "failed to rewrite deliveries log");
         }
     }
 
-    fn persist_one(path: &std::path::Path, delivery: &Delivery) {
-        if let Some(parent) = path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
+    fn persist_one(backend: &dyn PersistenceBackend, delivery: &Delivery) {
         if let Ok(json) = serde_json::to_string(delivery) {
-            use std::io::Write;
-            if let Ok(mut f) = std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(path)
-            {
-                let _ = writeln!(f, "{}", json);
-            }
+            let _ = backend.append(DELIVERIES_KEY, format!("{json}\n").as_bytes());
         }
     }
 
@@ -202,7 +221,7 @@ impl DeliveryStore {
         }
         drop(inner);
 
-        Self::persist_one(&self.persist_path, &d);
+        Self::persist_one(self.backend.as_ref(), &d);
         let _ = self.event_tx.send(DeliveryEvent::NewDelivery {
             delivery: d.clone(),
         });
@@ -243,16 +262,78 @@ impl DeliveryStore {
         drop(idx);
         let mut inner = self.inner.write().await;
         if let Some(d) = inner.get_mut(pos) {
+            if d.read {
+                return true;
+            }
             d.read = true;
             // Mark dirty — flushed periodically, not on every call.
             self.dirty.store(true, Ordering::Relaxed);
-            let _ = self.event_tx.send(DeliveryEvent::DeliveryRead { id: *id });
+            let unread = inner.iter().filter(|d| !d.read).count();
+            let _ = self
+                .event_tx
+                .send(DeliveryEvent::DeliveryRead { id: *id, unread });
             true
         } else {
             false
         }
     }
 
+    /// Mark every unread delivery matching `filter` as read.  Returns how
+    /// many changed.
+    pub async fn mark_read_where(&self, filter: &DeliveryFilter) -> usize {
+        let mut inner = self.inner.write().await;
+        let mut ids = Vec::new();
+        for d in inner.iter_mut().filter(|d| !d.read && filter.matches(d)) {
+            d.read = true;
+            ids.push(d.id);
+        }
+        if ids.is_empty() {
+            return 0;
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+        let unread = inner.iter().filter(|d| !d.read).count();
+        drop(inner);
+        let count = ids.len();
+        let _ = self
+            .event_tx
+            .send(DeliveryEvent::DeliveriesRead { ids, unread });
+        count
+    }
+
+    /// Delete every delivery matching `filter`.  Returns how many were
+    /// removed.  Rewrites the log immediately rather than waiting for the
+    /// periodic flush, so deleted deliveries can't reappear after a crash.
+    pub async fn delete_where(&self, filter: &DeliveryFilter) -> usize {
+        let mut inner = self.inner.write().await;
+        let mut ids = Vec::new();
+        inner.retain(|d| {
+            let hit = filter.matches(d);
+            if hit {
+                ids.push(d.id);
+            }
+            !hit
+        });
+        if ids.is_empty() {
+            return 0;
+        }
+        {
+            let mut idx = self.index.write().await;
+            idx.clear();
+            for (i, d) in inner.iter().enumerate() {
+                idx.insert(d.id, i);
+            }
+        }
+        Self::rewrite_jsonl(self.backend.as_ref(), &inner);
+        self.dirty.store(false, Ordering::Relaxed);
+        let unread = inner.iter().filter(|d| !d.read).count();
+        drop(inner);
+        let count = ids.len();
+        let _ = self
+            .event_tx
+            .send(DeliveryEvent::DeliveriesDeleted { ids, unread });
+        count
+    }
+
     /// Flush dirty state to disk if needed.  Called from the periodic
     /// cleanup loop in main.rs (every 60 s).
     pub async fn flush_if_dirty(&self) {
@@ -260,7 +341,7 @@ impl DeliveryStore {
             return;
         }
         let inner = self.inner.read().await;
-        Self::rewrite_jsonl(&self.persist_path, &inner);
+        Self::rewrite_jsonl(self.backend.as_ref(), &inner);
     }
 
     /// List deliveries scoped to a specific schedule.
@@ -439,6 +520,97 @@ mod tests {
         assert_eq!(store.unread_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn mark_read_broadcasts_unread_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DeliveryStore::new(dir.path());
+        let first = store.insert(Delivery::new("A".into(), "a".into())).await;
+        store.insert(Delivery::new("B".into(), "b".into())).await;
+
+        let mut rx = store.subscribe();
+        assert!(store.mark_read(&first.id).await);
+        match rx.try_recv().unwrap() {
+            DeliveryEvent::DeliveryRead { id, unread } => {
+                assert_eq!(id, first.id);
+                assert_eq!(unread, 1);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        // Already read: no state change, no event.
+        assert!(store.mark_read(&first.id).await);
+        assert!(rx.try_recv().is_err());
+    }
+
+    fn scheduled(schedule_id: Uuid, age_hours: i64) -> Delivery {
+        let mut d = Delivery::new("T".into(), "b".into());
+        d.schedule_id = Some(schedule_id);
+        d.created_at = Utc::now() - chrono::Duration::hours(age_hours);
+        d
+    }
+
+    #[tokio::test]
+    async fn read_all_respects_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DeliveryStore::new(dir.path());
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let old_a = store.insert(scheduled(a, 48)).await;
+        let new_a = store.insert(scheduled(a, 1)).await;
+        let old_b = store.insert(scheduled(b, 48)).await;
+
+        let mut rx = store.subscribe();
+        let filter = DeliveryFilter {
+            schedule_id: Some(a),
+            created_before: Some(Utc::now() - chrono::Duration::hours(24)),
+        };
+        assert_eq!(store.mark_read_where(&filter).await, 1);
+        assert!(store.get(&old_a.id).await.unwrap().read);
+        assert!(!store.get(&new_a.id).await.unwrap().read);
+        assert!(!store.get(&old_b.id).await.unwrap().read);
+        match rx.try_recv().unwrap() {
+            DeliveryEvent::DeliveriesRead { ids, unread } => {
+                assert_eq!(ids, [old_a.id]);
+                assert_eq!(unread, 2);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        // Nothing left to change for that filter: no event.
+        assert_eq!(store.mark_read_where(&filter).await, 0);
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(store.mark_read_where(&DeliveryFilter::default()).await, 2);
+        assert_eq!(store.unread_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn bulk_delete_respects_filters_and_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let keep = {
+            let store = DeliveryStore::new(dir.path());
+            store.insert(scheduled(a, 1)).await;
+            store.insert(scheduled(a, 2)).await;
+            let keep = store.insert(scheduled(b, 1)).await;
+
+            let filter = DeliveryFilter {
+                schedule_id: Some(a),
+                ..Default::default()
+            };
+            assert_eq!(store.delete_where(&filter).await, 2);
+            let (items, total) = store.list(10, 0).await;
+            assert_eq!(total, 1);
+            assert_eq!(items[0].id, keep.id);
+            assert!(store.get(&keep.id).await.is_some());
+            keep
+        };
+
+        let reloaded = DeliveryStore::new(dir.path());
+        let (items, _) = reloaded.list(10, 0).await;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, keep.id);
+    }
+
     #[tokio::test]
     async fn delivery_mark_read_persists() {
         let dir = tempfile::tempdir().unwrap();
@@ -475,6 +647,34 @@ mod tests {
         assert_eq!(items[0].title, "Match");
     }
 
+    #[tokio::test]
+    async fn memory_backend_matches_fs_backend() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let dir = tempfile::tempdir().unwrap();
+        let backends: [Arc<dyn PersistenceBackend>; 2] = [
+            Arc::new(FsBackend::new(dir.path())),
+            Arc::new(MemoryBackend::new()),
+        ];
+        for backend in backends {
+            let store = DeliveryStore::with_backend(backend.clone());
+            let first = Delivery::new("First".into(), "body".into());
+            let first_id = first.id;
+            store.insert(first).await;
+            store.insert(Delivery::new("Second".into(), "body".into())).await;
+            store.mark_read(&first_id).await;
+            store.flush_if_dirty().await;
+
+            let reloaded = DeliveryStore::with_backend(backend);
+            let (items, total) = reloaded.list(10, 0).await;
+            assert_eq!(total, 2);
+            assert_eq!(items[0].title, "Second");
+            assert!(reloaded.get(&first_id).await.unwrap().read);
+            assert_eq!(reloaded.unread_count().await, 1);
+        }
+        assert!(dir.path().join("deliveries.jsonl").is_file());
+    }
+
     #[tokio::test]
     async fn delivery_bounded() {
         let dir = tempfile::tempdir().unwrap();
@@ -83,7 +83,7 @@ pub async fn fetch_source(
     let now = Utc::now();
 
     let client = match reqwest::Client::builder()
-        .timeout(std::time::Duration::from_millis(config.timeout_ms))
+        .timeout(config.attempt_timeout())
         .user_agent(&config.user_agent)
         .build()
     {
@@ -154,6 +154,32 @@ pub async fn fetch_source(
     }
 }
 
+/// Pause between attempts at the same source.
+const RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Fetch a single URL, retrying failed attempts up to `config.retries`
+/// times.  A truncated body is not retried: the source answered, it was
+/// just too large.
+pub async fn fetch_source_with_retries(url: &str, config: &FetchConfig) -> FetchResult {
+    let attempts = config.attempts();
+    let mut attempt = 1;
+    loop {
+        let mut result = fetch_source(url, config).await;
+        let retryable = result.error.is_some() && result.content.is_empty();
+        if !retryable || attempt >= attempts {
+            if retryable && attempts > 1 {
+                if let Some(e) = result.error.as_mut() {
+                    e.push_str(&format!(" (after {attempts} attempts)"));
+                }
+            }
+            return result;
+        }
+        tracing::debug!(url, attempt, "source fetch failed, retrying");
+        tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+        attempt += 1;
+    }
+}
+
 /// Fetch all sources for a schedule concurrently, detecting changes against previous state.
 pub async fn fetch_all_sources(schedule: &Schedule) -> Vec<FetchResult> {
     let futs: Vec<_> = schedule
@@ -162,7 +188,7 @@ pub async fn fetch_all_sources(schedule: &Schedule) -> Vec<FetchResult> {
         .map(|url| {
             let url = url.clone();
             let config = schedule.fetch_config.clone();
-            async move { fetch_source(&url, &config).await }
+            async move { fetch_source_with_retries(&url, &config).await }
         })
         .collect();
 
@@ -251,33 +277,32 @@ pub fn build_digest_prompt(
     let now = Utc::now();
 
     // Build content based on digest mode.
-    let included: Vec<&FetchResult> = match schedule.digest_mode {
-        DigestMode::Full => results
-            .iter()
-            .filter(|r| r.error.is_none())
-            .collect(),
-        DigestMode::ChangesOnly => results
-            .iter()
-            .filter(|r| r.error.is_none() && r.changed)
-            .collect(),
+    let (content_block, has_content) = match schedule.digest_mode {
+        DigestMode::Full | DigestMode::ChangesOnly => {
+            let changes_only = schedule.digest_mode == DigestMode::ChangesOnly;
+            let included: Vec<&FetchResult> = results
+                .iter()
+                .filter(|r| r.error.is_none() && (r.changed || !changes_only))
+                .collect();
+            let block = included
+                .iter()
+                .map(|r| format!("## {}\n\n{}", r.url, clean_content(&r.content)))
+                .collect::<Vec<_>>()
+                .join("\n\n---\n\n");
+            (block, !included.is_empty())
+        }
+        DigestMode::PerSourceSections => {
+            let sections = build_source_sections(results);
+            let has_items = sections.iter().any(|s| !s.items.is_empty());
+            (render_sections(&sections), has_items)
+        }
     };
-
-    let content_block = if included.is_empty() {
+    let content_block = if has_content {
+        content_block
+    } else if content_block.is_empty() {
         "No content available.".to_string()
     } else {
-        included
-            .iter()
-            .map(|r| {
-                // Strip HTML tags from content to reduce token waste.
-                let clean = if r.content.contains('<') && r.content.contains('>') {
-                    strip_html_tags(&r.content)
-                } else {
-                    r.content.clone()
-                };
-                format!("## {}\n\n{}", r.url, clean)
-            })
-            .collect::<Vec<_>>()
-            .join("\n\n---\n\n")
+        format!("No content available.\n\n{content_block}")
     };
 
     let all_sources = results
@@ -307,7 +332,7 @@ pub fn build_digest_prompt(
             .replace("{{timezone}}", &schedule.timezone)
     } else {
         // Legacy mode: append content after the template.
-        if included.is_empty() {
+        if !has_content {
             template.clone()
         } else {
             format!(
@@ -318,11 +343,168 @@ pub fn build_digest_prompt(
     }
 }
 
+/// Strip HTML tags from content to reduce token waste.
+fn clean_content(content: &str) -> String {
+    if content.contains('<') && content.contains('>') {
+        strip_html_tags(content)
+    } else {
+        content.to_string()
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Per-source sections
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// One source's share of a [`DigestMode::PerSourceSections`] digest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestSection {
+    pub source: String,
+    /// Blank-line separated blocks of the source's content, each
+    /// collapsed onto a single line.
+    pub items: Vec<String>,
+    /// Set when the fetch failed (the section then has no items).
+    pub error: Option<String>,
+}
+
+/// Group fetch results into one section per source, in source order.
+/// Repeated URLs are merged into a single section.
+pub fn build_source_sections(results: &[FetchResult]) -> Vec<DigestSection> {
+    let mut sections: Vec<DigestSection> = Vec::new();
+    for r in results {
+        let idx = match sections.iter().position(|s| s.source == r.url) {
+            Some(idx) => idx,
+            None => {
+                sections.push(DigestSection {
+                    source: r.url.clone(),
+                    items: Vec::new(),
+                    error: None,
+                });
+                sections.len() - 1
+            }
+        };
+        let section = &mut sections[idx];
+        match &r.error {
+            Some(e) => section.error = Some(e.clone()),
+            None => section
+                .items
+                .extend(split_items(&clean_content(&r.content))),
+        }
+    }
+    sections
+}
+
+fn split_items(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                items.push(current.join(" "));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        items.push(current.join(" "));
+    }
+    items
+}
+
+/// Render sections as markdown: a `## source (N items)` header and a
+/// bullet per item for each non-empty source, followed by a single line
+/// noting the sources that produced nothing.
+fn render_sections(sections: &[DigestSection]) -> String {
+    let mut blocks: Vec<String> = sections
+        .iter()
+        .filter(|s| !s.items.is_empty())
+        .map(|s| {
+            let n = s.items.len();
+            let bullets = s
+                .items
+                .iter()
+                .map(|item| format!("- {item}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "## {} ({} item{})\n\n{}",
+                s.source,
+                n,
+                if n == 1 { "" } else { "s" },
+                bullets
+            )
+        })
+        .collect();
+
+    let empty: Vec<String> = sections
+        .iter()
+        .filter(|s| s.items.is_empty())
+        .map(|s| match s.error {
+            Some(_) => format!("{} (fetch failed)", s.source),
+            None => s.source.clone(),
+        })
+        .collect();
+    if !empty.is_empty() {
+        blocks.push(format!("_No items from: {}_", empty.join(", ")));
+    }
+    blocks.join("\n\n---\n\n")
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Failure isolation
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// A source that failed to fetch during a run.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SourceFailure {
+    pub url: String,
+    pub error: String,
+}
+
+/// Sources that failed to fetch, in source order.  The digest is built
+/// from the remaining ones; these are recorded alongside the delivery.
+pub fn source_failures(results: &[FetchResult]) -> Vec<SourceFailure> {
+    results
+        .iter()
+        .filter_map(|r| {
+            r.error.as_ref().map(|e| SourceFailure {
+                url: r.url.clone(),
+                error: e.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Whether every source failed, leaving nothing to digest.  Only then is
+/// the run itself treated as failed.
+pub fn all_sources_failed(results: &[FetchResult]) -> bool {
+    !results.is_empty() && results.iter().all(|r| r.error.is_some())
+}
+
+/// One-line summary of failed sources for `last_error`.
+pub fn describe_failures(failures: &[SourceFailure]) -> String {
+    let details = failures
+        .iter()
+        .map(|f| format!("{}: {}", f.url, f.error))
+        .collect::<Vec<_>>()
+        .join("; ");
+    format!("all {} source(s) failed to fetch ({details})", failures.len())
+}
+
 /// Convert fetch results into updated SourceState entries.
-pub fn build_source_states(results: &[FetchResult]) -> std::collections::HashMap<String, SourceState> {
+///
+/// A failed fetch keeps the source's previous content hash, so a
+/// transient error doesn't make the next successful fetch look changed.
+pub fn build_source_states(
+    results: &[FetchResult],
+    previous: &std::collections::HashMap<String, SourceState>,
+) -> std::collections::HashMap<String, SourceState> {
     results
         .iter()
         .map(|r| {
+            let prev = previous.get(&r.url);
             (
                 r.url.clone(),
                 SourceState {
@@ -330,7 +512,7 @@ pub fn build_source_states(results: &[FetchResult]) -> std::collections::HashMap
                     last_content_hash: if r.error.is_none() {
                         Some(r.content_hash.clone())
                     } else {
-                        None
+                        prev.and_then(|p| p.last_content_hash.clone())
                     },
                     last_http_status: if r.http_status > 0 {
                         Some(r.http_status)
@@ -408,6 +590,7 @@ mod tests {
             cooldown_until: None,
             routing_profile: None,
             webhook_secret: None,
+            trigger_params: Vec::new(),
             total_input_tokens: 0,
             total_output_tokens: 0,
             total_runs: 0,
@@ -529,13 +712,186 @@ mod tests {
         assert!(!prompt.contains("connection refused"), "Error content should not be in prompt");
     }
 
+    #[test]
+    fn per_source_sections_group_items_by_source() {
+        let sched = test_schedule_for_digest(
+            DigestMode::PerSourceSections,
+            "Digest:\n{{content}}",
+            vec!["https://a.com", "https://b.com"],
+        );
+        let results = vec![
+            make_result("https://a.com", "First story\nwraps here\n\nSecond story", false),
+            make_result("https://b.com", "<p>Only story</p>", true),
+        ];
+        let sections = build_source_sections(&results);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].items, ["First story wraps here", "Second story"]);
+        assert_eq!(sections[1].items, ["Only story"]);
+
+        let prompt = build_digest_prompt(&sched, &results);
+        let a = prompt.find("## https://a.com (2 items)").unwrap();
+        let b = prompt.find("## https://b.com (1 item)").unwrap();
+        assert!(a < b, "sections keep source order");
+        assert!(prompt.contains("- First story wraps here\n- Second story"));
+        assert!(!prompt.contains("No items from"));
+    }
+
+    #[test]
+    fn per_source_sections_note_empty_sources() {
+        let sched = test_schedule_for_digest(
+            DigestMode::PerSourceSections,
+            "{{content}}",
+            vec!["https://a.com", "https://empty.com", "https://bad.com"],
+        );
+        let results = vec![
+            make_result("https://a.com", "Story", true),
+            make_result("https://empty.com", "  \n\n ", true),
+            make_error_result("https://bad.com", "connection refused"),
+        ];
+        let prompt = build_digest_prompt(&sched, &results);
+        assert!(prompt.contains("## https://a.com (1 item)"));
+        assert!(!prompt.contains("## https://empty.com"));
+        assert!(!prompt.contains("## https://bad.com"));
+        assert!(prompt
+            .contains("_No items from: https://empty.com, https://bad.com (fetch failed)_"));
+        assert!(!prompt.contains("connection refused"));
+
+        let results = vec![make_error_result("https://bad.com", "timeout")];
+        let prompt = build_digest_prompt(&sched, &results);
+        assert!(prompt.starts_with("No content available."));
+        assert!(prompt.contains("https://bad.com (fetch failed)"));
+    }
+
+    #[test]
+    fn partial_source_failure_still_digests_healthy_sources() {
+        let mut sched = test_schedule_for_digest(
+            DigestMode::Full,
+            "Report: {{content}}",
+            vec!["https://a.com", "https://bad.com", "https://c.com"],
+        );
+        sched.source_states.insert(
+            "https://bad.com".into(),
+            SourceState {
+                last_fetched_at: Some(Utc::now()),
+                last_content_hash: Some("prev-hash".into()),
+                last_http_status: Some(200),
+                last_error: None,
+            },
+        );
+        let results = vec![
+            make_result("https://a.com", "Content A", true),
+            make_error_result("https://bad.com", "connection refused"),
+            make_result("https://c.com", "Content C", true),
+        ];
+
+        assert!(!all_sources_failed(&results));
+        let prompt = build_digest_prompt(&sched, &results);
+        assert!(prompt.contains("Content A"));
+        assert!(prompt.contains("Content C"));
+
+        assert_eq!(
+            source_failures(&results),
+            [SourceFailure {
+                url: "https://bad.com".into(),
+                error: "connection refused".into(),
+            }]
+        );
+
+        let states = build_source_states(&results, &sched.source_states);
+        let bad = &states["https://bad.com"];
+        assert_eq!(bad.last_error.as_deref(), Some("connection refused"));
+        assert_eq!(
+            bad.last_content_hash.as_deref(),
+            Some("prev-hash"),
+            "failed fetch keeps the previous hash for change detection"
+        );
+        assert!(states["https://a.com"].last_error.is_none());
+    }
+
+    #[test]
+    fn run_fails_only_when_every_source_fails() {
+        let results = vec![
+            make_error_result("https://a.com", "timeout"),
+            make_error_result("https://b.com", "HTTP request failed"),
+        ];
+        assert!(all_sources_failed(&results));
+        let summary = describe_failures(&source_failures(&results));
+        assert!(summary.starts_with("all 2 source(s) failed"));
+        assert!(summary.contains("https://a.com: timeout"));
+
+        assert!(!all_sources_failed(&[]));
+    }
+
+    /// A server that accepts connections and never answers, counting
+    /// how many it got.
+    async fn silent_server() -> (String, std::sync::Arc<std::sync::atomic::AtomicU32>) {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = std::sync::Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                held.push(socket);
+            }
+        });
+        (url, hits)
+    }
+
+    #[tokio::test]
+    async fn slow_source_times_out_and_is_retried() {
+        let (url, hits) = silent_server().await;
+        let config = FetchConfig {
+            timeout_sec: Some(1),
+            retries: 2,
+            ..FetchConfig::default()
+        };
+
+        let started = std::time::Instant::now();
+        let result = fetch_source_with_retries(&url, &config).await;
+        let error = result
+            .error
+            .expect("timed-out source is recorded as failed");
+        assert!(error.contains("after 3 attempts"), "{error}");
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn no_retries_means_a_single_attempt() {
+        let (url, hits) = silent_server().await;
+        let config = FetchConfig {
+            timeout_sec: Some(1),
+            ..FetchConfig::default()
+        };
+        let result = fetch_source_with_retries(&url, &config).await;
+        assert!(result.error.is_some());
+        assert!(!result.error.unwrap().contains("attempts"));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fetch_attempts_are_capped() {
+        let config = FetchConfig {
+            retries: 100,
+            ..FetchConfig::default()
+        };
+        assert_eq!(config.attempts(), MAX_FETCH_RETRIES + 1);
+        assert_eq!(
+            config.attempt_timeout(),
+            std::time::Duration::from_millis(config.timeout_ms)
+        );
+    }
+
     #[test]
     fn build_source_states_from_results() {
         let results = vec![
             make_result("https://a.com", "content", true),
             make_error_result("https://bad.com", "timeout"),
         ];
-        let states = build_source_states(&results);
+        let states = build_source_states(&results, &HashMap::new());
         assert_eq!(states.len(), 2);
         assert!(states["https://a.com"].last_content_hash.is_some());
         assert!(states["https://bad.com"].last_content_hash.is_none());
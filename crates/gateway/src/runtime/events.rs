@@ -0,0 +1,251 @@
+//! Unified event bus — one firehose across subsystems.
+//!
+//! Runs, schedules, deliveries and nodes each broadcast on their own
+//! channel, which back their own SSE endpoints.  [`EventBus::attach`]
+//! forwards all of them onto a single channel, tagged by source, for
+//! `GET /v1/events` (the dashboard overview).  Only run *status* changes
+//! are forwarded; per-node run detail stays on `/v1/runs/:id/events`.
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::nodes::registry::{NodeEvent, NodeRegistry};
+use crate::runtime::deliveries::{DeliveryEvent, DeliveryStore};
+use crate::runtime::runs::{RunEvent, RunStore};
+use crate::runtime::schedules::{ScheduleEvent, ScheduleStore};
+
+/// An event from any subsystem.
+#[derive(Debug, Clone)]
+pub enum BusEvent {
+    Run(RunEvent),
+    Schedule(ScheduleEvent),
+    Delivery(DeliveryEvent),
+    Node(NodeEvent),
+}
+
+impl BusEvent {
+    /// Subsystem the event came from: `run`, `schedule`, `delivery` or
+    /// `node`.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::Run(_) => "run",
+            Self::Schedule(_) => "schedule",
+            Self::Delivery(_) => "delivery",
+            Self::Node(_) => "node",
+        }
+    }
+
+    /// `<source>.<what>` tag, used as the SSE event name.
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Run(RunEvent::RunStatus { .. }) => "run.status",
+            Self::Run(RunEvent::NodeStarted { .. }) => "run.node_started",
+            Self::Run(RunEvent::NodeCompleted { .. }) => "run.node_completed",
+            Self::Run(RunEvent::NodeFailed { .. }) => "run.node_failed",
+            Self::Run(RunEvent::Log { .. }) => "run.log",
+            Self::Run(RunEvent::Usage { .. }) => "run.usage",
+            Self::Run(RunEvent::ExecApprovalRequired { .. }) => "run.approval_required",
+            Self::Schedule(ScheduleEvent::ScheduleUpdated { .. }) => "schedule.updated",
+            Self::Schedule(ScheduleEvent::ScheduleRunStarted { .. }) => "schedule.run_started",
+            Self::Schedule(ScheduleEvent::ScheduleRunCompleted { .. }) => "schedule.run_completed",
+            Self::Delivery(DeliveryEvent::NewDelivery { .. }) => "delivery.new",
+            Self::Delivery(DeliveryEvent::DeliveryRead { .. }) => "delivery.read",
+            Self::Delivery(DeliveryEvent::DeliveriesRead { .. }) => "delivery.read_bulk",
+            Self::Delivery(DeliveryEvent::DeliveriesDeleted { .. }) => "delivery.deleted",
+            Self::Node(NodeEvent::Connected { .. }) => "node.connected",
+            Self::Node(NodeEvent::Disconnected { .. }) => "node.disconnected",
+            Self::Node(NodeEvent::Pruned { .. }) => "node.pruned",
+        }
+    }
+
+    /// `{ "type", "source", "data" }`, where `data` is the subsystem's own
+    /// event as its per-resource stream would send it.
+    pub fn to_json(&self) -> serde_json::Value {
+        let data = match self {
+            Self::Run(e) => to_value(e),
+            Self::Schedule(e) => to_value(e),
+            Self::Delivery(e) => to_value(e),
+            Self::Node(e) => to_value(e),
+        };
+        serde_json::json!({
+            "type": self.event_type(),
+            "source": self.source(),
+            "data": data,
+        })
+    }
+}
+
+fn to_value<T: Serialize>(event: &T) -> serde_json::Value {
+    serde_json::to_value(event).unwrap_or(serde_json::Value::Null)
+}
+
+/// Broadcast channel carrying [`BusEvent`]s from every subsystem.
+pub struct EventBus {
+    tx: broadcast::Sender<BusEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            tx: broadcast::channel(256).0,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.tx.subscribe()
+    }
+
+    pub fn publish(&self, event: BusEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    /// Republish everything from `rx` (wrapped by `wrap`) until the
+    /// source channel closes.
+    pub fn forward<E>(
+        &self,
+        mut rx: broadcast::Receiver<E>,
+        wrap: fn(E) -> BusEvent,
+    ) -> JoinHandle<()>
+    where
+        E: Clone + Send + 'static,
+    {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        let _ = tx.send(wrap(event));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!(skipped = n, "event bus forwarder lagged");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Forward run status, schedule, delivery and node events onto the bus.
+    pub fn attach(
+        &self,
+        runs: &RunStore,
+        schedules: &ScheduleStore,
+        deliveries: &DeliveryStore,
+        nodes: &NodeRegistry,
+    ) {
+        self.forward(runs.subscribe_status(), BusEvent::Run);
+        self.forward(schedules.subscribe(), BusEvent::Schedule);
+        self.forward(deliveries.subscribe(), BusEvent::Delivery);
+        self.forward(nodes.subscribe(), BusEvent::Node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::registry::ConnectedNode;
+    use crate::runtime::deliveries::Delivery;
+    use crate::runtime::runs::{Run, RunStatus};
+    use chrono::Utc;
+    use sa_domain::persistence::MemoryBackend;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    async fn next(rx: &mut broadcast::Receiver<BusEvent>) -> BusEvent {
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("timed out waiting for a bus event")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn events_from_each_subsystem_reach_the_bus_tagged() {
+        let runs = RunStore::with_backend(Arc::new(MemoryBackend::new()));
+        let schedules = ScheduleStore::with_backend(Arc::new(MemoryBackend::new()));
+        let deliveries = DeliveryStore::with_backend(Arc::new(MemoryBackend::new()));
+        let nodes = NodeRegistry::new();
+
+        let bus = EventBus::new();
+        bus.attach(&runs, &schedules, &deliveries, &nodes);
+        let mut rx = bus.subscribe();
+
+        let run_id = runs.insert(Run::new("s".into(), "s".into(), "hi"));
+        runs.emit(
+            &run_id,
+            RunEvent::RunStatus {
+                run_id,
+                status: RunStatus::Running,
+            },
+        );
+        let event = next(&mut rx).await;
+        assert_eq!(event.event_type(), "run.status");
+        let json = event.to_json();
+        assert_eq!(json["source"], "run");
+        assert_eq!(json["data"]["run_id"], run_id.to_string());
+
+        deliveries
+            .insert(Delivery::new("Digest".into(), "body".into()))
+            .await;
+        let event = next(&mut rx).await;
+        assert_eq!(event.event_type(), "delivery.new");
+        assert_eq!(event.to_json()["data"]["delivery"]["title"], "Digest");
+
+        let (sink, _sink_rx) = tokio::sync::mpsc::channel(1);
+        nodes.register(ConnectedNode {
+            node_id: "mac1".into(),
+            node_type: "macos".into(),
+            name: "Mac".into(),
+            capabilities: vec![],
+            version: "0.1.0".into(),
+            tags: vec![],
+            session_id: "s-mac1".into(),
+            connected_at: Utc::now(),
+            last_seen: Utc::now() - chrono::Duration::seconds(120),
+            sink,
+        });
+        let connected = next(&mut rx).await;
+        assert_eq!(connected.event_type(), "node.connected");
+        assert_eq!(connected.to_json()["data"]["node_id"], "mac1");
+
+        nodes.prune_stale(60);
+        let event = next(&mut rx).await;
+        assert_eq!(event.event_type(), "node.disconnected");
+        let json = event.to_json();
+        assert_eq!(json["source"], "node");
+        assert_eq!(json["data"]["reason"], "pruned");
+    }
+
+    #[tokio::test]
+    async fn run_events_other_than_status_stay_off_the_bus() {
+        let runs = RunStore::with_backend(Arc::new(MemoryBackend::new()));
+        let bus = EventBus::new();
+        bus.forward(runs.subscribe_status(), BusEvent::Run);
+        let mut rx = bus.subscribe();
+
+        let run_id = runs.insert(Run::new("s".into(), "s".into(), "hi"));
+        runs.emit(
+            &run_id,
+            RunEvent::Log {
+                run_id,
+                level: "info".into(),
+                message: "noise".into(),
+            },
+        );
+        runs.emit(
+            &run_id,
+            RunEvent::RunStatus {
+                run_id,
+                status: RunStatus::Completed,
+            },
+        );
+        assert_eq!(next(&mut rx).await.event_type(), "run.status");
+        assert!(rx.try_recv().is_err());
+    }
+}
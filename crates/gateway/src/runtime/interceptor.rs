@@ -0,0 +1,218 @@
+//! Chat request/response interceptors — a hook for custom logic around
+//! every LLM call in a turn (redacting PII, appending a system suffix,
+//! rewriting tool results) without forking the runtime.
+//!
+//! Interceptors are registered on [`AppState::chat_interceptors`] and run
+//! in registration order.  `before_request` sees the fully built
+//! [`ChatRequest`] (including prior tool results) right before it goes to
+//! the provider; `after_response` sees the accumulated response once the
+//! stream ends.  Streamed deltas have already reached the client by then,
+//! so response edits affect what is persisted and acted on (transcript,
+//! tool calls), not what was displayed live.
+//!
+//! [`AppState::chat_interceptors`]: crate::state::AppState::chat_interceptors
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use sa_providers::traits::{ChatRequest, ChatResponse};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Trait
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Which turn an intercepted call belongs to.
+#[derive(Debug, Clone, Copy)]
+pub struct InterceptContext<'a> {
+    pub session_key: &'a str,
+    pub run_id: uuid::Uuid,
+    /// Set when the turn runs as a sub-agent.
+    pub agent_id: Option<&'a str>,
+}
+
+/// Hooks invoked around each provider call.  Both default to no-ops.
+pub trait ChatRequestInterceptor: Send + Sync {
+    /// Inspect or mutate the outgoing request.
+    fn before_request(&self, _ctx: &InterceptContext<'_>, _req: &mut ChatRequest) {}
+
+    /// Inspect or mutate the accumulated response.
+    fn after_response(&self, _ctx: &InterceptContext<'_>, _resp: &mut ChatResponse) {}
+}
+
+/// Interceptor that changes nothing.
+pub struct NoopInterceptor;
+
+impl ChatRequestInterceptor for NoopInterceptor {}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Registry
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Ordered set of registered interceptors.  Empty by default.
+#[derive(Default)]
+pub struct ChatInterceptors {
+    interceptors: RwLock<Vec<Arc<dyn ChatRequestInterceptor>>>,
+}
+
+impl ChatInterceptors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an interceptor; it runs after those already registered.
+    pub fn register(&self, interceptor: Arc<dyn ChatRequestInterceptor>) {
+        self.interceptors.write().push(interceptor);
+    }
+
+    pub fn len(&self) -> usize {
+        self.interceptors.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.read().is_empty()
+    }
+
+    /// Run every `before_request` hook in registration order.
+    pub fn before_request(&self, ctx: &InterceptContext<'_>, req: &mut ChatRequest) {
+        for interceptor in self.snapshot() {
+            interceptor.before_request(ctx, req);
+        }
+    }
+
+    /// Run every `after_response` hook in registration order.
+    pub fn after_response(&self, ctx: &InterceptContext<'_>, resp: &mut ChatResponse) {
+        for interceptor in self.snapshot() {
+            interceptor.after_response(ctx, resp);
+        }
+    }
+
+    /// Clone the list so hooks never run under the lock.
+    fn snapshot(&self) -> Vec<Arc<dyn ChatRequestInterceptor>> {
+        self.interceptors.read().clone()
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use sa_domain::tool::{Message, MessageContent, Role};
+
+    fn ctx() -> InterceptContext<'static> {
+        InterceptContext {
+            session_key: "agent:main:dm:42",
+            run_id: uuid::Uuid::nil(),
+            agent_id: None,
+        }
+    }
+
+    fn request(text: &str) -> ChatRequest {
+        ChatRequest {
+            messages: vec![Message::user(text)],
+            ..Default::default()
+        }
+    }
+
+    fn response(content: &str) -> ChatResponse {
+        ChatResponse {
+            content: content.into(),
+            tool_calls: Vec::new(),
+            usage: None,
+            model: "test-model".into(),
+            finish_reason: Some("stop".into()),
+        }
+    }
+
+    fn text(message: &Message) -> &str {
+        match &message.content {
+            MessageContent::Text(t) => t,
+            other => panic!("expected text content, got {other:?}"),
+        }
+    }
+
+    /// Redacts email-like tokens and appends a system suffix.
+    struct Redactor;
+
+    impl ChatRequestInterceptor for Redactor {
+        fn before_request(&self, _ctx: &InterceptContext<'_>, req: &mut ChatRequest) {
+            for message in &mut req.messages {
+                if let MessageContent::Text(t) = &mut message.content {
+                    *t = t
+                        .split(' ')
+                        .map(|w| if w.contains('@') { "[email]" } else { w })
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                }
+            }
+            req.messages.push(Message::system("Answer briefly."));
+        }
+    }
+
+    /// Records every response it sees.
+    #[derive(Default)]
+    struct Observer {
+        seen: Mutex<Vec<(String, String)>>,
+    }
+
+    impl ChatRequestInterceptor for Observer {
+        fn after_response(&self, ctx: &InterceptContext<'_>, resp: &mut ChatResponse) {
+            self.seen
+                .lock()
+                .push((ctx.session_key.to_owned(), resp.content.clone()));
+        }
+    }
+
+    #[test]
+    fn interceptor_mutates_outgoing_messages() {
+        let interceptors = ChatInterceptors::new();
+        interceptors.register(Arc::new(Redactor));
+
+        let mut req = request("mail me at bob@example.com please");
+        interceptors.before_request(&ctx(), &mut req);
+
+        assert_eq!(req.messages.len(), 2);
+        assert_eq!(text(&req.messages[0]), "mail me at [email] please");
+        assert_eq!(req.messages[1].role, Role::System);
+    }
+
+    #[test]
+    fn interceptor_observes_response() {
+        let observer = Arc::new(Observer::default());
+        let interceptors = ChatInterceptors::new();
+        interceptors.register(Arc::new(NoopInterceptor));
+        interceptors.register(observer.clone());
+
+        let mut resp = response("hello there");
+        interceptors.after_response(&ctx(), &mut resp);
+
+        assert_eq!(
+            *observer.seen.lock(),
+            [("agent:main:dm:42".to_owned(), "hello there".to_owned())]
+        );
+        assert_eq!(resp.content, "hello there", "no-op leaves it unchanged");
+    }
+
+    #[test]
+    fn interceptors_run_in_registration_order() {
+        struct Append(&'static str);
+        impl ChatRequestInterceptor for Append {
+            fn after_response(&self, _ctx: &InterceptContext<'_>, resp: &mut ChatResponse) {
+                resp.content.push_str(self.0);
+            }
+        }
+
+        let interceptors = ChatInterceptors::new();
+        assert!(interceptors.is_empty());
+        interceptors.register(Arc::new(Append("1")));
+        interceptors.register(Arc::new(Append("2")));
+
+        let mut resp = response("x");
+        interceptors.after_response(&ctx(), &mut resp);
+        assert_eq!(resp.content, "x12");
+        assert_eq!(interceptors.len(), 2);
+    }
+}
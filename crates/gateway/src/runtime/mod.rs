@@ -10,14 +10,23 @@ pub mod cancel;
 pub mod compact;
 pub mod deliveries;
 pub mod digest;
+pub mod events;
+pub mod interceptor;
 pub mod quota;
 pub mod runs;
 pub mod schedule_runner;
 pub mod schedules;
 pub mod session_lock;
+pub mod shutdown;
 pub mod tasks;
+pub mod tool_audit;
+pub mod tool_cache;
+pub mod tool_middleware;
+pub mod tool_rate_limit;
+pub mod tool_stats;
 pub mod tools;
 pub mod turn;
+pub mod usage;
 
 pub use turn::{run_turn, TurnEvent, TurnInput};
 
@@ -77,25 +86,30 @@ pub(super) fn fire_auto_capture(state: &AppState, input: &turn::TurnInput, final
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
 /// Provider resolution order:
-/// 1. Explicit model override (from API request / agent.run)
+/// 1. Explicit model override (from API request / agent.run), then the
+///    session's pinned model
 /// 2. Smart router (when enabled and no explicit override)
 /// 3. Agent-level model mapping (per sub-agent config)
 /// 4. Global role defaults (planner/executor/summarizer)
 /// 5. Any available provider
 ///
+/// Steps 4 and 5 skip providers whose circuit breaker is open.
+///
 /// Returns the provider and an optional model name (when the router
 /// selects a specific model within the provider).
 #[allow(clippy::type_complexity)]
-pub(super) fn resolve_provider(
+pub(crate) fn resolve_provider(
     state: &AppState,
     model_override: Option<&str>,
     agent_ctx: Option<&agent::AgentContext>,
     routing_profile: Option<sa_domain::config::RoutingProfile>,
+    pinned_model: Option<&str>,
 ) -> Result<(Arc<dyn sa_providers::LlmProvider>, Option<String>), Box<dyn std::error::Error + Send + Sync>> {
-    // 1. Explicit override.
-    if let Some(spec) = model_override {
-        let provider_id = spec.split('/').next().unwrap_or(spec);
-        if let Some(p) = state.llm.get(provider_id) {
+    // 1. Explicit override, then the session pin.
+    if let Some(spec) = pick_model_spec(model_override, pinned_model, |id| {
+        state.llm.get(id).is_some()
+    }) {
+        if let Some(p) = state.llm.get(model_provider_id(spec)) {
             let model_name = spec.split_once('/').map(|(_, m)| m.to_string());
             return Ok((p, model_name));
         }
@@ -139,13 +153,26 @@ pub(super) fn resolve_provider(
         }
     }
 
-    // 4. Global role defaults.
-    if let Some(p) = state.llm.for_role("executor") {
+    // 4. Global role defaults (skipped while the provider's breaker is open
+    //    or it is inside a 429 rate-limit window).
+    if let Some(p) = state
+        .llm
+        .for_role("executor")
+        .filter(|p| state.llm.try_acquire(p.provider_id()))
+    {
         return Ok((p, None));
     }
 
-    // 5. Any available provider.
-    if let Some((_, p)) = state.llm.iter().next() {
+    // 5. Any available provider, preferring one that is not tripped or
+    //    rate limited.  Candidates are screened without side effects and
+    //    only the one picked claims its breaker (and any half-open probe).
+    if let Some((_, p)) = state
+        .llm
+        .iter()
+        .filter(|(id, _)| state.llm.is_available(id))
+        .find(|(id, _)| state.llm.try_acquire(id))
+        .or_else(|| state.llm.iter().next())
+    {
         return Ok((p.clone(), None));
     }
 
@@ -155,6 +182,57 @@ pub(super) fn resolve_provider(
         .into())
 }
 
+/// Longest a request waits out its provider's rate-limit window before
+/// it is shed instead.
+const MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Hold a request to `provider_id` until its rate-limit window passes.
+/// Windows longer than [`MAX_RATE_LIMIT_WAIT`] shed the request with
+/// [`Error::RateLimited`] rather than sending it into another 429.
+///
+/// [`Error::RateLimited`]: sa_domain::error::Error::RateLimited
+pub(crate) async fn wait_for_rate_limit(
+    limits: &sa_providers::rate_limit::RateLimits,
+    provider_id: &str,
+) -> sa_domain::error::Result<()> {
+    let Some(retry_in) = limits.retry_in(provider_id) else {
+        return Ok(());
+    };
+    if retry_in > MAX_RATE_LIMIT_WAIT {
+        return Err(sa_domain::error::Error::RateLimited {
+            provider: provider_id.to_owned(),
+            retry_after_secs: Some(retry_in.as_secs_f64().ceil() as u64),
+            message: "request shed while the provider's rate-limit window is open".into(),
+        });
+    }
+    tracing::debug!(
+        provider = provider_id,
+        wait_ms = retry_in.as_millis() as u64,
+        "waiting out provider rate-limit window"
+    );
+    tokio::time::sleep(retry_in).await;
+    Ok(())
+}
+
+/// Provider id of a `provider/model` (or bare `provider`) spec.
+pub(crate) fn model_provider_id(spec: &str) -> &str {
+    spec.split('/').next().unwrap_or(spec)
+}
+
+/// The first of the request override and the session pin whose provider
+/// is configured.  An override naming an unknown provider falls back to
+/// the pin rather than straight to routing.
+fn pick_model_spec<'a>(
+    model_override: Option<&'a str>,
+    pinned_model: Option<&'a str>,
+    has_provider: impl Fn(&str) -> bool,
+) -> Option<&'a str> {
+    [model_override, pinned_model]
+        .into_iter()
+        .flatten()
+        .find(|spec| has_provider(model_provider_id(spec)))
+}
+
 /// Resolve the "summarizer" role provider for compaction. Falls back to executor.
 pub(super) fn resolve_summarizer(state: &AppState) -> Option<Arc<dyn sa_providers::LlmProvider>> {
     state
@@ -164,16 +242,25 @@ pub(super) fn resolve_summarizer(state: &AppState) -> Option<Arc<dyn sa_provider
         .or_else(|| state.llm.iter().next().map(|(_, p)| p.clone()))
 }
 
+/// Session mode used for the runtime system context: the bootstrap ritual
+/// on first run, normal otherwise.
+pub(crate) fn system_context_mode(is_first_run: bool) -> SessionMode {
+    if is_first_run {
+        SessionMode::Bootstrap
+    } else {
+        SessionMode::Normal
+    }
+}
+
+/// `user_message` picks which skills the index lists when
+/// `skills.index_top_k` is set.
 pub(super) async fn build_system_context(
     state: &AppState,
     agent_ctx: Option<&agent::AgentContext>,
+    user_message: &str,
 ) -> String {
     let is_first_run = state.bootstrap.is_first_run("default");
-    let session_mode = if is_first_run {
-        SessionMode::Bootstrap
-    } else {
-        SessionMode::Normal
-    };
+    let session_mode = system_context_mode(is_first_run);
 
     let user_facts = {
         let user_id = &state.config.serial_memory.default_user_id;
@@ -198,7 +285,11 @@ pub(super) async fn build_system_context(
                 state.memory.as_ref(),
                 user_id,
                 state.config.context.user_facts_max_chars,
-            );
+            )
+            .with_source_policy(state.config.serial_memory.user_facts.clone())
+            .with_token_counter(sa_domain::tokens::counter_for(
+                state.config.context.budget_unit,
+            ));
             let facts = facts_builder.build().await;
 
             // Populate cache (evict expired entries if too large).
@@ -225,20 +316,19 @@ pub(super) async fn build_system_context(
         Some(user_facts.as_str())
     };
 
-    let builder = ContextPackBuilder::new(
-        state.config.context.bootstrap_max_chars,
-        state.config.context.bootstrap_total_max_chars,
-    );
+    let builder = ContextPackBuilder::from_config(&state.config.context);
 
     // Use agent-scoped workspace/skills if running as a sub-agent.
     let ws_files = match agent_ctx {
         Some(ctx) => ctx.workspace.read_all_context_files(),
         None => state.workspace.read_all_context_files(),
     };
-    let skills_index = match agent_ctx {
-        Some(ctx) => ctx.skills.render_ready_index(),
-        None => state.skills.render_ready_index(),
+    let skills = match agent_ctx {
+        Some(ctx) => &ctx.skills,
+        None => &state.skills,
     };
+    let skills_index =
+        skills.render_relevant_index(user_message, state.config.skills.index_top_k);
     let skills_idx = if skills_index.is_empty() {
         None
     } else {
@@ -296,7 +386,7 @@ pub(super) fn transcript_lines_to_messages(lines: &[TranscriptLine]) -> Vec<Mess
     messages
 }
 
-pub(super) fn build_assistant_tool_message(text: &str, tool_calls: &[ToolCall]) -> Message {
+pub(crate) fn build_assistant_tool_message(text: &str, tool_calls: &[ToolCall]) -> Message {
     use sa_domain::tool::ContentPart;
 
     let mut parts = Vec::new();
@@ -321,7 +411,7 @@ pub(super) fn build_assistant_tool_message(text: &str, tool_calls: &[ToolCall])
     }
 }
 
-pub(super) async fn persist_transcript(
+pub(crate) async fn persist_transcript(
     transcripts: &Arc<TranscriptWriter>,
     session_id: &str,
     role: &str,
@@ -613,4 +703,55 @@ mod tests {
             _ => panic!("expected Parts content"),
         }
     }
+
+    #[test]
+    fn pinned_model_applies_without_request_override() {
+        let has = |id: &str| matches!(id, "openai" | "anthropic");
+        assert_eq!(
+            pick_model_spec(None, Some("anthropic/claude-sonnet"), has),
+            Some("anthropic/claude-sonnet")
+        );
+        assert_eq!(pick_model_spec(None, None, has), None);
+    }
+
+    #[test]
+    fn request_override_beats_pinned_model() {
+        let has = |id: &str| matches!(id, "openai" | "anthropic");
+        assert_eq!(
+            pick_model_spec(Some("openai/gpt-4o"), Some("anthropic/claude-sonnet"), has),
+            Some("openai/gpt-4o")
+        );
+        // An override naming an unconfigured provider falls back to the pin.
+        assert_eq!(
+            pick_model_spec(Some("nope/x"), Some("anthropic/claude-sonnet"), has),
+            Some("anthropic/claude-sonnet")
+        );
+    }
+
+    #[tokio::test]
+    async fn long_rate_limit_window_sheds_request() {
+        let limits = sa_providers::rate_limit::RateLimits::new();
+        assert!(wait_for_rate_limit(&limits, "openai").await.is_ok());
+
+        limits.record("openai", Some(30));
+        let err = wait_for_rate_limit(&limits, "openai").await.unwrap_err();
+        assert!(matches!(
+            err,
+            sa_domain::error::Error::RateLimited {
+                retry_after_secs: Some(secs),
+                ..
+            } if (25..=30).contains(&secs)
+        ));
+        assert!(wait_for_rate_limit(&limits, "anthropic").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn short_rate_limit_window_is_waited_out() {
+        let limits = sa_providers::rate_limit::RateLimits::new();
+        limits.record("openai", Some(1));
+        let started = std::time::Instant::now();
+        assert!(wait_for_rate_limit(&limits, "openai").await.is_ok());
+        assert!(started.elapsed() >= std::time::Duration::from_millis(500));
+        assert!(limits.allow("openai"));
+    }
 }
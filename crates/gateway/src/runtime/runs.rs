@@ -2,19 +2,25 @@
 //!
 //! Each call to [`run_turn`] produces a `Run` with a unique UUID. The run
 //! contains a list of `RunNode`s representing each step (LLM calls, tool
-//! invocations). Runs are persisted to a JSONL file and kept in a bounded
-//! in-memory ring for fast queries.
+//! invocations). Runs are persisted to a JSONL log through a
+//! [`PersistenceBackend`] and kept in a bounded in-memory ring for fast
+//! queries.
 
 use std::collections::{HashMap, VecDeque};
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use sa_domain::persistence::{
+    tag_unversioned, FsBackend, LoadedRecord, PersistenceBackend, RecordSchema,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
+use super::digest::SourceFailure;
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Run status
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -83,6 +89,9 @@ pub struct Run {
     pub status: RunStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agent_id: Option<String>,
+    /// Sub-agent nesting depth (0 = master, 1 = direct child, ...).
+    #[serde(default)]
+    pub depth: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model: Option<String>,
     pub started_at: DateTime<Utc>,
@@ -107,6 +116,13 @@ pub struct Run {
     /// Estimated cost in USD based on configured model pricing.
     #[serde(default)]
     pub estimated_cost_usd: f64,
+    /// Sampling seed the turn was run with, for reproducing it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Schedule sources that failed to fetch for this run; its digest was
+    /// built from the remaining ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_sources: Vec<SourceFailure>,
 }
 
 impl Run {
@@ -117,6 +133,7 @@ impl Run {
             session_id,
             status: RunStatus::Queued,
             agent_id: None,
+            depth: 0,
             model: None,
             started_at: Utc::now(),
             ended_at: None,
@@ -130,6 +147,8 @@ impl Run {
             nodes: Vec::new(),
             loop_count: 0,
             estimated_cost_usd: 0.0,
+            seed: None,
+            failed_sources: Vec::new(),
         }
     }
 
@@ -178,13 +197,47 @@ pub enum RunEvent {
 
 const MAX_RUNS_IN_MEMORY: usize = 2000;
 
+/// Backend key of the runs JSONL log.
+const RUNS_KEY: &str = "runs/runs.jsonl";
+
+/// Schema of persisted [`Run`] records.
+const RUN_SCHEMA: RecordSchema = RecordSchema {
+    current: 2,
+    migrations: &[tag_unversioned],
+};
+
 pub struct RunStore {
     /// Bounded ring of recent runs (newest last) + O(1) index.
     inner: RwLock<RunStoreInner>,
-    /// JSONL persistence path.
-    log_path: PathBuf,
-    /// Per-run broadcast channels for SSE.
-    event_channels: RwLock<HashMap<Uuid, broadcast::Sender<RunEvent>>>,
+    /// Where the JSONL log is persisted.
+    backend: Arc<dyn PersistenceBackend>,
+    /// Serializes every write to the log (appends and prune rewrites), so
+    /// a rewrite can't drop a record appended while it was filtering.
+    /// Never held together with `inner`.
+    log_lock: Mutex<()>,
+    /// Per-run broadcast channels (plus replay buffers) for SSE.
+    event_channels: RwLock<HashMap<Uuid, RunChannel>>,
+    /// Status changes of every run (for the unified event stream).
+    status_tx: broadcast::Sender<RunEvent>,
+}
+
+/// Most events kept for replay per run; older ones are dropped first.
+const MAX_REPLAY_EVENTS: usize = 512;
+
+/// A run's live broadcast channel plus every event emitted so far (up to
+/// [`MAX_REPLAY_EVENTS`]), so late subscribers can rebuild the node tree.
+struct RunChannel {
+    tx: broadcast::Sender<RunEvent>,
+    backlog: VecDeque<RunEvent>,
+}
+
+impl RunChannel {
+    fn new() -> Self {
+        Self {
+            tx: broadcast::channel(128).0,
+            backlog: VecDeque::new(),
+        }
+    }
 }
 
 /// Interior state behind the RwLock — VecDeque plus a HashMap index
@@ -243,74 +296,203 @@ impl RunStoreInner {
 }
 
 impl RunStore {
-    /// Create a new RunStore, loading recent runs from the JSONL file.
+    /// Create a new RunStore, loading recent runs from
+    /// `state_path/runs/runs.jsonl`.
     pub fn new(state_path: &Path) -> Self {
-        let dir = state_path.join("runs");
-        std::fs::create_dir_all(&dir).ok();
+        Self::with_backend(Arc::new(FsBackend::new(state_path)))
+    }
 
-        let log_path = dir.join("runs.jsonl");
-        let (runs, total_on_disk) = Self::load_recent(&log_path);
+    /// Create a RunStore persisting through `backend`, loading recent runs
+    /// from its log.
+    pub fn with_backend(backend: Arc<dyn PersistenceBackend>) -> Self {
+        let (runs, future, total_on_disk) = Self::load_recent(backend.as_ref());
 
         // Prune the JSONL file if it contained more entries than we kept.
-        if total_on_disk > runs.len() {
+        let kept = runs.len() + future.len();
+        if total_on_disk > kept {
             tracing::info!(
-                kept = runs.len(),
-                pruned = total_on_disk - runs.len(),
+                kept,
+                pruned = total_on_disk - kept,
                 "pruning runs JSONL on disk"
             );
-            Self::rewrite_jsonl(&log_path, &runs);
+            Self::rewrite_jsonl(backend.as_ref(), &future, &runs);
         }
 
         Self {
             inner: RwLock::new(RunStoreInner::new(runs)),
-            log_path,
+            backend,
+            log_lock: Mutex::new(()),
             event_channels: RwLock::new(HashMap::new()),
+            status_tx: broadcast::channel(128).0,
         }
     }
 
-    /// Load the most recent MAX_RUNS_IN_MEMORY runs from the JSONL file.
-    /// Returns (runs, total_line_count) to detect if pruning is needed.
-    fn load_recent(path: &Path) -> (VecDeque<Run>, usize) {
+    /// Load the most recent MAX_RUNS_IN_MEMORY runs from the JSONL log,
+    /// upgrading older records.  Lines written by a newer release are
+    /// returned verbatim so pruning keeps them.
+    /// Returns (runs, future_lines, total_line_count).
+    fn load_recent(backend: &dyn PersistenceBackend) -> (VecDeque<Run>, Vec<String>, usize) {
         let mut runs = VecDeque::new();
+        let mut future = Vec::new();
         let mut total = 0;
-        if let Ok(content) = std::fs::read_to_string(path) {
+        if let Ok(Some(raw)) = backend.load(RUNS_KEY) {
+            let content = String::from_utf8_lossy(&raw);
             let lines: Vec<&str> = content.lines().collect();
             total = lines.len();
-            for line in lines.iter().rev().take(MAX_RUNS_IN_MEMORY) {
-                if let Ok(run) = serde_json::from_str::<Run>(line) {
-                    runs.push_front(run);
+            for line in lines.iter().rev() {
+                let Ok(value) = serde_json::from_str(line) else {
+                    continue;
+                };
+                match RUN_SCHEMA.load::<Run>(value) {
+                    LoadedRecord::Current(run) if runs.len() < MAX_RUNS_IN_MEMORY => {
+                        runs.push_front(run)
+                    }
+                    LoadedRecord::Future(_) => future.insert(0, line.to_string()),
+                    _ => {}
                 }
             }
         }
-        (runs, total)
-    }
-
-    /// Rewrite the JSONL file with only the given runs (disk pruning).
-    fn rewrite_jsonl(path: &Path, runs: &VecDeque<Run>) {
-        let tmp = path.with_extension("jsonl.tmp");
-        let mut ok = false;
-        if let Ok(mut f) = std::fs::File::create(&tmp) {
-            use std::io::Write;
-            ok = true;
-            for run in runs {
-                if let Ok(json) = serde_json::to_string(run) {
-                    if writeln!(f, "{}", json).is_err() {
-                        ok = false;
-                        break;
-                    }
+        if !future.is_empty() {
+            tracing::warn!(
+                count = future.len(),
+                "runs log has records from a newer release; preserving them unread"
+            );
+        }
+        (runs, future, total)
+    }
+
+    /// Rewrite the JSONL log with only the given runs (pruning).
+    fn rewrite_jsonl(backend: &dyn PersistenceBackend, future: &[String], runs: &VecDeque<Run>) {
+        let mut log = String::new();
+        for line in future {
+            log.push_str(line);
+            log.push('\n');
+        }
+        for run in runs {
+            if let Ok(json) = RUN_SCHEMA.stamp(run) {
+                log.push_str(&json.to_string());
+                log.push('\n');
+            }
+        }
+        if let Err(e) = backend.save(RUNS_KEY, log.as_bytes()) {
+            tracing::warn!(error = %e, "failed to rewrite runs log");
+        }
+    }
+
+    /// Delete persisted runs beyond the retention limits: finished runs
+    /// that started before `max_age` ago, then the oldest finished runs
+    /// past `max_count`.  Unfinished runs don't count against the cap, and
+    /// neither they nor records from a newer release are ever deleted.  Deleted runs are also dropped from memory.
+    /// Returns how many runs were deleted.
+    pub fn prune_persisted(
+        &self,
+        max_age: Option<chrono::Duration>,
+        max_count: Option<usize>,
+    ) -> usize {
+        if max_age.is_none() && max_count.is_none() {
+            return 0;
+        }
+        // Held from read to rewrite so no append lands in between.
+        let log_guard = self.log_lock.lock();
+        let Ok(Some(raw)) = self.backend.load(RUNS_KEY) else {
+            return 0;
+        };
+        let content = String::from_utf8_lossy(&raw);
+
+        // Latest record per run (each update appends a fresh snapshot).
+        let mut latest: HashMap<Uuid, Run> = HashMap::new();
+        let mut lines: Vec<(Option<Uuid>, &str)> = Vec::new();
+        for line in content.lines() {
+            let Ok(value) = serde_json::from_str(line) else {
+                continue;
+            };
+            match RUN_SCHEMA.load::<Run>(value) {
+                LoadedRecord::Current(run) => {
+                    lines.push((Some(run.run_id), line));
+                    latest.insert(run.run_id, run);
                 }
+                LoadedRecord::Future(_) => lines.push((None, line)),
+                LoadedRecord::Invalid(_) => {}
             }
         }
-        if ok {
-            let _ = std::fs::rename(&tmp, path);
-        } else {
-            let _ = std::fs::remove_file(&tmp);
+
+        let mut deleted: std::collections::HashSet<Uuid> = std::collections::HashSet::new();
+        if let Some(age) = max_age {
+            let cutoff = Utc::now() - age;
+            deleted.extend(
+                latest
+                    .values()
+                    .filter(|r| r.status.is_terminal() && r.started_at < cutoff)
+                    .map(|r| r.run_id),
+            );
+        }
+        if let Some(max) = max_count {
+            let mut remaining: Vec<&Run> = latest
+                .values()
+                .filter(|r| r.status.is_terminal() && !deleted.contains(&r.run_id))
+                .collect();
+            if remaining.len() > max {
+                remaining.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+                deleted.extend(remaining[max..].iter().map(|r| r.run_id));
+            }
+        }
+        if deleted.is_empty() {
+            return 0;
+        }
+
+        let mut log = String::new();
+        for (id, line) in &lines {
+            if id.is_none_or(|id| !deleted.contains(&id)) {
+                log.push_str(line);
+                log.push('\n');
+            }
+        }
+        if let Err(e) = self.backend.save(RUNS_KEY, log.as_bytes()) {
+            tracing::warn!(error = %e, "failed to rewrite runs log");
+            return 0;
+        }
+        drop(log_guard);
+
+        let mut inner = self.inner.write();
+        let kept: VecDeque<Run> = std::mem::take(&mut inner.runs)
+            .into_iter()
+            .filter(|r| !deleted.contains(&r.run_id))
+            .collect();
+        *inner = RunStoreInner::new(kept);
+        deleted.len()
+    }
+
+    /// Every run in the on-disk log (latest record per run), oldest first.
+    /// Unlike [`list`](Self::list) this is not capped at the in-memory
+    /// window, so it reads the whole log — call it off the async runtime.
+    pub fn persisted_runs(&self) -> Vec<Run> {
+        let Ok(Some(raw)) = self.backend.load(RUNS_KEY) else {
+            return Vec::new();
+        };
+        let content = String::from_utf8_lossy(&raw);
+        let mut latest: HashMap<Uuid, Run> = HashMap::new();
+        for line in content.lines() {
+            let Ok(value) = serde_json::from_str(line) else {
+                continue;
+            };
+            if let LoadedRecord::Current(run) = RUN_SCHEMA.load::<Run>(value) {
+                latest.insert(run.run_id, run);
+            }
         }
+        let mut runs: Vec<Run> = latest.into_values().collect();
+        runs.sort_by_key(|r| r.started_at);
+        runs
     }
 
-    /// Insert a new run. Returns the run_id.
+    /// Insert a new run and open its event channel, so events are
+    /// buffered for replay even before anyone subscribes.  Returns the
+    /// run_id.
     pub fn insert(&self, run: Run) -> Uuid {
         let run_id = run.run_id;
+        self.event_channels
+            .write()
+            .entry(run_id)
+            .or_insert_with(RunChannel::new);
         let mut inner = self.inner.write();
         inner.push_back(run);
         if inner.runs.len() > MAX_RUNS_IN_MEMORY {
@@ -332,16 +514,11 @@ impl RunStore {
         false
     }
 
-    /// Persist a run to the JSONL file (append).
+    /// Persist a run to the JSONL log (append).
     pub fn persist(&self, run: &Run) {
-        if let Ok(json) = serde_json::to_string(run) {
-            if let Ok(mut file) = std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&self.log_path)
-            {
-                let _ = writeln!(file, "{json}");
-            }
+        if let Ok(json) = RUN_SCHEMA.stamp(run) {
+            let _guard = self.log_lock.lock();
+            let _ = self.backend.append(RUNS_KEY, format!("{json}\n").as_bytes());
         }
     }
 
@@ -397,24 +574,42 @@ impl RunStore {
         (page, total)
     }
 
-    /// Get or create a broadcast channel for a run (for SSE).
-    pub fn subscribe(&self, run_id: &Uuid) -> broadcast::Receiver<RunEvent> {
+    /// Get or create a broadcast channel for a run (for SSE).  Returns
+    /// the events emitted so far alongside a receiver for later ones;
+    /// both are taken under one lock, so nothing is missed or repeated.
+    pub fn subscribe(&self, run_id: &Uuid) -> (Vec<RunEvent>, broadcast::Receiver<RunEvent>) {
         let mut channels = self.event_channels.write();
-        let tx = channels
-            .entry(*run_id)
-            .or_insert_with(|| broadcast::channel(128).0);
-        tx.subscribe()
+        let channel = channels.entry(*run_id).or_insert_with(RunChannel::new);
+        (
+            channel.backlog.iter().cloned().collect(),
+            channel.tx.subscribe(),
+        )
     }
 
-    /// Emit an event for a run (broadcast to all subscribers).
+    /// Subscribe to status changes of all runs, whether or not anyone is
+    /// watching the individual run.
+    pub fn subscribe_status(&self) -> broadcast::Receiver<RunEvent> {
+        self.status_tx.subscribe()
+    }
+
+    /// Emit an event for a run (buffered for replay and broadcast to all
+    /// subscribers).
     pub fn emit(&self, run_id: &Uuid, event: RunEvent) {
-        let channels = self.event_channels.read();
-        if let Some(tx) = channels.get(run_id) {
-            let _ = tx.send(event);
+        if matches!(event, RunEvent::RunStatus { .. }) {
+            let _ = self.status_tx.send(event.clone());
+        }
+        let mut channels = self.event_channels.write();
+        if let Some(channel) = channels.get_mut(run_id) {
+            if channel.backlog.len() >= MAX_REPLAY_EVENTS {
+                channel.backlog.pop_front();
+            }
+            channel.backlog.push_back(event.clone());
+            let _ = channel.tx.send(event);
         }
     }
 
-    /// Clean up the broadcast channel for a completed run.
+    /// Clean up the broadcast channel and replay buffer for a completed
+    /// run.
     pub fn cleanup_channel(&self, run_id: &Uuid) {
         let mut channels = self.event_channels.write();
         channels.remove(run_id);
@@ -545,6 +740,118 @@ mod tests {
         assert_eq!(fetched.status, RunStatus::Completed);
     }
 
+    #[test]
+    fn seed_is_stored_on_the_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RunStore::new(dir.path());
+
+        let mut run = Run::new("sk".into(), "sid".into(), "msg");
+        run.seed = Some(42);
+        run.finish(RunStatus::Completed);
+        store.insert(run.clone());
+        store.persist(&run);
+
+        let reloaded = RunStore::new(dir.path());
+        assert_eq!(reloaded.get(&run.run_id).unwrap().seed, Some(42));
+
+        // Unseeded runs don't carry the field at all.
+        let json = serde_json::to_value(Run::new("sk".into(), "sid".into(), "m")).unwrap();
+        assert!(json.get("seed").is_none());
+    }
+
+    #[test]
+    fn memory_backend_matches_fs_backend() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let dir = tempfile::tempdir().unwrap();
+        let backends: [Arc<dyn PersistenceBackend>; 2] = [
+            Arc::new(FsBackend::new(dir.path())),
+            Arc::new(MemoryBackend::new()),
+        ];
+        for backend in backends {
+            let store = RunStore::with_backend(backend.clone());
+            let mut ids = Vec::new();
+            for i in 0..3 {
+                let mut run = Run::new("sk".into(), "sid".into(), &format!("msg{i}"));
+                run.finish(RunStatus::Completed);
+                ids.push(run.run_id);
+                store.insert(run.clone());
+                store.persist(&run);
+            }
+
+            let reloaded = RunStore::with_backend(backend.clone());
+            let (list, total) = reloaded.list(None, None, None, 10, 0);
+            assert_eq!(total, 3);
+            // Newest first, same as before the reload.
+            let listed: Vec<_> = list.iter().map(|r| r.run_id).collect();
+            assert_eq!(listed, ids.iter().rev().copied().collect::<Vec<_>>());
+            assert_eq!(
+                reloaded.get(&ids[0]).unwrap().status,
+                RunStatus::Completed
+            );
+
+            let log = backend.load(RUNS_KEY).unwrap().unwrap();
+            assert_eq!(String::from_utf8(log).unwrap().lines().count(), 3);
+        }
+        assert!(dir.path().join("runs/runs.jsonl").is_file());
+    }
+
+    #[test]
+    fn v1_runs_load_and_future_runs_survive_pruning() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let mut legacy = Run::new("sk".into(), "sid".into(), "old");
+        legacy.finish(RunStatus::Completed);
+        // v1: written before records carried a schema_version.
+        let v1 = serde_json::to_string(&legacy).unwrap();
+        assert!(!v1.contains("schema_version"));
+        let future = r#"{"schema_version":99,"run_id":"x","shape":"unknown"}"#;
+        let mut log = format!("{v1}\n{future}\n");
+        for i in 0..MAX_RUNS_IN_MEMORY {
+            let run = Run::new("sk".into(), "sid".into(), &format!("filler{i}"));
+            log.push_str(&serde_json::to_string(&run).unwrap());
+            log.push('\n');
+        }
+        backend.save(RUNS_KEY, log.as_bytes()).unwrap();
+
+        // The v1 record is readable, and pruning (which evicts it) keeps
+        // the unreadable newer record and stamps the rest as v2.
+        let (runs, _, _) = RunStore::load_recent(backend.as_ref());
+        assert_eq!(runs.len(), MAX_RUNS_IN_MEMORY);
+        let store = RunStore::with_backend(backend.clone());
+        assert!(store.get(&legacy.run_id).is_none());
+
+        let rewritten = String::from_utf8(backend.load(RUNS_KEY).unwrap().unwrap()).unwrap();
+        let lines: Vec<&str> = rewritten.lines().collect();
+        assert_eq!(lines.len(), MAX_RUNS_IN_MEMORY + 1);
+        assert_eq!(lines[0], future);
+        let first: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(first["schema_version"], 2);
+
+        // Round trip: a fresh run persisted as v2 reads back unchanged.
+        let mut run = Run::new("sk".into(), "sid".into(), "new");
+        run.finish(RunStatus::Failed);
+        store.persist(&run);
+        let reloaded = RunStore::with_backend(backend);
+        let got = reloaded.get(&run.run_id).unwrap();
+        assert_eq!(got.status, RunStatus::Failed);
+        assert_eq!(got.input_preview, run.input_preview);
+    }
+
+    #[test]
+    fn v1_run_loads_into_v2_store() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let legacy = Run::new("sk".into(), "sid".into(), "old");
+        let v1 = format!("{}\n", serde_json::to_string(&legacy).unwrap());
+        backend.save(RUNS_KEY, v1.as_bytes()).unwrap();
+
+        let store = RunStore::with_backend(backend);
+        assert_eq!(store.get(&legacy.run_id).unwrap().session_key, "sk");
+    }
+
     #[test]
     fn bounded_ring() {
         let dir = tempfile::tempdir().unwrap();
@@ -711,4 +1018,131 @@ mod tests {
         let deserialized: Run = serde_json::from_str(&json).unwrap();
         assert!((deserialized.estimated_cost_usd - 0.0).abs() < f64::EPSILON);
     }
+
+    fn log_event(run_id: Uuid, message: &str) -> RunEvent {
+        RunEvent::Log {
+            run_id,
+            level: "info".into(),
+            message: message.into(),
+        }
+    }
+
+    fn log_message(event: &RunEvent) -> &str {
+        match event {
+            RunEvent::Log { message, .. } => message,
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn late_subscriber_gets_backlog_then_live_events() {
+        use sa_domain::persistence::MemoryBackend;
+        let store = RunStore::with_backend(Arc::new(MemoryBackend::new()));
+        let run_id = store.insert(Run::new("s".into(), "s".into(), "hi"));
+
+        store.emit(&run_id, log_event(run_id, "first"));
+        store.emit(&run_id, log_event(run_id, "second"));
+
+        let (backlog, mut rx) = store.subscribe(&run_id);
+        let replayed: Vec<&str> = backlog.iter().map(log_message).collect();
+        assert_eq!(replayed, ["first", "second"]);
+
+        store.emit(&run_id, log_event(run_id, "third"));
+        assert_eq!(log_message(&rx.try_recv().unwrap()), "third");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn replay_buffer_is_bounded_and_dropped_on_cleanup() {
+        use sa_domain::persistence::MemoryBackend;
+        let store = RunStore::with_backend(Arc::new(MemoryBackend::new()));
+        let run_id = store.insert(Run::new("s".into(), "s".into(), "hi"));
+        for i in 0..MAX_REPLAY_EVENTS + 10 {
+            store.emit(&run_id, log_event(run_id, &i.to_string()));
+        }
+        let (backlog, _rx) = store.subscribe(&run_id);
+        assert_eq!(backlog.len(), MAX_REPLAY_EVENTS);
+        assert_eq!(log_message(&backlog[0]), "10");
+
+        store.cleanup_channel(&run_id);
+        let (backlog, _rx) = store.subscribe(&run_id);
+        assert!(backlog.is_empty());
+    }
+
+    #[test]
+    fn prune_persisted_enforces_age_and_count() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let store = RunStore::with_backend(backend.clone());
+        let run_aged = |days: i64, status: RunStatus| {
+            let mut run = Run::new("sk".into(), "sid".into(), "m");
+            run.started_at = Utc::now() - chrono::Duration::days(days);
+            run.status = status;
+            store.insert(run.clone());
+            store.persist(&run);
+            run.run_id
+        };
+        let ancient = run_aged(90, RunStatus::Completed);
+        let stuck = run_aged(90, RunStatus::Running);
+        let old = run_aged(10, RunStatus::Failed);
+        let recent = run_aged(2, RunStatus::Completed);
+        let newest = run_aged(0, RunStatus::Completed);
+
+        // Age: only the finished 90-day-old run goes.
+        let month = chrono::Duration::days(30);
+        assert_eq!(store.prune_persisted(Some(month), None), 1);
+        assert!(store.get(&ancient).is_none());
+        assert!(store.get(&stuck).is_some(), "unfinished runs are kept");
+
+        // Count: keep the newest two finished runs; the unfinished one
+        // doesn't count against the cap.
+        assert_eq!(store.prune_persisted(None, Some(2)), 1);
+        assert!(store.get(&old).is_none());
+
+        let reloaded = RunStore::with_backend(backend);
+        for id in [stuck, recent, newest] {
+            assert!(reloaded.get(&id).is_some());
+        }
+        for id in [ancient, old] {
+            assert!(reloaded.get(&id).is_none());
+        }
+        assert_eq!(store.prune_persisted(None, None), 0);
+    }
+
+    #[test]
+    fn prune_keeps_runs_persisted_while_it_runs() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let store = Arc::new(RunStore::with_backend(Arc::new(MemoryBackend::new())));
+        let mut old = Run::new("sk".into(), "sid".into(), "m");
+        old.started_at = Utc::now() - chrono::Duration::days(90);
+        old.status = RunStatus::Completed;
+        store.persist(&old);
+
+        let writer = {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                (0..200)
+                    .map(|_| {
+                        let run = Run::new("sk".into(), "sid".into(), "m");
+                        store.persist(&run);
+                        run.run_id
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let month = chrono::Duration::days(30);
+        while !writer.is_finished() {
+            store.prune_persisted(Some(month), None);
+        }
+        let written = writer.join().unwrap();
+
+        let persisted: std::collections::HashSet<Uuid> =
+            store.persisted_runs().iter().map(|r| r.run_id).collect();
+        assert!(!persisted.contains(&old.run_id));
+        for id in written {
+            assert!(persisted.contains(&id), "run {id} lost in a prune");
+        }
+    }
 }
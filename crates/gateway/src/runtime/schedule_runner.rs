@@ -235,18 +235,40 @@ pub async fn spawn_scheduled_run(
 
     // If the schedule has sources, use the digest pipeline (fetch + change detection).
     // Otherwise, use the simple prompt builder.
+    // A failing source is recorded and skipped; only when every source
+    // fails is the run itself failed.
+    let mut failed_sources = Vec::new();
     let user_prompt = if schedule.sources.is_empty() {
         schedule.prompt_template.clone()
     } else {
         let results = digest::fetch_all_sources(&schedule).await;
 
         // Update source states for change detection on next run.
-        let new_states = digest::build_source_states(&results);
+        let new_states = digest::build_source_states(&results, &schedule.source_states);
         state
             .schedule_store
             .update_source_states(&sched_id, new_states)
             .await;
 
+        failed_sources = digest::source_failures(&results);
+        for f in &failed_sources {
+            tracing::warn!(
+                schedule_id = %sched_id,
+                url = %f.url,
+                error = %f.error,
+                "schedule source fetch failed"
+            );
+        }
+        if digest::all_sources_failed(&results) {
+            let error = digest::describe_failures(&failed_sources);
+            tracing::warn!(schedule_id = %sched_id, error = %error, "scheduled run skipped");
+            state.schedule_store.record_failure(&sched_id, &error).await;
+            if let Some(counter) = concurrency_counter {
+                counter.fetch_sub(1, Ordering::SeqCst);
+            }
+            return;
+        }
+
         digest::build_digest_prompt(&schedule, &results)
     };
 
@@ -277,9 +299,14 @@ pub async fn spawn_scheduled_run(
         response_format: None,
         agent: None,
         routing_profile,
+        history: None,
+        images: Vec::new(),
+        seed: None,
+        stop: Vec::new(),
     };
 
     let (run_id, mut rx) = crate::runtime::run_turn(state.clone(), input);
+    record_failed_sources(&state.run_store, run_id, &failed_sources);
 
     // Record the run
     state.schedule_store.record_run(&sched_id, run_id).await;
@@ -363,6 +390,9 @@ pub async fn spawn_scheduled_run(
         delivery.schedule_name = Some(schedule.name.clone());
         delivery.run_id = Some(run_id);
         delivery.sources = schedule.sources.clone();
+        if !failed_sources.is_empty() {
+            delivery.metadata = serde_json::json!({ "failed_sources": failed_sources });
+        }
         delivery.input_tokens = input_tokens;
         delivery.output_tokens = output_tokens;
         delivery.total_tokens = total_tokens;
@@ -391,6 +421,23 @@ pub async fn spawn_scheduled_run(
     });
 }
 
+/// Attach the sources that failed to fetch to the run's record.  The turn
+/// is already running, so if it has finished and persisted its record in
+/// the meantime the updated record is persisted again (the latest wins).
+fn record_failed_sources(
+    run_store: &crate::runtime::runs::RunStore,
+    run_id: Uuid,
+    failed: &[crate::runtime::digest::SourceFailure],
+) {
+    if failed.is_empty() {
+        return;
+    }
+    run_store.update(&run_id, |r| r.failed_sources = failed.to_vec());
+    if let Some(run) = run_store.get(&run_id).filter(|r| r.status.is_terminal()) {
+        run_store.persist(&run);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -488,4 +535,29 @@ mod tests {
         assert!(guard.try_acquire(&id2, 1).await, "different schedule should be independent");
         assert!(!guard.try_acquire(&id1, 1).await, "same schedule still at limit");
     }
+
+    #[test]
+    fn failed_sources_are_recorded_on_the_run() {
+        use crate::runtime::runs::{Run, RunStatus, RunStore};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = RunStore::new(dir.path());
+        let failed = vec![crate::runtime::digest::SourceFailure {
+            url: "https://down.example/feed".into(),
+            error: "HTTP 503".into(),
+        }];
+
+        // The turn already finished and persisted before the sources landed.
+        let mut run = Run::new("schedule:x".into(), "sched-x".into(), "digest");
+        run.finish(RunStatus::Completed);
+        store.persist(&run);
+        let run_id = store.insert(run);
+
+        record_failed_sources(&store, run_id, &failed);
+
+        assert_eq!(store.get(&run_id).unwrap().failed_sources, failed);
+        let persisted = store.persisted_runs();
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted[0].failed_sources, failed);
+    }
 }
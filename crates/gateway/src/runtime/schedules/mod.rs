@@ -18,7 +18,11 @@ pub mod validation;
 pub use cron::{cron_matches, cron_next, cron_next_n, cron_next_n_tz, cron_next_tz, parse_tz};
 pub use model::{
     cooldown_minutes, DeliveryTarget, DigestMode, FetchConfig, MissedPolicy, Schedule,
-    ScheduleEvent, ScheduleStatus, ScheduleView, SourceState,
+    ScheduleEvent, ScheduleStatus, ScheduleView, SourceState, TriggerParam, TriggerParamType,
+    MAX_FETCH_RETRIES,
 };
 pub use store::ScheduleStore;
-pub use validation::{validate_cron, validate_timezone, validate_url};
+pub use validation::{
+    validate_cron, validate_timezone, validate_trigger_params, validate_trigger_payload,
+    validate_url,
+};
@@ -35,6 +35,9 @@ pub enum DigestMode {
     Full,
     /// Only include sources whose content changed since last run.
     ChangesOnly,
+    /// Group each source's items under its own header with an item
+    /// count; sources that yielded nothing are listed at the end.
+    PerSourceSections,
 }
 
 
@@ -50,6 +53,32 @@ pub struct FetchConfig {
     /// Maximum response body size in bytes (0 = unlimited).
     #[serde(default)]
     pub max_size_bytes: u64,
+    /// Per-source attempt timeout in seconds; overrides `timeout_ms`
+    /// when set.
+    #[serde(default)]
+    pub timeout_sec: Option<u64>,
+    /// Extra attempts after a source fails to fetch (capped at
+    /// [`MAX_FETCH_RETRIES`]).
+    #[serde(default)]
+    pub retries: u32,
+}
+
+/// Upper bound on [`FetchConfig::retries`].
+pub const MAX_FETCH_RETRIES: u32 = 5;
+
+impl FetchConfig {
+    /// Time allowed for one fetch attempt of one source.
+    pub fn attempt_timeout(&self) -> std::time::Duration {
+        match self.timeout_sec {
+            Some(secs) => std::time::Duration::from_secs(secs),
+            None => std::time::Duration::from_millis(self.timeout_ms),
+        }
+    }
+
+    /// Total attempts per source: the first try plus capped retries.
+    pub fn attempts(&self) -> u32 {
+        self.retries.min(MAX_FETCH_RETRIES) + 1
+    }
 }
 
 fn default_fetch_timeout_ms() -> u64 {
@@ -66,10 +95,35 @@ impl Default for FetchConfig {
             timeout_ms: default_fetch_timeout_ms(),
             user_agent: default_user_agent(),
             max_size_bytes: 0,
+            timeout_sec: None,
+            retries: 0,
         }
     }
 }
 
+/// Value type of a declared webhook trigger parameter.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerParamType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+}
+
+/// A parameter a webhook trigger body may carry.  Its value is
+/// substituted into the prompt template as `{{trigger.<name>}}`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TriggerParam {
+    pub name: String,
+    #[serde(default, rename = "type")]
+    pub kind: TriggerParamType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
 /// Per-source state tracking for change detection.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SourceState {
@@ -184,6 +238,10 @@ pub struct Schedule {
     /// the `X-Hub-Signature-256` header against this secret.
     #[serde(default)]
     pub webhook_secret: Option<String>,
+    /// Parameters the trigger body must match.  When empty the body is
+    /// not inspected.
+    #[serde(default)]
+    pub trigger_params: Vec<TriggerParam>,
 
     // ── Usage tracking ───────────────────────────────────────────────
     /// Cumulative input tokens across all runs.
@@ -300,6 +358,7 @@ mod tests {
             cooldown_until: None,
             routing_profile: None,
             webhook_secret: None,
+            trigger_params: Vec::new(),
             total_input_tokens: 0,
             total_output_tokens: 0,
             total_runs: 0,
@@ -380,7 +439,11 @@ mod tests {
 
     #[test]
     fn digest_mode_serde_roundtrip() {
-        let modes = [DigestMode::Full, DigestMode::ChangesOnly];
+        let modes = [
+            DigestMode::Full,
+            DigestMode::ChangesOnly,
+            DigestMode::PerSourceSections,
+        ];
         for m in &modes {
             let json = serde_json::to_string(m).unwrap();
             let back: DigestMode = serde_json::from_str(&json).unwrap();
@@ -1,60 +1,94 @@
 //! ScheduleStore — persistent schedule storage with event broadcasting.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::sync::Arc;
 
 use chrono::Utc;
+use sa_domain::persistence::{
+    tag_unversioned, FsBackend, LoadedRecord, PersistenceBackend, RecordSchema,
+};
 use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 use super::cron::{cron_next_tz, parse_tz};
 use super::model::{Schedule, ScheduleEvent, SourceState};
 
+/// Backend key of the schedule list.
+const SCHEDULES_KEY: &str = "schedules.json";
+
+/// Schema of persisted [`Schedule`] records.
+const SCHEDULE_SCHEMA: RecordSchema = RecordSchema {
+    current: 2,
+    migrations: &[tag_unversioned],
+};
+
 pub struct ScheduleStore {
     inner: RwLock<HashMap<Uuid, Schedule>>,
-    persist_path: PathBuf,
+    /// Schedules written by a newer release, kept verbatim and re-saved.
+    future: Vec<serde_json::Value>,
+    backend: Arc<dyn PersistenceBackend>,
     event_tx: broadcast::Sender<ScheduleEvent>,
 }
 
 impl ScheduleStore {
+    /// Load schedules from `state_path/schedules.json`.
     pub fn new(state_path: &std::path::Path) -> Self {
-        let persist_path = state_path.join("schedules.json");
+        Self::with_backend(Arc::new(FsBackend::new(state_path)))
+    }
+
+    /// Load schedules from, and persist them through, `backend`.
+    pub fn with_backend(backend: Arc<dyn PersistenceBackend>) -> Self {
         let (event_tx, _) = broadcast::channel(64);
 
         let mut store = Self {
             inner: RwLock::new(HashMap::new()),
-            persist_path,
+            future: Vec::new(),
+            backend,
             event_tx,
         };
         store.load();
         store
     }
 
+    /// Load schedules, upgrading older records and setting aside ones
+    /// written by a newer release.
     fn load(&mut self) {
-        if let Ok(data) = std::fs::read_to_string(&self.persist_path) {
-            if let Ok(schedules) = serde_json::from_str::<Vec<Schedule>>(&data) {
+        if let Ok(Some(data)) = self.backend.load(SCHEDULES_KEY) {
+            if let Ok(records) = serde_json::from_slice::<Vec<serde_json::Value>>(&data) {
                 let mut map = HashMap::new();
-                for s in schedules {
-                    map.insert(s.id, s);
+                for record in records {
+                    match SCHEDULE_SCHEMA.load::<Schedule>(record) {
+                        LoadedRecord::Current(s) => {
+                            map.insert(s.id, s);
+                        }
+                        LoadedRecord::Future(raw) => self.future.push(raw),
+                        LoadedRecord::Invalid(e) => {
+                            tracing::warn!(error = %e, "dropping unreadable schedule");
+                        }
+                    }
                 }
                 let count = map.len();
                 self.inner = RwLock::new(map);
-                tracing::info!(count, "loaded schedules from disk");
+                tracing::info!(count, "loaded schedules");
+                if !self.future.is_empty() {
+                    tracing::warn!(
+                        count = self.future.len(),
+                        "schedules from a newer release preserved unread"
+                    );
+                }
             }
         }
     }
 
     async fn persist(&self) {
         let map = self.inner.read().await;
-        let schedules: Vec<&Schedule> = map.values().collect();
-        if let Ok(json) = serde_json::to_string_pretty(&schedules) {
-            let path = self.persist_path.clone();
+        let mut records = self.future.clone();
+        records.extend(map.values().filter_map(|s| SCHEDULE_SCHEMA.stamp(s).ok()));
+        if let Ok(json) = serde_json::to_string_pretty(&records) {
+            let backend = self.backend.clone();
             // Spawn blocking to avoid blocking the Tokio executor.
             let _ = tokio::task::spawn_blocking(move || {
-                if let Some(parent) = path.parent() {
-                    let _ = std::fs::create_dir_all(parent);
-                }
-                if let Err(e) = std::fs::write(&path, json) {
+                if let Err(e) = backend.save(SCHEDULES_KEY, json.as_bytes()) {
                     tracing::warn!(error = %e, "failed to persist schedules");
                 }
             })
@@ -249,3 +283,112 @@ impl ScheduleStore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::schedules::model::{DigestMode, FetchConfig, MissedPolicy};
+    use sa_domain::persistence::MemoryBackend;
+
+    fn schedule(name: &str) -> Schedule {
+        Schedule {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            cron: "0 * * * *".into(),
+            timezone: "UTC".into(),
+            enabled: true,
+            agent_id: String::new(),
+            prompt_template: String::new(),
+            sources: vec![],
+            delivery_targets: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            last_run_id: None,
+            last_run_at: None,
+            next_run_at: None,
+            missed_policy: MissedPolicy::default(),
+            max_concurrency: 1,
+            timeout_ms: None,
+            model: None,
+            digest_mode: DigestMode::default(),
+            fetch_config: FetchConfig::default(),
+            max_catchup_runs: 5,
+            source_states: HashMap::new(),
+            last_error: None,
+            last_error_at: None,
+            consecutive_failures: 0,
+            cooldown_until: None,
+            routing_profile: None,
+            webhook_secret: None,
+            trigger_params: Vec::new(),
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            total_runs: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn memory_backend_matches_fs_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let backends: [Arc<dyn PersistenceBackend>; 2] = [
+            Arc::new(FsBackend::new(dir.path())),
+            Arc::new(MemoryBackend::new()),
+        ];
+        for backend in backends {
+            let store = ScheduleStore::with_backend(backend.clone());
+            let kept = store.insert(schedule("digest")).await;
+            let dropped = store.insert(schedule("alerts")).await;
+            store.record_failure(&kept.id, "boom").await;
+            store.add_usage(&kept.id, 100, 20).await;
+            assert!(store.delete(&dropped.id).await);
+
+            let reloaded = ScheduleStore::with_backend(backend);
+            let all = reloaded.list().await;
+            assert_eq!(all.len(), 1);
+            let got = &all[0];
+            assert_eq!(got.id, kept.id);
+            assert!(got.next_run_at.is_some());
+            assert_eq!(got.consecutive_failures, 1);
+            assert_eq!(got.last_error.as_deref(), Some("boom"));
+            assert_eq!(got.total_input_tokens, 100);
+            assert_eq!(got.total_runs, 1);
+            assert!(reloaded.name_exists("DIGEST", None).await);
+        }
+        assert!(dir.path().join("schedules.json").is_file());
+    }
+
+    #[tokio::test]
+    async fn v1_schedules_upgrade_and_future_schedules_round_trip() {
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        // v1: untagged, written before records carried a schema_version.
+        let legacy = schedule("legacy");
+        let mut v1 = serde_json::to_value(&legacy).unwrap();
+        v1.as_object_mut().unwrap().remove("schema_version");
+        let future = serde_json::json!({
+            "schema_version": 7,
+            "id": Uuid::new_v4(),
+            "trigger": { "kind": "event" }
+        });
+        let records = serde_json::json!([v1, future]);
+        backend
+            .save(SCHEDULES_KEY, records.to_string().as_bytes())
+            .unwrap();
+
+        let store = ScheduleStore::with_backend(backend.clone());
+        let loaded = store.get(&legacy.id).await.unwrap();
+        assert_eq!(loaded.name, "legacy");
+        assert_eq!(store.list().await.len(), 1);
+
+        // Any write re-saves both: the legacy one stamped, the future one as-is.
+        store.reset_errors(&legacy.id).await;
+        let saved: Vec<serde_json::Value> =
+            serde_json::from_slice(&backend.load(SCHEDULES_KEY).unwrap().unwrap()).unwrap();
+        assert_eq!(saved.len(), 2);
+        assert!(saved.contains(&future));
+        let upgraded = saved.iter().find(|v| v["name"] == "legacy").unwrap();
+        assert_eq!(upgraded["schema_version"], 2);
+
+        let reloaded = ScheduleStore::with_backend(backend);
+        assert_eq!(reloaded.get(&legacy.id).await.unwrap().cron, legacy.cron);
+    }
+}
@@ -1,4 +1,7 @@
-//! Input validation for schedule fields (URLs, cron expressions, timezones).
+//! Input validation for schedule fields (URLs, cron expressions, timezones)
+//! and webhook trigger payloads.
+
+use super::model::{TriggerParam, TriggerParamType};
 
 /// Validate a URL for safety: must be http(s) and must not target private/internal networks.
 ///
@@ -116,6 +119,88 @@ pub fn validate_timezone(tz: &str) -> Result<(), String> {
     }
 }
 
+/// Validate declared trigger parameters: identifier-like, unique names.
+pub fn validate_trigger_params(params: &[TriggerParam]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for p in params {
+        let valid = !p.name.is_empty()
+            && p.name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(format!(
+                "invalid trigger parameter name '{}' (use letters, digits, '_' or '-')",
+                p.name
+            ));
+        }
+        if !seen.insert(p.name.as_str()) {
+            return Err(format!("duplicate trigger parameter '{}'", p.name));
+        }
+    }
+    Ok(())
+}
+
+/// Check a webhook trigger body against the declared parameters and
+/// return `(name, value)` for every parameter, rendered as text for the
+/// prompt template (absent optional parameters render empty).
+///
+/// With no declared parameters the body is ignored.  Otherwise it must
+/// be a JSON object (an empty body counts as `{}`) with every required
+/// parameter, values of the declared types, and no undeclared keys.
+pub fn validate_trigger_payload(
+    params: &[TriggerParam],
+    body: &[u8],
+) -> Result<Vec<(String, String)>, String> {
+    if params.is_empty() {
+        return Ok(Vec::new());
+    }
+    let payload: serde_json::Map<String, serde_json::Value> =
+        if body.iter().all(u8::is_ascii_whitespace) {
+            serde_json::Map::new()
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(serde_json::Value::Object(map)) => map,
+                Ok(_) => return Err("trigger body must be a JSON object".into()),
+                Err(e) => return Err(format!("trigger body is not valid JSON: {e}")),
+            }
+        };
+
+    if let Some(unknown) = payload
+        .keys()
+        .find(|k| !params.iter().any(|p| &p.name == *k))
+    {
+        return Err(format!("unknown trigger parameter '{unknown}'"));
+    }
+
+    let mut values = Vec::with_capacity(params.len());
+    for p in params {
+        let value = match payload.get(&p.name) {
+            None | Some(serde_json::Value::Null) if p.required => {
+                return Err(format!("missing required trigger parameter '{}'", p.name));
+            }
+            None | Some(serde_json::Value::Null) => String::new(),
+            Some(v) => match (p.kind, v) {
+                (TriggerParamType::String, serde_json::Value::String(s)) => s.clone(),
+                (TriggerParamType::Number, serde_json::Value::Number(n)) => n.to_string(),
+                (TriggerParamType::Boolean, serde_json::Value::Bool(b)) => b.to_string(),
+                (kind, _) => {
+                    return Err(format!(
+                        "trigger parameter '{}' must be a {}",
+                        p.name,
+                        match kind {
+                            TriggerParamType::String => "string",
+                            TriggerParamType::Number => "number",
+                            TriggerParamType::Boolean => "boolean",
+                        }
+                    ));
+                }
+            },
+        };
+        values.push((p.name.clone(), value));
+    }
+    Ok(values)
+}
+
 /// Validate a 5-field cron expression. Returns `Ok(())` or an error message.
 pub fn validate_cron(cron: &str) -> Result<(), String> {
     let fields: Vec<&str> = cron.split_whitespace().collect();
@@ -279,4 +364,72 @@ mod tests {
         assert!(validate_timezone("GMT+5").is_err());
         assert!(validate_timezone("FakeZone").is_err());
     }
+
+    fn param(name: &str, kind: TriggerParamType, required: bool) -> TriggerParam {
+        TriggerParam {
+            name: name.into(),
+            kind,
+            required,
+            description: None,
+        }
+    }
+
+    fn trigger_params() -> Vec<TriggerParam> {
+        vec![
+            param("repo", TriggerParamType::String, true),
+            param("pr", TriggerParamType::Number, true),
+            param("draft", TriggerParamType::Boolean, false),
+        ]
+    }
+
+    #[test]
+    fn valid_trigger_payload_yields_values() {
+        let values =
+            validate_trigger_payload(&trigger_params(), br#"{"repo": "acme/api", "pr": 42}"#)
+                .unwrap();
+        assert_eq!(
+            values,
+            [
+                ("repo".to_string(), "acme/api".to_string()),
+                ("pr".to_string(), "42".to_string()),
+                ("draft".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_trigger_payloads_are_rejected() {
+        let params = trigger_params();
+        let cases: [(&[u8], &str); 6] = [
+            (br#"{"repo": "a"}"#, "missing required"),
+            (br#"{"repo": "a", "pr": "42"}"#, "'pr' must be a number"),
+            (br#"{"repo": "a", "pr": 1, "x": 0}"#, "unknown"),
+            (br#"["acme/api", 42]"#, "must be a JSON object"),
+            (b"repo=acme", "not valid JSON"),
+            (b"", "missing required"),
+        ];
+        for (body, why) in cases {
+            let err = validate_trigger_payload(&params, body).unwrap_err();
+            assert!(err.contains(why), "{err:?} should mention {why:?}");
+        }
+    }
+
+    #[test]
+    fn undeclared_trigger_params_ignore_the_body() {
+        let values = validate_trigger_payload(&[], b"not json at all").unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn trigger_param_names_are_validated() {
+        assert!(validate_trigger_params(&trigger_params()).is_ok());
+        let dup = vec![
+            param("repo", TriggerParamType::String, true),
+            param("repo", TriggerParamType::Number, false),
+        ];
+        let err = validate_trigger_params(&dup).unwrap_err();
+        assert!(err.contains("duplicate"));
+        let bad = vec![param("has space", TriggerParamType::String, false)];
+        assert!(validate_trigger_params(&bad).is_err());
+    }
 }
@@ -1,21 +1,88 @@
 //! Per-session concurrency control.
 //!
-//! Ensures only one turn runs per session at a time. A second message
-//! arriving while a turn is in-flight will wait (queue depth = 1) or
-//! be rejected with a "busy" error.
+//! Ensures only one turn runs per session at a time.  Messages arriving
+//! while a turn is in-flight wait in a FIFO queue (tokio's semaphore is
+//! fair), so they run in submission order.  The queue is bounded by
+//! `max_queue_depth`; arrivals beyond it are rejected with [`SessionBusy`]
+//! (HTTP 429) so a flood on one session can't buffer without limit.
+//!
+//! The lock is an RAII [`SessionPermit`]: it is released when dropped,
+//! including while unwinding from a panicking turn.  As an opt-in last line
+//! of defence against a leaked permit, `prune_idle` can force-release any
+//! lock held longer than `max_hold`.  It is off by default: the holder may
+//! be a slow but live turn, and releasing its lock would let the next turn
+//! run on the session concurrently.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
+use sa_domain::config::SessionLockConfig;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
+/// Default number of turns allowed to wait behind the running one.
+pub const DEFAULT_MAX_QUEUE_DEPTH: usize = 4;
+
+/// Lock state for one session.
+struct SessionSlot {
+    sem: Arc<Semaphore>,
+    /// Turns currently waiting for `sem`.
+    waiting: AtomicUsize,
+    /// When the current holder acquired the lock (`None` = free).
+    held_since: Mutex<Option<Instant>>,
+}
+
+impl SessionSlot {
+    fn new() -> Self {
+        Self {
+            sem: Arc::new(Semaphore::new(1)),
+            waiting: AtomicUsize::new(0),
+            held_since: Mutex::new(None),
+        }
+    }
+
+    fn grant(self: &Arc<Self>, permit: OwnedSemaphorePermit) -> SessionPermit {
+        *self.held_since.lock() = Some(Instant::now());
+        SessionPermit {
+            slot: self.clone(),
+            _permit: permit,
+        }
+    }
+}
+
+/// Exclusive right to run a turn on one session.  Releases the lock on
+/// drop — hold it for the duration of the turn.
+pub struct SessionPermit {
+    slot: Arc<SessionSlot>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for SessionPermit {
+    fn drop(&mut self) {
+        *self.slot.held_since.lock() = None;
+    }
+}
+
+/// Decrements the waiting count when a queued acquire finishes or is
+/// cancelled (e.g. the client disconnected while queued).
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Manages per-session run locks.
 ///
-/// Each session key maps to a `Semaphore(1)`.  Acquiring the permit
-/// ensures exclusive access for one turn at a time.
+/// Each session key maps to a `Semaphore(1)` plus a waiter count.
+/// Acquiring the permit ensures exclusive access for one turn at a time.
 pub struct SessionLockMap {
-    locks: Mutex<HashMap<String, Arc<Semaphore>>>,
+    locks: Mutex<HashMap<String, Arc<SessionSlot>>>,
+    max_queue_depth: usize,
+    max_hold: Option<Duration>,
 }
 
 impl Default for SessionLockMap {
@@ -28,41 +95,74 @@ impl SessionLockMap {
     pub fn new() -> Self {
         Self {
             locks: Mutex::new(HashMap::new()),
+            max_queue_depth: DEFAULT_MAX_QUEUE_DEPTH,
+            max_hold: None,
         }
     }
 
+    /// A lock map configured from `[sessions.locks]`.
+    pub fn from_config(config: &SessionLockConfig) -> Self {
+        Self::new()
+            .with_max_queue_depth(config.max_queue_depth)
+            .with_max_hold(
+                Some(config.max_hold_secs)
+                    .filter(|&s| s > 0)
+                    .map(Duration::from_secs),
+            )
+    }
+
+    /// Set how many turns may wait behind the running one.
+    pub fn with_max_queue_depth(mut self, max_queue_depth: usize) -> Self {
+        self.max_queue_depth = max_queue_depth;
+        self
+    }
+
+    /// Force-release locks held longer than `max_hold` on the next
+    /// `prune_idle`.  `None` (the default) never force-releases.
+    pub fn with_max_hold(mut self, max_hold: Option<Duration>) -> Self {
+        self.max_hold = max_hold;
+        self
+    }
+
     /// Acquire the run lock for a session.
     ///
     /// Returns `Ok(permit)` when the lock is acquired (hold it for the
-    /// duration of the turn — it auto-releases on drop).
+    /// duration of the turn — it auto-releases on drop).  Waiters are
+    /// served in arrival order.
     ///
-    /// Returns `Err(())` if the session already has a queued waiter
-    /// (prevents unbounded queue growth).
-    pub async fn acquire(&self, session_key: &str) -> Result<OwnedSemaphorePermit, SessionBusy> {
-        let sem = {
+    /// Returns `Err(SessionBusy)` if `max_queue_depth` turns are already
+    /// waiting on this session.
+    pub async fn acquire(&self, session_key: &str) -> Result<SessionPermit, SessionBusy> {
+        let slot = {
             let mut locks = self.locks.lock();
             locks
                 .entry(session_key.to_owned())
-                .or_insert_with(|| Arc::new(Semaphore::new(1)))
+                .or_insert_with(|| Arc::new(SessionSlot::new()))
                 .clone()
         };
 
-        // Try to acquire without waiting first.
-        match sem.clone().try_acquire_owned() {
-            Ok(permit) => return Ok(permit),
-            Err(_) => {
-                // Someone is running. Check if there's already a waiter.
-                if sem.available_permits() == 0 {
-                    // One turn running, allow one waiter.
-                    // (Semaphore(1) means at most 1 permit = 1 runner + 1 waiter.)
-                }
-            }
+        // Fast path: nobody running.  A released permit goes straight to
+        // the oldest waiter, so this cannot jump the queue.
+        if let Ok(permit) = slot.sem.clone().try_acquire_owned() {
+            return Ok(slot.grant(permit));
+        }
+
+        if slot.waiting.fetch_add(1, Ordering::SeqCst) >= self.max_queue_depth {
+            slot.waiting.fetch_sub(1, Ordering::SeqCst);
+            return Err(SessionBusy);
         }
+        let _queued = QueuedGuard(&slot.waiting);
 
-        // Wait for the permit (blocks until the current turn finishes).
-        sem.acquire_owned()
+        // Wait for the permit (blocks until the turns ahead finish).  The
+        // semaphore is closed if the lock gets force-released; waiters
+        // queued behind the stuck turn are then rejected as busy.
+        let permit = slot
+            .sem
+            .clone()
+            .acquire_owned()
             .await
-            .map_err(|_| SessionBusy)
+            .map_err(|_| SessionBusy)?;
+        Ok(slot.grant(permit))
     }
 
     /// Number of tracked sessions (for monitoring).
@@ -70,26 +170,83 @@ impl SessionLockMap {
         self.locks.lock().len()
     }
 
-    /// Remove orphaned session locks — entries where the only remaining
-    /// `Arc` reference is the map itself (strong_count == 1) AND the
+    /// Number of turns waiting on `session_key` (for monitoring).
+    pub fn queue_depth(&self, session_key: &str) -> usize {
+        self.locks
+            .lock()
+            .get(session_key)
+            .map_or(0, |slot| slot.waiting.load(Ordering::SeqCst))
+    }
+
+    /// Number of sessions with a turn running or queued.
+    pub fn active_count(&self) -> usize {
+        self.locks
+            .lock()
+            .values()
+            .filter(|slot| {
+                slot.sem.available_permits() == 0 || slot.waiting.load(Ordering::SeqCst) > 0
+            })
+            .count()
+    }
+
+    /// Wait until no turn is running or queued, polling until `timeout`.
+    /// Returns `false` if turns were still active when it expired.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.active_count() == 0 {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+    }
+
+    /// Remove orphaned session locks — entries that nobody outside the map
+    /// references (no queued acquire, no outstanding permit) AND whose
     /// semaphore is not currently held (available_permits > 0).
+    ///
+    /// Locks held longer than `max_hold` are force-released: the entry is
+    /// dropped (the next turn gets a fresh lock) and its semaphore closed
+    /// so queued waiters fail fast instead of waiting forever.
     pub fn prune_idle(&self) {
         let mut locks = self.locks.lock();
-        locks.retain(|_, sem| {
+        if let Some(max_hold) = self.max_hold {
+            locks.retain(|key, slot| {
+                let held_for = slot.held_since.lock().map(|t| t.elapsed());
+                match held_for {
+                    Some(held_for) if held_for > max_hold => {
+                        tracing::warn!(
+                            session_key = %key,
+                            held_secs = held_for.as_secs(),
+                            "force-releasing session lock held past max_hold"
+                        );
+                        slot.sem.close();
+                        false
+                    }
+                    _ => true,
+                }
+            });
+        }
+        locks.retain(|_, slot| {
             // Keep if someone outside the map still holds a reference,
             // or if the permit is currently acquired (turn in progress).
-            Arc::strong_count(sem) > 1 || sem.available_permits() == 0
+            Arc::strong_count(slot) > 1
+                || Arc::strong_count(&slot.sem) > 1
+                || slot.sem.available_permits() == 0
         });
     }
 }
 
-/// Error returned when a session is busy (turn already in progress + queued).
+/// Error returned when a session is busy (turn in progress + queue full).
 #[derive(Debug)]
 pub struct SessionBusy;
 
 impl std::fmt::Display for SessionBusy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "session is busy — a turn is already in progress")
+        write!(f, "session is busy — too many turns queued")
     }
 }
 
@@ -147,4 +304,167 @@ mod tests {
         let result = handle.await.unwrap();
         assert_eq!(result, 42);
     }
+
+    #[tokio::test]
+    async fn same_session_runs_in_submission_order() {
+        let map = Arc::new(SessionLockMap::new());
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let p1 = map.acquire("s1").await.unwrap();
+
+        let mut handles = Vec::new();
+        for i in 0..4 {
+            let map = map.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                let _p = map.acquire("s1").await.unwrap();
+                order.lock().push(i);
+                // Yield while holding the lock so a racing waiter would
+                // get a chance to jump ahead.
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }));
+            // Let each waiter enqueue before submitting the next.
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(map.queue_depth("s1"), 4);
+
+        drop(p1);
+        for h in handles {
+            h.await.unwrap();
+        }
+        assert_eq!(*order.lock(), vec![0, 1, 2, 3]);
+        assert_eq!(map.queue_depth("s1"), 0);
+    }
+
+    #[tokio::test]
+    async fn wait_idle_waits_for_running_and_queued_turns() {
+        let map = Arc::new(SessionLockMap::new());
+        assert!(map.wait_idle(Duration::ZERO).await);
+
+        let p1 = map.acquire("s1").await.unwrap();
+        let map2 = map.clone();
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let done2 = done.clone();
+        tokio::spawn(async move {
+            let _p = map2.acquire("s1").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            done2.store(true, Ordering::SeqCst);
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(map.active_count(), 1);
+        assert!(!map.wait_idle(Duration::from_millis(30)).await);
+
+        drop(p1);
+        assert!(map.wait_idle(Duration::from_secs(5)).await);
+        assert!(done.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn exceeding_queue_depth_is_rejected() {
+        let map = Arc::new(SessionLockMap::new().with_max_queue_depth(1));
+
+        let p1 = map.acquire("s1").await.unwrap();
+
+        let map2 = map.clone();
+        let waiter = tokio::spawn(async move { map2.acquire("s1").await.is_ok() });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        // One running + one queued: the next arrival is rejected...
+        assert!(map.acquire("s1").await.is_err());
+        // ...while other sessions are unaffected.
+        assert!(map.acquire("s2").await.is_ok());
+
+        drop(p1);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_frees_its_queue_slot() {
+        let map = Arc::new(SessionLockMap::new().with_max_queue_depth(1));
+        let p1 = map.acquire("s1").await.unwrap();
+
+        let map2 = map.clone();
+        let waiter = tokio::spawn(async move {
+            let _ = map2.acquire("s1").await;
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(map.queue_depth("s1"), 1);
+
+        waiter.abort();
+        let _ = waiter.await;
+        assert_eq!(map.queue_depth("s1"), 0);
+
+        drop(p1);
+        assert!(map.acquire("s1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn panicking_turn_does_not_deadlock_session() {
+        let map = Arc::new(SessionLockMap::new());
+
+        let map2 = map.clone();
+        let turn = tokio::spawn(async move {
+            let _p = map2.acquire("s1").await.unwrap();
+            panic!("turn blew up");
+        });
+        assert!(turn.await.unwrap_err().is_panic());
+
+        let next = tokio::time::timeout(std::time::Duration::from_secs(1), map.acquire("s1"))
+            .await
+            .expect("lock should be free after the panic");
+        assert!(next.is_ok());
+    }
+
+    #[tokio::test]
+    async fn prune_force_releases_lock_held_past_max_hold() {
+        let map = Arc::new(SessionLockMap::new().with_max_hold(Some(Duration::ZERO)));
+
+        // Simulate a leaked permit.
+        let leaked = map.acquire("s1").await.unwrap();
+        let map2 = map.clone();
+        let waiter = tokio::spawn(async move { map2.acquire("s1").await.is_ok() });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        map.prune_idle();
+
+        // The stuck waiter is rejected and new turns get a fresh lock.
+        assert!(!waiter.await.unwrap());
+        assert!(map.acquire("s1").await.is_ok());
+        drop(leaked);
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_locks_within_max_hold() {
+        let map = SessionLockMap::new().with_max_hold(Some(Duration::from_secs(3600)));
+        let _p = map.acquire("s1").await.unwrap();
+        map.prune_idle();
+        assert_eq!(map.session_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn long_running_live_turn_keeps_its_lock_by_default() {
+        let map = Arc::new(SessionLockMap::from_config(&SessionLockConfig::default()));
+        let running = map.acquire("s1").await.unwrap();
+        // Pretend the turn has been running for two hours.
+        if let Some(start) = Instant::now().checked_sub(Duration::from_secs(7200)) {
+            *map.locks.lock()["s1"].held_since.lock() = Some(start);
+        }
+
+        let map2 = map.clone();
+        let waiter = tokio::spawn(async move { map2.acquire("s1").await.is_ok() });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        map.prune_idle();
+
+        // No second turn gets in while the first is still running...
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), map.acquire("s1"))
+                .await
+                .is_err()
+        );
+        assert_eq!(map.queue_depth("s1"), 1);
+        // ...and the queued turn runs once it finishes.
+        drop(running);
+        assert!(waiter.await.unwrap());
+    }
 }
@@ -0,0 +1,163 @@
+//! Graceful shutdown: drain in-flight work, then flush stores.
+//!
+//! Runs after the HTTP server has stopped accepting connections.  Turns
+//! still holding a session lock (background schedules, inbound events,
+//! tasks) get up to the grace period to finish, then node tool calls are
+//! drained and nodes disconnected, and finally every buffered store is
+//! flushed so nothing written during the drain is lost.
+
+use std::time::{Duration, Instant};
+
+use sa_sessions::SessionStore;
+
+use crate::nodes::registry::NodeRegistry;
+use crate::nodes::router::ToolRouter;
+use crate::runtime::deliveries::DeliveryStore;
+use crate::runtime::session_lock::SessionLockMap;
+use crate::state::AppState;
+
+/// What the drain managed to do before the grace period ran out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Every running or queued turn finished within the grace period.
+    pub turns_drained: bool,
+    /// Node tool calls still pending at the deadline, failed so their
+    /// callers unblock.
+    pub node_requests_failed: usize,
+    pub nodes_disconnected: usize,
+}
+
+/// Drain turns and nodes, then flush all stores.  `grace` bounds the
+/// whole drain; flushing always runs.
+pub async fn drain_and_flush(state: &AppState, grace: Duration) -> ShutdownReport {
+    shutdown(
+        &state.session_locks,
+        &state.nodes,
+        &state.tool_router,
+        &state.sessions,
+        &state.delivery_store,
+        grace,
+    )
+    .await
+}
+
+async fn shutdown(
+    session_locks: &SessionLockMap,
+    nodes: &NodeRegistry,
+    tool_router: &ToolRouter,
+    sessions: &SessionStore,
+    delivery_store: &DeliveryStore,
+    grace: Duration,
+) -> ShutdownReport {
+    let deadline = Instant::now() + grace;
+    let mut report = ShutdownReport::default();
+
+    // ── In-flight turns ─────────────────────────────────────────────
+    let active = session_locks.active_count();
+    if active > 0 {
+        tracing::info!(active, "waiting for in-flight turns to finish");
+    }
+    report.turns_drained = session_locks.wait_idle(grace).await;
+    if !report.turns_drained {
+        tracing::warn!(
+            still_active = session_locks.active_count(),
+            "grace period expired with turns still running"
+        );
+    }
+
+    // ── Nodes ───────────────────────────────────────────────────────
+    while tool_router.pending_count() > 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    for node in nodes.list().iter() {
+        report.node_requests_failed += tool_router.fail_pending_for_node(&node.node_id);
+        nodes.remove(&node.node_id);
+        report.nodes_disconnected += 1;
+    }
+
+    // ── Stores ──────────────────────────────────────────────────────
+    if let Err(e) = sessions.flush().await {
+        tracing::warn!(error = %e, "session store flush on shutdown failed");
+    }
+    delivery_store.flush_if_dirty().await;
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use sa_domain::persistence::{MemoryBackend, PersistenceBackend};
+    use sa_sessions::store::SessionOrigin;
+
+    #[tokio::test]
+    async fn shutdown_completes_in_flight_turn_and_flushes_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let sessions = Arc::new(SessionStore::with_backend(dir.path(), backend.clone()).unwrap());
+        let deliveries = DeliveryStore::with_backend(backend.clone());
+        let locks = Arc::new(SessionLockMap::new());
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = ToolRouter::new(nodes.clone(), 30);
+
+        // A turn is mid-flight when the signal arrives: it holds the
+        // session lock and only records its session once it finishes.
+        let permit = locks.acquire("agent:main:dm:7").await.unwrap();
+        let finished = Arc::new(AtomicBool::new(false));
+        let turn = {
+            let sessions = sessions.clone();
+            let finished = finished.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                sessions.resolve_or_create("agent:main:dm:7", SessionOrigin::default());
+                finished.store(true, Ordering::SeqCst);
+                drop(permit);
+            })
+        };
+
+        let report = shutdown(
+            &locks,
+            &nodes,
+            &router,
+            &sessions,
+            &deliveries,
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(report.turns_drained);
+        assert!(finished.load(Ordering::SeqCst));
+        turn.await.unwrap();
+
+        let table = backend.load("sessions/sessions.json").unwrap().unwrap();
+        assert!(String::from_utf8(table)
+            .unwrap()
+            .contains("agent:main:dm:7"));
+    }
+
+    #[tokio::test]
+    async fn grace_period_bounds_a_stuck_turn() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let sessions = SessionStore::with_backend(dir.path(), backend.clone()).unwrap();
+        let deliveries = DeliveryStore::with_backend(backend);
+        let locks = SessionLockMap::new();
+        let nodes = Arc::new(NodeRegistry::new());
+        let router = ToolRouter::new(nodes.clone(), 30);
+
+        let _stuck = locks.acquire("agent:main:dm:8").await.unwrap();
+        let report = shutdown(
+            &locks,
+            &nodes,
+            &router,
+            &sessions,
+            &deliveries,
+            Duration::from_millis(50),
+        )
+        .await;
+        assert!(!report.turns_drained);
+    }
+}
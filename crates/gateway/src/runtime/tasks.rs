@@ -154,7 +154,7 @@ impl TaskStore {
 
         // Collect matching tasks sorted by created_at descending (newest first).
         let mut matching: Vec<&Task> = tasks.values().filter(filter).collect();
-        matching.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        matching.sort_by_key(|t| std::cmp::Reverse(t.created_at));
 
         let total = matching.len();
         let page: Vec<Task> = matching
@@ -0,0 +1,374 @@
+//! Append-only audit trail of tool dispatches.
+//!
+//! Every call through [`dispatch_tool`](super::tools::dispatch_tool) — agent
+//! turns and `POST /v1/tools/invoke` alike — appends one JSON line to
+//! `state_path/audit/tools.jsonl`: who called which tool, a redacted
+//! preview of the arguments, and how it ended.  The log is never rewritten;
+//! a bounded in-memory ring of recent entries backs `GET /v1/tools/audit`.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use sa_domain::persistence::PersistenceBackend;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Types
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// How an audited tool call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    Ok,
+    Error,
+    /// Refused by the agent's tool policy before dispatch.
+    Denied,
+}
+
+impl AuditStatus {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ok" => Some(Self::Ok),
+            "error" => Some(Self::Error),
+            "denied" => Some(Self::Denied),
+            _ => None,
+        }
+    }
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub session_key: Option<String>,
+    pub agent_id: Option<String>,
+    pub tool_name: String,
+    /// Arguments with secrets redacted, truncated to [`ARGS_PREVIEW_CHARS`].
+    pub args_preview: String,
+    pub result_status: AuditStatus,
+}
+
+/// Filters for [`ToolAuditLog::query`].  `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter<'a> {
+    pub tool_name: Option<&'a str>,
+    pub session_key: Option<&'a str>,
+    pub agent_id: Option<&'a str>,
+    pub status: Option<AuditStatus>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter<'_> {
+    fn matches(&self, e: &AuditEntry) -> bool {
+        self.tool_name.is_none_or(|t| e.tool_name == t)
+            && self
+                .session_key
+                .is_none_or(|k| e.session_key.as_deref() == Some(k))
+            && self
+                .agent_id
+                .is_none_or(|a| e.agent_id.as_deref() == Some(a))
+            && self.status.is_none_or(|s| e.result_status == s)
+            && self.since.is_none_or(|t| e.timestamp >= t)
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Redaction
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Maximum length of `args_preview`, in characters.
+pub const ARGS_PREVIEW_CHARS: usize = 512;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Argument keys whose values are always redacted (case-insensitive
+/// suffix match, so `github_token` and `accessToken` hit but `max_tokens`
+/// does not).
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "secret_key",
+    "access_key",
+    "private_key",
+    "authorization",
+    "cookie",
+    "credential",
+    "credentials",
+];
+
+/// Value prefixes of well-known credential formats.
+const SECRET_PREFIXES: &[&str] = &[
+    "sk-",
+    "ghp_",
+    "gho_",
+    "github_pat_",
+    "xoxb-",
+    "xoxp-",
+    "AKIA",
+];
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|s| key.ends_with(s))
+}
+
+/// Redact credentials embedded in a free-form string: `Bearer <tok>`
+/// headers and words (or `NAME=value` values) with a known secret prefix.
+fn redact_str(s: &str) -> String {
+    let mut out = Vec::new();
+    let mut after_bearer = false;
+    for word in s.split(' ') {
+        // `NAME=value` assignments are checked on the value side.
+        let value = word.rsplit_once('=').map_or(word, |(_, v)| v);
+        let secret = after_bearer || SECRET_PREFIXES.iter().any(|p| value.starts_with(p));
+        after_bearer = word.eq_ignore_ascii_case("bearer");
+        out.push(if secret && !word.is_empty() {
+            REDACTED
+        } else {
+            word
+        });
+    }
+    out.join(" ")
+}
+
+/// A copy of `args` with secret-looking values replaced by `[REDACTED]`.
+pub fn redact_args(args: &Value) -> Value {
+    match args {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = if is_secret_key(k) && !v.is_null() {
+                        Value::String(REDACTED.into())
+                    } else {
+                        redact_args(v)
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_args).collect()),
+        Value::String(s) => Value::String(redact_str(s)),
+        other => other.clone(),
+    }
+}
+
+fn args_preview(args: &Value) -> String {
+    let json = redact_args(args).to_string();
+    if json.chars().count() <= ARGS_PREVIEW_CHARS {
+        return json;
+    }
+    let mut preview: String = json.chars().take(ARGS_PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ToolAuditLog
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Backend key of the audit log.
+const AUDIT_KEY: &str = "audit/tools.jsonl";
+
+/// Entries kept in memory for the read endpoint.
+const MAX_RECENT: usize = 5_000;
+
+pub struct ToolAuditLog {
+    backend: Arc<dyn PersistenceBackend>,
+    recent: RwLock<VecDeque<AuditEntry>>,
+}
+
+impl ToolAuditLog {
+    /// Open the log, loading its most recent entries into memory.
+    pub fn new(backend: Arc<dyn PersistenceBackend>) -> Self {
+        let mut recent = VecDeque::new();
+        if let Ok(Some(raw)) = backend.load(AUDIT_KEY) {
+            for line in String::from_utf8_lossy(&raw).lines() {
+                if let Ok(e) = serde_json::from_str::<AuditEntry>(line) {
+                    recent.push_back(e);
+                    if recent.len() > MAX_RECENT {
+                        recent.pop_front();
+                    }
+                }
+            }
+        }
+        Self {
+            backend,
+            recent: RwLock::new(recent),
+        }
+    }
+
+    /// Append one dispatch to the log.
+    pub fn record(
+        &self,
+        session_key: Option<&str>,
+        agent_id: Option<&str>,
+        tool_name: &str,
+        args: &Value,
+        result_status: AuditStatus,
+    ) {
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            session_key: session_key.map(str::to_owned),
+            agent_id: agent_id.map(str::to_owned),
+            tool_name: tool_name.to_owned(),
+            args_preview: args_preview(args),
+            result_status,
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            if let Err(e) = self
+                .backend
+                .append(AUDIT_KEY, format!("{json}\n").as_bytes())
+            {
+                tracing::warn!(error = %e, tool = tool_name, "failed to append tool audit entry");
+            }
+        }
+        let mut recent = self.recent.write();
+        recent.push_back(entry);
+        if recent.len() > MAX_RECENT {
+            recent.pop_front();
+        }
+    }
+
+    /// Recent entries matching `filter`, newest first.
+    pub fn query(&self, filter: &AuditFilter<'_>, limit: usize) -> Vec<AuditEntry> {
+        self.recent
+            .read()
+            .iter()
+            .rev()
+            .filter(|e| filter.matches(e))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_domain::persistence::MemoryBackend;
+    use serde_json::json;
+
+    fn log() -> (Arc<dyn PersistenceBackend>, ToolAuditLog) {
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        (backend.clone(), ToolAuditLog::new(backend))
+    }
+
+    #[test]
+    fn record_appends_a_line() {
+        let (backend, audit) = log();
+        audit.record(
+            Some("agent:main:dm:1"),
+            Some("main"),
+            "exec",
+            &json!({"command": "ls"}),
+            AuditStatus::Ok,
+        );
+        audit.record(None, None, "file.read", &json!({}), AuditStatus::Error);
+
+        let raw = String::from_utf8(backend.load(AUDIT_KEY).unwrap().unwrap()).unwrap();
+        let lines: Vec<AuditEntry> = raw
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].tool_name, "exec");
+        assert_eq!(lines[0].session_key.as_deref(), Some("agent:main:dm:1"));
+        assert_eq!(lines[0].result_status, AuditStatus::Ok);
+        assert_eq!(lines[1].result_status, AuditStatus::Error);
+    }
+
+    #[test]
+    fn args_are_redacted() {
+        let (backend, audit) = log();
+        audit.record(
+            None,
+            None,
+            "http.request",
+            &json!({
+                "url": "https://api.example.com",
+                "api_key": "plain-value-123",
+                "max_tokens": 256,
+                "headers": {"Authorization": "Bearer abc.def"},
+                "env": ["OPENAI=sk-live-0123456789"],
+                "note": "use Bearer xyz789 here",
+            }),
+            AuditStatus::Ok,
+        );
+
+        let raw = String::from_utf8(backend.load(AUDIT_KEY).unwrap().unwrap()).unwrap();
+        assert!(raw.contains("api.example.com"));
+        assert!(raw.contains("256"));
+        for secret in ["plain-value-123", "abc.def", "sk-live-0123456789", "xyz789"] {
+            assert!(!raw.contains(secret), "{secret} leaked into the audit log");
+        }
+        assert!(raw.contains(REDACTED));
+    }
+
+    #[test]
+    fn long_args_are_truncated() {
+        let preview = args_preview(&json!({"content": "x".repeat(2_000)}));
+        assert_eq!(preview.chars().count(), ARGS_PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+    }
+
+    #[test]
+    fn query_filters_newest_first() {
+        let (backend, audit) = log();
+        audit.record(
+            Some("s1"),
+            Some("main"),
+            "exec",
+            &json!({}),
+            AuditStatus::Ok,
+        );
+        audit.record(
+            Some("s2"),
+            Some("coder"),
+            "exec",
+            &json!({}),
+            AuditStatus::Denied,
+        );
+        audit.record(
+            Some("s1"),
+            Some("main"),
+            "file.read",
+            &json!({}),
+            AuditStatus::Ok,
+        );
+
+        let exec = AuditFilter {
+            tool_name: Some("exec"),
+            ..Default::default()
+        };
+        let hits = audit.query(&exec, 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].session_key.as_deref(), Some("s2"));
+
+        let denied = AuditFilter {
+            status: Some(AuditStatus::Denied),
+            ..Default::default()
+        };
+        assert_eq!(audit.query(&denied, 10).len(), 1);
+        assert_eq!(audit.query(&AuditFilter::default(), 1).len(), 1);
+
+        // Entries survive a reopen.
+        let reopened = ToolAuditLog::new(backend);
+        let s1 = AuditFilter {
+            session_key: Some("s1"),
+            ..Default::default()
+        };
+        assert_eq!(reopened.query(&s1, 10).len(), 2);
+    }
+}
@@ -0,0 +1,425 @@
+//! Result cache for idempotent, read-only tool calls.
+//!
+//! Models often repeat the same read (a file, a doc, a URL) several times
+//! within a run.  [`ToolResultCache`] keys successful results on
+//! `(tool_name, canonicalized_args)` and serves repeats for a short TTL.
+//! Whether a tool may be cached comes from its metadata (see
+//! [`is_idempotent`](super::tools::is_idempotent)); any call to a
+//! non-idempotent tool flushes the whole cache so a read never returns
+//! content from before a write.
+//!
+//! [`ToolDefsCache`] memoizes the tool definitions sent to the model, per
+//! tool policy, until the node topology or MCP server set changes or a
+//! caller (e.g. a skills reload) invalidates it explicitly.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Mutex, RwLock};
+use serde_json::Value;
+
+use sa_domain::config::ToolResultCacheConfig;
+use sa_domain::tool::ToolDefinition;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ToolResultCache
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+type CacheKey = (String, String);
+
+struct Entry {
+    result: String,
+    inserted_at: Instant,
+}
+
+/// TTL cache of successful idempotent tool results.
+pub struct ToolResultCache {
+    enabled: bool,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<CacheKey, Entry>>,
+}
+
+impl ToolResultCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            enabled: true,
+            ttl,
+            max_entries: max_entries.max(1),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(cfg: &ToolResultCacheConfig) -> Self {
+        Self {
+            enabled: cfg.enabled,
+            ..Self::new(Duration::from_secs(cfg.ttl_secs), cfg.max_entries)
+        }
+    }
+
+    /// Run a tool call through the cache.
+    ///
+    /// Idempotent calls are served from the cache when a fresh entry
+    /// exists; otherwise `run` executes and a successful result is stored.
+    /// Non-idempotent calls always execute and flush the cache first.
+    pub async fn get_or_run<F, Fut>(
+        &self,
+        tool_name: &str,
+        arguments: &Value,
+        idempotent: bool,
+        run: F,
+    ) -> (String, bool)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = (String, bool)>,
+    {
+        if !self.enabled {
+            return run().await;
+        }
+        if !idempotent {
+            self.clear();
+            return run().await;
+        }
+
+        let key = (tool_name.to_owned(), canonical_json(arguments));
+        if let Some(hit) = self.get(&key) {
+            tracing::debug!(tool = tool_name, "tool result cache hit");
+            return (hit, false);
+        }
+
+        let (content, is_error) = run().await;
+        if !is_error {
+            self.insert(key, content.clone());
+        }
+        (content, is_error)
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<String> {
+        let mut entries = self.entries.lock();
+        match entries.get(key) {
+            Some(e) if e.inserted_at.elapsed() < self.ttl => Some(e.result.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: CacheKey, result: String) {
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.max_entries {
+            let ttl = self.ttl;
+            entries.retain(|_, e| e.inserted_at.elapsed() < ttl);
+        }
+        if entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.inserted_at)
+                .map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                entries.remove(&k);
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                result,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ToolDefsCache
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Generation counters of the dynamic tool sources.  A cached tool list
+/// is only served while both still match the values it was built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolDefsGeneration {
+    /// [`NodeRegistry::generation`](crate::nodes::registry::NodeRegistry::generation).
+    pub nodes: u64,
+    /// [`McpManager::generation`](sa_mcp_client::McpManager::generation).
+    pub mcp: u64,
+}
+
+struct CachedToolDefs {
+    defs: Arc<Vec<ToolDefinition>>,
+    generation: ToolDefsGeneration,
+}
+
+/// Tool definitions per tool-policy key.
+#[derive(Default)]
+pub struct ToolDefsCache {
+    entries: RwLock<HashMap<String, CachedToolDefs>>,
+    /// Bumped by [`invalidate`](Self::invalidate); a build that started
+    /// before an invalidation is not cached.
+    epoch: AtomicU64,
+}
+
+impl ToolDefsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the cached list for `key` if it was built at `generation`,
+    /// otherwise run `build` and cache its result.
+    pub fn get_or_build<F>(
+        &self,
+        key: &str,
+        generation: ToolDefsGeneration,
+        build: F,
+    ) -> Arc<Vec<ToolDefinition>>
+    where
+        F: FnOnce() -> Vec<ToolDefinition>,
+    {
+        // Cheap Arc::clone instead of deep-cloning the definitions.
+        if let Some(cached) = self.entries.read().get(key) {
+            if cached.generation == generation {
+                return Arc::clone(&cached.defs);
+            }
+        }
+
+        let epoch = self.epoch.load(Ordering::Acquire);
+        let defs = Arc::new(build());
+
+        let mut entries = self.entries.write();
+        if self.epoch.load(Ordering::Acquire) == epoch {
+            // Entries from other generations can never be served again.
+            entries.retain(|_, v| v.generation == generation);
+            entries.insert(
+                key.to_owned(),
+                CachedToolDefs {
+                    defs: Arc::clone(&defs),
+                    generation,
+                },
+            );
+        }
+        defs
+    }
+
+    /// Drop every cached list so the next turn rebuilds its tool set.
+    /// For sources without a generation counter (skills, skill engine)
+    /// and for MCP servers observed going down.
+    pub fn invalidate(&self, reason: &str) {
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        let dropped = {
+            let mut entries = self.entries.write();
+            let n = entries.len();
+            entries.clear();
+            n
+        };
+        tracing::debug!(reason, dropped, "tool definitions cache invalidated");
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Serialize `value` with object keys sorted at every level, so argument
+/// maps that differ only in key order share a cache key.
+fn canonical_json(value: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                let mut out = serde_json::Map::new();
+                for k in keys {
+                    out.insert(k.clone(), sorted(&map[k]));
+                }
+                Value::Object(out)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    sorted(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn call(
+        cache: &ToolResultCache,
+        calls: &AtomicUsize,
+        tool: &str,
+        args: Value,
+        idempotent: bool,
+    ) -> (String, bool) {
+        cache
+            .get_or_run(tool, &args, idempotent, || async {
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                (format!("result #{n}"), false)
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn identical_idempotent_calls_execute_once() {
+        let cache = ToolResultCache::new(Duration::from_secs(60), 16);
+        let calls = AtomicUsize::new(0);
+
+        let a = call(
+            &cache,
+            &calls,
+            "file.read",
+            json!({"path": "a.md", "limit": 10}),
+            true,
+        )
+        .await;
+        // Same args in a different key order hit the cache.
+        let b = call(
+            &cache,
+            &calls,
+            "file.read",
+            json!({"limit": 10, "path": "a.md"}),
+            true,
+        )
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a, b);
+
+        // Different args are a separate entry.
+        call(&cache, &calls, "file.read", json!({"path": "b.md"}), true).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_tool_always_executes_and_flushes() {
+        let cache = ToolResultCache::new(Duration::from_secs(60), 16);
+        let calls = AtomicUsize::new(0);
+
+        call(&cache, &calls, "exec", json!({"command": "ls"}), false).await;
+        call(&cache, &calls, "exec", json!({"command": "ls"}), false).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        assert_eq!(cache.len(), 1);
+        call(&cache, &calls, "file.write", json!({"path": "a.md"}), false).await;
+        assert!(cache.is_empty());
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn errors_and_expired_entries_are_not_served() {
+        let cache = ToolResultCache::new(Duration::ZERO, 16);
+        let calls = AtomicUsize::new(0);
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let cache = ToolResultCache::new(Duration::from_secs(60), 16);
+        for _ in 0..2 {
+            cache
+                .get_or_run("file.read", &json!({"path": "missing"}), true, || async {
+                    ("not found".to_owned(), true)
+                })
+                .await;
+        }
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_always_executes() {
+        let cache = ToolResultCache::from_config(&ToolResultCacheConfig::default());
+        let calls = AtomicUsize::new(0);
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        call(&cache, &calls, "file.read", json!({"path": "a.md"}), true).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn evicts_oldest_when_full() {
+        let cache = ToolResultCache::new(Duration::from_secs(60), 2);
+        cache.insert(("t".into(), "1".into()), "one".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(("t".into(), "2".into()), "two".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(("t".into(), "3".into()), "three".into());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&("t".into(), "1".into())).is_none());
+        assert_eq!(
+            cache.get(&("t".into(), "3".into())).as_deref(),
+            Some("three")
+        );
+    }
+
+    fn gen(nodes: u64) -> ToolDefsGeneration {
+        ToolDefsGeneration { nodes, mcp: 0 }
+    }
+
+    fn defs(names: &[&str]) -> Vec<ToolDefinition> {
+        names
+            .iter()
+            .map(|n| ToolDefinition {
+                name: n.to_string(),
+                description: String::new(),
+                parameters: json!({}),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tool_defs_rebuild_on_generation_change() {
+        let cache = ToolDefsCache::new();
+        let a = cache.get_or_build("k", gen(1), || defs(&["exec"]));
+        let b = cache.get_or_build("k", gen(1), || panic!("should be cached"));
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let c = cache.get_or_build("k", gen(2), || defs(&["exec", "node.tool"]));
+        assert_eq!(c.len(), 2);
+        let mcp_down = ToolDefsGeneration { nodes: 2, mcp: 1 };
+        let d = cache.get_or_build("k", mcp_down, || defs(&["exec"]));
+        assert_eq!(d.len(), 1);
+    }
+
+    #[test]
+    fn tool_defs_invalidate_forces_rebuild() {
+        let cache = ToolDefsCache::new();
+        cache.get_or_build("a", gen(1), || defs(&["exec"]));
+        cache.get_or_build("b", gen(1), || defs(&["file.read"]));
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate("test");
+        assert!(cache.is_empty());
+        let rebuilt = cache.get_or_build("a", gen(1), || defs(&["exec", "skill.new"]));
+        assert_eq!(rebuilt.len(), 2);
+
+        // A build racing an invalidation is returned but not cached.
+        let stale = cache.get_or_build("b", gen(1), || {
+            cache.invalidate("skills reloaded mid-build");
+            defs(&["file.read"])
+        });
+        assert_eq!(stale.len(), 1);
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,410 @@
+//! Tool-dispatch middleware — cross-cutting concerns (audit logging, tool
+//! policy, result caching, custom denylists) applied uniformly to every
+//! tool kind: built-ins, exec/process, skills, MCP and node tools alike.
+//!
+//! A call flows through the [`ToolChain`] from the outermost layer inwards
+//! and ends at a [`ToolHandler`] that routes it to the right backend.  Each
+//! layer may short-circuit (deny), rewrite, or observe the call by deciding
+//! whether and how to invoke [`Next::run`].
+//!
+//! Chain order:
+//! 1. built-in outer layers — [`AuditLayer`], [`PolicyLayer`], then the
+//!    per-session [`ToolRateLimiter`];
+//! 2. layers added with [`ToolChain::register`], in registration order;
+//! 3. built-in inner layers — [`CacheLayer`], closest to the handler so a
+//!    cached result still passes every other layer.
+//!
+//! Exec's command denylist and approval gate stay inside the exec handler:
+//! they match on the command string, not the tool name.
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde_json::Value;
+
+use crate::skills::SkillEngine;
+
+use super::agent::AgentContext;
+use super::tool_audit::{AuditStatus, ToolAuditLog};
+use super::tool_cache::ToolResultCache;
+use super::tool_rate_limit::ToolRateLimiter;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Types
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// One tool call flowing through the chain.
+#[derive(Clone, Copy)]
+pub struct ToolInvocation<'a> {
+    pub tool_name: &'a str,
+    pub arguments: &'a Value,
+    pub session_key: Option<&'a str>,
+    /// Who invoked the tool directly, outside any turn (e.g. the peer
+    /// address of an admin tool invoke).
+    pub caller: Option<&'a str>,
+    /// Set when the call comes from a sub-agent turn.
+    pub agent_ctx: Option<&'a AgentContext>,
+}
+
+impl ToolInvocation<'_> {
+    pub fn agent_id(&self) -> Option<&str> {
+        self.agent_ctx.map(|ctx| ctx.agent_id.as_str())
+    }
+}
+
+/// What a tool call produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolOutput {
+    pub content: String,
+    pub is_error: bool,
+    /// Refused by a layer before reaching its handler (audited as denied).
+    pub denied: bool,
+}
+
+impl ToolOutput {
+    /// A refusal; `reason` is what the model sees.
+    pub fn denied(reason: impl Into<String>) -> Self {
+        Self {
+            content: reason.into(),
+            is_error: true,
+            denied: true,
+        }
+    }
+}
+
+impl From<(String, bool)> for ToolOutput {
+    fn from((content, is_error): (String, bool)) -> Self {
+        Self {
+            content,
+            is_error,
+            denied: false,
+        }
+    }
+}
+
+/// A composable layer around tool dispatch.
+#[async_trait::async_trait]
+pub trait ToolMiddleware: Send + Sync {
+    /// Handle `call`, usually by delegating to `next` and inspecting or
+    /// adjusting its output.  Returning without calling `next` stops the
+    /// call here.
+    async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput;
+}
+
+/// The end of the chain: executes the call.
+#[async_trait::async_trait]
+pub trait ToolHandler: Send + Sync {
+    async fn call(&self, call: &ToolInvocation<'_>) -> ToolOutput;
+}
+
+/// The rest of the chain after the current layer.
+pub struct Next<'a> {
+    rest: &'a [Arc<dyn ToolMiddleware>],
+    handler: &'a dyn ToolHandler,
+}
+
+impl Next<'_> {
+    /// Pass `call` to the next layer, or to the handler after the last one.
+    pub async fn run(self, call: &ToolInvocation<'_>) -> ToolOutput {
+        match self.rest.split_first() {
+            Some((layer, rest)) => {
+                let next = Next {
+                    rest,
+                    handler: self.handler,
+                };
+                layer.handle(call, next).await
+            }
+            None => self.handler.call(call).await,
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Chain
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Ordered middleware around every tool dispatch.
+#[derive(Default)]
+pub struct ToolChain {
+    outer: Vec<Arc<dyn ToolMiddleware>>,
+    registered: RwLock<Vec<Arc<dyn ToolMiddleware>>>,
+    inner: Vec<Arc<dyn ToolMiddleware>>,
+}
+
+impl ToolChain {
+    /// A chain with fixed `outer` and `inner` layers; registered layers
+    /// run between the two.
+    pub fn new(outer: Vec<Arc<dyn ToolMiddleware>>, inner: Vec<Arc<dyn ToolMiddleware>>) -> Self {
+        Self {
+            outer,
+            registered: RwLock::new(Vec::new()),
+            inner,
+        }
+    }
+
+    /// The gateway's built-in layers: audit, tool policy and rate limits
+    /// outside, result caching inside.
+    pub fn builtin(
+        audit: Arc<ToolAuditLog>,
+        rate_limiter: Arc<ToolRateLimiter>,
+        cache: Arc<ToolResultCache>,
+        skill_engine: Arc<SkillEngine>,
+    ) -> Self {
+        Self::new(
+            vec![
+                Arc::new(AuditLayer { audit }),
+                Arc::new(PolicyLayer),
+                rate_limiter,
+            ],
+            vec![Arc::new(CacheLayer {
+                cache,
+                skill_engine,
+            })],
+        )
+    }
+
+    /// Add a layer; it runs inside those already registered.
+    pub fn register(&self, layer: Arc<dyn ToolMiddleware>) {
+        self.registered.write().push(layer);
+    }
+
+    /// Total number of layers.
+    pub fn len(&self) -> usize {
+        self.outer.len() + self.registered.read().len() + self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run `call` through every layer and then `handler`.
+    pub async fn run(&self, call: &ToolInvocation<'_>, handler: &dyn ToolHandler) -> ToolOutput {
+        // Snapshot so layers never run under the lock.
+        let layers: Vec<Arc<dyn ToolMiddleware>> = self
+            .outer
+            .iter()
+            .chain(self.registered.read().iter())
+            .chain(self.inner.iter())
+            .cloned()
+            .collect();
+        Next {
+            rest: &layers,
+            handler,
+        }
+        .run(call)
+        .await
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Built-in layers
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Appends every call, including refusals, to the tool audit log.
+pub struct AuditLayer {
+    pub audit: Arc<ToolAuditLog>,
+}
+
+#[async_trait::async_trait]
+impl ToolMiddleware for AuditLayer {
+    async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+        let out = next.run(call).await;
+        let status = if out.denied {
+            AuditStatus::Denied
+        } else if out.is_error {
+            AuditStatus::Error
+        } else {
+            AuditStatus::Ok
+        };
+        self.audit.record(
+            call.session_key,
+            call.agent_id(),
+            call.tool_name,
+            call.arguments,
+            status,
+        );
+        out
+    }
+}
+
+/// Enforces the calling agent's `ToolPolicy`.
+///
+/// Definition-time filtering is necessary but not sufficient: models can
+/// hallucinate tool names, and other code paths call dispatch directly.
+pub struct PolicyLayer;
+
+#[async_trait::async_trait]
+impl ToolMiddleware for PolicyLayer {
+    async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+        if let Some(ctx) = call.agent_ctx {
+            if !ctx.tool_policy.allows(call.tool_name) {
+                return ToolOutput::denied(format!(
+                    "tool '{}' is not permitted by this agent's tool policy (agent: {})",
+                    call.tool_name, ctx.agent_id
+                ));
+            }
+        }
+        next.run(call).await
+    }
+}
+
+/// Serves idempotent tools from the [`ToolResultCache`].
+pub struct CacheLayer {
+    pub cache: Arc<ToolResultCache>,
+    pub skill_engine: Arc<SkillEngine>,
+}
+
+#[async_trait::async_trait]
+impl ToolMiddleware for CacheLayer {
+    async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+        let idempotent = super::tools::is_idempotent(&self.skill_engine, call.tool_name);
+        self.cache
+            .get_or_run(call.tool_name, call.arguments, idempotent, || async move {
+                let out = next.run(call).await;
+                (out.content, out.is_error)
+            })
+            .await
+            .into()
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use sa_domain::persistence::{MemoryBackend, PersistenceBackend};
+    use serde_json::json;
+
+    /// Refuses the listed tool names, whatever kind of tool they are.
+    struct Denylist(&'static [&'static str]);
+
+    #[async_trait::async_trait]
+    impl ToolMiddleware for Denylist {
+        async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+            if self.0.contains(&call.tool_name) {
+                return ToolOutput::denied(format!("tool '{}' is denylisted", call.tool_name));
+            }
+            next.run(call).await
+        }
+    }
+
+    /// Records which tools reached it and answers with their name.
+    #[derive(Default)]
+    struct Recorder {
+        reached: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ToolHandler for Recorder {
+        async fn call(&self, call: &ToolInvocation<'_>) -> ToolOutput {
+            self.reached.lock().push(call.tool_name.to_owned());
+            (format!("ran {}", call.tool_name), false).into()
+        }
+    }
+
+    fn invocation<'a>(tool_name: &'a str, arguments: &'a Value) -> ToolInvocation<'a> {
+        ToolInvocation {
+            tool_name,
+            arguments,
+            session_key: Some("agent:main:dm:1"),
+            caller: None,
+            agent_ctx: None,
+        }
+    }
+
+    const NODE_TOOL: &str = "macos.notes.delete";
+    const MCP_TOOL: &str = "mcp:fs:delete_file";
+
+    #[tokio::test]
+    async fn denylist_applies_to_node_and_mcp_tools_identically() {
+        let chain = ToolChain::default();
+        chain.register(Arc::new(Denylist(&[NODE_TOOL, MCP_TOOL])));
+        let handler = Recorder::default();
+        let args = json!({ "path": "/tmp/x" });
+
+        let node = chain.run(&invocation(NODE_TOOL, &args), &handler).await;
+        let mcp = chain.run(&invocation(MCP_TOOL, &args), &handler).await;
+
+        assert!(node.denied && node.is_error);
+        assert!(mcp.denied && mcp.is_error);
+        assert_eq!(
+            node.content.replace(NODE_TOOL, "T"),
+            mcp.content.replace(MCP_TOOL, "T")
+        );
+        assert!(handler.reached.lock().is_empty(), "neither call was routed");
+
+        // Tools not on the list pass through to routing.
+        for tool in ["macos.notes.search", "mcp:fs:read_file"] {
+            let out = chain.run(&invocation(tool, &args), &handler).await;
+            assert_eq!(out.content, format!("ran {tool}"));
+            assert!(!out.denied);
+        }
+        assert_eq!(
+            *handler.reached.lock(),
+            ["macos.notes.search", "mcp:fs:read_file"]
+        );
+    }
+
+    #[tokio::test]
+    async fn audit_layer_records_denials_from_inner_layers() {
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let audit = Arc::new(ToolAuditLog::new(backend));
+        let chain = ToolChain::new(
+            vec![Arc::new(AuditLayer {
+                audit: audit.clone(),
+            })],
+            vec![],
+        );
+        chain.register(Arc::new(Denylist(&[MCP_TOOL])));
+        let handler = Recorder::default();
+        let args = json!({});
+
+        chain.run(&invocation(MCP_TOOL, &args), &handler).await;
+        chain
+            .run(&invocation("macos.notes.search", &args), &handler)
+            .await;
+
+        let entries = audit.query(&Default::default(), 10);
+        let statuses: Vec<_> = entries
+            .iter()
+            .map(|e| (e.tool_name.as_str(), e.result_status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("macos.notes.search", AuditStatus::Ok),
+                (MCP_TOOL, AuditStatus::Denied),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn layers_run_outer_registered_inner() {
+        struct Tag(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+        #[async_trait::async_trait]
+        impl ToolMiddleware for Tag {
+            async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+                self.1.lock().push(self.0);
+                next.run(call).await
+            }
+        }
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let tag = |name| Arc::new(Tag(name, order.clone())) as Arc<dyn ToolMiddleware>;
+        let chain = ToolChain::new(vec![tag("outer")], vec![tag("inner")]);
+        chain.register(tag("first"));
+        chain.register(tag("second"));
+        assert_eq!(chain.len(), 4);
+
+        let args = json!({});
+        chain
+            .run(&invocation("exec", &args), &Recorder::default())
+            .await;
+        assert_eq!(*order.lock(), ["outer", "first", "second", "inner"]);
+    }
+}
@@ -0,0 +1,265 @@
+//! Per-session tool rate limiting.
+//!
+//! A prompt-injected or looping agent can call `exec` or `web.fetch` many
+//! times within a single turn.  [`ToolRateLimiter`] keeps a sliding
+//! one-minute window of calls per `(caller, tool)` and refuses calls over
+//! the configured limit with an `ErrorKind::NotAllowed` message carrying a
+//! retry hint.  Other tools, and the same tool in other sessions, stay
+//! callable.  Calls without a session are counted per sub-agent or per
+//! direct caller (see [`RateKey`]) rather than in one shared window.
+//!
+//! It runs as a [`ToolMiddleware`] layer so it applies to every tool kind.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use sa_domain::config::ToolRateLimitConfig;
+use sa_protocol::ErrorKind;
+
+use super::tool_middleware::{Next, ToolInvocation, ToolMiddleware, ToolOutput};
+
+/// Length of the sliding window limits are counted over.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Once this many `(caller, tool)` windows are tracked, idle ones are
+/// swept on the next call.
+const SWEEP_THRESHOLD: usize = 1024;
+
+/// Whose window a call is counted in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateKey {
+    Session(String),
+    /// A sub-agent call without a session.
+    Agent(String),
+    /// A direct invoke without a session (e.g. an admin peer address).
+    Caller(String),
+    /// No identity at all.
+    Anonymous,
+}
+
+impl RateKey {
+    /// The session if there is one, else the agent, else the caller.
+    pub fn of(call: &ToolInvocation<'_>) -> Self {
+        if let Some(session_key) = call.session_key {
+            Self::Session(session_key.to_owned())
+        } else if let Some(agent_id) = call.agent_id() {
+            Self::Agent(agent_id.to_owned())
+        } else if let Some(caller) = call.caller {
+            Self::Caller(caller.to_owned())
+        } else {
+            Self::Anonymous
+        }
+    }
+}
+
+/// Calls per minute per `(caller, tool)`.
+pub struct ToolRateLimiter {
+    cfg: ToolRateLimitConfig,
+    windows: Mutex<HashMap<(RateKey, String), VecDeque<Instant>>>,
+}
+
+impl ToolRateLimiter {
+    pub fn new(cfg: ToolRateLimitConfig) -> Self {
+        Self {
+            cfg,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a call to `tool_name` by `key`.  Returns the wait until the
+    /// next call would be admitted when the limit is exceeded; refused
+    /// calls are not counted.
+    pub fn check(&self, key: &RateKey, tool_name: &str) -> Result<(), Duration> {
+        self.check_at(key, tool_name, Instant::now())
+    }
+
+    fn check_at(&self, key: &RateKey, tool_name: &str, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.cfg.limit_for(tool_name) else {
+            return Ok(());
+        };
+        let expired = |t: &Instant| now.duration_since(*t) >= WINDOW;
+
+        let mut windows = self.windows.lock();
+        if windows.len() >= SWEEP_THRESHOLD {
+            windows.retain(|_, calls| calls.back().is_some_and(|t| !expired(t)));
+        }
+        let calls = windows
+            .entry((key.clone(), tool_name.to_owned()))
+            .or_default();
+        while calls.front().is_some_and(expired) {
+            calls.pop_front();
+        }
+        if calls.len() >= limit as usize {
+            let oldest = calls[0];
+            return Err(WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+
+    /// The configured limit for `tool_name` (calls per minute), if any.
+    pub fn limit_for(&self, tool_name: &str) -> Option<u32> {
+        self.cfg.limit_for(tool_name)
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolMiddleware for ToolRateLimiter {
+    async fn handle(&self, call: &ToolInvocation<'_>, next: Next<'_>) -> ToolOutput {
+        let key = RateKey::of(call);
+        match self.check(&key, call.tool_name) {
+            Ok(()) => next.run(call).await,
+            Err(retry_in) => {
+                let limit = self.limit_for(call.tool_name).unwrap_or_default();
+                tracing::warn!(
+                    tool = call.tool_name,
+                    rate_key = ?key,
+                    limit,
+                    "tool rate limit exceeded"
+                );
+                ToolOutput::denied(format!(
+                    "{}: tool '{}' rate limit exceeded ({limit} calls/min per caller); \
+                     retry in {}s",
+                    ErrorKind::NotAllowed,
+                    call.tool_name,
+                    retry_in.as_secs().max(1)
+                ))
+            }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::tool_middleware::{ToolChain, ToolHandler};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn limiter(per_tool: &[(&str, u32)]) -> ToolRateLimiter {
+        ToolRateLimiter::new(ToolRateLimitConfig {
+            enabled: true,
+            default_per_minute: 0,
+            per_tool: per_tool
+                .iter()
+                .map(|(name, limit)| (name.to_string(), *limit))
+                .collect(),
+        })
+    }
+
+    fn session(key: &str) -> RateKey {
+        RateKey::Session(key.to_owned())
+    }
+
+    #[test]
+    fn exceeding_per_session_rate_is_blocked() {
+        let limits = limiter(&[("exec", 3)]);
+        let t0 = Instant::now();
+        for i in 0..3 {
+            let at = t0 + Duration::from_secs(i);
+            assert!(limits.check_at(&session("s1"), "exec", at).is_ok());
+        }
+
+        let retry = limits
+            .check_at(&session("s1"), "exec", t0 + Duration::from_secs(10))
+            .unwrap_err();
+        assert_eq!(retry, Duration::from_secs(50), "oldest call leaves at 60s");
+
+        // Other sessions have their own window.
+        assert!(limits.check_at(&session("s2"), "exec", t0).is_ok());
+
+        // Once the oldest call ages out, one more is admitted.
+        let later = t0 + WINDOW;
+        assert!(limits.check_at(&session("s1"), "exec", later).is_ok());
+        assert!(limits.check_at(&session("s1"), "exec", later).is_err());
+    }
+
+    #[test]
+    fn other_tools_remain_callable() {
+        let limits = limiter(&[("exec", 1)]);
+        let now = Instant::now();
+        assert!(limits.check_at(&session("s1"), "exec", now).is_ok());
+        assert!(limits.check_at(&session("s1"), "exec", now).is_err());
+
+        for _ in 0..100 {
+            assert!(limits.check_at(&session("s1"), "file.read", now).is_ok());
+        }
+    }
+
+    #[test]
+    fn sessionless_calls_are_counted_per_caller() {
+        let limits = limiter(&[("exec", 1)]);
+        let now = Instant::now();
+        let args = json!({});
+        let direct = |caller| ToolInvocation {
+            tool_name: "exec",
+            arguments: &args,
+            session_key: None,
+            caller,
+            agent_ctx: None,
+        };
+
+        let a = RateKey::of(&direct(Some("10.0.0.1")));
+        let b = RateKey::of(&direct(Some("10.0.0.2")));
+        assert_eq!(a, RateKey::Caller("10.0.0.1".into()));
+        assert!(limits.check_at(&a, "exec", now).is_ok());
+        assert!(limits.check_at(&a, "exec", now).is_err());
+        assert!(limits.check_at(&b, "exec", now).is_ok(), "own window");
+
+        // A session key wins over the caller.
+        let in_session = ToolInvocation {
+            session_key: Some("s1"),
+            ..direct(Some("10.0.0.1"))
+        };
+        assert_eq!(RateKey::of(&in_session), session("s1"));
+        assert_eq!(RateKey::of(&direct(None)), RateKey::Anonymous);
+    }
+
+    #[test]
+    fn default_limit_and_disabled_config() {
+        let mut cfg = ToolRateLimitConfig {
+            default_per_minute: 2,
+            ..Default::default()
+        };
+        assert_eq!(cfg.limit_for("file.read"), Some(2));
+        assert_eq!(cfg.limit_for("exec"), Some(30), "per-tool entry wins");
+        cfg.per_tool.insert("exec".into(), 0);
+        assert_eq!(cfg.limit_for("exec"), None, "0 = unlimited");
+        cfg.enabled = false;
+        assert_eq!(cfg.limit_for("file.read"), None);
+    }
+
+    struct Done;
+
+    #[async_trait::async_trait]
+    impl ToolHandler for Done {
+        async fn call(&self, _call: &ToolInvocation<'_>) -> ToolOutput {
+            ("done".to_owned(), false).into()
+        }
+    }
+
+    #[tokio::test]
+    async fn refusal_is_not_allowed_with_retry_hint() {
+        let chain = ToolChain::default();
+        chain.register(Arc::new(limiter(&[("web.fetch", 1)])));
+        let args = json!({ "url": "https://example.com" });
+        let call = ToolInvocation {
+            tool_name: "web.fetch",
+            arguments: &args,
+            session_key: Some("s1"),
+            caller: None,
+            agent_ctx: None,
+        };
+
+        assert_eq!(chain.run(&call, &Done).await.content, "done");
+        let refused = chain.run(&call, &Done).await;
+        assert!(refused.denied && refused.is_error);
+        assert!(refused.content.starts_with("not_allowed: "));
+        assert!(refused.content.contains("retry in "));
+    }
+}
@@ -0,0 +1,126 @@
+//! Aggregate per-tool execution metrics across runs.
+//!
+//! Run nodes record each individual tool call; [`ToolStats`] rolls them up
+//! per tool name (call count, failure rate, latency) so operators can spot
+//! tools that fail often or run slowly.  Counters live in memory and reset
+//! on restart.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Types
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Running counters for one tool.
+#[derive(Default)]
+struct Counters {
+    calls: u64,
+    errors: u64,
+    total_ms: u64,
+    max_ms: u64,
+    last_called_at: Option<DateTime<Utc>>,
+}
+
+/// Snapshot of one tool's aggregated metrics.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStat {
+    pub tool: String,
+    pub calls: u64,
+    pub errors: u64,
+    /// `errors / calls`, in `0.0..=1.0`.
+    pub failure_rate: f64,
+    pub mean_latency_ms: f64,
+    pub max_latency_ms: u64,
+    pub last_called_at: Option<DateTime<Utc>>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// ToolStats
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// In-memory per-tool metrics registry.
+#[derive(Default)]
+pub struct ToolStats {
+    tools: RwLock<HashMap<String, Counters>>,
+}
+
+impl ToolStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one finished tool call.
+    pub fn record(&self, tool: &str, is_error: bool, duration_ms: u64) {
+        let mut tools = self.tools.write();
+        let c = tools.entry(tool.to_owned()).or_default();
+        c.calls += 1;
+        if is_error {
+            c.errors += 1;
+        }
+        c.total_ms += duration_ms;
+        c.max_ms = c.max_ms.max(duration_ms);
+        c.last_called_at = Some(Utc::now());
+    }
+
+    /// Metrics for every tool seen so far, sorted by tool name.
+    pub fn snapshot(&self) -> Vec<ToolStat> {
+        let tools = self.tools.read();
+        let mut stats: Vec<ToolStat> = tools
+            .iter()
+            .map(|(tool, c)| ToolStat {
+                tool: tool.clone(),
+                calls: c.calls,
+                errors: c.errors,
+                failure_rate: c.errors as f64 / c.calls.max(1) as f64,
+                mean_latency_ms: c.total_ms as f64 / c.calls.max(1) as f64,
+                max_latency_ms: c.max_ms,
+                last_called_at: c.last_called_at,
+            })
+            .collect();
+        stats.sort_by(|a, b| a.tool.cmp(&b.tool));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_counts_rates_and_latency() {
+        let stats = ToolStats::new();
+        // exec: 10 calls, 3 failures.
+        for i in 0..10 {
+            stats.record("exec", i < 3, 100);
+        }
+        stats.record("macos.notes.search", false, 1500);
+        stats.record("macos.notes.search", false, 2500);
+
+        let snap = stats.snapshot();
+        assert_eq!(snap.len(), 2);
+
+        let exec = &snap[0];
+        assert_eq!(exec.tool, "exec");
+        assert_eq!(exec.calls, 10);
+        assert_eq!(exec.errors, 3);
+        assert!((exec.failure_rate - 0.3).abs() < 1e-9);
+        assert!((exec.mean_latency_ms - 100.0).abs() < 1e-9);
+
+        let notes = &snap[1];
+        assert_eq!(notes.tool, "macos.notes.search");
+        assert_eq!(notes.errors, 0);
+        assert_eq!(notes.failure_rate, 0.0);
+        assert!((notes.mean_latency_ms - 2000.0).abs() < 1e-9);
+        assert_eq!(notes.max_latency_ms, 2500);
+        assert!(notes.last_called_at.is_some());
+    }
+
+    #[test]
+    fn empty_registry_has_no_entries() {
+        assert!(ToolStats::new().snapshot().is_empty());
+    }
+}
@@ -4,19 +4,24 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use sa_domain::config::ToolPolicy;
 use sa_domain::tool::ToolDefinition;
+use sa_mcp_client::McpToolDef;
 use sa_tools::exec::{self, ExecRequest};
 use sa_tools::file_ops;
 use sa_tools::process::{self, ProcessRequest};
 
+use crate::nodes::registry::NodeInfo;
 use crate::nodes::router::{LocalTool, ToolDestination};
+use crate::skills::SkillEngine;
 use crate::state::AppState;
 
-use super::agent::AgentContext;
+use super::agent::{AgentContext, FanOut};
+use super::tool_cache::ToolDefsGeneration;
+use super::tool_middleware::{ToolHandler, ToolInvocation, ToolOutput};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Tool definitions
@@ -27,7 +32,17 @@ use super::agent::AgentContext;
 fn policy_cache_key(tool_policy: Option<&ToolPolicy>) -> String {
     match tool_policy {
         None => "__none__".to_owned(),
-        Some(p) => format!("a:{};d:{}", p.allow.join(","), p.deny.join(",")),
+        Some(p) => format!(
+            "a:{};d:{};r:{};def:{:?}",
+            p.allow.join(","),
+            p.deny.join(","),
+            p.rules
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            p.default,
+        ),
     }
 }
 
@@ -36,27 +51,30 @@ fn policy_cache_key(tool_policy: Option<&ToolPolicy>) -> String {
 /// When `tool_policy` is `Some`, definitions are filtered through it so that
 /// sub-agents only see tools their config permits.
 ///
-/// Results are cached per `(node_generation, tool_policy)` to avoid
-/// rebuilding the definitions on every turn when the node topology and
-/// policy haven't changed.
+/// Results are cached per tool policy and reused until the node topology
+/// or MCP server set changes, or the cache is invalidated (skills reload).
 pub fn build_tool_definitions(
     state: &AppState,
     tool_policy: Option<&ToolPolicy>,
 ) -> Arc<Vec<ToolDefinition>> {
-    let current_gen = state.nodes.generation();
-    let key = policy_cache_key(tool_policy);
-
-    // Check cache — returns a cheap Arc::clone instead of deep-cloning
-    // the entire Vec<ToolDefinition>.
-    {
-        let cache = state.tool_defs_cache.read();
-        if let Some(cached) = cache.get(&key) {
-            if cached.generation == current_gen {
-                return Arc::clone(&cached.defs);
+    let generation = ToolDefsGeneration {
+        nodes: state.nodes.generation(),
+        mcp: state.mcp.generation(),
+    };
+    state
+        .tool_defs_cache
+        .get_or_build(&policy_cache_key(tool_policy), generation, || {
+            let (mut defs, _) = build_unfiltered_definitions(state);
+            if let Some(policy) = tool_policy {
+                defs.retain(|d| policy.allows(&d.name));
             }
-        }
-    }
+            defs
+        })
+}
 
+/// Every tool definition before policy filtering, plus the node
+/// capabilities that were dropped because the name was already taken.
+fn build_unfiltered_definitions(state: &AppState) -> (Vec<ToolDefinition>, Vec<ShadowedTool>) {
     let mut defs = Vec::new();
 
     // ── Built-in local tools ──────────────────────────────────────
@@ -68,6 +86,7 @@ pub fn build_tool_definitions(
             "properties": {
                 "command": { "type": "string", "description": "Shell command to execute" },
                 "background": { "type": "boolean", "description": "Run in background" },
+                "label": { "type": "string", "description": "Label for grouping background sessions (filter with process list)" },
                 "workdir": { "type": "string", "description": "Working directory" },
                 "timeout_sec": { "type": "integer", "description": "Hard timeout in seconds" }
             },
@@ -87,7 +106,18 @@ pub fn build_tool_definitions(
                     "description": "Action to perform"
                 },
                 "session_id": { "type": "string", "description": "Process session ID" },
-                "data": { "type": "string", "description": "Data to write to stdin" }
+                "data": { "type": "string", "description": "Data to write to stdin" },
+                "label": { "type": "string", "description": "For list: only sessions with this label" },
+                "status": {
+                    "type": "string",
+                    "enum": ["running", "finished", "killed", "timedout", "failed"],
+                    "description": "For list: only sessions in this status"
+                },
+                "stream": {
+                    "type": "string",
+                    "enum": ["combined", "stdout", "stderr"],
+                    "description": "For log: which output to read (combined keeps emission order)"
+                }
             },
             "required": ["action"]
         }),
@@ -183,6 +213,15 @@ pub fn build_tool_definitions(
         }),
     });
 
+    defs.push(ToolDefinition {
+        name: "skills.list".into(),
+        description: "List every ready skill (the system prompt may show only the most relevant ones).".into(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {}
+        }),
+    });
+
     defs.push(ToolDefinition {
         name: "skill.read_resource".into(),
         description: "Read a bundled resource from a skill (references/, scripts/, assets/).".into(),
@@ -223,6 +262,32 @@ pub fn build_tool_definitions(
         }),
     });
 
+    defs.push(ToolDefinition {
+        name: "memory.remember".into(),
+        description: "Deliberately remember a specific fact the user asked you to keep (e.g. 'my flight is at 6pm'). Stored as an explicit memory, ranked above automatically captured conversation.".into(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "fact": { "type": "string", "description": "The fact to remember, as a self-contained statement" },
+                "tags": { "type": "array", "items": { "type": "string" }, "description": "Optional labels (e.g. 'travel')" }
+            },
+            "required": ["fact"]
+        }),
+    });
+
+    defs.push(ToolDefinition {
+        name: "memory.forget".into(),
+        description: "Delete memories matching a statement the user asked you to forget. A human must approve the deletion; returns what was removed.".into(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "What to forget, e.g. 'I live in Lyon'" },
+                "limit": { "type": "integer", "description": "Max memories to consider (default 5, max 20)" }
+            },
+            "required": ["query"]
+        }),
+    });
+
     // ── Skill engine tools ────────────────────────────────────────
     // Add tool definitions for every registered callable skill.
     for spec in state.skill_engine.list() {
@@ -282,6 +347,30 @@ pub fn build_tool_definitions(
                 }),
             });
 
+            defs.push(ToolDefinition {
+                name: "agent.run_parallel".into(),
+                description: "Run several sub-agent tasks concurrently and return all their answers as one array, in the order given. Use for independent tasks that can proceed in parallel.".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "runs": {
+                            "type": "array",
+                            "description": "The sub-agent tasks to run",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "agent_id": { "type": "string", "description": "ID of the agent to run (from agent.list)" },
+                                    "task": { "type": "string", "description": "The task or question to give the agent" },
+                                    "model": { "type": "string", "description": "Optional model override" }
+                                },
+                                "required": ["agent_id", "task"]
+                            }
+                        }
+                    },
+                    "required": ["runs"]
+                }),
+            });
+
             defs.push(ToolDefinition {
                 name: "agent.list".into(),
                 description: "List all available sub-agents and their capabilities.".into(),
@@ -293,24 +382,46 @@ pub fn build_tool_definitions(
         }
     }
 
+    // ── MCP + node-advertised tools ───────────────────────────────
+    let shadowed = append_dynamic_tools(&mut defs, &state.mcp.list_tools(), &state.nodes.list());
+    (defs, shadowed)
+}
+
+/// A node capability left out of the tool set because an earlier
+/// definition (built-in, skill, MCP or another node) already uses its name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShadowedTool {
+    pub name: String,
+    pub node_id: String,
+}
+
+/// Append MCP tools (as `mcp:{server_id}:{tool}`) and node-advertised
+/// capabilities to `defs`.  Node capabilities never replace an existing
+/// definition; the ones skipped are returned.
+fn append_dynamic_tools(
+    defs: &mut Vec<ToolDefinition>,
+    mcp_tools: &[(&str, &McpToolDef)],
+    nodes: &[NodeInfo],
+) -> Vec<ShadowedTool> {
     // ── MCP tools ──────────────────────────────────────────────────
-    // Add definitions for tools discovered from MCP servers.
-    for (server_id, tool) in state.mcp.list_tools() {
-        let prefixed_name = format!("mcp:{server_id}:{}", tool.name);
+    for (server_id, tool) in mcp_tools {
         defs.push(ToolDefinition {
-            name: prefixed_name,
+            name: format!("mcp:{server_id}:{}", tool.name),
             description: tool.description.clone(),
             parameters: tool.input_schema.clone(),
         });
     }
 
     // ── Node-advertised tools ─────────────────────────────────────
-    // Add definitions for capabilities advertised by connected nodes.
-    let node_list = state.nodes.list();
-    for node_info in node_list.iter() {
+    let mut shadowed = Vec::new();
+    for node_info in nodes {
         for cap in &node_info.capabilities {
             // Don't duplicate tools we already defined.
             if defs.iter().any(|d| d.name == *cap) {
+                shadowed.push(ShadowedTool {
+                    name: cap.clone(),
+                    node_id: node_info.node_id.clone(),
+                });
                 continue;
             }
             defs.push(ToolDefinition {
@@ -324,31 +435,60 @@ pub fn build_tool_definitions(
             });
         }
     }
+    shadowed
+}
 
-    // ── Apply tool policy filter ─────────────────────────────────
-    if let Some(policy) = tool_policy {
-        defs.retain(|d| policy.allows(&d.name));
-    }
+/// What the model would see for a given tool policy, with the reasons
+/// other tools were left out.  Built uncached — for debugging only.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinitionsReport {
+    /// Definitions sent to the model, in order.
+    pub tools: Vec<ToolDefinition>,
+    /// Tools that exist but the policy does not permit.
+    pub denied_by_policy: Vec<String>,
+    /// Node capabilities hidden by an earlier definition of the same name.
+    pub shadowed: Vec<ShadowedTool>,
+    /// Names defined more than once (e.g. a skill named like a built-in);
+    /// providers typically reject such a tool list.
+    pub duplicate_names: Vec<String>,
+}
 
-    // Wrap in Arc and populate cache (clear stale entries from old generations).
-    let defs = Arc::new(defs);
-    {
-        let mut cache = state.tool_defs_cache.write();
-        cache.retain(|_, v| v.generation == current_gen);
-        cache.insert(
-            key,
-            crate::state::CachedToolDefs {
-                defs: Arc::clone(&defs),
-                generation: current_gen,
-                policy_key: policy_cache_key(tool_policy),
-            },
-        );
+/// Resolve the tool definitions for `tool_policy` along with a report of
+/// collisions and policy exclusions.
+pub fn tool_definitions_report(
+    state: &AppState,
+    tool_policy: Option<&ToolPolicy>,
+) -> ToolDefinitionsReport {
+    let (defs, shadowed) = build_unfiltered_definitions(state);
+    resolve_report(defs, shadowed, tool_policy)
+}
+
+fn resolve_report(
+    defs: Vec<ToolDefinition>,
+    shadowed: Vec<ShadowedTool>,
+    tool_policy: Option<&ToolPolicy>,
+) -> ToolDefinitionsReport {
+    let (tools, denied): (Vec<_>, Vec<_>) = defs
+        .into_iter()
+        .partition(|d| tool_policy.is_none_or(|p| p.allows(&d.name)));
+
+    let mut seen = HashSet::new();
+    let mut duplicate_names = Vec::new();
+    for def in &tools {
+        if !seen.insert(def.name.as_str()) && !duplicate_names.contains(&def.name) {
+            duplicate_names.push(def.name.clone());
+        }
     }
 
-    defs
+    ToolDefinitionsReport {
+        tools,
+        denied_by_policy: denied.into_iter().map(|d| d.name).collect(),
+        shadowed,
+        duplicate_names,
+    }
 }
 
-/// Collect all base tool names for effective_tool_count calculations.
+/// Collect all base tool names for effective tool-set resolution.
 pub fn all_base_tool_names(state: &AppState) -> Vec<String> {
     let mut names: HashSet<String> = HashSet::from([
         "exec".into(),
@@ -361,11 +501,15 @@ pub fn all_base_tool_names(state: &AppState) -> Vec<String> {
         "file.list".into(),
         "skill.read_doc".into(),
         "skill.read_resource".into(),
+        "skills.list".into(),
         "memory.search".into(),
         "memory.ingest".into(),
+        "memory.remember".into(),
+        "memory.forget".into(),
         "web.search".into(),
         "http.request".into(),
         "agent.run".into(),
+        "agent.run_parallel".into(),
         "agent.list".into(),
     ]);
     let node_list = state.nodes.list();
@@ -388,33 +532,80 @@ pub fn all_base_tool_names(state: &AppState) -> Vec<String> {
 /// Dispatch a single tool call. Returns (result_content, is_error).
 ///
 /// `agent_ctx` carries the parent agent's context (for depth guards,
-/// provenance metadata on memory calls, etc.).
+/// provenance metadata on memory calls, etc.); `caller` identifies a
+/// direct invoke made outside any turn; `fan_out` is the calling turn's
+/// sub-agent budget, charged by `agent.run` and `agent.run_parallel`.
 ///
-/// **Important**: ToolPolicy is enforced here at dispatch time (not just
-/// at definition time) to block hallucinated/injected tool names.
+/// Every call runs through the [`ToolChain`](super::tool_middleware::ToolChain)
+/// in `state.tool_middleware` — audit logging, ToolPolicy enforcement,
+/// result caching and any registered layers — before being routed to its
+/// backend.
 pub async fn dispatch_tool(
     state: &AppState,
     tool_name: &str,
     arguments: &Value,
     session_key: Option<&str>,
+    caller: Option<&str>,
     agent_ctx: Option<&AgentContext>,
+    fan_out: &FanOut,
 ) -> (String, bool) {
-    // ── Enforce ToolPolicy at dispatch time ──────────────────────
-    // Definition-time filtering is necessary but not sufficient:
-    // models can hallucinate tool names, and future code paths might
-    // call dispatch directly.
-    if let Some(ctx) = agent_ctx {
-        if !ctx.tool_policy.allows(tool_name) {
-            return (
-                format!(
-                    "tool '{}' is not permitted by this agent's tool policy (agent: {})",
-                    tool_name, ctx.agent_id
-                ),
-                true,
-            );
-        }
+    let call = ToolInvocation {
+        tool_name,
+        arguments,
+        session_key,
+        caller,
+        agent_ctx,
+    };
+    let route = RouteTool { state, fan_out };
+    let out = state.tool_middleware.run(&call, &route).await;
+    (out.content, out.is_error)
+}
+
+/// Terminal handler of the middleware chain: routes the call to the
+/// built-in, MCP, skill or node backend.
+struct RouteTool<'a> {
+    state: &'a AppState,
+    fan_out: &'a FanOut,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for RouteTool<'_> {
+    async fn call(&self, call: &ToolInvocation<'_>) -> ToolOutput {
+        dispatch_uncached(
+            self.state,
+            call.tool_name,
+            call.arguments,
+            call.session_key,
+            call.agent_ctx,
+            self.fan_out,
+        )
+        .await
+        .into()
     }
+}
+
+/// Whether a tool's metadata marks it idempotent and side-effect free, so
+/// its results may be served from the [`ToolResultCache`](super::tool_cache::ToolResultCache).
+///
+/// The cache is keyed on tool name and arguments only, so tools whose
+/// output depends on the calling agent (e.g. `skills.list`) never qualify.
+pub fn is_idempotent(skill_engine: &SkillEngine, tool_name: &str) -> bool {
+    match tool_name {
+        "file.read" | "file.list" | "skill.read_doc" | "skill.read_resource" => true,
+        _ => skill_engine
+            .get(tool_name)
+            .is_some_and(|skill| skill.spec().idempotent),
+    }
+}
 
+async fn dispatch_uncached(
+    state: &AppState,
+    tool_name: &str,
+    arguments: &Value,
+    session_key: Option<&str>,
+    agent_ctx: Option<&AgentContext>,
+    fan_out: &FanOut,
+) -> (String, bool) {
     // Handle MCP tools (mcp:{server_id}:{tool_name}).
     if let Some(rest) = tool_name.strip_prefix("mcp:") {
         return dispatch_mcp_tool(state, rest, arguments).await;
@@ -432,9 +623,22 @@ pub async fn dispatch_tool(
         "file.list" => dispatch_file_list(state, arguments).await,
         "skill.read_doc" => dispatch_skill_read_doc(state, arguments),
         "skill.read_resource" => dispatch_skill_read_resource(state, arguments),
+        "skills.list" => {
+            let skills = agent_ctx.map_or(&state.skills, |ctx| &ctx.skills);
+            (skills.render_ready_index(), false)
+        }
         "memory.search" => dispatch_memory_search(state, arguments).await,
         "memory.ingest" => dispatch_memory_ingest(state, arguments, agent_ctx, session_key).await,
-        "agent.run" => dispatch_agent_run(state, arguments, session_key, agent_ctx).await,
+        "memory.remember" => {
+            dispatch_memory_remember(state, arguments, agent_ctx, session_key).await
+        }
+        "memory.forget" => dispatch_memory_forget(state, arguments, session_key).await,
+        "agent.run" => {
+            dispatch_agent_run(state, arguments, session_key, agent_ctx, fan_out).await
+        }
+        "agent.run_parallel" => {
+            dispatch_agent_run_parallel(state, arguments, session_key, agent_ctx, fan_out).await
+        }
         "agent.list" => dispatch_agent_list(state),
         "web.search" => stub_tool("web.search", "Web search is not yet configured. Use exec with curl or a search CLI tool as an alternative."),
         "http.request" => stub_tool("http.request", "HTTP requests are not yet configured. Use exec with curl as an alternative."),
@@ -476,65 +680,26 @@ async fn dispatch_exec(
     // Approval gate — commands matching approval_patterns require human approval.
     if state.approval_command_set.is_match(&req.command) {
         tracing::info!(command = %req.command, "exec command requires approval");
-
-        let sk = session_key.unwrap_or("anonymous").to_string();
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        let approval_id = uuid::Uuid::new_v4();
-
-        let pending = crate::runtime::approval::PendingApproval {
-            id: approval_id,
-            command: req.command.clone(),
-            session_key: sk.clone(),
-            created_at: chrono::Utc::now(),
-            respond: tx,
-        };
-        state.approval_store.insert(pending);
-
-        // Emit SSE event to all run subscribers so the dashboard can show the dialog.
-        // We broadcast on a well-known "global" run ID derived from the approval UUID
-        // as well as attempt to emit on any active run for the session.
-        let event = crate::runtime::runs::RunEvent::ExecApprovalRequired {
-            approval_id,
-            command: req.command.clone(),
-            session_key: sk,
-        };
-        // Best-effort broadcast: emit on all currently tracked run channels.
-        // The SSE endpoint for runs will pick this up.
-        state.run_store.emit(&approval_id, event);
-
-        // Await human decision with a timeout.
-        let timeout = state.approval_store.timeout();
-        match tokio::time::timeout(timeout, rx).await {
-            Ok(Ok(crate::runtime::approval::ApprovalDecision::Approved)) => {
-                tracing::info!(approval_id = %approval_id, "exec command approved");
+        match await_approval(state, req.command.clone(), session_key).await {
+            Ok(()) => {
                 // Fall through to execute the command.
             }
-            Ok(Ok(crate::runtime::approval::ApprovalDecision::Denied { reason })) => {
+            Err(ApprovalRefused::Denied(reason)) => {
                 let msg = match reason {
                     Some(r) => format!("command denied by human reviewer: {r}"),
                     None => "command denied by human reviewer".to_owned(),
                 };
-                tracing::warn!(approval_id = %approval_id, "exec command denied");
                 return (msg, true);
             }
-            Ok(Err(_)) => {
-                // Sender dropped (store cleaned up) — treat as timeout.
-                state.approval_store.remove_expired(&approval_id);
-                tracing::warn!(approval_id = %approval_id, "exec approval channel dropped");
+            Err(ApprovalRefused::ChannelClosed) => {
                 return (
                     "exec approval timed out (reviewer channel closed)".to_owned(),
                     true,
                 );
             }
-            Err(_) => {
-                // Timeout elapsed — clean up and reject.
-                state.approval_store.remove_expired(&approval_id);
-                tracing::warn!(approval_id = %approval_id, "exec approval timed out");
+            Err(ApprovalRefused::TimedOut(timeout)) => {
                 return (
-                    format!(
-                        "exec approval timed out after {}s",
-                        timeout.as_secs()
-                    ),
+                    format!("exec approval timed out after {}s", timeout.as_secs()),
                     true,
                 );
             }
@@ -546,6 +711,73 @@ async fn dispatch_exec(
     (json, false)
 }
 
+/// Why an approval request did not end in approval.
+enum ApprovalRefused {
+    Denied(Option<String>),
+    /// The pending entry was dropped before a decision.
+    ChannelClosed,
+    TimedOut(std::time::Duration),
+}
+
+/// Park `command` in the approval store, announce it on the run event
+/// stream (so the dashboard can show the dialog), and wait for a human
+/// decision, bounded by the store's timeout.
+async fn await_approval(
+    state: &AppState,
+    command: String,
+    session_key: Option<&str>,
+) -> Result<(), ApprovalRefused> {
+    let sk = session_key.unwrap_or("anonymous").to_string();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let approval_id = uuid::Uuid::new_v4();
+
+    let pending = crate::runtime::approval::PendingApproval {
+        id: approval_id,
+        command: command.clone(),
+        session_key: sk.clone(),
+        created_at: chrono::Utc::now(),
+        respond: tx,
+    };
+    state.approval_store.insert(pending);
+
+    // Emit SSE event to all run subscribers so the dashboard can show the dialog.
+    // We broadcast on a well-known "global" run ID derived from the approval UUID
+    // as well as attempt to emit on any active run for the session.
+    let event = crate::runtime::runs::RunEvent::ExecApprovalRequired {
+        approval_id,
+        command,
+        session_key: sk,
+    };
+    // Best-effort broadcast: emit on all currently tracked run channels.
+    // The SSE endpoint for runs will pick this up.
+    state.run_store.emit(&approval_id, event);
+
+    // Await human decision with a timeout.
+    let timeout = state.approval_store.timeout();
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(crate::runtime::approval::ApprovalDecision::Approved)) => {
+            tracing::info!(approval_id = %approval_id, "approval granted");
+            Ok(())
+        }
+        Ok(Ok(crate::runtime::approval::ApprovalDecision::Denied { reason })) => {
+            tracing::warn!(approval_id = %approval_id, "approval denied");
+            Err(ApprovalRefused::Denied(reason))
+        }
+        Ok(Err(_)) => {
+            // Sender dropped (store cleaned up) — treat as timeout.
+            state.approval_store.remove_expired(&approval_id);
+            tracing::warn!(approval_id = %approval_id, "approval channel dropped");
+            Err(ApprovalRefused::ChannelClosed)
+        }
+        Err(_) => {
+            // Timeout elapsed — clean up and reject.
+            state.approval_store.remove_expired(&approval_id);
+            tracing::warn!(approval_id = %approval_id, "approval timed out");
+            Err(ApprovalRefused::TimedOut(timeout))
+        }
+    }
+}
+
 async fn dispatch_process(state: &AppState, arguments: &Value) -> (String, bool) {
     let req: ProcessRequest = match ProcessRequest::deserialize(arguments) {
         Ok(r) => r,
@@ -751,11 +983,154 @@ async fn dispatch_memory_ingest(
     }
 }
 
+/// Memory `source` for facts the agent was asked to remember, as opposed
+/// to `auto_capture`.  List it in `serial_memory.user_facts.preferred_sources`
+/// to rank these first.
+const EXPLICIT_MEMORY_SOURCE: &str = "explicit";
+
+/// Build the ingest request for `memory.remember`: the fact under the
+/// explicit source, with provenance (session, capture kind, sub-agent
+/// fields and tags) in the metadata.
+fn remember_request(
+    arguments: &Value,
+    agent_ctx: Option<&AgentContext>,
+    session_key: Option<&str>,
+) -> Result<sa_memory::MemoryIngestRequest, String> {
+    let fact = arguments
+        .get("fact")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .unwrap_or("");
+    if fact.is_empty() {
+        return Err("memory.remember requires a non-empty 'fact'".to_owned());
+    }
+    let tags: Vec<&str> = arguments
+        .get("tags")
+        .and_then(|v| v.as_array())
+        .map(|tags| tags.iter().filter_map(|t| t.as_str()).collect())
+        .unwrap_or_default();
+
+    let session_key = session_key.unwrap_or("");
+    let mut metadata =
+        super::agent::provenance_metadata(agent_ctx, session_key, "").unwrap_or_default();
+    metadata.insert("sa.session_key".into(), serde_json::json!(session_key));
+    metadata.insert(
+        "sa.capture".into(),
+        serde_json::json!(EXPLICIT_MEMORY_SOURCE),
+    );
+    if !tags.is_empty() {
+        metadata.insert("sa.tags".into(), serde_json::json!(tags));
+    }
+
+    Ok(sa_memory::MemoryIngestRequest {
+        content: fact.to_owned(),
+        source: Some(EXPLICIT_MEMORY_SOURCE.into()),
+        session_id: None,
+        metadata: Some(metadata),
+        extract_entities: Some(true),
+    })
+}
+
+async fn dispatch_memory_remember(
+    state: &AppState,
+    arguments: &Value,
+    agent_ctx: Option<&AgentContext>,
+    session_key: Option<&str>,
+) -> (String, bool) {
+    let req = match remember_request(arguments, agent_ctx, session_key) {
+        Ok(req) => req,
+        Err(e) => return (e, true),
+    };
+    match state.memory.ingest(req).await {
+        Ok(resp) => {
+            let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+            (json, false)
+        }
+        Err(e) => (format!("memory remember error: {e}"), true),
+    }
+}
+
+/// Min similarity for a memory to count as matching a forget request.
+const FORGET_MIN_SIMILARITY: f64 = 0.75;
+
+async fn dispatch_memory_forget(
+    state: &AppState,
+    arguments: &Value,
+    session_key: Option<&str>,
+) -> (String, bool) {
+    let query = arguments
+        .get("query")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    if query.is_empty() {
+        return (
+            "memory.forget requires a non-empty 'query'".to_owned(),
+            true,
+        );
+    }
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(5, |v| v.clamp(1, 20) as u32);
+
+    let req = sa_memory::ForgetRequest {
+        query,
+        limit,
+        min_similarity: FORGET_MIN_SIMILARITY,
+        tombstone: state.config.serial_memory.soft_delete.enabled,
+    };
+    let outcome = sa_memory::forget_matching(state.memory.as_ref(), &req, |candidates| {
+        approve_forget(state, session_key, candidates)
+    })
+    .await;
+
+    match outcome {
+        Ok(outcome) => {
+            let is_error = matches!(outcome, sa_memory::ForgetOutcome::Denied { .. });
+            let json = serde_json::to_string_pretty(&outcome).unwrap_or_default();
+            (json, is_error)
+        }
+        Err(e) => (format!("memory forget error: {e}"), true),
+    }
+}
+
+/// Ask a human to approve deleting `candidates`; `Err` carries the reason
+/// reported back to the model.
+async fn approve_forget(
+    state: &AppState,
+    session_key: Option<&str>,
+    candidates: Vec<sa_memory::ForgetCandidate>,
+) -> Result<Vec<sa_memory::ForgetCandidate>, String> {
+    let listing: Vec<String> = candidates
+        .iter()
+        .map(|c| format!("[{}] {}", c.id, c.content))
+        .collect();
+    let command = format!(
+        "memory.forget: delete {} memor{}: {}",
+        candidates.len(),
+        if candidates.len() == 1 { "y" } else { "ies" },
+        listing.join("; ")
+    );
+    match await_approval(state, command, session_key).await {
+        Ok(()) => Ok(candidates),
+        Err(ApprovalRefused::Denied(reason)) => {
+            Err(reason.unwrap_or_else(|| "denied by human reviewer".to_owned()))
+        }
+        Err(ApprovalRefused::ChannelClosed) => Err("approval channel closed".to_owned()),
+        Err(ApprovalRefused::TimedOut(timeout)) => {
+            Err(format!("approval timed out after {}s", timeout.as_secs()))
+        }
+    }
+}
+
 async fn dispatch_agent_run(
     state: &AppState,
     arguments: &Value,
     session_key: Option<&str>,
     parent_agent: Option<&AgentContext>,
+    fan_out: &FanOut,
 ) -> (String, bool) {
     let agent_id = match arguments.get("agent_id").and_then(|v| v.as_str()) {
         Some(id) => id,
@@ -772,7 +1147,44 @@ async fn dispatch_agent_run(
 
     let parent_key = session_key.unwrap_or("anonymous");
 
-    super::agent::run_agent(state, agent_id, task, model, parent_key, parent_agent).await
+    let outcome =
+        super::agent::run_agent(state, agent_id, task, model, parent_key, parent_agent, fan_out)
+            .await;
+    (outcome.output, outcome.is_error)
+}
+
+async fn dispatch_agent_run_parallel(
+    state: &AppState,
+    arguments: &Value,
+    session_key: Option<&str>,
+    parent_agent: Option<&AgentContext>,
+    fan_out: &FanOut,
+) -> (String, bool) {
+    let jobs: Vec<super::agent::AgentJob> = match arguments.get("runs") {
+        Some(runs) => match serde_json::from_value(runs.clone()) {
+            Ok(jobs) => jobs,
+            Err(e) => return (format!("invalid runs: {e}"), true),
+        },
+        None => return ("missing required argument: runs".into(), true),
+    };
+    if jobs.is_empty() {
+        return ("runs must not be empty".into(), true);
+    }
+
+    let parent_key = session_key.unwrap_or("anonymous");
+    let results =
+        super::agent::run_agents_parallel(state, jobs, parent_key, parent_agent, fan_out).await;
+    let errors = results.iter().filter(|r| r.is_error).count();
+    let all_failed = errors == results.len();
+    (
+        serde_json::json!({
+            "results": results,
+            "count": results.len(),
+            "errors": errors,
+        })
+        .to_string(),
+        all_failed,
+    )
 }
 
 fn dispatch_agent_list(state: &AppState) -> (String, bool) {
@@ -796,7 +1208,7 @@ fn dispatch_agent_list(state: &AppState) -> (String, bool) {
             let runtime = manager.get(&id);
             match runtime {
                 Some(r) => {
-                    let effective_count = manager.effective_tool_count(&id, &tool_refs);
+                    let effective_tools = manager.effective_tools(&id, &tool_refs);
                     let resolved_model = r
                         .config
                         .models
@@ -807,16 +1219,21 @@ fn dispatch_agent_list(state: &AppState) -> (String, bool) {
                         "id": id,
                         "tools_allow": r.config.tool_policy.allow,
                         "tools_deny": r.config.tool_policy.deny,
-                        "effective_tools_count": effective_count,
+                        "tools_rules": r.config.tool_policy.rules,
+                        "tools_default": r.config.tool_policy.default,
+                        "effective_tools_count": effective_tools.len(),
+                        "effective_tools": effective_tools,
                         "models": r.config.models,
                         "resolved_executor": resolved_model,
                         "memory_mode": r.config.memory_mode,
                         "limits": {
                             "max_depth": r.config.limits.max_depth,
                             "max_children_per_turn": r.config.limits.max_children_per_turn,
+                            "max_concurrent_children": r.config.limits.max_concurrent_children,
                             "max_duration_ms": r.config.limits.max_duration_ms,
                         },
                         "compaction_enabled": r.config.compaction_enabled,
+                        "tool_choice": r.config.tool_choice,
                     })
                 }
                 None => serde_json::json!({ "id": id }),
@@ -957,3 +1374,202 @@ async fn dispatch_to_node(
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn def(name: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.into(),
+            description: String::new(),
+            parameters: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    fn node(id: &str, caps: &[&str]) -> NodeInfo {
+        NodeInfo {
+            node_id: id.into(),
+            node_type: "test".into(),
+            name: id.into(),
+            capabilities: caps.iter().map(|c| c.to_string()).collect(),
+            version: "1".into(),
+            tags: Vec::new(),
+            session_id: "s".into(),
+            connected_at: Utc::now(),
+            last_seen: Utc::now(),
+        }
+    }
+
+    fn mcp_tool(name: &str) -> McpToolDef {
+        McpToolDef {
+            name: name.into(),
+            description: format!("{name} via MCP"),
+            input_schema: serde_json::json!({ "type": "object", "properties": { "q": { "type": "string" } } }),
+        }
+    }
+
+    fn names(defs: &[ToolDefinition]) -> Vec<&str> {
+        defs.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn includes_mcp_and_node_tools() {
+        let search = mcp_tool("search");
+        let mut defs = vec![def("exec")];
+        let shadowed = append_dynamic_tools(
+            &mut defs,
+            &[("github", &search)],
+            &[node("mac", &["macos.clipboard.get", "exec"])],
+        );
+        assert_eq!(
+            names(&defs),
+            ["exec", "mcp:github:search", "macos.clipboard.get"]
+        );
+        assert_eq!(defs[1].parameters, search.input_schema);
+        assert_eq!(
+            shadowed,
+            [ShadowedTool {
+                name: "exec".into(),
+                node_id: "mac".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn report_respects_tool_policy() {
+        let search = mcp_tool("search");
+        let mut defs = vec![def("exec"), def("file.read"), def("file.write")];
+        append_dynamic_tools(
+            &mut defs,
+            &[("github", &search)],
+            &[node("mac", &["macos.clipboard.get"])],
+        );
+        let policy = ToolPolicy {
+            allow: vec!["file.*".into(), "mcp:*".into(), "macos.*".into()],
+            deny: vec!["file.write".into()],
+            ..Default::default()
+        };
+
+        let report = resolve_report(defs.clone(), Vec::new(), Some(&policy));
+        assert_eq!(
+            names(&report.tools),
+            ["file.read", "mcp:github:search", "macos.clipboard.get"]
+        );
+        assert_eq!(report.denied_by_policy, ["exec", "file.write"]);
+
+        let unrestricted = resolve_report(defs, Vec::new(), None);
+        assert_eq!(unrestricted.tools.len(), 5);
+        assert!(unrestricted.denied_by_policy.is_empty());
+    }
+
+    #[test]
+    fn node_disconnect_invalidates_cached_definitions() {
+        use crate::nodes::registry::{ConnectedNode, NodeRegistry};
+        use crate::runtime::tool_cache::ToolDefsCache;
+
+        let registry = NodeRegistry::new();
+        let (sink, _rx) = tokio::sync::mpsc::channel(1);
+        registry.register(ConnectedNode {
+            node_id: "mac".into(),
+            node_type: "macos".into(),
+            name: "mac".into(),
+            capabilities: vec!["macos.clipboard.get".into()],
+            version: "1".into(),
+            tags: Vec::new(),
+            session_id: "s".into(),
+            connected_at: Utc::now(),
+            last_seen: Utc::now(),
+            sink,
+        });
+
+        let cache = ToolDefsCache::new();
+        let build = || {
+            let generation = ToolDefsGeneration {
+                nodes: registry.generation(),
+                mcp: 0,
+            };
+            cache.get_or_build("__none__", generation, || {
+                let mut defs = vec![def("exec")];
+                append_dynamic_tools(&mut defs, &[], &registry.list());
+                defs
+            })
+        };
+
+        let first = build();
+        assert_eq!(names(&first), ["exec", "macos.clipboard.get"]);
+        assert!(
+            Arc::ptr_eq(&first, &build()),
+            "unchanged topology is cached"
+        );
+
+        registry.remove("mac");
+        assert_eq!(names(&build()), ["exec"]);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn agent_scoped_tools_are_not_cacheable() {
+        let engine = SkillEngine::new();
+        assert!(is_idempotent(&engine, "file.read"));
+        assert!(!is_idempotent(&engine, "skills.list"));
+    }
+
+    #[test]
+    fn report_flags_duplicate_names() {
+        let defs = vec![def("exec"), def("web.search"), def("exec"), def("exec")];
+        let report = resolve_report(defs, Vec::new(), None);
+        assert_eq!(report.duplicate_names, ["exec"]);
+    }
+
+    #[test]
+    fn remember_ingests_under_the_explicit_source() {
+        let args = serde_json::json!({ "fact": "  flight is at 6pm ", "tags": ["travel"] });
+        let req = remember_request(&args, None, Some("sk-1")).unwrap();
+
+        assert_eq!(req.content, "flight is at 6pm");
+        assert_eq!(req.source.as_deref(), Some("explicit"));
+        let meta = req.metadata.unwrap();
+        assert_eq!(meta["sa.capture"], "explicit");
+        assert_eq!(meta["sa.session_key"], "sk-1");
+        assert_eq!(meta["sa.tags"], serde_json::json!(["travel"]));
+
+        let empty = serde_json::json!({ "fact": "  " });
+        assert!(remember_request(&empty, None, None).is_err());
+    }
+
+    #[test]
+    fn remember_records_sub_agent_provenance() {
+        use crate::runtime::agent::AgentRuntime;
+        use crate::workspace::files::WorkspaceReader;
+        use sa_domain::config::{AgentConfig, AgentLimits, MemoryMode, ToolPolicy};
+        use sa_skills::registry::SkillsRegistry;
+        use std::sync::Arc;
+
+        let rt = AgentRuntime {
+            id: "planner".into(),
+            config: AgentConfig {
+                workspace_path: None,
+                skills_path: None,
+                tool_policy: ToolPolicy::default(),
+                models: Default::default(),
+                memory_mode: MemoryMode::Shared,
+                limits: AgentLimits::default(),
+                compaction_enabled: false,
+                tool_choice: Default::default(),
+            },
+            workspace: Arc::new(WorkspaceReader::new(".".into())),
+            skills: Arc::new(SkillsRegistry::empty()),
+        };
+        let ctx = rt.context(None, 1, "main");
+
+        let args = serde_json::json!({ "fact": "prefers window seats" });
+        let req = remember_request(&args, Some(&ctx), Some("sk-2")).unwrap();
+        let meta = req.metadata.unwrap();
+        assert_eq!(meta["sa.agent_id"], "planner");
+        assert_eq!(meta["sa.agent_path"], "main>planner");
+        assert_eq!(meta["sa.capture"], "explicit");
+        assert!(!meta.contains_key("sa.tags"));
+    }
+}
@@ -4,6 +4,7 @@
 //! Entry point: [`run_turn`] spawns the async loop and returns a
 //! channel of [`TurnEvent`]s.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use futures_util::StreamExt;
@@ -12,14 +13,17 @@ use serde_json::Value;
 use tokio::sync::mpsc;
 use tracing::Instrument;
 
+use sa_domain::config::{AgentLimits, ModelPricing};
 use sa_domain::stream::{StreamEvent, Usage};
-use sa_domain::tool::{Message, ToolCall, ToolDefinition};
+use sa_domain::tool::{ContentPart, Message, ToolCall, ToolChoice, ToolDefinition};
 
 use crate::state::AppState;
 
 use super::agent;
 use super::cancel::CancelToken;
 use super::compact;
+use super::interceptor::InterceptContext;
+use super::quota::QuotaTracker;
 use super::runs;
 use super::tools;
 use super::{
@@ -43,6 +47,8 @@ pub(super) struct TurnContext {
     tool_defs: Arc<Vec<ToolDefinition>>,
     /// Model name selected by the smart router (if any).
     router_model: Option<String>,
+    /// Sub-agent budget shared by every tool call of the turn.
+    fan_out: agent::FanOut,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -120,6 +126,17 @@ pub struct TurnInput {
     pub agent: Option<agent::AgentContext>,
     /// Routing profile override. None = use default.
     pub routing_profile: Option<sa_domain::config::RoutingProfile>,
+    /// Prior conversation supplied by the caller (stateless clients).
+    /// When set it is used verbatim instead of the stored transcript;
+    /// only the new exchange is persisted.
+    pub history: Option<Vec<Message>>,
+    /// Images sent with `user_message` (not persisted to the transcript).
+    pub images: Vec<ContentPart>,
+    /// Sampling seed for reproducible turns.  When set the turn also runs
+    /// at temperature 0; the seed is recorded on the run.
+    pub seed: Option<u64>,
+    /// Stop sequences sent with every LLM call of the turn.
+    pub stop: Vec<String>,
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -148,6 +165,8 @@ pub fn run_turn(
     );
     run.model = input.model.clone();
     run.agent_id = input.agent.as_ref().map(|a| a.agent_id.clone());
+    run.depth = input.agent.as_ref().map_or(0, |a| a.depth);
+    run.seed = input.seed;
     run.status = runs::RunStatus::Running;
     let run_id = run.run_id;
     state.run_store.insert(run);
@@ -180,21 +199,7 @@ pub fn run_turn(
 
         if let Err(e) = result {
             let err_msg = e.to_string();
-            state_ref.run_store.update(&run_id, |r| {
-                r.error = Some(err_msg.clone());
-                r.finish(runs::RunStatus::Failed);
-            });
-            if let Some(run) = state_ref.run_store.get(&run_id) {
-                state_ref.run_store.persist(&run);
-            }
-            state_ref.run_store.emit(
-                &run_id,
-                runs::RunEvent::RunStatus {
-                    run_id,
-                    status: runs::RunStatus::Failed,
-                },
-            );
-            state_ref.run_store.cleanup_channel(&run_id);
+            fail_run(&state_ref.run_store, run_id, err_msg.clone());
             let _ = tx
                 .send(TurnEvent::Error {
                     message: err_msg,
@@ -210,6 +215,127 @@ pub fn run_turn(
 // Extracted helpers
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// Sampling temperature for ordinary turns.
+const DEFAULT_TEMPERATURE: f32 = 0.2;
+
+/// Tool choice for LLM call `loop_idx` given the agent's default.  A
+/// forced choice applies to the first call only; after that the model must
+/// be free to answer in text or the tool loop would never end.
+fn step_tool_choice(default: &ToolChoice, loop_idx: usize) -> ToolChoice {
+    match default {
+        ToolChoice::Required | ToolChoice::Specific(_) if loop_idx > 0 => ToolChoice::Auto,
+        other => other.clone(),
+    }
+}
+
+/// Build the provider request for one LLM call of the turn.  A seeded
+/// turn runs at temperature 0 so the seed actually pins the output.
+fn llm_request(
+    input: &TurnInput,
+    loop_idx: usize,
+    messages: Vec<Message>,
+    tools: Vec<ToolDefinition>,
+    model: Option<String>,
+) -> sa_providers::ChatRequest {
+    let tool_choice = input
+        .agent
+        .as_ref()
+        .map(|a| step_tool_choice(&a.tool_choice, loop_idx))
+        .unwrap_or_default();
+    let temperature = if input.seed.is_some() {
+        0.0
+    } else {
+        DEFAULT_TEMPERATURE
+    };
+    sa_providers::ChatRequest {
+        messages,
+        tools,
+        temperature: Some(temperature),
+        max_tokens: None,
+        response_format: input.response_format.clone().unwrap_or_default(),
+        model,
+        seed: input.seed,
+        tool_choice,
+        stop: input.stop.clone(),
+    }
+}
+
+/// Mark a run completed with its token usage: estimate cost from the
+/// model's pricing, persist the run, emit completion events, and charge
+/// the usage to the run's agent quota.
+pub(crate) fn complete_run(
+    run_store: &runs::RunStore,
+    quota_tracker: &QuotaTracker,
+    pricing: &HashMap<String, ModelPricing>,
+    run_id: uuid::Uuid,
+    usage: &Usage,
+    output: &str,
+) {
+    run_store.update(&run_id, |r| {
+        r.input_tokens = usage.prompt_tokens;
+        r.output_tokens = usage.completion_tokens;
+        r.total_tokens = usage.total_tokens;
+        r.output_preview = Some(truncate_str(output, 200));
+        // Compute estimated cost from per-model pricing config.
+        if let Some(model_name) = r.model.as_deref() {
+            if let Some(pricing) = pricing.get(model_name) {
+                r.estimated_cost_usd =
+                    pricing.estimate_cost(usage.prompt_tokens, usage.completion_tokens);
+            }
+        }
+        r.finish(runs::RunStatus::Completed);
+    });
+    let run = run_store.get(&run_id);
+    if let Some(run) = &run {
+        run_store.persist(run);
+    }
+    run_store.emit(
+        &run_id,
+        runs::RunEvent::RunStatus {
+            run_id,
+            status: runs::RunStatus::Completed,
+        },
+    );
+    run_store.emit(
+        &run_id,
+        runs::RunEvent::Usage {
+            run_id,
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        },
+    );
+    run_store.cleanup_channel(&run_id);
+
+    // ── Record usage against quota tracker ─────────────────
+    if let Some(run) = run {
+        quota_tracker.record_usage(
+            run.agent_id.as_deref(),
+            usage.total_tokens as u64,
+            run.estimated_cost_usd,
+        );
+    }
+}
+
+/// Mark a run failed with `error`, persist it, and emit the final status.
+pub(crate) fn fail_run(run_store: &runs::RunStore, run_id: uuid::Uuid, error: String) {
+    run_store.update(&run_id, |r| {
+        r.error = Some(error);
+        r.finish(runs::RunStatus::Failed);
+    });
+    if let Some(run) = run_store.get(&run_id) {
+        run_store.persist(&run);
+    }
+    run_store.emit(
+        &run_id,
+        runs::RunEvent::RunStatus {
+            run_id,
+            status: runs::RunStatus::Failed,
+        },
+    );
+    run_store.cleanup_channel(&run_id);
+}
+
 /// Handle a cancellation event: update the run store, persist a
 /// transcript marker, and send a [`TurnEvent::Stopped`] to the caller.
 ///
@@ -301,56 +427,14 @@ async fn finalize_run_success(
         total_usage.completion_tokens as u64,
     );
 
-    // ── Finalize run (success) ───────────────────────────
-    let pricing_map = &state.config.llm.pricing;
-    state.run_store.update(&run_id, |r| {
-        r.input_tokens = total_usage.prompt_tokens;
-        r.output_tokens = total_usage.completion_tokens;
-        r.total_tokens = total_usage.total_tokens;
-        r.output_preview = Some(truncate_str(text_buf, 200));
-        // Compute estimated cost from per-model pricing config.
-        if let Some(model_name) = r.model.as_deref() {
-            if let Some(pricing) = pricing_map.get(model_name) {
-                r.estimated_cost_usd =
-                    pricing.estimate_cost(total_usage.prompt_tokens, total_usage.completion_tokens);
-            }
-        }
-        r.finish(runs::RunStatus::Completed);
-    });
-    if let Some(run) = state.run_store.get(&run_id) {
-        state.run_store.persist(&run);
-    }
-    state.run_store.emit(
-        &run_id,
-        runs::RunEvent::RunStatus {
-            run_id,
-            status: runs::RunStatus::Completed,
-        },
-    );
-    state.run_store.emit(
-        &run_id,
-        runs::RunEvent::Usage {
-            run_id,
-            input_tokens: total_usage.prompt_tokens,
-            output_tokens: total_usage.completion_tokens,
-            total_tokens: total_usage.total_tokens,
-        },
+    complete_run(
+        &state.run_store,
+        &state.quota_tracker,
+        &state.config.llm.pricing,
+        run_id,
+        total_usage,
+        text_buf,
     );
-    state.run_store.cleanup_channel(&run_id);
-
-    // ── Record usage against quota tracker ─────────────────
-    {
-        let estimated_cost = state
-            .run_store
-            .get(&run_id)
-            .map(|r| r.estimated_cost_usd)
-            .unwrap_or(0.0);
-        state.quota_tracker.record_usage(
-            input.agent.as_ref().map(|a| a.agent_id.as_str()),
-            total_usage.total_tokens as u64,
-            estimated_cost,
-        );
-    }
 
     // ── Memory auto-capture (fire-and-forget) ─────────────
     fire_auto_capture(state, input, text_buf);
@@ -378,21 +462,11 @@ async fn run_turn_inner(
                 exceeded.kind, exceeded.used, exceeded.limit,
             );
             let _ = tx.send(TurnEvent::Error { message: msg }).await;
-            state.run_store.update(&run_id, |r| {
-                r.error = Some(format!("quota exceeded: {}", exceeded.kind));
-                r.finish(runs::RunStatus::Failed);
-            });
-            if let Some(run) = state.run_store.get(&run_id) {
-                state.run_store.persist(&run);
-            }
-            state.run_store.emit(
-                &run_id,
-                runs::RunEvent::RunStatus {
-                    run_id,
-                    status: runs::RunStatus::Failed,
-                },
+            fail_run(
+                &state.run_store,
+                run_id,
+                format!("quota exceeded: {}", exceeded.kind),
             );
-            state.run_store.cleanup_channel(&run_id);
             return Ok(());
         }
     }
@@ -404,6 +478,7 @@ async fn run_turn_inner(
         mut messages,
         tool_defs,
         router_model,
+        fan_out,
     } = ctx;
 
     // ── Phase 2: Tool loop ───────────────────────────────────────────────
@@ -477,17 +552,21 @@ async fn run_turn_inner(
             router_model.clone()
         };
 
-        let req = sa_providers::ChatRequest {
-            messages: messages.clone(),
-            tools: (*tool_defs).clone(),
-            temperature: Some(0.2),
-            max_tokens: None,
-            response_format: input
-                .response_format
-                .clone()
-                .unwrap_or_default(),
-            model: effective_model,
+        let mut req = llm_request(
+            &input,
+            loop_idx,
+            messages.clone(),
+            (*tool_defs).clone(),
+            effective_model,
+        );
+        let intercept_ctx = InterceptContext {
+            session_key: &input.session_key,
+            run_id,
+            agent_id: input.agent.as_ref().map(|a| a.agent_id.as_str()),
         };
+        state
+            .chat_interceptors
+            .before_request(&intercept_ctx, &mut req);
 
         let llm_call_span = tracing::info_span!(
             "llm.call",
@@ -501,12 +580,24 @@ async fn run_turn_inner(
         // consumption + token recording) so OTel captures the full duration.
         let _llm_guard = llm_call_span.enter();
 
-        let mut stream = provider.chat_stream(&req).await?;
+        let breakers = state.llm.breakers();
+        let provider_id = provider.provider_id();
+        super::wait_for_rate_limit(state.llm.rate_limits(), provider_id).await?;
+        let mut stream = match provider.chat_stream(&req).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                if !state.llm.rate_limits().record_error(provider_id, &e) {
+                    breakers.record_failure(provider_id);
+                }
+                return Err(e.into());
+            }
+        };
 
         // Accumulate the response.
         let mut text_buf = String::new();
         let mut pending_tool_calls: Vec<ToolCall> = Vec::new();
         let mut turn_usage: Option<Usage> = None;
+        let mut turn_finish_reason: Option<String> = None;
         let mut was_cancelled = false;
 
         // Tool call assembly state.
@@ -522,7 +613,13 @@ async fn run_turn_inner(
                 break;
             }
 
-            let event = event_result?;
+            let event = match event_result {
+                Ok(event) => event,
+                Err(e) => {
+                    breakers.record_failure(provider_id);
+                    return Err(e.into());
+                }
+            };
             match event {
                 StreamEvent::Thinking { text } => {
                     let _ = tx
@@ -569,16 +666,21 @@ async fn run_turn_inner(
                 }
                 StreamEvent::Done {
                     usage,
-                    finish_reason: _,
+                    finish_reason,
                 } => {
                     turn_usage = usage;
+                    turn_finish_reason = finish_reason;
                 }
                 StreamEvent::Error { message } => {
+                    breakers.record_failure(provider_id);
                     let _ = tx.send(TurnEvent::Error { message }).await;
                     return Ok(());
                 }
             }
         }
+        if !was_cancelled {
+            breakers.record_success(provider_id);
+        }
 
         // Record token usage while the span is still entered.
         if let Some(u) = &turn_usage {
@@ -648,6 +750,26 @@ async fn run_turn_inner(
             });
         }
 
+        // Let interceptors observe (and rewrite) what the model produced.
+        if !state.chat_interceptors.is_empty() {
+            let mut resp = sa_providers::ChatResponse {
+                content: std::mem::take(&mut text_buf),
+                tool_calls: std::mem::take(&mut pending_tool_calls),
+                usage: turn_usage.take(),
+                model: req
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| provider.provider_id().to_owned()),
+                finish_reason: turn_finish_reason.take(),
+            };
+            state
+                .chat_interceptors
+                .after_response(&intercept_ctx, &mut resp);
+            text_buf = resp.content;
+            pending_tool_calls = resp.tool_calls;
+            turn_usage = resp.usage;
+        }
+
         // Accumulate usage.
         if let Some(u) = &turn_usage {
             total_usage.prompt_tokens += u.prompt_tokens;
@@ -767,7 +889,9 @@ async fn run_turn_inner(
                     &tc.tool_name,
                     &tc.arguments,
                     Some(&input.session_key),
+                    None,
                     input.agent.as_ref(),
+                    &fan_out,
                 )
                 .instrument(tool_span)
             })
@@ -795,6 +919,7 @@ async fn run_turn_inner(
                     n.is_error = is_error;
                 }
             });
+            state.tool_stats.record(&tc.tool_name, is_error, tool_dur);
 
             let _ = tx
                 .send(TurnEvent::ToolResult {
@@ -838,20 +963,87 @@ async fn run_turn_inner(
 // Phase 1 helper
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-/// Phase 1: Resolve the provider, build the system prompt, load and
-/// compact the transcript, assemble messages, and persist the user turn.
+/// Phase 1: Resolve the provider, build the system prompt, load the prior
+/// conversation (supplied, or the compacted transcript), assemble
+/// messages, and persist the user turn.
 ///
 /// Returns a [`TurnContext`] containing everything the tool loop needs.
 async fn prepare_turn_context(
     state: &AppState,
     input: &TurnInput,
 ) -> Result<TurnContext, Box<dyn std::error::Error + Send + Sync>> {
-    // 1. Resolve the LLM provider (explicit -> router -> agent models -> global roles -> any).
-    let (provider, resolved_model) = resolve_provider(state, input.model.as_deref(), input.agent.as_ref(), input.routing_profile)?;
+    // 1. Resolve the LLM provider
+    //    (explicit -> session pin -> router -> agent models -> global roles -> any).
+    let pinned_model = state
+        .sessions
+        .get(&input.session_key)
+        .and_then(|entry| entry.pinned_model);
+    let (provider, resolved_model) = resolve_provider(
+        state,
+        input.model.as_deref(),
+        input.agent.as_ref(),
+        input.routing_profile,
+        pinned_model.as_deref(),
+    )?;
 
     // 2. Build system context (agent-scoped workspace/skills if present).
-    let system_prompt = build_system_context(state, input.agent.as_ref()).await;
+    let system_prompt =
+        build_system_context(state, input.agent.as_ref(), &input.user_message).await;
+
+    // 3–4. Prior conversation: caller-supplied messages verbatim, or the
+    //      stored transcript (compacted if needed).
+    let history = match &input.history {
+        Some(messages) => messages.clone(),
+        None => load_transcript_history(state, input, &provider).await,
+    };
+
+    // 5. Build the tool definitions (filtered by agent tool policy).
+    let tool_policy = input.agent.as_ref().map(|a| &a.tool_policy);
+    let tool_defs = tools::build_tool_definitions(state, tool_policy);
+
+    // 6. Build conversation messages.
+    let mut messages = Vec::new();
+    messages.push(Message::system(&system_prompt));
+    messages.extend(history);
+    messages.push(Message::user_with_images(
+        &input.user_message,
+        input.images.clone(),
+    ));
+
+    // 7. Persist user message to transcript.
+    persist_transcript(
+        &state.transcripts,
+        &input.session_id,
+        "user",
+        &input.user_message,
+        None,
+        Some(state.sessions.search_index()),
+    )
+    .await;
+
+    // 8. Sub-agent budget: the agent's own limits, or the defaults for the
+    //    master (which has no agent config of its own).
+    let fan_out = input.agent.as_ref().map_or_else(
+        || agent::FanOut::new(&AgentLimits::default()),
+        |a| a.fan_out.clone(),
+    );
+
+    Ok(TurnContext {
+        provider,
+        messages,
+        tool_defs,
+        router_model: resolved_model,
+        fan_out,
+    })
+}
 
+/// Load the session transcript as LLM messages, running auto-compaction
+/// first when it is due.
+async fn load_transcript_history(
+    state: &AppState,
+    input: &TurnInput,
+    provider: &Arc<dyn sa_providers::LlmProvider>,
+) -> Vec<Message> {
     // 3. Load raw transcript and check compaction.
     //    Child agents have compaction disabled by default (short-lived sessions).
     let mut all_lines = load_raw_transcript(&state.transcripts, &input.session_id);
@@ -916,33 +1108,116 @@ async fn prepare_turn_context(
     }
 
     // 4. Convert active transcript lines (after last compaction) to messages.
-    let history = transcript_lines_to_messages(&all_lines[boundary..]);
+    transcript_lines_to_messages(&all_lines[boundary..])
+}
 
-    // 5. Build the tool definitions (filtered by agent tool policy).
-    let tool_policy = input.agent.as_ref().map(|a| &a.tool_policy);
-    let tool_defs = tools::build_tool_definitions(state, tool_policy);
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-    // 6. Build conversation messages.
-    let mut messages = Vec::new();
-    messages.push(Message::system(&system_prompt));
-    messages.extend(history);
-    messages.push(Message::user(&input.user_message));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(seed: Option<u64>) -> TurnInput {
+        TurnInput {
+            session_key: "sk".into(),
+            session_id: "sid".into(),
+            user_message: "hi".into(),
+            model: None,
+            response_format: None,
+            agent: None,
+            routing_profile: None,
+            history: None,
+            images: Vec::new(),
+            seed,
+            stop: Vec::new(),
+        }
+    }
 
-    // 7. Persist user message to transcript.
-    persist_transcript(
-        &state.transcripts,
-        &input.session_id,
-        "user",
-        &input.user_message,
-        None,
-        Some(state.sessions.search_index()),
-    )
-    .await;
+    #[test]
+    fn seed_is_threaded_into_provider_request() {
+        let req = llm_request(&input(Some(7)), 0, Vec::new(), Vec::new(), None);
+        assert_eq!(req.seed, Some(7));
+        assert_eq!(req.temperature, Some(0.0));
+    }
 
-    Ok(TurnContext {
-        provider,
-        messages,
-        tool_defs,
-        router_model: resolved_model,
-    })
+    #[test]
+    fn unseeded_turn_keeps_default_temperature() {
+        let req = llm_request(&input(None), 0, Vec::new(), Vec::new(), None);
+        assert_eq!(req.seed, None);
+        assert_eq!(req.temperature, Some(DEFAULT_TEMPERATURE));
+    }
+
+    #[test]
+    fn stop_sequences_are_threaded_into_provider_request() {
+        let turn = TurnInput {
+            stop: vec!["</json>".into()],
+            ..input(None)
+        };
+        let req = llm_request(&turn, 0, Vec::new(), Vec::new(), None);
+        assert_eq!(req.stop, ["</json>"]);
+    }
+
+    #[test]
+    fn forced_tool_choice_applies_to_first_call_only() {
+        let specific = ToolChoice::Specific("exec".into());
+        assert_eq!(step_tool_choice(&specific, 0), specific);
+        assert_eq!(step_tool_choice(&specific, 1), ToolChoice::Auto);
+        let required = ToolChoice::Required;
+        assert_eq!(step_tool_choice(&required, 0), required);
+        assert_eq!(step_tool_choice(&required, 3), ToolChoice::Auto);
+        assert_eq!(step_tool_choice(&ToolChoice::None, 3), ToolChoice::None);
+
+        let req = llm_request(&input(None), 0, Vec::new(), Vec::new(), None);
+        assert_eq!(req.tool_choice, ToolChoice::Auto, "no agent means auto");
+    }
+
+    #[test]
+    fn completed_run_records_usage_cost_and_quota() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = runs::RunStore::new(dir.path());
+        let quota = QuotaTracker::new(sa_domain::config::QuotaConfig::default());
+        let pricing = HashMap::from([(
+            "gpt-4o".to_string(),
+            ModelPricing {
+                input_per_1m: 1_000_000.0,
+                output_per_1m: 2_000_000.0,
+            },
+        )]);
+        let mut run = runs::Run::new("sk".into(), "sid".into(), "hi");
+        run.model = Some("gpt-4o".into());
+        let run_id = store.insert(run);
+
+        let usage = Usage {
+            prompt_tokens: 3,
+            completion_tokens: 2,
+            total_tokens: 5,
+        };
+        complete_run(&store, &quota, &pricing, run_id, &usage, "done");
+
+        let run = store.get(&run_id).unwrap();
+        assert_eq!(run.status, runs::RunStatus::Completed);
+        assert_eq!(run.total_tokens, 5);
+        assert!((run.estimated_cost_usd - 7.0).abs() < 1e-9);
+        assert_eq!(run.output_preview.as_deref(), Some("done"));
+
+        let status = quota.snapshot();
+        let default = status.iter().find(|s| s.agent_id == "default").unwrap();
+        assert_eq!(default.tokens_used, 5);
+        assert!((default.cost_used_usd - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn failed_run_is_finished_with_its_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = runs::RunStore::new(dir.path());
+        let run_id = store.insert(runs::Run::new("sk".into(), "sid".into(), "hi"));
+
+        fail_run(&store, run_id, "provider down".into());
+
+        let run = store.get(&run_id).unwrap();
+        assert_eq!(run.status, runs::RunStatus::Failed);
+        assert_eq!(run.error.as_deref(), Some("provider down"));
+    }
 }
@@ -0,0 +1,184 @@
+//! Usage reports — token and cost totals over a time window.
+//!
+//! Aggregates persisted [`Run`] records into buckets keyed by day, model
+//! or agent, for chargeback and trend analysis.  Unlike `/v1/metrics`,
+//! which reports running totals, a report covers only the runs that
+//! started inside the requested window.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::runs::Run;
+
+/// Bucket key for runs without a model override (default routing).
+const DEFAULT_MODEL_KEY: &str = "default";
+/// Bucket key for top-level (non-sub-agent) runs.
+const MAIN_AGENT_KEY: &str = "main";
+
+/// Dimension a usage report is grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    /// UTC calendar day of `started_at` (`YYYY-MM-DD`).
+    #[default]
+    Day,
+    Model,
+    Agent,
+}
+
+/// Totals for one group.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageBucket {
+    pub key: String,
+    pub runs: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub estimated_cost_usd: f64,
+}
+
+impl UsageBucket {
+    fn add(&mut self, run: &Run) {
+        self.runs += 1;
+        self.input_tokens += u64::from(run.input_tokens);
+        self.output_tokens += u64::from(run.output_tokens);
+        self.total_tokens += u64::from(run.total_tokens);
+        self.estimated_cost_usd += run.estimated_cost_usd;
+    }
+}
+
+/// Aggregate `runs` that started in `[from, to)` (either bound optional).
+///
+/// Day buckets come back in chronological order; model and agent buckets
+/// by total tokens, largest first.
+pub fn aggregate(
+    runs: &[Run],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    group_by: UsageGroupBy,
+) -> Vec<UsageBucket> {
+    let mut buckets: HashMap<String, UsageBucket> = HashMap::new();
+    for run in runs {
+        if from.is_some_and(|f| run.started_at < f) || to.is_some_and(|t| run.started_at >= t) {
+            continue;
+        }
+        let key = match group_by {
+            UsageGroupBy::Day => run.started_at.format("%Y-%m-%d").to_string(),
+            UsageGroupBy::Model => run
+                .model
+                .as_deref()
+                .unwrap_or(DEFAULT_MODEL_KEY)
+                .to_string(),
+            UsageGroupBy::Agent => run
+                .agent_id
+                .as_deref()
+                .unwrap_or(MAIN_AGENT_KEY)
+                .to_string(),
+        };
+        buckets
+            .entry(key.clone())
+            .or_insert_with(|| UsageBucket {
+                key,
+                ..Default::default()
+            })
+            .add(run);
+    }
+
+    let mut out: Vec<UsageBucket> = buckets.into_values().collect();
+    match group_by {
+        UsageGroupBy::Day => out.sort_by(|a, b| a.key.cmp(&b.key)),
+        UsageGroupBy::Model | UsageGroupBy::Agent => out.sort_by(|a, b| {
+            b.total_tokens
+                .cmp(&a.total_tokens)
+                .then_with(|| a.key.cmp(&b.key))
+        }),
+    }
+    out
+}
+
+/// Sum of every bucket, keyed `"total"`.
+pub fn totals(buckets: &[UsageBucket]) -> UsageBucket {
+    let mut total = UsageBucket {
+        key: "total".into(),
+        ..Default::default()
+    };
+    for b in buckets {
+        total.runs += b.runs;
+        total.input_tokens += b.input_tokens;
+        total.output_tokens += b.output_tokens;
+        total.total_tokens += b.total_tokens;
+        total.estimated_cost_usd += b.estimated_cost_usd;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn run(day: u32, model: Option<&str>, agent: Option<&str>, tokens: u32, cost: f64) -> Run {
+        let mut r = Run::new("sk".into(), "sid".into(), "m");
+        r.started_at = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        r.model = model.map(String::from);
+        r.agent_id = agent.map(String::from);
+        r.input_tokens = tokens / 2;
+        r.output_tokens = tokens - tokens / 2;
+        r.total_tokens = tokens;
+        r.estimated_cost_usd = cost;
+        r
+    }
+
+    fn fixture() -> Vec<Run> {
+        vec![
+            run(1, Some("gpt-4o"), None, 100, 0.01),
+            run(1, Some("claude"), Some("researcher"), 300, 0.03),
+            run(2, Some("gpt-4o"), Some("researcher"), 200, 0.02),
+            run(3, None, None, 50, 0.0),
+        ]
+    }
+
+    fn keys(buckets: &[UsageBucket]) -> Vec<&str> {
+        buckets.iter().map(|b| b.key.as_str()).collect()
+    }
+
+    #[test]
+    fn groups_by_day_in_order() {
+        let buckets = aggregate(&fixture(), None, None, UsageGroupBy::Day);
+        assert_eq!(keys(&buckets), ["2026-03-01", "2026-03-02", "2026-03-03"]);
+        assert_eq!(buckets[0].runs, 2);
+        assert_eq!(buckets[0].total_tokens, 400);
+        assert_eq!(buckets[0].input_tokens + buckets[0].output_tokens, 400);
+        assert!((buckets[0].estimated_cost_usd - 0.04).abs() < 1e-9);
+    }
+
+    #[test]
+    fn groups_by_model_largest_first() {
+        let buckets = aggregate(&fixture(), None, None, UsageGroupBy::Model);
+        assert_eq!(keys(&buckets), ["claude", "gpt-4o", "default"]);
+        assert_eq!(buckets[1].runs, 2);
+        assert_eq!(buckets[1].total_tokens, 300);
+    }
+
+    #[test]
+    fn groups_by_agent() {
+        let buckets = aggregate(&fixture(), None, None, UsageGroupBy::Agent);
+        assert_eq!(keys(&buckets), ["researcher", "main"]);
+        assert_eq!(buckets[0].total_tokens, 500);
+        assert_eq!(buckets[1].runs, 2);
+    }
+
+    #[test]
+    fn window_is_half_open() {
+        let from = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2026, 3, 3, 12, 0, 0).unwrap();
+        let buckets = aggregate(&fixture(), Some(from), Some(to), UsageGroupBy::Day);
+        assert_eq!(keys(&buckets), ["2026-03-02"]);
+
+        let total = totals(&aggregate(&fixture(), None, None, UsageGroupBy::Model));
+        assert_eq!(total.runs, 4);
+        assert_eq!(total.total_tokens, 650);
+    }
+}
@@ -3,13 +3,24 @@
 //! This is distinct from `sa_skills::SkillsRegistry` which manages documentation
 //! and resource packs. The skill engine here provides actual callable tools
 //! (e.g. `web.fetch`, `rss.fetch`) that integrate with the tool dispatch system.
+//!
+//! A skill whose name falls under an installed ClawHub pack's
+//! `tool_prefixes` belongs to that pack, and is bounded by the permissions
+//! the pack was granted at install (its origin.json): calling one whose
+//! danger level needs a permission outside that grant is refused.  Grants
+//! are loaded with [`SkillEngine::load_pack_grants`] at startup and on every
+//! skills reload.
 
 pub mod web_fetch;
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
+use parking_lot::RwLock;
+use sa_protocol::ErrorKind;
+use sa_skills::permissions::{self, Permission};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -34,6 +45,10 @@ pub struct SkillSpec {
     pub args_schema: Value,
     pub returns_schema: Value,
     pub danger_level: DangerLevel,
+    /// Same args always yield the same result with no side effects, so
+    /// results may be served from the tool result cache.
+    #[serde(default)]
+    pub idempotent: bool,
 }
 
 /// How dangerous a skill is — used for UI display and future approval flows.
@@ -46,6 +61,18 @@ pub enum DangerLevel {
     Execution,
 }
 
+impl DangerLevel {
+    /// The pack permission needed to provide a skill of this level.
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            Self::Safe => None,
+            Self::Network => Some(Permission::Network),
+            Self::Filesystem => Some(Permission::Filesystem),
+            Self::Execution => Some(Permission::Execution),
+        }
+    }
+}
+
 /// Result of a skill invocation.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SkillResult {
@@ -69,9 +96,20 @@ pub trait Skill: Send + Sync {
 // SkillEngine — the callable skill registry
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// Permissions granted to an installed pack, and the skill names it owns.
+#[derive(Debug, Clone)]
+struct PackGrant {
+    /// `owner/repo`.
+    pack: String,
+    tool_prefixes: Vec<String>,
+    granted: Vec<Permission>,
+}
+
 /// Registry of callable skills, keyed by name.
 pub struct SkillEngine {
     skills: HashMap<String, Arc<dyn Skill>>,
+    /// Grants of the installed packs, replaced on every skills reload.
+    pack_grants: RwLock<Vec<PackGrant>>,
 }
 
 impl Default for SkillEngine {
@@ -84,6 +122,7 @@ impl SkillEngine {
     pub fn new() -> Self {
         Self {
             skills: HashMap::new(),
+            pack_grants: RwLock::new(Vec::new()),
         }
     }
 
@@ -94,6 +133,42 @@ impl SkillEngine {
         self
     }
 
+    /// Replace the pack grants with those of the packs installed under
+    /// `skills_root`: each pack's `tool_prefixes` (from its SKILL.md) and
+    /// the permissions recorded in its origin.json.  Returns how many packs
+    /// claim skills.
+    pub fn load_pack_grants(&self, skills_root: &Path) -> usize {
+        let grants: Vec<PackGrant> = sa_skills::installer::list_installed(skills_root)
+            .into_iter()
+            .filter_map(|origin| {
+                let dir = skills_root
+                    .join("third_party")
+                    .join(&origin.owner)
+                    .join(&origin.repo);
+                let content = std::fs::read_to_string(dir.join("SKILL.md")).ok()?;
+                let manifest = sa_skills::manifest::parse_frontmatter(&content).0?;
+                (!manifest.tool_prefixes.is_empty()).then(|| PackGrant {
+                    pack: format!("{}/{}", origin.owner, origin.repo),
+                    tool_prefixes: manifest.tool_prefixes,
+                    granted: origin.permissions,
+                })
+            })
+            .collect();
+        let count = grants.len();
+        *self.pack_grants.write() = grants;
+        count
+    }
+
+    /// Every installed pack claiming `name`, with its grant.
+    fn grants_for(&self, name: &str) -> Vec<PackGrant> {
+        self.pack_grants
+            .read()
+            .iter()
+            .filter(|g| g.tool_prefixes.iter().any(|p| name.starts_with(p.as_str())))
+            .cloned()
+            .collect()
+    }
+
     /// List all registered skill specs (sorted by name).
     pub fn list(&self) -> Vec<SkillSpec> {
         let mut v: Vec<_> = self.skills.values().map(|s| s.spec()).collect();
@@ -112,6 +187,18 @@ impl SkillEngine {
             .skills
             .get(name)
             .ok_or_else(|| anyhow::anyhow!("unknown skill: {}", name))?;
+        let needed = skill.spec().danger_level.permission();
+        for PackGrant { pack, granted, .. } in self.grants_for(name) {
+            if !permissions::allows(&granted, needed) {
+                let needed = needed.map(Permission::as_str).unwrap_or_default();
+                tracing::warn!(skill = name, pack = %pack, needed, "pack skill exceeds permissions");
+                anyhow::bail!(
+                    "{}: skill '{name}' needs the '{needed}' permission, which pack {pack} \
+                     was not granted",
+                    ErrorKind::NotAllowed
+                );
+            }
+        }
         skill.call(ctx, args).await
     }
 
@@ -142,11 +229,114 @@ pub fn build_default_engine() -> Result<SkillEngine> {
 mod tests {
     use super::*;
 
+    struct Stub(DangerLevel);
+
+    #[async_trait::async_trait]
+    impl Skill for Stub {
+        fn spec(&self) -> SkillSpec {
+            SkillSpec {
+                name: format!("stub.{:?}", self.0).to_lowercase(),
+                title: "Stub".into(),
+                description: "test".into(),
+                args_schema: Value::Null,
+                returns_schema: Value::Null,
+                danger_level: self.0.clone(),
+                idempotent: false,
+            }
+        }
+
+        async fn call(&self, _ctx: SkillContext, _args: Value) -> Result<SkillResult> {
+            Ok(SkillResult {
+                ok: true,
+                output: Value::Null,
+                preview: "ran".into(),
+            })
+        }
+    }
+
+    fn ctx() -> SkillContext {
+        SkillContext {
+            run_id: uuid::Uuid::new_v4(),
+            session_key: "s1".into(),
+            actor: "test".into(),
+        }
+    }
+
     #[test]
     fn build_default_engine_works() {
         let engine = build_default_engine().unwrap();
-        assert!(engine.len() >= 1);
+        assert!(!engine.is_empty());
         let specs = engine.list();
         assert!(specs.iter().any(|s| s.name == "web.fetch"));
     }
+
+    /// Install pack `o/stubs` (claiming `stub.*`) under `root` with
+    /// `granted`, the way `install_with_deps` records it.
+    fn install_stub_pack(root: &Path, granted: Vec<Permission>) {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::write(
+            src.path().join("SKILL.md"),
+            "---\nname: stubs\ndescription: test\ntool_prefixes: [\"stub.\"]\n---\n",
+        )
+        .unwrap();
+        sa_skills::installer::install_from_dir(root, "o", "stubs", src.path(), "1.0.0", None, None)
+            .unwrap();
+        sa_skills::installer::update_origin(root, "o", "stubs", |o| o.permissions = granted)
+            .unwrap();
+    }
+
+    fn stub_engine() -> SkillEngine {
+        SkillEngine::new()
+            .register(Arc::new(Stub(DangerLevel::Safe)))
+            .register(Arc::new(Stub(DangerLevel::Network)))
+            .register(Arc::new(Stub(DangerLevel::Execution)))
+    }
+
+    #[tokio::test]
+    async fn installed_pack_skill_exceeding_its_grant_is_blocked() {
+        let root = tempfile::tempdir().unwrap();
+        install_stub_pack(root.path(), vec![Permission::Network]);
+        let engine = stub_engine();
+        assert_eq!(engine.load_pack_grants(root.path()), 1);
+
+        let err = engine
+            .call(ctx(), "stub.execution", Value::Null)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("not_allowed: "), "{err}");
+        assert!(err.contains("'execution'"), "{err}");
+        assert!(err.contains("o/stubs"), "{err}");
+
+        for name in ["stub.network", "stub.safe"] {
+            let out = engine.call(ctx(), name, Value::Null).await.unwrap();
+            assert_eq!(out.preview, "ran");
+        }
+    }
+
+    #[tokio::test]
+    async fn reloading_grants_picks_up_a_reinstall() {
+        let root = tempfile::tempdir().unwrap();
+        let engine = stub_engine();
+        // Before any pack claims them, skills run unrestricted.
+        assert_eq!(engine.load_pack_grants(root.path()), 0);
+        assert!(engine
+            .call(ctx(), "stub.execution", Value::Null)
+            .await
+            .is_ok());
+
+        install_stub_pack(root.path(), vec![]);
+        engine.load_pack_grants(root.path());
+        assert!(engine
+            .call(ctx(), "stub.execution", Value::Null)
+            .await
+            .is_err());
+
+        install_stub_pack(root.path(), vec![Permission::Execution]);
+        engine.load_pack_grants(root.path());
+        assert!(engine
+            .call(ctx(), "stub.execution", Value::Null)
+            .await
+            .is_ok());
+    }
 }
@@ -276,6 +276,7 @@ impl Skill for WebFetchSkill {
                 }
             }),
             danger_level: DangerLevel::Network,
+            idempotent: true,
         }
     }
 
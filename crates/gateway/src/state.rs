@@ -22,10 +22,16 @@ use crate::runtime::approval::ApprovalStore;
 use crate::runtime::cancel::CancelMap;
 use crate::runtime::quota::QuotaTracker;
 use crate::runtime::deliveries::DeliveryStore;
+use crate::runtime::events::EventBus;
+use crate::runtime::interceptor::ChatInterceptors;
 use crate::runtime::runs::RunStore;
 use crate::runtime::schedules::ScheduleStore;
 use crate::runtime::session_lock::SessionLockMap;
 use crate::runtime::tasks::{TaskRunner, TaskStore};
+use crate::runtime::tool_audit::ToolAuditLog;
+use crate::runtime::tool_cache::{ToolDefsCache, ToolResultCache};
+use crate::runtime::tool_middleware::ToolChain;
+use crate::runtime::tool_stats::ToolStats;
 use crate::skills::SkillEngine;
 use crate::workspace::bootstrap::BootstrapTracker;
 use crate::workspace::files::WorkspaceReader;
@@ -37,14 +43,6 @@ pub struct CachedUserFacts {
     pub fetched_at: Instant,
 }
 
-/// Cached tool definitions keyed on (node generation, policy fingerprint).
-#[derive(Clone)]
-pub struct CachedToolDefs {
-    pub defs: Arc<Vec<sa_domain::tool::ToolDefinition>>,
-    pub generation: u64,
-    pub policy_key: String,
-}
-
 /// Smart router state (None when [llm.router] is not configured or disabled).
 pub struct SmartRouterState {
     pub classifier: Option<EmbeddingClassifier>,
@@ -96,12 +94,25 @@ pub struct AppState {
     pub schedule_store: Arc<ScheduleStore>,
     /// Delivery store (inbox notifications from scheduled runs).
     pub delivery_store: Arc<DeliveryStore>,
+    /// Unified stream of run, schedule, delivery and node events.
+    pub events: Arc<EventBus>,
     /// Sub-agent manager. `None` if no agents are configured.
     pub agents: Option<Arc<AgentManager>>,
     pub processes: Arc<ProcessManager>,
     pub cancel_map: Arc<CancelMap>,
     /// Per-agent daily token and cost quota tracker.
     pub quota_tracker: Arc<QuotaTracker>,
+    /// Per-tool call counts, failure rates and latency across runs.
+    pub tool_stats: Arc<ToolStats>,
+    /// Cached results of idempotent tool calls.
+    pub tool_cache: Arc<ToolResultCache>,
+    /// Append-only log of every tool dispatch.
+    pub tool_audit: Arc<ToolAuditLog>,
+    /// Middleware applied to every tool dispatch (audit, policy, cache,
+    /// plus any registered layers).
+    pub tool_middleware: Arc<ToolChain>,
+    /// Hooks run around every LLM call in a turn (empty by default).
+    pub chat_interceptors: Arc<ChatInterceptors>,
 
     // ── MCP (Model Context Protocol) servers ────────────────────────────
     /// MCP server connections and tool registry.
@@ -124,9 +135,9 @@ pub struct AppState {
     pub shutdown_tx: Arc<tokio::sync::Notify>,
 
     // ── Security (startup-computed) ───────────────────────────────────
-    /// SHA-256 hash of the API bearer token (read once at startup).
-    /// `None` = dev mode (no auth enforced).
-    pub api_token_hash: Option<Vec<u8>>,
+    /// Hashed API bearer tokens and their scopes (read once at startup).
+    /// Empty = dev mode (no auth enforced).
+    pub api_tokens: Arc<crate::api::auth::ApiTokens>,
     /// SHA-256 hash of the admin bearer token (read once at startup).
     /// `None` = dev mode (admin endpoints accessible without auth).
     pub admin_token_hash: Option<Vec<u8>>,
@@ -141,6 +152,6 @@ pub struct AppState {
     /// Per-user TTL cache for user facts (avoids network calls every turn).
     pub user_facts_cache: Arc<RwLock<HashMap<String, CachedUserFacts>>>,
     /// Cached tool definitions keyed on policy fingerprint; invalidated by
-    /// node registry generation counter.
-    pub tool_defs_cache: Arc<RwLock<HashMap<String, CachedToolDefs>>>,
+    /// node/MCP generation changes and explicitly on skills reload.
+    pub tool_defs_cache: Arc<ToolDefsCache>,
 }
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::time::SystemTime;
 
 use parking_lot::RwLock;
@@ -8,6 +8,8 @@ use sha2::{Digest, Sha256};
 use sa_contextpack::builder::WorkspaceFile;
 use sa_domain::trace::TraceEvent;
 
+use crate::paths::validate_relative_path;
+
 #[derive(Debug, Clone)]
 struct CachedFile {
     content: String,
@@ -22,6 +24,33 @@ pub struct FileHash {
     pub size: u64,
 }
 
+/// Largest file accepted by [`WorkspaceReader::write_file`].
+pub const MAX_WRITE_BYTES: usize = 1024 * 1024;
+
+/// Directory under the workspace root holding the previous version of
+/// every overwritten file.
+pub const BACKUP_DIR: &str = ".backups";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceWriteError {
+    #[error("invalid path: {0}")]
+    InvalidPath(String),
+    #[error("file is {size} bytes, limit is {max} bytes")]
+    TooLarge { size: usize, max: usize },
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result of a successful [`WorkspaceReader::write_file`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorkspaceWrite {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Backup of the previous version, relative to the workspace root.
+    pub backup: Option<String>,
+}
+
 /// Reads and caches workspace context files with mtime + size + sha256 invalidation.
 pub struct WorkspaceReader {
     root: PathBuf,
@@ -146,4 +175,179 @@ impl WorkspaceReader {
     pub fn root(&self) -> &Path {
         &self.root
     }
+
+    /// Write `content` to `rel` within the workspace root.
+    ///
+    /// The path goes through the same traversal checks as OpenClaw archive
+    /// import, and must not resolve (via symlinks) outside the root.  An
+    /// existing file is first copied to [`BACKUP_DIR`], which must not be a
+    /// symlink.
+    pub fn write_file(&self, rel: &str, content: &str) -> Result<WorkspaceWrite, WorkspaceWriteError> {
+        let rel_path = Path::new(rel);
+        validate_relative_path(rel_path).map_err(WorkspaceWriteError::InvalidPath)?;
+        let first = rel_path.components().find(|c| !matches!(c, Component::CurDir));
+        if first == Some(Component::Normal(BACKUP_DIR.as_ref())) {
+            return Err(WorkspaceWriteError::InvalidPath(format!(
+                "{BACKUP_DIR} is reserved for backups"
+            )));
+        }
+        if content.len() > MAX_WRITE_BYTES {
+            return Err(WorkspaceWriteError::TooLarge {
+                size: content.len(),
+                max: MAX_WRITE_BYTES,
+            });
+        }
+
+        let target = self.root.join(rel_path);
+        self.ensure_within_root(&target)?;
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let backup = if target.is_file() {
+            let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+            let backup_rel = format!("{BACKUP_DIR}/{rel}.{stamp}");
+            let backup_path = self.root.join(&backup_rel);
+            let backup_dir = self.root.join(BACKUP_DIR);
+            if backup_dir
+                .symlink_metadata()
+                .is_ok_and(|m| m.file_type().is_symlink())
+            {
+                return Err(WorkspaceWriteError::InvalidPath(format!(
+                    "{BACKUP_DIR} must not be a symlink"
+                )));
+            }
+            self.ensure_within_root(&backup_path)?;
+            if let Some(parent) = backup_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&target, &backup_path)?;
+            Some(backup_rel)
+        } else {
+            None
+        };
+
+        std::fs::write(&target, content)?;
+        self.cache.write().remove(rel);
+
+        let sha256 = format!("{:x}", Sha256::digest(content.as_bytes()));
+        Ok(WorkspaceWrite {
+            path: rel.to_string(),
+            size: content.len() as u64,
+            sha256,
+            backup,
+        })
+    }
+
+    /// Reject targets that are symlinks or whose nearest existing ancestor
+    /// resolves outside the workspace root.
+    fn ensure_within_root(&self, target: &Path) -> Result<(), WorkspaceWriteError> {
+        let escapes = || WorkspaceWriteError::InvalidPath("path escapes workspace root".into());
+
+        if target
+            .symlink_metadata()
+            .is_ok_and(|m| m.file_type().is_symlink())
+        {
+            return Err(escapes());
+        }
+
+        let root = self.root.canonicalize()?;
+        let existing = target
+            .ancestors()
+            .find(|p| p.exists())
+            .ok_or_else(escapes)?
+            .canonicalize()?;
+        if !existing.starts_with(&root) {
+            return Err(escapes());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader() -> (tempfile::TempDir, WorkspaceReader) {
+        let dir = tempfile::tempdir().unwrap();
+        let reader = WorkspaceReader::new(dir.path().to_path_buf());
+        (dir, reader)
+    }
+
+    #[test]
+    fn write_creates_file_within_root() {
+        let (dir, ws) = reader();
+        let written = ws.write_file("notes/MEMORY.md", "# Memory\n").unwrap();
+
+        assert_eq!(written.path, "notes/MEMORY.md");
+        assert_eq!(written.size, 9);
+        assert!(written.backup.is_none());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("notes/MEMORY.md")).unwrap(),
+            "# Memory\n"
+        );
+    }
+
+    #[test]
+    fn write_rejects_traversal() {
+        let (dir, ws) = reader();
+        for path in ["../escape.md", "notes/../../escape.md", "/etc/passwd", ""] {
+            let err = ws.write_file(path, "x").unwrap_err();
+            assert!(matches!(err, WorkspaceWriteError::InvalidPath(_)), "{path}: {err}");
+        }
+        assert!(!dir.path().parent().unwrap().join("escape.md").exists());
+    }
+
+    #[test]
+    fn write_backs_up_previous_version() {
+        let (dir, ws) = reader();
+        std::fs::write(dir.path().join("MEMORY.md"), "old").unwrap();
+        assert_eq!(ws.read_file("MEMORY.md").as_deref(), Some("old"));
+
+        let written = ws.write_file("MEMORY.md", "new").unwrap();
+
+        let backup = written.backup.expect("previous version backed up");
+        assert!(backup.starts_with(".backups/MEMORY.md."));
+        assert_eq!(std::fs::read_to_string(dir.path().join(&backup)).unwrap(), "old");
+        assert_eq!(ws.read_file("MEMORY.md").as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn write_rejects_oversized_content() {
+        let (_dir, ws) = reader();
+        let content = "x".repeat(MAX_WRITE_BYTES + 1);
+        assert!(matches!(
+            ws.write_file("big.md", &content),
+            Err(WorkspaceWriteError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn write_rejects_backup_dir() {
+        let (_dir, ws) = reader();
+        assert!(matches!(
+            ws.write_file(".backups/MEMORY.md", "x"),
+            Err(WorkspaceWriteError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            ws.write_file("./.backups/MEMORY.md", "x"),
+            Err(WorkspaceWriteError::InvalidPath(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_rejects_symlinked_backup_dir() {
+        let (dir, ws) = reader();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join(BACKUP_DIR)).unwrap();
+        std::fs::write(dir.path().join("MEMORY.md"), "old").unwrap();
+
+        assert!(matches!(
+            ws.write_file("MEMORY.md", "new"),
+            Err(WorkspaceWriteError::InvalidPath(_))
+        ));
+        assert_eq!(std::fs::read_to_string(dir.path().join("MEMORY.md")).unwrap(), "old");
+        assert_eq!(std::fs::read_dir(outside.path()).unwrap().count(), 0);
+    }
 }
@@ -11,7 +11,7 @@
 //!   SA_NODE_TOKEN=secret sa-hello-node ws://localhost:3210/v1/nodes/ws
 //!
 //! Env vars:
-//!   SA_NODE_TOKEN    — auth token (must match gateway)
+//!   SA_NODE_TOKEN    — auth token (must match gateway; sent as a Bearer header)
 //!   SA_NODE_ID       — node ID (default: "hello-node")
 //!   SA_ALLOWED_DIR   — directory allowed for fs.read_text (default: ".")
 
@@ -20,6 +20,9 @@ use std::path::PathBuf;
 use chrono::Utc;
 use futures_util::{SinkExt, StreamExt};
 use sa_protocol::{ErrorKind, NodeInfo, ToolResponseError, WsMessage, MAX_TOOL_RESPONSE_BYTES, PROTOCOL_VERSION};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::Message;
 use tracing_subscriber::EnvFilter;
 
@@ -42,22 +45,25 @@ async fn main() -> anyhow::Result<()> {
         std::env::var("SA_ALLOWED_DIR").unwrap_or_else(|_| ".".into()),
     );
 
-    // Build connection URL with auth params.
-    let url = if token.is_empty() {
-        gateway_url
-    } else {
-        format!(
-            "{}{}token={}&node_id={}",
-            gateway_url,
-            if gateway_url.contains('?') { "&" } else { "?" },
-            token,
-            node_id,
-        )
-    };
-
+    // Build the upgrade request; the token travels in a header so it
+    // stays out of URLs and proxy logs.
+    let url = format!(
+        "{}{}node_id={}",
+        gateway_url,
+        if gateway_url.contains('?') { "&" } else { "?" },
+        node_id,
+    );
     tracing::info!(url = %url, node_id = %node_id, "connecting to gateway");
 
-    let (ws, _response) = tokio_tungstenite::connect_async(&url).await?;
+    let mut request = url.into_client_request()?;
+    if !token.is_empty() {
+        request.headers_mut().insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}"))?,
+        );
+    }
+
+    let (ws, _response) = tokio_tungstenite::connect_async(request).await?;
     let (mut sink, mut stream) = ws.split();
 
     tracing::info!("WebSocket connected, sending node_hello");
@@ -2,6 +2,8 @@
 //! discovery and dispatch.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use serde_json::Value;
 
@@ -26,10 +28,14 @@ pub struct McpServer {
 impl McpServer {
     /// Initialize a server: spawn the process (or connect via SSE),
     /// perform the MCP handshake, and discover tools.
-    async fn initialize(config: &McpServerConfig) -> Result<Self, McpError> {
+    async fn initialize(
+        config: &McpServerConfig,
+        generation: &Arc<AtomicU64>,
+    ) -> Result<Self, McpError> {
         let transport: Box<dyn McpTransport> = match config.transport {
             McpTransportKind::Stdio => {
-                let t = StdioTransport::spawn(config).map_err(McpError::Transport)?;
+                let t = StdioTransport::spawn(config, generation.clone())
+                    .map_err(McpError::Transport)?;
                 Box::new(t)
             }
             McpTransportKind::Sse => {
@@ -159,6 +165,9 @@ impl McpServer {
 /// Manager that holds all MCP server connections.
 pub struct McpManager {
     servers: HashMap<String, McpServer>,
+    /// Bumped on every server connect and disconnect; see
+    /// [`generation`](Self::generation).
+    generation: Arc<AtomicU64>,
 }
 
 impl McpManager {
@@ -166,6 +175,7 @@ impl McpManager {
     pub fn empty() -> Self {
         Self {
             servers: HashMap::new(),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -174,6 +184,7 @@ impl McpManager {
     /// Servers that fail to initialize are logged and skipped (not fatal).
     pub async fn from_config(config: &McpConfig) -> Self {
         let mut servers = HashMap::new();
+        let generation = Arc::new(AtomicU64::new(0));
         let effective = config.effective_servers();
 
         for server_config in &effective {
@@ -184,9 +195,10 @@ impl McpManager {
                 "initializing MCP server"
             );
 
-            match McpServer::initialize(server_config).await {
+            match McpServer::initialize(server_config, &generation).await {
                 Ok(server) => {
                     servers.insert(server_config.id.clone(), server);
+                    generation.fetch_add(1, Ordering::SeqCst);
                 }
                 Err(e) => {
                     tracing::warn!(
@@ -205,7 +217,10 @@ impl McpManager {
             );
         }
 
-        Self { servers }
+        Self {
+            servers,
+            generation,
+        }
     }
 
     /// Get all discovered tools across all servers.
@@ -221,6 +236,14 @@ impl McpManager {
             .collect()
     }
 
+    /// Increases whenever a server connects or goes down (exits, breaks
+    /// protocol or is shut down), so callers caching
+    /// [`list_tools`](Self::list_tools) output can tell it is stale.
+    /// Never repeats a value.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     /// Call a tool on a specific server.
     pub async fn call_tool(
         &self,
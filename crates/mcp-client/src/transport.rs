@@ -5,6 +5,7 @@
 //! - **Sse**: stub for future HTTP SSE transport.
 
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde_json::Value;
@@ -70,11 +71,17 @@ pub struct StdioTransport {
     request_lock: Mutex<()>,
     next_id: AtomicU64,
     alive: AtomicBool,
+    /// The owning manager's generation counter, bumped when this transport
+    /// goes down.
+    generation: Arc<AtomicU64>,
 }
 
 impl StdioTransport {
     /// Spawn a child process from the given server config.
-    pub fn spawn(config: &McpServerConfig) -> Result<Self, TransportError> {
+    pub fn spawn(
+        config: &McpServerConfig,
+        generation: Arc<AtomicU64>,
+    ) -> Result<Self, TransportError> {
         let mut cmd = tokio::process::Command::new(&config.command);
         cmd.args(&config.args)
             .stdin(std::process::Stdio::piped())
@@ -111,9 +118,17 @@ impl StdioTransport {
             request_lock: Mutex::new(()),
             next_id: AtomicU64::new(1),
             alive: AtomicBool::new(true),
+            generation,
         })
     }
 
+    /// Mark the transport dead, bumping the generation on the first call.
+    fn mark_down(&self) {
+        if self.alive.swap(false, Ordering::SeqCst) {
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
     /// Get the next unique request ID.
     fn next_request_id(&self) -> u64 {
         self.next_id.fetch_add(1, Ordering::Relaxed)
@@ -147,7 +162,7 @@ impl StdioTransport {
             let mut line = String::new();
             let bytes_read = stdout.read_line(&mut line).await?;
             if bytes_read == 0 {
-                self.alive.store(false, Ordering::SeqCst);
+                self.mark_down();
                 return Err(TransportError::ProcessExited);
             }
             let trimmed = line.trim();
@@ -160,7 +175,7 @@ impl StdioTransport {
             }
             skipped += 1;
             if skipped >= MAX_SKIP_LINES {
-                self.alive.store(false, Ordering::SeqCst);
+                self.mark_down();
                 return Err(TransportError::Io(std::io::Error::new(
                     std::io::ErrorKind::InvalidData,
                     "MCP server produced too many non-JSON lines on stdout",
@@ -227,7 +242,7 @@ impl McpTransport for StdioTransport {
     }
 
     async fn shutdown(&self) {
-        self.alive.store(false, Ordering::SeqCst);
+        self.mark_down();
         let mut child = self.child.lock().await;
         // Close stdin to signal the process to exit.
         {
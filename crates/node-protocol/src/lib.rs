@@ -140,6 +140,11 @@ pub const MAX_TOOL_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
 /// failures.
 pub const PROTOCOL_VERSION: u32 = 1;
 
+/// Tool name of the standard self-test every node answers.  The gateway
+/// sends it with `{ "nonce": "<uuid>" }` and expects the nonce echoed back
+/// in the result's `nonce` field; it has no side effects on the node.
+pub const SELFTEST_TOOL: &str = "node.selftest";
+
 /// Default for `#[serde(default)]` on protocol_version fields.
 /// Returns 1 so older payloads without the field are treated as v1.
 fn default_protocol_version() -> u32 {
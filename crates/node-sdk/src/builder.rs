@@ -64,7 +64,8 @@ impl NodeClientBuilder {
         self
     }
 
-    /// Set the authentication token (`SA_NODE_TOKEN`).
+    /// Set the authentication token (`SA_NODE_TOKEN`), sent as an
+    /// `Authorization: Bearer` header on the WebSocket upgrade.
     pub fn token(mut self, token: impl Into<String>) -> Self {
         self.token = Some(token.into());
         self
@@ -9,6 +9,10 @@ use chrono::Utc;
 use futures_util::{FutureExt, SinkExt, StreamExt};
 use sa_protocol::{ErrorKind, NodeInfo, ToolResponseError, WsMessage, PROTOCOL_VERSION};
 use tokio::sync::{mpsc, Semaphore};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_util::sync::CancellationToken;
 
@@ -136,10 +140,10 @@ impl NodeClient {
         &self,
         registry: &Arc<ToolRegistry>,
     ) -> Result<bool, anyhow::Error> {
-        let url = self.build_url();
-        tracing::info!(url = %url, node_id = %self.node_id, "connecting to gateway");
+        let request = self.build_request()?;
+        tracing::info!(url = %request.uri(), node_id = %self.node_id, "connecting to gateway");
 
-        let (ws, _response) = tokio_tungstenite::connect_async(&url).await?;
+        let (ws, _response) = tokio_tungstenite::connect_async(request).await?;
         let (mut sink, mut stream) = ws.split();
 
         // ── Send node_hello ──────────────────────────────────────────
@@ -161,14 +165,21 @@ impl NodeClient {
         let welcome_timeout = Duration::from_secs(10);
         let welcome = tokio::time::timeout(welcome_timeout, async {
             while let Some(Ok(msg)) = stream.next().await {
-                if let Message::Text(text) = msg {
-                    if let Ok(WsMessage::GatewayWelcome {
-                        gateway_version,
-                        ..
-                    }) = serde_json::from_str(&text)
-                    {
-                        return Ok(gateway_version);
+                match msg {
+                    Message::Text(text) => {
+                        if let Ok(WsMessage::GatewayWelcome {
+                            gateway_version,
+                            ..
+                        }) = serde_json::from_str(&text)
+                        {
+                            return Ok(gateway_version);
+                        }
+                    }
+                    // The gateway refuses incompatible nodes with a reason.
+                    Message::Close(Some(frame)) => {
+                        return Err(anyhow::anyhow!("gateway refused node: {}", frame.reason));
                     }
+                    _ => {}
                 }
             }
             Err(anyhow::anyhow!("connection closed before welcome"))
@@ -394,22 +405,23 @@ impl NodeClient {
         Ok(true) // handshake was completed
     }
 
-    /// Build the full connection URL with auth params.
+    /// Build the connection URL (identifies the node; carries no secret).
     fn build_url(&self) -> String {
         let base = &self.gateway_ws_url;
         let sep = if base.contains('?') { "&" } else { "?" };
+        format!("{base}{sep}node_id={}", self.node_id)
+    }
 
-        match &self.token {
-            Some(token) => {
-                format!(
-                    "{base}{sep}token={token}&node_id={}",
-                    self.node_id
-                )
-            }
-            None => {
-                format!("{base}{sep}node_id={}", self.node_id)
-            }
+    /// Build the upgrade request, sending the token as an
+    /// `Authorization: Bearer` header so it stays out of URLs and logs.
+    fn build_request(&self) -> Result<Request, anyhow::Error> {
+        let mut request = self.build_url().into_client_request()?;
+        if let Some(token) = &self.token {
+            request
+                .headers_mut()
+                .insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}"))?);
         }
+        Ok(request)
     }
 }
 
@@ -429,17 +441,6 @@ fn tool_error_to_protocol(err: &ToolError) -> ToolResponseError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::registry::NodeTool;
-    use crate::types::ToolResult;
-
-    struct NullTool;
-
-    #[async_trait::async_trait]
-    impl NodeTool for NullTool {
-        async fn call(&self, _ctx: ToolContext, _args: serde_json::Value) -> ToolResult {
-            Ok(serde_json::json!(null))
-        }
-    }
 
     fn test_client() -> NodeClient {
         NodeClient {
@@ -459,24 +460,29 @@ mod tests {
     }
 
     #[test]
-    fn build_url_with_token() {
+    fn build_request_sends_token_as_bearer_header() {
         let client = test_client();
-        let url = client.build_url();
+        let request = client.build_request().unwrap();
         assert_eq!(
-            url,
-            "ws://localhost:3210/v1/nodes/ws?token=secret&node_id=test-node"
+            request.uri().to_string(),
+            "ws://localhost:3210/v1/nodes/ws?node_id=test-node"
+        );
+        assert_eq!(
+            request.headers().get(AUTHORIZATION).unwrap(),
+            "Bearer secret"
         );
     }
 
     #[test]
-    fn build_url_without_token() {
+    fn build_request_without_token() {
         let mut client = test_client();
         client.token = None;
-        let url = client.build_url();
+        let request = client.build_request().unwrap();
         assert_eq!(
-            url,
+            request.uri().to_string(),
             "ws://localhost:3210/v1/nodes/ws?node_id=test-node"
         );
+        assert!(request.headers().get(AUTHORIZATION).is_none());
     }
 
     #[test]
@@ -484,6 +490,7 @@ mod tests {
         let mut client = test_client();
         client.gateway_ws_url = "ws://localhost:3210/v1/nodes/ws?foo=bar".into();
         let url = client.build_url();
-        assert!(url.starts_with("ws://localhost:3210/v1/nodes/ws?foo=bar&token=secret"));
+        assert_eq!(url, "ws://localhost:3210/v1/nodes/ws?foo=bar&node_id=test-node");
+        assert!(!url.contains("secret"));
     }
 }
@@ -29,11 +29,12 @@
 //!
 //! # Connection flow (hard-coded by the SDK)
 //!
-//! 1. Connect WS (with `token=<SA_NODE_TOKEN>` query param)
+//! 1. Connect WS (with `Authorization: Bearer <SA_NODE_TOKEN>`)
 //! 2. Send `node_hello { protocol_version, node: { id, name, node_type, version, tags }, capabilities }`
 //! 3. Wait for `gateway_welcome { gateway_version }`
 //! 4. Main loop:
 //!    - On `tool_request`: dispatch to registered handler, always send `tool_response`
+//!      (`node.selftest` has a built-in handler)
 //!    - On `ping`: reply `pong`
 //!    - Emit periodic `ping` to keep `last_seen` fresh
 //! 5. On disconnect: reconnect with jittered exponential back-off
@@ -55,7 +56,7 @@ pub mod types;
 pub use builder::NodeClientBuilder;
 pub use client::NodeClient;
 pub use reconnect::ReconnectBackoff;
-pub use registry::{NodeTool, ToolRegistry};
+pub use registry::{NodeTool, SelfTest, ToolRegistry};
 pub use types::{NodeSdkError, ToolContext, ToolError, ToolResult};
 
 // Re-export the entire protocol crate so downstream nodes never need a
@@ -65,4 +66,5 @@ pub use sa_protocol as protocol;
 // Convenience re-exports of the most commonly used protocol types.
 pub use sa_protocol::{
     ErrorKind, NodeInfo, ToolResponseError, WsMessage, MAX_TOOL_RESPONSE_BYTES, PROTOCOL_VERSION,
+    SELFTEST_TOOL,
 };
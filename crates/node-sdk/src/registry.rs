@@ -175,8 +175,30 @@ impl ToolRegistry {
     }
 
     /// Look up a handler by tool name (case-insensitive).
+    ///
+    /// [`SELFTEST_TOOL`](sa_protocol::SELFTEST_TOOL) is always answered by
+    /// the built-in [`SelfTest`] unless a handler is registered for it.
     pub fn get(&self, tool_name: &str) -> Option<Arc<dyn NodeTool>> {
-        self.tools.get(&tool_name.to_ascii_lowercase()).cloned()
+        let name = tool_name.to_ascii_lowercase();
+        self.tools.get(&name).cloned().or_else(|| {
+            (name == sa_protocol::SELFTEST_TOOL).then(|| Arc::new(SelfTest) as Arc<dyn NodeTool>)
+        })
+    }
+}
+
+/// Built-in `node.selftest` handler: echoes the gateway's nonce along with
+/// the node clock, proving the request/response path works end to end
+/// without touching anything on the node.
+pub struct SelfTest;
+
+#[async_trait::async_trait]
+impl NodeTool for SelfTest {
+    async fn call(&self, _ctx: ToolContext, args: serde_json::Value) -> ToolResult {
+        Ok(serde_json::json!({
+            "ok": true,
+            "nonce": args.get("nonce").cloned().unwrap_or(serde_json::Value::Null),
+            "node_time": chrono::Utc::now().to_rfc3339(),
+        }))
     }
 }
 
@@ -287,6 +309,30 @@ mod tests {
         assert_eq!(reg.capabilities(), vec!["macos.notes"]);
     }
 
+    #[tokio::test]
+    async fn selftest_is_built_in_and_echoes_nonce() {
+        let reg = ToolRegistry::new();
+        assert!(reg.tool_names().is_empty(), "built-in is not listed");
+        assert!(reg.capabilities().is_empty(), "built-in is not advertised");
+
+        let handler = reg.get("node.selftest").unwrap();
+        let args = serde_json::json!({"nonce": "n-1"});
+        let result = handler.call(test_ctx("node.selftest"), args).await.unwrap();
+        assert_eq!(result["ok"], true);
+        assert_eq!(result["nonce"], "n-1");
+    }
+
+    #[tokio::test]
+    async fn registered_selftest_overrides_built_in() {
+        let mut reg = ToolRegistry::new();
+        reg.register("node.selftest", Fail);
+        let handler = reg.get("node.selftest").unwrap();
+        let result = handler
+            .call(test_ctx("node.selftest"), serde_json::json!({}))
+            .await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn fail_tool_returns_error() {
         let mut reg = ToolRegistry::new();
@@ -5,15 +5,18 @@
 //! separate top-level `system` field.
 
 use crate::auth::AuthRotator;
-use crate::util::from_reqwest;
+use crate::util::{from_reqwest, http_client};
 use crate::traits::{
     ChatRequest, ChatResponse, EmbeddingsRequest, EmbeddingsResponse, LlmProvider, ResponseFormat,
+    ToolChoice,
 };
 use sa_domain::capability::LlmCapabilities;
 use sa_domain::config::ProviderConfig;
 use sa_domain::error::{Error, Result};
 use sa_domain::stream::{BoxStream, StreamEvent, Usage};
-use sa_domain::tool::{ContentPart, Message, MessageContent, Role, ToolCall, ToolDefinition};
+use sa_domain::tool::{
+    ContentPart, ImageSource, Message, MessageContent, Role, ToolCall, ToolDefinition,
+};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -35,6 +38,7 @@ pub struct AnthropicProvider {
     default_model: String,
     capabilities: LlmCapabilities,
     client: reqwest::Client,
+    stream_idle_timeout: std::time::Duration,
 }
 
 impl AnthropicProvider {
@@ -55,10 +59,7 @@ impl AnthropicProvider {
             max_output_tokens: Some(8_192),
         };
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .map_err(from_reqwest)?;
+        let client = http_client(cfg)?;
 
         Ok(Self {
             id: cfg.id.clone(),
@@ -67,6 +68,7 @@ impl AnthropicProvider {
             default_model,
             capabilities,
             client,
+            stream_idle_timeout: std::time::Duration::from_secs(cfg.stream_idle_timeout_sec),
         })
     }
 
@@ -149,6 +151,9 @@ impl AnthropicProvider {
         if !req.tools.is_empty() {
             let tools: Vec<Value> = req.tools.iter().map(tool_to_anthropic).collect();
             body["tools"] = Value::Array(tools);
+            if let Some(choice) = tool_choice_to_anthropic(&req.tool_choice) {
+                body["tool_choice"] = choice;
+            }
         }
 
         if let Some(temp) = req.temperature {
@@ -156,6 +161,9 @@ impl AnthropicProvider {
         }
         let max_tokens = req.max_tokens.unwrap_or(4096);
         body["max_tokens"] = serde_json::json!(max_tokens);
+        if !req.stop.is_empty() {
+            body["stop_sequences"] = serde_json::json!(req.stop);
+        }
 
         body
     }
@@ -180,14 +188,20 @@ fn user_msg_to_anthropic(msg: &Message) -> Value {
                         "text": text,
                     })),
                     ContentPart::Image { url, media_type } => {
-                        let mt = media_type.as_deref().unwrap_or("image/png");
+                        let source = match ImageSource::parse(url, media_type.as_deref()) {
+                            ImageSource::Url(url) => serde_json::json!({
+                                "type": "url",
+                                "url": url,
+                            }),
+                            ImageSource::Base64 { media_type, data } => serde_json::json!({
+                                "type": "base64",
+                                "media_type": media_type,
+                                "data": data,
+                            }),
+                        };
                         Some(serde_json::json!({
                             "type": "image",
-                            "source": {
-                                "type": "base64",
-                                "media_type": mt,
-                                "data": url,
-                            }
+                            "source": source,
                         }))
                     }
                     _ => None,
@@ -273,6 +287,16 @@ fn tool_to_anthropic(tool: &ToolDefinition) -> Value {
     })
 }
 
+/// `tool_choice` value; `None` for `Auto` (the API default).
+fn tool_choice_to_anthropic(choice: &ToolChoice) -> Option<Value> {
+    match choice {
+        ToolChoice::Auto => None,
+        ToolChoice::None => Some(serde_json::json!({ "type": "none" })),
+        ToolChoice::Required => Some(serde_json::json!({ "type": "any" })),
+        ToolChoice::Specific(name) => Some(serde_json::json!({ "type": "tool", "name": name })),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Response deserialization
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -329,11 +353,7 @@ fn parse_anthropic_response(body: &Value) -> Result<ChatResponse> {
     let finish_reason = body
         .get("stop_reason")
         .and_then(|v| v.as_str())
-        .map(|s| match s {
-            "end_turn" => "stop".to_string(),
-            "tool_use" => "tool_calls".to_string(),
-            other => other.to_string(),
-        });
+        .map(finish_reason_from_stop_reason);
 
     let usage = body.get("usage").and_then(parse_anthropic_usage);
 
@@ -346,6 +366,17 @@ fn parse_anthropic_response(body: &Value) -> Result<ChatResponse> {
     })
 }
 
+/// Map Anthropic's `stop_reason` onto the OpenAI-style finish reasons used
+/// elsewhere.  `stop_sequence` is kept distinct so callers can tell a
+/// requested stop sequence from a natural end of turn.
+fn finish_reason_from_stop_reason(stop_reason: &str) -> String {
+    match stop_reason {
+        "end_turn" => "stop".to_string(),
+        "tool_use" => "tool_calls".to_string(),
+        other => other.to_string(),
+    }
+}
+
 fn parse_anthropic_usage(v: &Value) -> Option<Usage> {
     let input = v.get("input_tokens")?.as_u64()? as u32;
     let output = v.get("output_tokens")?.as_u64()? as u32;
@@ -442,14 +473,12 @@ fn parse_anthropic_sse(data: &str, state: &mut StreamState) -> Vec<Result<Stream
             if let Some(delta) = v.get("delta") {
                 let delta_type = delta.get("type").and_then(|v| v.as_str()).unwrap_or("");
                 match delta_type {
-                    "thinking_delta" => {
-                        if state.thinking_blocks.contains(&idx) {
-                            if let Some(text) = delta.get("thinking").and_then(|v| v.as_str()) {
-                                if !text.is_empty() {
-                                    events.push(Ok(StreamEvent::Thinking {
-                                        text: text.to_string(),
-                                    }));
-                                }
+                    "thinking_delta" if state.thinking_blocks.contains(&idx) => {
+                        if let Some(text) = delta.get("thinking").and_then(|v| v.as_str()) {
+                            if !text.is_empty() {
+                                events.push(Ok(StreamEvent::Thinking {
+                                    text: text.to_string(),
+                                }));
                             }
                         }
                     }
@@ -506,11 +535,7 @@ fn parse_anthropic_sse(data: &str, state: &mut StreamState) -> Vec<Result<Stream
                 .get("delta")
                 .and_then(|d| d.get("stop_reason"))
                 .and_then(|v| v.as_str())
-                .map(|s| match s {
-                    "end_turn" => "stop".to_string(),
-                    "tool_use" => "tool_calls".to_string(),
-                    other => other.to_string(),
-                });
+                .map(finish_reason_from_stop_reason);
             if stop_reason.is_some() {
                 state.done_emitted = true;
                 events.push(Ok(StreamEvent::Done {
@@ -520,14 +545,12 @@ fn parse_anthropic_sse(data: &str, state: &mut StreamState) -> Vec<Result<Stream
             }
         }
 
-        "message_stop" => {
-            if !state.done_emitted {
-                state.done_emitted = true;
-                events.push(Ok(StreamEvent::Done {
-                    usage: state.usage.clone(),
-                    finish_reason: Some("stop".into()),
-                }));
-            }
+        "message_stop" if !state.done_emitted => {
+            state.done_emitted = true;
+            events.push(Ok(StreamEvent::Done {
+                usage: state.usage.clone(),
+                finish_reason: Some("stop".into()),
+            }));
         }
 
         "error" => {
@@ -568,15 +591,10 @@ impl LlmProvider for AnthropicProvider {
             .await
             .map_err(from_reqwest)?;
 
-        let status = resp.status();
-        let resp_text = resp.text().await.map_err(from_reqwest)?;
-
-        if !status.is_success() {
-            return Err(Error::Provider {
-                provider: self.id.clone(),
-                message: format!("HTTP {} - {}", status.as_u16(), resp_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
+        let resp_text = resp.text().await.map_err(from_reqwest)?;
 
         let resp_json: Value = serde_json::from_str(&resp_text)?;
         parse_anthropic_response(&resp_json)
@@ -588,7 +606,6 @@ impl LlmProvider for AnthropicProvider {
     ) -> Result<BoxStream<'static, Result<StreamEvent>>> {
         let url = format!("{}/v1/messages", self.base_url);
         let body = self.build_messages_body(req, true);
-        let provider_id = self.id.clone();
 
         tracing::debug!(provider = %self.id, url = %url, "anthropic stream request");
 
@@ -599,17 +616,12 @@ impl LlmProvider for AnthropicProvider {
             .await
             .map_err(from_reqwest)?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            let err_text = resp.text().await.map_err(from_reqwest)?;
-            return Err(Error::Provider {
-                provider: provider_id,
-                message: format!("HTTP {} - {}", status.as_u16(), err_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
 
         let mut state = StreamState::new();
-        Ok(crate::sse::sse_response_stream(resp, move |data| {
+        Ok(crate::sse::sse_response_stream(resp, self.stream_idle_timeout, move |data| {
             parse_anthropic_sse(data, &mut state)
         }))
     }
@@ -632,4 +644,144 @@ impl LlmProvider for AnthropicProvider {
     fn provider_id(&self) -> &str {
         &self.id
     }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let entry = self.auth.next_key();
+        let resp = self
+            .client
+            .get(format!("{}/v1/models?limit=1000", self.base_url))
+            .header("x-api-key", &entry.key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .send()
+            .await
+            .map_err(from_reqwest)?;
+        let body = crate::util::json_response(&self.id, resp).await?;
+        Ok(crate::util::model_ids(&body, "data", "id"))
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_parts_serialize_as_base64_or_url_sources() {
+        let msg = Message::user_with_images(
+            "compare",
+            vec![
+                ContentPart::Image {
+                    url: "data:image/jpeg;base64,QUJD".into(),
+                    media_type: None,
+                },
+                ContentPart::Image {
+                    url: "https://example.com/cat.png".into(),
+                    media_type: None,
+                },
+            ],
+        );
+        assert_eq!(
+            user_msg_to_anthropic(&msg),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "compare" },
+                    {
+                        "type": "image",
+                        "source": { "type": "base64", "media_type": "image/jpeg", "data": "QUJD" }
+                    },
+                    {
+                        "type": "image",
+                        "source": { "type": "url", "url": "https://example.com/cat.png" }
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn text_only_user_message_is_unchanged() {
+        assert_eq!(
+            user_msg_to_anthropic(&Message::user("hi")),
+            serde_json::json!({ "role": "user", "content": "hi" })
+        );
+    }
+
+    fn provider() -> AnthropicProvider {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "anthropic",
+            "kind": "anthropic",
+            "base_url": "https://api.anthropic.com",
+            "auth": { "mode": "api_key", "key": "sk-ant-test" },
+        }))
+        .unwrap();
+        AnthropicProvider::from_config(&cfg).unwrap()
+    }
+
+    #[test]
+    fn stop_sequences_are_sent_only_when_set() {
+        let req = ChatRequest {
+            messages: vec![Message::user("hi")],
+            stop: vec!["</answer>".into(), "\n\n".into()],
+            ..Default::default()
+        };
+        let body = provider().build_messages_body(&req, false);
+        assert_eq!(
+            body["stop_sequences"],
+            serde_json::json!(["</answer>", "\n\n"])
+        );
+
+        let body = provider().build_messages_body(&ChatRequest::default(), false);
+        assert!(body.get("stop_sequences").is_none());
+    }
+
+    #[test]
+    fn tool_choice_maps_to_anthropic_values() {
+        let body = |tool_choice: ToolChoice| {
+            let req = ChatRequest {
+                messages: vec![Message::user("hi")],
+                tools: vec![ToolDefinition {
+                    name: "exec".into(),
+                    description: "Run a command".into(),
+                    parameters: serde_json::json!({ "type": "object" }),
+                }],
+                tool_choice,
+                ..Default::default()
+            };
+            provider().build_messages_body(&req, false)
+        };
+
+        assert!(body(ToolChoice::Auto).get("tool_choice").is_none());
+        assert_eq!(
+            body(ToolChoice::Required)["tool_choice"],
+            serde_json::json!({ "type": "any" })
+        );
+        assert_eq!(
+            body(ToolChoice::Specific("exec".into()))["tool_choice"],
+            serde_json::json!({ "type": "tool", "name": "exec" })
+        );
+        assert_eq!(
+            body(ToolChoice::None)["tool_choice"],
+            serde_json::json!({ "type": "none" })
+        );
+    }
+
+    #[test]
+    fn stop_sequence_reason_reaches_done_event() {
+        let mut state = StreamState::new();
+        let events = parse_anthropic_sse(
+            r#"{"type":"message_delta","delta":{"stop_reason":"stop_sequence","stop_sequence":"</answer>"}}"#,
+            &mut state,
+        );
+        match events.as_slice() {
+            [Ok(StreamEvent::Done { finish_reason, .. })] => {
+                assert_eq!(finish_reason.as_deref(), Some("stop_sequence"));
+            }
+            other => panic!("expected a Done event, got {other:?}"),
+        }
+        assert_eq!(finish_reason_from_stop_reason("end_turn"), "stop");
+    }
 }
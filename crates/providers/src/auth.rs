@@ -161,6 +161,17 @@ impl AuthRotator {
         }
     }
 
+    /// Replace every key with a single freshly issued one (e.g. a
+    /// refreshed OAuth access token), clearing any cooldown state.
+    pub fn set_key(&self, key: String) {
+        let mut slots = self.slots.lock().expect("AuthRotator lock poisoned");
+        *slots = vec![KeySlot {
+            key,
+            failed_at: None,
+        }];
+        self.index.store(0, Ordering::Relaxed);
+    }
+
     /// Number of keys in the rotator.
     pub fn len(&self) -> usize {
         self.slots.lock().expect("AuthRotator lock poisoned").len()
@@ -196,6 +207,16 @@ pub struct KeyEntry {
     pub key: String,
 }
 
+/// Source of renewed credentials for providers whose access tokens expire.
+///
+/// Providers call [`TokenRefresher::refresh`] after a `401 Unauthorized`
+/// and retry the request once with the returned token.
+#[async_trait::async_trait]
+pub trait TokenRefresher: Send + Sync {
+    /// Obtain a fresh access token, persisting any rotated refresh token.
+    async fn refresh(&self) -> Result<String>;
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Tests
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -204,6 +225,16 @@ pub struct KeyEntry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn set_key_replaces_all_slots() {
+        let rotator =
+            AuthRotator::new(vec!["a".into(), "b".into()], Duration::from_secs(60)).unwrap();
+        rotator.mark_failed(0);
+        rotator.set_key("fresh".into());
+        assert_eq!(rotator.len(), 1);
+        assert_eq!(rotator.next_key().key, "fresh");
+    }
+
     #[test]
     fn single_key_always_returns_same() {
         let rotator =
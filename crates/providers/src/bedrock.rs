@@ -69,6 +69,10 @@ impl BedrockProvider {
         })
     }
 
+    /// The error every runtime method returns.  No request is sent, so
+    /// there is no HTTP status to map; once native calls land, failed
+    /// responses must go through `util::error_response` so 429s and
+    /// `Retry-After` reach the registry's rate limits.
     fn stub_error(&self) -> Error {
         Error::Provider {
             provider: self.id.clone(),
@@ -0,0 +1,288 @@
+//! Per-provider circuit breakers.
+//!
+//! Surfaced via `GET /v1/router/breakers` and force-closed via
+//! `POST /v1/router/breakers/:provider/reset`.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use sa_domain::config::BreakerConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Breaker state for a single provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakerState {
+    /// Requests flow normally.
+    Closed,
+    /// Too many consecutive failures; requests are routed elsewhere until
+    /// the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; one request at a time is let through as a probe.
+    /// Success closes the breaker, failure re-opens it.
+    HalfOpen,
+}
+
+/// Point-in-time view of one provider's breaker.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub provider: String,
+    pub state: BreakerState,
+    /// Consecutive failures since the last success.
+    pub failure_count: u32,
+    /// When an open breaker will let the next probe through.
+    pub next_probe_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    failure_count: u32,
+    opened_at: Option<DateTime<Utc>>,
+    half_open: bool,
+    /// When the in-flight half-open probe was admitted.
+    probe_started_at: Option<DateTime<Utc>>,
+}
+
+/// How long an unanswered probe holds the half-open slot before another
+/// request may probe (the probing caller may have been cancelled).
+const MIN_PROBE_LEASE_SECS: i64 = 30;
+
+/// Per-provider circuit breakers.
+///
+/// A breaker opens after `failure_threshold` consecutive failures and moves
+/// to half-open once `cooldown_sec` has elapsed, admitting a single probe
+/// until that probe's outcome is recorded.  Uses `parking_lot::Mutex` like
+/// the other in-memory router state.
+pub struct CircuitBreakers {
+    inner: Mutex<HashMap<String, Breaker>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreakers {
+    pub fn new(config: &BreakerConfig) -> Self {
+        Self {
+            inner: Mutex::new(HashMap::new()),
+            failure_threshold: config.failure_threshold.max(1),
+            cooldown: Duration::seconds(config.cooldown_sec as i64),
+        }
+    }
+
+    /// Whether a request may be sent to `provider`, without changing any
+    /// state: use this while choosing between providers, then
+    /// [`Self::try_acquire`] the one that is picked.
+    pub fn allow(&self, provider: &str) -> bool {
+        let map = self.inner.lock();
+        map.get(provider)
+            .is_none_or(|breaker| self.admits(breaker, Utc::now()))
+    }
+
+    /// Claim the right to send a request to `provider`.  A breaker whose
+    /// cooldown has elapsed turns half-open and hands out exactly one probe;
+    /// later callers are refused until the probe's outcome is recorded.
+    pub fn try_acquire(&self, provider: &str) -> bool {
+        let mut map = self.inner.lock();
+        let Some(breaker) = map.get_mut(provider) else {
+            return true;
+        };
+        let now = Utc::now();
+        if !self.admits(breaker, now) {
+            return false;
+        }
+        if breaker.opened_at.is_some() {
+            breaker.half_open = true;
+            breaker.probe_started_at = Some(now);
+        }
+        true
+    }
+
+    /// Whether `breaker` would let a request through at `now`: it is closed,
+    /// or its cooldown has elapsed and no live probe holds the slot.
+    fn admits(&self, breaker: &Breaker, now: DateTime<Utc>) -> bool {
+        let Some(opened_at) = breaker.opened_at else {
+            return true;
+        };
+        if !breaker.half_open && now < opened_at + self.cooldown {
+            return false;
+        }
+        let lease = self.cooldown.max(Duration::seconds(MIN_PROBE_LEASE_SECS));
+        breaker
+            .probe_started_at
+            .is_none_or(|started| now >= started + lease)
+    }
+
+    /// Record a successful call, closing the breaker.
+    pub fn record_success(&self, provider: &str) {
+        self.inner.lock().remove(provider);
+    }
+
+    /// Record a failed call.  Opens the breaker at the threshold, and
+    /// re-opens it immediately when a half-open probe fails.
+    pub fn record_failure(&self, provider: &str) {
+        let mut map = self.inner.lock();
+        let breaker = map.entry(provider.to_owned()).or_default();
+        breaker.failure_count += 1;
+        if breaker.half_open || breaker.failure_count >= self.failure_threshold {
+            if breaker.opened_at.is_none() || breaker.half_open {
+                tracing::warn!(
+                    provider,
+                    failures = breaker.failure_count,
+                    "circuit breaker opened"
+                );
+            }
+            breaker.opened_at = Some(Utc::now());
+            breaker.half_open = false;
+            breaker.probe_started_at = None;
+        }
+    }
+
+    /// Force a provider's breaker closed.
+    pub fn reset(&self, provider: &str) {
+        self.inner.lock().remove(provider);
+    }
+
+    /// Current status of `provider`'s breaker (closed if never tripped).
+    pub fn status(&self, provider: &str) -> BreakerStatus {
+        let map = self.inner.lock();
+        let Some(breaker) = map.get(provider) else {
+            return BreakerStatus {
+                provider: provider.to_owned(),
+                state: BreakerState::Closed,
+                failure_count: 0,
+                next_probe_at: None,
+            };
+        };
+
+        let (state, next_probe_at) = match breaker.opened_at {
+            None => (BreakerState::Closed, None),
+            Some(_) if breaker.half_open => (BreakerState::HalfOpen, None),
+            Some(opened_at) => {
+                let next_probe = opened_at + self.cooldown;
+                if Utc::now() >= next_probe {
+                    (BreakerState::HalfOpen, None)
+                } else {
+                    (BreakerState::Open, Some(next_probe))
+                }
+            }
+        };
+
+        BreakerStatus {
+            provider: provider.to_owned(),
+            state,
+            failure_count: breaker.failure_count,
+            next_probe_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakers(cooldown_sec: u64) -> CircuitBreakers {
+        CircuitBreakers::new(&BreakerConfig {
+            failure_threshold: 3,
+            cooldown_sec,
+        })
+    }
+
+    #[test]
+    fn untracked_provider_is_closed() {
+        let b = breakers(30);
+        assert!(b.allow("openai"));
+        let status = b.status("openai");
+        assert_eq!(status.state, BreakerState::Closed);
+        assert_eq!(status.failure_count, 0);
+        assert!(status.next_probe_at.is_none());
+    }
+
+    #[test]
+    fn trips_open_at_threshold() {
+        let b = breakers(30);
+        b.record_failure("openai");
+        b.record_failure("openai");
+        assert_eq!(b.status("openai").state, BreakerState::Closed);
+        assert!(b.allow("openai"));
+
+        b.record_failure("openai");
+        let status = b.status("openai");
+        assert_eq!(status.state, BreakerState::Open);
+        assert_eq!(status.failure_count, 3);
+        assert!(status.next_probe_at.is_some_and(|t| t > Utc::now()));
+        assert!(!b.allow("openai"));
+    }
+
+    #[test]
+    fn reset_closes_tripped_breaker() {
+        let b = breakers(30);
+        for _ in 0..3 {
+            b.record_failure("openai");
+        }
+        assert_eq!(b.status("openai").state, BreakerState::Open);
+
+        b.reset("openai");
+        let status = b.status("openai");
+        assert_eq!(status.state, BreakerState::Closed);
+        assert_eq!(status.failure_count, 0);
+        assert!(b.allow("openai"));
+    }
+
+    #[test]
+    fn half_open_probe_success_closes_and_failure_reopens() {
+        let b = breakers(0);
+        for _ in 0..3 {
+            b.record_failure("openai");
+        }
+        // Zero cooldown: the next request is admitted as a probe.
+        assert!(b.try_acquire("openai"));
+        assert_eq!(b.status("openai").state, BreakerState::HalfOpen);
+
+        b.record_failure("openai");
+        assert_eq!(b.status("openai").failure_count, 4);
+
+        assert!(b.try_acquire("openai"));
+        b.record_success("openai");
+        assert_eq!(b.status("openai").state, BreakerState::Closed);
+    }
+
+    #[test]
+    fn state_serializes_kebab_case() {
+        let json = serde_json::to_value(BreakerState::HalfOpen).unwrap();
+        assert_eq!(json, "half-open");
+    }
+
+    #[test]
+    fn half_open_admits_one_probe_at_a_time() {
+        let b = breakers(0);
+        for _ in 0..3 {
+            b.record_failure("openai");
+        }
+
+        // Checking availability is side-effect free.
+        assert!(b.allow("openai"));
+        assert!(b.allow("openai"));
+
+        assert!(b.try_acquire("openai"));
+        assert!(!b.allow("openai"));
+        assert!(
+            !b.try_acquire("openai"),
+            "second probe while one is in flight"
+        );
+
+        // The probe fails: the breaker re-opens and (zero cooldown) the
+        // next caller gets a fresh probe.
+        b.record_failure("openai");
+        assert!(b.try_acquire("openai"));
+        assert!(!b.try_acquire("openai"));
+    }
+
+    #[test]
+    fn open_breaker_refuses_to_acquire() {
+        let b = breakers(30);
+        for _ in 0..3 {
+            b.record_failure("openai");
+        }
+        assert!(!b.try_acquire("openai"));
+        assert_eq!(b.status("openai").state, BreakerState::Open);
+    }
+}
@@ -397,12 +397,7 @@ impl EmbeddingClassifier {
             .map_err(|e| Error::Http(format!("embedding request failed: {e}")))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let body_text = resp.text().await.unwrap_or_default();
-            return Err(Error::Provider {
-                provider: config.provider.clone(),
-                message: format!("embedding HTTP {status}: {body_text}"),
-            });
+            return Err(crate::util::error_response(&config.provider, resp).await);
         }
 
         let json: serde_json::Value = resp
@@ -569,7 +564,7 @@ mod tests {
     fn compute_centroid_average() {
         let vectors = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]];
         let centroid = compute_centroid(&vectors);
-        let expected = vec![1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0];
+        let expected = [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0];
         for (a, b) in centroid.iter().zip(expected.iter()) {
             assert!(
                 (a - b).abs() < 1e-6,
@@ -643,7 +638,7 @@ mod tests {
         assert!(prompts.contains_key(&ModelTier::Complex));
         assert!(prompts.contains_key(&ModelTier::Reasoning));
         // Each tier should have multiple reference prompts.
-        for (_tier, texts) in &prompts {
+        for texts in prompts.values() {
             assert!(texts.len() >= 3, "each tier should have at least 3 reference prompts");
         }
     }
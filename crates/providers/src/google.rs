@@ -4,15 +4,18 @@
 //! Auth is via an API key passed as a query parameter (`key={api_key}`).
 
 use crate::auth::AuthRotator;
-use crate::util::from_reqwest;
+use crate::util::{from_reqwest, http_client};
 use crate::traits::{
     ChatRequest, ChatResponse, EmbeddingsRequest, EmbeddingsResponse, LlmProvider, ResponseFormat,
+    ToolChoice,
 };
 use sa_domain::capability::LlmCapabilities;
 use sa_domain::config::ProviderConfig;
 use sa_domain::error::{Error, Result};
 use sa_domain::stream::{BoxStream, StreamEvent, Usage};
-use sa_domain::tool::{ContentPart, Message, MessageContent, Role, ToolCall, ToolDefinition};
+use sa_domain::tool::{
+    ContentPart, ImageSource, Message, MessageContent, Role, ToolCall, ToolDefinition,
+};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -28,6 +31,7 @@ pub struct GoogleProvider {
     default_model: String,
     capabilities: LlmCapabilities,
     client: reqwest::Client,
+    stream_idle_timeout: std::time::Duration,
 }
 
 impl GoogleProvider {
@@ -48,10 +52,7 @@ impl GoogleProvider {
             max_output_tokens: Some(8_192),
         };
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .map_err(from_reqwest)?;
+        let client = http_client(cfg)?;
 
         Ok(Self {
             id: cfg.id.clone(),
@@ -60,6 +61,7 @@ impl GoogleProvider {
             default_model,
             capabilities,
             client,
+            stream_idle_timeout: std::time::Duration::from_secs(cfg.stream_idle_timeout_sec),
         })
     }
 
@@ -116,6 +118,9 @@ impl GoogleProvider {
             body["tools"] = serde_json::json!([{
                 "functionDeclarations": function_declarations,
             }]);
+            if let Some(config) = tool_choice_to_gemini(&req.tool_choice) {
+                body["toolConfig"] = config;
+            }
         }
 
         // Generation config.
@@ -126,6 +131,9 @@ impl GoogleProvider {
         if let Some(max) = req.max_tokens {
             gen_config["maxOutputTokens"] = serde_json::json!(max);
         }
+        if !req.stop.is_empty() {
+            gen_config["stopSequences"] = serde_json::json!(req.stop);
+        }
         match &req.response_format {
             ResponseFormat::Text => {}
             ResponseFormat::JsonObject => {
@@ -239,13 +247,20 @@ fn content_to_gemini_parts(content: &MessageContent) -> Vec<Value> {
             .filter_map(|p| match p {
                 ContentPart::Text { text } => Some(serde_json::json!({"text": text})),
                 ContentPart::Image { url, media_type } => {
-                    let mt = media_type.as_deref().unwrap_or("image/png");
-                    Some(serde_json::json!({
-                        "inlineData": {
-                            "mimeType": mt,
-                            "data": url,
-                        }
-                    }))
+                    Some(match ImageSource::parse(url, media_type.as_deref()) {
+                        ImageSource::Url(url) => serde_json::json!({
+                            "fileData": {
+                                "mimeType": media_type.as_deref().unwrap_or("image/png"),
+                                "fileUri": url,
+                            }
+                        }),
+                        ImageSource::Base64 { media_type, data } => serde_json::json!({
+                            "inlineData": {
+                                "mimeType": media_type,
+                                "data": data,
+                            }
+                        }),
+                    })
                 }
                 _ => None,
             })
@@ -261,6 +276,20 @@ fn tool_to_gemini(tool: &ToolDefinition) -> Value {
     })
 }
 
+/// `toolConfig` value; `None` for `Auto` (the API default).
+fn tool_choice_to_gemini(choice: &ToolChoice) -> Option<Value> {
+    let config = match choice {
+        ToolChoice::Auto => return None,
+        ToolChoice::None => serde_json::json!({ "mode": "NONE" }),
+        ToolChoice::Required => serde_json::json!({ "mode": "ANY" }),
+        ToolChoice::Specific(name) => serde_json::json!({
+            "mode": "ANY",
+            "allowedFunctionNames": [name],
+        }),
+    };
+    Some(serde_json::json!({ "functionCallingConfig": config }))
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Response deserialization
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -460,15 +489,10 @@ impl LlmProvider for GoogleProvider {
             .await
             .map_err(from_reqwest)?;
 
-        let status = resp.status();
-        let resp_text = resp.text().await.map_err(from_reqwest)?;
-
-        if !status.is_success() {
-            return Err(Error::Provider {
-                provider: self.id.clone(),
-                message: format!("HTTP {} - {}", status.as_u16(), resp_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
+        let resp_text = resp.text().await.map_err(from_reqwest)?;
 
         let resp_json: Value = serde_json::from_str(&resp_text)?;
         parse_gemini_response(&resp_json, &model)
@@ -485,7 +509,6 @@ impl LlmProvider for GoogleProvider {
         let entry = self.auth.next_key();
         let url = self.stream_url(&model, &entry.key);
         let body = self.build_body(req);
-        let provider_id = self.id.clone();
         let model_owned = model.clone();
 
         tracing::debug!(provider = %self.id, url = %redact_url_key(&url), "google stream request");
@@ -499,16 +522,11 @@ impl LlmProvider for GoogleProvider {
             .await
             .map_err(from_reqwest)?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            let err_text = resp.text().await.map_err(from_reqwest)?;
-            return Err(Error::Provider {
-                provider: provider_id,
-                message: format!("HTTP {} - {}", status.as_u16(), err_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
 
-        Ok(crate::sse::sse_response_stream(resp, move |data| {
+        Ok(crate::sse::sse_response_stream(resp, self.stream_idle_timeout, move |data| {
             parse_gemini_sse_data(data, &model_owned)
         }))
     }
@@ -552,15 +570,10 @@ impl LlmProvider for GoogleProvider {
             .await
             .map_err(from_reqwest)?;
 
-        let status = resp.status();
-        let resp_text = resp.text().await.map_err(from_reqwest)?;
-
-        if !status.is_success() {
-            return Err(Error::Provider {
-                provider: self.id.clone(),
-                message: format!("HTTP {} - {}", status.as_u16(), resp_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
+        let resp_text = resp.text().await.map_err(from_reqwest)?;
 
         let resp_json: Value = serde_json::from_str(&resp_text)?;
         let embed_arr = resp_json
@@ -594,4 +607,129 @@ impl LlmProvider for GoogleProvider {
     fn provider_id(&self) -> &str {
         &self.id
     }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let entry = self.auth.next_key();
+        let resp = self
+            .client
+            .get(format!(
+                "{}/v1beta/models?pageSize=1000&key={}",
+                self.base_url, entry.key
+            ))
+            .send()
+            .await
+            // The URL carries the API key; keep it out of the error.
+            .map_err(|e| from_reqwest(e.without_url()))?;
+        let body = crate::util::json_response(&self.id, resp).await?;
+        // Gemini names models `models/<id>`.
+        Ok(crate::util::model_ids(&body, "models", "name")
+            .into_iter()
+            .map(|name| match name.strip_prefix("models/") {
+                Some(id) => id.to_owned(),
+                None => name,
+            })
+            .collect())
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_parts_serialize_as_inline_or_file_data() {
+        let msg = Message::user_with_images(
+            "compare",
+            vec![
+                ContentPart::Image {
+                    url: "QUJD".into(),
+                    media_type: Some("image/webp".into()),
+                },
+                ContentPart::Image {
+                    url: "https://example.com/cat.png".into(),
+                    media_type: None,
+                },
+            ],
+        );
+        assert_eq!(
+            content_to_gemini_parts(&msg.content),
+            [
+                serde_json::json!({ "text": "compare" }),
+                serde_json::json!({ "inlineData": { "mimeType": "image/webp", "data": "QUJD" } }),
+                serde_json::json!({
+                    "fileData": { "mimeType": "image/png", "fileUri": "https://example.com/cat.png" }
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_only_content_is_unchanged() {
+        assert_eq!(
+            content_to_gemini_parts(&MessageContent::Text("hi".into())),
+            [serde_json::json!({ "text": "hi" })]
+        );
+    }
+
+    fn provider() -> GoogleProvider {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "google",
+            "kind": "google",
+            "base_url": "https://generativelanguage.googleapis.com",
+            "auth": { "mode": "api_key", "key": "test-key" },
+        }))
+        .unwrap();
+        GoogleProvider::from_config(&cfg).unwrap()
+    }
+
+    #[test]
+    fn stop_sequences_go_in_generation_config() {
+        let req = ChatRequest {
+            messages: vec![Message::user("hi")],
+            stop: vec!["END".into()],
+            ..Default::default()
+        };
+        let body = provider().build_body(&req);
+        assert_eq!(
+            body["generationConfig"]["stopSequences"],
+            serde_json::json!(["END"])
+        );
+
+        let body = provider().build_body(&ChatRequest::default());
+        assert!(body.get("generationConfig").is_none());
+    }
+
+    #[test]
+    fn tool_choice_maps_to_function_calling_config() {
+        let config = |tool_choice: ToolChoice| {
+            let req = ChatRequest {
+                tools: vec![ToolDefinition {
+                    name: "exec".into(),
+                    description: "Run a command".into(),
+                    parameters: serde_json::json!({ "type": "object" }),
+                }],
+                tool_choice,
+                ..Default::default()
+            };
+            provider().build_body(&req)["toolConfig"]["functionCallingConfig"].clone()
+        };
+
+        assert_eq!(config(ToolChoice::Auto), Value::Null);
+        assert_eq!(
+            config(ToolChoice::Required),
+            serde_json::json!({ "mode": "ANY" })
+        );
+        assert_eq!(
+            config(ToolChoice::Specific("exec".into())),
+            serde_json::json!({ "mode": "ANY", "allowedFunctionNames": ["exec"] })
+        );
+        assert_eq!(
+            config(ToolChoice::None),
+            serde_json::json!({ "mode": "NONE" })
+        );
+    }
 }
@@ -1,11 +1,13 @@
 pub mod anthropic;
 pub mod auth;
 pub mod bedrock;
+pub mod breaker;
 pub mod classifier;
 pub mod decisions;
 pub mod google;
 pub mod oauth;
 pub mod openai_compat;
+pub mod rate_limit;
 pub mod registry;
 pub mod router;
 pub mod smart_router;
@@ -18,4 +20,5 @@ pub use registry::ProviderRegistry;
 pub use router::LlmRouter;
 pub use traits::{
     ChatRequest, ChatResponse, EmbeddingsRequest, EmbeddingsResponse, LlmProvider, ResponseFormat,
+    ToolChoice,
 };
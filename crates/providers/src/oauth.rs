@@ -6,9 +6,12 @@
 //! Token lifecycle:
 //! - Access tokens last ~8 days.
 //! - Proactive refresh happens within 5 minutes of expiry.
+//! - A request rejected with 401 triggers one reactive refresh and retry
+//!   (see [`OAuthRefresher`]).
 //! - Tokens are stored at `~/.serialagent/oauth-tokens.json` with `0o600`
 //!   permissions on Unix.
 
+use crate::auth::TokenRefresher;
 use sa_domain::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -471,29 +474,81 @@ pub fn resolve_oauth_token(profile: &str) -> Result<String> {
     let token_resp: TokenResponse = serde_json::from_str(&body)
         .map_err(|e| Error::Auth(format!("parsing refresh response: {e}")))?;
 
-    let expires_in = token_resp
+    let updated_tokens = merge_refreshed(tokens, token_resp);
+    persist_refreshed(profile, &updated_tokens);
+    Ok(updated_tokens.access_token)
+}
+
+/// Combine a refresh response with the previously stored tokens.
+///
+/// The old refresh token is kept when the server does not rotate it.
+fn merge_refreshed(previous: OAuthTokens, resp: TokenResponse) -> OAuthTokens {
+    let expires_in = resp
         .expires_in
         .unwrap_or(DEFAULT_EXPIRES_IN_SECS)
         .min(86_400 * 365); // cap to 1 year to prevent i64 overflow
-    let expires_at = chrono::Utc::now().timestamp() + expires_in as i64;
-
-    let updated_tokens = OAuthTokens {
-        access_token: token_resp.access_token.clone(),
-        refresh_token: token_resp
-            .refresh_token
-            .unwrap_or(tokens.refresh_token),
-        expires_at,
-        email: tokens.email,
-    };
+    OAuthTokens {
+        access_token: resp.access_token,
+        refresh_token: resp.refresh_token.unwrap_or(previous.refresh_token),
+        expires_at: chrono::Utc::now().timestamp() + expires_in as i64,
+        email: previous.email,
+    }
+}
 
-    if let Err(e) = OAuthTokenStore::save(profile, &updated_tokens) {
+fn persist_refreshed(profile: &str, tokens: &OAuthTokens) {
+    if let Err(e) = OAuthTokenStore::save(profile, tokens) {
         tracing::warn!(
             error = %e,
             "failed to persist refreshed OAuth token — using in-memory token"
         );
     }
+}
 
-    Ok(updated_tokens.access_token)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Reactive refresh (used by providers on 401)
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// [`TokenRefresher`] backed by the on-disk OAuth token store.
+///
+/// Used when a provider's access token is rejected before its recorded
+/// expiry (revoked, clock skew, or a long-running process that resolved
+/// the token at startup).
+pub struct OAuthRefresher {
+    profile: String,
+    client: reqwest::Client,
+}
+
+impl OAuthRefresher {
+    pub fn new(profile: impl Into<String>) -> Self {
+        Self {
+            profile: profile.into(),
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenRefresher for OAuthRefresher {
+    async fn refresh(&self) -> Result<String> {
+        let profile = &self.profile;
+        let tokens = OAuthTokenStore::load(profile)?
+            .filter(|t| !t.refresh_token.is_empty())
+            .ok_or_else(|| {
+                Error::Auth(format!(
+                    "no refresh token stored for profile '{profile}' \
+                     — run `serialagent config login {profile}`"
+                ))
+            })?;
+
+        tracing::info!(profile = %profile, "refreshing rejected OAuth access token");
+        let resp = refresh_token_async(&self.client, &tokens.refresh_token).await?;
+        let updated_tokens = merge_refreshed(tokens, resp);
+        persist_refreshed(profile, &updated_tokens);
+        Ok(updated_tokens.access_token)
+    }
 }
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -559,7 +614,7 @@ mod tests {
         write_store_to(&store_path, &store);
 
         let loaded = read_store_from(&store_path);
-        assert!(loaded.profiles.get("nonexistent").is_none());
+        assert!(!loaded.profiles.contains_key("nonexistent"));
     }
 
     #[test]
@@ -797,6 +852,23 @@ mod tests {
         assert_eq!(parsed.email, tokens.email);
     }
 
+    #[test]
+    fn merge_refreshed_keeps_old_refresh_token_when_not_rotated() {
+        let previous = OAuthTokens {
+            access_token: "old-access".into(),
+            refresh_token: "rt_old".into(),
+            expires_at: 0,
+            email: Some("user@example.com".into()),
+        };
+        let resp: TokenResponse =
+            serde_json::from_str(r#"{"access_token":"new-access","expires_in":60}"#).unwrap();
+        let merged = merge_refreshed(previous, resp);
+        assert_eq!(merged.access_token, "new-access");
+        assert_eq!(merged.refresh_token, "rt_old");
+        assert_eq!(merged.email.as_deref(), Some("user@example.com"));
+        assert!(merged.expires_at > chrono::Utc::now().timestamp());
+    }
+
     #[test]
     fn resolve_oauth_token_error_message_is_helpful() {
         // Verify the error message format includes the profile name and
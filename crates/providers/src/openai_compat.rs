@@ -3,16 +3,20 @@
 //! Works with OpenAI, Azure OpenAI, Ollama, vLLM, LM Studio, Together,
 //! and any other endpoint that follows the OpenAI chat completions contract.
 
-use crate::auth::AuthRotator;
+use crate::auth::{AuthRotator, TokenRefresher};
+use crate::oauth::{OAuthRefresher, DEFAULT_OAUTH_PROFILE};
 use crate::traits::{
     ChatRequest, ChatResponse, EmbeddingsRequest, EmbeddingsResponse, LlmProvider, ResponseFormat,
+    ToolChoice,
 };
-use crate::util::from_reqwest;
+use crate::util::{from_reqwest, http_client};
 use sa_domain::capability::LlmCapabilities;
-use sa_domain::config::{ProviderConfig, ProviderKind};
+use sa_domain::config::{AuthMode, ProviderConfig, ProviderKind};
 use sa_domain::error::{Error, Result};
 use sa_domain::stream::{BoxStream, StreamEvent, Usage};
-use sa_domain::tool::{ContentPart, Message, MessageContent, Role, ToolCall, ToolDefinition};
+use sa_domain::tool::{
+    ContentPart, ImageSource, Message, MessageContent, Role, ToolCall, ToolDefinition,
+};
 use serde_json::Value;
 use std::sync::Arc;
 
@@ -37,8 +41,11 @@ pub struct OpenAiCompatProvider {
     default_model: String,
     capabilities: LlmCapabilities,
     client: reqwest::Client,
+    stream_idle_timeout: std::time::Duration,
     /// When true, uses Azure OpenAI URL pattern and omits `model` from body.
     is_azure: bool,
+    /// Renews the access token after a 401 (OAuth device-flow providers).
+    refresher: Option<Arc<dyn TokenRefresher>>,
 }
 
 impl OpenAiCompatProvider {
@@ -79,10 +86,7 @@ impl OpenAiCompatProvider {
             max_output_tokens: Some(16_384),
         };
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .map_err(from_reqwest)?;
+        let client = http_client(cfg)?;
 
         Ok(Self {
             id: cfg.id.clone(),
@@ -93,10 +97,20 @@ impl OpenAiCompatProvider {
             default_model,
             capabilities,
             client,
+            stream_idle_timeout: std::time::Duration::from_secs(cfg.stream_idle_timeout_sec),
             is_azure,
+            refresher: (cfg.auth.mode == AuthMode::OauthDevice).then(|| {
+                Arc::new(OAuthRefresher::new(DEFAULT_OAUTH_PROFILE)) as Arc<dyn TokenRefresher>
+            }),
         })
     }
 
+    /// Override how expired access tokens are renewed.
+    pub fn with_token_refresher(mut self, refresher: Arc<dyn TokenRefresher>) -> Self {
+        self.refresher = Some(refresher);
+        self
+    }
+
     // ── Internal: build authenticated request builder ──────────────
 
     fn authed_post(&self, url: &str) -> reqwest::RequestBuilder {
@@ -108,6 +122,48 @@ impl OpenAiCompatProvider {
             .header("Content-Type", "application/json")
     }
 
+    /// POST `body` to `url`.  When a token refresher is configured, a 401
+    /// triggers one refresh and retry; a second rejection (or a failed
+    /// refresh) surfaces as [`Error::AuthFailed`].
+    async fn post_json(&self, url: &str, body: &Value) -> Result<reqwest::Response> {
+        let resp = self
+            .authed_post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(from_reqwest)?;
+
+        let Some(refresher) = &self.refresher else {
+            return Ok(resp);
+        };
+        if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(resp);
+        }
+
+        tracing::info!(provider = %self.id, "access token rejected, refreshing");
+        let token = refresher.refresh().await.map_err(|e| Error::AuthFailed {
+            provider: self.id.clone(),
+            message: format!("token refresh failed: {e}"),
+        })?;
+        self.auth.set_key(token);
+
+        let resp = self
+            .authed_post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(from_reqwest)?;
+        if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(Error::AuthFailed {
+                provider: self.id.clone(),
+                message: "refreshed access token was rejected \
+                          — run `serialagent config login`"
+                    .into(),
+            });
+        }
+        Ok(resp)
+    }
+
     // ── Internal: build the JSON body ─────────────────────────────
 
     /// Resolve the effective model name for this request.
@@ -157,6 +213,9 @@ impl OpenAiCompatProvider {
         if !req.tools.is_empty() {
             let tools: Vec<Value> = req.tools.iter().map(tool_to_openai).collect();
             body["tools"] = Value::Array(tools);
+            if let Some(choice) = tool_choice_to_openai(&req.tool_choice) {
+                body["tool_choice"] = choice;
+            }
         }
         if let Some(temp) = req.temperature {
             body["temperature"] = serde_json::json!(temp);
@@ -164,6 +223,12 @@ impl OpenAiCompatProvider {
         if let Some(max) = req.max_tokens {
             body["max_tokens"] = serde_json::json!(max);
         }
+        if let Some(seed) = req.seed {
+            body["seed"] = serde_json::json!(seed);
+        }
+        if !req.stop.is_empty() {
+            body["stop"] = serde_json::json!(req.stop);
+        }
         match &req.response_format {
             ResponseFormat::Text => {}
             ResponseFormat::JsonObject => {
@@ -223,6 +288,7 @@ fn msg_to_openai(msg: &Message) -> Value {
     match msg.role {
         Role::Tool => tool_result_to_openai(msg),
         Role::Assistant => assistant_to_openai(msg),
+        Role::User if has_images(&msg.content) => user_parts_to_openai(&msg.content),
         _ => {
             let text = msg.content.extract_all_text();
             serde_json::json!({
@@ -233,6 +299,37 @@ fn msg_to_openai(msg: &Message) -> Value {
     }
 }
 
+fn has_images(content: &MessageContent) -> bool {
+    matches!(content, MessageContent::Parts(parts)
+        if parts.iter().any(|p| matches!(p, ContentPart::Image { .. })))
+}
+
+/// A user message with images, as an OpenAI content-part array.  Inline
+/// images are sent as `data:` URLs.
+fn user_parts_to_openai(content: &MessageContent) -> Value {
+    let MessageContent::Parts(parts) = content else {
+        return serde_json::json!({ "role": "user", "content": content.extract_all_text() });
+    };
+    let content: Vec<Value> = parts
+        .iter()
+        .filter_map(|p| match p {
+            ContentPart::Text { text } => Some(serde_json::json!({
+                "type": "text",
+                "text": text,
+            })),
+            ContentPart::Image { url, media_type } => Some(serde_json::json!({
+                "type": "image_url",
+                "image_url": { "url": ImageSource::parse(url, media_type.as_deref()).to_url() },
+            })),
+            _ => None,
+        })
+        .collect();
+    serde_json::json!({
+        "role": "user",
+        "content": content,
+    })
+}
+
 fn assistant_to_openai(msg: &Message) -> Value {
     let mut obj = serde_json::json!({"role": "assistant"});
     let mut text_parts: Vec<String> = Vec::new();
@@ -323,6 +420,19 @@ fn tool_to_openai(tool: &ToolDefinition) -> Value {
     })
 }
 
+/// `tool_choice` value; `None` for `Auto` (the API default).
+fn tool_choice_to_openai(choice: &ToolChoice) -> Option<Value> {
+    match choice {
+        ToolChoice::Auto => None,
+        ToolChoice::None => Some(serde_json::json!("none")),
+        ToolChoice::Required => Some(serde_json::json!("required")),
+        ToolChoice::Specific(name) => Some(serde_json::json!({
+            "type": "function",
+            "function": { "name": sanitize_tool_name(name) },
+        })),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Response deserialization helpers
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -531,22 +641,12 @@ impl LlmProvider for OpenAiCompatProvider {
 
         tracing::debug!(provider = %self.id, url = %url, "openai_compat chat request");
 
-        let resp = self
-            .authed_post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(from_reqwest)?;
+        let resp = self.post_json(&url, &body).await?;
 
-        let status = resp.status();
-        let resp_text = resp.text().await.map_err(from_reqwest)?;
-
-        if !status.is_success() {
-            return Err(Error::Provider {
-                provider: self.id.clone(),
-                message: format!("HTTP {} - {}", status.as_u16(), resp_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
+        let resp_text = resp.text().await.map_err(from_reqwest)?;
 
         let resp_json: Value = serde_json::from_str(&resp_text)?;
         parse_chat_response(&resp_json)
@@ -562,27 +662,16 @@ impl LlmProvider for OpenAiCompatProvider {
             format!("{}/chat/completions", self.base_url)
         };
         let body = self.build_chat_body(req, true);
-        let provider_id = self.id.clone();
 
         tracing::debug!(provider = %self.id, url = %url, "openai_compat stream request");
 
-        let resp = self
-            .authed_post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(from_reqwest)?;
+        let resp = self.post_json(&url, &body).await?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            let err_text = resp.text().await.map_err(from_reqwest)?;
-            return Err(Error::Provider {
-                provider: provider_id,
-                message: format!("HTTP {} - {}", status.as_u16(), err_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
 
-        Ok(crate::sse::sse_response_stream(resp, parse_sse_data_vec))
+        Ok(crate::sse::sse_response_stream(resp, self.stream_idle_timeout, parse_sse_data_vec))
     }
 
     async fn embeddings(&self, req: EmbeddingsRequest) -> Result<EmbeddingsResponse> {
@@ -601,22 +690,12 @@ impl LlmProvider for OpenAiCompatProvider {
             serde_json::json!({ "model": model, "input": req.input })
         };
 
-        let resp = self
-            .authed_post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(from_reqwest)?;
-
-        let status = resp.status();
-        let resp_text = resp.text().await.map_err(from_reqwest)?;
+        let resp = self.post_json(&url, &body).await?;
 
-        if !status.is_success() {
-            return Err(Error::Provider {
-                provider: self.id.clone(),
-                message: format!("HTTP {} - {}", status.as_u16(), resp_text),
-            });
+        if !resp.status().is_success() {
+            return Err(crate::util::error_response(&self.id, resp).await);
         }
+        let resp_text = resp.text().await.map_err(from_reqwest)?;
 
         let resp_json: Value = serde_json::from_str(&resp_text)?;
         let data = resp_json
@@ -650,4 +729,293 @@ impl LlmProvider for OpenAiCompatProvider {
     fn provider_id(&self) -> &str {
         &self.id
     }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        // Azure serves named deployments, not a model catalogue.
+        if self.is_azure {
+            return Ok(Vec::new());
+        }
+        let entry = self.auth.next_key();
+        let resp = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .header(&self.auth_header, format!("{}{}", self.auth_prefix, entry.key))
+            .send()
+            .await
+            .map_err(from_reqwest)?;
+        let body = crate::util::json_response(&self.id, resp).await?;
+        Ok(crate::util::model_ids(&body, "data", "id"))
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Tests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Minimal HTTP server that answers 200 only for `Bearer <valid>`.
+    async fn spawn_chat_server(valid: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut sock, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    // Read headers plus the announced body before replying.
+                    loop {
+                        let n = sock.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        let text = String::from_utf8_lossy(&buf);
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let len = text[..end]
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_ascii_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                                })
+                                .unwrap_or(0);
+                            if buf.len() >= end + 4 + len {
+                                break;
+                            }
+                        }
+                    }
+                    let text = String::from_utf8_lossy(&buf);
+                    let authorized = text
+                        .lines()
+                        .any(|l| l.eq_ignore_ascii_case(&format!("authorization: Bearer {valid}")));
+                    let (status, body) = if authorized {
+                        (
+                            "200 OK",
+                            r#"{"model":"gpt-test","choices":[{"message":{"role":"assistant","content":"hi"},"finish_reason":"stop"}]}"#,
+                        )
+                    } else {
+                        (
+                            "401 Unauthorized",
+                            r#"{"error":{"message":"token expired"}}"#,
+                        )
+                    };
+                    let resp = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = sock.write_all(resp.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    struct FakeRefresher {
+        result: std::result::Result<&'static str, &'static str>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl TokenRefresher for FakeRefresher {
+        async fn refresh(&self) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.result
+                .map(str::to_string)
+                .map_err(|e| Error::Auth(e.to_string()))
+        }
+    }
+
+    fn oauth_provider(base_url: &str, refresher: Arc<FakeRefresher>) -> OpenAiCompatProvider {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "codex",
+            "kind": "openai_codex_oauth",
+            "base_url": base_url,
+            "auth": { "mode": "oauth_device", "key": "expired-token" },
+        }))
+        .unwrap();
+        OpenAiCompatProvider::from_config(&cfg)
+            .unwrap()
+            .with_token_refresher(refresher)
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_refreshed_and_retried() {
+        let base = spawn_chat_server("fresh-token").await;
+        let refresher = Arc::new(FakeRefresher {
+            result: Ok("fresh-token"),
+            calls: AtomicUsize::new(0),
+        });
+        let provider = oauth_provider(&base, refresher.clone());
+
+        let resp = provider.chat(&ChatRequest::default()).await.unwrap();
+        assert_eq!(resp.content, "hi");
+        assert_eq!(refresher.calls.load(Ordering::SeqCst), 1);
+
+        // The refreshed token is reused without another refresh.
+        provider.chat(&ChatRequest::default()).await.unwrap();
+        assert_eq!(refresher.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_surfaces_auth_failed() {
+        let base = spawn_chat_server("fresh-token").await;
+        let refresher = Arc::new(FakeRefresher {
+            result: Err("refresh token revoked"),
+            calls: AtomicUsize::new(0),
+        });
+        let provider = oauth_provider(&base, refresher.clone());
+
+        let err = provider.chat(&ChatRequest::default()).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::AuthFailed { provider, message }
+                if provider == "codex" && message.contains("refresh token revoked")),
+            "unexpected error: {err}"
+        );
+        assert_eq!(refresher.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_refreshed_token_surfaces_auth_failed() {
+        let base = spawn_chat_server("fresh-token").await;
+        let refresher = Arc::new(FakeRefresher {
+            result: Ok("still-wrong"),
+            calls: AtomicUsize::new(0),
+        });
+        let provider = oauth_provider(&base, refresher);
+
+        let err = provider.chat(&ChatRequest::default()).await.unwrap_err();
+        assert!(
+            matches!(err, Error::AuthFailed { .. }),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn user_images_serialize_as_image_url_parts() {
+        let msg = Message::user_with_images(
+            "what is this?",
+            vec![
+                ContentPart::Image {
+                    url: "https://example.com/cat.png".into(),
+                    media_type: None,
+                },
+                ContentPart::Image {
+                    url: "QUJD".into(),
+                    media_type: Some("image/jpeg".into()),
+                },
+            ],
+        );
+        assert_eq!(
+            msg_to_openai(&msg),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": "what is this?" },
+                    { "type": "image_url", "image_url": { "url": "https://example.com/cat.png" } },
+                    { "type": "image_url", "image_url": { "url": "data:image/jpeg;base64,QUJD" } },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn seed_is_sent_only_when_set() {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "openai",
+            "kind": "openai_compat",
+            "base_url": "https://api.openai.com/v1",
+            "auth": { "mode": "api_key", "key": "sk-test" },
+        }))
+        .unwrap();
+        let provider = OpenAiCompatProvider::from_config(&cfg).unwrap();
+
+        let seeded = ChatRequest {
+            seed: Some(42),
+            ..Default::default()
+        };
+        assert_eq!(provider.build_chat_body(&seeded, false)["seed"], 42);
+
+        let body = provider.build_chat_body(&ChatRequest::default(), false);
+        assert!(body.get("seed").is_none());
+    }
+
+    #[test]
+    fn stop_sequences_are_sent_only_when_set() {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "openai",
+            "kind": "openai_compat",
+            "base_url": "https://api.openai.com/v1",
+            "auth": { "mode": "api_key", "key": "sk-test" },
+        }))
+        .unwrap();
+        let provider = OpenAiCompatProvider::from_config(&cfg).unwrap();
+
+        let req = ChatRequest {
+            stop: vec!["###".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            provider.build_chat_body(&req, false)["stop"],
+            serde_json::json!(["###"])
+        );
+
+        let body = provider.build_chat_body(&ChatRequest::default(), false);
+        assert!(body.get("stop").is_none());
+    }
+
+    #[test]
+    fn tool_choice_maps_to_openai_values() {
+        let cfg: ProviderConfig = serde_json::from_value(serde_json::json!({
+            "id": "openai",
+            "kind": "openai_compat",
+            "base_url": "https://api.openai.com/v1",
+            "auth": { "mode": "api_key", "key": "sk-test" },
+        }))
+        .unwrap();
+        let provider = OpenAiCompatProvider::from_config(&cfg).unwrap();
+        let body = |tool_choice: ToolChoice| {
+            let req = ChatRequest {
+                tools: vec![ToolDefinition {
+                    name: "exec".into(),
+                    description: "Run a command".into(),
+                    parameters: serde_json::json!({ "type": "object" }),
+                }],
+                tool_choice,
+                ..Default::default()
+            };
+            provider.build_chat_body(&req, false)
+        };
+
+        assert!(body(ToolChoice::Auto).get("tool_choice").is_none());
+        assert_eq!(body(ToolChoice::Required)["tool_choice"], "required");
+        assert_eq!(
+            body(ToolChoice::Specific("exec".into()))["tool_choice"],
+            serde_json::json!({ "type": "function", "function": { "name": "exec" } })
+        );
+        assert_eq!(body(ToolChoice::None)["tool_choice"], "none");
+
+        // No tools, no tool_choice (the API rejects one without the other).
+        let req = ChatRequest {
+            tool_choice: ToolChoice::Required,
+            ..Default::default()
+        };
+        let body = provider.build_chat_body(&req, false);
+        assert!(body.get("tool_choice").is_none());
+    }
+
+    #[test]
+    fn text_only_user_message_stays_a_string() {
+        assert_eq!(
+            msg_to_openai(&Message::user("hi")),
+            serde_json::json!({ "role": "user", "content": "hi" })
+        );
+    }
 }
@@ -0,0 +1,142 @@
+//! Per-provider rate-limit windows learned from HTTP 429 responses.
+//!
+//! When a provider answers 429 the runtime records its `Retry-After` delay
+//! here.  Until the window passes, provider selection skips that provider,
+//! and requests pinned to it either wait out a short window or are shed
+//! instead of hammering the provider into another 429.
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use sa_domain::error::Error;
+use std::collections::HashMap;
+
+/// Window applied when a 429 carries no usable `Retry-After`.
+const DEFAULT_RETRY_AFTER_SECS: u64 = 10;
+
+/// Upper bound on a recorded window, so a bogus header can't take a
+/// provider out of rotation for days.
+const MAX_RETRY_AFTER_SECS: u64 = 3600;
+
+/// Per-provider rate-limit windows.  Uses `parking_lot::Mutex` like the
+/// circuit breakers.
+#[derive(Default)]
+pub struct RateLimits {
+    inner: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl RateLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a 429 from `provider`.  An existing longer window is kept.
+    pub fn record(&self, provider: &str, retry_after_secs: Option<u64>) {
+        let secs = retry_after_secs
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECS)
+            .min(MAX_RETRY_AFTER_SECS);
+        let until = Utc::now() + Duration::seconds(secs as i64);
+        let mut map = self.inner.lock();
+        let entry = map.entry(provider.to_owned()).or_insert(until);
+        if until > *entry {
+            *entry = until;
+        }
+        tracing::warn!(provider, retry_after_secs = secs, "provider rate limited");
+    }
+
+    /// Record `err` if it is [`Error::RateLimited`].  Returns whether it was.
+    pub fn record_error(&self, provider: &str, err: &Error) -> bool {
+        match err {
+            Error::RateLimited {
+                retry_after_secs, ..
+            } => {
+                self.record(provider, *retry_after_secs);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Time left before `provider` may be called again, or `None` when it
+    /// is not rate limited.  Expired windows are dropped.
+    pub fn retry_in(&self, provider: &str) -> Option<std::time::Duration> {
+        let mut map = self.inner.lock();
+        let until = *map.get(provider)?;
+        match (until - Utc::now()).to_std() {
+            Ok(left) if !left.is_zero() => Some(left),
+            _ => {
+                map.remove(provider);
+                None
+            }
+        }
+    }
+
+    /// Whether a request may be sent to `provider` right now.
+    pub fn allow(&self, provider: &str) -> bool {
+        self.retry_in(provider).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limited(retry_after_secs: Option<u64>) -> Error {
+        Error::RateLimited {
+            provider: "openai".into(),
+            retry_after_secs,
+            message: "HTTP 429 - slow down".into(),
+        }
+    }
+
+    #[test]
+    fn retry_after_blocks_provider_until_window_passes() {
+        let limits = RateLimits::new();
+        assert!(limits.allow("openai"));
+
+        assert!(limits.record_error("openai", &rate_limited(Some(30))));
+        assert!(!limits.allow("openai"));
+        assert!(limits.allow("anthropic"), "other providers are unaffected");
+        let left = limits.retry_in("openai").unwrap();
+        assert!(left <= std::time::Duration::from_secs(30));
+        assert!(left > std::time::Duration::from_secs(25));
+    }
+
+    #[test]
+    fn elapsed_window_admits_requests_again() {
+        let limits = RateLimits::new();
+        limits.record("openai", Some(0));
+        assert!(limits.allow("openai"));
+        assert!(limits.inner.lock().is_empty(), "expired window is dropped");
+    }
+
+    #[test]
+    fn missing_retry_after_uses_default_and_huge_values_are_capped() {
+        let limits = RateLimits::new();
+        limits.record("openai", None);
+        let left = limits.retry_in("openai").unwrap();
+        assert!(left <= std::time::Duration::from_secs(DEFAULT_RETRY_AFTER_SECS));
+
+        limits.record("google", Some(u64::MAX));
+        let left = limits.retry_in("google").unwrap();
+        assert!(left <= std::time::Duration::from_secs(MAX_RETRY_AFTER_SECS));
+    }
+
+    #[test]
+    fn shorter_window_does_not_shrink_longer_one() {
+        let limits = RateLimits::new();
+        limits.record("openai", Some(60));
+        limits.record("openai", Some(1));
+        assert!(limits.retry_in("openai").unwrap() > std::time::Duration::from_secs(30));
+    }
+
+    #[test]
+    fn other_errors_are_not_recorded() {
+        let limits = RateLimits::new();
+        let err = Error::Provider {
+            provider: "openai".into(),
+            message: "HTTP 500 - boom".into(),
+        };
+        assert!(!limits.record_error("openai", &err));
+        assert!(limits.allow("openai"));
+    }
+}
@@ -6,13 +6,16 @@
 
 use crate::anthropic::AnthropicProvider;
 use crate::bedrock::BedrockProvider;
+use crate::breaker::{BreakerStatus, CircuitBreakers};
 use crate::google::GoogleProvider;
 use crate::openai_compat::OpenAiCompatProvider;
+use crate::rate_limit::RateLimits;
 use crate::traits::LlmProvider;
 use sa_domain::config::{LlmConfig, LlmStartupPolicy, ProviderKind};
 use sa_domain::error::{Error, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // ProviderRegistry
@@ -29,6 +32,28 @@ pub struct ProviderRegistry {
     /// Provider IDs that failed to initialize, with their error messages.
     /// Exposed via [`Self::init_errors`] for dashboard / readiness reporting.
     init_errors: Vec<ProviderInitError>,
+    /// Per-provider circuit breakers, fed by the runtime's call outcomes.
+    breakers: CircuitBreakers,
+    /// Per-provider `Retry-After` windows from observed 429s.
+    rate_limits: RateLimits,
+    /// Discovered model lists keyed by provider ID.
+    model_cache: ModelCache,
+    /// Freshness window for `model_cache`; zero disables discovery.
+    model_cache_ttl: Duration,
+    /// Held by whichever caller is fetching model lists, so concurrent
+    /// reads share one fetch instead of each hitting the providers.
+    model_fetch: Arc<tokio::sync::Mutex<()>>,
+}
+
+type ModelCache = Arc<parking_lot::RwLock<HashMap<String, CachedModels>>>;
+
+/// Providers paired with their IDs, as handed to a model fetch.
+type ProviderList = Vec<(String, Arc<dyn LlmProvider>)>;
+
+/// One provider's model list and when it was fetched.
+struct CachedModels {
+    models: Vec<String>,
+    fetched_at: Instant,
 }
 
 /// Records a provider that failed to initialize.
@@ -182,6 +207,11 @@ impl ProviderRegistry {
             providers,
             roles,
             init_errors,
+            breakers: CircuitBreakers::new(&config.breaker),
+            rate_limits: RateLimits::new(),
+            model_cache: Default::default(),
+            model_cache_ttl: Duration::from_secs(config.model_discovery_ttl_sec),
+            model_fetch: Default::default(),
         })
     }
 
@@ -239,4 +269,339 @@ impl ProviderRegistry {
     pub fn init_errors(&self) -> &[ProviderInitError] {
         &self.init_errors
     }
+
+    /// Circuit breakers for the registered providers.
+    pub fn breakers(&self) -> &CircuitBreakers {
+        &self.breakers
+    }
+
+    /// Rate-limit windows for the registered providers.
+    pub fn rate_limits(&self) -> &RateLimits {
+        &self.rate_limits
+    }
+
+    /// Whether `provider_id` could be picked for a request: it is not
+    /// inside a rate-limit window and its breaker admits calls.  Changes no
+    /// state, so it is safe to call while scanning candidates.
+    pub fn is_available(&self, provider_id: &str) -> bool {
+        self.rate_limits.allow(provider_id) && self.breakers.allow(provider_id)
+    }
+
+    /// Claim `provider_id` for a request: like [`Self::is_available`], but a
+    /// half-open breaker hands its single probe to this caller.  The window
+    /// is checked first so the probe isn't spent on a rate-limited provider.
+    pub fn try_acquire(&self, provider_id: &str) -> bool {
+        self.rate_limits.allow(provider_id) && self.breakers.try_acquire(provider_id)
+    }
+
+    /// Breaker status of every registered provider (sorted by id).
+    pub fn breaker_statuses(&self) -> Vec<BreakerStatus> {
+        self.list_providers()
+            .iter()
+            .map(|id| self.breakers.status(id))
+            .collect()
+    }
+
+    /// Every provider's discovered model IDs, keyed by provider ID.
+    ///
+    /// Lists are served from cache while younger than
+    /// `llm.model_discovery_ttl_sec`.  Stale lists are still served and
+    /// refreshed in the background; only providers with no list yet are
+    /// fetched inline.  At most one fetch runs at a time and callers
+    /// arriving during it reuse its results.  A failed fetch keeps the
+    /// previous list (or an empty one) until the next TTL window so a
+    /// broken provider is not hammered.
+    pub async fn discovered_models(&self) -> BTreeMap<String, Vec<String>> {
+        if self.model_cache_ttl.is_zero() {
+            return BTreeMap::new();
+        }
+
+        let (mut out, stale, missing) = self.cached_models();
+
+        if !stale.is_empty() {
+            // Skip if a fetch is already running; the next read retries.
+            if let Ok(guard) = self.model_fetch.clone().try_lock_owned() {
+                let cache = self.model_cache.clone();
+                tokio::spawn(async move {
+                    fetch_models(&cache, stale).await;
+                    drop(guard);
+                });
+            }
+        }
+
+        if !missing.is_empty() {
+            let _guard = self.model_fetch.lock().await;
+            // Another caller may have fetched these while we waited.
+            let (cached, _, missing) = self.cached_models();
+            out.extend(cached);
+            out.extend(fetch_models(&self.model_cache, missing).await);
+        }
+        out
+    }
+
+    /// Split providers into cached lists (fresh or stale), stale providers
+    /// due a refresh, and providers with no list yet.
+    fn cached_models(&self) -> (BTreeMap<String, Vec<String>>, ProviderList, ProviderList) {
+        let mut out = BTreeMap::new();
+        let mut stale = Vec::new();
+        let mut missing = Vec::new();
+        let cache = self.model_cache.read();
+        for (id, provider) in &self.providers {
+            match cache.get(id) {
+                Some(c) => {
+                    if c.fetched_at.elapsed() >= self.model_cache_ttl {
+                        stale.push((id.clone(), provider.clone()));
+                    }
+                    out.insert(id.clone(), c.models.clone());
+                }
+                None => missing.push((id.clone(), provider.clone())),
+            }
+        }
+        (out, stale, missing)
+    }
+
+    /// Discard cached model lists and fetch them again.
+    pub async fn refresh_models(&self) -> BTreeMap<String, Vec<String>> {
+        if self.model_cache_ttl.is_zero() {
+            return BTreeMap::new();
+        }
+        let _guard = self.model_fetch.lock().await;
+        self.model_cache.write().clear();
+        tracing::info!("model discovery cache cleared");
+        let targets = self
+            .providers
+            .iter()
+            .map(|(id, provider)| (id.clone(), provider.clone()))
+            .collect();
+        fetch_models(&self.model_cache, targets).await
+    }
+
+    /// Register a provider instance directly (tests only).
+    #[cfg(test)]
+    pub(crate) fn insert_provider(&mut self, id: &str, provider: Arc<dyn LlmProvider>) {
+        self.providers.insert(id.to_owned(), provider);
+    }
+
+    /// Force-close a provider's breaker.  Returns `false` for unknown ids.
+    pub fn reset_breaker(&self, provider_id: &str) -> bool {
+        if !self.providers.contains_key(provider_id) {
+            return false;
+        }
+        self.breakers.reset(provider_id);
+        tracing::info!(provider_id, "circuit breaker reset");
+        true
+    }
+}
+
+/// Fetch model lists from `targets` concurrently and store them in `cache`.
+async fn fetch_models(cache: &ModelCache, targets: ProviderList) -> BTreeMap<String, Vec<String>> {
+    let fetched = futures_util::future::join_all(
+        targets
+            .into_iter()
+            .map(|(id, provider)| async move { (id, provider.list_models().await) }),
+    )
+    .await;
+
+    let mut out = BTreeMap::new();
+    let mut cache = cache.write();
+    for (id, result) in fetched {
+        let models = match result {
+            Ok(mut models) => {
+                models.sort();
+                models.dedup();
+                tracing::debug!(provider_id = %id, count = models.len(), "discovered models");
+                models
+            }
+            Err(e) => {
+                tracing::warn!(
+                    provider_id = %id,
+                    error = %mask_secrets(&e.to_string()),
+                    "model discovery failed"
+                );
+                cache.get(&id).map(|c| c.models.clone()).unwrap_or_default()
+            }
+        };
+        cache.insert(
+            id.clone(),
+            CachedModels {
+                models: models.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        out.insert(id, models);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breaker::BreakerState;
+
+    fn registry() -> ProviderRegistry {
+        let config: LlmConfig = serde_json::from_value(serde_json::json!({
+            "breaker": { "failure_threshold": 2 },
+            "providers": [{
+                "id": "openai",
+                "kind": "openai_compat",
+                "base_url": "https://api.openai.com/v1",
+                "auth": { "mode": "api_key", "key": "sk-test" },
+            }],
+        }))
+        .unwrap();
+        ProviderRegistry::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn tripped_breaker_reports_open_until_reset() {
+        let reg = registry();
+        reg.breakers().record_failure("openai");
+        reg.breakers().record_failure("openai");
+
+        let statuses = reg.breaker_statuses();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].provider, "openai");
+        assert_eq!(statuses[0].state, BreakerState::Open);
+        assert_eq!(statuses[0].failure_count, 2);
+
+        assert!(reg.reset_breaker("openai"));
+        assert_eq!(reg.breaker_statuses()[0].state, BreakerState::Closed);
+    }
+
+    #[test]
+    fn rate_limited_provider_is_unavailable_until_window_passes() {
+        let reg = registry();
+        assert!(reg.is_available("openai"));
+
+        reg.rate_limits().record("openai", Some(30));
+        assert!(!reg.is_available("openai"));
+        // A rate-limited provider is not a breaker failure.
+        assert_eq!(reg.breaker_statuses()[0].failure_count, 0);
+
+        let elapsed = registry();
+        elapsed.rate_limits().record("openai", Some(0));
+        assert!(elapsed.is_available("openai"));
+    }
+
+    #[test]
+    fn reset_unknown_provider_is_rejected() {
+        assert!(!registry().reset_breaker("missing"));
+    }
+
+    /// Provider stub whose `list_models` counts calls.
+    struct CountingProvider {
+        calls: std::sync::atomic::AtomicUsize,
+        capabilities: sa_domain::capability::LlmCapabilities,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for CountingProvider {
+        async fn chat(&self, _: &crate::traits::ChatRequest) -> Result<crate::traits::ChatResponse> {
+            Err(unsupported())
+        }
+        async fn chat_stream(
+            &self,
+            _: &crate::traits::ChatRequest,
+        ) -> Result<sa_domain::stream::BoxStream<'static, Result<sa_domain::stream::StreamEvent>>>
+        {
+            Err(unsupported())
+        }
+        async fn embeddings(
+            &self,
+            _: crate::traits::EmbeddingsRequest,
+        ) -> Result<crate::traits::EmbeddingsResponse> {
+            Err(unsupported())
+        }
+        fn capabilities(&self) -> &sa_domain::capability::LlmCapabilities {
+            &self.capabilities
+        }
+        fn provider_id(&self) -> &str {
+            "counting"
+        }
+        async fn list_models(&self) -> Result<Vec<String>> {
+            let n = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            // Yield so concurrent callers overlap with the fetch.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(vec![format!("model-{n}"), "model-b".into()])
+        }
+    }
+
+    fn unsupported() -> Error {
+        Error::Provider {
+            provider: "counting".into(),
+            message: "only list_models is supported".into(),
+        }
+    }
+
+    fn counting_registry() -> (ProviderRegistry, Arc<CountingProvider>) {
+        let mut reg = ProviderRegistry::from_config(&LlmConfig::default()).unwrap();
+        let provider = Arc::new(CountingProvider {
+            calls: Default::default(),
+            capabilities: Default::default(),
+        });
+        reg.insert_provider("counting", provider.clone());
+        (reg, provider)
+    }
+
+    #[tokio::test]
+    async fn model_reads_within_ttl_hit_cache() {
+        let (reg, provider) = counting_registry();
+
+        let first = reg.discovered_models().await;
+        let second = reg.discovered_models().await;
+
+        assert_eq!(first["counting"], vec!["model-0", "model-b"]);
+        assert_eq!(first, second);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_forces_new_fetch() {
+        let (reg, provider) = counting_registry();
+
+        reg.discovered_models().await;
+        let refreshed = reg.refresh_models().await;
+
+        assert_eq!(refreshed["counting"], vec!["model-1", "model-b"]);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        // The refreshed list is what later reads see.
+        assert_eq!(reg.discovered_models().await, refreshed);
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_disables_discovery() {
+        let (mut reg, provider) = counting_registry();
+        reg.model_cache_ttl = Duration::ZERO;
+
+        assert!(reg.discovered_models().await.is_empty());
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn concurrent_cold_reads_share_one_fetch() {
+        let (reg, provider) = counting_registry();
+
+        let (a, b) = tokio::join!(reg.discovered_models(), reg.discovered_models());
+
+        assert_eq!(a, b);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stale_list_is_served_while_refreshing_in_background() {
+        let (mut reg, provider) = counting_registry();
+        reg.model_cache_ttl = Duration::from_millis(1);
+
+        reg.discovered_models().await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        // The stale list comes back immediately; the refetch runs behind it.
+        let stale = reg.discovered_models().await;
+        assert_eq!(stale["counting"], vec!["model-0", "model-b"]);
+
+        drop(reg.model_fetch.lock().await);
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        let cached = reg.model_cache.read()["counting"].models.clone();
+        assert_eq!(cached, vec!["model-1", "model-b"]);
+    }
 }
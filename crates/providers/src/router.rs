@@ -81,7 +81,13 @@ impl LlmRouter {
         // Attempt primary model.
         let (provider_id, model_name) = resolve_model(&role_cfg.model);
         if let Some(provider) = self.registry.get(provider_id) {
-            if Self::check_capabilities(provider.capabilities(), role_cfg) {
+            if let Some(retry_in) = self.registry.rate_limits().retry_in(provider_id) {
+                tracing::warn!(
+                    provider = %provider_id,
+                    retry_in_ms = retry_in.as_millis() as u64,
+                    "primary provider is rate limited, trying fallbacks"
+                );
+            } else if Self::check_capabilities(provider.capabilities(), role_cfg) {
                 req.model = Some(model_name.to_string());
 
                 let start = Instant::now();
@@ -153,6 +159,14 @@ impl LlmRouter {
                 }
             };
 
+            if !self.registry.rate_limits().allow(fb_provider_id) {
+                tracing::warn!(
+                    provider = %fb_provider_id,
+                    "fallback provider is rate limited, skipping"
+                );
+                continue;
+            }
+
             // Check fallback capabilities.
             let cap = fb_provider.capabilities();
             if fallback.require_tools && cap.supports_tools == ToolSupport::None {
@@ -235,21 +249,28 @@ impl LlmRouter {
 
     // ── Internal helpers ───────────────────────────────────────────
 
-    /// Send a chat request with a timeout wrapper.
+    /// Send a chat request with a timeout wrapper.  A 429 opens the
+    /// provider's rate-limit window.
     async fn try_chat(
         &self,
         provider: &Arc<dyn LlmProvider>,
         req: &ChatRequest,
     ) -> Result<ChatResponse> {
         let timeout = std::time::Duration::from_millis(self.default_timeout_ms);
-        match tokio::time::timeout(timeout, provider.chat(req)).await {
+        let result = match tokio::time::timeout(timeout, provider.chat(req)).await {
             Ok(result) => result,
-            Err(_) => Err(Error::Timeout(format!(
+            Err(_) => Err(Error::Transient(format!(
                 "provider '{}' timed out after {}ms",
                 provider.provider_id(),
                 self.default_timeout_ms
             ))),
+        };
+        if let Err(e) = &result {
+            self.registry
+                .rate_limits()
+                .record_error(provider.provider_id(), e);
         }
+        result
     }
 
     /// Check whether a provider's capabilities satisfy a role config's requirements.
@@ -266,11 +287,14 @@ impl LlmRouter {
         true
     }
 
-    /// Determine if an error is retriable (timeout or 5xx-like provider errors).
+    /// Determine if an error is retriable (timeout, 429 or 5xx-like
+    /// provider errors).
     fn is_retriable(err: &Error) -> bool {
         match err {
             Error::Timeout(_) => true,
+            Error::Transient(_) => true,
             Error::Http(_) => true,
+            Error::RateLimited { .. } => true,
             Error::Provider { message, .. } => {
                 // Treat 5xx as retriable.
                 message.contains("HTTP 5")
@@ -310,3 +334,119 @@ fn role_to_string(role: ModelRole) -> String {
         ModelRole::Embedder => "embedder".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{EmbeddingsRequest, EmbeddingsResponse};
+    use sa_domain::config::FallbackConfig;
+    use sa_domain::stream::{BoxStream, StreamEvent};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider stub that answers 429 with `Retry-After: 30` when
+    /// `rate_limited`, and counts its calls.
+    struct StubProvider {
+        id: &'static str,
+        rate_limited: bool,
+        calls: AtomicUsize,
+        capabilities: LlmCapabilities,
+    }
+
+    impl StubProvider {
+        fn new(id: &'static str, rate_limited: bool) -> Arc<Self> {
+            Arc::new(Self {
+                id,
+                rate_limited,
+                calls: AtomicUsize::new(0),
+                capabilities: Default::default(),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn unsupported(&self) -> Error {
+            Error::Provider {
+                provider: self.id.into(),
+                message: "only chat is supported".into(),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for StubProvider {
+        async fn chat(&self, _: &ChatRequest) -> Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.rate_limited {
+                return Err(Error::RateLimited {
+                    provider: self.id.into(),
+                    retry_after_secs: Some(30),
+                    message: "HTTP 429 - slow down".into(),
+                });
+            }
+            Ok(ChatResponse {
+                content: format!("from {}", self.id),
+                tool_calls: Vec::new(),
+                usage: None,
+                model: "m".into(),
+                finish_reason: Some("stop".into()),
+            })
+        }
+        async fn chat_stream(
+            &self,
+            _: &ChatRequest,
+        ) -> Result<BoxStream<'static, Result<StreamEvent>>> {
+            Err(self.unsupported())
+        }
+        async fn embeddings(&self, _: EmbeddingsRequest) -> Result<EmbeddingsResponse> {
+            Err(self.unsupported())
+        }
+        fn capabilities(&self) -> &LlmCapabilities {
+            &self.capabilities
+        }
+        fn provider_id(&self) -> &str {
+            self.id
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limited_primary_is_skipped_until_window_passes() {
+        let primary = StubProvider::new("primary", true);
+        let backup = StubProvider::new("backup", false);
+        let mut registry = ProviderRegistry::from_config(&LlmConfig::default()).unwrap();
+        registry.insert_provider("primary", primary.clone());
+        registry.insert_provider("backup", backup.clone());
+
+        let role = RoleConfig {
+            model: "primary/m".into(),
+            require_tools: false,
+            require_json: false,
+            require_streaming: false,
+            fallbacks: vec![FallbackConfig {
+                model: "backup/m".into(),
+                require_tools: false,
+                require_json: false,
+            }],
+        };
+        let router = LlmRouter::new(registry, HashMap::from([("executor".into(), role)]), 5_000);
+
+        // The 429 falls back and opens primary's window.
+        let first = router
+            .chat_for_role(ModelRole::Executor, ChatRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(first.content, "from backup");
+        assert_eq!(primary.calls(), 1);
+        assert!(!router.registry().rate_limits().allow("primary"));
+
+        // Inside the window primary is not called at all.
+        let second = router
+            .chat_for_role(ModelRole::Executor, ChatRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(second.content, "from backup");
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(backup.calls(), 2);
+    }
+}
@@ -8,8 +8,12 @@
 //! - [`drain_data_lines`] -- pull complete `data:` payloads from an SSE buffer
 //! - [`sse_response_stream`] -- build a `BoxStream` from a response + parser closure
 
+use std::time::Duration;
+
 use crate::util::from_reqwest;
-use sa_domain::error::Result;
+use futures_core::Stream;
+use futures_util::StreamExt;
+use sa_domain::error::{Error, Result};
 use sa_domain::stream::{BoxStream, StreamEvent};
 
 /// Extract complete `data:` payloads from an SSE buffer.
@@ -52,22 +56,52 @@ pub(crate) fn drain_data_lines(buffer: &mut String) -> Vec<String> {
 /// 1. Buffers incoming chunks and drains complete SSE events
 /// 2. Flushes the remaining buffer when the response body closes
 /// 3. Emits a fallback `Done` event if the parser never produced one
+/// 4. Aborts with [`Error::Transient`] if no chunk arrives within `idle_timeout`
 pub(crate) fn sse_response_stream<F>(
     response: reqwest::Response,
+    idle_timeout: Duration,
+    parse_data: F,
+) -> BoxStream<'static, Result<StreamEvent>>
+where
+    F: FnMut(&str) -> Vec<Result<StreamEvent>> + Send + 'static,
+{
+    sse_chunk_stream(response.bytes_stream(), idle_timeout, parse_data)
+}
+
+/// [`sse_response_stream`] over any source of body chunks.
+fn sse_chunk_stream<S, B, F>(
+    chunks: S,
+    idle_timeout: Duration,
     mut parse_data: F,
 ) -> BoxStream<'static, Result<StreamEvent>>
 where
+    S: Stream<Item = reqwest::Result<B>> + Send + 'static,
+    B: AsRef<[u8]> + Send,
     F: FnMut(&str) -> Vec<Result<StreamEvent>> + Send + 'static,
 {
     let stream = async_stream::stream! {
-        let mut response = response;
+        let mut chunks = Box::pin(chunks);
         let mut buffer = String::new();
         let mut done_emitted = false;
+        let mut timed_out = false;
 
         loop {
-            match response.chunk().await {
-                Ok(Some(bytes)) => {
-                    buffer.push_str(&String::from_utf8_lossy(&bytes));
+            let next = match tokio::time::timeout(idle_timeout, chunks.next()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    // A stalled provider: surface a retriable timeout and
+                    // skip the fallback `Done`, since the response is incomplete.
+                    yield Err(Error::Transient(format!(
+                        "stream idle for more than {}s",
+                        idle_timeout.as_secs_f64()
+                    )));
+                    timed_out = true;
+                    break;
+                }
+            };
+            match next {
+                Some(Ok(bytes)) => {
+                    buffer.push_str(&String::from_utf8_lossy(bytes.as_ref()));
 
                     let data_lines = drain_data_lines(&mut buffer);
                     for data in data_lines {
@@ -80,7 +114,7 @@ where
                         }
                     }
                 }
-                Ok(None) => {
+                None => {
                     // Stream ended -- flush any remaining partial event.
                     if !buffer.trim().is_empty() {
                         buffer.push_str("\n\n");
@@ -97,14 +131,14 @@ where
                     }
                     break;
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     yield Err(from_reqwest(e));
                     break;
                 }
             }
         }
 
-        if !done_emitted {
+        if !done_emitted && !timed_out {
             yield Ok(StreamEvent::Done {
                 usage: None,
                 finish_reason: Some("stop".into()),
@@ -186,6 +220,41 @@ mod tests {
         assert_eq!(lines, vec!["{\"key\":\"val\"}"]);
     }
 
+    /// Run the SSE stream over `chunks`, optionally stalling afterwards.
+    async fn collect_events(chunks: Vec<&'static str>, stall: bool) -> Vec<Result<StreamEvent>> {
+        let body = futures_util::stream::iter(
+            chunks
+                .into_iter()
+                .map(|c| Ok::<_, reqwest::Error>(c.as_bytes())),
+        );
+        let stall = futures_util::stream::iter(stall.then_some(()))
+            .flat_map(|_| futures_util::stream::pending());
+        sse_chunk_stream(body.chain(stall), Duration::from_millis(50), |data| {
+            vec![Ok(StreamEvent::Token {
+                text: data.to_string(),
+            })]
+        })
+        .collect()
+        .await
+    }
+
+    #[tokio::test]
+    async fn stream_completes_before_idle_timeout() {
+        let events = collect_events(vec!["data: one\n\n", "data: two\n\n"], false).await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], Ok(StreamEvent::Token { text }) if text == "one"));
+        assert!(matches!(&events[1], Ok(StreamEvent::Token { text }) if text == "two"));
+        assert!(matches!(&events[2], Ok(StreamEvent::Done { .. })));
+    }
+
+    #[tokio::test]
+    async fn stalled_stream_aborts_with_transient_error() {
+        let events = collect_events(vec!["data: one\n\n"], true).await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], Ok(StreamEvent::Token { text }) if text == "one"));
+        assert!(matches!(&events[1], Err(Error::Transient(_))));
+    }
+
     #[test]
     fn drain_incremental_buffering() {
         let mut buf = String::from("data: chunk1");
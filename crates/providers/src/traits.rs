@@ -2,6 +2,7 @@ use sa_domain::capability::LlmCapabilities;
 use sa_domain::error::Result;
 use sa_domain::stream::Usage;
 use sa_domain::stream::{BoxStream, StreamEvent};
+pub use sa_domain::tool::ToolChoice;
 use sa_domain::tool::{Message, ToolCall, ToolDefinition};
 use serde::{Deserialize, Serialize};
 
@@ -48,6 +49,15 @@ pub struct ChatRequest {
     pub response_format: ResponseFormat,
     /// Model identifier override. When `None`, the provider uses its default.
     pub model: Option<String>,
+    /// Sampling seed for best-effort reproducible output.  Sent by
+    /// providers whose API accepts one (OpenAI-compat); ignored elsewhere.
+    pub seed: Option<u64>,
+    /// Sequences that end generation when the model produces one (the
+    /// sequence itself is not returned).  Empty = none.
+    pub stop: Vec<String>,
+    /// Whether and which of `tools` the model may call.  Ignored when
+    /// `tools` is empty.
+    pub tool_choice: ToolChoice,
 }
 
 /// A provider-agnostic chat completion response.
@@ -109,4 +119,13 @@ pub trait LlmProvider: Send + Sync {
 
     /// A unique identifier for this provider instance.
     fn provider_id(&self) -> &str;
+
+    /// Model IDs the provider currently serves, from its model-list API.
+    ///
+    /// Adapters without a discovery endpoint return an empty list.  Callers
+    /// should go through [`crate::registry::ProviderRegistry::discovered_models`],
+    /// which caches the result.
+    async fn list_models(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
 }
@@ -1,20 +1,99 @@
 //! Shared utility functions for provider adapters.
 
-use sa_domain::config::{AuthConfig, AuthMode};
+use std::time::Duration;
+
+use sa_domain::config::{AuthConfig, AuthMode, ProviderConfig};
 use sa_domain::error::{Error, Result};
 
 /// Convert a [`reqwest::Error`] into the domain [`Error`] type.
 ///
-/// Timeout errors map to [`Error::Timeout`]; everything else maps to
-/// [`Error::Http`].
+/// Timeouts (connect or request) map to [`Error::Transient`] so retry and
+/// fallback pick them up; everything else maps to [`Error::Http`].
 pub(crate) fn from_reqwest(e: reqwest::Error) -> Error {
     if e.is_timeout() {
-        Error::Timeout(e.to_string())
+        Error::Transient(e.to_string())
     } else {
         Error::Http(e.to_string())
     }
 }
 
+/// Build a provider's HTTP client with its configured connect and request
+/// timeouts.
+pub(crate) fn http_client(cfg: &ProviderConfig) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(cfg.connect_timeout_sec))
+        .timeout(Duration::from_secs(cfg.request_timeout_sec))
+        .build()
+        .map_err(from_reqwest)
+}
+
+/// Read a JSON response body, turning non-2xx statuses into
+/// [`Error::Provider`].
+pub(crate) async fn json_response(
+    provider_id: &str,
+    resp: reqwest::Response,
+) -> Result<serde_json::Value> {
+    if !resp.status().is_success() {
+        return Err(error_response(provider_id, resp).await);
+    }
+    let text = resp.text().await.map_err(from_reqwest)?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Turn a non-2xx response into an error.  HTTP 429 becomes
+/// [`Error::RateLimited`] carrying the `Retry-After` delay; everything else
+/// is [`Error::Provider`].
+pub(crate) async fn error_response(provider_id: &str, resp: reqwest::Response) -> Error {
+    let status = resp.status();
+    let retry_after_secs = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after);
+    let text = match resp.text().await {
+        Ok(text) => text,
+        Err(e) => return from_reqwest(e),
+    };
+    let message = format!("HTTP {} - {}", status.as_u16(), text);
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Error::RateLimited {
+            provider: provider_id.to_owned(),
+            retry_after_secs,
+            message,
+        }
+    } else {
+        Error::Provider {
+            provider: provider_id.to_owned(),
+            message,
+        }
+    }
+}
+
+/// Parse a `Retry-After` value: delay-seconds or an HTTP date.
+pub(crate) fn parse_retry_after(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(secs.max(0) as u64)
+}
+
+/// Collect `body[list_key][*][id_key]` strings from a model-list response.
+pub(crate) fn model_ids(body: &serde_json::Value, list_key: &str, id_key: &str) -> Vec<String> {
+    body.get(list_key)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|m| m.get(id_key).and_then(|v| v.as_str()))
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Resolve the API key from an [`AuthConfig`].
 ///
 /// Precedence:
@@ -113,6 +192,15 @@ mod tests {
     use super::*;
     use sa_domain::config::AuthMode;
 
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 30 "), Some(30));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(0));
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        assert!(parse_retry_after(&soon).is_some_and(|s| (118..=120).contains(&s)));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
     #[test]
     fn fallback_env_name_basic() {
         assert_eq!(
@@ -0,0 +1,226 @@
+//! Forgetting on request — backs the `memory.forget` agent tool.
+//!
+//! [`forget_matching`] searches for memories matching what the user asked
+//! to forget, hands the matches to an approval callback (deleting user
+//! memories is destructive, so a human confirms), and only then
+//! soft-deletes them.  The returned [`ForgetOutcome`] lists exactly what was
+//! removed so the agent can confirm it back to the user.
+
+use std::future::Future;
+
+use sa_domain::error::Result;
+use serde::Serialize;
+
+use crate::provider::SerialMemoryProvider;
+use crate::types::RagSearchRequest;
+
+/// A memory selected for deletion.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForgetCandidate {
+    pub id: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+/// A candidate whose delete call failed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForgetFailure {
+    pub id: String,
+    pub error: String,
+}
+
+/// Result of a [`forget_matching`] call.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ForgetOutcome {
+    /// No memory matched closely enough; approval was not requested.
+    NothingFound,
+    /// The reviewer declined; nothing was deleted.
+    Denied {
+        reason: String,
+        candidates: Vec<ForgetCandidate>,
+    },
+    /// Approved and deleted (possibly partially).
+    Forgotten {
+        removed: Vec<ForgetCandidate>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        failed: Vec<ForgetFailure>,
+    },
+}
+
+/// What to search for and how to delete.
+#[derive(Debug, Clone)]
+pub struct ForgetRequest {
+    pub query: String,
+    /// Max memories considered.
+    pub limit: u32,
+    /// Matches scoring below this (or unscored) are left alone.
+    pub min_similarity: f64,
+    /// `true` deletes through [`SerialMemoryProvider::delete_memory`],
+    /// which tombstones when the provider is a
+    /// [`SoftDeleteProvider`](crate::SoftDeleteProvider); `false` only sets
+    /// the upstream `deleted` flag.  Neither path hard-deletes directly.
+    pub tombstone: bool,
+}
+
+/// Search for memories matching `req.query`, ask `approve` to confirm the
+/// matches, then soft-delete them.  `approve` returns `Err(reason)` to
+/// decline.  Search failures propagate; per-memory delete failures are
+/// reported in `ForgetOutcome::Forgotten::failed`.
+pub async fn forget_matching<F, Fut>(
+    provider: &dyn SerialMemoryProvider,
+    req: &ForgetRequest,
+    approve: F,
+) -> Result<ForgetOutcome>
+where
+    F: FnOnce(Vec<ForgetCandidate>) -> Fut,
+    Fut: Future<Output = std::result::Result<Vec<ForgetCandidate>, String>>,
+{
+    let resp = provider
+        .search(RagSearchRequest {
+            query: req.query.clone(),
+            limit: Some(req.limit),
+            ..Default::default()
+        })
+        .await?;
+
+    let candidates: Vec<ForgetCandidate> = resp
+        .memories
+        .into_iter()
+        .filter(|m| m.similarity.is_some_and(|s| s >= req.min_similarity))
+        .filter_map(|m| {
+            Some(ForgetCandidate {
+                id: m.id?,
+                content: m.content,
+                similarity: m.similarity,
+            })
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Ok(ForgetOutcome::NothingFound);
+    }
+
+    let approved = match approve(candidates.clone()).await {
+        Ok(approved) => approved,
+        Err(reason) => return Ok(ForgetOutcome::Denied { reason, candidates }),
+    };
+
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+    for candidate in approved {
+        let result = if req.tombstone {
+            provider.delete_memory(&candidate.id).await
+        } else {
+            provider
+                .set_memory_deleted(&candidate.id, true)
+                .await
+                .map(|_| ())
+        };
+        match result {
+            Ok(()) => removed.push(candidate),
+            Err(e) => failed.push(ForgetFailure {
+                id: candidate.id,
+                error: e.to_string(),
+            }),
+        }
+    }
+    Ok(ForgetOutcome::Forgotten { removed, failed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory, StubProvider};
+    use crate::types::RetrievedMemoryDto;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn with_id(id: &str, content: &str, similarity: f64) -> RetrievedMemoryDto {
+        RetrievedMemoryDto {
+            id: Some(id.into()),
+            ..memory(content, None, Some(similarity))
+        }
+    }
+
+    fn request(tombstone: bool) -> ForgetRequest {
+        ForgetRequest {
+            query: "I live in Lyon".into(),
+            limit: 5,
+            min_similarity: 0.75,
+            tombstone,
+        }
+    }
+
+    fn stub() -> StubProvider {
+        StubProvider::new(vec![
+            with_id("m1", "lives in Lyon", 0.92),
+            with_id("m2", "moved to Lyon last year", 0.81),
+            with_id("m3", "likes rust", 0.40),
+        ])
+    }
+
+    #[tokio::test]
+    async fn tombstone_mode_goes_through_delete_memory() {
+        let provider = stub();
+        forget_matching(&provider, &request(true), |c| async move { Ok(c) })
+            .await
+            .unwrap();
+        assert_eq!(*provider.hard_deleted.lock().unwrap(), ["m1", "m2"]);
+    }
+
+    #[tokio::test]
+    async fn approved_matches_are_soft_deleted() {
+        let provider = stub();
+        let asked = AtomicBool::new(false);
+        let outcome = forget_matching(&provider, &request(false), |candidates| {
+            asked.store(true, Ordering::SeqCst);
+            async move { Ok(candidates) }
+        })
+        .await
+        .unwrap();
+
+        assert!(asked.load(Ordering::SeqCst), "approval must be requested");
+        let ForgetOutcome::Forgotten { removed, failed } = outcome else {
+            panic!("expected Forgotten, got {outcome:?}");
+        };
+        let ids: Vec<&str> = removed.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["m1", "m2"]);
+        assert!(failed.is_empty());
+        assert_eq!(
+            *provider.flagged.lock().unwrap(),
+            [("m1".to_owned(), true), ("m2".to_owned(), true)]
+        );
+        assert!(provider.hard_deleted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn denied_approval_deletes_nothing() {
+        let provider = stub();
+        let outcome = forget_matching(&provider, &request(false), |_| async {
+            Err("not now".to_owned())
+        })
+        .await
+        .unwrap();
+
+        let ForgetOutcome::Denied { reason, candidates } = outcome else {
+            panic!("expected Denied, got {outcome:?}");
+        };
+        assert_eq!(reason, "not now");
+        assert_eq!(candidates.len(), 2);
+        assert!(provider.flagged.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn weak_matches_skip_approval() {
+        let provider = StubProvider::new(vec![with_id("m3", "likes rust", 0.40)]);
+        let asked = AtomicBool::new(false);
+        let outcome = forget_matching(&provider, &request(false), |candidates| {
+            asked.store(true, Ordering::SeqCst);
+            async move { Ok(candidates) }
+        })
+        .await
+        .unwrap();
+        assert!(matches!(outcome, ForgetOutcome::NothingFound));
+        assert!(!asked.load(Ordering::SeqCst));
+    }
+}
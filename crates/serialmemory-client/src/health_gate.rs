@@ -0,0 +1,314 @@
+//! `HealthGatedProvider` — a [`SerialMemoryProvider`] wrapper that keeps
+//! the gateway serving turns while SerialMemory is unreachable.
+//!
+//! When a call fails with a connectivity error (`Http` / `Timeout`) the
+//! gate marks the backend down.  While down, context reads (`search`,
+//! `multi_hop_search`, `get_persona`) return empty results immediately
+//! instead of waiting out the request timeout, so USER_FACTS is simply
+//! empty.  Every other call passes straight through.  A background probe
+//! polls `health` and reopens the gate once the server answers again.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sa_domain::error::{Error, Result};
+
+use crate::provider::SerialMemoryProvider;
+use crate::types::{
+    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
+    RagSearchResponse, SessionRequest, UserPersonaRequest,
+};
+
+/// Wraps another provider and degrades reads to empty while it is down.
+pub struct HealthGatedProvider {
+    inner: Arc<dyn SerialMemoryProvider>,
+    healthy: AtomicBool,
+}
+
+impl HealthGatedProvider {
+    /// Wrap `inner`.  The backend is assumed healthy until a call or probe
+    /// says otherwise.
+    pub fn new(inner: Arc<dyn SerialMemoryProvider>) -> Self {
+        Self {
+            inner,
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    /// Whether the last observed call or probe reached the server.
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Check `health` once and update the gate.  Returns the new state.
+    pub async fn probe(&self) -> bool {
+        let result = self.inner.health().await;
+        self.observe(&result);
+        self.is_healthy()
+    }
+
+    /// Probe immediately, then every `interval` while degraded.  The task
+    /// exits once the provider is dropped.
+    pub fn spawn_probe(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let gate: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(interval);
+            let mut first = true;
+            loop {
+                tick.tick().await;
+                let Some(gate) = gate.upgrade() else { break };
+                // The startup probe runs even while the gate is still
+                // optimistically open.
+                if first || !gate.is_healthy() {
+                    gate.probe().await;
+                }
+                first = false;
+            }
+        })
+    }
+
+    fn observe<T>(&self, result: &Result<T>) {
+        match result {
+            Ok(_) => self.mark(true, None),
+            Err(e) if is_unreachable(e) => self.mark(false, Some(e)),
+            // The server answered, just not happily.
+            Err(_) => self.mark(true, None),
+        }
+    }
+
+    fn mark(&self, healthy: bool, cause: Option<&Error>) {
+        let was = self.healthy.swap(healthy, Ordering::Relaxed);
+        match (was, healthy) {
+            (true, false) => tracing::warn!(
+                error = %cause.map(ToString::to_string).unwrap_or_default(),
+                "SerialMemory unreachable — serving turns without memory until it recovers"
+            ),
+            (false, true) => tracing::info!("SerialMemory reachable again — memory reads resumed"),
+            _ => {}
+        }
+    }
+
+    /// Run a context read, or return `empty` without calling the backend
+    /// when it is down (or turns out to be).
+    async fn degraded_read<T, F>(&self, what: &str, empty: impl FnOnce() -> T, call: F) -> Result<T>
+    where
+        F: std::future::Future<Output = Result<T>>,
+    {
+        if !self.is_healthy() {
+            tracing::debug!(
+                call = what,
+                "SerialMemory degraded — returning empty result"
+            );
+            return Ok(empty());
+        }
+        let result = call.await;
+        self.observe(&result);
+        match result {
+            Err(e) if is_unreachable(&e) => Ok(empty()),
+            other => other,
+        }
+    }
+
+    async fn pass<T>(&self, call: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        let result = call.await;
+        self.observe(&result);
+        result
+    }
+}
+
+/// Connectivity failures, as opposed to the server rejecting a request.
+fn is_unreachable(e: &Error) -> bool {
+    matches!(e, Error::Http(_) | Error::Timeout(_))
+}
+
+fn empty_search(query: String) -> RagSearchResponse {
+    RagSearchResponse {
+        query,
+        memories: Vec::new(),
+        count: 0,
+    }
+}
+
+#[async_trait]
+impl SerialMemoryProvider for HealthGatedProvider {
+    async fn search(&self, req: RagSearchRequest) -> Result<RagSearchResponse> {
+        let query = req.query.clone();
+        self.degraded_read("search", || empty_search(query), self.inner.search(req))
+            .await
+    }
+
+    async fn multi_hop_search(
+        &self,
+        req: RagSearchRequest,
+        hops: u32,
+    ) -> Result<RagSearchResponse> {
+        let query = req.query.clone();
+        self.degraded_read(
+            "multi_hop_search",
+            || empty_search(query),
+            self.inner.multi_hop_search(req, hops),
+        )
+        .await
+    }
+
+    async fn answer(&self, req: RagAnswerRequest) -> Result<RagAnswerResponse> {
+        self.pass(self.inner.answer(req)).await
+    }
+
+    async fn ingest(&self, req: MemoryIngestRequest) -> Result<IngestResponse> {
+        self.pass(self.inner.ingest(req)).await
+    }
+
+    async fn get_persona(&self) -> Result<serde_json::Value> {
+        self.degraded_read(
+            "get_persona",
+            || serde_json::json!({}),
+            self.inner.get_persona(),
+        )
+        .await
+    }
+
+    async fn set_persona(&self, req: UserPersonaRequest) -> Result<()> {
+        self.pass(self.inner.set_persona(req)).await
+    }
+
+    async fn init_session(&self, req: SessionRequest) -> Result<serde_json::Value> {
+        self.pass(self.inner.init_session(req)).await
+    }
+
+    async fn end_session(&self, session_id: &str) -> Result<()> {
+        self.pass(self.inner.end_session(session_id)).await
+    }
+
+    async fn graph(&self, hops: u32, limit: u32) -> Result<serde_json::Value> {
+        self.pass(self.inner.graph(hops, limit)).await
+    }
+
+    async fn stats(&self) -> Result<serde_json::Value> {
+        self.pass(self.inner.stats()).await
+    }
+
+    async fn health(&self) -> Result<serde_json::Value> {
+        self.pass(self.inner.health()).await
+    }
+
+    async fn update_memory(&self, id: &str, content: &str) -> Result<serde_json::Value> {
+        self.pass(self.inner.update_memory(id, content)).await
+    }
+
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        self.pass(self.inner.set_memory_deleted(id, deleted)).await
+    }
+
+    async fn delete_memory(&self, id: &str) -> Result<()> {
+        self.pass(self.inner.delete_memory(id)).await
+    }
+
+    // Not observed: the trait defaults succeed without touching the
+    // network, which would wrongly reopen the gate.
+    async fn restore_memory(&self, id: &str) -> Result<bool> {
+        self.inner.restore_memory(id).await
+    }
+
+    async fn purge_expired_tombstones(&self) -> Result<usize> {
+        self.inner.purge_expired_tombstones().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory, StubProvider};
+    use crate::UserFactsBuilder;
+
+    /// Unreachable backend holding one memory and an empty persona.
+    fn down() -> Arc<StubProvider> {
+        Arc::new(StubProvider {
+            persona: Some(serde_json::json!({})),
+            down: AtomicBool::new(true),
+            ..StubProvider::new(vec![memory("likes rust", None, Some(0.9))])
+        })
+    }
+
+    fn reached(backend: &StubProvider) -> usize {
+        backend.calls.load(Ordering::SeqCst)
+    }
+
+    async fn user_facts(provider: &dyn SerialMemoryProvider) -> String {
+        UserFactsBuilder::new(provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await
+    }
+
+    #[tokio::test]
+    async fn turns_proceed_without_facts_while_down_and_resume_after_recovery() {
+        let backend = down();
+        let gate = HealthGatedProvider::new(backend.clone());
+
+        // First turn discovers the outage; facts are just empty.
+        assert!(!user_facts(&gate).await.contains("likes rust"));
+        assert!(!gate.is_healthy());
+        let calls = reached(&backend);
+
+        // Later turns short-circuit without touching the backend.
+        let resp = gate
+            .search(RagSearchRequest {
+                query: "prefs".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(resp.count, 0);
+        assert!(!user_facts(&gate).await.contains("likes rust"));
+        assert_eq!(reached(&backend), calls);
+
+        // Still down: the probe keeps the gate closed.
+        assert!(!gate.probe().await);
+
+        backend.down.store(false, Ordering::SeqCst);
+        assert!(gate.probe().await);
+        assert!(user_facts(&gate).await.contains("- likes rust"));
+    }
+
+    #[tokio::test]
+    async fn server_errors_do_not_close_the_gate() {
+        let gate = HealthGatedProvider::new(down());
+        assert!(gate.stats().await.is_err());
+        assert!(gate.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn background_probe_reopens_the_gate() {
+        let backend = down();
+        let gate = Arc::new(HealthGatedProvider::new(backend.clone()));
+        let probe = gate.spawn_probe(Duration::from_millis(10));
+
+        // The startup probe notices the outage without any traffic.
+        for _ in 0..100 {
+            if !gate.is_healthy() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(!gate.is_healthy());
+
+        backend.down.store(false, Ordering::SeqCst);
+        for _ in 0..100 {
+            if gate.is_healthy() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(gate.is_healthy());
+
+        // Dropping the provider ends the probe task.
+        drop(gate);
+        tokio::time::timeout(Duration::from_secs(1), probe)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
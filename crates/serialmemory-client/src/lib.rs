@@ -41,27 +41,37 @@
 //! # }
 //! ```
 
+pub mod forget;
+pub mod health_gate;
 pub mod mcp;
 pub mod provider;
 pub mod rest;
+pub mod soft_delete;
 pub mod types;
 pub mod user_facts;
 
+#[cfg(test)]
+mod test_support;
+
 // ── Re-exports for ergonomic imports ─────────────────────────────────
 
+pub use forget::{forget_matching, ForgetCandidate, ForgetOutcome, ForgetRequest};
+pub use health_gate::HealthGatedProvider;
 pub use mcp::McpSerialMemoryClient;
 pub use provider::SerialMemoryProvider;
 pub use rest::{from_reqwest, RestSerialMemoryClient};
+pub use soft_delete::SoftDeleteProvider;
 pub use types::{
-    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
-    RagSearchResponse, RetrievedMemoryDto, SessionRequest, UserPersonaRequest,
+    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagMultiHopRequest,
+    RagSearchRequest, RagSearchResponse, RetrievedMemoryDto, SessionRequest, UserPersonaRequest,
 };
-pub use user_facts::UserFactsBuilder;
+pub use user_facts::{PersonaSection, RankedFact, UserFactsBuilder, UserFactsSummary};
 
 use std::sync::Arc;
 
-use sa_domain::config::{SerialMemoryConfig, SmTransport};
+use sa_domain::config::{MemoryUnavailablePolicy, SerialMemoryConfig, SmTransport};
 use sa_domain::error::Result;
+use sa_domain::persistence::PersistenceBackend;
 
 /// Create the appropriate [`SerialMemoryProvider`] based on the transport
 /// config.
@@ -85,7 +95,41 @@ use sa_domain::error::Result;
 /// dedicated `FallbackProvider` wrapper that retries on the secondary
 /// transport — but keep the policy explicit (e.g. "retry reads on MCP,
 /// never retry writes").
-pub fn create_provider(cfg: &SerialMemoryConfig) -> Result<Arc<dyn SerialMemoryProvider>> {
+///
+/// With `availability.on_unavailable = "degrade"` (the default) the
+/// transport is wrapped in a [`HealthGatedProvider`], and its reconnect
+/// probe is started when called inside a Tokio runtime.  When
+/// `soft_delete.enabled` is set, the result is wrapped in a
+/// [`SoftDeleteProvider`] persisting its tombstones through `backend`.
+pub fn create_provider(
+    cfg: &SerialMemoryConfig,
+    backend: Arc<dyn PersistenceBackend>,
+) -> Result<Arc<dyn SerialMemoryProvider>> {
+    let mut provider = create_transport(cfg)?;
+    if cfg.availability.on_unavailable == MemoryUnavailablePolicy::Degrade {
+        let gate = Arc::new(HealthGatedProvider::new(provider));
+        if tokio::runtime::Handle::try_current().is_ok() {
+            gate.spawn_probe(std::time::Duration::from_secs(
+                cfg.availability.probe_interval_secs.max(1),
+            ));
+        }
+        provider = gate;
+    }
+    if !cfg.soft_delete.enabled {
+        return Ok(provider);
+    }
+    tracing::info!(
+        retention_hours = cfg.soft_delete.retention_hours,
+        "SerialMemory soft delete enabled"
+    );
+    Ok(Arc::new(SoftDeleteProvider::with_backend(
+        provider,
+        std::time::Duration::from_secs(cfg.soft_delete.retention_hours * 3600),
+        backend,
+    )))
+}
+
+fn create_transport(cfg: &SerialMemoryConfig) -> Result<Arc<dyn SerialMemoryProvider>> {
     match cfg.transport {
         SmTransport::Rest | SmTransport::Hybrid => {
             let client = RestSerialMemoryClient::new(cfg)?;
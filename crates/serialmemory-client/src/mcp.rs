@@ -18,6 +18,7 @@
 //! | `answer`           | `serialmemory.rag.answer`      |
 //! | `ingest`           | `serialmemory.memories.add`    |
 //! | `update_memory`    | `serialmemory.memories.update` |
+//! | `set_memory_deleted` | `serialmemory.memories.update` |
 //! | `delete_memory`    | `serialmemory.memories.delete` |
 //! | `get_persona`      | `memory_about_user`     |
 //! | `set_persona`      | `execute_tool`     |
@@ -339,6 +340,14 @@ impl SerialMemoryProvider for McpSerialMemoryClient {
         .await
     }
 
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        self.call_tool_via_execute(
+            "lifecycle.memory_update",
+            serde_json::json!({ "memory_id": id, "deleted": deleted }),
+        )
+        .await
+    }
+
     async fn delete_memory(&self, id: &str) -> Result<()> {
         self.call_tool_via_execute(
             "lifecycle.memory_delete",
@@ -2,7 +2,7 @@
 //! SerialMemory backends (REST, MCP, hybrid, mock/test).
 
 use async_trait::async_trait;
-use sa_domain::error::Result;
+use sa_domain::error::{Error, Result};
 
 use crate::types::{
     IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
@@ -18,6 +18,20 @@ pub trait SerialMemoryProvider: Send + Sync {
     /// Semantic search across the memory graph (POST /api/rag/search).
     async fn search(&self, req: RagSearchRequest) -> Result<RagSearchResponse>;
 
+    /// Graph-expanded search that follows entity relationships up to
+    /// `hops` away from the seed matches (POST /api/rag/multi-hop).
+    ///
+    /// Backends without multi-hop support fall back to a single-hop
+    /// [`search`](Self::search).
+    async fn multi_hop_search(
+        &self,
+        req: RagSearchRequest,
+        hops: u32,
+    ) -> Result<RagSearchResponse> {
+        let _ = hops;
+        self.search(req).await
+    }
+
     /// RAG-powered answer grounded in the user's memories (POST /api/rag/answer).
     async fn answer(&self, req: RagAnswerRequest) -> Result<RagAnswerResponse>;
 
@@ -48,6 +62,55 @@ pub trait SerialMemoryProvider: Send + Sync {
     /// Update an existing memory (PATCH /api/memories/{id}).
     async fn update_memory(&self, id: &str, content: &str) -> Result<serde_json::Value>;
 
+    /// Set or clear the soft-delete flag on a memory
+    /// (PATCH /api/memories/{id} with `{"deleted": ...}`).
+    ///
+    /// Backends without a deleted flag report it as unsupported.
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        let _ = (id, deleted);
+        Err(Error::SerialMemory(
+            "set_memory_deleted is not supported by this backend".into(),
+        ))
+    }
+
     /// Delete a memory (DELETE /api/memories/{id}).
     async fn delete_memory(&self, id: &str) -> Result<()>;
+
+    /// Undo a soft delete.  Returns `false` when `id` is not currently
+    /// tombstoned — always the case for backends without soft-delete
+    /// (see [`SoftDeleteProvider`](crate::SoftDeleteProvider)).
+    async fn restore_memory(&self, id: &str) -> Result<bool> {
+        let _ = id;
+        Ok(false)
+    }
+
+    /// Hard-delete tombstoned memories whose retention window has passed.
+    /// Returns how many were purged; a no-op without soft-delete.
+    async fn purge_expired_tombstones(&self) -> Result<usize> {
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory, StubProvider};
+
+    #[tokio::test]
+    async fn multi_hop_falls_back_to_single_hop_search() {
+        let provider = StubProvider::new(vec![memory("likes rust", None, Some(0.9))]);
+        let resp = provider
+            .multi_hop_search(
+                RagSearchRequest {
+                    query: "languages".into(),
+                    ..Default::default()
+                },
+                3,
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.query, "languages");
+        assert_eq!(resp.count, 1);
+        assert_eq!(resp.memories[0].content, "likes rust");
+    }
 }
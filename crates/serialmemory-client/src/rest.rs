@@ -16,8 +16,8 @@ use uuid::Uuid;
 
 use crate::provider::SerialMemoryProvider;
 use crate::types::{
-    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
-    RagSearchResponse, SessionRequest, UserPersonaRequest,
+    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagMultiHopRequest,
+    RagSearchRequest, RagSearchResponse, SessionRequest, UserPersonaRequest,
 };
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -190,6 +190,26 @@ impl SerialMemoryProvider for RestSerialMemoryClient {
         })
     }
 
+    async fn multi_hop_search(
+        &self,
+        req: RagSearchRequest,
+        hops: u32,
+    ) -> Result<RagSearchResponse> {
+        let url = self.url("/api/rag/multi-hop");
+        let req = RagMultiHopRequest {
+            search: req,
+            max_hops: hops,
+        };
+        let resp = self
+            .execute_with_retry("POST /api/rag/multi-hop", || self.http.post(&url).json(&req))
+            .await?;
+
+        let body = resp.text().await.map_err(from_reqwest)?;
+        serde_json::from_str(&body).map_err(|e| {
+            Error::SerialMemory(format!("failed to parse multi-hop response: {e}: {body}"))
+        })
+    }
+
     async fn answer(&self, req: RagAnswerRequest) -> Result<RagAnswerResponse> {
         let url = self.url("/api/rag/answer");
         let resp = self
@@ -296,6 +316,22 @@ impl SerialMemoryProvider for RestSerialMemoryClient {
         })
     }
 
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        let url = self.url(&format!("/api/memories/{id}"));
+        let endpoint = format!("PATCH /api/memories/{id}");
+        let body = serde_json::json!({ "deleted": deleted });
+        let resp = self
+            .execute_with_retry(&endpoint, || self.http.patch(&url).json(&body))
+            .await?;
+
+        let text = resp.text().await.map_err(from_reqwest)?;
+        serde_json::from_str(&text).map_err(|e| {
+            Error::SerialMemory(format!(
+                "failed to parse set_memory_deleted response: {e}: {text}"
+            ))
+        })
+    }
+
     async fn delete_memory(&self, id: &str) -> Result<()> {
         let url = self.url(&format!("/api/memories/{id}"));
         let endpoint = format!("DELETE /api/memories/{id}");
@@ -346,3 +382,88 @@ pub fn from_reqwest(e: reqwest::Error) -> Error {
         Error::Http(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve exactly one HTTP request with `response_body`, returning the
+    /// raw request (head + body) that was received.
+    async fn serve_once(response_body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut chunk).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&buf);
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..head_end]
+                        .lines()
+                        .find_map(|l| {
+                            let (k, v) = l.split_once(':')?;
+                            k.eq_ignore_ascii_case("content-length")
+                                .then(|| v.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if buf.len() >= head_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response_body.len(),
+                response_body
+            );
+            stream.write_all(reply.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf).into_owned()
+        });
+        (base_url, handle)
+    }
+
+    #[tokio::test]
+    async fn multi_hop_search_posts_to_multi_hop_endpoint() {
+        let (base_url, server) = serve_once(
+            r#"{"query":"projects","memories":[{"content":"works on SerialAgent"}],"count":1}"#,
+        )
+        .await;
+        let client = RestSerialMemoryClient::new(&SerialMemoryConfig {
+            base_url,
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let resp = client
+            .multi_hop_search(
+                RagSearchRequest {
+                    query: "projects".into(),
+                    limit: Some(7),
+                    ..Default::default()
+                },
+                3,
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.count, 1);
+        assert_eq!(resp.memories[0].content, "works on SerialAgent");
+
+        let raw = server.await.unwrap();
+        assert!(raw.starts_with("POST /api/rag/multi-hop "), "{raw}");
+        let body: serde_json::Value =
+            serde_json::from_str(&raw[raw.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+        assert_eq!(body["query"], "projects");
+        assert_eq!(body["limit"], 7);
+        assert_eq!(body["maxHops"], 3);
+    }
+}
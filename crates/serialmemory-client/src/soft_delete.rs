@@ -0,0 +1,325 @@
+//! `SoftDeleteProvider` — a [`SerialMemoryProvider`] wrapper that turns
+//! `delete_memory` into a reversible tombstone.
+//!
+//! A soft-deleted memory is flagged `deleted` upstream (so the server can
+//! hide it too) and recorded locally with its deletion time.  Until the
+//! retention window passes it is filtered out of every search/answer
+//! result and can be brought back with `restore_memory`;
+//! `purge_expired_tombstones` then hard-deletes it.
+//!
+//! With a persistence backend the tombstone set is saved under
+//! `memory/tombstones.json` on every change and loaded at startup, so
+//! soft-deleted entries stay hidden and restorable across restarts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sa_domain::error::Result;
+use sa_domain::persistence::PersistenceBackend;
+
+use crate::provider::SerialMemoryProvider;
+use crate::types::{
+    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
+    RagSearchResponse, RetrievedMemoryDto, SessionRequest, UserPersonaRequest,
+};
+
+/// Backend key of the persisted tombstone set.
+const TOMBSTONES_KEY: &str = "memory/tombstones.json";
+
+/// Wraps another provider and tombstones deletes for a retention window.
+pub struct SoftDeleteProvider {
+    inner: Arc<dyn SerialMemoryProvider>,
+    retention: Duration,
+    /// Memory id -> when it was soft-deleted.
+    tombstones: Mutex<HashMap<String, DateTime<Utc>>>,
+    /// Where tombstones are persisted (`None` = process memory only).
+    backend: Option<Arc<dyn PersistenceBackend>>,
+}
+
+impl SoftDeleteProvider {
+    /// Tombstones kept in process memory only.
+    pub fn new(inner: Arc<dyn SerialMemoryProvider>, retention: Duration) -> Self {
+        Self {
+            inner,
+            retention,
+            tombstones: Mutex::new(HashMap::new()),
+            backend: None,
+        }
+    }
+
+    /// Tombstones persisted through `backend`, loading any saved by a
+    /// previous run.
+    pub fn with_backend(
+        inner: Arc<dyn SerialMemoryProvider>,
+        retention: Duration,
+        backend: Arc<dyn PersistenceBackend>,
+    ) -> Self {
+        let tombstones = match backend.load(TOMBSTONES_KEY) {
+            Ok(Some(raw)) => serde_json::from_slice(&raw).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "ignoring unreadable memory tombstones");
+                HashMap::new()
+            }),
+            Ok(None) => HashMap::new(),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to load memory tombstones");
+                HashMap::new()
+            }
+        };
+        Self {
+            inner,
+            retention,
+            tombstones: Mutex::new(tombstones),
+            backend: Some(backend),
+        }
+    }
+
+    /// Save the tombstone set, if persistent.
+    fn save(&self, tombstones: &HashMap<String, DateTime<Utc>>) {
+        let Some(backend) = &self.backend else {
+            return;
+        };
+        let result = serde_json::to_vec(tombstones)
+            .map_err(sa_domain::error::Error::from)
+            .and_then(|json| backend.save(TOMBSTONES_KEY, &json));
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "failed to persist memory tombstones");
+        }
+    }
+
+    fn is_expired(&self, deleted_at: DateTime<Utc>) -> bool {
+        (Utc::now() - deleted_at)
+            .to_std()
+            .is_ok_and(|age| age >= self.retention)
+    }
+
+    /// Whether `id` is currently tombstoned.
+    pub fn is_tombstoned(&self, id: &str) -> bool {
+        self.tombstones.lock().unwrap().contains_key(id)
+    }
+
+    fn hide_tombstoned(&self, memories: &mut Vec<RetrievedMemoryDto>) {
+        let tombstones = self.tombstones.lock().unwrap();
+        if tombstones.is_empty() {
+            return;
+        }
+        memories.retain(|m| {
+            m.id.as_deref()
+                .is_none_or(|id| !tombstones.contains_key(id))
+        });
+    }
+
+    fn filter_search(&self, mut resp: RagSearchResponse) -> RagSearchResponse {
+        let before = resp.memories.len();
+        self.hide_tombstoned(&mut resp.memories);
+        let hidden = (before - resp.memories.len()) as u32;
+        resp.count = resp.count.saturating_sub(hidden);
+        resp
+    }
+}
+
+#[async_trait]
+impl SerialMemoryProvider for SoftDeleteProvider {
+    async fn search(&self, req: RagSearchRequest) -> Result<RagSearchResponse> {
+        let resp = self.inner.search(req).await?;
+        Ok(self.filter_search(resp))
+    }
+
+    async fn multi_hop_search(
+        &self,
+        req: RagSearchRequest,
+        hops: u32,
+    ) -> Result<RagSearchResponse> {
+        let resp = self.inner.multi_hop_search(req, hops).await?;
+        Ok(self.filter_search(resp))
+    }
+
+    async fn answer(&self, req: RagAnswerRequest) -> Result<RagAnswerResponse> {
+        let mut resp = self.inner.answer(req).await?;
+        self.hide_tombstoned(&mut resp.memories);
+        Ok(resp)
+    }
+
+    async fn ingest(&self, req: MemoryIngestRequest) -> Result<IngestResponse> {
+        self.inner.ingest(req).await
+    }
+
+    async fn get_persona(&self) -> Result<serde_json::Value> {
+        self.inner.get_persona().await
+    }
+
+    async fn set_persona(&self, req: UserPersonaRequest) -> Result<()> {
+        self.inner.set_persona(req).await
+    }
+
+    async fn init_session(&self, req: SessionRequest) -> Result<serde_json::Value> {
+        self.inner.init_session(req).await
+    }
+
+    async fn end_session(&self, session_id: &str) -> Result<()> {
+        self.inner.end_session(session_id).await
+    }
+
+    async fn graph(&self, hops: u32, limit: u32) -> Result<serde_json::Value> {
+        self.inner.graph(hops, limit).await
+    }
+
+    async fn stats(&self) -> Result<serde_json::Value> {
+        self.inner.stats().await
+    }
+
+    async fn health(&self) -> Result<serde_json::Value> {
+        self.inner.health().await
+    }
+
+    async fn update_memory(&self, id: &str, content: &str) -> Result<serde_json::Value> {
+        self.inner.update_memory(id, content).await
+    }
+
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        self.inner.set_memory_deleted(id, deleted).await
+    }
+
+    /// Tombstone instead of deleting.  Re-deleting an already tombstoned
+    /// entry keeps the original deletion time.
+    async fn delete_memory(&self, id: &str) -> Result<()> {
+        self.inner.set_memory_deleted(id, true).await?;
+        let mut tombstones = self.tombstones.lock().unwrap();
+        tombstones.entry(id.to_owned()).or_insert_with(Utc::now);
+        self.save(&tombstones);
+        Ok(())
+    }
+
+    async fn restore_memory(&self, id: &str) -> Result<bool> {
+        if !self.is_tombstoned(id) {
+            return Ok(false);
+        }
+        self.inner.set_memory_deleted(id, false).await?;
+        let mut tombstones = self.tombstones.lock().unwrap();
+        let removed = tombstones.remove(id).is_some();
+        self.save(&tombstones);
+        Ok(removed)
+    }
+
+    async fn purge_expired_tombstones(&self) -> Result<usize> {
+        let expired: Vec<String> = self
+            .tombstones
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, deleted_at)| self.is_expired(**deleted_at))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        let mut purged = 0;
+        for id in expired {
+            match self.inner.delete_memory(&id).await {
+                Ok(()) => {
+                    let mut tombstones = self.tombstones.lock().unwrap();
+                    tombstones.remove(&id);
+                    self.save(&tombstones);
+                    purged += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(memory_id = %id, error = %e, "failed to purge tombstoned memory");
+                }
+            }
+        }
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory, StubProvider};
+
+    fn with_id(id: &str, content: &str) -> RetrievedMemoryDto {
+        RetrievedMemoryDto {
+            id: Some(id.into()),
+            ..memory(content, None, Some(0.9))
+        }
+    }
+
+    fn query() -> RagSearchRequest {
+        RagSearchRequest {
+            query: "anything".into(),
+            ..Default::default()
+        }
+    }
+
+    fn stub() -> Arc<StubProvider> {
+        Arc::new(StubProvider::new(vec![
+            with_id("m1", "likes rust"),
+            with_id("m2", "lives in Lyon"),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn soft_delete_hides_entry_and_restore_recovers_it() {
+        let inner = stub();
+        let provider = SoftDeleteProvider::new(inner.clone(), Duration::from_secs(3600));
+
+        provider.delete_memory("m1").await.unwrap();
+        let resp = provider.search(query()).await.unwrap();
+        assert_eq!(resp.count, 1);
+        assert_eq!(resp.memories.len(), 1);
+        assert_eq!(resp.memories[0].id.as_deref(), Some("m2"));
+        assert!(inner.hard_deleted.lock().unwrap().is_empty());
+
+        assert!(provider.restore_memory("m1").await.unwrap());
+        let resp = provider.search(query()).await.unwrap();
+        assert_eq!(resp.count, 2);
+        assert!(resp.memories.iter().any(|m| m.content == "likes rust"));
+
+        assert_eq!(
+            *inner.flagged.lock().unwrap(),
+            vec![("m1".to_string(), true), ("m1".to_string(), false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_of_live_entry_reports_false() {
+        let provider = SoftDeleteProvider::new(stub(), Duration::from_secs(3600));
+        assert!(!provider.restore_memory("m2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn purge_hard_deletes_only_expired_tombstones() {
+        let inner = stub();
+        let provider = SoftDeleteProvider::new(inner.clone(), Duration::from_secs(3600));
+        provider.delete_memory("m1").await.unwrap();
+        assert_eq!(provider.purge_expired_tombstones().await.unwrap(), 0);
+        assert!(provider.is_tombstoned("m1"));
+
+        let provider = SoftDeleteProvider::new(inner.clone(), Duration::ZERO);
+        provider.delete_memory("m2").await.unwrap();
+        assert_eq!(provider.purge_expired_tombstones().await.unwrap(), 1);
+        assert!(!provider.is_tombstoned("m2"));
+        assert_eq!(*inner.hard_deleted.lock().unwrap(), vec!["m2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn tombstones_survive_a_restart() {
+        use sa_domain::persistence::MemoryBackend;
+
+        let inner = stub();
+        let backend: Arc<dyn PersistenceBackend> = Arc::new(MemoryBackend::new());
+        let retention = Duration::from_secs(3600);
+        let provider = SoftDeleteProvider::with_backend(inner.clone(), retention, backend.clone());
+        provider.delete_memory("m1").await.unwrap();
+        drop(provider);
+
+        let restarted = SoftDeleteProvider::with_backend(inner.clone(), retention, backend.clone());
+        assert!(restarted.is_tombstoned("m1"));
+        let resp = restarted.search(query()).await.unwrap();
+        assert_eq!(resp.count, 1);
+        assert_eq!(resp.memories[0].id.as_deref(), Some("m2"));
+
+        assert!(restarted.restore_memory("m1").await.unwrap());
+        let again = SoftDeleteProvider::with_backend(inner, retention, backend);
+        assert!(!again.is_tombstoned("m1"));
+    }
+}
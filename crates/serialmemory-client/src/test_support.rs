@@ -0,0 +1,125 @@
+//! Shared test doubles for the crate's unit tests.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sa_domain::error::{Error, Result};
+
+use crate::provider::SerialMemoryProvider;
+use crate::types::{
+    IngestResponse, MemoryIngestRequest, RagAnswerRequest, RagAnswerResponse, RagSearchRequest,
+    RagSearchResponse, RetrievedMemoryDto, SessionRequest, UserPersonaRequest,
+};
+
+/// Provider that answers every search with a fixed memory list, records
+/// delete/flag calls, serves an optional persona, and fails everything else.  Does not override
+/// `multi_hop_search` or `restore_memory`.  Setting `down` makes `search`,
+/// `get_persona` and `health` fail with a connectivity error.
+#[derive(Default)]
+pub(crate) struct StubProvider {
+    pub memories: Vec<RetrievedMemoryDto>,
+    /// Ids passed to `delete_memory`.
+    pub hard_deleted: Mutex<Vec<String>>,
+    /// `(id, deleted)` pairs passed to `set_memory_deleted`.
+    pub flagged: Mutex<Vec<(String, bool)>>,
+    /// Returned by `get_persona`; `None` fails the call.
+    pub persona: Option<serde_json::Value>,
+    /// Simulates an unreachable server.
+    pub down: AtomicBool,
+    /// `search`, `get_persona` and `health` calls received, up or down.
+    pub calls: AtomicUsize,
+}
+
+impl StubProvider {
+    pub fn new(memories: Vec<RetrievedMemoryDto>) -> Self {
+        Self {
+            memories,
+            ..Default::default()
+        }
+    }
+
+    /// Count a call and fail it if the server is down.
+    fn reach(&self) -> Result<()> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.down.load(Ordering::SeqCst) {
+            return Err(Error::Http("connection refused".into()));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn memory(
+    content: &str,
+    source: Option<&str>,
+    similarity: Option<f64>,
+) -> RetrievedMemoryDto {
+    RetrievedMemoryDto {
+        id: None,
+        content: content.into(),
+        source: source.map(Into::into),
+        similarity,
+        rank: None,
+        created_at: None,
+        metadata: None,
+        entities: None,
+        memory_type: None,
+        layer: None,
+    }
+}
+
+fn unsupported<T>() -> Result<T> {
+    Err(Error::SerialMemory("not supported by stub".into()))
+}
+
+#[async_trait]
+impl SerialMemoryProvider for StubProvider {
+    async fn search(&self, req: RagSearchRequest) -> Result<RagSearchResponse> {
+        self.reach()?;
+        Ok(RagSearchResponse {
+            query: req.query,
+            memories: self.memories.clone(),
+            count: self.memories.len() as u32,
+        })
+    }
+    async fn answer(&self, _req: RagAnswerRequest) -> Result<RagAnswerResponse> {
+        unsupported()
+    }
+    async fn ingest(&self, _req: MemoryIngestRequest) -> Result<IngestResponse> {
+        unsupported()
+    }
+    async fn get_persona(&self) -> Result<serde_json::Value> {
+        self.reach()?;
+        self.persona.clone().map_or_else(unsupported, Ok)
+    }
+    async fn set_persona(&self, _req: UserPersonaRequest) -> Result<()> {
+        unsupported()
+    }
+    async fn init_session(&self, _req: SessionRequest) -> Result<serde_json::Value> {
+        unsupported()
+    }
+    async fn end_session(&self, _session_id: &str) -> Result<()> {
+        unsupported()
+    }
+    async fn graph(&self, _hops: u32, _limit: u32) -> Result<serde_json::Value> {
+        unsupported()
+    }
+    async fn stats(&self) -> Result<serde_json::Value> {
+        unsupported()
+    }
+    async fn health(&self) -> Result<serde_json::Value> {
+        self.reach()?;
+        Ok(serde_json::json!({ "status": "ok" }))
+    }
+    async fn update_memory(&self, _id: &str, _content: &str) -> Result<serde_json::Value> {
+        unsupported()
+    }
+    async fn set_memory_deleted(&self, id: &str, deleted: bool) -> Result<serde_json::Value> {
+        self.flagged.lock().unwrap().push((id.to_owned(), deleted));
+        Ok(serde_json::json!({ "id": id, "deleted": deleted }))
+    }
+    async fn delete_memory(&self, id: &str) -> Result<()> {
+        self.hard_deleted.lock().unwrap().push(id.to_owned());
+        Ok(())
+    }
+}
@@ -46,6 +46,16 @@ pub struct RagSearchResponse {
     pub count: u32,
 }
 
+/// POST /api/rag/multi-hop — request body.  Same shape as a single-hop
+/// search plus the number of graph hops to expand from the seed matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RagMultiHopRequest {
+    #[serde(flatten)]
+    pub search: RagSearchRequest,
+    pub max_hops: u32,
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // RAG answer
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -5,7 +5,13 @@
 //! Gracefully degrades: if SerialMemory is unreachable or returns errors,
 //! the builder returns an empty string rather than propagating the failure.
 
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use sa_domain::config::{FactConflictPolicy, PersonaPosition, UserFactsSourceConfig};
+use sa_domain::tokens::{CharCounter, TokenCounter};
 use sa_domain::trace::TraceEvent;
+use serde::Serialize;
 use tracing::warn;
 
 use crate::provider::SerialMemoryProvider;
@@ -17,14 +23,56 @@ pub struct UserFactsBuilder<'a> {
     user_id: String,
     max_chars: usize,
     search_queries: Vec<String>,
+    source_policy: UserFactsSourceConfig,
+    counter: Arc<dyn TokenCounter>,
+}
+
+/// Structured persona + top facts, as returned by
+/// [`UserFactsBuilder::summary`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserFactsSummary {
+    /// Persona attributes grouped by heading (canonical sections first).
+    pub persona: Vec<PersonaSection>,
+    /// Retrieved facts, best-ranked first.
+    pub facts: Vec<RankedFact>,
+}
+
+/// One titled group of persona attributes.
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonaSection {
+    pub heading: String,
+    /// Attribute lines, e.g. `"**editor**: helix"` or `"likes rust"`.
+    pub items: Vec<String>,
+}
+
+/// A retrieved memory that passed the source policy.
+#[derive(Debug, Clone, Serialize)]
+pub struct RankedFact {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
 }
 
+/// Persona sections + ranked facts, before rendering.
+struct Gathered {
+    sections: Vec<(&'static str, String)>,
+    facts: Vec<RankedFact>,
+    pinned_count: usize,
+    search_count: usize,
+}
+
+/// Appended when the persona sections overflow the budget.
+const TRUNCATED_MARKER: &str = "\n[USER_FACTS_TRUNCATED]\n";
+
 impl<'a> UserFactsBuilder<'a> {
     /// Create a new builder.
     ///
     /// * `provider`       — any implementation of `SerialMemoryProvider`
     /// * `user_id`        — user identifier for trace events
-    /// * `max_chars`      — hard cap on the resulting string length
+    /// * `max_chars`      — hard cap on the resulting size, measured by the
+    ///   builder's token counter (characters by default)
     pub fn new(
         provider: &'a dyn SerialMemoryProvider,
         user_id: impl Into<String>,
@@ -35,6 +83,8 @@ impl<'a> UserFactsBuilder<'a> {
             user_id: user_id.into(),
             max_chars,
             search_queries: Vec::new(),
+            source_policy: UserFactsSourceConfig::default(),
+            counter: Arc::new(CharCounter),
         }
     }
 
@@ -52,11 +102,107 @@ impl<'a> UserFactsBuilder<'a> {
         self
     }
 
+    /// Filter and order retrieved memories by their ingest `source`
+    /// (see [`UserFactsSourceConfig`]).  Persona attributes are unaffected.
+    pub fn with_source_policy(mut self, policy: UserFactsSourceConfig) -> Self {
+        self.source_policy = policy;
+        self
+    }
+
+    /// Measure the budget with `counter` instead of raw characters.
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.counter = counter;
+        self
+    }
+
     /// Fetch persona + search results and assemble the USER_FACTS string.
     ///
     /// Never fails — returns an empty string on error.
     pub async fn build(&self) -> String {
-        let mut sections: Vec<(&str, String)> = Vec::new();
+        let gathered = self.gather().await;
+
+        // ── 3. Assemble markdown ─────────────────────────────────────
+        // The leading side gets the budget first; the other fills whatever
+        // remains.  Facts are best-ranked first, so truncation only ever
+        // drops the weakest.
+        let ranked_facts: Vec<String> = gathered.facts.into_iter().map(|f| f.content).collect();
+        let counter = self.counter.as_ref();
+        let assembled = match self.source_policy.persona_position {
+            PersonaPosition::First => {
+                let mut assembled = self.assemble_markdown(&gathered.sections, self.max_chars);
+                if !ranked_facts.is_empty() && !assembled.ends_with("[USER_FACTS_TRUNCATED]\n") {
+                    let budget = self.max_chars.saturating_sub(counter.count(&assembled));
+                    assembled.push_str(&fit_ranked_facts(&ranked_facts, budget, counter));
+                }
+                assembled
+            }
+            PersonaPosition::Last => {
+                let mut assembled = fit_ranked_facts(&ranked_facts, self.max_chars, counter);
+                let budget = self.max_chars.saturating_sub(counter.count(&assembled));
+                // Skip the persona outright when not even the marker fits.
+                if budget > counter.count(TRUNCATED_MARKER) {
+                    assembled.push_str(&self.assemble_markdown(&gathered.sections, budget));
+                }
+                assembled
+            }
+        };
+
+        // ── 4. Emit trace event ──────────────────────────────────────
+        TraceEvent::UserFactsFetched {
+            user_id: self.user_id.clone(),
+            facts_chars: assembled.len(),
+            pinned_count: gathered.pinned_count,
+            search_count: gathered.search_count,
+        }
+        .emit();
+
+        assembled
+    }
+
+    /// Fetch the same persona + ranked facts as [`build`](Self::build), but
+    /// return them structured (for UI display) instead of rendered into the
+    /// prompt.  At most `max_facts` facts are kept; the character budget
+    /// does not apply.
+    ///
+    /// Never fails — sections the server can't provide are left empty.
+    pub async fn summary(&self, max_facts: usize) -> UserFactsSummary {
+        let gathered = self.gather().await;
+
+        let mut persona: Vec<PersonaSection> = Vec::new();
+        for (heading, body) in &gathered.sections {
+            // "Other" bodies carry their own `**Heading**` line per group.
+            if *heading != "Other" {
+                persona.push(PersonaSection {
+                    heading: (*heading).to_owned(),
+                    items: Vec::new(),
+                });
+            }
+            for line in body.lines() {
+                if let Some(sub) = line.strip_prefix("**").and_then(|l| l.strip_suffix("**")) {
+                    persona.push(PersonaSection {
+                        heading: sub.to_owned(),
+                        items: Vec::new(),
+                    });
+                    continue;
+                }
+                let item = line.strip_prefix("- ").unwrap_or(line).trim();
+                if item.is_empty() {
+                    continue;
+                }
+                if let Some(section) = persona.last_mut() {
+                    section.items.push(item.to_owned());
+                }
+            }
+        }
+
+        let mut facts = gathered.facts;
+        facts.truncate(max_facts);
+        UserFactsSummary { persona, facts }
+    }
+
+    /// Fetch persona sections and the ranked, de-duplicated search facts.
+    async fn gather(&self) -> Gathered {
+        let mut sections: Vec<(&'static str, String)> = Vec::new();
         let mut pinned_count: usize = 0;
         let mut search_count: usize = 0;
 
@@ -92,8 +238,17 @@ impl<'a> UserFactsBuilder<'a> {
                 Ok(resp) => {
                     for mem in &resp.memories {
                         let content = mem.content.trim();
-                        if !content.is_empty() {
-                            retrieved_facts.push(content.to_owned());
+                        let source = mem.source.as_deref().unwrap_or("");
+                        if !content.is_empty() && self.source_policy.admits(source, mem.similarity)
+                        {
+                            retrieved_facts.push((
+                                self.source_policy.preference_rank(source),
+                                RankedFact {
+                                    content: content.to_owned(),
+                                    source: mem.source.clone(),
+                                    similarity: mem.similarity,
+                                },
+                            ));
                         }
                     }
                     search_count += resp.memories.len();
@@ -109,36 +264,34 @@ impl<'a> UserFactsBuilder<'a> {
             }
         }
 
-        if !retrieved_facts.is_empty() {
-            // De-duplicate (stable order)
-            let mut seen = std::collections::HashSet::new();
-            let mut unique = Vec::new();
-            for fact in &retrieved_facts {
-                if seen.insert(fact.clone()) {
-                    unique.push(fact.clone());
-                }
+        // Preferred sources first, then highest similarity.  Stable, so
+        // retrieval order breaks ties; unscored facts sort last.
+        retrieved_facts.sort_by(|(rank_a, a), (rank_b, b)| {
+            rank_a.cmp(rank_b).then_with(|| {
+                let a = a.similarity.unwrap_or(f64::NEG_INFINITY);
+                let b = b.similarity.unwrap_or(f64::NEG_INFINITY);
+                b.total_cmp(&a)
+            })
+        });
+
+        // De-duplicate, keeping the best-ranked occurrence.
+        let mut seen = std::collections::HashSet::new();
+        let mut facts = Vec::new();
+        for (_, fact) in retrieved_facts {
+            if seen.insert(fact.content.clone()) {
+                facts.push(fact);
             }
-            let body = unique
-                .iter()
-                .map(|f| format!("- {f}"))
-                .collect::<Vec<_>>()
-                .join("\n");
-            sections.push(("Retrieved Facts", body));
         }
 
-        // ── 3. Assemble markdown ─────────────────────────────────────
-        let assembled = self.assemble_markdown(&sections);
+        reconcile_persona_facts(&mut sections, &mut facts, &self.source_policy);
+        pinned_count = pinned_count.min(sections.iter().map(|(_, v)| v.lines().count()).sum());
 
-        // ── 4. Emit trace event ──────────────────────────────────────
-        TraceEvent::UserFactsFetched {
-            user_id: self.user_id.clone(),
-            facts_chars: assembled.len(),
+        Gathered {
+            sections,
+            facts,
             pinned_count,
             search_count,
         }
-        .emit();
-
-        assembled
     }
 
     // ── internal helpers ─────────────────────────────────────────────
@@ -294,30 +447,28 @@ impl<'a> UserFactsBuilder<'a> {
         }
     }
 
-    /// Assemble titled sections into final markdown, respecting `max_chars`.
-    fn assemble_markdown(&self, sections: &[(&str, String)]) -> String {
+    /// Assemble titled sections into markdown, respecting `budget`.
+    fn assemble_markdown(&self, sections: &[(&str, String)], budget: usize) -> String {
         if sections.is_empty() {
             return String::new();
         }
 
+        let counter = self.counter.as_ref();
+        // Room reserved for the truncation marker when cutting a section.
+        let reserve = counter.count(TRUNCATED_MARKER) + 1;
         let mut output = String::new();
 
         for (heading, body) in sections {
             let section_block = format!("### {heading}\n{body}\n\n");
+            let used = counter.count(&output);
 
-            if output.len() + section_block.len() > self.max_chars {
+            if used + counter.count(&section_block) > budget {
                 // Try to fit a partial section
-                let remaining = self.max_chars.saturating_sub(output.len());
-                if remaining > 30 {
+                let remaining = budget.saturating_sub(used);
+                if remaining > reserve + 5 {
                     // Enough room for at least a heading + truncation marker
-                    let truncated = &section_block[..section_block
-                        .char_indices()
-                        .take_while(|(i, _)| *i < remaining.saturating_sub(25))
-                        .last()
-                        .map(|(i, c)| i + c.len_utf8())
-                        .unwrap_or(0)];
-                    output.push_str(truncated);
-                    output.push_str("\n[USER_FACTS_TRUNCATED]\n");
+                    output.push_str(counter.truncate(&section_block, remaining - reserve));
+                    output.push_str(TRUNCATED_MARKER);
                 } else {
                     output.push_str("[USER_FACTS_TRUNCATED]\n");
                 }
@@ -328,21 +479,122 @@ impl<'a> UserFactsBuilder<'a> {
         }
 
         // Final length check (defensive)
-        if output.len() > self.max_chars {
-            let cut = output
-                .char_indices()
-                .take_while(|(i, _)| *i < self.max_chars.saturating_sub(25))
-                .last()
-                .map(|(i, c)| i + c.len_utf8())
-                .unwrap_or(0);
+        if counter.count(&output) > budget {
+            let cut = counter
+                .truncate(&output, budget.saturating_sub(reserve))
+                .len();
             output.truncate(cut);
-            output.push_str("\n[USER_FACTS_TRUNCATED]\n");
+            output.push_str(TRUNCATED_MARKER);
         }
 
         output
     }
 }
 
+/// Apply the persona/fact merge policy: drop facts that restate a persona
+/// attribute (when `dedupe_persona` is set), then resolve `key: value`
+/// facts that contradict a persona attribute per `on_conflict`.  Persona
+/// sections left without attributes are removed.
+fn reconcile_persona_facts(
+    sections: &mut Vec<(&'static str, String)>,
+    facts: &mut Vec<RankedFact>,
+    policy: &UserFactsSourceConfig,
+) {
+    let persona: Vec<String> = sections
+        .iter()
+        .flat_map(|(_, body)| body.lines())
+        .filter(|line| line.starts_with("- "))
+        .map(normalize_statement)
+        .collect();
+    if persona.is_empty() {
+        return;
+    }
+
+    if policy.dedupe_persona {
+        facts.retain(|f| !persona.contains(&normalize_statement(&f.content)));
+    }
+
+    // Facts whose key the persona also sets, with a different value.
+    let conflicting_keys: HashSet<String> = facts
+        .iter()
+        .filter_map(|f| {
+            let fact = normalize_statement(&f.content);
+            let (key, value) = statement_key_value(&fact)?;
+            let mut persona_values = persona
+                .iter()
+                .filter_map(|p| statement_key_value(p))
+                .filter(|(k, _)| *k == key)
+                .map(|(_, v)| v)
+                .peekable();
+            let conflicts = persona_values.peek().is_some() && persona_values.all(|v| v != value);
+            conflicts.then(|| key.to_owned())
+        })
+        .collect();
+    if conflicting_keys.is_empty() {
+        return;
+    }
+
+    let is_conflicting = |statement: &str| {
+        let normalized = normalize_statement(statement);
+        statement_key_value(&normalized).is_some_and(|(k, _)| conflicting_keys.contains(k))
+    };
+    match policy.on_conflict {
+        FactConflictPolicy::PersonaWins => facts.retain(|f| !is_conflicting(&f.content)),
+        FactConflictPolicy::FactsWin => {
+            for (_, body) in sections.iter_mut() {
+                *body = body
+                    .lines()
+                    .filter(|line| !(line.starts_with("- ") && is_conflicting(line)))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+            sections.retain(|(_, body)| body.lines().any(|line| line.starts_with("- ")));
+        }
+    }
+}
+
+/// Lower-cased, markup-free form of a persona line or fact, for comparing
+/// the two: `"- **Editor**: Helix."` and `"editor: helix"` are equal.
+fn normalize_statement(s: &str) -> String {
+    let s = s.trim();
+    let s = s.strip_prefix("- ").unwrap_or(s).replace("**", "");
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches('.')
+        .to_lowercase()
+}
+
+/// Split a normalized `key: value` statement.  Only short keys count, so
+/// ordinary sentences containing a colon are not treated as attributes.
+fn statement_key_value(normalized: &str) -> Option<(&str, &str)> {
+    const MAX_KEY_LEN: usize = 40;
+    let (key, value) = normalized.split_once(": ")?;
+    (!key.is_empty() && key.len() <= MAX_KEY_LEN && !value.is_empty()).then_some((key, value))
+}
+
+/// Render ranked facts as a `### Retrieved Facts` section, taking them in
+/// order until the next one would push the section past `budget` (measured
+/// by `counter`).  Returns an empty string when not even the first fact fits.
+fn fit_ranked_facts(facts: &[String], budget: usize, counter: &dyn TokenCounter) -> String {
+    let mut block = String::from("### Retrieved Facts\n");
+    let mut kept = 0;
+    for fact in facts {
+        let line = format!("- {fact}\n");
+        // The blank line closes the section.
+        if counter.count(&format!("{block}{line}\n")) > budget {
+            break;
+        }
+        block.push_str(&line);
+        kept += 1;
+    }
+    if kept == 0 {
+        return String::new();
+    }
+    block.push('\n');
+    block
+}
+
 /// Simple title-case helper: `"some_key"` -> `"Some Key"`.
 fn title_case(s: &str) -> String {
     s.replace('_', " ")
@@ -364,6 +616,296 @@ fn title_case(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{memory, StubProvider};
+
+    #[tokio::test]
+    async fn excluded_source_is_omitted() {
+        let provider = StubProvider::new(vec![
+            memory("likes rust", Some("explicit"), Some(0.9)),
+            memory("said hi once", Some("auto_capture"), Some(0.9)),
+        ]);
+        let policy = UserFactsSourceConfig {
+            exclude_sources: vec!["auto_capture".into()],
+            ..Default::default()
+        };
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(policy)
+            .build()
+            .await;
+        assert!(out.contains("- likes rust"));
+        assert!(!out.contains("said hi once"));
+    }
+
+    #[tokio::test]
+    async fn per_source_threshold_drops_low_confidence() {
+        let provider = StubProvider::new(vec![
+            memory("strong capture", Some("auto_capture"), Some(0.8)),
+            memory("weak capture", Some("auto_capture"), Some(0.4)),
+            memory("unscored capture", Some("auto_capture"), None),
+            memory("weak explicit", Some("explicit"), Some(0.4)),
+        ]);
+        let mut policy = UserFactsSourceConfig::default();
+        policy.min_similarity.insert("auto_capture".into(), 0.6);
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(policy)
+            .build()
+            .await;
+        assert!(out.contains("- strong capture"));
+        assert!(!out.contains("weak capture"));
+        assert!(!out.contains("unscored capture"));
+        assert!(out.contains("- weak explicit"));
+    }
+
+    #[tokio::test]
+    async fn preferred_sources_come_first() {
+        let provider = StubProvider::new(vec![
+            memory("captured fact", Some("auto_capture"), Some(0.9)),
+            memory("unsourced fact", None, Some(0.9)),
+            memory("explicit fact", Some("explicit"), Some(0.5)),
+        ]);
+        let policy = UserFactsSourceConfig {
+            preferred_sources: vec!["explicit".into()],
+            ..Default::default()
+        };
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(policy)
+            .build()
+            .await;
+        let explicit = out.find("explicit fact").unwrap();
+        let captured = out.find("captured fact").unwrap();
+        let unsourced = out.find("unsourced fact").unwrap();
+        assert!(explicit < captured);
+        assert!(captured < unsourced);
+    }
+
+    #[tokio::test]
+    async fn facts_are_ordered_by_score() {
+        let provider = StubProvider::new(vec![
+            memory("low", None, Some(0.35)),
+            memory("unscored", None, None),
+            memory("high", None, Some(0.95)),
+            memory("mid", None, Some(0.6)),
+        ]);
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await;
+        let pos = |s: &str| out.find(s).unwrap();
+        assert!(pos("- high") < pos("- mid"));
+        assert!(pos("- mid") < pos("- low"));
+        assert!(pos("- low") < pos("- unscored"));
+    }
+
+    #[tokio::test]
+    async fn tight_budget_keeps_top_scored_facts() {
+        let provider = StubProvider::new(vec![
+            memory("fact ranked third", None, Some(0.5)),
+            memory("fact ranked first", None, Some(0.9)),
+            memory("fact ranked fourth", None, Some(0.4)),
+            memory("fact ranked second", None, Some(0.7)),
+        ]);
+        // Heading (20) + the two best lines (41) + closing newline = 62.
+        let max_chars = 70;
+        let out = UserFactsBuilder::new(&provider, "u", max_chars)
+            .with_query("prefs")
+            .build()
+            .await;
+        assert!(out.len() <= max_chars, "{} > {max_chars}", out.len());
+        assert!(out.contains("- fact ranked first"));
+        assert!(out.contains("- fact ranked second"));
+        assert!(!out.contains("third"));
+        assert!(!out.contains("fourth"));
+    }
+
+    #[test]
+    fn fit_ranked_facts_respects_budget() {
+        let facts = vec!["aaaa".to_string(), "bbbb".to_string()];
+        assert_eq!(fit_ranked_facts(&facts, 10, &CharCounter), "");
+        let one = fit_ranked_facts(&facts, 28, &CharCounter);
+        assert_eq!(one, "### Retrieved Facts\n- aaaa\n\n");
+        assert!(one.len() <= 28);
+        let both = fit_ranked_facts(&facts, 100, &CharCounter);
+        assert_eq!(both, "### Retrieved Facts\n- aaaa\n- bbbb\n\n");
+    }
+
+    #[tokio::test]
+    async fn token_budget_admits_facts_a_char_budget_drops() {
+        use sa_domain::tokens::ApproxTokenCounter;
+
+        let provider = StubProvider::new(vec![
+            memory("fact ranked first", None, Some(0.9)),
+            memory("fact ranked second", None, Some(0.7)),
+        ]);
+        // Both facts need 62 chars but only 16 approximate tokens.
+        let by_chars = UserFactsBuilder::new(&provider, "u", 50)
+            .with_query("prefs")
+            .build()
+            .await;
+        let by_tokens = UserFactsBuilder::new(&provider, "u", 50)
+            .with_query("prefs")
+            .with_token_counter(Arc::new(ApproxTokenCounter))
+            .build()
+            .await;
+        assert!(by_chars.contains("- fact ranked first"));
+        assert!(!by_chars.contains("second"));
+        assert!(by_tokens.contains("- fact ranked first"));
+        assert!(by_tokens.contains("- fact ranked second"));
+    }
+
+    #[tokio::test]
+    async fn default_policy_keeps_everything() {
+        let provider = StubProvider::new(vec![
+            memory("a", Some("auto_capture"), None),
+            memory("b", None, None),
+        ]);
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await;
+        assert!(out.contains("- a"));
+        assert!(out.contains("- b"));
+    }
+
+    #[tokio::test]
+    async fn summary_returns_persona_and_ranked_facts() {
+        let provider = StubProvider {
+            persona: Some(serde_json::json!({
+                "preferences": ["dark mode", { "key": "editor", "value": "helix" }],
+                "pets": ["a cat"],
+            })),
+            ..StubProvider::new(vec![
+                memory("weak fact", None, Some(0.3)),
+                memory("strong fact", Some("explicit"), Some(0.9)),
+                memory("middle fact", None, Some(0.6)),
+            ])
+        };
+        let summary = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("about")
+            .summary(2)
+            .await;
+
+        let headings: Vec<&str> = summary.persona.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(headings, ["Preferences", "Pets"]);
+        assert_eq!(summary.persona[0].items, ["dark mode", "**editor**: helix"]);
+        assert_eq!(summary.persona[1].items, ["a cat"]);
+
+        let facts: Vec<&str> = summary.facts.iter().map(|f| f.content.as_str()).collect();
+        assert_eq!(facts, ["strong fact", "middle fact"]);
+        assert_eq!(summary.facts[0].source.as_deref(), Some("explicit"));
+    }
+
+    #[tokio::test]
+    async fn summary_of_empty_memory_is_empty() {
+        // No persona (the call fails) and no search hits.
+        let provider = StubProvider::new(Vec::new());
+        let summary = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("about")
+            .summary(10)
+            .await;
+        assert!(summary.persona.is_empty());
+        assert!(summary.facts.is_empty());
+    }
+
+    fn editor_persona(memories: Vec<crate::types::RetrievedMemoryDto>) -> StubProvider {
+        StubProvider {
+            persona: Some(serde_json::json!({
+                "preferences": [{ "key": "editor", "value": "helix" }, "dark mode"],
+            })),
+            ..StubProvider::new(memories)
+        }
+    }
+
+    #[tokio::test]
+    async fn persona_statement_in_search_results_is_not_repeated() {
+        let provider = editor_persona(vec![
+            memory("Editor: Helix.", None, Some(0.9)),
+            memory("likes rust", None, Some(0.8)),
+        ]);
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await;
+        assert!(out.contains("- **editor**: helix"));
+        assert!(!out.contains("Editor: Helix."));
+        assert!(out.contains("- likes rust"));
+
+        let policy = UserFactsSourceConfig {
+            dedupe_persona: false,
+            ..Default::default()
+        };
+        let out = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(policy)
+            .build()
+            .await;
+        assert!(out.contains("- Editor: Helix."));
+    }
+
+    #[tokio::test]
+    async fn persona_position_orders_the_block() {
+        let provider = editor_persona(vec![memory("likes rust", None, Some(0.8))]);
+        let first = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await;
+        let last = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(UserFactsSourceConfig {
+                persona_position: PersonaPosition::Last,
+                ..Default::default()
+            })
+            .build()
+            .await;
+
+        let position = |out: &str, heading: &str| out.find(heading).unwrap();
+        assert!(position(&first, "### Preferences") < position(&first, "### Retrieved Facts"));
+        assert!(position(&last, "### Retrieved Facts") < position(&last, "### Preferences"));
+    }
+
+    #[tokio::test]
+    async fn persona_leading_gets_the_budget_first() {
+        let provider = editor_persona(vec![memory(&"x".repeat(40), None, Some(0.8))]);
+        // Room for the persona section (50 chars) or the fact (64), not both.
+        let out = UserFactsBuilder::new(&provider, "u", 70)
+            .with_query("prefs")
+            .with_source_policy(UserFactsSourceConfig {
+                persona_position: PersonaPosition::Last,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(out.starts_with("### Retrieved Facts"));
+        assert!(!out.contains("dark mode"));
+        assert!(out.len() <= 70, "{} > 70", out.len());
+    }
+
+    #[tokio::test]
+    async fn conflicting_fact_resolution_follows_policy() {
+        let provider = editor_persona(vec![memory("editor: vim", None, Some(0.9))]);
+
+        let persona_wins = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .build()
+            .await;
+        assert!(persona_wins.contains("**editor**: helix"));
+        assert!(!persona_wins.contains("vim"));
+
+        let facts_win = UserFactsBuilder::new(&provider, "u", 4000)
+            .with_query("prefs")
+            .with_source_policy(UserFactsSourceConfig {
+                on_conflict: FactConflictPolicy::FactsWin,
+                ..Default::default()
+            })
+            .build()
+            .await;
+        assert!(facts_win.contains("- editor: vim"));
+        assert!(!facts_win.contains("helix"));
+        // The rest of the section survives.
+        assert!(facts_win.contains("- dark mode"));
+    }
 
     #[test]
     fn test_title_case() {
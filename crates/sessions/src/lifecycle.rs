@@ -197,6 +197,8 @@ mod tests {
             context_tokens: 0,
             sm_session_id: None,
             origin: Default::default(),
+            tags: Vec::new(),
+            pinned_model: None,
         };
         let meta = InboundMetadata {
             is_direct: true,
@@ -175,7 +175,7 @@ impl TranscriptIndex {
 
         // Sort by score descending and take top results.
         let mut results: Vec<_> = scored.into_iter().collect();
-        results.sort_by(|a, b| b.1.cmp(&a.1));
+        results.sort_by_key(|b| std::cmp::Reverse(b.1));
         results.truncate(MAX_RESULTS);
 
         results
@@ -1,6 +1,7 @@
 //! Gateway-owned session store.
 //!
-//! Persists session state in `sessions.json` under the configured state path.
+//! Persists session state as `sessions/sessions.json` through a
+//! [`PersistenceBackend`] (the state path on disk by default).
 //! Each session key maps to a `SessionEntry` tracking the session ID, token
 //! counters, origin metadata, and the SerialMemory session ID.
 
@@ -13,6 +14,9 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
 use sa_domain::error::{Error, Result};
+use sa_domain::persistence::{
+    tag_unversioned, FsBackend, LoadedRecord, PersistenceBackend, RecordSchema,
+};
 use sa_domain::trace::TraceEvent;
 
 use crate::search::{SearchHit, TranscriptIndex};
@@ -44,6 +48,14 @@ pub struct SessionEntry {
     pub sm_session_id: Option<String>,
     #[serde(default)]
     pub origin: SessionOrigin,
+    /// User-assigned labels (e.g. `"work"`, `"personal"`).  Kept across
+    /// resets since they describe the session key, not one conversation.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Model spec (`provider/model`) this session always uses unless a
+    /// request names one explicitly.  Kept across resets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_model: Option<String>,
 }
 
 /// Origin metadata describing where the session came from.
@@ -70,27 +82,42 @@ impl From<&sa_domain::config::InboundMetadata> for SessionOrigin {
 // Session store
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-/// Gateway-owned session store backed by a JSON file.
+/// Backend key holding the session table.
+const SESSIONS_KEY: &str = "sessions/sessions.json";
+
+/// Schema of persisted [`SessionEntry`] records.
+const SESSION_SCHEMA: RecordSchema = RecordSchema {
+    current: 2,
+    migrations: &[tag_unversioned],
+};
+
+/// Gateway-owned session store backed by a JSON blob.
 pub struct SessionStore {
-    sessions_path: PathBuf,
+    backend: Arc<dyn PersistenceBackend>,
+    /// Transcripts are append-only files and always live on local disk.
+    transcript_dir: PathBuf,
     sessions: RwLock<HashMap<String, SessionEntry>>,
+    /// Entries written by a newer release, kept verbatim and re-saved.
+    future: serde_json::Map<String, serde_json::Value>,
     search_index: Arc<TranscriptIndex>,
 }
 
 impl SessionStore {
     /// Load or create the session store at `state_path/sessions/sessions.json`.
     pub fn new(state_path: &Path) -> Result<Self> {
+        Self::with_backend(state_path, Arc::new(FsBackend::new(state_path)))
+    }
+
+    /// Load the session table from `backend`.  Transcripts are still read
+    /// from `state_path/sessions`.
+    pub fn with_backend(state_path: &Path, backend: Arc<dyn PersistenceBackend>) -> Result<Self> {
         let dir = state_path.join("sessions");
         std::fs::create_dir_all(&dir)
             .map_err(Error::Io)?;
 
-        let sessions_path = dir.join("sessions.json");
-        let sessions = if sessions_path.exists() {
-            let raw = std::fs::read_to_string(&sessions_path)
-                .map_err(Error::Io)?;
-            serde_json::from_str(&raw).unwrap_or_default()
-        } else {
-            HashMap::new()
+        let (sessions, future) = match backend.load(SESSIONS_KEY)? {
+            Some(raw) => load_entries(&raw),
+            None => Default::default(),
         };
 
         // Build the full-text search index from existing transcript files.
@@ -98,13 +125,15 @@ impl SessionStore {
 
         tracing::info!(
             sessions = sessions.len(),
-            path = %sessions_path.display(),
+            key = SESSIONS_KEY,
             "session store loaded"
         );
 
         Ok(Self {
-            sessions_path,
+            backend,
+            transcript_dir: dir,
             sessions: RwLock::new(sessions),
+            future,
             search_index,
         })
     }
@@ -143,6 +172,8 @@ impl SessionStore {
             context_tokens: 0,
             sm_session_id: None,
             origin,
+            tags: Vec::new(),
+            pinned_model: None,
         };
 
         let mut sessions = self.sessions.write();
@@ -219,6 +250,36 @@ impl SessionStore {
         }
     }
 
+    /// Replace a session's tags.  Tags are trimmed, empty ones dropped and
+    /// duplicates removed (first occurrence wins).  Returns the updated
+    /// entry, or `None` if the session does not exist.
+    pub fn set_tags(&self, session_key: &str, tags: Vec<String>) -> Option<SessionEntry> {
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+                normalized.push(tag.to_owned());
+            }
+        }
+        let mut sessions = self.sessions.write();
+        let entry = sessions.get_mut(session_key)?;
+        entry.tags = normalized;
+        Some(entry.clone())
+    }
+
+    /// Pin (or with `None`, unpin) the model a session uses.  Returns the
+    /// updated entry, or `None` if the session does not exist.
+    pub fn set_pinned_model(
+        &self,
+        session_key: &str,
+        model: Option<String>,
+    ) -> Option<SessionEntry> {
+        let mut sessions = self.sessions.write();
+        let entry = sessions.get_mut(session_key)?;
+        entry.pinned_model = model;
+        Some(entry.clone())
+    }
+
     /// Touch the updated_at timestamp.
     pub fn touch(&self, session_key: &str) {
         let mut sessions = self.sessions.write();
@@ -232,22 +293,28 @@ impl SessionStore {
         self.sessions.read().values().cloned().collect()
     }
 
-    /// Persist the current session state to disk.
+    /// Persist the current session state to the backend.
     ///
     /// Serializes under the read lock (avoiding a full HashMap clone), then
-    /// releases the lock before writing. The blocking `std::fs::write` is
+    /// releases the lock before writing. The blocking backend write is
     /// offloaded via [`tokio::task::spawn_blocking`] so the async runtime
     /// is never stalled. Other readers are not blocked (RwLock allows
     /// concurrent reads).
     pub async fn flush(&self) -> Result<()> {
         let json = {
             let sessions = self.sessions.read();
-            serde_json::to_string(&*sessions)
-                .map_err(|e| Error::Other(format!("serializing sessions: {e}")))?
+            let mut table = self.future.clone();
+            for (key, entry) in sessions.iter() {
+                let value = SESSION_SCHEMA
+                    .stamp(entry)
+                    .map_err(|e| Error::Other(format!("serializing sessions: {e}")))?;
+                table.insert(key.clone(), value);
+            }
+            serde_json::Value::Object(table).to_string()
         };
-        let path = self.sessions_path.clone();
+        let backend = self.backend.clone();
         tokio::task::spawn_blocking(move || {
-            std::fs::write(&path, json).map_err(Error::Io)
+            backend.save(SESSIONS_KEY, json.as_bytes())
         })
         .await
         .map_err(|e| Error::Other(format!("flush join error: {e}")))?
@@ -268,13 +335,46 @@ impl SessionStore {
 
     /// Return the transcript directory for a given session ID.
     pub fn transcript_dir(&self) -> PathBuf {
-        self.sessions_path
-            .parent()
-            .unwrap_or(Path::new("."))
-            .to_path_buf()
+        self.transcript_dir.clone()
     }
 }
 
+/// Parse the session table, upgrading older entries and setting aside
+/// ones from a newer release.  Unreadable entries are dropped.
+fn load_entries(
+    raw: &[u8],
+) -> (HashMap<String, SessionEntry>, serde_json::Map<String, serde_json::Value>) {
+    let mut sessions = HashMap::new();
+    let mut future = serde_json::Map::new();
+    let table: serde_json::Map<String, serde_json::Value> = match serde_json::from_slice(raw) {
+        Ok(table) => table,
+        Err(e) => {
+            tracing::warn!(error = %e, "session table unreadable; starting empty");
+            return (sessions, future);
+        }
+    };
+    for (key, value) in table {
+        match SESSION_SCHEMA.load::<SessionEntry>(value) {
+            LoadedRecord::Current(entry) => {
+                sessions.insert(key, entry);
+            }
+            LoadedRecord::Future(value) => {
+                future.insert(key, value);
+            }
+            LoadedRecord::Invalid(e) => {
+                tracing::warn!(session_key = %key, error = %e, "dropping unreadable session entry");
+            }
+        }
+    }
+    if !future.is_empty() {
+        tracing::warn!(
+            count = future.len(),
+            "session table has entries from a newer release; preserving them unread"
+        );
+    }
+    (sessions, future)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,4 +407,161 @@ mod tests {
         assert!(origin.peer.is_none());
         assert!(origin.group.is_none());
     }
+
+    /// Create a session, bump its counters, flush and reload.
+    async fn round_trip(state: &Path, backend: Arc<dyn PersistenceBackend>) -> SessionEntry {
+        let store = SessionStore::with_backend(state, backend.clone()).unwrap();
+        let (entry, is_new) = store.resolve_or_create("agent:main:dm:42", SessionOrigin::default());
+        assert!(is_new);
+        store.record_usage("agent:main:dm:42", 10, 5);
+        store.set_sm_session_id("agent:main:dm:42", "sm-1".into());
+        store.flush().await.unwrap();
+
+        let reloaded = SessionStore::with_backend(state, backend).unwrap();
+        let got = reloaded.get("agent:main:dm:42").unwrap();
+        assert_eq!(got.session_id, entry.session_id);
+        assert!(!reloaded.resolve_or_create("agent:main:dm:42", SessionOrigin::default()).1);
+        got
+    }
+
+    #[tokio::test]
+    async fn memory_and_fs_backends_behave_identically() {
+        let fs_dir = tempfile::tempdir().unwrap();
+        let mem_dir = tempfile::tempdir().unwrap();
+
+        let on_disk = round_trip(
+            fs_dir.path(),
+            Arc::new(FsBackend::new(fs_dir.path())),
+        )
+        .await;
+        let in_memory = round_trip(
+            mem_dir.path(),
+            Arc::new(sa_domain::persistence::MemoryBackend::new()),
+        )
+        .await;
+
+        for got in [&on_disk, &in_memory] {
+            assert_eq!(got.input_tokens, 10);
+            assert_eq!(got.output_tokens, 5);
+            assert_eq!(got.total_tokens, 15);
+            assert_eq!(got.sm_session_id.as_deref(), Some("sm-1"));
+        }
+        // The default backend keeps the historical on-disk layout.
+        assert!(fs_dir.path().join("sessions/sessions.json").is_file());
+        assert!(!mem_dir.path().join("sessions/sessions.json").exists());
+    }
+
+    #[tokio::test]
+    async fn v1_entries_upgrade_and_future_entries_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend: Arc<dyn PersistenceBackend> =
+            Arc::new(sa_domain::persistence::MemoryBackend::new());
+        // v1: untagged, and predating the `origin` and `sm_session_id` fields.
+        let table = serde_json::json!({
+            "agent:main:dm:1": {
+                "session_key": "agent:main:dm:1",
+                "session_id": "s-1",
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z",
+                "input_tokens": 7
+            },
+            "agent:main:dm:2": {
+                "schema_version": 3,
+                "session_key": "agent:main:dm:2",
+                "identity": { "renamed": true }
+            }
+        });
+        backend
+            .save(SESSIONS_KEY, table.to_string().as_bytes())
+            .unwrap();
+
+        let store = SessionStore::with_backend(dir.path(), backend.clone()).unwrap();
+        let legacy = store.get("agent:main:dm:1").unwrap();
+        assert_eq!(legacy.session_id, "s-1");
+        assert_eq!(legacy.input_tokens, 7);
+        assert!(store.get("agent:main:dm:2").is_none());
+        store.flush().await.unwrap();
+
+        let saved: serde_json::Value =
+            serde_json::from_slice(&backend.load(SESSIONS_KEY).unwrap().unwrap()).unwrap();
+        assert_eq!(saved["agent:main:dm:1"]["schema_version"], 2);
+        assert_eq!(saved["agent:main:dm:2"], table["agent:main:dm:2"]);
+
+        let reloaded = SessionStore::with_backend(dir.path(), backend).unwrap();
+        assert_eq!(reloaded.get("agent:main:dm:1").unwrap().input_tokens, 7);
+    }
+
+    #[tokio::test]
+    async fn interrupted_flush_keeps_last_good_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path()).unwrap();
+        let (entry, _) = store.resolve_or_create("agent:main:dm:42", SessionOrigin::default());
+        store.flush().await.unwrap();
+
+        // A crash mid-flush leaves a truncated temp file next to the
+        // last good table.
+        std::fs::write(
+            dir.path().join("sessions/sessions.json.tmp"),
+            b"{\"agent:main:dm:42\":{\"session_key\":",
+        )
+        .unwrap();
+
+        let reloaded = SessionStore::new(dir.path()).unwrap();
+        assert_eq!(
+            reloaded.get("agent:main:dm:42").unwrap().session_id,
+            entry.session_id
+        );
+
+        // The next flush goes through despite the stale temp file.
+        reloaded.record_usage("agent:main:dm:42", 1, 1);
+        reloaded.flush().await.unwrap();
+        let again = SessionStore::new(dir.path()).unwrap();
+        assert_eq!(again.get("agent:main:dm:42").unwrap().total_tokens, 2);
+    }
+
+    #[tokio::test]
+    async fn tags_persist_across_flush_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path()).unwrap();
+        store.resolve_or_create("agent:main:dm:42", SessionOrigin::default());
+
+        let entry = store
+            .set_tags(
+                "agent:main:dm:42",
+                vec![" work ".into(), "".into(), "urgent".into(), "work".into()],
+            )
+            .unwrap();
+        assert_eq!(entry.tags, ["work", "urgent"]);
+        assert!(store
+            .set_tags("agent:main:dm:missing", vec!["x".into()])
+            .is_none());
+
+        // Reset keeps the labels.
+        store.reset_session("agent:main:dm:42", "manual reset");
+        store.flush().await.unwrap();
+
+        let reloaded = SessionStore::new(dir.path()).unwrap();
+        assert_eq!(
+            reloaded.get("agent:main:dm:42").unwrap().tags,
+            ["work", "urgent"]
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_model_persists_and_can_be_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path()).unwrap();
+        store.resolve_or_create("agent:main:dm:42", SessionOrigin::default());
+        store.set_pinned_model("agent:main:dm:42", Some("anthropic/claude-sonnet".into()));
+        store.flush().await.unwrap();
+
+        let reloaded = SessionStore::new(dir.path()).unwrap();
+        let entry = reloaded.get("agent:main:dm:42").unwrap();
+        assert_eq!(
+            entry.pinned_model.as_deref(),
+            Some("anthropic/claude-sonnet")
+        );
+        let cleared = reloaded.set_pinned_model("agent:main:dm:42", None).unwrap();
+        assert!(cleared.pinned_model.is_none());
+    }
 }
@@ -0,0 +1,805 @@
+//! ClawHub pack dependency resolution.
+//!
+//! A pack's SKILL.md may declare `depends:` on other packs, each with an
+//! optional version constraint.  [`install_with_deps`] fetches the requested
+//! pack, walks its dependencies transitively (fetching only packs that are
+//! missing or whose installed version doesn't satisfy the constraint), and
+//! rejects cycles and conflicting constraints before anything is written.
+//! Packs are then installed dependencies-first, and each pack's direct
+//! dependencies are recorded in its origin.json so
+//! [`uninstall`](crate::installer::uninstall) can refuse to remove a pack
+//! others still need.  Packs needing high-danger permissions are refused
+//! unless the request accepts them (see [`crate::permissions`]).
+//!
+//! Fetching is left to the caller (the gateway downloads GitHub tarballs),
+//! so resolution is testable against local directories.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use crate::installer::{self, InstallResult};
+use crate::manifest::{parse_frontmatter, PackDependency, SkillManifest};
+use crate::permissions::{self, Permission};
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Pack ids and requests
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// `owner/repo` of a ClawHub pack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackId {
+    pub owner: String,
+    pub repo: String,
+}
+
+impl PackId {
+    pub fn new(owner: &str, repo: &str) -> Self {
+        Self {
+            owner: owner.into(),
+            repo: repo.into(),
+        }
+    }
+
+    /// Parse `owner/repo`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (owner, repo) = s.trim().split_once('/')?;
+        let valid = |part: &str| !part.is_empty() && !part.contains(['/', '\\']) && part != "..";
+        (valid(owner) && valid(repo)).then(|| Self::new(owner, repo))
+    }
+}
+
+impl fmt::Display for PackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.owner, self.repo)
+    }
+}
+
+/// What to fetch for one pack.
+#[derive(Debug, Clone)]
+pub struct PackRequest {
+    pub id: PackId,
+    /// User-facing version label (e.g. "v1.2.3", "latest").
+    pub version: String,
+    pub git_ref: Option<String>,
+    pub subdir: Option<String>,
+    /// High-danger permissions the operator accepted, per pack.  Read from
+    /// the root request only; every pack it brings in, dependencies
+    /// included, needs its own entry.
+    pub accept_permissions: HashMap<PackId, Vec<Permission>>,
+}
+
+impl PackRequest {
+    /// The request for a declared dependency: an exact constraint pins that
+    /// version, anything else fetches the latest.
+    fn for_dependency(id: PackId, dep: &PackDependency, req: &VersionReq) -> Self {
+        Self {
+            id,
+            version: req.exact().unwrap_or("latest").to_owned(),
+            git_ref: dep.git_ref.clone(),
+            subdir: dep.subdir.clone(),
+            accept_permissions: HashMap::new(),
+        }
+    }
+}
+
+/// A pack downloaded by the caller's fetcher.
+pub struct FetchedPack {
+    /// Directory holding the pack's files (SKILL.md at its root).
+    pub source_dir: PathBuf,
+    /// Git ref actually fetched.
+    pub git_ref: Option<String>,
+    pub files_hash: Option<String>,
+    /// Keeps temporary download directories alive until installed.
+    pub keep_alive: Option<Box<dyn std::any::Any + Send>>,
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Version constraints
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    /// `^1.2`: same leftmost non-zero component.
+    Caret,
+    /// `~1.2`: same major and minor (same major if only major given).
+    Tilde,
+}
+
+/// A comma-separated list of comparators, all of which must hold (e.g.
+/// `">=1.2, <2"`).  A bare version means `^version`; empty or `*` matches
+/// anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<(Op, Version)>,
+}
+
+/// Numeric version with the number of components actually written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub parts: [u64; 3],
+    given: usize,
+    /// As written (e.g. `v1.2.3`), used as the fetch label for exact pins.
+    text: String,
+}
+
+/// Leading numeric components of a dotted version (`"v1.2.3-beta"` →
+/// `1.2.3`), padded to three so `"1.2"` equals `"1.2.0"`.
+pub fn parse_version(v: &str) -> Option<Version> {
+    let text = v.trim();
+    let core = text.trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or(core);
+    let nums = core
+        .split('.')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if nums.is_empty() || nums.len() > 3 {
+        return None;
+    }
+    let mut parts = [0; 3];
+    parts[..nums.len()].copy_from_slice(&nums);
+    Some(Version {
+        parts,
+        given: nums.len(),
+        text: text.to_owned(),
+    })
+}
+
+impl VersionReq {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s.is_empty() || s == "*" {
+            return Ok(Self::default());
+        }
+        let comparators = s
+            .split(',')
+            .map(|c| {
+                let c = c.trim();
+                let (op, rest) = [
+                    (">=", Op::Ge),
+                    ("<=", Op::Le),
+                    (">", Op::Gt),
+                    ("<", Op::Lt),
+                    ("=", Op::Eq),
+                    ("^", Op::Caret),
+                    ("~", Op::Tilde),
+                ]
+                .into_iter()
+                .find_map(|(prefix, op)| c.strip_prefix(prefix).map(|rest| (op, rest)))
+                .unwrap_or((Op::Caret, c));
+                parse_version(rest)
+                    .map(|v| (op, v))
+                    .ok_or_else(|| format!("invalid version constraint '{c}'"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { comparators })
+    }
+
+    /// Whether any version is accepted.
+    pub fn is_any(&self) -> bool {
+        self.comparators.is_empty()
+    }
+
+    /// The pinned version of a single `=x.y.z` constraint.
+    fn exact(&self) -> Option<&str> {
+        match self.comparators.as_slice() {
+            [(Op::Eq, v)] => Some(&v.text),
+            _ => None,
+        }
+    }
+
+    /// Whether the version label `version` satisfies every comparator.
+    /// Labels that aren't numeric versions (e.g. "latest") only satisfy an
+    /// unconstrained requirement.
+    pub fn matches(&self, version: &str) -> bool {
+        if self.is_any() {
+            return true;
+        }
+        let Some(v) = parse_version(version) else {
+            return false;
+        };
+        let have = v.parts;
+        self.comparators.iter().all(|(op, want)| {
+            let w = want.parts;
+            match op {
+                Op::Eq => have == w,
+                Op::Gt => have > w,
+                Op::Ge => have >= w,
+                Op::Lt => have < w,
+                Op::Le => have <= w,
+                Op::Caret => {
+                    let upper = match w {
+                        [0, 0, p] if want.given == 3 => [0, 0, p + 1],
+                        [0, m, _] if want.given >= 2 => [0, m + 1, 0],
+                        [major, ..] => [major + 1, 0, 0],
+                    };
+                    have >= w && have < upper
+                }
+                Op::Tilde => {
+                    let upper = if want.given >= 2 {
+                        [w[0], w[1] + 1, 0]
+                    } else {
+                        [w[0] + 1, 0, 0]
+                    };
+                    have >= w && have < upper
+                }
+            }
+        })
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// Resolution
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+/// Why a pack and its dependencies couldn't be installed.
+#[derive(Debug)]
+pub enum DependencyError {
+    /// A dependency chain leads back to a pack already being resolved.
+    Cycle(Vec<PackId>),
+    /// A pack's version doesn't satisfy a dependent's constraint.
+    Unsatisfied {
+        pack: PackId,
+        required_by: PackId,
+        constraint: String,
+        version: String,
+    },
+    /// A manifest declares a malformed dependency.
+    InvalidManifest {
+        pack: PackId,
+        message: String,
+    },
+    /// Packs need high-danger permissions the operator hasn't accepted.
+    PermissionsNotAccepted(Vec<(PackId, Vec<Permission>)>),
+    Fetch {
+        pack: PackId,
+        message: String,
+    },
+    Install {
+        pack: PackId,
+        error: std::io::Error,
+    },
+}
+
+impl DependencyError {
+    /// Whether the dependency graph itself is unsatisfiable, as opposed to
+    /// a download or disk failure.
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            Self::Cycle(_) | Self::Unsatisfied { .. } | Self::InvalidManifest { .. }
+        )
+    }
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(path) => {
+                let path: Vec<String> = path.iter().map(PackId::to_string).collect();
+                write!(f, "dependency cycle: {}", path.join(" -> "))
+            }
+            Self::Unsatisfied {
+                pack,
+                required_by,
+                constraint,
+                version,
+            } => write!(
+                f,
+                "{required_by} requires {pack} {constraint}, but the available version is {version}"
+            ),
+            Self::InvalidManifest { pack, message } => write!(f, "{pack}: {message}"),
+            Self::PermissionsNotAccepted(packs) => {
+                let packs: Vec<String> = packs
+                    .iter()
+                    .map(|(pack, perms)| {
+                        let perms: Vec<&str> = perms.iter().map(|p| p.as_str()).collect();
+                        format!("{pack} ({})", perms.join(", "))
+                    })
+                    .collect();
+                write!(
+                    f,
+                    "permissions must be accepted before installing: {}",
+                    packs.join("; ")
+                )
+            }
+            Self::Fetch { pack, message } => write!(f, "fetching {pack} failed: {message}"),
+            Self::Install { pack, error } => write!(f, "installing {pack} failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+/// Outcome of [`install_with_deps`].
+#[derive(Debug, serde::Serialize)]
+pub struct DepsInstall {
+    /// The requested pack.
+    pub root: InstallResult,
+    /// Dependencies installed or replaced along the way, dependencies-first.
+    pub dependencies: Vec<InstallResult>,
+    /// Dependencies (`owner/repo`) already installed at a satisfying version.
+    pub already_satisfied: Vec<String>,
+}
+
+/// A fetched pack awaiting installation.
+struct Node {
+    request: PackRequest,
+    fetched: FetchedPack,
+    /// The manifest's `version`, else the requested label.
+    version: String,
+    depends: Vec<PackDependency>,
+    permissions: Vec<Permission>,
+}
+
+/// DFS position: the next dependency of `id` to visit.
+struct Frame {
+    id: PackId,
+    next: usize,
+}
+
+fn read_manifest(dir: &Path) -> Option<SkillManifest> {
+    let content = std::fs::read_to_string(dir.join("SKILL.md")).ok()?;
+    parse_frontmatter(&content).0
+}
+
+/// Version of an installed pack: its manifest's `version`, else the label
+/// it was installed under.
+pub(crate) fn installed_version(skills_root: &Path, id: &PackId) -> Option<String> {
+    let origin = installer::read_origin(skills_root, &id.owner, &id.repo)?;
+    let dir = skills_root
+        .join("third_party")
+        .join(&id.owner)
+        .join(&id.repo);
+    Some(
+        read_manifest(&dir)
+            .and_then(|m| m.version)
+            .unwrap_or(origin.version),
+    )
+}
+
+async fn fetch_node<F, Fut>(fetch: &mut F, request: PackRequest) -> Result<Node, DependencyError>
+where
+    F: FnMut(PackRequest) -> Fut,
+    Fut: Future<Output = Result<FetchedPack, String>>,
+{
+    let fetched = fetch(request.clone())
+        .await
+        .map_err(|message| DependencyError::Fetch {
+            pack: request.id.clone(),
+            message,
+        })?;
+    let manifest = read_manifest(&fetched.source_dir).unwrap_or_default();
+    Ok(Node {
+        permissions: manifest.required_permissions(),
+        version: manifest.version.unwrap_or_else(|| request.version.clone()),
+        depends: manifest.depends,
+        request,
+        fetched,
+    })
+}
+
+/// Install `root` and, transitively, the packs it depends on.
+///
+/// The whole graph is fetched and checked before anything is installed, so
+/// a cycle or an unsatisfiable constraint leaves the skills directory
+/// untouched.  `root` is always (re)installed; dependencies are fetched
+/// only when missing or installed at a version that doesn't satisfy the
+/// constraint.  Every fetched pack's high-danger permissions must be in
+/// its own `root.accept_permissions` entry, and each pack's origin.json
+/// records the permissions it was granted.
+pub async fn install_with_deps<F, Fut>(
+    skills_root: &Path,
+    root: PackRequest,
+    mut fetch: F,
+) -> Result<DepsInstall, DependencyError>
+where
+    F: FnMut(PackRequest) -> Fut,
+    Fut: Future<Output = Result<FetchedPack, String>>,
+{
+    let mut nodes: HashMap<PackId, Node> = HashMap::new();
+    let mut satisfied: HashMap<PackId, String> = HashMap::new();
+    // Post-order: every pack comes after its dependencies.
+    let mut order: Vec<PackId> = Vec::new();
+
+    let root_id = root.id.clone();
+    let accepted = root.accept_permissions.clone();
+    nodes.insert(root_id.clone(), fetch_node(&mut fetch, root).await?);
+    let mut stack = vec![Frame {
+        id: root_id,
+        next: 0,
+    }];
+
+    while let Some(frame) = stack.last_mut() {
+        let parent = frame.id.clone();
+        let Some(dep) = nodes[&parent].depends.get(frame.next).cloned() else {
+            stack.pop();
+            order.push(parent);
+            continue;
+        };
+        frame.next += 1;
+
+        let invalid = |message: String| DependencyError::InvalidManifest {
+            pack: parent.clone(),
+            message,
+        };
+        let id = PackId::parse(&dep.pack).ok_or_else(|| {
+            invalid(format!(
+                "invalid dependency '{}': expected owner/repo",
+                dep.pack
+            ))
+        })?;
+        let constraint = dep.version.clone().unwrap_or_default();
+        let req = VersionReq::parse(&constraint)
+            .map_err(|e| invalid(format!("dependency '{id}': {e}")))?;
+
+        if let Some(pos) = stack.iter().position(|f| f.id == id) {
+            let mut cycle: Vec<PackId> = stack[pos..].iter().map(|f| f.id.clone()).collect();
+            cycle.push(id);
+            return Err(DependencyError::Cycle(cycle));
+        }
+        let unsatisfied = |version: &str| DependencyError::Unsatisfied {
+            pack: id.clone(),
+            required_by: parent.clone(),
+            constraint: constraint.clone(),
+            version: version.to_owned(),
+        };
+
+        // Already resolved by another dependent in this install.
+        if let Some(version) = nodes.get(&id).map(|n| &n.version).or(satisfied.get(&id)) {
+            if !req.matches(version) {
+                return Err(unsatisfied(version));
+            }
+            continue;
+        }
+        // Already installed at a satisfying version.
+        if let Some(version) = installed_version(skills_root, &id) {
+            if req.matches(&version) {
+                satisfied.insert(id.clone(), version);
+                continue;
+            }
+        }
+
+        let request = PackRequest::for_dependency(id.clone(), &dep, &req);
+        let node = fetch_node(&mut fetch, request).await?;
+        if !req.matches(&node.version) {
+            return Err(unsatisfied(&node.version));
+        }
+        nodes.insert(id.clone(), node);
+        stack.push(Frame { id, next: 0 });
+    }
+
+    let mut unaccepted = Vec::new();
+    for id in &order {
+        let accepted = accepted.get(id).map(Vec::as_slice).unwrap_or_default();
+        let missing = permissions::unaccepted(&nodes[id].permissions, accepted);
+        if !missing.is_empty() {
+            unaccepted.push((id.clone(), missing));
+        }
+    }
+    if !unaccepted.is_empty() {
+        return Err(DependencyError::PermissionsNotAccepted(unaccepted));
+    }
+
+    let mut installed = Vec::with_capacity(order.len());
+    for id in &order {
+        let node = nodes.remove(id).expect("every ordered pack was fetched");
+        let install_err = |error| DependencyError::Install {
+            pack: id.clone(),
+            error,
+        };
+        let deps: Vec<String> = node
+            .depends
+            .iter()
+            .filter_map(|d| PackId::parse(&d.pack))
+            .map(|d| d.to_string())
+            .collect();
+        let mut result = installer::install_from_dir(
+            skills_root,
+            &id.owner,
+            &id.repo,
+            &node.fetched.source_dir,
+            &node.request.version,
+            node.fetched.git_ref.clone(),
+            node.fetched.files_hash.clone(),
+        )
+        .map_err(install_err)?;
+        result.origin = installer::update_origin(skills_root, &id.owner, &id.repo, |o| {
+            o.dependencies = deps;
+            o.subdir = node.request.subdir.clone();
+            o.permissions = node.permissions.clone();
+        })
+        .map_err(install_err)?;
+        installed.push(result);
+    }
+
+    let root = installed.pop().expect("the root pack is installed last");
+    let mut already_satisfied: Vec<String> = satisfied.keys().map(PackId::to_string).collect();
+    already_satisfied.sort();
+    Ok(DepsInstall {
+        root,
+        dependencies: installed,
+        already_satisfied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::fs;
+
+    /// Write a pack with `version` depending on `(owner/repo, constraint)`s.
+    fn write_pack(dir: &Path, version: &str, depends: &[(&str, &str)]) {
+        fs::create_dir_all(dir).unwrap();
+        let name = dir.file_name().unwrap().to_string_lossy();
+        let mut md = format!("---\nname: {name}\ndescription: test\nversion: {version}\n");
+        if !depends.is_empty() {
+            md.push_str("depends:\n");
+            for (pack, req) in depends {
+                md.push_str(&format!("  - pack: {pack}\n    version: \"{req}\"\n"));
+            }
+        }
+        md.push_str("---\n# Pack\n");
+        fs::write(dir.join("SKILL.md"), md).unwrap();
+    }
+
+    /// Local "registry": one source dir per repo under `o/`.
+    struct Registry {
+        sources: tempfile::TempDir,
+        fetched: RefCell<Vec<String>>,
+    }
+
+    impl Registry {
+        fn new() -> Self {
+            Self {
+                sources: tempfile::tempdir().unwrap(),
+                fetched: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn publish(&self, repo: &str, version: &str, depends: &[(&str, &str)]) {
+            write_pack(&self.sources.path().join(repo), version, depends);
+        }
+
+        fn fetch(&self, req: PackRequest) -> impl Future<Output = Result<FetchedPack, String>> {
+            self.fetched.borrow_mut().push(req.id.to_string());
+            let dir = self.sources.path().join(&req.id.repo);
+            async move {
+                if !dir.exists() {
+                    return Err(format!("{} not found", req.id));
+                }
+                Ok(FetchedPack {
+                    source_dir: dir,
+                    git_ref: None,
+                    files_hash: None,
+                    keep_alive: None,
+                })
+            }
+        }
+    }
+
+    fn request(repo: &str) -> PackRequest {
+        PackRequest {
+            id: PackId::new("o", repo),
+            version: "latest".into(),
+            git_ref: None,
+            subdir: None,
+            accept_permissions: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn transitive_install_installs_dependencies_first() {
+        let reg = Registry::new();
+        reg.publish("app", "1.0.0", &[("o/lib", "^1")]);
+        reg.publish("lib", "1.3.0", &[("o/core", ">=2.0, <3")]);
+        reg.publish("core", "2.1.0", &[]);
+        let root = tempfile::tempdir().unwrap();
+
+        let out = install_with_deps(root.path(), request("app"), |r| reg.fetch(r))
+            .await
+            .unwrap();
+
+        assert_eq!(out.root.origin.repo, "app");
+        let deps: Vec<&str> = out
+            .dependencies
+            .iter()
+            .map(|r| r.origin.repo.as_str())
+            .collect();
+        assert_eq!(deps, ["core", "lib"]);
+
+        let origin = |repo| installer::read_origin(root.path(), "o", repo).unwrap();
+        assert_eq!(origin("app").dependencies, ["o/lib"]);
+        assert_eq!(origin("lib").dependencies, ["o/core"]);
+        assert!(origin("core").dependencies.is_empty());
+        assert_eq!(installer::list_installed(root.path()).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn satisfied_dependencies_are_not_refetched_and_conflicts_are_rejected() {
+        let reg = Registry::new();
+        reg.publish("core", "1.4.0", &[]);
+        reg.publish("app", "1.0.0", &[("o/core", ">=1.2")]);
+        reg.publish("strict", "1.0.0", &[("o/core", ">=2")]);
+        let root = tempfile::tempdir().unwrap();
+
+        install_with_deps(root.path(), request("core"), |r| reg.fetch(r))
+            .await
+            .unwrap();
+        reg.fetched.borrow_mut().clear();
+
+        let out = install_with_deps(root.path(), request("app"), |r| reg.fetch(r))
+            .await
+            .unwrap();
+        assert_eq!(*reg.fetched.borrow(), ["o/app"]);
+        assert_eq!(out.already_satisfied, ["o/core"]);
+        assert!(out.dependencies.is_empty());
+
+        // core 1.4.0 is installed and the latest available is still 1.4.0.
+        let err = install_with_deps(root.path(), request("strict"), |r| reg.fetch(r))
+            .await
+            .unwrap_err();
+        assert!(err.is_conflict());
+        assert!(
+            matches!(err, DependencyError::Unsatisfied { ref version, .. } if version == "1.4.0")
+        );
+        assert!(installer::read_origin(root.path(), "o", "strict").is_none());
+    }
+
+    #[tokio::test]
+    async fn cycles_are_rejected_before_installing() {
+        let reg = Registry::new();
+        reg.publish("a", "1.0.0", &[("o/b", "*")]);
+        reg.publish("b", "1.0.0", &[("o/c", "*")]);
+        reg.publish("c", "1.0.0", &[("o/a", "*")]);
+        let root = tempfile::tempdir().unwrap();
+
+        let err = install_with_deps(root.path(), request("a"), |r| reg.fetch(r))
+            .await
+            .unwrap_err();
+        assert!(err.is_conflict());
+        assert_eq!(
+            err.to_string(),
+            "dependency cycle: o/a -> o/b -> o/c -> o/a"
+        );
+        assert!(installer::list_installed(root.path()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn high_danger_permissions_must_be_accepted() {
+        let reg = Registry::new();
+        reg.publish("app", "1.0.0", &[("o/runner", "*")]);
+        // `exec` implies the execution permission it doesn't declare.
+        let runner = reg.sources.path().join("runner");
+        fs::create_dir_all(&runner).unwrap();
+        fs::write(
+            runner.join("SKILL.md"),
+            "---\nname: runner\ndescription: test\npermissions: [network]\ntools: [exec]\n---\n",
+        )
+        .unwrap();
+        let root = tempfile::tempdir().unwrap();
+
+        let err = install_with_deps(root.path(), request("app"), |r| reg.fetch(r))
+            .await
+            .unwrap_err();
+        assert!(!err.is_conflict());
+        assert!(matches!(
+            err,
+            DependencyError::PermissionsNotAccepted(ref packs)
+                if packs == &[(PackId::new("o", "runner"), vec![Permission::Execution])]
+        ));
+        assert!(installer::list_installed(root.path()).is_empty());
+
+        // Accepting for the root doesn't cover what its dependency needs.
+        let root_only = PackRequest {
+            accept_permissions: HashMap::from([(
+                PackId::new("o", "app"),
+                vec![Permission::Execution],
+            )]),
+            ..request("app")
+        };
+        let err = install_with_deps(root.path(), root_only, |r| reg.fetch(r))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DependencyError::PermissionsNotAccepted(ref packs)
+                if packs[0].0 == PackId::new("o", "runner")
+        ));
+        assert!(installer::list_installed(root.path()).is_empty());
+
+        let accepted = PackRequest {
+            accept_permissions: HashMap::from([(
+                PackId::new("o", "runner"),
+                vec![Permission::Execution],
+            )]),
+            ..request("app")
+        };
+        install_with_deps(root.path(), accepted, |r| reg.fetch(r))
+            .await
+            .unwrap();
+        let runner = installer::read_origin(root.path(), "o", "runner").unwrap();
+        assert_eq!(
+            runner.permissions,
+            [Permission::Network, Permission::Execution]
+        );
+        let app = installer::read_origin(root.path(), "o", "app").unwrap();
+        assert!(app.permissions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn uninstall_is_blocked_while_a_dependent_exists() {
+        let reg = Registry::new();
+        reg.publish("app", "1.0.0", &[("o/lib", "*")]);
+        reg.publish("lib", "1.0.0", &[]);
+        let root = tempfile::tempdir().unwrap();
+        install_with_deps(root.path(), request("app"), |r| reg.fetch(r))
+            .await
+            .unwrap();
+
+        let blocked = installer::uninstall(root.path(), "o", "lib", false).unwrap();
+        assert!(!blocked.removed);
+        assert_eq!(blocked.dependents, ["o/app"]);
+        assert!(blocked.skill_dir.exists());
+
+        // Removing the dependent unblocks it.
+        assert!(
+            installer::uninstall(root.path(), "o", "app", false)
+                .unwrap()
+                .removed
+        );
+        let removed = installer::uninstall(root.path(), "o", "lib", false).unwrap();
+        assert!(removed.removed);
+        assert!(removed.dependents.is_empty());
+    }
+
+    #[tokio::test]
+    async fn forced_uninstall_removes_despite_dependents() {
+        let reg = Registry::new();
+        reg.publish("app", "1.0.0", &[("o/lib", "*")]);
+        reg.publish("lib", "1.0.0", &[]);
+        let root = tempfile::tempdir().unwrap();
+        install_with_deps(root.path(), request("app"), |r| reg.fetch(r))
+            .await
+            .unwrap();
+
+        let forced = installer::uninstall(root.path(), "o", "lib", true).unwrap();
+        assert!(forced.removed);
+        assert_eq!(forced.dependents, ["o/app"]);
+    }
+
+    #[test]
+    fn version_requirements() {
+        let req = |s| VersionReq::parse(s).unwrap();
+        assert!(req("").matches("latest"));
+        assert!(req("*").matches("0.1.0"));
+        assert!(req(">=1.2, <2").matches("v1.9.3"));
+        assert!(!req(">=1.2, <2").matches("2.0.0"));
+        assert!(!req(">=1.2").matches("latest"));
+        assert!(req("^1.2").matches("1.5.0"));
+        assert!(!req("^1.2").matches("2.0.0"));
+        assert!(req("^0.2").matches("0.2.9"));
+        assert!(!req("^0.2").matches("0.3.0"));
+        assert!(req("~1.2").matches("1.2.7"));
+        assert!(!req("~1.2").matches("1.3.0"));
+        assert!(req("1.4").matches("1.9.0"), "bare version is caret");
+        assert!(req("=1.4.0").matches("1.4"));
+        assert_eq!(req("=v1.4.0").exact(), Some("v1.4.0"));
+        assert!(VersionReq::parse(">=banana").is_err());
+    }
+
+    #[test]
+    fn pack_ids() {
+        assert_eq!(PackId::parse("o/r"), Some(PackId::new("o", "r")));
+        assert_eq!(PackId::parse("o"), None);
+        assert_eq!(PackId::parse("o/r/x"), None);
+        assert_eq!(PackId::parse("../r"), None);
+    }
+}
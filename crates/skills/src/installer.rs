@@ -7,6 +7,8 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::permissions::Permission;
+
 /// Max total extracted size (50 MB) — prevents zip-bomb-style tarball attacks.
 const MAX_TOTAL_EXTRACT_BYTES: u64 = 50 * 1024 * 1024;
 /// Max single file size (10 MB).
@@ -32,6 +34,16 @@ pub struct OriginMeta {
     /// Whether scripts/ dir contents changed since last install.
     #[serde(default)]
     pub scripts_changed: Option<bool>,
+    /// Packs (`owner/repo`) this pack was installed with as dependencies.
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Subdirectory within the repo the pack was installed from.
+    #[serde(default)]
+    pub subdir: Option<String>,
+    /// Permissions granted to the pack at install (see
+    /// [`permissions`](crate::permissions)).
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
 }
 
 /// Result of an install operation.
@@ -51,6 +63,9 @@ pub struct InstallResult {
 pub struct UninstallResult {
     pub skill_dir: PathBuf,
     pub removed: bool,
+    /// Installed packs (`owner/repo`) that depend on this one.  When
+    /// non-empty and the uninstall wasn't forced, nothing was removed.
+    pub dependents: Vec<String>,
 }
 
 /// Install a skill pack from a local directory (already downloaded).
@@ -102,6 +117,9 @@ pub fn install_from_dir(
         git_ref,
         files_hash,
         scripts_changed: Some(scripts_changed),
+        dependencies: Vec::new(),
+        subdir: None,
+        permissions: Vec::new(),
     };
     let meta_dir = target.join(".serialagent");
     std::fs::create_dir_all(&meta_dir)?;
@@ -218,12 +236,31 @@ pub fn safe_untar(tarball_bytes: &[u8], dest_dir: &Path) -> Result<(), String> {
 }
 
 /// Uninstall a skill pack by removing its directory.
+///
+/// Refuses (returns `removed: false` with `dependents` set) while other
+/// installed packs depend on it, unless `force` is set.
 pub fn uninstall(
     skills_root: &Path,
     owner: &str,
     repo: &str,
+    force: bool,
 ) -> std::io::Result<UninstallResult> {
     let target = skills_root.join("third_party").join(owner).join(repo);
+    let dependents = dependents(skills_root, owner, repo);
+    if !dependents.is_empty() {
+        if !force {
+            return Ok(UninstallResult {
+                skill_dir: target,
+                removed: false,
+                dependents,
+            });
+        }
+        tracing::warn!(
+            pack = %format!("{owner}/{repo}"),
+            dependents = ?dependents,
+            "force-uninstalling a pack other packs depend on"
+        );
+    }
     let removed = if target.exists() {
         std::fs::remove_dir_all(&target)?;
         true
@@ -238,9 +275,47 @@ pub fn uninstall(
     Ok(UninstallResult {
         skill_dir: target,
         removed,
+        dependents,
     })
 }
 
+/// Installed packs (`owner/repo`) that list `owner/repo` as a dependency.
+pub fn dependents(skills_root: &Path, owner: &str, repo: &str) -> Vec<String> {
+    let id = format!("{owner}/{repo}");
+    let mut found: Vec<String> = list_installed(skills_root)
+        .into_iter()
+        .filter(|o| o.dependencies.contains(&id))
+        .map(|o| format!("{}/{}", o.owner, o.repo))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Apply `edit` to an installed pack's origin.json and return the result.
+pub fn update_origin(
+    skills_root: &Path,
+    owner: &str,
+    repo: &str,
+    edit: impl FnOnce(&mut OriginMeta),
+) -> std::io::Result<OriginMeta> {
+    let mut origin = read_origin(skills_root, owner, repo).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{owner}/{repo} is not installed"),
+        )
+    })?;
+    edit(&mut origin);
+    let path = skills_root
+        .join("third_party")
+        .join(owner)
+        .join(repo)
+        .join(".serialagent")
+        .join("origin.json");
+    let json = serde_json::to_string_pretty(&origin).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)?;
+    Ok(origin)
+}
+
 /// Read origin.json for an installed pack (returns None if not installed).
 pub fn read_origin(skills_root: &Path, owner: &str, repo: &str) -> Option<OriginMeta> {
     let path = skills_root
@@ -410,7 +485,7 @@ mod tests {
         assert_eq!(installed.len(), 1);
         assert_eq!(installed[0].repo, "testrepo");
 
-        let uninstall_result = uninstall(&skills_root, "testowner", "testrepo").unwrap();
+        let uninstall_result = uninstall(&skills_root, "testowner", "testrepo", false).unwrap();
         assert!(uninstall_result.removed);
         assert!(!result.skill_dir.exists());
         assert!(list_installed(&skills_root).is_empty());
@@ -438,7 +513,7 @@ mod tests {
     #[test]
     fn uninstall_nonexistent() {
         let tmp = tempfile::tempdir().unwrap();
-        let result = uninstall(tmp.path(), "no", "exist").unwrap();
+        let result = uninstall(tmp.path(), "no", "exist", false).unwrap();
         assert!(!result.removed);
     }
 
@@ -1,6 +1,9 @@
 pub mod aliases;
+pub mod deps;
 pub mod installer;
 pub mod loader;
 pub mod manifest;
+pub mod outdated;
+pub mod permissions;
 pub mod registry;
 pub mod types;
@@ -30,11 +30,18 @@
 //!   - kind: go
 //!     command: "go install github.com/steipete/sonoscli/cmd/sonos@latest"
 //!     provides: sonos
+//! version: 1.4.0
+//! depends:
+//!   - pack: steipete/sonos-core
+//!     version: ">=1.2, <2"
+//! permissions: [network, execution]
 //! ---
 //! ```
 
 use serde::{Deserialize, Serialize};
 
+use crate::permissions::Permission;
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Name validation
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -97,6 +104,31 @@ pub struct SkillManifest {
     /// Install instructions for missing dependencies.
     #[serde(default)]
     pub install: Vec<InstallEntry>,
+    /// Pack version (e.g. `1.4.0`), checked against dependents' constraints.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Other ClawHub packs this pack needs, installed along with it.
+    #[serde(default)]
+    pub depends: Vec<PackDependency>,
+    /// Capabilities the pack's skills need (network, filesystem, execution).
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+}
+
+/// A dependency on another ClawHub pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackDependency {
+    /// `owner/repo` of the pack.
+    pub pack: String,
+    /// Version constraint (e.g. `">=1.2, <2"`, `"^1.4"`).  Absent = any.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Git ref to fetch when the pack must be installed.
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    /// Subdirectory within the repo holding the pack.
+    #[serde(default)]
+    pub subdir: Option<String>,
 }
 
 /// What the skill needs to function.
@@ -210,6 +242,20 @@ impl SkillManifest {
             }
         }
 
+        for dep in &self.depends {
+            if crate::deps::PackId::parse(&dep.pack).is_none() {
+                errors.push(format!(
+                    "invalid dependency '{}': expected owner/repo",
+                    dep.pack
+                ));
+            }
+            if let Some(ref req) = dep.version {
+                if let Err(e) = crate::deps::VersionReq::parse(req) {
+                    errors.push(format!("dependency '{}': {e}", dep.pack));
+                }
+            }
+        }
+
         ManifestValidation { errors, warnings }
     }
 
@@ -0,0 +1,181 @@
+//! ClawHub update check — which installed packs have a newer version
+//! upstream.
+//!
+//! The installed version is the pack's SKILL.md `version` (falling back to
+//! the label it was installed under); the remote version comes from a
+//! caller-supplied lookup (the gateway reads the repo's SKILL.md from
+//! GitHub).  A pack is outdated when both versions parse and the remote
+//! one is newer; packs without comparable versions are reported as
+//! unchecked rather than guessed at.
+
+use std::future::Future;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::deps::{installed_version, parse_version, PackId};
+use crate::installer::{self, OriginMeta};
+
+/// An installed pack with a newer version available.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutdatedPack {
+    pub owner: String,
+    pub repo: String,
+    pub installed: String,
+    pub latest: String,
+}
+
+/// A pack whose remote version couldn't be compared.
+#[derive(Debug, Clone, Serialize)]
+pub struct UncheckedPack {
+    pub owner: String,
+    pub repo: String,
+    pub reason: String,
+}
+
+/// Result of [`check_outdated`].
+#[derive(Debug, Default, Serialize)]
+pub struct OutdatedReport {
+    pub outdated: Vec<OutdatedPack>,
+    /// Packs that couldn't be checked (lookup failed, no comparable version).
+    pub unchecked: Vec<UncheckedPack>,
+    /// Number of installed packs looked at.
+    pub checked: usize,
+}
+
+/// Whether `latest` is a strictly newer version than `installed`.  `None`
+/// when either isn't a numeric version.
+pub fn is_newer(latest: &str, installed: &str) -> Option<bool> {
+    Some(parse_version(latest)?.parts > parse_version(installed)?.parts)
+}
+
+/// Compare every installed pack against `latest_version`, which returns the
+/// remote version of a pack (`Ok(None)` when the remote declares none).
+pub async fn check_outdated<F, Fut>(skills_root: &Path, mut latest_version: F) -> OutdatedReport
+where
+    F: FnMut(OriginMeta) -> Fut,
+    Fut: Future<Output = Result<Option<String>, String>>,
+{
+    let mut installed = installer::list_installed(skills_root);
+    installed.sort_by(|a, b| (&a.owner, &a.repo).cmp(&(&b.owner, &b.repo)));
+
+    let mut report = OutdatedReport {
+        checked: installed.len(),
+        ..Default::default()
+    };
+    for origin in installed {
+        let (owner, repo) = (origin.owner.clone(), origin.repo.clone());
+        let unchecked = |reason: String| UncheckedPack {
+            owner: owner.clone(),
+            repo: repo.clone(),
+            reason,
+        };
+        let current = installed_version(skills_root, &PackId::new(&owner, &repo))
+            .unwrap_or_else(|| origin.version.clone());
+
+        let latest = match latest_version(origin).await {
+            Ok(Some(v)) => v,
+            Ok(None) => {
+                report
+                    .unchecked
+                    .push(unchecked("remote declares no version".into()));
+                continue;
+            }
+            Err(e) => {
+                report.unchecked.push(unchecked(e));
+                continue;
+            }
+        };
+        match is_newer(&latest, &current) {
+            Some(true) => report.outdated.push(OutdatedPack {
+                owner,
+                repo,
+                installed: current,
+                latest,
+            }),
+            Some(false) => {}
+            None => report.unchecked.push(unchecked(format!(
+                "cannot compare installed '{current}' with remote '{latest}'"
+            ))),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    fn install(skills_root: &Path, repo: &str, version: &str) {
+        let src = tempfile::tempdir().unwrap();
+        fs::write(
+            src.path().join("SKILL.md"),
+            format!("---\nname: {repo}\ndescription: test\nversion: {version}\n---\n"),
+        )
+        .unwrap();
+        installer::install_from_dir(skills_root, "o", repo, src.path(), "latest", None, None)
+            .unwrap();
+    }
+
+    /// Mock remote: latest version per repo; unknown repos fail the lookup.
+    fn remote(
+        versions: &HashMap<&str, Option<&str>>,
+        origin: OriginMeta,
+    ) -> impl Future<Output = Result<Option<String>, String>> {
+        let found: Option<Option<String>> = versions
+            .get(origin.repo.as_str())
+            .map(|v| v.map(str::to_owned));
+        async move {
+            match found {
+                Some(v) => Ok(v),
+                None => Err(format!("{}/{} not found", origin.owner, origin.repo)),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn out_of_date_pack_is_flagged_and_up_to_date_is_not() {
+        let root = tempfile::tempdir().unwrap();
+        install(root.path(), "stale", "1.2.0");
+        install(root.path(), "fresh", "2.0.0");
+        install(root.path(), "ahead", "3.1.0");
+        let versions = HashMap::from([
+            ("stale", Some("v1.3.0")),
+            ("fresh", Some("2.0.0")),
+            ("ahead", Some("3.0.0")),
+        ]);
+
+        let report = check_outdated(root.path(), |o| remote(&versions, o)).await;
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.outdated.len(), 1);
+        let stale = &report.outdated[0];
+        assert_eq!(stale.repo, "stale");
+        assert_eq!(stale.installed, "1.2.0");
+        assert_eq!(stale.latest, "v1.3.0");
+        assert!(report.unchecked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_or_versionless_lookups_are_unchecked() {
+        let root = tempfile::tempdir().unwrap();
+        install(root.path(), "gone", "1.0.0");
+        install(root.path(), "bare", "1.0.0");
+        let versions = HashMap::from([("bare", None)]);
+
+        let report = check_outdated(root.path(), |o| remote(&versions, o)).await;
+
+        assert!(report.outdated.is_empty());
+        let repos: Vec<&str> = report.unchecked.iter().map(|u| u.repo.as_str()).collect();
+        assert_eq!(repos, ["bare", "gone"]);
+    }
+
+    #[test]
+    fn newer_versions() {
+        assert_eq!(is_newer("1.10.0", "1.9.9"), Some(true));
+        assert_eq!(is_newer("1.0", "1.0.0"), Some(false));
+        assert_eq!(is_newer("latest", "1.0.0"), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! ClawHub pack permissions.
+//!
+//! A pack declares the capabilities its skills need in SKILL.md:
+//!
+//! ```yaml
+//! permissions: [network, execution]
+//! ```
+//!
+//! Tools listed under `tools:` imply their permission too (`exec` needs
+//! `execution`, `web.fetch` needs `network`, ...), so a pack can't reach a
+//! capability just by leaving it out of `permissions`.  The required set is
+//! shown to the operator at install time, and high-danger permissions
+//! (`filesystem`, `execution`) must be accepted explicitly before
+//! [`install_with_deps`](crate::deps::install_with_deps) writes anything.
+//! Each pack's own permissions are accepted separately, so accepting a pack
+//! never approves what its dependencies need.  What was granted is recorded
+//! in the pack's origin.json; callable skills under a pack's
+//! `tool_prefixes` are refused at runtime when their danger level needs a
+//! permission outside that grant.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::manifest::SkillManifest;
+
+/// A capability a pack's skills may use.  Ordered from least to most
+/// dangerous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Outbound network requests.
+    Network,
+    /// Reading and writing files.
+    Filesystem,
+    /// Running commands.
+    Execution,
+}
+
+impl Permission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Filesystem => "filesystem",
+            Self::Execution => "execution",
+        }
+    }
+
+    /// Whether installing a pack that needs this permission requires the
+    /// operator's explicit acceptance.
+    pub fn is_high_danger(self) -> bool {
+        matches!(self, Self::Filesystem | Self::Execution)
+    }
+
+    /// The permission a tool needs, if any.
+    pub fn for_tool(tool: &str) -> Option<Self> {
+        let family = tool.split(['.', '_']).next().unwrap_or(tool);
+        match family {
+            "exec" | "process" | "shell" => Some(Self::Execution),
+            "file" | "fs" => Some(Self::Filesystem),
+            "web" | "http" | "browser" => Some(Self::Network),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SkillManifest {
+    /// Permissions the pack needs: those declared under `permissions:` plus
+    /// those implied by its `tools:`, sorted and deduplicated.
+    pub fn required_permissions(&self) -> Vec<Permission> {
+        let mut required: Vec<Permission> = self
+            .permissions
+            .iter()
+            .copied()
+            .chain(self.tools.iter().filter_map(|t| Permission::for_tool(t)))
+            .collect();
+        required.sort();
+        required.dedup();
+        required
+    }
+}
+
+/// The high-danger permissions in `required` that `accepted` doesn't cover.
+pub fn unaccepted(required: &[Permission], accepted: &[Permission]) -> Vec<Permission> {
+    required
+        .iter()
+        .copied()
+        .filter(|p| p.is_high_danger() && !accepted.contains(p))
+        .collect()
+}
+
+/// Whether a pack granted `granted` may use `needed` (`None` = a skill that
+/// needs no permission).
+pub fn allows(granted: &[Permission], needed: Option<Permission>) -> bool {
+    match needed {
+        Some(p) => granted.contains(&p),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::parse_frontmatter;
+
+    #[test]
+    fn tools_imply_permissions() {
+        assert_eq!(Permission::for_tool("exec"), Some(Permission::Execution));
+        assert_eq!(
+            Permission::for_tool("process.list"),
+            Some(Permission::Execution)
+        );
+        assert_eq!(
+            Permission::for_tool("fs.read_text"),
+            Some(Permission::Filesystem)
+        );
+        assert_eq!(
+            Permission::for_tool("file.write"),
+            Some(Permission::Filesystem)
+        );
+        assert_eq!(
+            Permission::for_tool("web.search"),
+            Some(Permission::Network)
+        );
+        assert_eq!(
+            Permission::for_tool("http.request"),
+            Some(Permission::Network)
+        );
+        assert_eq!(Permission::for_tool("memory.search"), None);
+    }
+
+    #[test]
+    fn required_permissions_merge_declared_and_tools() {
+        let md = "---\nname: p\ndescription: d\npermissions: [network]\n\
+                  tools: [exec, web.fetch, memory.search]\n---\n";
+        let m = parse_frontmatter(md).0.unwrap();
+        assert_eq!(m.permissions, [Permission::Network]);
+        assert_eq!(
+            m.required_permissions(),
+            [Permission::Network, Permission::Execution]
+        );
+    }
+
+    #[test]
+    fn only_high_danger_permissions_need_acceptance() {
+        let required = [
+            Permission::Network,
+            Permission::Filesystem,
+            Permission::Execution,
+        ];
+        assert_eq!(
+            unaccepted(&required, &[]),
+            [Permission::Filesystem, Permission::Execution]
+        );
+        assert_eq!(
+            unaccepted(&required, &[Permission::Execution]),
+            [Permission::Filesystem]
+        );
+        assert!(unaccepted(&[Permission::Network], &[]).is_empty());
+    }
+
+    #[test]
+    fn grants_bound_what_skills_may_use() {
+        let granted = [Permission::Network];
+        assert!(allows(&granted, None));
+        assert!(allows(&granted, Some(Permission::Network)));
+        assert!(!allows(&granted, Some(Permission::Execution)));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -9,6 +10,9 @@ use crate::loader;
 use crate::manifest::ReadinessStatus;
 use crate::types::SkillEntry;
 
+/// Skill subdirectories that may be read as resources.
+const RESOURCE_DIRS: [&str; 3] = ["references/", "scripts/", "assets/"];
+
 /// In-memory skills registry.
 pub struct SkillsRegistry {
     entries: RwLock<Arc<Vec<SkillEntry>>>,
@@ -73,6 +77,49 @@ impl SkillsRegistry {
         lines.join("\n")
     }
 
+    /// Like [`render_ready_index`](Self::render_ready_index), but when more
+    /// than `top_k` skills are ready only the `top_k` most relevant to
+    /// `query` are listed, followed by a pointer to the `skills.list` tool.
+    ///
+    /// Relevance is keyword overlap between the query and each skill's
+    /// name (weighted double) and description; ties keep registry order.
+    /// `top_k == 0` disables filtering.
+    pub fn render_relevant_index(&self, query: &str, top_k: usize) -> String {
+        let entries = self.entries.read();
+        let ready: Vec<&SkillEntry> = entries.iter().filter(|e| e.is_ready()).collect();
+        if top_k == 0 || ready.len() <= top_k {
+            drop(entries);
+            return self.render_ready_index();
+        }
+
+        let query_words = keywords(query);
+        let mut scored: Vec<(usize, &SkillEntry)> = ready
+            .iter()
+            .map(|e| (relevance(e, &query_words), *e))
+            .collect();
+        // Stable sort: equal scores keep registry order.
+        scored.sort_by_key(|b| std::cmp::Reverse(b.0));
+
+        let mut lines: Vec<String> = scored
+            .iter()
+            .take(top_k)
+            .map(|(_, e)| e.render_index_line())
+            .collect();
+        let hidden = ready.len() - top_k;
+        lines.push(format!(
+            "({hidden} more skill{} not shown — call skills.list for the full index)",
+            if hidden == 1 { "" } else { "s" }
+        ));
+        let blocked = entries.len() - ready.len();
+        if blocked > 0 {
+            lines.push(format!(
+                "({blocked} additional skill{} not shown — missing deps or unsupported platform)",
+                if blocked == 1 { "" } else { "s" }
+            ));
+        }
+        lines.join("\n")
+    }
+
     pub fn read_doc(&self, skill_name: &str) -> Result<String> {
         let exists = self.entries.read().iter().any(|e| e.name == skill_name);
         if !exists {
@@ -90,19 +137,38 @@ impl SkillsRegistry {
     /// Only allows reading from `references/`, `scripts/`, `assets/` subdirs.
     /// Blocks path traversal (`..", absolute paths, symlinks out of tree).
     pub fn read_resource(&self, skill_name: &str, relative_path: &str) -> Result<String> {
+        let canonical = self.resolve_resource(skill_name, relative_path)?;
+        let content = std::fs::read_to_string(&canonical)
+            .map_err(|_| Error::SkillNotFound(format!("resource not found: {relative_path}")))?;
+        Ok(content)
+    }
+
+    /// Raw bytes of a bundled resource (images, PDFs, ...).  Same path
+    /// restrictions as [`read_resource`](Self::read_resource).
+    pub fn read_resource_bytes(&self, skill_name: &str, relative_path: &str) -> Result<Vec<u8>> {
+        let canonical = self.resolve_resource(skill_name, relative_path)?;
+        std::fs::read(&canonical)
+            .map_err(|_| Error::SkillNotFound(format!("resource not found: {relative_path}")))
+    }
+
+    /// Validate `relative_path` and resolve it to a canonical path inside
+    /// the skill's directory.
+    fn resolve_resource(&self, skill_name: &str, relative_path: &str) -> Result<PathBuf> {
         let exists = self.entries.read().iter().any(|e| e.name == skill_name);
         if !exists {
             return Err(Error::SkillNotFound(skill_name.to_string()));
         }
 
         // Validate relative path safety.
-        if relative_path.contains("..") || relative_path.starts_with('/') {
+        if relative_path.contains("..")
+            || relative_path.starts_with('/')
+            || relative_path.contains('\\')
+        {
             return Err(Error::Auth("path traversal blocked".into()));
         }
 
         // Only allow reading from allowed subdirs.
-        let allowed_prefixes = ["references/", "scripts/", "assets/"];
-        if !allowed_prefixes.iter().any(|p| relative_path.starts_with(p)) {
+        if !RESOURCE_DIRS.iter().any(|p| relative_path.starts_with(p)) {
             return Err(Error::Auth(format!(
                 "resource path must start with references/, scripts/, or assets/ (got: {relative_path})"
             )));
@@ -121,10 +187,7 @@ impl SkillsRegistry {
         if !canonical.starts_with(&canonical_root) {
             return Err(Error::Auth("path traversal blocked (symlink)".into()));
         }
-
-        let content = std::fs::read_to_string(&canonical)
-            .map_err(|_| Error::SkillNotFound(format!("resource not found: {relative_path}")))?;
-        Ok(content)
+        Ok(canonical)
     }
 
     pub fn list(&self) -> Arc<Vec<SkillEntry>> {
@@ -173,8 +236,84 @@ impl SkillsRegistry {
             ready_count = ready,
             "skills registry reloaded"
         );
+        for dangling in self.validate_resource_refs() {
+            tracing::warn!(
+                skill = %dangling.skill,
+                path = %dangling.path,
+                "skill doc references a missing resource"
+            );
+        }
         Ok(count)
     }
+
+    /// Scan every skill's SKILL.md for `references/`, `scripts/` and
+    /// `assets/` paths and report the ones that don't resolve to a file
+    /// inside the skill's directory.
+    pub fn validate_resource_refs(&self) -> Vec<DanglingResourceRef> {
+        let entries = self.list();
+        let mut dangling = Vec::new();
+        for entry in entries.iter() {
+            let doc_path = self.skills_root.join(&entry.name).join("SKILL.md");
+            let Ok(doc) = std::fs::read_to_string(&doc_path) else {
+                continue;
+            };
+            for path in resource_refs(&doc) {
+                let found = self
+                    .resolve_resource(&entry.name, &path)
+                    .is_ok_and(|p| p.is_file());
+                if !found {
+                    dangling.push(DanglingResourceRef {
+                        skill: entry.name.clone(),
+                        path,
+                    });
+                }
+            }
+        }
+        dangling
+    }
+}
+
+/// A resource path mentioned in a skill's doc that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DanglingResourceRef {
+    pub skill: String,
+    pub path: String,
+}
+
+/// Resource paths (`references/…`, `scripts/…`, `assets/…`) mentioned in a
+/// skill doc, deduplicated in order of first appearance.  Paths are cut
+/// at anything that can't be part of a file name, so markdown links,
+/// backticks and `?path=` query strings all work.
+fn resource_refs(doc: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    doc.split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '/')))
+        .map(|token| token.strip_prefix("./").unwrap_or(token))
+        .map(|token| token.trim_end_matches(['.', '/']))
+        .filter(|token| {
+            RESOURCE_DIRS
+                .iter()
+                .any(|p| token.len() > p.len() && token.starts_with(p))
+        })
+        .filter(|token| seen.insert(token.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercased alphanumeric words of three or more characters.
+fn keywords(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn relevance(entry: &SkillEntry, query_words: &HashSet<String>) -> usize {
+    let name = keywords(&entry.name);
+    let description = keywords(&entry.description);
+    query_words
+        .iter()
+        .map(|w| 2 * usize::from(name.contains(w)) + usize::from(description.contains(w)))
+        .sum()
 }
 
 /// Counts for dashboard readiness display.
@@ -185,3 +324,135 @@ pub struct ReadinessSummary {
     pub missing_deps: usize,
     pub unsupported: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn registry_with_asset(bytes: &[u8]) -> (tempfile::TempDir, SkillsRegistry) {
+        let root = tempfile::tempdir().unwrap();
+        let skill = root.path().join("demo");
+        fs::create_dir_all(skill.join("assets")).unwrap();
+        fs::write(skill.join("SKILL.md"), "---\nname: demo\ndescription: Demo\n---\n# Demo").unwrap();
+        fs::write(skill.join("assets/logo.png"), bytes).unwrap();
+        let registry = SkillsRegistry::load(root.path()).unwrap();
+        (root, registry)
+    }
+
+    #[test]
+    fn reads_binary_resource_bytes() {
+        let bytes = [0x89, b'P', b'N', b'G', 0xff, 0x00];
+        let (_root, registry) = registry_with_asset(&bytes);
+        assert_eq!(
+            registry.read_resource_bytes("demo", "assets/logo.png").unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn resource_bytes_reject_traversal() {
+        let (_root, registry) = registry_with_asset(b"x");
+        for path in ["assets/../SKILL.md", "/etc/passwd", "assets\\..\\SKILL.md", "SKILL.md"] {
+            assert!(
+                matches!(registry.read_resource_bytes("demo", path), Err(Error::Auth(_))),
+                "{path} should be rejected"
+            );
+        }
+    }
+
+    fn registry_with_skills(skills: &[(&str, &str)]) -> (tempfile::TempDir, SkillsRegistry) {
+        let root = tempfile::tempdir().unwrap();
+        for (name, description) in skills {
+            let dir = root.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: {description}\n---\n# {name}"),
+            )
+            .unwrap();
+        }
+        let registry = SkillsRegistry::load(root.path()).unwrap();
+        (root, registry)
+    }
+
+    #[test]
+    fn relevant_index_keeps_only_top_k() {
+        let (_root, registry) = registry_with_skills(&[
+            ("apple-notes", "Search and edit Apple Notes"),
+            ("weather", "Current weather and forecasts"),
+            ("github", "Manage GitHub issues and pull requests"),
+            ("calendar", "Read and create calendar events"),
+            ("spotify", "Control Spotify playback"),
+            ("pdf-tools", "Merge and split PDF files"),
+        ]);
+
+        let index = registry.render_relevant_index("add the weather forecast to my calendar", 2);
+        let listed: Vec<&str> = index.lines().filter(|l| l.starts_with("- ")).collect();
+        assert_eq!(listed.len(), 2);
+        assert!(index.contains("- weather:"));
+        assert!(index.contains("- calendar:"));
+        assert!(!index.contains("spotify"));
+        assert!(index.contains("4 more skills not shown"));
+        assert!(index.contains("skills.list"));
+
+        let index = registry.render_relevant_index("open the github pull requests", 1);
+        assert!(index.contains("- github:"));
+        assert!(!index.contains("- weather:"));
+    }
+
+    #[test]
+    fn extracts_resource_refs_from_markdown() {
+        let doc = "See [guide](references/guide.md) and run `scripts/run.sh`.\n\
+                   Fetch /v1/skills/demo/resource?path=assets/logo.png, then \
+                   ./references/guide.md again. The assets/ folder is optional.";
+        assert_eq!(
+            resource_refs(doc),
+            ["references/guide.md", "scripts/run.sh", "assets/logo.png"]
+        );
+    }
+
+    #[test]
+    fn flags_missing_resource_refs() {
+        let (root, registry) = registry_with_asset(b"x");
+        fs::write(
+            root.path().join("demo/SKILL.md"),
+            "---\nname: demo\ndescription: Demo\n---\n\
+             Logo: assets/logo.png\nGuide: references/missing.md\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.validate_resource_refs(),
+            [DanglingResourceRef {
+                skill: "demo".into(),
+                path: "references/missing.md".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn valid_resource_refs_pass() {
+        let (root, registry) = registry_with_asset(b"x");
+        fs::write(
+            root.path().join("demo/SKILL.md"),
+            "---\nname: demo\ndescription: Demo\n---\n![logo](assets/logo.png)\n",
+        )
+        .unwrap();
+        assert!(registry.validate_resource_refs().is_empty());
+    }
+
+    #[test]
+    fn relevant_index_without_limit_lists_everything() {
+        let (_root, registry) =
+            registry_with_skills(&[("weather", "Forecasts"), ("github", "Issues")]);
+        assert_eq!(
+            registry.render_relevant_index("weather", 0),
+            registry.render_ready_index()
+        );
+        assert_eq!(
+            registry.render_relevant_index("weather", 5),
+            registry.render_ready_index()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Shell-words parsing for the exec tool's argv mode.
+//!
+//! Splits a command line into program + args the way a POSIX shell would
+//! tokenize it (single quotes, double quotes, backslash escapes), but
+//! performs no expansion.  Unquoted shell metacharacters are rejected
+//! rather than passed through, so `echo hi; rm x` fails instead of
+//! silently becoming `echo "hi;" rm x`.
+
+/// Characters with special meaning to `sh` when unquoted.
+pub const SHELL_METACHARS: &[char] = &[
+    ';', '&', '|', '<', '>', '(', ')', '$', '`', '*', '?', '[', ']', '{', '}', '~', '!', '#', '\n',
+    '\r',
+];
+
+/// Split `command` into argv.  Metacharacters in `allowed` may appear
+/// unquoted; quoted or backslash-escaped metacharacters are always
+/// literal.
+pub fn parse_argv(command: &str, allowed: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Whether `current` holds a word (possibly empty, e.g. `''`).
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err("unterminated single quote".into()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err("unterminated double quote".into()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("unterminated double quote".into()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(ch) => {
+                    in_word = true;
+                    current.push(ch);
+                }
+                None => return Err("trailing backslash".into()),
+            },
+            c if SHELL_METACHARS.contains(&c) && !allowed.contains(c) => {
+                return Err(format!(
+                    "shell metacharacter '{}' is not allowed in argv mode (quote it or use shell mode)",
+                    c.escape_default()
+                ));
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    if args.is_empty() {
+        return Err("empty command".into());
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_plain_words() {
+        assert_eq!(
+            parse_argv("git  log\t-n 5", "").unwrap(),
+            ["git", "log", "-n", "5"]
+        );
+    }
+
+    #[test]
+    fn quotes_and_escapes() {
+        assert_eq!(
+            parse_argv(r#"echo 'a b' "c \"d\" $HOME" e\ f ''"#, "").unwrap(),
+            ["echo", "a b", r#"c "d" $HOME"#, "e f", ""]
+        );
+    }
+
+    #[test]
+    fn rejects_unquoted_metachars() {
+        for cmd in [
+            "echo hi; rm x",
+            "cat a | sh",
+            "sleep 1 &",
+            "echo $(id)",
+            "echo `id`",
+            "ls > out",
+            "rm *",
+            "echo a\nrm x",
+        ] {
+            assert!(parse_argv(cmd, "").is_err(), "{cmd:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn quoted_metachars_are_literal() {
+        assert_eq!(
+            parse_argv(r"grep 'a;b|c' \;", "").unwrap(),
+            ["grep", "a;b|c", ";"]
+        );
+    }
+
+    #[test]
+    fn allowed_metachars_pass_through() {
+        assert!(parse_argv("find . -name x -exec rm {} +", "").is_err());
+        assert_eq!(
+            parse_argv("find . -exec rm {} +", "{}").unwrap(),
+            ["find", ".", "-exec", "rm", "{}", "+"]
+        );
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert!(parse_argv("echo 'open", "").is_err());
+        assert!(parse_argv("echo \"open", "").is_err());
+        assert!(parse_argv("echo \\", "").is_err());
+        assert!(parse_argv("   ", "").is_err());
+    }
+}
@@ -4,17 +4,26 @@
 //! - Foreground: run command, wait up to `yield_ms`, return output.
 //! - Background: spawn command, return immediately with session ID + initial tail.
 //! - If foreground exceeds `yield_ms`, auto-background and return session ID.
+//!
+//! Output that isn't valid UTF-8 is returned base64-encoded with
+//! `encoding: "base64"` instead of being lossily converted.
+//!
+//! Commands run through `sh -c` by default; with `mode = "argv"` they are
+//! split by [`parse_argv`] and spawned directly, without a shell.
 
 use std::sync::Arc;
 
+use base64::Engine as _;
 use chrono::Utc;
+use sa_domain::config::ExecMode;
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::{mpsc, Notify};
 
+use crate::argv::parse_argv;
 use crate::manager::{
-    OutputBuffer, ProcessManager, ProcessSession, ProcessStatus, StdinMessage,
+    OutputBuffer, OutputStream, ProcessManager, ProcessSession, ProcessStatus, StdinMessage,
 };
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -36,6 +45,9 @@ pub struct ExecRequest {
     /// Extra environment variables.
     #[serde(default)]
     pub env: Option<std::collections::HashMap<String, String>>,
+    /// Free-form label for grouping sessions in `process list`.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -45,12 +57,35 @@ pub struct ExecResponse {
     pub exit_code: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
+    /// Set when `output` is not plain text (absent = UTF-8).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<OutputEncoding>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub session_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tail: Option<String>,
 }
 
+/// Encoding of [`ExecResponse::output`] when it isn't UTF-8 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputEncoding {
+    /// Standard base64 of the exact output bytes.
+    Base64,
+}
+
+/// The output to return for a finished process: the text if it is exact,
+/// otherwise the raw bytes base64-encoded.
+fn encode_output(buf: &OutputBuffer) -> (String, Option<OutputEncoding>) {
+    match &buf.raw {
+        None => (buf.combined.clone(), None),
+        Some(raw) => (
+            base64::engine::general_purpose::STANDARD.encode(raw),
+            Some(OutputEncoding::Base64),
+        ),
+    }
+}
+
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Exec logic
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -88,10 +123,54 @@ pub async fn exec(
     };
     let timeout_sec = req.timeout_sec.unwrap_or(cfg.timeout_sec);
 
+    if let Err(e) = manager.check_capacity() {
+        return ExecResponse {
+            status: ProcessStatus::Failed,
+            exit_code: None,
+            output: Some(e),
+            encoding: None,
+            session_id: None,
+            tail: None,
+        };
+    }
+
     // Spawn the child process.
     let session_id = uuid::Uuid::new_v4().to_string();
-    let mut cmd = Command::new("sh");
-    cmd.arg("-c").arg(&req.command);
+    let mut cmd = match cfg.mode {
+        ExecMode::Shell => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(&req.command);
+            cmd
+        }
+        ExecMode::Argv => match parse_argv(&req.command, &cfg.allowed_metachars) {
+            Ok(argv) => {
+                let mut cmd = Command::new(&argv[0]);
+                cmd.args(&argv[1..]);
+                cmd
+            }
+            Err(e) => {
+                return ExecResponse {
+                    status: ProcessStatus::Failed,
+                    exit_code: None,
+                    output: Some(format!("command rejected: {e}")),
+                    encoding: None,
+                    session_id: None,
+                    tail: None,
+                };
+            }
+        },
+    };
+    crate::limits::apply(&mut cmd, &cfg.limits);
+    if let Err(e) = crate::sandbox::apply(&mut cmd, &cfg.sandbox) {
+        return ExecResponse {
+            status: ProcessStatus::Failed,
+            exit_code: None,
+            output: Some(e),
+            encoding: None,
+            session_id: None,
+            tail: None,
+        };
+    }
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
     cmd.stdin(std::process::Stdio::piped());
@@ -106,6 +185,7 @@ pub async fn exec(
                     status: ProcessStatus::Failed,
                     exit_code: None,
                     output: Some(format!("environment variable '{k}' is blocked by security policy")),
+                    encoding: None,
                     session_id: None,
                     tail: None,
                 };
@@ -121,6 +201,7 @@ pub async fn exec(
                 status: ProcessStatus::Failed,
                 exit_code: None,
                 output: Some(format!("failed to spawn: {e}")),
+                encoding: None,
                 session_id: None,
                 tail: None,
             };
@@ -143,6 +224,8 @@ pub async fn exec(
         stdin_tx: Some(stdin_tx),
         kill_tx: Some(kill_tx),
         name: None,
+        label: req.label.clone(),
+        stdin_bytes: 0,
     };
 
     let session_arc = manager.register(session);
@@ -160,6 +243,7 @@ pub async fn exec(
             status: ProcessStatus::Running,
             exit_code: None,
             output: None,
+            encoding: None,
             session_id: Some(session_id),
             tail: Some(String::new()),
         };
@@ -176,10 +260,12 @@ pub async fn exec(
     tokio::select! {
         _ = done_notify.notified() => {
             let s = session_arc.read();
+            let (output, encoding) = encode_output(&s.output);
             ExecResponse {
                 status: s.status,
                 exit_code: s.exit_code,
-                output: Some(s.output.combined.clone()),
+                output: Some(output),
+                encoding,
                 session_id: None,
                 tail: None,
             }
@@ -191,6 +277,7 @@ pub async fn exec(
                 status: ProcessStatus::Running,
                 exit_code: None,
                 output: None,
+                encoding: None,
                 session_id: Some(session_id),
                 tail: Some(tail),
             }
@@ -198,6 +285,27 @@ pub async fn exec(
     }
 }
 
+/// How long to keep reading output after killing a process.  Grandchildren
+/// spawned by the shell can hold the pipes open long after the shell dies.
+const KILL_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Collect remaining output from the reader tasks, giving up after
+/// [`KILL_DRAIN_TIMEOUT`] so a killed session is not left `Running`.
+async fn drain_output(
+    mut stdout_task: tokio::task::JoinHandle<()>,
+    mut stderr_task: tokio::task::JoinHandle<()>,
+) {
+    let drained = tokio::time::timeout(KILL_DRAIN_TIMEOUT, async {
+        let _ = (&mut stdout_task).await;
+        let _ = (&mut stderr_task).await;
+    })
+    .await;
+    if drained.is_err() {
+        stdout_task.abort();
+        stderr_task.abort();
+    }
+}
+
 /// Spawn the background task that monitors the child process.
 fn spawn_monitor(
     mut child: tokio::process::Child,
@@ -216,11 +324,17 @@ fn spawn_monitor(
         let session_out = session.clone();
         let stdout_task = tokio::spawn(async move {
             if let Some(stdout) = stdout {
-                let mut reader = BufReader::new(stdout).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let mut s = session_out.write();
-                    s.output.push(&line);
-                    s.output.push("\n");
+                let mut reader = BufReader::new(stdout);
+                let mut line = Vec::new();
+                while let Ok(n) = reader.read_until(b'\n', &mut line).await {
+                    if n == 0 {
+                        break;
+                    }
+                    session_out
+                        .write()
+                        .output
+                        .push_line(OutputStream::Stdout, &line);
+                    line.clear();
                 }
             }
         });
@@ -229,11 +343,17 @@ fn spawn_monitor(
         let session_err = session.clone();
         let stderr_task = tokio::spawn(async move {
             if let Some(stderr) = stderr {
-                let mut reader = BufReader::new(stderr).lines();
-                while let Ok(Some(line)) = reader.next_line().await {
-                    let mut s = session_err.write();
-                    s.output.push(&line);
-                    s.output.push("\n");
+                let mut reader = BufReader::new(stderr);
+                let mut line = Vec::new();
+                while let Ok(n) = reader.read_until(b'\n', &mut line).await {
+                    if n == 0 {
+                        break;
+                    }
+                    session_err
+                        .write()
+                        .output
+                        .push_line(OutputStream::Stderr, &line);
+                    line.clear();
                 }
             }
         });
@@ -289,8 +409,7 @@ fn spawn_monitor(
             }
             _ = kill_rx.recv() => {
                 let _ = child.kill().await;
-                let _ = stdout_task.await;
-                let _ = stderr_task.await;
+                drain_output(stdout_task, stderr_task).await;
                 stdin_task.abort();
 
                 let mut s = session.write();
@@ -303,8 +422,7 @@ fn spawn_monitor(
             }
             _ = tokio::time::sleep(timeout_dur) => {
                 let _ = child.kill().await;
-                let _ = stdout_task.await;
-                let _ = stderr_task.await;
+                drain_output(stdout_task, stderr_task).await;
                 stdin_task.abort();
 
                 let mut s = session.write();
@@ -327,3 +445,144 @@ fn spawn_monitor(
         );
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sa_domain::config::ExecConfig;
+
+    fn manager(mode: ExecMode) -> ProcessManager {
+        ProcessManager::new(ExecConfig {
+            mode,
+            ..ExecConfig::default()
+        })
+    }
+
+    fn request(command: &str) -> ExecRequest {
+        ExecRequest {
+            command: command.into(),
+            background: false,
+            yield_ms: Some(10_000),
+            timeout_sec: Some(10),
+            workdir: None,
+            env: None,
+            label: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn argv_mode_rejects_chained_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let victim = dir.path().join("x");
+        std::fs::write(&victim, "keep").unwrap();
+
+        let mgr = manager(ExecMode::Argv);
+        let mut req = request("echo hi; rm x");
+        req.workdir = Some(dir.path().to_string_lossy().into_owned());
+        let resp = exec(&mgr, req).await;
+
+        assert_eq!(resp.status, ProcessStatus::Failed);
+        assert!(resp.output.unwrap().contains("';'"));
+        assert!(victim.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn argv_mode_runs_clean_argv_without_a_shell() {
+        let mgr = manager(ExecMode::Argv);
+        let resp = exec(&mgr, request("echo 'a;b' $HOME")).await;
+        // Rejected: `$` is unquoted.
+        assert_eq!(resp.status, ProcessStatus::Failed);
+
+        let resp = exec(&mgr, request("echo 'a;b' '$HOME'")).await;
+        assert_eq!(resp.status, ProcessStatus::Finished);
+        assert_eq!(resp.exit_code, Some(0));
+        // No shell, so nothing was expanded.
+        assert_eq!(resp.output.unwrap(), "a;b $HOME\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shell_mode_keeps_shell_semantics() {
+        let mgr = manager(ExecMode::Shell);
+        let resp = exec(&mgr, request("echo hi; echo there")).await;
+        assert_eq!(resp.status, ProcessStatus::Finished);
+        assert_eq!(resp.output.unwrap(), "hi\nthere\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn non_utf8_output_round_trips_as_base64() {
+        let mgr = manager(ExecMode::Shell);
+        let resp = exec(&mgr, request(r"printf 'ok\n\377\376bin'")).await;
+        assert_eq!(resp.status, ProcessStatus::Finished);
+        assert_eq!(resp.encoding, Some(OutputEncoding::Base64));
+
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(resp.output.unwrap())
+            .unwrap();
+        assert_eq!(bytes, b"ok\n\xff\xfebin");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn utf8_output_passes_through() {
+        let mgr = manager(ExecMode::Shell);
+        let resp = exec(&mgr, request("printf 'héllo wörld\\n'")).await;
+        assert_eq!(resp.encoding, None);
+        assert_eq!(resp.output.unwrap(), "héllo wörld\n");
+    }
+
+    #[test]
+    fn output_buffer_keeps_exact_bytes_after_invalid_chunk() {
+        let mut buf = OutputBuffer::new(1_000);
+        buf.push_bytes(b"text\n");
+        assert!(buf.is_utf8());
+        buf.push_bytes(b"\xc3\x28\n");
+        buf.push("[killed]");
+        assert!(!buf.is_utf8());
+        assert_eq!(buf.raw.as_deref().unwrap(), b"text\n\xc3\x28\n[killed]");
+        // The text view stays readable.
+        assert!(buf.combined.starts_with("text\n\u{fffd}("));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn background_process_limit_is_enforced_and_freed() {
+        let mgr = ProcessManager::new(ExecConfig {
+            max_background_processes: 2,
+            cleanup_ms: 0,
+            ..ExecConfig::default()
+        });
+        let mut sleeper = request("sleep 30");
+        sleeper.background = true;
+
+        let first = exec(&mgr, sleeper.clone()).await;
+        let second = exec(&mgr, sleeper.clone()).await;
+        assert_eq!(first.status, ProcessStatus::Running);
+        assert_eq!(second.status, ProcessStatus::Running);
+
+        let refused = exec(&mgr, sleeper.clone()).await;
+        assert_eq!(refused.status, ProcessStatus::Failed);
+        assert!(refused.output.unwrap().contains("too many running processes"));
+        assert_eq!(mgr.list().len(), 2);
+
+        // Killing one frees its slot, and the terminated session is
+        // reclaimed before the next start.
+        let first_id = first.session_id.unwrap();
+        assert!(mgr.kill(&first_id));
+        for _ in 0..100 {
+            if mgr.running_count() < 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let third = exec(&mgr, sleeper).await;
+        assert_eq!(third.status, ProcessStatus::Running);
+        assert!(mgr.get(&first_id).is_none());
+
+        mgr.remove(second.session_id.as_deref().unwrap());
+        mgr.remove(third.session_id.as_deref().unwrap());
+    }
+}
@@ -627,6 +627,6 @@ mod tests {
 
         // Check that subdir is marked as a directory.
         let subdir_entry = entries.iter().find(|e| e["name"] == "subdir").unwrap();
-        assert_eq!(subdir_entry["is_dir"].as_bool().unwrap(), true);
+        assert!(subdir_entry["is_dir"].as_bool().unwrap());
     }
 }
@@ -4,9 +4,12 @@
 //! - `exec`: run commands foreground or auto-background after yieldMs
 //! - `process`: manage background sessions (list/poll/log/write/kill/clear/remove)
 
+pub mod argv;
 pub mod exec;
 pub mod file_ops;
+pub mod limits;
 pub mod manager;
 pub mod process;
+pub mod sandbox;
 
 pub use manager::ProcessManager;
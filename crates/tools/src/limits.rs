@@ -0,0 +1,121 @@
+//! Kernel resource limits for exec'd commands.
+//!
+//! On Unix, [`apply`] installs a `pre_exec` hook that calls `setrlimit` in
+//! the forked child before it execs, so a runaway build is stopped by the
+//! kernel (SIGXCPU/SIGKILL, failed allocations) instead of starving the
+//! host.  Elsewhere it is a no-op.
+
+use sa_domain::config::ExecLimitsConfig;
+use tokio::process::Command;
+
+/// Whether any limit is configured.
+pub fn is_configured(limits: &ExecLimitsConfig) -> bool {
+    limits.max_memory_mb.is_some()
+        || limits.max_cpu_secs.is_some()
+        || limits.max_open_files.is_some()
+}
+
+/// `setrlimit(resource, soft, hard)`, returning `errno` on failure.  A
+/// macro because the resource constant's type differs between libcs.
+#[cfg(unix)]
+macro_rules! set_rlimit {
+    ($resource:expr, $soft:expr, $hard:expr) => {{
+        let limit = libc::rlimit {
+            rlim_cur: $soft as libc::rlim_t,
+            rlim_max: $hard as libc::rlim_t,
+        };
+        // SAFETY: `limit` is a valid, initialized rlimit for the call.
+        if unsafe { libc::setrlimit($resource, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }};
+}
+
+/// Apply `limits` to the process `cmd` will spawn.
+#[cfg(unix)]
+pub fn apply(cmd: &mut Command, limits: &ExecLimitsConfig) {
+    if !is_configured(limits) {
+        return;
+    }
+    let memory = limits
+        .max_memory_mb
+        .map(|mb| mb.saturating_mul(1024 * 1024));
+    let cpu = limits.max_cpu_secs;
+    let files = limits.max_open_files;
+
+    let hook = move || {
+        if let Some(bytes) = memory {
+            set_rlimit!(libc::RLIMIT_AS, bytes, bytes);
+        }
+        if let Some(secs) = cpu {
+            // Soft limit → SIGXCPU; the hard limit a second later is
+            // SIGKILL for children that ignore SIGXCPU.
+            set_rlimit!(libc::RLIMIT_CPU, secs, secs.saturating_add(1));
+        }
+        if let Some(n) = files {
+            set_rlimit!(libc::RLIMIT_NOFILE, n, n);
+        }
+        Ok(())
+    };
+
+    // SAFETY: the hook runs between fork and exec, so it may only call
+    // async-signal-safe functions.  It captures plain integers and calls
+    // nothing but `setrlimit` and `errno` access, both of which are.
+    unsafe {
+        cmd.pre_exec(hook);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn apply(_cmd: &mut Command, limits: &ExecLimitsConfig) {
+    if is_configured(limits) {
+        tracing::debug!("exec resource limits are only enforced on Unix");
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn cpu_limit_kills_a_busy_loop() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("while :; do :; done");
+        apply(
+            &mut cmd,
+            &ExecLimitsConfig {
+                max_cpu_secs: Some(1),
+                ..Default::default()
+            },
+        );
+
+        let started = std::time::Instant::now();
+        let status = tokio::time::timeout(std::time::Duration::from_secs(20), cmd.status())
+            .await
+            .expect("busy loop should have been killed by RLIMIT_CPU")
+            .unwrap();
+
+        use std::os::unix::process::ExitStatusExt;
+        assert!(!status.success());
+        assert!(matches!(
+            status.signal(),
+            Some(libc::SIGXCPU) | Some(libc::SIGKILL)
+        ));
+        assert!(started.elapsed() < std::time::Duration::from_secs(20));
+    }
+
+    #[tokio::test]
+    async fn open_files_limit_is_visible_to_the_child() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("ulimit -n");
+        apply(
+            &mut cmd,
+            &ExecLimitsConfig {
+                max_open_files: Some(64),
+                ..Default::default()
+            },
+        );
+        let out = cmd.output().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "64");
+    }
+}
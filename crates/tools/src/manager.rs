@@ -4,12 +4,12 @@
 //! The manager owns no child processes directly — each spawn creates a
 //! background tokio task that writes into the shared `ProcessSession`.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
 use sa_domain::config::ExecConfig;
@@ -18,7 +18,7 @@ use sa_domain::config::ExecConfig;
 // Types
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProcessStatus {
     Running,
@@ -43,10 +43,42 @@ pub struct ProcessSession {
     /// Send a kill signal to the background task.
     pub kill_tx: Option<mpsc::Sender<()>>,
     pub name: Option<String>,
+    /// Caller-supplied grouping label (from `exec`'s `label`).
+    pub label: Option<String>,
+    /// Bytes written to stdin so far (checked against
+    /// `max_stdin_total_bytes`).
+    pub stdin_bytes: usize,
+}
+
+/// Which pipe a captured line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// One captured line, tagged with its stream and capture order.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputLine {
+    /// Monotonic per-process sequence number; orders lines across streams.
+    pub seq: u64,
+    pub stream: OutputStream,
+    pub at: DateTime<Utc>,
+    pub text: String,
 }
 
 pub struct OutputBuffer {
+    /// Output as text, both streams interleaved in capture order.  Invalid
+    /// UTF-8 is replaced with U+FFFD here; see `raw` for the exact bytes.
     pub combined: String,
+    /// Exact output bytes, kept only once the child has emitted something
+    /// that isn't valid UTF-8 (until then `combined` is exact).
+    pub raw: Option<Vec<u8>>,
+    /// Per-line capture log backing stream-separated reads.
+    pub lines: VecDeque<OutputLine>,
+    lines_chars: usize,
+    next_seq: u64,
     pub max_chars: usize,
 }
 
@@ -54,12 +86,72 @@ impl OutputBuffer {
     pub fn new(max_chars: usize) -> Self {
         Self {
             combined: String::new(),
+            raw: None,
+            lines: VecDeque::new(),
+            lines_chars: 0,
+            next_seq: 0,
             max_chars,
         }
     }
 
     pub fn push(&mut self, text: &str) {
-        self.combined.push_str(text);
+        self.push_bytes(text.as_bytes());
+    }
+
+    /// Append one line read from the child's `stream`: to the combined
+    /// view and, tagged with the next sequence number, to the line log.
+    pub fn push_line(&mut self, stream: OutputStream, bytes: &[u8]) {
+        self.push_bytes(bytes);
+        let text = String::from_utf8_lossy(bytes).into_owned();
+        self.lines_chars += text.len();
+        self.lines.push_back(OutputLine {
+            seq: self.next_seq,
+            stream,
+            at: Utc::now(),
+            text,
+        });
+        self.next_seq += 1;
+        while self.lines_chars > self.max_chars {
+            match self.lines.pop_front() {
+                Some(old) => self.lines_chars -= old.text.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// The last `tail` captured lines, in capture order, optionally
+    /// restricted to one stream.
+    pub fn stream_lines(&self, stream: Option<OutputStream>, tail: usize) -> Vec<OutputLine> {
+        let mut out: Vec<OutputLine> = self
+            .lines
+            .iter()
+            .rev()
+            .filter(|l| stream.is_none_or(|s| l.stream == s))
+            .take(tail)
+            .cloned()
+            .collect();
+        out.reverse();
+        out
+    }
+
+    /// Append raw child output.  Valid UTF-8 goes straight into
+    /// `combined`; the first invalid chunk switches on byte tracking so
+    /// the output can still be returned losslessly.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        match std::str::from_utf8(bytes) {
+            Ok(text) if self.raw.is_none() => self.combined.push_str(text),
+            _ => {
+                let raw = self
+                    .raw
+                    .get_or_insert_with(|| self.combined.as_bytes().to_vec());
+                raw.extend_from_slice(bytes);
+                if raw.len() > self.max_chars {
+                    let keep = self.max_chars * 3 / 4;
+                    raw.drain(..raw.len() - keep);
+                }
+                self.combined.push_str(&String::from_utf8_lossy(bytes));
+            }
+        }
         if self.combined.len() > self.max_chars {
             let keep = self.max_chars * 3 / 4;
             let drain_count = self.combined.len() - keep;
@@ -72,6 +164,11 @@ impl OutputBuffer {
         }
     }
 
+    /// Whether `combined` is an exact copy of the output.
+    pub fn is_utf8(&self) -> bool {
+        self.raw.is_none()
+    }
+
     pub fn len(&self) -> usize {
         self.combined.len()
     }
@@ -116,6 +213,15 @@ impl OutputBuffer {
     }
 }
 
+/// Why [`ProcessManager::write_stdin`] refused a write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StdinWriteError {
+    /// No such session, or its stdin is already closed.
+    NotWritable,
+    /// The write exceeds the per-write or per-session byte cap.
+    TooLarge(String),
+}
+
 /// Messages that can be sent to a process's stdin.
 pub enum StdinMessage {
     Data(Vec<u8>),
@@ -144,6 +250,29 @@ impl ProcessManager {
         &self.config
     }
 
+    /// Number of sessions whose process is still running.
+    pub fn running_count(&self) -> usize {
+        self.sessions
+            .read()
+            .values()
+            .filter(|s| s.read().status == ProcessStatus::Running)
+            .count()
+    }
+
+    /// Check `max_background_processes` before spawning.  Stale finished
+    /// sessions are cleaned up first so they don't linger in the registry.
+    pub fn check_capacity(&self) -> Result<(), String> {
+        self.cleanup_stale();
+        let max = self.config.max_background_processes;
+        let running = self.running_count();
+        if max > 0 && running >= max {
+            return Err(format!(
+                "too many running processes ({running}/{max}); kill or wait for one to finish"
+            ));
+        }
+        Ok(())
+    }
+
     /// Register a new process session.
     pub fn register(&self, session: ProcessSession) -> Arc<RwLock<ProcessSession>> {
         let id = session.id.clone();
@@ -159,12 +288,27 @@ impl ProcessManager {
 
     /// List all process sessions with their current status.
     pub fn list(&self) -> Vec<ProcessInfo> {
+        self.list_filtered(None, None)
+    }
+
+    /// List process sessions, keeping only those with `label` and/or in
+    /// `status` when given.
+    pub fn list_filtered(
+        &self,
+        label: Option<&str>,
+        status: Option<ProcessStatus>,
+    ) -> Vec<ProcessInfo> {
         self.sessions
             .read()
             .values()
-            .map(|s| {
+            .filter_map(|s| {
                 let s = s.read();
-                ProcessInfo {
+                if label.is_some_and(|l| s.label.as_deref() != Some(l))
+                    || status.is_some_and(|st| s.status != st)
+                {
+                    return None;
+                }
+                Some(ProcessInfo {
                     id: s.id.clone(),
                     command: s.command.clone(),
                     status: s.status,
@@ -173,7 +317,8 @@ impl ProcessManager {
                     finished_at: s.finished_at,
                     output_chars: s.output.len(),
                     name: s.name.clone(),
-                }
+                    label: s.label.clone(),
+                })
             })
             .collect()
     }
@@ -203,6 +348,20 @@ impl ProcessManager {
         }
     }
 
+    /// Captured lines of a process (last `tail_lines`, default 200), in
+    /// emission order, optionally only one stream.
+    pub fn stream_lines(
+        &self,
+        id: &str,
+        stream: Option<OutputStream>,
+        tail_lines: Option<usize>,
+    ) -> Option<Vec<OutputLine>> {
+        let sessions = self.sessions.read();
+        let arc = sessions.get(id)?;
+        let s = arc.read();
+        Some(s.output.stream_lines(stream, tail_lines.unwrap_or(200)))
+    }
+
     /// Kill a running process.
     pub fn kill(&self, id: &str) -> bool {
         let sessions = self.sessions.read();
@@ -218,28 +377,45 @@ impl ProcessManager {
         false
     }
 
-    /// Write data to a process's stdin.
-    pub async fn write_stdin(&self, id: &str, data: Vec<u8>, eof: bool) -> bool {
+    /// Write data to a process's stdin, enforcing `max_stdin_write_bytes`
+    /// per call and `max_stdin_total_bytes` per session.
+    pub async fn write_stdin(
+        &self,
+        id: &str,
+        data: Vec<u8>,
+        eof: bool,
+    ) -> Result<(), StdinWriteError> {
+        let max_write = self.config.max_stdin_write_bytes;
+        if max_write > 0 && data.len() > max_write {
+            return Err(StdinWriteError::TooLarge(format!(
+                "stdin write of {} bytes exceeds the {max_write}-byte limit per write",
+                data.len()
+            )));
+        }
+
         let tx = {
             let sessions = self.sessions.read();
-            let arc = sessions.get(id);
-            arc.and_then(|a| {
-                let s = a.read();
-                s.stdin_tx.clone()
-            })
+            let arc = sessions.get(id).ok_or(StdinWriteError::NotWritable)?;
+            let mut s = arc.write();
+            let tx = s.stdin_tx.clone().ok_or(StdinWriteError::NotWritable)?;
+            let max_total = self.config.max_stdin_total_bytes;
+            let total = s.stdin_bytes + data.len();
+            if max_total > 0 && total > max_total {
+                return Err(StdinWriteError::TooLarge(format!(
+                    "stdin write would bring this session to {total} bytes, over the {max_total}-byte limit"
+                )));
+            }
+            s.stdin_bytes = total;
+            tx
         };
 
-        if let Some(tx) = tx {
-            if !data.is_empty() {
-                let _ = tx.send(StdinMessage::Data(data)).await;
-            }
-            if eof {
-                let _ = tx.send(StdinMessage::Eof).await;
-            }
-            true
-        } else {
-            false
+        if !data.is_empty() {
+            let _ = tx.send(StdinMessage::Data(data)).await;
+        }
+        if eof {
+            let _ = tx.send(StdinMessage::Eof).await;
         }
+        Ok(())
     }
 
     /// Remove all finished sessions.
@@ -289,6 +465,7 @@ pub struct ProcessInfo {
     pub finished_at: Option<DateTime<Utc>>,
     pub output_chars: usize,
     pub name: Option<String>,
+    pub label: Option<String>,
 }
 
 /// Result of polling a process.
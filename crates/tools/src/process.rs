@@ -1,10 +1,14 @@
 //! Process tool — manage background process sessions.
 //!
 //! Actions: list, poll, log, write, kill, clear, remove.
+//!
+//! `log` reads stdout and stderr interleaved in emission order by default,
+//! or a single stream with `stream: "stdout" | "stderr"`.
 
+use sa_protocol::ErrorKind;
 use serde::{Deserialize, Serialize};
 
-use crate::manager::ProcessManager;
+use crate::manager::{OutputStream, ProcessManager, ProcessStatus, StdinWriteError};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 // Request / Response
@@ -31,6 +35,40 @@ pub struct ProcessRequest {
     /// For `write`: close stdin after sending.
     #[serde(default)]
     pub eof: bool,
+    /// For `list`: only sessions with this label.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// For `list`: only sessions in this status.
+    #[serde(default)]
+    pub status: Option<ProcessStatus>,
+    /// For `log`: which output to read (default `combined`).
+    #[serde(default)]
+    pub stream: LogStream,
+    /// For `log`: also return the captured lines with their sequence
+    /// number, stream and capture timestamp.
+    #[serde(default)]
+    pub timestamps: bool,
+}
+
+/// Output selection for the `log` action.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    /// stdout and stderr interleaved in the order they were emitted.
+    #[default]
+    Combined,
+    Stdout,
+    Stderr,
+}
+
+impl LogStream {
+    fn only(self) -> Option<OutputStream> {
+        match self {
+            Self::Combined => None,
+            Self::Stdout => Some(OutputStream::Stdout),
+            Self::Stderr => Some(OutputStream::Stderr),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
@@ -50,6 +88,9 @@ pub struct ProcessResponse {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Machine-readable failure class, when one applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<ErrorKind>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<serde_json::Value>,
 }
@@ -65,10 +106,11 @@ pub async fn handle_process(
 ) -> ProcessResponse {
     match req.action {
         ProcessAction::List => {
-            let sessions = manager.list();
+            let sessions = manager.list_filtered(req.label.as_deref(), req.status);
             ProcessResponse {
                 success: true,
                 error: None,
+                error_kind: None,
                 data: Some(serde_json::json!({
                     "sessions": sessions,
                     "count": sessions.len(),
@@ -83,6 +125,7 @@ pub async fn handle_process(
                     return ProcessResponse {
                         success: false,
                         error: Some("session_id required for poll".into()),
+                        error_kind: None,
                         data: None,
                     }
                 }
@@ -91,11 +134,13 @@ pub async fn handle_process(
                 Some(result) => ProcessResponse {
                     success: true,
                     error: None,
+                    error_kind: None,
                     data: Some(serde_json::to_value(result).unwrap_or_default()),
                 },
                 None => ProcessResponse {
                     success: false,
                     error: Some("session not found".into()),
+                    error_kind: None,
                     data: None,
                 },
             }
@@ -108,19 +153,47 @@ pub async fn handle_process(
                     return ProcessResponse {
                         success: false,
                         error: Some("session_id required for log".into()),
+                        error_kind: None,
                         data: None,
                     }
                 }
             };
+            // A single stream (or a line listing) comes from the line log;
+            // the plain combined view keeps byte-offset paging.
+            if req.stream != LogStream::Combined || req.timestamps {
+                return match manager.stream_lines(sid, req.stream.only(), req.tail_lines) {
+                    Some(lines) => {
+                        let log: String = lines.iter().map(|l| l.text.as_str()).collect();
+                        let mut data = serde_json::json!({ "log": log });
+                        if req.timestamps {
+                            data["lines"] = serde_json::to_value(&lines).unwrap_or_default();
+                        }
+                        ProcessResponse {
+                            success: true,
+                            error: None,
+                            error_kind: None,
+                            data: Some(data),
+                        }
+                    }
+                    None => ProcessResponse {
+                        success: false,
+                        error: Some("session not found".into()),
+                        error_kind: None,
+                        data: None,
+                    },
+                };
+            }
             match manager.log(sid, req.offset, req.limit, req.tail_lines) {
                 Some(log) => ProcessResponse {
                     success: true,
                     error: None,
+                    error_kind: None,
                     data: Some(serde_json::json!({ "log": log })),
                 },
                 None => ProcessResponse {
                     success: false,
                     error: Some("session not found".into()),
+                    error_kind: None,
                     data: None,
                 },
             }
@@ -133,16 +206,31 @@ pub async fn handle_process(
                     return ProcessResponse {
                         success: false,
                         error: Some("session_id required for write".into()),
+                        error_kind: None,
                         data: None,
                     }
                 }
             };
             let data = req.data.unwrap_or_default().into_bytes();
-            let ok = manager.write_stdin(sid, data, req.eof).await;
-            ProcessResponse {
-                success: ok,
-                error: if ok { None } else { Some("session not found or stdin closed".into()) },
-                data: None,
+            match manager.write_stdin(sid, data, req.eof).await {
+                Ok(()) => ProcessResponse {
+                    success: true,
+                    error: None,
+                    error_kind: None,
+                    data: None,
+                },
+                Err(StdinWriteError::NotWritable) => ProcessResponse {
+                    success: false,
+                    error: Some("session not found or stdin closed".into()),
+                    error_kind: None,
+                    data: None,
+                },
+                Err(StdinWriteError::TooLarge(msg)) => ProcessResponse {
+                    success: false,
+                    error: Some(msg),
+                    error_kind: Some(ErrorKind::InvalidArgs),
+                    data: None,
+                },
             }
         }
 
@@ -153,6 +241,7 @@ pub async fn handle_process(
                     return ProcessResponse {
                         success: false,
                         error: Some("session_id required for kill".into()),
+                        error_kind: None,
                         data: None,
                     }
                 }
@@ -161,6 +250,7 @@ pub async fn handle_process(
             ProcessResponse {
                 success: ok,
                 error: if ok { None } else { Some("session not found or not running".into()) },
+                error_kind: None,
                 data: None,
             }
         }
@@ -170,6 +260,7 @@ pub async fn handle_process(
             ProcessResponse {
                 success: true,
                 error: None,
+                error_kind: None,
                 data: Some(serde_json::json!({ "cleared": cleared })),
             }
         }
@@ -181,6 +272,7 @@ pub async fn handle_process(
                     return ProcessResponse {
                         success: false,
                         error: Some("session_id required for remove".into()),
+                        error_kind: None,
                         data: None,
                     }
                 }
@@ -189,8 +281,146 @@ pub async fn handle_process(
             ProcessResponse {
                 success: ok,
                 error: if ok { None } else { Some("session not found".into()) },
+                error_kind: None,
                 data: None,
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::{exec, ExecRequest};
+    use sa_domain::config::ExecConfig;
+
+    async fn start(manager: &ProcessManager, command: &str, background: bool, label: Option<&str>) {
+        let req = ExecRequest {
+            command: command.into(),
+            background,
+            yield_ms: Some(10_000),
+            timeout_sec: Some(30),
+            workdir: None,
+            env: None,
+            label: label.map(str::to_owned),
+        };
+        exec(manager, req).await;
+    }
+
+    async fn list(manager: &ProcessManager, filter: serde_json::Value) -> Vec<serde_json::Value> {
+        let mut req = serde_json::json!({ "action": "list" });
+        req.as_object_mut()
+            .unwrap()
+            .extend(filter.as_object().unwrap().clone());
+        let resp = handle_process(manager, serde_json::from_value(req).unwrap()).await;
+        assert!(resp.success);
+        resp.data.unwrap()["sessions"].as_array().unwrap().clone()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn list_filters_by_label_and_status() {
+        let manager = ProcessManager::new(ExecConfig::default());
+        start(&manager, "sleep 30", true, Some("build")).await;
+        start(&manager, "true", false, Some("build")).await;
+        start(&manager, "true", false, None).await;
+
+        // No filter: everything, unlabeled sessions included.
+        let all = list(&manager, serde_json::json!({})).await;
+        assert_eq!(all.len(), 3);
+        assert_eq!(all.iter().filter(|s| s["label"].is_null()).count(), 1);
+
+        let build = list(&manager, serde_json::json!({ "label": "build" })).await;
+        assert_eq!(build.len(), 2);
+
+        let running = list(
+            &manager,
+            serde_json::json!({ "label": "build", "status": "running" }),
+        )
+        .await;
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0]["command"], "sleep 30");
+
+        let finished = list(&manager, serde_json::json!({ "status": "finished" })).await;
+        assert_eq!(finished.len(), 2);
+
+        assert!(list(&manager, serde_json::json!({ "label": "deploy" }))
+            .await
+            .is_empty());
+
+        manager.kill(running[0]["id"].as_str().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn log_streams_preserve_emission_order() {
+        let manager = ProcessManager::new(ExecConfig::default());
+        start(
+            &manager,
+            "echo out1; sleep 0.1; echo err1 >&2; sleep 0.1; echo out2; sleep 0.1; echo err2 >&2",
+            false,
+            None,
+        )
+        .await;
+        let id = manager.list()[0].id.clone();
+
+        let log = |stream: &str| {
+            let req = serde_json::json!({
+                "action": "log",
+                "session_id": id,
+                "stream": stream,
+                "timestamps": true,
+            });
+            let manager = &manager;
+            async move { handle_process(manager, serde_json::from_value(req).unwrap()).await }
+        };
+
+        let combined = log("combined").await.data.unwrap();
+        assert_eq!(combined["log"], "out1\nerr1\nout2\nerr2\n");
+        let lines = combined["lines"].as_array().unwrap();
+        let streams: Vec<_> = lines.iter().map(|l| l["stream"].as_str().unwrap()).collect();
+        assert_eq!(streams, ["stdout", "stderr", "stdout", "stderr"]);
+        let seqs: Vec<_> = lines.iter().map(|l| l["seq"].as_u64().unwrap()).collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+
+        let stdout = log("stdout").await.data.unwrap();
+        assert_eq!(stdout["log"], "out1\nout2\n");
+        let stderr = log("stderr").await.data.unwrap();
+        assert_eq!(stderr["log"], "err1\nerr2\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdin_writes_are_capped() {
+        let manager = ProcessManager::new(ExecConfig {
+            max_stdin_write_bytes: 8,
+            max_stdin_total_bytes: 12,
+            ..ExecConfig::default()
+        });
+        start(&manager, "cat", true, None).await;
+        let id = manager.list()[0].id.clone();
+
+        let write = |data: &str| {
+            let req = serde_json::json!({ "action": "write", "session_id": id, "data": data });
+            let manager = &manager;
+            async move { handle_process(manager, serde_json::from_value(req).unwrap()).await }
+        };
+
+        let ok = write("hello").await;
+        assert!(ok.success);
+        assert_eq!(ok.error_kind, None);
+
+        let too_big = write("123456789").await;
+        assert!(!too_big.success);
+        assert_eq!(too_big.error_kind, Some(ErrorKind::InvalidArgs));
+
+        // The rejected write didn't count: 5 + 7 bytes fits the session cap.
+        assert!(write("1234567").await.success);
+        let over_total = write("x").await;
+        assert!(!over_total.success);
+        assert_eq!(over_total.error_kind, Some(ErrorKind::InvalidArgs));
+        assert!(over_total.error.unwrap().contains("12-byte limit"));
+
+        manager.kill(&id);
+    }
+}
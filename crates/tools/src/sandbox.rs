@@ -0,0 +1,291 @@
+//! Seccomp sandbox for exec'd commands.
+//!
+//! With `[tools.exec.sandbox] enabled = true`, [`apply`] installs a
+//! `pre_exec` hook that sets `no_new_privs` and loads a seccomp-BPF filter
+//! in the forked child before it execs.  The filter:
+//!
+//! - refuses syscalls a tool command never needs (`ptrace`, `mount`,
+//!   module loading, `bpf`, namespace changes, ...) with `EPERM`;
+//! - refuses `clone` with any `CLONE_NEW*` flag with `EPERM`, and fails
+//!   `clone3` (whose flags it cannot inspect) with `ENOSYS` so libc falls
+//!   back to `clone`;
+//! - unless `allow_network` is set, refuses `socket()` for every family
+//!   but `AF_UNIX` with `EACCES`, so commands have no network access;
+//! - kills the process on a foreign syscall ABI (e.g. x32), which would
+//!   otherwise bypass the syscall-number checks.
+//!
+//! The filter is inherited across `fork`/`exec` and cannot be removed.
+//! Only Linux on x86_64/aarch64 with the `sandbox` feature is supported;
+//! elsewhere an enabled sandbox makes exec refuse to run.
+
+use sa_domain::config::ExecSandboxConfig;
+use tokio::process::Command;
+
+#[cfg(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod seccomp {
+    // From linux/filter.h, linux/bpf_common.h and linux/seccomp.h.
+    const BPF_LD_W_ABS: u16 = 0x20; // BPF_LD | BPF_W | BPF_ABS
+    const BPF_JMP_JEQ_K: u16 = 0x15; // BPF_JMP | BPF_JEQ | BPF_K
+    const BPF_JMP_JGE_K: u16 = 0x35; // BPF_JMP | BPF_JGE | BPF_K
+    const BPF_JMP_JSET_K: u16 = 0x45; // BPF_JMP | BPF_JSET | BPF_K
+    const BPF_RET_K: u16 = 0x06; // BPF_RET | BPF_K
+
+    const SECCOMP_MODE_FILTER: libc::c_ulong = 2;
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+    // Offsets into `struct seccomp_data`.  Both supported targets are
+    // little-endian, so the low half of `args[0]` sits at its start.
+    const OFFSET_NR: u32 = 0;
+    const OFFSET_ARCH: u32 = 4;
+    const OFFSET_ARG0: u32 = 16;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7;
+
+    /// `clone` flags that create a namespace.  All fit in the low half of
+    /// `args[0]`; `CLONE_NEWTIME` is only accepted by `clone3`/`unshare`.
+    const CLONE_NEW_MASK: u32 = (libc::CLONE_NEWNS
+        | libc::CLONE_NEWCGROUP
+        | libc::CLONE_NEWUTS
+        | libc::CLONE_NEWIPC
+        | libc::CLONE_NEWUSER
+        | libc::CLONE_NEWPID
+        | libc::CLONE_NEWNET) as u32;
+
+    /// Syscalls refused with `EPERM`.
+    const DENIED: &[libc::c_long] = &[
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_reboot,
+        libc::SYS_kexec_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_userfaultfd,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        // io_uring can open sockets without going through `socket()`.
+        libc::SYS_io_uring_setup,
+    ];
+
+    fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// The BPF program for a sandbox that does or doesn't allow network.
+    pub(super) fn filter(allow_network: bool) -> Vec<libc::sock_filter> {
+        let mut f = vec![
+            stmt(BPF_LD_W_ABS, OFFSET_ARCH),
+            jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+            stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            stmt(BPF_LD_W_ABS, OFFSET_NR),
+        ];
+        #[cfg(target_arch = "x86_64")]
+        {
+            // x32 syscalls share the arch value but set this bit.
+            const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+            f.push(jump(BPF_JMP_JGE_K, X32_SYSCALL_BIT, 0, 1));
+            f.push(stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS));
+        }
+        for &nr in DENIED {
+            f.push(jump(BPF_JMP_JEQ_K, nr as u32, 0, 1));
+            f.push(stmt(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EPERM as u32));
+        }
+        f.push(jump(BPF_JMP_JEQ_K, libc::SYS_clone3 as u32, 0, 1));
+        f.push(stmt(BPF_RET_K, SECCOMP_RET_ERRNO | libc::ENOSYS as u32));
+        f.push(jump(BPF_JMP_JEQ_K, libc::SYS_clone as u32, 0, 4));
+        f.push(stmt(BPF_LD_W_ABS, OFFSET_ARG0));
+        f.push(jump(BPF_JMP_JSET_K, CLONE_NEW_MASK, 0, 1));
+        f.push(stmt(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EPERM as u32));
+        f.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        if !allow_network {
+            f.push(jump(BPF_JMP_JEQ_K, libc::SYS_socket as u32, 0, 3));
+            f.push(stmt(BPF_LD_W_ABS, OFFSET_ARG0));
+            f.push(jump(BPF_JMP_JEQ_K, libc::AF_UNIX as u32, 1, 0));
+            f.push(stmt(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EACCES as u32));
+        }
+        f.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        f
+    }
+
+    /// Load `filter` into the calling thread.  Only makes `prctl` calls, so
+    /// it is async-signal-safe and may run between fork and exec.
+    pub(super) fn install(filter: &[libc::sock_filter]) -> std::io::Result<()> {
+        let prog = libc::sock_fprog {
+            len: filter.len() as libc::c_ushort,
+            filter: filter.as_ptr() as *mut libc::sock_filter,
+        };
+        // prctl reads its variadic arguments as unsigned longs.
+        const ON: libc::c_ulong = 1;
+        const UNUSED: libc::c_ulong = 0;
+        // SAFETY: plain prctl calls; `prog` points at `filter`, which
+        // outlives both calls, and the kernel copies the program.
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, ON, UNUSED, UNUSED, UNUSED) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::prctl(
+                libc::PR_SET_SECCOMP,
+                SECCOMP_MODE_FILTER,
+                &prog as *const libc::sock_fprog,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Apply the sandbox to the process `cmd` will spawn.
+#[cfg(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn apply(cmd: &mut Command, cfg: &ExecSandboxConfig) -> Result<(), String> {
+    if !cfg.enabled {
+        return Ok(());
+    }
+    // Built before the fork: the hook must not allocate.
+    let filter = seccomp::filter(cfg.allow_network);
+
+    // SAFETY: the hook only calls `prctl` (see `seccomp::install`) on a
+    // filter owned by the closure.
+    unsafe {
+        cmd.pre_exec(move || seccomp::install(&filter));
+    }
+    Ok(())
+}
+
+/// Without sandbox support an enabled sandbox fails closed.
+#[cfg(not(all(
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub fn apply(_cmd: &mut Command, cfg: &ExecSandboxConfig) -> Result<(), String> {
+    if cfg.enabled {
+        return Err("exec sandbox is enabled but unsupported by this build \
+                    (requires Linux on x86_64/aarch64 and the `sandbox` feature)"
+            .into());
+    }
+    Ok(())
+}
+
+#[cfg(all(
+    test,
+    feature = "sandbox",
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod tests {
+    use super::*;
+
+    /// Exit code: 0 when an `AF_INET` (or `AF_UNIX`) socket opens, 3 when
+    /// it is refused.
+    async fn open_socket(family: &str, sandbox: ExecSandboxConfig) -> Option<i32> {
+        let script = format!("use Socket; socket(my $s, {family}, SOCK_STREAM, 0) or exit 3");
+        let mut cmd = Command::new("perl");
+        cmd.arg("-e").arg(script);
+        apply(&mut cmd, &sandbox).unwrap();
+        match cmd.status().await {
+            Ok(status) => status.code(),
+            // No perl on this host: nothing to check.
+            Err(_) => None,
+        }
+    }
+
+    fn sandboxed() -> ExecSandboxConfig {
+        ExecSandboxConfig {
+            enabled: true,
+            allow_network: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn sandboxed_command_cannot_open_network_socket() {
+        let Some(unsandboxed) = open_socket("PF_INET", ExecSandboxConfig::default()).await else {
+            return;
+        };
+        assert_eq!(unsandboxed, 0, "unsandboxed command opens a socket");
+
+        assert_eq!(open_socket("PF_INET", sandboxed()).await, Some(3));
+        assert_eq!(open_socket("PF_INET6", sandboxed()).await, Some(3));
+        assert_eq!(
+            open_socket("PF_UNIX", sandboxed()).await,
+            Some(0),
+            "Unix sockets stay available"
+        );
+
+        let networked = ExecSandboxConfig {
+            allow_network: true,
+            ..sandboxed()
+        };
+        assert_eq!(open_socket("PF_INET", networked).await, Some(0));
+    }
+
+    /// Exit code: 0 when `clone(flags)` succeeds, 3 when it fails with
+    /// `EPERM`, 4 on any other error.
+    async fn raw_clone(flags: libc::c_int) -> Option<i32> {
+        let script = format!(
+            "my $r = syscall({}, {}, 0, 0, 0, 0); \
+             exit 0 if $r >= 0; exit($!{{EPERM}} ? 3 : 4)",
+            libc::SYS_clone,
+            flags | libc::SIGCHLD
+        );
+        let mut cmd = Command::new("perl");
+        cmd.arg("-e").arg(script);
+        apply(&mut cmd, &sandboxed()).unwrap();
+        cmd.status().await.ok().and_then(|status| status.code())
+    }
+
+    #[tokio::test]
+    async fn sandboxed_command_cannot_create_namespaces() {
+        let Some(plain) = raw_clone(0).await else {
+            return;
+        };
+        assert_eq!(plain, 0, "plain fork-style clone stays available");
+        assert_eq!(raw_clone(libc::CLONE_NEWUSER).await, Some(3));
+        assert_eq!(raw_clone(libc::CLONE_NEWNET).await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn sandboxed_command_still_runs() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo ok");
+        apply(&mut cmd, &sandboxed()).unwrap();
+        let out = cmd.output().await.unwrap();
+        assert!(out.status.success());
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "ok\n");
+    }
+}